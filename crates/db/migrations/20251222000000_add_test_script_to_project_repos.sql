@@ -0,0 +1,2 @@
+-- Per-repo verification script, used to validate agent-resolved merge conflicts
+ALTER TABLE project_repos ADD COLUMN test_script TEXT;
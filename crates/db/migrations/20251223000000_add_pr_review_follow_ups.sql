@@ -0,0 +1,5 @@
+-- Opt-in: turn new PR review comments into coding agent follow-ups
+ALTER TABLE projects ADD COLUMN auto_review_follow_ups BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Last PR comment already handed to the agent (NULL until the PR was first polled)
+ALTER TABLE merges ADD COLUMN pr_last_seen_comment_id TEXT;
@@ -0,0 +1,13 @@
+-- Opt-in: hand failing CI checks of open PRs to the coding agent
+ALTER TABLE projects ADD COLUMN auto_fix_ci BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Latest CI results for the PR head commit, refreshed by the PR monitor
+ALTER TABLE merges ADD COLUMN pr_ci_status TEXT CHECK (pr_ci_status IN ('pending', 'success', 'failure'));
+ALTER TABLE merges ADD COLUMN pr_ci_head_sha TEXT;
+ALTER TABLE merges ADD COLUMN pr_ci_checks TEXT; -- JSON array of checks
+ALTER TABLE merges ADD COLUMN pr_ci_updated_at TEXT;
+
+-- Head commit whose failure was last handed to the agent, and how many fixes
+-- in a row were attempted without CI passing
+ALTER TABLE merges ADD COLUMN pr_ci_fix_sha TEXT;
+ALTER TABLE merges ADD COLUMN pr_ci_fix_attempts INTEGER NOT NULL DEFAULT 0;
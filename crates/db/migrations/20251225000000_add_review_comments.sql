@@ -0,0 +1,28 @@
+-- Inline review comments on a workspace's diff, left before any PR exists
+CREATE TABLE review_comments (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    repo_id              BLOB NOT NULL,
+    -- Process whose changes were reviewed (NULL when commented on the live worktree)
+    execution_process_id BLOB,
+    file_path            TEXT NOT NULL,
+    side                 TEXT NOT NULL DEFAULT 'new'
+                            CHECK (side IN ('old', 'new')),
+    line_start           INTEGER NOT NULL,
+    line_end             INTEGER NOT NULL,
+    body                 TEXT NOT NULL,
+    -- Commit the line range refers to; moved forward when comments are re-anchored
+    anchor_commit        TEXT NOT NULL,
+    -- The commented lines, used to find them again after they moved
+    anchor_text          TEXT,
+    outdated             BOOLEAN NOT NULL DEFAULT FALSE,
+    sent_at              TEXT,
+    resolved_at          TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_review_comments_workspace_id ON review_comments(workspace_id);
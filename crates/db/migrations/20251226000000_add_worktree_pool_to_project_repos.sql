@@ -0,0 +1,4 @@
+-- Pre-created worktrees with the setup script already run, claimed by new attempts.
+-- A pool size of 0 disables the pool for the repo.
+ALTER TABLE project_repos ADD COLUMN worktree_pool_size INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE project_repos ADD COLUMN worktree_pool_max_age_minutes INTEGER NOT NULL DEFAULT 60;
@@ -0,0 +1,4 @@
+-- Sparse-checkout cone directories for attempt worktrees (one per line); NULL checks out the whole tree
+ALTER TABLE project_repos ADD COLUMN sparse_checkout_paths TEXT;
+-- Also include the task's agent working directory in the cone
+ALTER TABLE project_repos ADD COLUMN sparse_checkout_agent_working_dir BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,12 @@
+-- Named API tokens for the local server. Only a hash of each token is stored.
+CREATE TABLE api_tokens (
+    id           BLOB PRIMARY KEY,
+    name         TEXT NOT NULL,
+    token_hash   TEXT NOT NULL UNIQUE,
+    -- Leading characters of the token, to tell tokens apart in the UI
+    token_prefix TEXT NOT NULL,
+    -- JSON array of scopes
+    scopes       TEXT NOT NULL DEFAULT '[]',
+    last_used_at TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
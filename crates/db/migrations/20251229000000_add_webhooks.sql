@@ -0,0 +1,36 @@
+-- Outgoing webhooks and their delivery queue
+CREATE TABLE webhooks (
+    id          BLOB PRIMARY KEY,
+    name        TEXT NOT NULL,
+    url         TEXT NOT NULL,
+    -- Key for the HMAC-SHA256 signature of each payload
+    secret      TEXT NOT NULL,
+    -- JSON array of subscribed event types; empty means all events
+    events      TEXT NOT NULL DEFAULT '[]',
+    enabled     BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE TABLE webhook_deliveries (
+    id              BLOB PRIMARY KEY,
+    webhook_id      BLOB NOT NULL,
+    event_type      TEXT NOT NULL,
+    -- JSON body sent to the endpoint
+    payload         TEXT NOT NULL,
+    status          TEXT NOT NULL DEFAULT 'pending'
+                       CHECK (status IN ('pending', 'succeeded', 'failed')),
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    -- When a pending delivery is due next
+    next_attempt_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    response_status INTEGER,
+    response_body   TEXT,
+    last_error      TEXT,
+    delivered_at    TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id, created_at);
+CREATE INDEX idx_webhook_deliveries_due ON webhook_deliveries(status, next_attempt_at);
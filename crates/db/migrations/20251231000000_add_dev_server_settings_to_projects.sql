@@ -0,0 +1,4 @@
+-- Extra port variables allocated for each dev server (comma-separated names,
+-- PORT is always allocated) and an optional log pattern signalling readiness.
+ALTER TABLE projects ADD COLUMN dev_server_ports TEXT;
+ALTER TABLE projects ADD COLUMN dev_server_ready_pattern TEXT;
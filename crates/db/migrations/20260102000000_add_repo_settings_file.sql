@@ -0,0 +1,10 @@
+-- Settings committed to a repository in `.vibe-kanban.toml`: which side wins
+-- when they differ from the local ones (NULL until the user chose) and the
+-- branch the file was last read from.
+ALTER TABLE project_repos ADD COLUMN settings_file_mode TEXT
+    CHECK (settings_file_mode IN ('repo_file', 'local'));
+ALTER TABLE project_repos ADD COLUMN settings_file_branch TEXT;
+
+-- Project defaults a settings file may declare
+ALTER TABLE projects ADD COLUMN default_executor_profile TEXT;
+ALTER TABLE projects ADD COLUMN mcp_servers TEXT;
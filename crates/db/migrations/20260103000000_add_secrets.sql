@@ -0,0 +1,23 @@
+-- Encrypted secrets injected into the environment of agents and scripts.
+-- A secret applies to every project (both ids NULL), to one project, or to
+-- every attempt that includes one repository.
+CREATE TABLE secrets (
+    id              BLOB PRIMARY KEY,
+    name            TEXT NOT NULL,
+    project_id      BLOB,
+    repo_id         BLOB,
+    -- AES-256-GCM nonce followed by the ciphertext
+    encrypted_value BLOB NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    CHECK (project_id IS NULL OR repo_id IS NULL)
+);
+
+CREATE UNIQUE INDEX idx_secrets_global_name ON secrets(name)
+    WHERE project_id IS NULL AND repo_id IS NULL;
+CREATE UNIQUE INDEX idx_secrets_project_name ON secrets(project_id, name)
+    WHERE project_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_secrets_repo_name ON secrets(repo_id, name)
+    WHERE repo_id IS NOT NULL;
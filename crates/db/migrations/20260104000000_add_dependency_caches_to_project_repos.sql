@@ -0,0 +1,3 @@
+-- Dependency directories shared between attempt worktrees, one per line as
+-- `path` or `path: key files`; NULL disables dependency caching
+ALTER TABLE project_repos ADD COLUMN dependency_caches TEXT;
@@ -0,0 +1,3 @@
+-- Git host of a project's repositories as JSON (provider, API URL and the name
+-- of the secret holding the token), overriding the global git_hosts entry.
+ALTER TABLE projects ADD COLUMN git_host TEXT;
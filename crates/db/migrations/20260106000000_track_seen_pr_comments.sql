@@ -0,0 +1,6 @@
+-- Keys of every PR comment already handed to the agent, as a JSON array. The
+-- last seen comment alone is not enough: a review submitted late can sort
+-- before comments that were already handled. NULL until the PR is polled, so
+-- PRs polled before this change start from a fresh baseline.
+ALTER TABLE merges ADD COLUMN pr_seen_comment_ids TEXT;
+ALTER TABLE merges DROP COLUMN pr_last_seen_comment_id;
@@ -0,0 +1,10 @@
+-- Agent-assisted conflict resolutions, one per workspace, so a resolution in
+-- progress survives a restart. `resolution` holds the serialized state; the
+-- branch tip to roll back to is kept in its own column.
+CREATE TABLE conflict_resolutions (
+    workspace_id BLOB PRIMARY KEY,
+    resolution   TEXT NOT NULL,
+    restore_oid  TEXT,
+    updated_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
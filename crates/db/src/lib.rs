@@ -1,4 +1,8 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, OnceLock},
+};
 
 use sqlx::{
     Error, Pool, Sqlite, SqlitePool,
@@ -8,17 +12,31 @@ use utils::assets::asset_dir;
 
 pub mod models;
 
+static DATABASE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
 #[derive(Clone)]
 pub struct DBService {
     pub pool: Pool<Sqlite>,
 }
 
 impl DBService {
+    /// Open the database at `path` instead of `db.sqlite` in the asset
+    /// directory. Must be called before the first connection; later calls are
+    /// ignored.
+    pub fn use_database_path(path: PathBuf) {
+        let _ = DATABASE_PATH.set(path);
+    }
+
+    fn database_url() -> String {
+        let path = DATABASE_PATH
+            .get()
+            .cloned()
+            .unwrap_or_else(|| asset_dir().join("db.sqlite"));
+        format!("sqlite://{}", path.to_string_lossy())
+    }
+
     pub async fn new() -> Result<DBService, Error> {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
+        let database_url = Self::database_url();
         let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(true);
         let pool = SqlitePool::connect_with(options).await?;
         sqlx::migrate!("./migrations").run(&pool).await?;
@@ -49,10 +67,7 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
+        let database_url = Self::database_url();
         let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(true);
 
         let pool = if let Some(hook) = after_connect {
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What an API token may do. Every token can read; the other scopes allow
+/// changes to one area each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiTokenScope {
+    /// Read everything, including the websocket and SSE streams
+    Read,
+    /// Create and edit tasks, tags and drafts
+    Tasks,
+    /// Start and stop attempts, follow-ups, scripts and dev servers
+    Execute,
+    /// Change settings, projects and repositories, and manage API tokens
+    Config,
+}
+
+/// A named token for the local server. The token itself is only shown once,
+/// when it is created.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub name: String,
+    /// Leading characters of the token
+    pub token_prefix: String,
+    #[ts(type = "Array<ApiTokenScope>")]
+    pub scopes: Json<Vec<ApiTokenScope>>,
+    #[ts(type = "Date | null")]
+    pub last_used_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiToken {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      token_prefix,
+                      scopes as "scopes!: Json<Vec<ApiTokenScope>>",
+                      last_used_at as "last_used_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               ORDER BY created_at DESC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      token_prefix,
+                      scopes as "scopes!: Json<Vec<ApiTokenScope>>",
+                      last_used_at as "last_used_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               WHERE token_hash = $1"#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        name: &str,
+        token_hash: &str,
+        token_prefix: &str,
+        scopes: &[ApiTokenScope],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let scopes = Json(scopes);
+        sqlx::query_as!(
+            ApiToken,
+            r#"INSERT INTO api_tokens (id, name, token_hash, token_prefix, scopes)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                      name,
+                      token_prefix,
+                      scopes as "scopes!: Json<Vec<ApiTokenScope>>",
+                      last_used_at as "last_used_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            name,
+            token_hash,
+            token_prefix,
+            scopes
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn touch(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            "UPDATE api_tokens SET last_used_at = $2 WHERE id = $1",
+            id,
+            now
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM api_tokens WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// Saved state of a workspace's conflict resolution. `resolution` is the
+/// JSON the conflict resolution service serializes; it is opaque here.
+#[derive(Debug, Clone, FromRow)]
+pub struct ConflictResolutionRecord {
+    pub workspace_id: Uuid,
+    pub resolution: String,
+    pub restore_oid: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ConflictResolutionRecord {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ConflictResolutionRecord,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      resolution,
+                      restore_oid,
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM conflict_resolutions"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        resolution: &str,
+        restore_oid: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO conflict_resolutions (workspace_id, resolution, restore_oid)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(workspace_id) DO UPDATE SET
+                   resolution = excluded.resolution,
+                   restore_oid = excluded.restore_oid,
+                   updated_at = datetime('now', 'subsec')"#,
+            workspace_id,
+            resolution,
+            restore_oid
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, workspace_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM conflict_resolutions WHERE workspace_id = $1",
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
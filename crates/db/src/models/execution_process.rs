@@ -41,6 +41,7 @@ pub enum ExecutionProcessError {
 #[serde(rename_all = "lowercase")]
 #[ts(use_ts_enum)]
 pub enum ExecutionProcessStatus {
+    Queued,
     Running,
     Completed,
     Failed,
@@ -55,6 +56,8 @@ pub enum ExecutionProcessRunReason {
     CleanupScript,
     CodingAgent,
     DevServer,
+    /// A named project script run on demand
+    Script,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -91,6 +94,13 @@ pub struct UpdateExecutionProcess {
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+/// A running coding agent, as counted against the concurrency limits
+#[derive(Debug, Clone)]
+pub struct RunningAgentSlot {
+    pub executor: Option<String>,
+    pub project_id: Uuid,
+}
+
 #[derive(Debug)]
 pub struct ExecutionContext {
     pub execution_process: ExecutionProcess,
@@ -270,6 +280,24 @@ impl ExecutionProcess {
         .await
     }
 
+    /// Executor and project of every running coding agent
+    pub async fn find_running_agent_slots(
+        pool: &SqlitePool,
+    ) -> Result<Vec<RunningAgentSlot>, sqlx::Error> {
+        sqlx::query_as!(
+            RunningAgentSlot,
+            r#"SELECT s.executor,
+                      t.project_id as "project_id!: Uuid"
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               JOIN workspaces w ON s.workspace_id = w.id
+               JOIN tasks t ON w.task_id = t.id
+               WHERE ep.status = 'running' AND ep.run_reason = 'codingagent'"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Find running dev servers for a specific project
     pub async fn find_running_dev_servers_by_project(
         pool: &SqlitePool,
@@ -292,7 +320,7 @@ impl ExecutionProcess {
         .await
     }
 
-    /// Check if there are running processes (excluding dev servers) for a workspace (across all sessions)
+    /// Check if there are running or queued processes (excluding dev servers) for a workspace (across all sessions)
     pub async fn has_running_non_dev_server_processes_for_workspace(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -302,7 +330,7 @@ impl ExecutionProcess {
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
                WHERE s.workspace_id = $1
-                 AND ep.status = 'running'
+                 AND ep.status IN ('queued', 'running')
                  AND ep.run_reason != 'devserver'"#,
             workspace_id
         )
@@ -445,6 +473,7 @@ impl ExecutionProcess {
         pool: &SqlitePool,
         data: &CreateExecutionProcess,
         process_id: Uuid,
+        status: ExecutionProcessStatus,
         repo_states: &[CreateExecutionProcessRepoState],
     ) -> Result<Self, sqlx::Error> {
         let now = Utc::now();
@@ -459,7 +488,7 @@ impl ExecutionProcess {
             data.session_id,
             data.run_reason,
             executor_action_json,
-            ExecutionProcessStatus::Running,
+            status,
             None::<i64>,
             now,
             None::<DateTime<Utc>>,
@@ -476,6 +505,22 @@ impl ExecutionProcess {
             .ok_or(sqlx::Error::RowNotFound)
     }
 
+    /// Move a queued process to running, restarting its clock
+    pub async fn mark_started(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET status = $1, started_at = $2
+               WHERE id = $3"#,
+            ExecutionProcessStatus::Running,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn was_stopped(pool: &SqlitePool, id: Uuid) -> bool {
         if let Ok(exp_process) = Self::find_by_id(pool, id).await
             && exp_process.is_some_and(|ep| {
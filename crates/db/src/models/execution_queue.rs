@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A coding agent run waiting for a free slot, with what the scheduler and
+/// the queue view need to know about it
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct QueuedExecution {
+    pub execution_process_id: Uuid,
+    pub session_id: Uuid,
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub project_id: Uuid,
+    pub executor: Option<String>,
+    /// Higher runs first
+    pub priority: i64,
+    /// Order among runs of the same priority
+    pub sequence: i64,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateQueuedExecution {
+    pub priority: Option<i64>,
+    /// Zero-based index to move the run to in the whole queue
+    pub position: Option<usize>,
+}
+
+impl QueuedExecution {
+    /// The whole queue in dispatch order
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedExecution,
+            r#"SELECT q.execution_process_id as "execution_process_id!: Uuid",
+                      ep.session_id as "session_id!: Uuid",
+                      s.workspace_id as "workspace_id!: Uuid",
+                      t.id as "task_id!: Uuid",
+                      t.title as "task_title!",
+                      t.project_id as "project_id!: Uuid",
+                      s.executor,
+                      q.priority,
+                      q.sequence,
+                      q.created_at as "created_at!: DateTime<Utc>"
+               FROM execution_queue q
+               JOIN execution_processes ep ON ep.id = q.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               ORDER BY q.priority DESC, q.sequence ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Append a run to the end of its priority band
+    pub async fn enqueue(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        priority: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO execution_queue (execution_process_id, priority, sequence)
+               VALUES ($1, $2, (SELECT COALESCE(MAX(sequence), 0) + 1 FROM execution_queue))"#,
+            execution_process_id,
+            priority
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_order<'e, E>(
+        executor: E,
+        execution_process_id: Uuid,
+        priority: i64,
+        sequence: i64,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query!(
+            r#"UPDATE execution_queue
+               SET priority = $2, sequence = $3
+               WHERE execution_process_id = $1"#,
+            execution_process_id,
+            priority,
+            sequence
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove a run from the queue; returns whether it was queued
+    pub async fn dequeue(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM execution_queue WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
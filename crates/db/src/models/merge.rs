@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool, Type};
@@ -14,6 +16,52 @@ pub enum MergeStatus {
     Unknown,
 }
 
+/// Outcome of a CI check, or of all checks on a commit combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "ci_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CiStatus {
+    Pending,
+    Success,
+    Failure,
+}
+
+impl CiStatus {
+    /// Any failing check fails the commit, otherwise any pending check keeps
+    /// it pending. `None` when there are no checks at all.
+    pub fn combine(checks: &[CiCheck]) -> Option<Self> {
+        if checks.is_empty() {
+            None
+        } else if checks.iter().any(|c| c.status == CiStatus::Failure) {
+            Some(CiStatus::Failure)
+        } else if checks.iter().any(|c| c.status == CiStatus::Pending) {
+            Some(CiStatus::Pending)
+        } else {
+            Some(CiStatus::Success)
+        }
+    }
+}
+
+/// A check run, commit status or pipeline job reported by the git host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct CiCheck {
+    pub name: String,
+    pub status: CiStatus,
+    pub url: Option<String>,
+    /// Host-specific job id, used to fetch the job log
+    #[serde(default)]
+    pub job_id: Option<String>,
+}
+
+/// CI results for the head commit of a pull request
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PrCiStatus {
+    pub status: CiStatus,
+    pub head_sha: String,
+    pub checks: Vec<CiCheck>,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Merge {
@@ -40,6 +88,8 @@ pub struct PrMerge {
     pub created_at: DateTime<Utc>,
     pub target_branch_name: String,
     pub pr_info: PullRequestInfo,
+    /// Latest CI results, refreshed while the PR is open
+    pub ci: Option<PrCiStatus>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -71,6 +121,10 @@ struct MergeRow {
     pr_status: Option<MergeStatus>,
     pr_merged_at: Option<DateTime<Utc>>,
     pr_merge_commit_sha: Option<String>,
+    pr_ci_status: Option<CiStatus>,
+    pr_ci_head_sha: Option<String>,
+    pr_ci_checks: Option<sqlx::types::Json<Vec<CiCheck>>>,
+    pr_ci_updated_at: Option<DateTime<Utc>>,
     created_at: DateTime<Utc>,
 }
 
@@ -109,6 +163,10 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_checks as "pr_ci_checks?: sqlx::types::Json<Vec<CiCheck>>",
+                pr_ci_updated_at as "pr_ci_updated_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -151,6 +209,10 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_checks as "pr_ci_checks?: sqlx::types::Json<Vec<CiCheck>>",
+                pr_ci_updated_at as "pr_ci_updated_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -182,6 +244,10 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_checks as "pr_ci_checks?: sqlx::types::Json<Vec<CiCheck>>",
+                pr_ci_updated_at as "pr_ci_updated_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
                FROM merges
@@ -223,6 +289,104 @@ impl Merge {
 
         Ok(())
     }
+
+    /// Keys of the PR comments already handed to the agent. `None` until the
+    /// PR has been polled once.
+    pub async fn get_seen_comment_ids(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+    ) -> Result<Option<BTreeSet<String>>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT pr_seen_comment_ids as "pr_seen_comment_ids?: sqlx::types::Json<BTreeSet<String>>"
+               FROM merges WHERE id = $1"#,
+            merge_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|ids| ids.flatten().map(|ids| ids.0))
+    }
+
+    pub async fn set_seen_comment_ids(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        comment_ids: &BTreeSet<String>,
+    ) -> Result<(), sqlx::Error> {
+        let comment_ids = sqlx::types::Json(comment_ids.clone());
+        sqlx::query!(
+            r#"UPDATE merges SET pr_seen_comment_ids = $1 WHERE id = $2"#,
+            comment_ids,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store the latest CI results of an open PR
+    pub async fn update_ci_status(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        ci: &PrCiStatus,
+    ) -> Result<(), sqlx::Error> {
+        let checks = sqlx::types::Json(&ci.checks);
+        sqlx::query!(
+            r#"UPDATE merges
+            SET pr_ci_status = $1,
+                pr_ci_head_sha = $2,
+                pr_ci_checks = $3,
+                pr_ci_updated_at = $4
+            WHERE id = $5"#,
+            ci.status,
+            ci.head_sha,
+            checks,
+            ci.updated_at,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Head commit whose CI failure was last handed to the agent, and the
+    /// number of fixes attempted since CI last passed
+    pub async fn get_ci_fix_state(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+    ) -> Result<(Option<String>, i64), sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT pr_ci_fix_sha, pr_ci_fix_attempts as "pr_ci_fix_attempts!: i64"
+               FROM merges
+               WHERE id = $1"#,
+            merge_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row
+            .map(|row| (row.pr_ci_fix_sha, row.pr_ci_fix_attempts))
+            .unwrap_or_default())
+    }
+
+    pub async fn set_ci_fix_state(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        fix_sha: Option<&str>,
+        attempts: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merges SET pr_ci_fix_sha = $1, pr_ci_fix_attempts = $2 WHERE id = $3"#,
+            fix_sha,
+            attempts,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Find all merges for a workspace (returns both direct and PR merges)
     pub async fn find_by_workspace_id(
         pool: &SqlitePool,
@@ -242,6 +406,10 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_checks as "pr_ci_checks?: sqlx::types::Json<Vec<CiCheck>>",
+                pr_ci_updated_at as "pr_ci_updated_at?: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges
@@ -275,6 +443,10 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_checks as "pr_ci_checks?: sqlx::types::Json<Vec<CiCheck>>",
+                pr_ci_updated_at as "pr_ci_updated_at?: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges
@@ -308,6 +480,15 @@ impl From<MergeRow> for DirectMerge {
 
 impl From<MergeRow> for PrMerge {
     fn from(row: MergeRow) -> Self {
+        let ci = match (row.pr_ci_status, row.pr_ci_head_sha, row.pr_ci_updated_at) {
+            (Some(status), Some(head_sha), Some(updated_at)) => Some(PrCiStatus {
+                status,
+                head_sha,
+                checks: row.pr_ci_checks.map(|checks| checks.0).unwrap_or_default(),
+                updated_at,
+            }),
+            _ => None,
+        };
         PrMerge {
             id: row.id,
             workspace_id: row.workspace_id,
@@ -320,6 +501,7 @@ impl From<MergeRow> for PrMerge {
                 merged_at: row.pr_merged_at,
                 merge_commit_sha: row.pr_merge_commit_sha,
             },
+            ci,
             created_at: row.created_at,
         }
     }
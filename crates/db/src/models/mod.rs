@@ -1,15 +1,23 @@
+pub mod api_token;
 pub mod coding_agent_turn;
+pub mod conflict_resolution;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
+pub mod execution_queue;
 pub mod image;
 pub mod merge;
 pub mod project;
 pub mod project_repo;
+pub mod project_script;
 pub mod repo;
+pub mod review_comment;
 pub mod scratch;
+pub mod secret;
 pub mod session;
 pub mod tag;
 pub mod task;
+pub mod webhook;
+pub mod webhook_delivery;
 pub mod workspace;
 pub mod workspace_repo;
@@ -1,6 +1,10 @@
+use std::{collections::BTreeMap, fmt};
+
 use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
 use serde::{Deserialize, Serialize};
-use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use serde_json::Value;
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, types::Json};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -25,12 +29,92 @@ pub struct Project {
     pub dev_script_working_dir: Option<String>,
     pub default_agent_working_dir: Option<String>,
     pub remote_project_id: Option<Uuid>,
+    /// Turn new PR review comments into coding agent follow-ups
+    pub auto_review_follow_ups: bool,
+    /// Hand failing CI checks of open PRs to the coding agent
+    pub auto_fix_ci: bool,
+    /// Comma-separated names of extra port variables for dev servers, e.g. `API_PORT`
+    pub dev_server_ports: Option<String>,
+    /// Regex matched against dev server output to detect readiness
+    pub dev_server_ready_pattern: Option<String>,
+    /// Preselected for new attempts instead of the global default
+    #[ts(type = "ExecutorProfileId | null")]
+    pub default_executor_profile: Option<Json<ExecutorProfileId>>,
+    /// MCP servers the project's repositories declare for coding agents
+    #[ts(type = "{ [key in string]?: JsonValue } | null")]
+    pub mcp_servers: Option<Json<BTreeMap<String, Value>>>,
+    /// Overrides the `git_hosts` config entry for the repositories' host
+    #[ts(type = "ProjectGitHost | null")]
+    pub git_host: Option<Json<ProjectGitHost>>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
     pub updated_at: DateTime<Utc>,
 }
 
+/// Hosting provider of a repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(rename_all = "lowercase")]
+pub enum GitHostKind {
+    GitHub,
+    GitLab,
+    Gitea,
+}
+
+impl GitHostKind {
+    /// Guess the provider from a hostname. Returns `None` for hosts that need
+    /// an explicit `git_hosts` entry.
+    pub fn detect(host: &str) -> Option<Self> {
+        let host = host.to_ascii_lowercase();
+        if host == "github.com" || host.ends_with(".github.com") {
+            Some(Self::GitHub)
+        } else if host.contains("gitlab") {
+            Some(Self::GitLab)
+        } else if host == "codeberg.org" || host.contains("gitea") || host.contains("forgejo") {
+            Some(Self::Gitea)
+        } else if host.contains("github") {
+            Some(Self::GitHub)
+        } else {
+            None
+        }
+    }
+
+    /// Environment variable consulted when no token is configured
+    pub fn token_env_var(&self) -> Option<&'static str> {
+        match self {
+            // gh manages its own credentials
+            Self::GitHub => None,
+            Self::GitLab => Some("GITLAB_TOKEN"),
+            Self::Gitea => Some("GITEA_TOKEN"),
+        }
+    }
+}
+
+impl fmt::Display for GitHostKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GitHub => write!(f, "GitHub"),
+            Self::GitLab => write!(f, "GitLab"),
+            Self::Gitea => write!(f, "Gitea"),
+        }
+    }
+}
+
+/// Git host of a project's repositories, for hosts their remote URL doesn't
+/// identify or that need their own API URL or token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ProjectGitHost {
+    pub provider: GitHostKind,
+    /// API base URL, derived from the remote URL when unset
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// Name of the secret holding the access token, starting with
+    /// `GIT_HOST_`; a project secret of that name wins over a global one
+    #[serde(default)]
+    pub token_secret: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, TS)]
 pub struct CreateProject {
     pub name: String,
@@ -43,6 +127,19 @@ pub struct UpdateProject {
     pub dev_script: Option<String>,
     pub dev_script_working_dir: Option<String>,
     pub default_agent_working_dir: Option<String>,
+    pub auto_review_follow_ups: Option<bool>,
+    pub auto_fix_ci: Option<bool>,
+    /// `None` keeps the current value, an empty string clears it
+    pub dev_server_ports: Option<String>,
+    /// `None` keeps the current value, an empty string clears it
+    pub dev_server_ready_pattern: Option<String>,
+    /// `None` keeps the current value
+    #[serde(default)]
+    pub default_executor_profile: Option<ExecutorProfileId>,
+    /// `None` keeps the current value
+    #[serde(default)]
+    #[ts(type = "{ [key in string]?: JsonValue } | null")]
+    pub mcp_servers: Option<BTreeMap<String, Value>>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -75,6 +172,13 @@ impl Project {
                       dev_script_working_dir,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      auto_review_follow_ups as "auto_review_follow_ups!: bool",
+                      auto_fix_ci as "auto_fix_ci!: bool",
+                      dev_server_ports,
+                      dev_server_ready_pattern,
+                      default_executor_profile as "default_executor_profile: Json<ExecutorProfileId>",
+                      mcp_servers as "mcp_servers: Json<BTreeMap<String, Value>>",
+                      git_host as "git_host: Json<ProjectGitHost>",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -92,6 +196,12 @@ impl Project {
             SELECT p.id as "id!: Uuid", p.name, p.dev_script, p.dev_script_working_dir,
                    p.default_agent_working_dir,
                    p.remote_project_id as "remote_project_id: Uuid",
+                   p.auto_review_follow_ups as "auto_review_follow_ups!: bool",
+                   p.auto_fix_ci as "auto_fix_ci!: bool",
+                   p.dev_server_ports, p.dev_server_ready_pattern,
+                   p.default_executor_profile as "default_executor_profile: Json<ExecutorProfileId>",
+                   p.mcp_servers as "mcp_servers: Json<BTreeMap<String, Value>>",
+                   p.git_host as "git_host: Json<ProjectGitHost>",
                    p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
             WHERE p.id IN (
@@ -117,6 +227,13 @@ impl Project {
                       dev_script_working_dir,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      auto_review_follow_ups as "auto_review_follow_ups!: bool",
+                      auto_fix_ci as "auto_fix_ci!: bool",
+                      dev_server_ports,
+                      dev_server_ready_pattern,
+                      default_executor_profile as "default_executor_profile: Json<ExecutorProfileId>",
+                      mcp_servers as "mcp_servers: Json<BTreeMap<String, Value>>",
+                      git_host as "git_host: Json<ProjectGitHost>",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -136,6 +253,13 @@ impl Project {
                       dev_script_working_dir,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      auto_review_follow_ups as "auto_review_follow_ups!: bool",
+                      auto_fix_ci as "auto_fix_ci!: bool",
+                      dev_server_ports,
+                      dev_server_ready_pattern,
+                      default_executor_profile as "default_executor_profile: Json<ExecutorProfileId>",
+                      mcp_servers as "mcp_servers: Json<BTreeMap<String, Value>>",
+                      git_host as "git_host: Json<ProjectGitHost>",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -158,6 +282,13 @@ impl Project {
                       dev_script_working_dir,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      auto_review_follow_ups as "auto_review_follow_ups!: bool",
+                      auto_fix_ci as "auto_fix_ci!: bool",
+                      dev_server_ports,
+                      dev_server_ready_pattern,
+                      default_executor_profile as "default_executor_profile: Json<ExecutorProfileId>",
+                      mcp_servers as "mcp_servers: Json<BTreeMap<String, Value>>",
+                      git_host as "git_host: Json<ProjectGitHost>",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -188,6 +319,13 @@ impl Project {
                           dev_script_working_dir,
                           default_agent_working_dir,
                           remote_project_id as "remote_project_id: Uuid",
+                          auto_review_follow_ups as "auto_review_follow_ups!: bool",
+                          auto_fix_ci as "auto_fix_ci!: bool",
+                          dev_server_ports,
+                          dev_server_ready_pattern,
+                          default_executor_profile as "default_executor_profile: Json<ExecutorProfileId>",
+                          mcp_servers as "mcp_servers: Json<BTreeMap<String, Value>>",
+                          git_host as "git_host: Json<ProjectGitHost>",
                           created_at as "created_at!: DateTime<Utc>",
                           updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
@@ -210,11 +348,37 @@ impl Project {
         let dev_script = payload.dev_script.clone();
         let dev_script_working_dir = payload.dev_script_working_dir.clone();
         let default_agent_working_dir = payload.default_agent_working_dir.clone();
+        let auto_review_follow_ups = payload
+            .auto_review_follow_ups
+            .unwrap_or(existing.auto_review_follow_ups);
+        let auto_fix_ci = payload.auto_fix_ci.unwrap_or(existing.auto_fix_ci);
+        let dev_server_ports = payload
+            .dev_server_ports
+            .clone()
+            .or(existing.dev_server_ports)
+            .filter(|s| !s.trim().is_empty());
+        let dev_server_ready_pattern = payload
+            .dev_server_ready_pattern
+            .clone()
+            .or(existing.dev_server_ready_pattern)
+            .filter(|s| !s.trim().is_empty());
+        let default_executor_profile = payload
+            .default_executor_profile
+            .clone()
+            .map(Json)
+            .or(existing.default_executor_profile);
+        let mcp_servers = payload
+            .mcp_servers
+            .clone()
+            .map(Json)
+            .or(existing.mcp_servers);
 
         sqlx::query_as!(
             Project,
             r#"UPDATE projects
-               SET name = $2, dev_script = $3, dev_script_working_dir = $4, default_agent_working_dir = $5
+               SET name = $2, dev_script = $3, dev_script_working_dir = $4, default_agent_working_dir = $5,
+                   auto_review_follow_ups = $6, auto_fix_ci = $7, dev_server_ports = $8,
+                   dev_server_ready_pattern = $9, default_executor_profile = $10, mcp_servers = $11
                WHERE id = $1
                RETURNING id as "id!: Uuid",
                          name,
@@ -222,6 +386,13 @@ impl Project {
                          dev_script_working_dir,
                          default_agent_working_dir,
                          remote_project_id as "remote_project_id: Uuid",
+                         auto_review_follow_ups as "auto_review_follow_ups!: bool",
+                         auto_fix_ci as "auto_fix_ci!: bool",
+                         dev_server_ports,
+                         dev_server_ready_pattern,
+                         default_executor_profile as "default_executor_profile: Json<ExecutorProfileId>",
+                         mcp_servers as "mcp_servers: Json<BTreeMap<String, Value>>",
+                         git_host as "git_host: Json<ProjectGitHost>",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -229,11 +400,34 @@ impl Project {
             dev_script,
             dev_script_working_dir,
             default_agent_working_dir,
+            auto_review_follow_ups,
+            auto_fix_ci,
+            dev_server_ports,
+            dev_server_ready_pattern,
+            default_executor_profile,
+            mcp_servers,
         )
         .fetch_one(pool)
         .await
     }
 
+    /// Set or clear the project's git host override
+    pub async fn set_git_host(
+        pool: &SqlitePool,
+        id: Uuid,
+        git_host: Option<&ProjectGitHost>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let git_host = git_host.cloned().map(Json);
+        sqlx::query!(
+            "UPDATE projects SET git_host = $2 WHERE id = $1",
+            id,
+            git_host
+        )
+        .execute(pool)
+        .await?;
+        Self::find_by_id(pool, id).await
+    }
+
     pub async fn clear_default_agent_working_dir(
         pool: &SqlitePool,
         id: Uuid,
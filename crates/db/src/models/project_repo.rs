@@ -2,7 +2,7 @@ use std::path::Path;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqlitePool, Type};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -19,6 +19,17 @@ pub enum ProjectRepoError {
     AlreadyExists,
 }
 
+/// How a repository's `.vibe-kanban.toml` relates to the settings stored here
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "settings_file_mode", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SettingsFileMode {
+    /// The settings file overwrites the keys it declares
+    RepoFile,
+    /// Local settings are kept; the file is only compared against them
+    Local,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct ProjectRepo {
     pub id: Uuid,
@@ -28,6 +39,26 @@ pub struct ProjectRepo {
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
+    pub test_script: Option<String>,
+    /// Number of pre-created worktrees kept ready for new attempts (0 disables the pool)
+    #[ts(type = "number")]
+    pub worktree_pool_size: i64,
+    /// Pooled worktrees older than this are discarded and recreated
+    #[ts(type = "number")]
+    pub worktree_pool_max_age_minutes: i64,
+    /// Directories checked out in attempt worktrees (sparse-checkout cone, one
+    /// per line); `None` checks out the whole tree
+    pub sparse_checkout_paths: Option<String>,
+    /// Add the task's agent working directory to the sparse-checkout cone
+    pub sparse_checkout_agent_working_dir: bool,
+    /// Directories shared between attempt worktrees through the dependency
+    /// cache, one per line as `path` or `path: key files`
+    pub dependency_caches: Option<String>,
+    /// Which side wins when the repository's settings file differs from these
+    /// settings; `None` until the user chose
+    pub settings_file_mode: Option<SettingsFileMode>,
+    /// Branch the settings file was last read from
+    pub settings_file_branch: Option<String>,
 }
 
 /// ProjectRepo with the associated repo name (for script execution in worktrees)
@@ -41,6 +72,12 @@ pub struct ProjectRepoWithName {
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
+    pub test_script: Option<String>,
+    pub worktree_pool_size: i64,
+    pub worktree_pool_max_age_minutes: i64,
+    pub sparse_checkout_paths: Option<String>,
+    pub sparse_checkout_agent_working_dir: bool,
+    pub dependency_caches: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, TS)]
@@ -56,6 +93,14 @@ pub struct UpdateProjectRepo {
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: Option<bool>,
+    pub test_script: Option<String>,
+    #[ts(type = "number | null")]
+    pub worktree_pool_size: Option<i64>,
+    #[ts(type = "number | null")]
+    pub worktree_pool_max_age_minutes: Option<i64>,
+    pub sparse_checkout_paths: Option<String>,
+    pub sparse_checkout_agent_working_dir: Option<bool>,
+    pub dependency_caches: Option<String>,
 }
 
 impl ProjectRepo {
@@ -71,7 +116,15 @@ impl ProjectRepo {
                       setup_script,
                       cleanup_script,
                       copy_files,
-                      parallel_setup_script as "parallel_setup_script!: bool"
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      test_script,
+                      worktree_pool_size as "worktree_pool_size!: i64",
+                      worktree_pool_max_age_minutes as "worktree_pool_max_age_minutes!: i64",
+                      sparse_checkout_paths,
+                      sparse_checkout_agent_working_dir as "sparse_checkout_agent_working_dir!: bool",
+                      dependency_caches,
+                      settings_file_mode as "settings_file_mode: SettingsFileMode",
+                      settings_file_branch
                FROM project_repos
                WHERE project_id = $1"#,
             project_id
@@ -92,7 +145,15 @@ impl ProjectRepo {
                       setup_script,
                       cleanup_script,
                       copy_files,
-                      parallel_setup_script as "parallel_setup_script!: bool"
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      test_script,
+                      worktree_pool_size as "worktree_pool_size!: i64",
+                      worktree_pool_max_age_minutes as "worktree_pool_max_age_minutes!: i64",
+                      sparse_checkout_paths,
+                      sparse_checkout_agent_working_dir as "sparse_checkout_agent_working_dir!: bool",
+                      dependency_caches,
+                      settings_file_mode as "settings_file_mode: SettingsFileMode",
+                      settings_file_branch
                FROM project_repos
                WHERE repo_id = $1"#,
             repo_id
@@ -114,7 +175,13 @@ impl ProjectRepo {
                       pr.setup_script,
                       pr.cleanup_script,
                       pr.copy_files,
-                      pr.parallel_setup_script as "parallel_setup_script!: bool"
+                      pr.parallel_setup_script as "parallel_setup_script!: bool",
+                      pr.test_script,
+                      pr.worktree_pool_size as "worktree_pool_size!: i64",
+                      pr.worktree_pool_max_age_minutes as "worktree_pool_max_age_minutes!: i64",
+                      pr.sparse_checkout_paths,
+                      pr.sparse_checkout_agent_working_dir as "sparse_checkout_agent_working_dir!: bool",
+                      pr.dependency_caches
                FROM project_repos pr
                JOIN repos r ON r.id = pr.repo_id
                WHERE pr.project_id = $1
@@ -160,7 +227,15 @@ impl ProjectRepo {
                       setup_script,
                       cleanup_script,
                       copy_files,
-                      parallel_setup_script as "parallel_setup_script!: bool"
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      test_script,
+                      worktree_pool_size as "worktree_pool_size!: i64",
+                      worktree_pool_max_age_minutes as "worktree_pool_max_age_minutes!: i64",
+                      sparse_checkout_paths,
+                      sparse_checkout_agent_working_dir as "sparse_checkout_agent_working_dir!: bool",
+                      dependency_caches,
+                      settings_file_mode as "settings_file_mode: SettingsFileMode",
+                      settings_file_branch
                FROM project_repos
                WHERE project_id = $1 AND repo_id = $2"#,
             project_id,
@@ -170,6 +245,32 @@ impl ProjectRepo {
         .await
     }
 
+    /// Project repos that keep a pool of pre-created worktrees
+    pub async fn find_with_worktree_pool(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectRepo,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      setup_script,
+                      cleanup_script,
+                      copy_files,
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      test_script,
+                      worktree_pool_size as "worktree_pool_size!: i64",
+                      worktree_pool_max_age_minutes as "worktree_pool_max_age_minutes!: i64",
+                      sparse_checkout_paths,
+                      sparse_checkout_agent_working_dir as "sparse_checkout_agent_working_dir!: bool",
+                      dependency_caches,
+                      settings_file_mode as "settings_file_mode: SettingsFileMode",
+                      settings_file_branch
+               FROM project_repos
+               WHERE worktree_pool_size > 0"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn add_repo_to_project(
         pool: &SqlitePool,
         project_id: Uuid,
@@ -235,7 +336,15 @@ impl ProjectRepo {
                          setup_script,
                          cleanup_script,
                          copy_files,
-                         parallel_setup_script as "parallel_setup_script!: bool""#,
+                         parallel_setup_script as "parallel_setup_script!: bool",
+                         test_script,
+                         worktree_pool_size as "worktree_pool_size!: i64",
+                         worktree_pool_max_age_minutes as "worktree_pool_max_age_minutes!: i64",
+                         sparse_checkout_paths,
+                         sparse_checkout_agent_working_dir as "sparse_checkout_agent_working_dir!: bool",
+                         dependency_caches,
+                         settings_file_mode as "settings_file_mode: SettingsFileMode",
+                         settings_file_branch"#,
             id,
             project_id,
             repo_id
@@ -256,9 +365,23 @@ impl ProjectRepo {
         let setup_script = payload.setup_script.clone();
         let cleanup_script = payload.cleanup_script.clone();
         let copy_files = payload.copy_files.clone();
+        let test_script = payload.test_script.clone();
         let parallel_setup_script = payload
             .parallel_setup_script
             .unwrap_or(existing.parallel_setup_script);
+        let worktree_pool_size = payload
+            .worktree_pool_size
+            .unwrap_or(existing.worktree_pool_size)
+            .max(0);
+        let worktree_pool_max_age_minutes = payload
+            .worktree_pool_max_age_minutes
+            .unwrap_or(existing.worktree_pool_max_age_minutes)
+            .max(1);
+        let sparse_checkout_paths = payload.sparse_checkout_paths.clone();
+        let sparse_checkout_agent_working_dir = payload
+            .sparse_checkout_agent_working_dir
+            .unwrap_or(existing.sparse_checkout_agent_working_dir);
+        let dependency_caches = payload.dependency_caches.clone();
 
         sqlx::query_as!(
             ProjectRepo,
@@ -266,19 +389,39 @@ impl ProjectRepo {
                SET setup_script = $1,
                    cleanup_script = $2,
                    copy_files = $3,
-                   parallel_setup_script = $4
-               WHERE project_id = $5 AND repo_id = $6
+                   parallel_setup_script = $4,
+                   test_script = $5,
+                   worktree_pool_size = $6,
+                   worktree_pool_max_age_minutes = $7,
+                   sparse_checkout_paths = $8,
+                   sparse_checkout_agent_working_dir = $9,
+                   dependency_caches = $10
+               WHERE project_id = $11 AND repo_id = $12
                RETURNING id as "id!: Uuid",
                          project_id as "project_id!: Uuid",
                          repo_id as "repo_id!: Uuid",
                          setup_script,
                          cleanup_script,
                          copy_files,
-                         parallel_setup_script as "parallel_setup_script!: bool""#,
+                         parallel_setup_script as "parallel_setup_script!: bool",
+                         test_script,
+                         worktree_pool_size as "worktree_pool_size!: i64",
+                         worktree_pool_max_age_minutes as "worktree_pool_max_age_minutes!: i64",
+                         sparse_checkout_paths,
+                         sparse_checkout_agent_working_dir as "sparse_checkout_agent_working_dir!: bool",
+                         dependency_caches,
+                         settings_file_mode as "settings_file_mode: SettingsFileMode",
+                         settings_file_branch"#,
             setup_script,
             cleanup_script,
             copy_files,
             parallel_setup_script,
+            test_script,
+            worktree_pool_size,
+            worktree_pool_max_age_minutes,
+            sparse_checkout_paths,
+            sparse_checkout_agent_working_dir,
+            dependency_caches,
             project_id,
             repo_id
         )
@@ -286,4 +429,38 @@ impl ProjectRepo {
         .await
         .map_err(ProjectRepoError::from)
     }
+
+    pub async fn set_settings_file_mode(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        mode: SettingsFileMode,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE project_repos SET settings_file_mode = $1 WHERE project_id = $2 AND repo_id = $3",
+            mode,
+            project_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_settings_file_branch(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        branch: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE project_repos SET settings_file_branch = $1 WHERE project_id = $2 AND repo_id = $3",
+            branch,
+            project_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use executors::actions::script::ScriptRequestLanguage;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Pipelines a script can be a step of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "script_pipeline", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptPipeline {
+    /// Runs before the coding agent starts
+    Setup,
+    /// Runs after the coding agent finished
+    Cleanup,
+    /// Runs on demand to check the work of an attempt
+    Verification,
+}
+
+/// A named script of a project, e.g. `db:migrate` or `test`
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectScript {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Repository the working directory is relative to; none for the workspace root
+    pub repo_id: Option<Uuid>,
+    pub name: String,
+    pub script: String,
+    pub language: ScriptRequestLanguage,
+    pub working_dir: Option<String>,
+    #[ts(type = "Record<string, string>")]
+    pub env: Json<BTreeMap<String, String>>,
+    pub timeout_seconds: Option<i64>,
+    /// Names of the scripts that have to succeed before this one runs
+    #[ts(type = "Array<string>")]
+    pub depends_on: Json<Vec<String>>,
+    pub pipeline: Option<ScriptPipeline>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateProjectScript {
+    pub repo_id: Option<Uuid>,
+    pub name: String,
+    pub script: String,
+    pub language: Option<ScriptRequestLanguage>,
+    pub working_dir: Option<String>,
+    #[ts(type = "Record<string, string> | null")]
+    pub env: Option<BTreeMap<String, String>>,
+    pub timeout_seconds: Option<i64>,
+    pub depends_on: Option<Vec<String>>,
+    pub pipeline: Option<ScriptPipeline>,
+}
+
+/// Fields left out keep their value; an empty `working_dir` clears it.
+/// `repo_id`, `timeout_seconds` and `pipeline` are replaced as given.
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateProjectScript {
+    pub repo_id: Option<Uuid>,
+    pub name: Option<String>,
+    pub script: Option<String>,
+    pub language: Option<ScriptRequestLanguage>,
+    pub working_dir: Option<String>,
+    #[ts(type = "Record<string, string> | null")]
+    pub env: Option<BTreeMap<String, String>>,
+    pub timeout_seconds: Option<i64>,
+    pub depends_on: Option<Vec<String>>,
+    pub pipeline: Option<ScriptPipeline>,
+}
+
+impl ProjectScript {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectScript,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id: Uuid",
+                      name,
+                      script,
+                      language as "language!: ScriptRequestLanguage",
+                      working_dir,
+                      env as "env!: Json<BTreeMap<String, String>>",
+                      timeout_seconds,
+                      depends_on as "depends_on!: Json<Vec<String>>",
+                      pipeline as "pipeline: ScriptPipeline",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_scripts
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectScript,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id: Uuid",
+                      name,
+                      script,
+                      language as "language!: ScriptRequestLanguage",
+                      working_dir,
+                      env as "env!: Json<BTreeMap<String, String>>",
+                      timeout_seconds,
+                      depends_on as "depends_on!: Json<Vec<String>>",
+                      pipeline as "pipeline: ScriptPipeline",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_scripts
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &CreateProjectScript,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let language = data.language.unwrap_or_default();
+        let working_dir = data.working_dir.as_deref().filter(|d| !d.is_empty());
+        let env = Json(data.env.clone().unwrap_or_default());
+        let depends_on = Json(data.depends_on.clone().unwrap_or_default());
+        sqlx::query_as!(
+            ProjectScript,
+            r#"INSERT INTO project_scripts (id, project_id, repo_id, name, script, language, working_dir, env, timeout_seconds, depends_on, pipeline)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+               RETURNING id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id: Uuid",
+                      name,
+                      script,
+                      language as "language!: ScriptRequestLanguage",
+                      working_dir,
+                      env as "env!: Json<BTreeMap<String, String>>",
+                      timeout_seconds,
+                      depends_on as "depends_on!: Json<Vec<String>>",
+                      pipeline as "pipeline: ScriptPipeline",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.repo_id,
+            data.name,
+            data.script,
+            language,
+            working_dir,
+            env,
+            data.timeout_seconds,
+            depends_on,
+            data.pipeline
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateProjectScript,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let Some(existing) = Self::find_by_id(pool, id).await? else {
+            return Ok(None);
+        };
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let script = data.script.as_ref().unwrap_or(&existing.script);
+        let language = data.language.unwrap_or(existing.language);
+        let working_dir = match &data.working_dir {
+            Some(dir) if dir.is_empty() => None,
+            Some(dir) => Some(dir.clone()),
+            None => existing.working_dir.clone(),
+        };
+        let env = Json(data.env.as_ref().unwrap_or(&existing.env.0));
+        let depends_on = Json(data.depends_on.as_ref().unwrap_or(&existing.depends_on.0));
+        sqlx::query_as!(
+            ProjectScript,
+            r#"UPDATE project_scripts
+               SET repo_id = $2, name = $3, script = $4, language = $5, working_dir = $6,
+                   env = $7, timeout_seconds = $8, depends_on = $9, pipeline = $10,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id: Uuid",
+                      name,
+                      script,
+                      language as "language!: ScriptRequestLanguage",
+                      working_dir,
+                      env as "env!: Json<BTreeMap<String, String>>",
+                      timeout_seconds,
+                      depends_on as "depends_on!: Json<Vec<String>>",
+                      pipeline as "pipeline: ScriptPipeline",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.repo_id,
+            name,
+            script,
+            language,
+            working_dir,
+            env,
+            data.timeout_seconds,
+            depends_on,
+            data.pipeline
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM project_scripts WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
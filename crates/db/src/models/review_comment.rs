@@ -0,0 +1,252 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Which side of the diff a review comment points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type, Default)]
+#[sqlx(type_name = "review_side", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewSide {
+    /// Lines of the base version the workspace branched from
+    Old,
+    /// Lines of the workspace's version
+    #[default]
+    New,
+}
+
+/// A line comment on a workspace's diff, anchored to a commit of the workspace
+/// branch. Comments are re-anchored as new commits arrive.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ReviewComment {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    /// Process whose changes were reviewed
+    pub execution_process_id: Option<Uuid>,
+    /// Path relative to the repository root
+    pub file_path: String,
+    pub side: ReviewSide,
+    /// First commented line (1-based)
+    pub line_start: i64,
+    /// Last commented line (inclusive)
+    pub line_end: i64,
+    pub body: String,
+    /// Commit the line range refers to
+    pub anchor_commit: String,
+    /// The commented lines as of `anchor_commit`
+    pub anchor_text: Option<String>,
+    /// The commented lines were changed or removed by a later commit
+    pub outdated: bool,
+    #[ts(type = "Date | null")]
+    pub sent_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date | null")]
+    pub resolved_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateReviewComment {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub execution_process_id: Option<Uuid>,
+    pub file_path: String,
+    pub side: ReviewSide,
+    pub line_start: i64,
+    pub line_end: i64,
+    pub body: String,
+    pub anchor_commit: String,
+    pub anchor_text: Option<String>,
+}
+
+impl ReviewComment {
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_at.is_some()
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ReviewComment,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      execution_process_id as "execution_process_id: Uuid",
+                      file_path,
+                      side as "side!: ReviewSide",
+                      line_start,
+                      line_end,
+                      body,
+                      anchor_commit,
+                      anchor_text,
+                      outdated as "outdated!: bool",
+                      sent_at as "sent_at: DateTime<Utc>",
+                      resolved_at as "resolved_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM review_comments
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// All comments of a workspace, ordered by file and line
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ReviewComment,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      execution_process_id as "execution_process_id: Uuid",
+                      file_path,
+                      side as "side!: ReviewSide",
+                      line_start,
+                      line_end,
+                      body,
+                      anchor_commit,
+                      anchor_text,
+                      outdated as "outdated!: bool",
+                      sent_at as "sent_at: DateTime<Utc>",
+                      resolved_at as "resolved_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM review_comments
+               WHERE workspace_id = $1
+               ORDER BY repo_id, file_path, line_start, created_at"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateReviewComment,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ReviewComment,
+            r#"INSERT INTO review_comments (
+                    id, workspace_id, repo_id, execution_process_id, file_path, side,
+                    line_start, line_end, body, anchor_commit, anchor_text
+               ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+               RETURNING id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      execution_process_id as "execution_process_id: Uuid",
+                      file_path,
+                      side as "side!: ReviewSide",
+                      line_start,
+                      line_end,
+                      body,
+                      anchor_commit,
+                      anchor_text,
+                      outdated as "outdated!: bool",
+                      sent_at as "sent_at: DateTime<Utc>",
+                      resolved_at as "resolved_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.workspace_id,
+            data.repo_id,
+            data.execution_process_id,
+            data.file_path,
+            data.side,
+            data.line_start,
+            data.line_end,
+            data.body,
+            data.anchor_commit,
+            data.anchor_text
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update_body(pool: &SqlitePool, id: Uuid, body: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE review_comments
+               SET body = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            body
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Move the comment to `anchor_commit`, or mark it outdated when its lines
+    /// could not be found there.
+    pub async fn update_anchor(
+        pool: &SqlitePool,
+        id: Uuid,
+        anchor_commit: &str,
+        line_start: i64,
+        line_end: i64,
+        outdated: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE review_comments
+               SET anchor_commit = $2, line_start = $3, line_end = $4, outdated = $5,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            anchor_commit,
+            line_start,
+            line_end,
+            outdated
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_resolved(
+        pool: &SqlitePool,
+        id: Uuid,
+        resolved: bool,
+    ) -> Result<(), sqlx::Error> {
+        let resolved_at = resolved.then(Utc::now);
+        sqlx::query!(
+            r#"UPDATE review_comments
+               SET resolved_at = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            resolved_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_sent(pool: &SqlitePool, ids: &[Uuid]) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        for id in ids {
+            sqlx::query!(
+                r#"UPDATE review_comments
+                   SET sent_at = $2, updated_at = datetime('now', 'subsec')
+                   WHERE id = $1"#,
+                id,
+                now
+            )
+            .execute(pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM review_comments WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
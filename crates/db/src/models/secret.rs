@@ -0,0 +1,222 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Secrets named with this prefix hold git host tokens. They are only read by
+/// name for API calls and never injected into an environment.
+pub const GIT_HOST_TOKEN_PREFIX: &str = "GIT_HOST_";
+
+/// A named secret injected as an environment variable. The value is stored
+/// encrypted and never leaves the server, so it is not part of this type.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Secret {
+    pub id: Uuid,
+    pub name: String,
+    /// Set for secrets of one project
+    pub project_id: Option<Uuid>,
+    /// Set for secrets of one repository; neither id is set for global secrets
+    pub repo_id: Option<Uuid>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Deserialize, TS)]
+pub struct CreateSecret {
+    pub name: String,
+    pub value: String,
+    pub project_id: Option<Uuid>,
+    pub repo_id: Option<Uuid>,
+}
+
+#[derive(Clone, Deserialize, TS)]
+pub struct UpdateSecret {
+    pub value: String,
+}
+
+/// A secret with its encrypted value, for injection into an execution
+#[derive(Clone, FromRow)]
+pub struct EncryptedSecret {
+    pub name: String,
+    pub project_id: Option<Uuid>,
+    pub repo_id: Option<Uuid>,
+    pub encrypted_value: Vec<u8>,
+}
+
+impl Secret {
+    /// Secrets of one scope: a project, a repository, or global when both are `None`
+    pub async fn find_by_scope(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+        repo_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Secret,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      project_id as "project_id: Uuid",
+                      repo_id as "repo_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM secrets
+               WHERE project_id IS $1 AND repo_id IS $2
+               ORDER BY name ASC"#,
+            project_id,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Secret,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      project_id as "project_id: Uuid",
+                      repo_id as "repo_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM secrets
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_name(
+        pool: &SqlitePool,
+        name: &str,
+        project_id: Option<Uuid>,
+        repo_id: Option<Uuid>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Secret,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      project_id as "project_id: Uuid",
+                      repo_id as "repo_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM secrets
+               WHERE name = $1 AND project_id IS $2 AND repo_id IS $3"#,
+            name,
+            project_id,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateSecret,
+        encrypted_value: &[u8],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            Secret,
+            r#"INSERT INTO secrets (id, name, project_id, repo_id, encrypted_value)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                      name,
+                      project_id as "project_id: Uuid",
+                      repo_id as "repo_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            data.project_id,
+            data.repo_id,
+            encrypted_value
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update_value(
+        pool: &SqlitePool,
+        id: Uuid,
+        encrypted_value: &[u8],
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Secret,
+            r#"UPDATE secrets
+               SET encrypted_value = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                      name,
+                      project_id as "project_id: Uuid",
+                      repo_id as "repo_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            encrypted_value
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM secrets WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl EncryptedSecret {
+    /// Global secrets, those of the project and those of the repositories in
+    /// the workspace, except git host tokens
+    pub async fn find_for_workspace(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            EncryptedSecret,
+            r#"SELECT name,
+                      project_id as "project_id: Uuid",
+                      repo_id as "repo_id: Uuid",
+                      encrypted_value as "encrypted_value!: Vec<u8>"
+               FROM secrets
+               WHERE name NOT GLOB 'GIT_HOST_*'
+                 AND ((project_id IS NULL AND repo_id IS NULL)
+                   OR project_id = $1
+                   OR repo_id IN (SELECT repo_id FROM workspace_repos WHERE workspace_id = $2))"#,
+            project_id,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The project's secret named `name`, else the global one
+    pub async fn find_by_name(
+        pool: &SqlitePool,
+        name: &str,
+        project_id: Option<Uuid>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            EncryptedSecret,
+            r#"SELECT name,
+                      project_id as "project_id: Uuid",
+                      repo_id as "repo_id: Uuid",
+                      encrypted_value as "encrypted_value!: Vec<u8>"
+               FROM secrets
+               WHERE name = $1
+                 AND repo_id IS NULL
+                 AND (project_id IS NULL OR project_id = $2)
+               ORDER BY project_id IS NULL
+               LIMIT 1"#,
+            name,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
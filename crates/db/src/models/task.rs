@@ -41,6 +41,8 @@ pub struct TaskWithAttemptStatus {
     #[ts(flatten)]
     pub task: Task,
     pub has_in_progress_attempt: bool,
+    /// A coding agent run is waiting for a free slot
+    pub has_queued_attempt: bool,
     pub last_attempt_failed: bool,
     pub executor: String,
 }
@@ -161,6 +163,16 @@ impl Task {
      LIMIT 1
   ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
 
+  CASE WHEN EXISTS (
+    SELECT 1
+      FROM workspaces w
+      JOIN sessions s ON s.workspace_id = w.id
+      JOIN execution_processes ep ON ep.session_id = s.id
+     WHERE w.task_id       = t.id
+       AND ep.status        = 'queued'
+     LIMIT 1
+  ) THEN 1 ELSE 0 END            AS "has_queued_attempt!: i64",
+
   CASE WHEN (
     SELECT ep.status
       FROM workspaces w
@@ -204,6 +216,7 @@ ORDER BY t.created_at DESC"#,
                     updated_at: rec.updated_at,
                 },
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
+                has_queued_attempt: rec.has_queued_attempt != 0,
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 executor: rec.executor,
             })
@@ -266,6 +279,17 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 
+    /// Current status of every task
+    pub async fn find_all_statuses(
+        pool: &SqlitePool,
+    ) -> Result<Vec<(Uuid, TaskStatus)>, sqlx::Error> {
+        let rows =
+            sqlx::query!(r#"SELECT id as "id!: Uuid", status as "status!: TaskStatus" FROM tasks"#)
+                .fetch_all(pool)
+                .await?;
+        Ok(rows.into_iter().map(|row| (row.id, row.status)).collect())
+    }
+
     pub async fn create(
         pool: &SqlitePool,
         data: &CreateTask,
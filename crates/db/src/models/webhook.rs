@@ -0,0 +1,177 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Events a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "webhook_event_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    TaskStatusChanged,
+    ExecutionFinished,
+    ExecutionFailed,
+    ApprovalRequested,
+    PrOpened,
+    PrMerged,
+    /// Sent on request from the settings; ignores the event filter
+    Test,
+}
+
+/// An endpoint receiving signed JSON payloads for the events it subscribed to
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub name: String,
+    pub url: String,
+    /// Key for the `X-Vibe-Kanban-Signature` HMAC
+    pub secret: String,
+    /// Subscribed events; empty means all events
+    #[ts(type = "Array<WebhookEventType>")]
+    pub events: Json<Vec<WebhookEventType>>,
+    pub enabled: bool,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateWebhook {
+    pub name: String,
+    pub url: String,
+    /// Generated when not given
+    pub secret: Option<String>,
+    pub events: Vec<WebhookEventType>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateWebhook {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub secret: Option<String>,
+    pub events: Option<Vec<WebhookEventType>>,
+    pub enabled: Option<bool>,
+}
+
+impl Webhook {
+    /// Whether the webhook wants `event`
+    pub fn subscribes_to(&self, event: WebhookEventType) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEventType>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEventType>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateWebhook,
+        secret: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let events = Json(&data.events);
+        let enabled = data.enabled.unwrap_or(true);
+        sqlx::query_as!(
+            Webhook,
+            r#"INSERT INTO webhooks (id, name, url, secret, events, enabled)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                      name,
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEventType>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            data.url,
+            secret,
+            events,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateWebhook,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let Some(existing) = Self::find_by_id(pool, id).await? else {
+            return Ok(None);
+        };
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let url = data.url.as_ref().unwrap_or(&existing.url);
+        let secret = data.secret.as_ref().unwrap_or(&existing.secret);
+        let events = Json(data.events.as_ref().unwrap_or(&existing.events.0));
+        let enabled = data.enabled.unwrap_or(existing.enabled);
+        sqlx::query_as!(
+            Webhook,
+            r#"UPDATE webhooks
+               SET name = $2, url = $3, secret = $4, events = $5, enabled = $6,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                      name,
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEventType>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            url,
+            secret,
+            events,
+            enabled
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM webhooks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
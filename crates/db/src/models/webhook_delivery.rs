@@ -0,0 +1,228 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::webhook::WebhookEventType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its first attempt or a retry
+    Pending,
+    Succeeded,
+    /// Gave up after the last retry
+    Failed,
+}
+
+/// One event queued for one webhook, with the outcome of its last attempt
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event_type: WebhookEventType,
+    /// JSON body sent to the endpoint
+    pub payload: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i64,
+    #[ts(type = "Date")]
+    pub next_attempt_at: DateTime<Utc>,
+    /// HTTP status of the last response
+    pub response_status: Option<i64>,
+    /// Start of the last response body
+    pub response_body: Option<String>,
+    pub last_error: Option<String>,
+    #[ts(type = "Date | null")]
+    pub delivered_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Outcome of one delivery attempt
+#[derive(Debug, Clone)]
+pub struct WebhookAttempt {
+    pub response_status: Option<i64>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+}
+
+impl WebhookDelivery {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event_type as "event_type!: WebhookEventType",
+                      payload,
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts,
+                      next_attempt_at as "next_attempt_at!: DateTime<Utc>",
+                      response_status,
+                      response_body,
+                      last_error,
+                      delivered_at as "delivered_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Latest deliveries of a webhook, newest first
+    pub async fn find_by_webhook_id(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event_type as "event_type!: WebhookEventType",
+                      payload,
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts,
+                      next_attempt_at as "next_attempt_at!: DateTime<Utc>",
+                      response_status,
+                      response_body,
+                      last_error,
+                      delivered_at as "delivered_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE webhook_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            webhook_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Pending deliveries whose next attempt is due, oldest first
+    pub async fn find_due(
+        pool: &SqlitePool,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event_type as "event_type!: WebhookEventType",
+                      payload,
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts,
+                      next_attempt_at as "next_attempt_at!: DateTime<Utc>",
+                      response_status,
+                      response_body,
+                      last_error,
+                      delivered_at as "delivered_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE status = 'pending' AND next_attempt_at <= $1
+               ORDER BY next_attempt_at ASC
+               LIMIT $2"#,
+            now,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Queue a delivery; `id` is also the `id` field of `payload`
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        webhook_id: Uuid,
+        event_type: WebhookEventType,
+        payload: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"INSERT INTO webhook_deliveries (id, webhook_id, event_type, payload, next_attempt_at)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event_type as "event_type!: WebhookEventType",
+                      payload,
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts,
+                      next_attempt_at as "next_attempt_at!: DateTime<Utc>",
+                      response_status,
+                      response_body,
+                      last_error,
+                      delivered_at as "delivered_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            webhook_id,
+            event_type,
+            payload,
+            now
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record an attempt. `next_attempt_at` of `None` ends the delivery: it
+    /// succeeded when the attempt has no error, and failed otherwise.
+    pub async fn record_attempt(
+        pool: &SqlitePool,
+        id: Uuid,
+        attempt: &WebhookAttempt,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        let status = match (next_attempt_at, &attempt.error) {
+            (Some(_), _) => WebhookDeliveryStatus::Pending,
+            (None, None) => WebhookDeliveryStatus::Succeeded,
+            (None, Some(_)) => WebhookDeliveryStatus::Failed,
+        };
+        let delivered_at = (status == WebhookDeliveryStatus::Succeeded).then_some(now);
+        let next_attempt_at = next_attempt_at.unwrap_or(now);
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = $2, attempts = attempts + 1, next_attempt_at = $3,
+                   response_status = $4, response_body = $5, last_error = $6,
+                   delivered_at = $7, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            next_attempt_at,
+            attempt.response_status,
+            attempt.response_body,
+            attempt.error,
+            delivered_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Queue a finished delivery again, keeping its payload
+    pub async fn requeue(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = 'pending', attempts = 0, next_attempt_at = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            now
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -34,13 +34,14 @@ pub struct RepoWithTargetBranch {
     pub target_branch: String,
 }
 
-/// Repo info with copy_files configuration from project_repos.
+/// Repo info with copy_files and dependency_caches configuration from project_repos.
 #[derive(Debug, Clone)]
 pub struct RepoWithCopyFiles {
     pub id: Uuid,
     pub path: PathBuf,
     pub name: String,
     pub copy_files: Option<String>,
+    pub dependency_caches: Option<String>,
 }
 
 impl WorkspaceRepo {
@@ -248,7 +249,7 @@ impl WorkspaceRepo {
         workspace_id: Uuid,
     ) -> Result<Vec<RepoWithCopyFiles>, sqlx::Error> {
         let rows = sqlx::query!(
-            r#"SELECT r.id as "id!: Uuid", r.path, r.name, pr.copy_files
+            r#"SELECT r.id as "id!: Uuid", r.path, r.name, pr.copy_files, pr.dependency_caches
                FROM repos r
                JOIN workspace_repos wr ON r.id = wr.repo_id
                JOIN workspaces w ON w.id = wr.workspace_id
@@ -267,6 +268,7 @@ impl WorkspaceRepo {
                 path: PathBuf::from(row.path),
                 name: row.name,
                 copy_files: row.copy_files,
+                dependency_caches: row.dependency_caches,
             })
             .collect())
     }
@@ -21,6 +21,7 @@ use services::services::{
     auth::AuthContext,
     config::{Config, ConfigError},
     container::{ContainerError, ContainerService},
+    content_search::ContentSearchService,
     events::{EventError, EventService},
     file_search_cache::FileSearchCache,
     filesystem::{FilesystemError, FilesystemService},
@@ -89,7 +90,7 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn analytics(&self) -> &Option<AnalyticsService>;
 
-    fn container(&self) -> &impl ContainerService;
+    fn container(&self) -> &(impl ContainerService + Clone + Send + Sync + 'static);
 
     fn git(&self) -> &GitService;
 
@@ -105,6 +106,8 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn file_search_cache(&self) -> &Arc<FileSearchCache>;
 
+    fn content_search(&self) -> &Arc<ContentSearchService>;
+
     fn approvals(&self) -> &Approvals;
 
     fn queued_message_service(&self) -> &QueuedMessageService;
@@ -133,7 +136,21 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 analytics_service: analytics_service.clone(),
             });
         let publisher = self.share_publisher().ok();
-        PrMonitorService::spawn(db, analytics, publisher).await
+        PrMonitorService::spawn(db, self.container().clone(), analytics, publisher).await
+    }
+
+    /// Start queued agent runs as slots free up. Spawn it after orphaned
+    /// executions are cleaned up, so dispatched runs are not mistaken for them.
+    fn spawn_execution_dispatcher(&self) -> tokio::task::JoinHandle<()> {
+        let container = self.container().clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = container.dispatch_queued_executions().await {
+                    tracing::error!("Failed to dispatch queued executions: {}", e);
+                }
+                container.scheduler().wait().await;
+            }
+        })
     }
 
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
@@ -176,7 +193,12 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
                     match self
                         .project()
-                        .create_project(&self.db().pool, self.repo(), create_data.clone())
+                        .create_project(
+                            &self.db().pool,
+                            self.repo(),
+                            self.git(),
+                            create_data.clone(),
+                        )
                         .await
                     {
                         Ok(project) => {
@@ -1,22 +1,39 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
+use sqlx::Type;
+use tokio::{process::Command, sync::oneshot};
 use ts_rs::TS;
-use workspace_utils::shell::get_shell_command;
+use workspace_utils::shell::{get_shell_command, resolve_executable_path};
 
 use crate::{
     actions::Executable,
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
-    executors::{ExecutorError, SpawnedChild},
+    executors::{ExecutorError, ExecutorExitResult, SpawnedChild},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, Type)]
+#[sqlx(type_name = "TEXT")]
 pub enum ScriptRequestLanguage {
+    #[default]
     Bash,
+    Python,
+    Node,
+}
+
+impl ScriptRequestLanguage {
+    /// Interpreter and the flag that makes it run the script given as argument
+    fn interpreter(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Self::Bash => None,
+            Self::Python if cfg!(windows) => Some(("python", "-c")),
+            Self::Python => Some(("python3", "-c")),
+            Self::Node => Some(("node", "-e")),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -25,6 +42,8 @@ pub enum ScriptContext {
     CleanupScript,
     DevServer,
     ToolInstallScript,
+    /// A named project script run on demand or as a verification step
+    NamedScript,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -36,6 +55,16 @@ pub struct ScriptRequest {
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    /// Name of the project script this runs, if any
+    #[serde(default)]
+    pub script_name: Option<String>,
+    /// Variables added to the execution environment
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// The script is killed and marked failed after this many seconds
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub timeout_seconds: Option<u64>,
 }
 
 #[async_trait]
@@ -52,22 +81,53 @@ impl Executable for ScriptRequest {
             None => current_dir.to_path_buf(),
         };
 
-        let (shell_cmd, shell_arg) = get_shell_command();
-        let mut command = Command::new(shell_cmd);
+        let mut command = match self.language.interpreter() {
+            Some((program, flag)) => {
+                let executable = resolve_executable_path(program).await.ok_or_else(|| {
+                    ExecutorError::ExecutableNotFound {
+                        program: program.to_string(),
+                    }
+                })?;
+                let mut command = Command::new(executable);
+                command.arg(flag);
+                command
+            }
+            None => {
+                let (shell_cmd, shell_arg) = get_shell_command();
+                let mut command = Command::new(shell_cmd);
+                command.arg(shell_arg);
+                command
+            }
+        };
         command
             .kill_on_drop(true)
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
-            .arg(shell_arg)
             .arg(&self.script)
             .current_dir(&effective_dir);
 
         // Apply environment variables
         env.apply_to_command(&mut command);
+        command.envs(&self.env);
 
         let child = command.group_spawn()?;
 
-        Ok(child.into())
+        let mut spawned = SpawnedChild::from(child);
+        if let Some(timeout) = self.timeout_seconds {
+            // The container kills the process group when the exit signal fires
+            let (mut exit_tx, exit_rx) = oneshot::channel();
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_secs(timeout)) => {
+                        let _ = exit_tx.send(ExecutorExitResult::Failure);
+                    }
+                    _ = exit_tx.closed() => {}
+                }
+            });
+            spawned.exit_signal = Some(exit_rx);
+        }
+
+        Ok(spawned)
     }
 }
@@ -17,7 +17,9 @@ use db::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
+        project::Project,
         project_repo::ProjectRepo,
+        project_script::ProjectScript,
         repo::Repo,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
         task::{Task, TaskStatus},
@@ -30,7 +32,7 @@ use executors::{
     actions::{
         Executable, ExecutorAction, ExecutorActionType,
         coding_agent_follow_up::CodingAgentFollowUpRequest,
-        coding_agent_initial::CodingAgentInitialRequest,
+        coding_agent_initial::CodingAgentInitialRequest, script::ScriptContext,
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::ExecutionEnv,
@@ -38,26 +40,43 @@ use executors::{
     logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
     profile::ExecutorProfileId,
 };
-use futures::{FutureExt, TryStreamExt, stream::select};
+use futures::{
+    FutureExt, Stream, StreamExt, TryStreamExt, future,
+    stream::{self, select},
+};
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
     config::Config,
+    conflict_resolution::ConflictResolutionService,
     container::{ContainerError, ContainerRef, ContainerService},
+    dependency_cache::DependencyCacheService,
+    dev_server::{self, DevServerInfo, DevServerService},
     diff_stream::{self, DiffStreamHandle},
     git::{Commit, GitCli, GitService},
+    git_host::GitHostConfig,
     image::ImageService,
     notification::NotificationService,
+    pr_review::PrReviewService,
     queued_message::QueuedMessageService,
+    scheduler::ExecutionScheduler,
+    secrets::SecretsService,
     share::SharePublisher,
+    sparse_checkout::cone_for_repo,
+    webhooks::WebhookService,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
+    worktree_manager::WorktreeManager,
+    worktree_pool::{PooledWorktree, WorktreePool, WorktreePoolError, run_setup_script},
 };
 use tokio::{sync::RwLock, task::JoinHandle};
 use tokio_util::io::ReaderStream;
 use utils::{
+    assets::{dependency_cache_dir, secrets_key_path},
     log_msg::LogMsg,
+    metrics,
     msg_store::MsgStore,
+    redact::{Redactor, StreamRedactor},
     text::{git_branch_id, short_uuid, truncate_to_char_boundary},
 };
 use uuid::Uuid;
@@ -78,6 +97,62 @@ pub struct LocalContainerService {
     queued_message_service: QueuedMessageService,
     publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     notification_service: NotificationService,
+    webhooks: WebhookService,
+    conflict_resolution_service: ConflictResolutionService,
+    pr_review_service: PrReviewService,
+    worktree_pool: WorktreePool,
+    scheduler: ExecutionScheduler,
+    dev_servers: DevServerService,
+    secrets: SecretsService,
+    dependency_caches: DependencyCacheService,
+}
+
+/// How often running dev servers are checked for idle shutdown
+const DEV_SERVER_IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Decode output chunks, masking secrets even when they span two chunks
+fn redacted_chunks<S, B>(
+    chunks: S,
+    redactor: Arc<Redactor>,
+) -> impl Stream<Item = io::Result<String>>
+where
+    S: Stream<Item = io::Result<B>>,
+    B: AsRef<[u8]>,
+{
+    let state = Arc::new(std::sync::Mutex::new(StreamRedactor::new(redactor)));
+    let tail = state.clone();
+    chunks
+        .map_ok(move |chunk| {
+            state
+                .lock()
+                .unwrap()
+                .push(&String::from_utf8_lossy(chunk.as_ref()))
+        })
+        .chain(stream::once(
+            async move { Ok(tail.lock().unwrap().finish()) },
+        ))
+        .try_filter(|text| future::ready(!text.is_empty()))
+}
+
+/// Count an exited execution process and observe its wall time
+fn record_execution_finished(ctx: &ExecutionContext) {
+    let process = &ctx.execution_process;
+    let labels = [
+        ctx.session
+            .executor
+            .as_deref()
+            .unwrap_or("none")
+            .to_string(),
+        metrics::label(&process.run_reason),
+        metrics::label(&process.status),
+    ];
+    let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+    metrics::EXECUTION_PROCESSES_FINISHED.inc(&labels);
+    if let Some(completed_at) = process.completed_at
+        && let Ok(duration) = (completed_at - process.started_at).to_std()
+    {
+        metrics::EXECUTION_DURATION.observe_duration(&labels, duration);
+    }
 }
 
 impl LocalContainerService {
@@ -96,6 +171,19 @@ impl LocalContainerService {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let interrupt_senders = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
+        let webhooks = WebhookService::new(db.clone());
+        let conflict_resolution_service = ConflictResolutionService::load(db.pool.clone())
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to load conflict resolutions: {}", e);
+                ConflictResolutionService::new()
+            });
+        let pr_review_service = PrReviewService::new();
+        let worktree_pool = WorktreePool::new();
+        let scheduler = ExecutionScheduler::new(db.clone(), config.clone());
+        let dev_servers = DevServerService::new();
+        let secrets = SecretsService::new(secrets_key_path());
+        let dependency_caches = DependencyCacheService::new(dependency_cache_dir());
 
         let container = LocalContainerService {
             db,
@@ -110,9 +198,19 @@ impl LocalContainerService {
             queued_message_service,
             publisher,
             notification_service,
+            webhooks,
+            conflict_resolution_service,
+            pr_review_service,
+            worktree_pool,
+            scheduler,
+            dev_servers,
+            secrets,
+            dependency_caches,
         };
 
         container.spawn_workspace_cleanup().await;
+        container.spawn_worktree_pool_refresh();
+        container.webhooks.spawn_delivery_worker();
 
         container
     }
@@ -211,6 +309,266 @@ impl LocalContainerService {
         });
     }
 
+    /// Keep the worktree pools of project repos that have one configured filled
+    /// with worktrees on the current tip of the repo's checked-out branch.
+    fn spawn_worktree_pool_refresh(&self) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            WorktreePool::clear_base_dir().await;
+            let mut interval = tokio::time::interval(Duration::from_secs(30));
+            loop {
+                interval.tick().await;
+                if let Err(e) = container.refresh_worktree_pool().await {
+                    tracing::error!("Failed to refresh worktree pool: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Mark a dev server ready once it answers (or logs the project's ready
+    /// pattern), stop it after the configured idle time without preview
+    /// traffic, and free its ports when it exits.
+    fn spawn_dev_server_monitor(&self, info: DevServerInfo, ready_pattern: Option<String>) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            let (workspace_id, process_id) = (info.workspace_id, info.execution_process_id);
+            let Some(store) = container.get_msg_store_by_id(&process_id).await else {
+                container.dev_servers.release(workspace_id, process_id);
+                return;
+            };
+
+            let pattern = dev_server::ready_pattern(ready_pattern.as_deref());
+            let mut readiness = Box::pin(dev_server::wait_until_ready(
+                store.clone(),
+                info.port,
+                pattern,
+            ));
+            let mut finished = Box::pin(async move {
+                let mut stream = store.history_plus_stream();
+                while let Some(msg) = stream.next().await {
+                    if matches!(msg, Ok(LogMsg::Finished)) {
+                        break;
+                    }
+                }
+            });
+            let mut waiting_for_ready = true;
+            let mut idle_check = tokio::time::interval(DEV_SERVER_IDLE_CHECK_INTERVAL);
+
+            loop {
+                tokio::select! {
+                    ready = &mut readiness, if waiting_for_ready => {
+                        waiting_for_ready = false;
+                        if !ready {
+                            break;
+                        }
+                        tracing::info!(
+                            "Dev server {} for workspace {} is ready on port {}",
+                            process_id,
+                            workspace_id,
+                            info.port
+                        );
+                        container.dev_servers.mark_ready(workspace_id, process_id);
+                    }
+                    _ = &mut finished => break,
+                    _ = idle_check.tick() => {
+                        let timeout = container.config.read().await.dev_servers.idle_timeout();
+                        let Some(timeout) = timeout else {
+                            continue;
+                        };
+                        if !container
+                            .dev_servers
+                            .idle_for(workspace_id)
+                            .is_some_and(|idle| idle >= timeout)
+                        {
+                            continue;
+                        }
+                        tracing::info!(
+                            "Stopping dev server {} for workspace {} after {} idle minutes",
+                            process_id,
+                            workspace_id,
+                            timeout.as_secs() / 60
+                        );
+                        match ExecutionProcess::find_by_id(&container.db.pool, process_id).await {
+                            Ok(Some(process)) => {
+                                if let Err(e) = container
+                                    .stop_execution(&process, ExecutionProcessStatus::Killed)
+                                    .await
+                                {
+                                    tracing::error!(
+                                        "Failed to stop idle dev server {}: {}",
+                                        process_id,
+                                        e
+                                    );
+                                }
+                            }
+                            Ok(None) => {}
+                            Err(e) => {
+                                tracing::error!("Failed to load dev server {}: {}", process_id, e)
+                            }
+                        }
+                        break;
+                    }
+                }
+            }
+
+            container.dev_servers.release(workspace_id, process_id);
+        });
+    }
+
+    async fn refresh_worktree_pool(&self) -> Result<(), sqlx::Error> {
+        let configs = ProjectRepo::find_with_worktree_pool(&self.db.pool).await?;
+        let configured = configs.iter().map(|c| (c.project_id, c.repo_id)).collect();
+        WorktreePool::discard(self.worktree_pool.drain_unconfigured(&configured)).await;
+
+        for config in configs {
+            let Some(repo) = Repo::find_by_id(&self.db.pool, config.repo_id).await? else {
+                continue;
+            };
+            let Some(project) = Project::find_by_id(&self.db.pool, config.project_id).await? else {
+                continue;
+            };
+            let sparse_paths = cone_for_repo(
+                config.sparse_checkout_paths.as_deref(),
+                config.sparse_checkout_agent_working_dir,
+                &repo.name,
+                project.default_agent_working_dir.as_deref(),
+            );
+            let key = (config.project_id, config.repo_id);
+            let Ok(target_branch) = self.git.get_current_branch(&repo.path) else {
+                continue;
+            };
+            let Ok(base_commit) = self.git.get_branch_oid(&repo.path, &target_branch) else {
+                continue;
+            };
+            let max_age =
+                Duration::from_secs(config.worktree_pool_max_age_minutes.max(1) as u64 * 60);
+            let size = config.worktree_pool_size.max(0) as usize;
+
+            let mut stale = self.worktree_pool.drain_stale(
+                key,
+                &target_branch,
+                &base_commit,
+                config.setup_script.as_deref(),
+                &sparse_paths,
+                max_age,
+            );
+            stale.extend(self.worktree_pool.drain_excess(key, size));
+            WorktreePool::discard(stale).await;
+
+            while self.worktree_pool.ready_count(key) < size {
+                match self
+                    .prepare_pooled_worktree(
+                        &config,
+                        &repo,
+                        &target_branch,
+                        &base_commit,
+                        &sparse_paths,
+                        max_age,
+                    )
+                    .await
+                {
+                    Ok(worktree) => self.worktree_pool.add(worktree),
+                    Err(e) => {
+                        // Retried on the next refresh
+                        tracing::warn!(
+                            "Failed to prepare pooled worktree for repo '{}': {}",
+                            repo.name,
+                            e
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Create a detached worktree at `base_commit`, copy the project files into
+    /// it and run the setup script
+    async fn prepare_pooled_worktree(
+        &self,
+        config: &ProjectRepo,
+        repo: &Repo,
+        target_branch: &str,
+        base_commit: &str,
+        sparse_paths: &[String],
+        max_age: Duration,
+    ) -> Result<PooledWorktree, WorktreePoolError> {
+        let path = WorktreePool::new_worktree_path(&repo.name);
+        WorktreeManager::create_detached_worktree(&repo.path, &path, base_commit, sparse_paths)
+            .await?;
+
+        let worktree = PooledWorktree {
+            path,
+            project_id: config.project_id,
+            repo_id: repo.id,
+            repo_path: repo.path.clone(),
+            target_branch: target_branch.to_string(),
+            base_commit: base_commit.to_string(),
+            setup_script: config.setup_script.clone(),
+            sparse_paths: sparse_paths.to_vec(),
+            created_at: std::time::Instant::now(),
+            max_age,
+        };
+
+        if let Some(copy_files) = &config.copy_files
+            && let Err(e) = self
+                .copy_project_files(&repo.path, &worktree.path, copy_files)
+                .await
+        {
+            tracing::warn!("Failed to copy project files to pooled worktree: {}", e);
+        }
+
+        if let Some(caches) = &config.dependency_caches {
+            self.dependency_caches
+                .provision(repo.id, &worktree.path, caches)
+                .await;
+        }
+
+        if let Some(script) = &config.setup_script
+            && let Err(e) = run_setup_script(&worktree.path, script).await
+        {
+            WorktreePool::discard(vec![worktree]).await;
+            return Err(e);
+        }
+
+        if let Some(caches) = &config.dependency_caches {
+            self.dependency_caches
+                .save(repo.id, &worktree.path, caches)
+                .await;
+        }
+
+        tracing::debug!(
+            "Prepared pooled worktree for repo '{}' at {}",
+            repo.name,
+            base_commit
+        );
+        Ok(worktree)
+    }
+
+    /// Sparse-checkout cones of the workspace's repos, for those that have one
+    async fn sparse_paths_for_workspace(
+        &self,
+        workspace: &Workspace,
+        project_id: Uuid,
+        repositories: &[Repo],
+    ) -> Result<HashMap<Uuid, Vec<String>>, sqlx::Error> {
+        let project_repos = ProjectRepo::find_by_project_id(&self.db.pool, project_id).await?;
+        Ok(repositories
+            .iter()
+            .filter_map(|repo| {
+                let config = project_repos.iter().find(|pr| pr.repo_id == repo.id)?;
+                let cone = cone_for_repo(
+                    config.sparse_checkout_paths.as_deref(),
+                    config.sparse_checkout_agent_working_dir,
+                    &repo.name,
+                    workspace.agent_working_dir.as_deref(),
+                );
+                (!cone.is_empty()).then_some((repo.id, cone))
+            })
+            .collect())
+    }
+
     /// Record the current HEAD commit for each repository as the "after" state.
     /// Errors are silently ignored since this runs after the main execution completes
     /// and failure should not block process finalization.
@@ -290,6 +648,17 @@ impl LocalContainerService {
         for repo in repos {
             let worktree_path = workspace_root.join(&repo.name);
 
+            // Never auto-commit in the middle of a rebase/merge; the conflict
+            // resolution flow continues the operation itself.
+            if let Ok(Some(op)) = self.git().detect_conflict_op(&worktree_path) {
+                tracing::debug!(
+                    "Skipping auto-commit for repo '{}': {:?} in progress",
+                    repo.name,
+                    op
+                );
+                continue;
+            }
+
             match git.has_changes(&worktree_path) {
                 Ok(true) => {
                     repos_with_changes.push((repo.clone(), worktree_path));
@@ -340,6 +709,48 @@ impl LocalContainerService {
 
     /// Spawn a background task that polls the child process for completion and
     /// cleans up the execution entry when it exits.
+    /// Store the dependency caches of the repository a setup script just
+    /// installed into, in the background
+    fn spawn_dependency_cache_save(&self, ctx: &ExecutionContext) {
+        let Ok(ExecutorActionType::ScriptRequest(request)) = ctx
+            .execution_process
+            .executor_action()
+            .map(ExecutorAction::typ)
+        else {
+            return;
+        };
+        let (ScriptContext::SetupScript, Some(repo_name), Some(container_ref)) = (
+            &request.context,
+            request.working_dir.clone(),
+            ctx.workspace.container_ref.clone(),
+        ) else {
+            return;
+        };
+        let container = self.clone();
+        let workspace_id = ctx.workspace.id;
+        tokio::spawn(async move {
+            let repos =
+                match WorkspaceRepo::find_repos_with_copy_files(&container.db.pool, workspace_id)
+                    .await
+                {
+                    Ok(repos) => repos,
+                    Err(e) => {
+                        tracing::warn!("Failed to load dependency caches to store: {}", e);
+                        return;
+                    }
+                };
+            if let Some(repo) = repos.iter().find(|repo| repo.name == repo_name)
+                && let Some(caches) = &repo.dependency_caches
+            {
+                let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+                container
+                    .dependency_caches
+                    .save(repo.id, &worktree_path, caches)
+                    .await;
+            }
+        });
+    }
+
     pub fn spawn_exit_monitor(
         &self,
         exec_id: &Uuid,
@@ -411,6 +822,12 @@ impl LocalContainerService {
             }
 
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
+                container.webhooks.execution_ended(&ctx).await;
+                record_execution_finished(&ctx);
+                if ctx.execution_process.run_reason == ExecutionProcessRunReason::CodingAgent {
+                    container.scheduler.wake();
+                }
+
                 // Update executor session summary if available
                 if let Err(e) = container.update_executor_session_summary(&exec_id).await {
                     tracing::warn!("Failed to update executor session summary: {}", e);
@@ -429,6 +846,10 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
+                if success {
+                    container.spawn_dependency_cache_save(&ctx);
+                }
+
                 if success || cleanup_done {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
@@ -536,6 +957,26 @@ impl LocalContainerService {
                         "exit_code": ctx.execution_process.exit_code,
                     })));
                 }
+
+                // Continue an agent-assisted conflict resolution driven by this process
+                if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::CodingAgent
+                ) {
+                    let container = container.clone();
+                    let workspace_id = ctx.workspace.id;
+                    tokio::spawn(async move {
+                        container
+                            .advance_conflict_resolution(workspace_id, exec_id)
+                            .await;
+                    });
+                }
+
+                // Push (and reply on the PR) once a PR follow-up has finished
+                let container = container.clone();
+                tokio::spawn(async move {
+                    container.advance_pr_follow_up(exec_id).await;
+                });
             }
 
             // Now that commit/next-action/finalization steps for this process are complete,
@@ -603,19 +1044,22 @@ impl LocalContainerService {
         format!("{}-{}", short_uuid(workspace_id), task_title_id)
     }
 
-    async fn track_child_msgs_in_store(&self, id: Uuid, child: &mut AsyncGroupChild) {
-        let store = Arc::new(MsgStore::new());
+    async fn track_child_msgs_in_store(
+        &self,
+        id: Uuid,
+        child: &mut AsyncGroupChild,
+        redactor: Arc<Redactor>,
+    ) {
+        let store = Arc::new(MsgStore::with_redactor(redactor.clone()));
 
         let out = child.inner().stdout.take().expect("no stdout");
         let err = child.inner().stderr.take().expect("no stderr");
 
         // Map stdout bytes -> LogMsg::Stdout
-        let out = ReaderStream::new(out)
-            .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
+        let out = redacted_chunks(ReaderStream::new(out), redactor.clone()).map_ok(LogMsg::Stdout);
 
         // Map stderr bytes -> LogMsg::Stderr
-        let err = ReaderStream::new(err)
-            .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
+        let err = redacted_chunks(ReaderStream::new(err), redactor).map_ok(LogMsg::Stderr);
 
         // If you have a JSON Patch source, map it to LogMsg::JsonPatch too, then select all three.
 
@@ -697,8 +1141,8 @@ impl LocalContainerService {
         Ok(())
     }
 
-    /// Copy project files and images to the workspace.
-    /// Skips files/images that already exist (fast no-op if all exist).
+    /// Copy project files, dependency caches and images to the workspace.
+    /// Skips files/images/caches that already exist (fast no-op if all exist).
     async fn copy_files_and_images(
         &self,
         workspace_dir: &Path,
@@ -707,10 +1151,15 @@ impl LocalContainerService {
         let repos = WorkspaceRepo::find_repos_with_copy_files(&self.db.pool, workspace.id).await?;
 
         for repo in &repos {
+            let worktree_path = workspace_dir.join(&repo.name);
+            if let Some(caches) = &repo.dependency_caches {
+                self.dependency_caches
+                    .provision(repo.id, &worktree_path, caches)
+                    .await;
+            }
             if let Some(copy_files) = &repo.copy_files
                 && !copy_files.trim().is_empty()
             {
-                let worktree_path = workspace_dir.join(&repo.name);
                 self.copy_project_files(&repo.path, &worktree_path, copy_files)
                     .await
                     .unwrap_or_else(|e| {
@@ -818,7 +1267,9 @@ impl LocalContainerService {
 
         let project_repos =
             ProjectRepo::find_by_project_id_with_names(&self.db.pool, ctx.project.id).await?;
-        let cleanup_action = self.cleanup_actions_for_repos(&project_repos);
+        let project_scripts =
+            ProjectScript::find_by_project_id(&self.db.pool, ctx.project.id).await?;
+        let cleanup_action = self.cleanup_actions_for_repos(&project_repos, &project_scripts);
 
         let working_dir = ctx
             .workspace
@@ -889,10 +1340,54 @@ impl ContainerService for LocalContainerService {
         &self.notification_service
     }
 
+    fn webhooks(&self) -> &WebhookService {
+        &self.webhooks
+    }
+
+    fn conflict_resolution_service(&self) -> &ConflictResolutionService {
+        &self.conflict_resolution_service
+    }
+
+    fn queued_message_service(&self) -> &QueuedMessageService {
+        &self.queued_message_service
+    }
+
+    fn pr_review_service(&self) -> &PrReviewService {
+        &self.pr_review_service
+    }
+
+    fn worktree_pool(&self) -> &WorktreePool {
+        &self.worktree_pool
+    }
+
+    fn scheduler(&self) -> &ExecutionScheduler {
+        &self.scheduler
+    }
+
+    fn dev_servers(&self) -> &DevServerService {
+        &self.dev_servers
+    }
+
+    fn secrets(&self) -> &SecretsService {
+        &self.secrets
+    }
+
+    fn dependency_caches(&self) -> &DependencyCacheService {
+        &self.dependency_caches
+    }
+
     async fn git_branch_prefix(&self) -> String {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn git_hosts(&self) -> Vec<GitHostConfig> {
+        self.config.read().await.git_hosts.clone()
+    }
+
+    async fn pr_review_authors(&self) -> Vec<String> {
+        self.config.read().await.pr_review_authors.clone()
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -923,11 +1418,16 @@ impl ContainerService for LocalContainerService {
             .map(|wr| (wr.repo_id, wr.target_branch.clone()))
             .collect();
 
+        let mut sparse_paths = self
+            .sparse_paths_for_workspace(workspace, task.project_id, &repositories)
+            .await?;
+
         let workspace_inputs: Vec<RepoWorkspaceInput> = repositories
             .iter()
             .map(|repo| {
                 let target_branch = target_branches.get(&repo.id).cloned().unwrap_or_default();
                 RepoWorkspaceInput::new(repo.clone(), target_branch)
+                    .with_sparse_paths(sparse_paths.remove(&repo.id).unwrap_or_default())
             })
             .collect();
 
@@ -935,8 +1435,18 @@ impl ContainerService for LocalContainerService {
             &workspace_dir,
             &workspace_inputs,
             &workspace.branch,
+            Some((&self.worktree_pool, task.project_id)),
         )
         .await?;
+        self.worktree_pool.mark_prepared(
+            workspace.id,
+            created_workspace
+                .worktrees
+                .iter()
+                .filter(|w| w.from_pool)
+                .map(|w| w.repo_id)
+                .collect(),
+        );
 
         // Copy project files and images to workspace
         self.copy_files_and_images(&created_workspace.workspace_dir, workspace)
@@ -977,20 +1487,28 @@ impl ContainerService for LocalContainerService {
             )));
         }
 
+        let task = workspace
+            .parent_task(&self.db.pool)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
         let workspace_dir = if let Some(container_ref) = &workspace.container_ref {
             PathBuf::from(container_ref)
         } else {
-            let task = workspace
-                .parent_task(&self.db.pool)
-                .await?
-                .ok_or(sqlx::Error::RowNotFound)?;
             let workspace_dir_name =
                 LocalContainerService::dir_name_from_workspace(&workspace.id, &task.title);
             WorkspaceManager::get_workspace_base_dir().join(&workspace_dir_name)
         };
 
-        WorkspaceManager::ensure_workspace_exists(&workspace_dir, &repositories, &workspace.branch)
+        let sparse_paths = self
+            .sparse_paths_for_workspace(workspace, task.project_id, &repositories)
             .await?;
+        WorkspaceManager::ensure_workspace_exists(
+            &workspace_dir,
+            &repositories,
+            &workspace.branch,
+            &sparse_paths,
+        )
+        .await?;
 
         if workspace.container_ref.is_none() {
             Workspace::update_container_ref(
@@ -1060,6 +1578,7 @@ impl ContainerService for LocalContainerService {
                     self.approvals.clone(),
                     self.db.clone(),
                     self.notification_service.clone(),
+                    self.webhooks.clone(),
                     execution_process.id,
                 ),
                 _ => Arc::new(NoopExecutorApprovalService {}),
@@ -1080,14 +1599,43 @@ impl ContainerService for LocalContainerService {
             .await?
             .ok_or(ContainerError::Other(anyhow!("Project not found for task")))?;
 
+        // Secrets first, so they cannot shadow the VK_* variables
+        let secrets = self
+            .secrets
+            .resolve_for_workspace(&self.db.pool, project.id, workspace.id)
+            .await
+            .map_err(|e| ContainerError::Other(e.into()))?;
+        let redactor = Arc::new(Redactor::new(secrets.values().cloned()));
+        for (name, value) in secrets {
+            env.insert(name, value);
+        }
+
         env.insert("VK_PROJECT_NAME", &project.name);
         env.insert("VK_PROJECT_ID", project.id.to_string());
         env.insert("VK_TASK_ID", task.id.to_string());
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
 
+        // Dev servers get their own ports so several workspaces can run side by side
+        let dev_server = if execution_process.run_reason == ExecutionProcessRunReason::DevServer {
+            let info = self
+                .dev_servers
+                .allocate(
+                    workspace.id,
+                    execution_process.id,
+                    &dev_server::parse_port_names(project.dev_server_ports.as_deref()),
+                )
+                .map_err(|e| ContainerError::Other(e.into()))?;
+            for (key, value) in info.env_vars() {
+                env.insert(key, value);
+            }
+            Some(info)
+        } else {
+            None
+        };
+
         // Create the child and stream, add to execution tracker with timeout
-        let mut spawned = tokio::time::timeout(
+        let spawned = tokio::time::timeout(
             Duration::from_secs(30),
             executor_action.spawn(&current_dir, approvals_service, &env),
         )
@@ -1096,9 +1644,20 @@ impl ContainerService for LocalContainerService {
             ContainerError::Other(anyhow!(
                 "Timeout: process took more than 30 seconds to start"
             ))
-        })??;
+        })
+        .and_then(|res| res.map_err(ContainerError::from));
+        let mut spawned = match spawned {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                if let Some(info) = &dev_server {
+                    self.dev_servers
+                        .release(info.workspace_id, info.execution_process_id);
+                }
+                return Err(e);
+            }
+        };
 
-        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
+        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child, redactor)
             .await;
 
         self.add_child_to_store(execution_process.id, spawned.child)
@@ -1113,6 +1672,10 @@ impl ContainerService for LocalContainerService {
         // Spawn unified exit monitor: watches OS exit and optional executor signal
         let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
 
+        if let Some(info) = dev_server {
+            self.spawn_dev_server_monitor(info, project.dev_server_ready_pattern);
+        }
+
         Ok(())
     }
 
@@ -1189,7 +1752,7 @@ impl ContainerService for LocalContainerService {
         if let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
             && !matches!(
                 ctx.execution_process.run_reason,
-                ExecutionProcessRunReason::DevServer
+                ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::Script
             )
         {
             match Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await {
@@ -10,10 +10,12 @@ use services::services::{
     auth::AuthContext,
     config::{Config, load_config_from_file, save_config_to_file},
     container::ContainerService,
+    content_search::ContentSearchService,
     events::EventService,
     file_search_cache::FileSearchCache,
     filesystem::FilesystemService,
     git::GitService,
+    git_host::move_tokens_to_secrets,
     image::ImageService,
     oauth_credentials::OAuthCredentials,
     project::ProjectService,
@@ -49,6 +51,7 @@ pub struct LocalDeployment {
     filesystem: FilesystemService,
     events: EventService,
     file_search_cache: Arc<FileSearchCache>,
+    content_search: Arc<ContentSearchService>,
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
     share_publisher: Result<SharePublisher, RemoteClientNotConfigured>,
@@ -185,9 +188,21 @@ impl Deployment for LocalDeployment {
         )
         .await;
 
+        // Older configs kept git host tokens in plaintext
+        {
+            let mut config = config.write().await;
+            if move_tokens_to_secrets(&mut config.git_hosts, container.secrets(), &db.pool).await {
+                save_config_to_file(&config, &config_path()).await?;
+            }
+        }
+
         let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
+        container
+            .webhooks()
+            .spawn_task_status_watcher(events.msg_store().clone());
 
         let file_search_cache = Arc::new(FileSearchCache::new());
+        let content_search = Arc::new(ContentSearchService::new());
 
         let deployment = Self {
             config,
@@ -202,6 +217,7 @@ impl Deployment for LocalDeployment {
             filesystem,
             events,
             file_search_cache,
+            content_search,
             approvals,
             queued_message_service,
             share_publisher,
@@ -230,7 +246,7 @@ impl Deployment for LocalDeployment {
         &self.analytics
     }
 
-    fn container(&self) -> &impl ContainerService {
+    fn container(&self) -> &(impl ContainerService + Clone + Send + Sync + 'static) {
         &self.container
     }
 
@@ -262,6 +278,10 @@ impl Deployment for LocalDeployment {
         &self.file_search_cache
     }
 
+    fn content_search(&self) -> &Arc<ContentSearchService> {
+        &self.content_search
+    }
+
     fn approvals(&self) -> &Approvals {
         &self.approvals
     }
@@ -16,6 +16,7 @@ fn generate_types_content() -> String {
         remote::db::tasks::SharedTask::decl(),
         remote::db::users::UserData::decl(),
         db::models::project::Project::decl(),
+        db::models::project::ProjectGitHost::decl(),
         db::models::project::CreateProject::decl(),
         db::models::project::UpdateProject::decl(),
         db::models::project::SearchResult::decl(),
@@ -24,6 +25,11 @@ fn generate_types_content() -> String {
         db::models::project_repo::ProjectRepo::decl(),
         db::models::project_repo::CreateProjectRepo::decl(),
         db::models::project_repo::UpdateProjectRepo::decl(),
+        db::models::project_repo::SettingsFileMode::decl(),
+        db::models::project_script::ScriptPipeline::decl(),
+        db::models::project_script::ProjectScript::decl(),
+        db::models::project_script::CreateProjectScript::decl(),
+        db::models::project_script::UpdateProjectScript::decl(),
         db::models::workspace_repo::WorkspaceRepo::decl(),
         db::models::workspace_repo::CreateWorkspaceRepo::decl(),
         db::models::workspace_repo::RepoWithTargetBranch::decl(),
@@ -49,12 +55,30 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_queue::QueuedExecution::decl(),
+        db::models::execution_queue::UpdateQueuedExecution::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
         db::models::merge::MergeStatus::decl(),
         db::models::merge::PullRequestInfo::decl(),
+        db::models::merge::CiStatus::decl(),
+        db::models::merge::CiCheck::decl(),
+        db::models::merge::PrCiStatus::decl(),
+        db::models::review_comment::ReviewSide::decl(),
+        db::models::review_comment::ReviewComment::decl(),
+        db::models::api_token::ApiTokenScope::decl(),
+        db::models::api_token::ApiToken::decl(),
+        db::models::webhook::WebhookEventType::decl(),
+        db::models::webhook::Webhook::decl(),
+        db::models::webhook::CreateWebhook::decl(),
+        db::models::webhook::UpdateWebhook::decl(),
+        db::models::webhook_delivery::WebhookDeliveryStatus::decl(),
+        db::models::webhook_delivery::WebhookDelivery::decl(),
+        db::models::secret::Secret::decl(),
+        db::models::secret::CreateSecret::decl(),
+        db::models::secret::UpdateSecret::decl(),
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::CreateApprovalRequest::decl(),
         utils::approvals::ApprovalResponse::decl(),
@@ -91,7 +115,9 @@ fn generate_types_content() -> String {
         utils::api::projects::RemoteProjectMembersResponse::decl(),
         server::routes::projects::CreateRemoteProjectRequest::decl(),
         server::routes::projects::LinkToExistingRequest::decl(),
+        server::routes::projects::SetSettingsFileModeRequest::decl(),
         server::routes::repo::RegisterRepoRequest::decl(),
+        server::routes::dependency_caches::DependencyCacheInfo::decl(),
         server::routes::repo::InitRepoRequest::decl(),
         server::routes::tags::TagSearchParams::decl(),
         server::routes::oauth::TokenResponse::decl(),
@@ -104,6 +130,10 @@ fn generate_types_content() -> String {
         server::routes::config::CheckEditorAvailabilityResponse::decl(),
         server::routes::config::CheckAgentAvailabilityQuery::decl(),
         server::routes::oauth::CurrentUserResponse::decl(),
+        server::routes::local_auth::LocalAuthStatus::decl(),
+        server::routes::local_auth::LocalLoginRequest::decl(),
+        server::routes::local_auth::CreateApiTokenRequest::decl(),
+        server::routes::local_auth::CreateApiTokenResponse::decl(),
         server::routes::sessions::CreateFollowUpAttempt::decl(),
         server::routes::task_attempts::ChangeTargetBranchRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchResponse::decl(),
@@ -126,27 +156,54 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::gh_cli_setup::GhCliSetupError::decl(),
         server::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
         server::routes::task_attempts::AbortConflictsRequest::decl(),
+        server::routes::task_attempts::ResolveConflictsRequest::decl(),
+        server::routes::task_attempts::ResolveConflictsError::decl(),
         server::routes::task_attempts::GitOperationError::decl(),
         server::routes::task_attempts::PushError::decl(),
         server::routes::task_attempts::pr::CreatePrError::decl(),
         server::routes::task_attempts::BranchStatus::decl(),
         server::routes::task_attempts::RunScriptError::decl(),
+        server::routes::task_attempts::RunProjectScriptRequest::decl(),
         server::routes::task_attempts::pr::AttachPrResponse::decl(),
         server::routes::task_attempts::pr::AttachExistingPrRequest::decl(),
         server::routes::task_attempts::pr::PrCommentsResponse::decl(),
         server::routes::task_attempts::pr::GetPrCommentsError::decl(),
         server::routes::task_attempts::pr::GetPrCommentsQuery::decl(),
-        services::services::github::UnifiedPrComment::decl(),
+        server::routes::task_attempts::review_comments::CreateReviewCommentRequest::decl(),
+        server::routes::task_attempts::review_comments::UpdateReviewCommentRequest::decl(),
+        server::routes::task_attempts::review_comments::ResolveReviewCommentRequest::decl(),
+        server::routes::task_attempts::review_comments::ReviewCommentWithRepo::decl(),
+        server::routes::task_attempts::review_comments::SendReviewRequest::decl(),
+        server::routes::task_attempts::review_comments::SendReviewResponse::decl(),
+        server::routes::task_attempts::sparse_checkout::RepoSparseCheckout::decl(),
+        server::routes::task_attempts::sparse_checkout::ExpandSparseCheckoutRequest::decl(),
+        services::services::git_host::UnifiedPrComment::decl(),
         server::routes::task_attempts::RepoBranchStatus::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
         services::services::filesystem::DirectoryListResponse::decl(),
+        services::services::content_search::ContentMatch::decl(),
+        services::services::content_search::ContentSearchResponse::decl(),
         services::services::config::Config::decl(),
         services::services::config::NotificationConfig::decl(),
+        services::services::notification::NotificationEvent::decl(),
+        services::services::notification::WebhookPreset::decl(),
+        services::services::notification::NotificationChannelKind::decl(),
+        services::services::notification::NotificationChannel::decl(),
         services::services::config::ThemeMode::decl(),
         services::services::config::EditorConfig::decl(),
         services::services::config::EditorType::decl(),
         services::services::config::EditorOpenError::decl(),
         services::services::config::GitHubConfig::decl(),
+        services::services::git_host::GitHostKind::decl(),
+        services::services::git_host::GitHostConfig::decl(),
+        services::services::scheduler::ConcurrencyLimits::decl(),
+        services::services::dev_server::DevServerSettings::decl(),
+        services::services::dev_server::DevServerInfo::decl(),
+        services::services::settings_file::SettingsFileDifference::decl(),
+        services::services::settings_file::RepoSettingsFileStatus::decl(),
+        services::services::dependency_cache::DependencyCacheEntry::decl(),
+        services::services::dependency_cache::PruneDependencyCaches::decl(),
+        services::services::dependency_cache::PruneDependencyCachesResult::decl(),
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
@@ -154,6 +211,8 @@ fn generate_types_content() -> String {
         services::services::share::SharedTaskDetails::decl(),
         services::services::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
+        services::services::conflict_resolution::ConflictResolutionState::decl(),
+        services::services::conflict_resolution::ConflictResolution::decl(),
         services::services::git::ConflictOp::decl(),
         executors::actions::ExecutorAction::decl(),
         executors::mcp_config::McpConfig::decl(),
@@ -2,7 +2,7 @@
 //!
 //! This binary starts the Vibe Kanban MCP System server with the selected transport protocol.
 
-use server::mcp::system_server::SystemServer;
+use server::mcp::{backend_api_token, gateway, system_server::SystemServer};
 use std::env;
 use tracing_subscriber::{EnvFilter, prelude::*};
 use utils::{
@@ -64,8 +64,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Transport: {}", transport);
     tracing::info!("Backend API: {}", base_url);
 
+    // Token for a backend with authentication enabled
+    let api_token = backend_api_token().await;
+    if api_token.is_some() {
+        tracing::info!("Authenticating to the backend with an API token");
+    }
+
     // Create server instance
-    let server = SystemServer::new(&base_url);
+    let server = SystemServer::new(&base_url, api_token.as_deref());
 
     // Run with selected transport
     match transport.to_lowercase().as_str() {
@@ -79,13 +85,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                 let addr = format!("0.0.0.0:{}", port);
                 tracing::info!("🚀 Starting HTTP transport");
-                tracing::info!("📡 Listening on: http://{}", addr);
-                tracing::info!("🔗 Endpoint: http://{}/mcp", addr);
+                tracing::info!("📡 Listening on: {}", addr);
+                tracing::info!("🔗 Endpoint: /mcp");
                 tracing::info!("⚠️  CORS: Allowing all origins (development mode)");
                 tracing::info!("Ready for MCP client connections");
 
-                // Use custom HTTP runner with permissive security for development
-                server.run_http_custom(&addr).await?;
+                // Behind the authenticating gateway when tokens, TLS or an
+                // audit log are configured
+                gateway::run_http(&addr, server.http_router(), |addr| async move {
+                    server.run_http_custom(&addr).await
+                })
+                .await?;
             }
             #[cfg(not(feature = "http"))]
             {
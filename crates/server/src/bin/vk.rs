@@ -0,0 +1,12 @@
+//! `vk`, a command-line client for a running Vibe Kanban server.
+
+use clap::Parser;
+use server::cli::{Cli, run};
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run(Cli::parse()).await {
+        eprintln!("error: {e:#}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use anyhow::{Context, anyhow, bail};
+use futures_util::StreamExt;
+use reqwest::RequestBuilder;
+use serde::{Serialize, de::DeserializeOwned};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{Message, client::IntoClientRequest, http::HeaderValue},
+};
+use utils::{log_msg::LogMsg, port_file::read_port_file};
+
+use crate::mcp::{backend_api_token, backend_client};
+
+type Socket = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+#[derive(serde::Deserialize)]
+struct ApiResponseEnvelope<T> {
+    success: bool,
+    data: Option<T>,
+    message: Option<String>,
+}
+
+/// Client for the local server's REST and websocket API
+pub struct VkClient {
+    base_url: String,
+    api_token: Option<String>,
+    client: reqwest::Client,
+}
+
+impl VkClient {
+    /// Connect to `url`, or find the local server the same way the MCP server
+    /// does: `VIBE_BACKEND_URL`, then `HOST` with `BACKEND_PORT`/`PORT`, then
+    /// the port file
+    pub async fn discover(url: Option<String>) -> anyhow::Result<Self> {
+        let base_url = match url.or_else(|| std::env::var("VIBE_BACKEND_URL").ok()) {
+            Some(url) => url,
+            None => {
+                let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+                let port = match std::env::var("BACKEND_PORT").or_else(|_| std::env::var("PORT")) {
+                    Ok(port) => port
+                        .parse::<u16>()
+                        .with_context(|| format!("Invalid port value '{port}'"))?,
+                    Err(_) => read_port_file("vibe-kanban")
+                        .await
+                        .context("Could not find a running Vibe Kanban server")?,
+                };
+                format!("http://{host}:{port}")
+            }
+        };
+        let api_token = backend_api_token().await;
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client: backend_client(api_token.as_deref()),
+            api_token,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn send_envelope<T: DeserializeOwned>(
+        &self,
+        rb: RequestBuilder,
+    ) -> anyhow::Result<Option<T>> {
+        let resp = rb.send().await.context("Failed to connect to the server")?;
+        let status = resp.status();
+        let body = resp.text().await?;
+        let envelope: ApiResponseEnvelope<T> = serde_json::from_str(&body)
+            .map_err(|_| anyhow!("Server returned {status}: {}", body.trim()))?;
+        if !envelope.success || !status.is_success() {
+            bail!(
+                "{}",
+                envelope
+                    .message
+                    .unwrap_or_else(|| format!("Server returned {status}"))
+            );
+        }
+        Ok(envelope.data)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, rb: RequestBuilder) -> anyhow::Result<T> {
+        self.send_envelope(rb)
+            .await?
+            .ok_or_else(|| anyhow!("Server response is missing data"))
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        self.send(self.client.get(self.url(path))).await
+    }
+
+    pub async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> anyhow::Result<T> {
+        self.send(self.client.post(self.url(path)).json(body)).await
+    }
+
+    pub async fn put<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> anyhow::Result<T> {
+        self.send(self.client.put(self.url(path)).json(body)).await
+    }
+
+    /// POST to an endpoint that answers with `ApiResponse<()>`
+    pub async fn post_no_data(&self, path: &str, body: &impl Serialize) -> anyhow::Result<()> {
+        self.send_envelope::<serde_json::Value>(self.client.post(self.url(path)).json(body))
+            .await?;
+        Ok(())
+    }
+
+    /// POST to an endpoint that answers with a bare JSON body instead of an
+    /// `ApiResponse`
+    pub async fn post_raw<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> anyhow::Result<T> {
+        let resp = self
+            .client
+            .post(self.url(path))
+            .json(body)
+            .send()
+            .await
+            .context("Failed to connect to the server")?;
+        let status = resp.status();
+        if !status.is_success() {
+            bail!("Server returned {status}");
+        }
+        Ok(resp.json().await?)
+    }
+
+    pub async fn websocket(&self, path: &str) -> anyhow::Result<LogStream> {
+        LogStream::connect(&self.url(path), self.api_token.as_deref()).await
+    }
+}
+
+/// A websocket of `LogMsg` JSON, as sent by the log, diff and event streams
+pub struct LogStream {
+    socket: Socket,
+}
+
+impl LogStream {
+    /// Open the websocket at `url`, given with its `http(s)://` scheme
+    pub async fn connect(url: &str, api_token: Option<&str>) -> anyhow::Result<Self> {
+        let url = url.replacen("http", "ws", 1);
+        let mut request = url.as_str().into_client_request()?;
+        if let Some(token) = api_token {
+            request.headers_mut().insert(
+                "Authorization",
+                HeaderValue::from_str(&format!("Bearer {token}"))?,
+            );
+        }
+        let (socket, _) = connect_async(request)
+            .await
+            .with_context(|| format!("Failed to open {url}"))?;
+        Ok(Self { socket })
+    }
+
+    /// The next message; `None` once the stream is finished or closed, or when
+    /// `idle` passes without a message
+    pub async fn next(&mut self, idle: Option<Duration>) -> anyhow::Result<Option<LogMsg>> {
+        loop {
+            let message = match idle {
+                Some(idle) => match tokio::time::timeout(idle, self.socket.next()).await {
+                    Ok(message) => message,
+                    Err(_) => return Ok(None),
+                },
+                None => self.socket.next().await,
+            };
+            let text = match message.transpose()? {
+                Some(Message::Text(text)) => text,
+                Some(Message::Close(_)) | None => return Ok(None),
+                Some(_) => continue,
+            };
+            if let Ok(msg) = serde_json::from_str::<LogMsg>(&text) {
+                return Ok(Some(msg));
+            }
+            let value: serde_json::Value = serde_json::from_str(&text)?;
+            if value.get("finished").is_some() {
+                return Ok(None);
+            }
+            if let Some(error) = value.get("error").and_then(|e| e.as_str()) {
+                bail!("{error}");
+            }
+        }
+    }
+}
@@ -0,0 +1,666 @@
+//! `vk`, a command-line client for a running Vibe Kanban server.
+//!
+//! Every command prints a human-readable table or text by default and the
+//! server's JSON with `--json`, for scripts.
+
+pub mod client;
+pub mod output;
+
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
+
+use anyhow::{Context, bail};
+use clap::{Args, Parser, Subcommand};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    project::Project,
+    repo::Repo,
+    session::Session,
+    task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    workspace::Workspace,
+    workspace_repo::RepoWithTargetBranch,
+};
+use executors::{
+    executors::BaseCodingAgent,
+    logs::{NormalizedEntry, utils::patch::extract_normalized_entry_from_patch},
+    profile::ExecutorProfileId,
+};
+use serde_json::json;
+use services::services::git::GitBranch;
+use utils::{
+    approvals::{ApprovalResponse, ApprovalStatus},
+    diff::{Diff, create_unified_diff},
+    log_msg::LogMsg,
+};
+use uuid::Uuid;
+
+use crate::{
+    cli::client::{LogStream, VkClient},
+    routes::task_attempts::{
+        CreateTaskAttemptBody, MergeTaskAttemptRequest, WorkspaceRepoInput,
+        pr::CreateGitHubPrRequest,
+    },
+};
+
+/// How long a snapshot read waits for more messages before it is complete
+const SNAPSHOT_IDLE: Duration = Duration::from_millis(750);
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "vk",
+    version,
+    about = "Command-line client for a running Vibe Kanban server"
+)]
+pub struct Cli {
+    /// Server URL; found from `VIBE_BACKEND_URL` or the port file when unset
+    #[arg(long, global = true)]
+    url: Option<String>,
+    /// Print JSON instead of tables
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Manage projects
+    #[command(subcommand)]
+    Projects(ProjectsCommand),
+    /// Manage tasks
+    #[command(subcommand)]
+    Tasks(TasksCommand),
+    /// Run and manage task attempts
+    #[command(subcommand)]
+    Attempt(AttemptCommand),
+    /// Show a coding agent's conversation
+    Logs(LogsArgs),
+    /// Show an attempt's changes against its target branch
+    Diff(DiffArgs),
+    /// Answer tool approval requests
+    #[command(subcommand)]
+    Approvals(ApprovalsCommand),
+}
+
+#[derive(Subcommand, Debug)]
+enum ProjectsCommand {
+    /// List all projects
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum TasksCommand {
+    /// List the tasks of a project
+    List {
+        #[arg(long)]
+        project: Uuid,
+        /// Only tasks with this status, e.g. `todo` or `inprogress`
+        #[arg(long)]
+        status: Option<TaskStatus>,
+    },
+    /// Create a task
+    Create {
+        #[arg(long)]
+        project: Uuid,
+        #[arg(long)]
+        title: String,
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Change a task's title, description or status
+    Update {
+        task_id: Uuid,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long)]
+        status: Option<TaskStatus>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AttemptCommand {
+    /// Start a coding agent on a task
+    Start {
+        task_id: Uuid,
+        /// Coding agent, e.g. `CLAUDE_CODE` or `CODEX`
+        #[arg(long, value_parser = parse_executor)]
+        executor: BaseCodingAgent,
+        #[arg(long)]
+        variant: Option<String>,
+        /// `REPO_ID[:TARGET_BRANCH]`, repeatable; defaults to every project
+        /// repository on its current branch
+        #[arg(long = "repo")]
+        repos: Vec<String>,
+    },
+    /// Send a follow-up prompt to an attempt's coding agent
+    FollowUp {
+        attempt_id: Uuid,
+        prompt: String,
+        #[arg(long)]
+        variant: Option<String>,
+    },
+    /// Stop everything running in an attempt
+    Stop { attempt_id: Uuid },
+    /// Merge an attempt into its target branch
+    Merge {
+        attempt_id: Uuid,
+        /// Needed when the attempt spans several repositories
+        #[arg(long)]
+        repo: Option<Uuid>,
+    },
+    /// Open a pull request for an attempt
+    Pr {
+        attempt_id: Uuid,
+        #[arg(long)]
+        title: String,
+        #[arg(long)]
+        body: Option<String>,
+        #[arg(long)]
+        target_branch: Option<String>,
+        #[arg(long)]
+        draft: bool,
+        /// Needed when the attempt spans several repositories
+        #[arg(long)]
+        repo: Option<Uuid>,
+    },
+}
+
+#[derive(Args, Debug)]
+struct LogsArgs {
+    /// Execution process to show
+    #[arg(required_unless_present = "attempt")]
+    process_id: Option<Uuid>,
+    /// Show the attempt's latest coding agent run instead
+    #[arg(long, conflicts_with = "process_id")]
+    attempt: Option<Uuid>,
+    /// Keep printing new entries until the run ends
+    #[arg(long, short)]
+    follow: bool,
+}
+
+#[derive(Args, Debug)]
+struct DiffArgs {
+    attempt_id: Uuid,
+    /// Only list changed files with line counts
+    #[arg(long)]
+    stat: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum ApprovalsCommand {
+    /// Approve or deny a pending tool call, as shown by `vk logs`
+    Respond {
+        approval_id: String,
+        /// Execution process that asked for the approval
+        #[arg(long)]
+        process: Uuid,
+        #[arg(long, conflicts_with = "deny", required_unless_present = "deny")]
+        approve: bool,
+        #[arg(long)]
+        deny: bool,
+        /// Reason passed back to the agent when denying
+        #[arg(long, requires = "deny")]
+        reason: Option<String>,
+    },
+}
+
+fn parse_executor(value: &str) -> Result<BaseCodingAgent, String> {
+    BaseCodingAgent::from_str(&value.to_uppercase().replace('-', "_"))
+        .map_err(|_| format!("unknown executor '{value}'"))
+}
+
+pub async fn run(cli: Cli) -> anyhow::Result<()> {
+    let client = VkClient::discover(cli.url).await?;
+    let json = cli.json;
+    match cli.command {
+        Command::Projects(ProjectsCommand::List) => list_projects(&client, json).await,
+        Command::Tasks(command) => tasks(&client, command, json).await,
+        Command::Attempt(command) => attempt(&client, command, json).await,
+        Command::Logs(args) => logs(&client, args, json).await,
+        Command::Diff(args) => diff(&client, args, json).await,
+        Command::Approvals(command) => approvals(&client, command, json).await,
+    }
+}
+
+async fn list_projects(client: &VkClient, json: bool) -> anyhow::Result<()> {
+    let projects: Vec<Project> = client.get("/api/projects").await?;
+    if json {
+        return output::json(&projects);
+    }
+    output::table(
+        ["ID", "NAME", "CREATED"],
+        projects
+            .iter()
+            .map(|p| {
+                [
+                    p.id.to_string(),
+                    p.name.clone(),
+                    p.created_at.format("%Y-%m-%d").to_string(),
+                ]
+            })
+            .collect(),
+    );
+    Ok(())
+}
+
+fn task_row(task: &TaskWithAttemptStatus) -> [String; 4] {
+    let attempt = if task.has_in_progress_attempt {
+        "running"
+    } else if task.has_queued_attempt {
+        "queued"
+    } else if task.last_attempt_failed {
+        "failed"
+    } else {
+        ""
+    };
+    [
+        task.id.to_string(),
+        task.status.to_string(),
+        attempt.to_string(),
+        output::truncate(&task.title, 60),
+    ]
+}
+
+async fn tasks(client: &VkClient, command: TasksCommand, json: bool) -> anyhow::Result<()> {
+    match command {
+        TasksCommand::List { project, status } => {
+            let mut tasks: Vec<TaskWithAttemptStatus> = client
+                .get(&format!("/api/tasks?project_id={project}"))
+                .await?;
+            if let Some(status) = status {
+                tasks.retain(|t| t.status == status);
+            }
+            if json {
+                return output::json(&tasks);
+            }
+            output::table(
+                ["ID", "STATUS", "ATTEMPT", "TITLE"],
+                tasks.iter().map(task_row).collect(),
+            );
+        }
+        TasksCommand::Create {
+            project,
+            title,
+            description,
+        } => {
+            let task: Task = client
+                .post(
+                    "/api/tasks",
+                    &CreateTask::from_title_description(project, title, description),
+                )
+                .await?;
+            if json {
+                return output::json(&task);
+            }
+            println!("{}", task.id);
+        }
+        TasksCommand::Update {
+            task_id,
+            title,
+            description,
+            status,
+        } => {
+            let payload = UpdateTask {
+                title,
+                description,
+                status,
+                parent_workspace_id: None,
+                image_ids: None,
+            };
+            let task: Task = client
+                .put(&format!("/api/tasks/{task_id}"), &payload)
+                .await?;
+            if json {
+                return output::json(&task);
+            }
+            println!("{} {}", task.id, task.status);
+        }
+    }
+    Ok(())
+}
+
+/// The attempt's only repository, or `repo` when it has several
+async fn attempt_repo(
+    client: &VkClient,
+    attempt_id: Uuid,
+    repo: Option<Uuid>,
+) -> anyhow::Result<Uuid> {
+    if let Some(repo) = repo {
+        return Ok(repo);
+    }
+    let repos: Vec<RepoWithTargetBranch> = client
+        .get(&format!("/api/task-attempts/{attempt_id}/repos"))
+        .await?;
+    match repos.as_slice() {
+        [only] => Ok(only.repo.id),
+        [] => bail!("Attempt {attempt_id} has no repositories"),
+        _ => bail!(
+            "Attempt {attempt_id} has several repositories, pick one with --repo: {}",
+            repos
+                .iter()
+                .map(|r| format!("{} ({})", r.repo.id, r.repo.name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// `--repo` values, or every project repository on its current branch
+async fn attempt_repos(
+    client: &VkClient,
+    task_id: Uuid,
+    repos: Vec<String>,
+) -> anyhow::Result<Vec<WorkspaceRepoInput>> {
+    let mut inputs = Vec::new();
+    let mut needs_branch = Vec::new();
+    for repo in repos {
+        let (id, branch) = match repo.split_once(':') {
+            Some((id, branch)) => (id, Some(branch.to_string())),
+            None => (repo.as_str(), None),
+        };
+        let repo_id = Uuid::parse_str(id).with_context(|| format!("Invalid repo id '{id}'"))?;
+        match branch {
+            Some(target_branch) => inputs.push(WorkspaceRepoInput {
+                repo_id,
+                target_branch,
+            }),
+            None => needs_branch.push(repo_id),
+        }
+    }
+    if inputs.is_empty() && needs_branch.is_empty() {
+        let task: Task = client.get(&format!("/api/tasks/{task_id}")).await?;
+        let project_repos: Vec<Repo> = client
+            .get(&format!("/api/projects/{}/repositories", task.project_id))
+            .await?;
+        needs_branch = project_repos.into_iter().map(|r| r.id).collect();
+    }
+    for repo_id in needs_branch {
+        let branches: Vec<GitBranch> = client
+            .get(&format!("/api/repos/{repo_id}/branches"))
+            .await?;
+        let current = branches
+            .into_iter()
+            .find(|b| b.is_current)
+            .with_context(|| {
+                format!("Repo {repo_id} has no current branch, pass REPO_ID:BRANCH")
+            })?;
+        inputs.push(WorkspaceRepoInput {
+            repo_id,
+            target_branch: current.name,
+        });
+    }
+    Ok(inputs)
+}
+
+async fn attempt(client: &VkClient, command: AttemptCommand, json: bool) -> anyhow::Result<()> {
+    match command {
+        AttemptCommand::Start {
+            task_id,
+            executor,
+            variant,
+            repos,
+        } => {
+            let payload = CreateTaskAttemptBody {
+                task_id,
+                executor_profile_id: ExecutorProfileId { executor, variant },
+                repos: attempt_repos(client, task_id, repos).await?,
+            };
+            let workspace: Workspace = client.post("/api/task-attempts", &payload).await?;
+            if json {
+                return output::json(&workspace);
+            }
+            println!("{}", workspace.id);
+        }
+        AttemptCommand::FollowUp {
+            attempt_id,
+            prompt,
+            variant,
+        } => {
+            let sessions: Vec<Session> = client
+                .get(&format!("/api/sessions?workspace_id={attempt_id}"))
+                .await?;
+            let session = sessions
+                .first()
+                .with_context(|| format!("Attempt {attempt_id} has no session"))?;
+            let process: ExecutionProcess = client
+                .post(
+                    &format!("/api/sessions/{}/follow-up", session.id),
+                    &json!({ "prompt": prompt, "variant": variant }),
+                )
+                .await?;
+            if json {
+                return output::json(&process);
+            }
+            println!("{}", process.id);
+        }
+        AttemptCommand::Stop { attempt_id } => {
+            client
+                .post_no_data(&format!("/api/task-attempts/{attempt_id}/stop"), &json!({}))
+                .await?;
+            if !json {
+                println!("Stopped {attempt_id}");
+            }
+        }
+        AttemptCommand::Merge { attempt_id, repo } => {
+            let repo_id = attempt_repo(client, attempt_id, repo).await?;
+            client
+                .post_no_data(
+                    &format!("/api/task-attempts/{attempt_id}/merge"),
+                    &MergeTaskAttemptRequest { repo_id },
+                )
+                .await?;
+            if !json {
+                println!("Merged {attempt_id}");
+            }
+        }
+        AttemptCommand::Pr {
+            attempt_id,
+            title,
+            body,
+            target_branch,
+            draft,
+            repo,
+        } => {
+            let payload = CreateGitHubPrRequest {
+                title,
+                body,
+                target_branch,
+                draft: Some(draft),
+                repo_id: attempt_repo(client, attempt_id, repo).await?,
+                auto_generate_description: false,
+            };
+            let url: String = client
+                .post(&format!("/api/task-attempts/{attempt_id}/pr"), &payload)
+                .await?;
+            if json {
+                return output::json(&json!({ "url": url }));
+            }
+            println!("{url}");
+        }
+    }
+    Ok(())
+}
+
+/// The latest coding agent run of an attempt
+async fn latest_agent_process(client: &VkClient, attempt_id: Uuid) -> anyhow::Result<Uuid> {
+    let mut stream = client
+        .websocket(&format!(
+            "/api/execution-processes/stream/ws?workspace_id={attempt_id}"
+        ))
+        .await?;
+    let Some(LogMsg::JsonPatch(patch)) = stream.next(Some(Duration::from_secs(10))).await? else {
+        bail!("No execution processes for attempt {attempt_id}");
+    };
+    let snapshot = serde_json::to_value(&patch)?;
+    let processes: Vec<ExecutionProcess> = snapshot
+        .get(0)
+        .and_then(|op| op.get("value"))
+        .and_then(|v| v.as_object())
+        .map(|map| {
+            map.values()
+                .filter_map(|v| serde_json::from_value(v.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    processes
+        .into_iter()
+        .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent && !p.dropped)
+        .max_by_key(|p| p.created_at)
+        .map(|p| p.id)
+        .with_context(|| format!("Attempt {attempt_id} has no coding agent runs"))
+}
+
+pub(crate) fn print_entry(entry: &NormalizedEntry, json: bool) -> anyhow::Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(entry)?);
+    } else {
+        let text = output::entry(entry);
+        if !text.is_empty() {
+            println!("{text}\n");
+        }
+    }
+    Ok(())
+}
+
+async fn logs(client: &VkClient, args: LogsArgs, json: bool) -> anyhow::Result<()> {
+    let process_id = match (args.process_id, args.attempt) {
+        (Some(process_id), _) => process_id,
+        (None, Some(attempt_id)) => latest_agent_process(client, attempt_id).await?,
+        (None, None) => bail!("Pass an execution process id or --attempt"),
+    };
+    let mut stream = client
+        .websocket(&format!(
+            "/api/execution-processes/{process_id}/normalized-logs/ws"
+        ))
+        .await?;
+    let idle = (!args.follow).then_some(SNAPSHOT_IDLE);
+
+    // Entries are rewritten while the agent streams them, so one is printed
+    // once a later entry starts or the stream ends
+    let mut pending: Option<(usize, NormalizedEntry)> = None;
+    while let Some(msg) = stream.next(idle).await? {
+        let LogMsg::JsonPatch(patch) = msg else {
+            continue;
+        };
+        let Some((index, entry)) = extract_normalized_entry_from_patch(&patch) else {
+            continue;
+        };
+        if let Some((pending_index, previous)) = pending.take()
+            && pending_index != index
+        {
+            print_entry(&previous, json)?;
+        }
+        pending = Some((index, entry));
+    }
+    if let Some((_, entry)) = pending {
+        print_entry(&entry, json)?;
+    }
+    Ok(())
+}
+
+/// The attempt's current diffs by path, read from the start of the live
+/// diff stream
+pub(crate) async fn read_diffs(stream: &mut LogStream) -> anyhow::Result<BTreeMap<String, Diff>> {
+    let mut diffs = BTreeMap::new();
+    while let Some(msg) = stream.next(Some(SNAPSHOT_IDLE)).await? {
+        let LogMsg::JsonPatch(patch) = msg else {
+            continue;
+        };
+        let ops = serde_json::to_value(&patch)?;
+        for op in ops.as_array().into_iter().flatten() {
+            let Some(path) = op
+                .get("path")
+                .and_then(|p| p.as_str())
+                .and_then(|p| p.strip_prefix("/entries/"))
+            else {
+                continue;
+            };
+            let path = path.replace("~1", "/").replace("~0", "~");
+            if op.get("op").and_then(|o| o.as_str()) == Some("remove") {
+                diffs.remove(&path);
+            } else if let Some(diff) = op
+                .get("value")
+                .filter(|v| v.get("type").and_then(|t| t.as_str()) == Some("DIFF"))
+                .and_then(|v| v.get("content"))
+                .and_then(|c| serde_json::from_value::<Diff>(c.clone()).ok())
+            {
+                diffs.insert(path, diff);
+            }
+        }
+    }
+    Ok(diffs)
+}
+
+async fn diff(client: &VkClient, args: DiffArgs, json: bool) -> anyhow::Result<()> {
+    let mut stream = client
+        .websocket(&format!(
+            "/api/task-attempts/{}/diff/ws?stats_only={}",
+            args.attempt_id, args.stat
+        ))
+        .await?;
+    let diffs = read_diffs(&mut stream).await?;
+    if json {
+        return output::json(&diffs);
+    }
+    if args.stat {
+        output::table(
+            ["FILE", "+", "-"],
+            diffs
+                .iter()
+                .map(|(path, d)| {
+                    [
+                        path.clone(),
+                        d.additions.unwrap_or_default().to_string(),
+                        d.deletions.unwrap_or_default().to_string(),
+                    ]
+                })
+                .collect(),
+        );
+        return Ok(());
+    }
+    for (path, d) in &diffs {
+        if d.content_omitted {
+            println!("--- {path}: content omitted\n");
+            continue;
+        }
+        print!(
+            "{}",
+            create_unified_diff(
+                path,
+                d.old_content.as_deref().unwrap_or_default(),
+                d.new_content.as_deref().unwrap_or_default(),
+            )
+        );
+    }
+    Ok(())
+}
+
+async fn approvals(client: &VkClient, command: ApprovalsCommand, json: bool) -> anyhow::Result<()> {
+    let ApprovalsCommand::Respond {
+        approval_id,
+        process,
+        approve,
+        reason,
+        ..
+    } = command;
+    let status = if approve {
+        ApprovalStatus::Approved
+    } else {
+        ApprovalStatus::Denied { reason }
+    };
+    let status: ApprovalStatus = client
+        .post_raw(
+            &format!("/api/approvals/{approval_id}/respond"),
+            &ApprovalResponse {
+                execution_process_id: process,
+                status,
+            },
+        )
+        .await?;
+    if json {
+        return output::json(&status);
+    }
+    println!("{approval_id}: {status:?}");
+    Ok(())
+}
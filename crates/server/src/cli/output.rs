@@ -0,0 +1,95 @@
+use executors::logs::{NormalizedEntry, NormalizedEntryType, ToolStatus};
+use serde::Serialize;
+
+/// Print `value` as pretty JSON
+pub fn json(value: &impl Serialize) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Print rows under `headers` with left-aligned, space-padded columns
+pub fn table<const N: usize>(headers: [&str; N], rows: Vec<[String; N]>) {
+    if rows.is_empty() {
+        println!("(none)");
+        return;
+    }
+    let mut widths = headers.map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    line(headers.to_vec());
+    for row in &rows {
+        line(row.iter().map(String::as_str).collect());
+    }
+}
+
+/// Shorten `text` to its first line, at most `max` characters
+pub fn truncate(text: &str, max: usize) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() > max {
+        let cut: String = line.chars().take(max.saturating_sub(1)).collect();
+        format!("{cut}…")
+    } else {
+        line.to_string()
+    }
+}
+
+/// One conversation entry as terminal text
+pub fn entry(entry: &NormalizedEntry) -> String {
+    let content = entry.content.trim_end();
+    match &entry.entry_type {
+        NormalizedEntryType::UserMessage => format!("> {content}"),
+        NormalizedEntryType::UserFeedback { denied_tool } => {
+            format!("> (denied {denied_tool}) {content}")
+        }
+        NormalizedEntryType::AssistantMessage => content.to_string(),
+        NormalizedEntryType::ToolUse {
+            tool_name, status, ..
+        } => {
+            let status = match status {
+                ToolStatus::Created => String::new(),
+                ToolStatus::Success => " ✓".to_string(),
+                ToolStatus::Failed => " ✗".to_string(),
+                ToolStatus::Denied { .. } => " (denied)".to_string(),
+                ToolStatus::PendingApproval { approval_id, .. } => {
+                    format!(" (waiting for approval {approval_id})")
+                }
+                ToolStatus::TimedOut => " (approval timed out)".to_string(),
+            };
+            format!("[{tool_name}] {content}{status}")
+        }
+        NormalizedEntryType::SystemMessage => format!("[system] {content}"),
+        NormalizedEntryType::ErrorMessage { .. } => format!("[error] {content}"),
+        NormalizedEntryType::Thinking => format!("[thinking] {content}"),
+        NormalizedEntryType::Loading => String::new(),
+        NormalizedEntryType::NextAction { failed, .. } => {
+            if *failed {
+                "[finished with errors]".to_string()
+            } else {
+                "[finished]".to_string()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_keeps_first_line_within_limit() {
+        assert_eq!(truncate("short title", 20), "short title");
+        assert_eq!(truncate("first line\nsecond", 20), "first line");
+        assert_eq!(truncate("a rather long task title", 10), "a rather …");
+    }
+}
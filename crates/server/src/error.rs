@@ -15,17 +15,22 @@ use git2::Error as Git2Error;
 use services::services::{
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
+    content_search::ContentSearchError,
+    dependency_cache::DependencyCacheError,
     git::GitServiceError,
+    git_host::GitHostError,
     github::GitHubServiceError,
     image::ImageError,
     project::ProjectServiceError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
+    review_comments::ReviewCommentError,
+    secrets::SecretError,
     share::ShareError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
-use utils::response::ApiResponse;
+use utils::{metrics, response::ApiResponse};
 
 #[derive(Debug, Error, ts_rs::TS)]
 #[ts(type = "string")]
@@ -47,6 +52,8 @@ pub enum ApiError {
     #[error(transparent)]
     GitHubService(#[from] GitHubServiceError),
     #[error(transparent)]
+    GitHost(#[from] GitHostError),
+    #[error(transparent)]
     Deployment(#[from] DeploymentError),
     #[error(transparent)]
     Container(#[from] ContainerError),
@@ -60,6 +67,8 @@ pub enum ApiError {
     Config(#[from] ConfigError),
     #[error(transparent)]
     Image(#[from] ImageError),
+    #[error(transparent)]
+    ReviewComment(#[from] ReviewCommentError),
     #[error("Multipart error: {0}")]
     Multipart(#[from] MultipartError),
     #[error("IO error: {0}")]
@@ -72,6 +81,8 @@ pub enum ApiError {
     Unauthorized,
     #[error("Bad request: {0}")]
     BadRequest(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
     #[error("Conflict: {0}")]
     Conflict(String),
     #[error("Forbidden: {0}")]
@@ -98,6 +109,9 @@ impl From<RemoteClientNotConfigured> for ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
+        if matches!(self, ApiError::Database(_)) {
+            metrics::DB_QUERY_ERRORS.inc(&["api"]);
+        }
         let (status_code, error_type) = match &self {
             ApiError::Project(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ProjectError"),
             ApiError::Repo(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ProjectRepoError"),
@@ -121,6 +135,19 @@ impl IntoResponse for ApiError {
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "GitServiceError"),
             },
             ApiError::GitHubService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError"),
+            ApiError::GitHost(err) => match err {
+                GitHostError::InvalidRemoteUrl(_)
+                | GitHostError::UnsupportedHost(_)
+                | GitHostError::MissingTokenSecret(_)
+                | GitHostError::InvalidTokenSecret(_) => (StatusCode::BAD_REQUEST, "GitHostError"),
+                GitHostError::AuthFailed { .. } => (StatusCode::UNAUTHORIZED, "GitHostError"),
+                GitHostError::InsufficientPermissions(_) => (StatusCode::FORBIDDEN, "GitHostError"),
+                GitHostError::NotFound(_) => (StatusCode::NOT_FOUND, "GitHostError"),
+                GitHostError::Transport(_) | GitHostError::Http { .. } => {
+                    (StatusCode::BAD_GATEWAY, "GitHostError")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "GitHostError"),
+            },
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
             ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
@@ -133,6 +160,12 @@ impl IntoResponse for ApiError {
                 ImageError::NotFound => (StatusCode::NOT_FOUND, "ImageNotFound"),
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "ImageError"),
             },
+            ApiError::ReviewComment(err) => match err {
+                ReviewCommentError::InvalidRange(..) => {
+                    (StatusCode::BAD_REQUEST, "ReviewCommentError")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ReviewCommentError"),
+            },
             ApiError::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, "IoError"),
             ApiError::EditorOpen(err) => match err {
                 EditorOpenError::LaunchFailed { .. } => {
@@ -175,6 +208,7 @@ impl IntoResponse for ApiError {
             },
             ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
             ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "BadRequest"),
+            ApiError::NotFound(_) => (StatusCode::NOT_FOUND, "NotFound"),
             ApiError::Conflict(_) => (StatusCode::CONFLICT, "ConflictError"),
             ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, "ForbiddenError"),
         };
@@ -249,8 +283,10 @@ impl IntoResponse for ApiError {
                 RemoteClientError::Serde(_) => "Unexpected response from remote service.".to_string(),
                 RemoteClientError::Url(_) => "Remote service URL is invalid.".to_string(),
             },
+            ApiError::GitHost(err) => err.to_string(),
             ApiError::Unauthorized => "Unauthorized. Please sign in again.".to_string(),
             ApiError::BadRequest(msg) => msg.clone(),
+            ApiError::NotFound(msg) => msg.clone(),
             ApiError::Conflict(msg) => msg.clone(),
             ApiError::Forbidden(msg) => msg.clone(),
             _ => format!("{}: {}", error_type, self),
@@ -348,6 +384,39 @@ impl From<ProjectServiceError> for ApiError {
     }
 }
 
+impl From<SecretError> for ApiError {
+    fn from(err: SecretError) -> Self {
+        match err {
+            SecretError::Database(db_err) => ApiError::Database(db_err),
+            SecretError::Key(io_err) => ApiError::Io(io_err),
+            SecretError::DuplicateName(_) => ApiError::Conflict(err.to_string()),
+            _ => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<ContentSearchError> for ApiError {
+    fn from(err: ContentSearchError) -> Self {
+        match err {
+            ContentSearchError::Io(io_err) => ApiError::Io(io_err),
+            ContentSearchError::InvalidPattern(_) | ContentSearchError::EmptyQuery => {
+                ApiError::BadRequest(err.to_string())
+            }
+            _ => ApiError::Io(std::io::Error::other(err.to_string())),
+        }
+    }
+}
+
+impl From<DependencyCacheError> for ApiError {
+    fn from(err: DependencyCacheError) -> Self {
+        match err {
+            DependencyCacheError::Io(io_err) => ApiError::Io(io_err),
+            DependencyCacheError::NotFound => ApiError::BadRequest(err.to_string()),
+            _ => ApiError::Io(std::io::Error::other(err.to_string())),
+        }
+    }
+}
+
 impl From<RepoServiceError> for ApiError {
     fn from(err: RepoServiceError) -> Self {
         match err {
@@ -0,0 +1,647 @@
+//! `server headless <spec>`: run one task from a spec file without the UI.
+//!
+//! The run gets its own throwaway database and worktree directory, so it can
+//! sit next to a running server and leaves nothing behind but the task branch
+//! in the repository and the JSON result.
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, bail};
+use clap::Parser;
+use db::{
+    DBService,
+    models::{
+        execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+        project::CreateProject,
+        project_repo::{CreateProjectRepo, ProjectRepo},
+        repo::Repo,
+        session::{CreateSession, Session},
+        task::{CreateTask, Task},
+        workspace::{CreateWorkspace, Workspace},
+        workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+    },
+};
+use deployment::Deployment;
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType,
+        coding_agent_initial::CodingAgentInitialRequest,
+        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+    },
+    logs::{NormalizedEntry, utils::patch::extract_normalized_entry_from_patch},
+    profile::ExecutorProfileId,
+};
+use futures_util::{StreamExt, stream::BoxStream};
+use serde::{Deserialize, Serialize};
+use services::services::{container::ContainerService, git::DiffTarget};
+use utils::{diff::create_unified_diff, log_msg::LogMsg, path::override_vibe_kanban_temp_dir};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    cli::{output, print_entry},
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "server headless",
+    about = "Run a task from a spec file without the UI and write a JSON result"
+)]
+pub struct HeadlessArgs {
+    /// Task spec (JSON)
+    pub spec: PathBuf,
+    /// Where to write the JSON result
+    #[arg(long, short, default_value = "vibe-kanban-result.json")]
+    pub output: PathBuf,
+}
+
+/// What to run. Relative `repo` paths are resolved against the spec file's
+/// directory.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeadlessSpec {
+    pub repo: PathBuf,
+    pub prompt: String,
+    /// Task title, used for the branch name; defaults to the prompt's first line
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Defaults to the profile selected in the settings
+    #[serde(default)]
+    pub executor_profile: Option<ExecutorProfileId>,
+    /// Defaults to the repository's current branch
+    #[serde(default)]
+    pub target_branch: Option<String>,
+    #[serde(default)]
+    pub setup_script: Option<String>,
+    /// Checks the agent's work; a non-zero exit fails the run
+    #[serde(default)]
+    pub verification_script: Option<String>,
+    /// Runs last, even when an earlier step failed
+    #[serde(default)]
+    pub cleanup_script: Option<String>,
+    /// Per-step limit; a step that runs longer is killed and fails the run
+    #[serde(default)]
+    pub timeout_minutes: Option<u64>,
+}
+
+impl HeadlessSpec {
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut spec: Self = serde_json::from_str(&text)
+            .with_context(|| format!("Invalid spec {}", path.display()))?;
+        if spec.repo.is_relative() {
+            let base = path.parent().unwrap_or(Path::new("."));
+            spec.repo = base.join(&spec.repo);
+        }
+        if spec.prompt.trim().is_empty() {
+            bail!("The spec's prompt is empty");
+        }
+        Ok(spec)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HeadlessStatus {
+    Succeeded,
+    SetupFailed,
+    AgentFailed,
+    VerificationFailed,
+    CleanupFailed,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HeadlessStep {
+    Setup,
+    Agent,
+    Verification,
+    Cleanup,
+}
+
+impl HeadlessStep {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Setup => "setup",
+            Self::Agent => "agent",
+            Self::Verification => "verification",
+            Self::Cleanup => "cleanup",
+        }
+    }
+
+    fn failure(self) -> HeadlessStatus {
+        match self {
+            Self::Setup => HeadlessStatus::SetupFailed,
+            Self::Agent => HeadlessStatus::AgentFailed,
+            Self::Verification => HeadlessStatus::VerificationFailed,
+            Self::Cleanup => HeadlessStatus::CleanupFailed,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StepResult {
+    pub step: HeadlessStep,
+    pub execution_process_id: Uuid,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+    pub duration_secs: f64,
+}
+
+impl StepResult {
+    fn succeeded(&self) -> bool {
+        self.status == ExecutionProcessStatus::Completed && self.exit_code == Some(0)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HeadlessResult {
+    pub status: HeadlessStatus,
+    pub repo: PathBuf,
+    /// Branch holding the agent's commits; it is kept after the run
+    pub branch: String,
+    pub target_branch: String,
+    pub steps: Vec<StepResult>,
+    /// Subjects of the commits on `branch` since it left `target_branch`
+    pub commits: Vec<String>,
+    /// Unified diff of `branch` against `target_branch`
+    pub diff: String,
+    /// As reported by the agent; `None` when it does not report usage
+    pub tokens: Option<TokenUsage>,
+}
+
+/// Run the spec and write the result. Returns the process exit code: 0 when
+/// every step succeeded, 1 when a step failed and 2 when the run could not be
+/// carried out.
+pub async fn run(args: HeadlessArgs) -> i32 {
+    let spec = match HeadlessSpec::load(&args.spec) {
+        Ok(spec) => spec,
+        Err(e) => {
+            eprintln!("{e:#}");
+            return 2;
+        }
+    };
+
+    let scratch_dir = std::env::temp_dir().join(format!("vibe-kanban-headless-{}", Uuid::new_v4()));
+    let result = execute(&spec, &scratch_dir).await;
+    if let Err(e) = std::fs::remove_dir_all(&scratch_dir) {
+        tracing::warn!("Failed to remove {}: {}", scratch_dir.display(), e);
+    }
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Headless run failed: {e:#}");
+            return 2;
+        }
+    };
+
+    let json = serde_json::to_string_pretty(&result).expect("result serializes to JSON");
+    if let Err(e) = std::fs::write(&args.output, json + "\n") {
+        eprintln!("Failed to write {}: {e}", args.output.display());
+        return 2;
+    }
+
+    let succeeded = result.status == HeadlessStatus::Succeeded;
+    eprintln!(
+        "Run {}; result written to {}",
+        if succeeded { "succeeded" } else { "failed" },
+        args.output.display()
+    );
+    if succeeded { 0 } else { 1 }
+}
+
+async fn execute(spec: &HeadlessSpec, scratch_dir: &Path) -> anyhow::Result<HeadlessResult> {
+    std::fs::create_dir_all(scratch_dir)?;
+    DBService::use_database_path(scratch_dir.join("db.sqlite"));
+    override_vibe_kanban_temp_dir(scratch_dir.join("tmp"));
+
+    let deployment = DeploymentImpl::new().await?;
+    deployment.spawn_execution_dispatcher();
+    let pool = &deployment.db().pool;
+
+    let repo_path = deployment
+        .repo()
+        .normalize_path(&spec.repo.to_string_lossy())?;
+    let display_name = repo_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "repo".to_string());
+    let project = deployment
+        .project()
+        .create_project(
+            pool,
+            deployment.repo(),
+            deployment.git(),
+            CreateProject {
+                name: display_name.clone(),
+                repositories: vec![CreateProjectRepo {
+                    display_name,
+                    git_repo_path: repo_path.to_string_lossy().to_string(),
+                }],
+            },
+        )
+        .await?;
+    let repo = ProjectRepo::find_repos_for_project(pool, project.id)
+        .await?
+        .into_iter()
+        .next()
+        .context("Project has no repository")?;
+
+    let target_branch = match &spec.target_branch {
+        Some(branch) => branch.clone(),
+        None => deployment.git().get_head_info(&repo.path)?.branch,
+    };
+    let title = spec
+        .title
+        .clone()
+        .unwrap_or_else(|| output::truncate(&spec.prompt, 60));
+    let task = Task::create(
+        pool,
+        &CreateTask::from_title_description(project.id, title.clone(), Some(spec.prompt.clone())),
+        Uuid::new_v4(),
+    )
+    .await?;
+
+    let workspace_id = Uuid::new_v4();
+    let branch = deployment
+        .container()
+        .git_branch_from_workspace(&workspace_id, &title)
+        .await;
+    Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch: branch.clone(),
+            agent_working_dir: Some(repo.name.clone()),
+        },
+        workspace_id,
+        task.id,
+    )
+    .await?;
+    WorkspaceRepo::create_many(
+        pool,
+        workspace_id,
+        &[CreateWorkspaceRepo {
+            repo_id: repo.id,
+            target_branch: target_branch.clone(),
+        }],
+    )
+    .await?;
+    let workspace = Workspace::find_by_id(pool, workspace_id)
+        .await?
+        .context("Workspace not found")?;
+    deployment.container().create(&workspace).await?;
+    // Reload for the container ref set by `create`
+    let workspace = Workspace::find_by_id(pool, workspace_id)
+        .await?
+        .context("Workspace not found")?;
+
+    let executor_profile_id = match &spec.executor_profile {
+        Some(profile) => profile.clone(),
+        None => deployment.config().read().await.executor_profile.clone(),
+    };
+    let session = Session::create(
+        pool,
+        &CreateSession {
+            executor: Some(executor_profile_id.executor.to_string()),
+        },
+        Uuid::new_v4(),
+        workspace.id,
+    )
+    .await?;
+
+    let runner = StepRunner {
+        deployment: &deployment,
+        workspace: &workspace,
+        session: &session,
+        timeout: spec.timeout_minutes.map(|m| Duration::from_secs(m * 60)),
+        tokens: None,
+    };
+    let outcome = run_steps(runner, spec, &repo, executor_profile_id).await;
+
+    let summary = summarize(&deployment, &repo, &branch, &target_branch);
+    if let Err(e) = deployment.container().delete(&workspace).await {
+        tracing::warn!("Failed to remove the headless worktree: {}", e);
+    }
+    let (status, steps, tokens) = outcome?;
+    let (commits, diff) = summary?;
+
+    Ok(HeadlessResult {
+        status,
+        repo: repo.path,
+        branch,
+        target_branch,
+        steps,
+        commits,
+        diff,
+        tokens,
+    })
+}
+
+/// Setup → agent → verification, stopping at the first failure, then cleanup
+async fn run_steps(
+    mut runner: StepRunner<'_>,
+    spec: &HeadlessSpec,
+    repo: &Repo,
+    executor_profile_id: ExecutorProfileId,
+) -> anyhow::Result<(HeadlessStatus, Vec<StepResult>, Option<TokenUsage>)> {
+    let script = |script: &String, context: ScriptContext| {
+        ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: script.clone(),
+                language: ScriptRequestLanguage::Bash,
+                context,
+                working_dir: Some(repo.name.clone()),
+                script_name: None,
+                env: Default::default(),
+                timeout_seconds: None,
+            }),
+            None,
+        )
+    };
+
+    // The verification script runs as a setup script so that, unlike cleanup,
+    // anything it leaves in the worktree is not committed
+    let mut plan = Vec::new();
+    if let Some(setup) = &spec.setup_script {
+        plan.push((
+            HeadlessStep::Setup,
+            script(setup, ScriptContext::SetupScript),
+            ExecutionProcessRunReason::SetupScript,
+        ));
+    }
+    plan.push((
+        HeadlessStep::Agent,
+        ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt: spec.prompt.clone(),
+                executor_profile_id,
+                working_dir: Some(repo.name.clone()),
+            }),
+            None,
+        ),
+        ExecutionProcessRunReason::CodingAgent,
+    ));
+    if let Some(verification) = &spec.verification_script {
+        plan.push((
+            HeadlessStep::Verification,
+            script(verification, ScriptContext::SetupScript),
+            ExecutionProcessRunReason::SetupScript,
+        ));
+    }
+
+    let mut status = HeadlessStatus::Succeeded;
+    let mut steps = Vec::new();
+    for (step, action, run_reason) in plan {
+        let result = runner.run(step, &action, &run_reason).await?;
+        let succeeded = result.succeeded();
+        steps.push(result);
+        if !succeeded {
+            status = step.failure();
+            break;
+        }
+    }
+    if let Some(cleanup) = &spec.cleanup_script {
+        let action = script(cleanup, ScriptContext::CleanupScript);
+        let result = runner
+            .run(
+                HeadlessStep::Cleanup,
+                &action,
+                &ExecutionProcessRunReason::CleanupScript,
+            )
+            .await?;
+        if !result.succeeded() && status == HeadlessStatus::Succeeded {
+            status = HeadlessStatus::CleanupFailed;
+        }
+        steps.push(result);
+    }
+
+    Ok((status, steps, runner.tokens))
+}
+
+/// Commit subjects and unified diff of `branch` against `target_branch`
+fn summarize(
+    deployment: &DeploymentImpl,
+    repo: &Repo,
+    branch: &str,
+    target_branch: &str,
+) -> anyhow::Result<(Vec<String>, String)> {
+    let git = deployment.git();
+    let base = git.get_base_commit(&repo.path, branch, target_branch)?;
+    let head = git.get_branch_oid(&repo.path, branch)?;
+    let commits = git.get_commit_subjects_between(&repo.path, &base.to_string(), &head)?;
+    let diff = git
+        .get_diffs(
+            DiffTarget::Branch {
+                repo_path: &repo.path,
+                branch_name: branch,
+                base_branch: target_branch,
+            },
+            None,
+        )?
+        .iter()
+        .map(|d| {
+            let path = d
+                .new_path
+                .as_deref()
+                .or(d.old_path.as_deref())
+                .unwrap_or_default();
+            if d.content_omitted {
+                format!("--- {path}: content omitted\n")
+            } else {
+                create_unified_diff(
+                    path,
+                    d.old_content.as_deref().unwrap_or_default(),
+                    d.new_content.as_deref().unwrap_or_default(),
+                )
+            }
+        })
+        .collect();
+    Ok((commits, diff))
+}
+
+struct StepRunner<'a> {
+    deployment: &'a DeploymentImpl,
+    workspace: &'a Workspace,
+    session: &'a Session,
+    timeout: Option<Duration>,
+    tokens: Option<TokenUsage>,
+}
+
+impl StepRunner<'_> {
+    async fn run(
+        &mut self,
+        step: HeadlessStep,
+        action: &ExecutorAction,
+        run_reason: &ExecutionProcessRunReason,
+    ) -> anyhow::Result<StepResult> {
+        println!("==> {}", step.as_str());
+        let started = Instant::now();
+        let deployment = self.deployment;
+        let container = deployment.container();
+        let process = container
+            .start_execution(self.workspace, self.session, action, run_reason)
+            .await?;
+
+        let agent = step == HeadlessStep::Agent;
+        let finished = match self.timeout {
+            Some(timeout) => {
+                match tokio::time::timeout(timeout, self.wait(process.id, agent)).await {
+                    Ok(finished) => finished?,
+                    Err(_) => {
+                        eprintln!("Step timed out after {}s", timeout.as_secs());
+                        container
+                            .stop_execution(&process, ExecutionProcessStatus::Killed)
+                            .await?;
+                        self.wait(process.id, agent).await?
+                    }
+                }
+            }
+            None => self.wait(process.id, agent).await?,
+        };
+
+        Ok(StepResult {
+            step,
+            execution_process_id: finished.id,
+            status: finished.status,
+            exit_code: finished.exit_code,
+            duration_secs: started.elapsed().as_secs_f64(),
+        })
+    }
+
+    /// Stream the process's logs until it has exited and the exit monitor is
+    /// done with it (its changes committed), then return its final record
+    async fn wait(&mut self, process_id: Uuid, agent: bool) -> anyhow::Result<ExecutionProcess> {
+        let deployment = self.deployment;
+        let container = deployment.container();
+        loop {
+            // The exit monitor drops the log store only after committing
+            if let Some(store) = container.get_msg_store_by_id(&process_id).await {
+                let stream = store.history_plus_stream();
+                drop(store);
+                self.print_logs(stream, agent).await?;
+            }
+            let process = ExecutionProcess::find_by_id(&deployment.db().pool, process_id)
+                .await?
+                .context("Execution process not found")?;
+            let done = !matches!(
+                process.status,
+                ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+            );
+            if done && container.get_msg_store_by_id(&process_id).await.is_none() {
+                return Ok(process);
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+    }
+
+    /// Agents are shown as conversation entries, scripts as their raw output
+    async fn print_logs(
+        &mut self,
+        mut stream: BoxStream<'static, Result<LogMsg, std::io::Error>>,
+        agent: bool,
+    ) -> anyhow::Result<()> {
+        // Entries are rewritten while the agent streams them, so one is printed
+        // once a later entry starts or the stream ends
+        let mut pending: Option<(usize, NormalizedEntry)> = None;
+        let mut stdout_line = String::new();
+        while let Some(msg) = stream.next().await {
+            match msg? {
+                LogMsg::JsonPatch(patch) if agent => {
+                    let Some((index, entry)) = extract_normalized_entry_from_patch(&patch) else {
+                        continue;
+                    };
+                    if let Some((pending_index, previous)) = pending.take()
+                        && pending_index != index
+                    {
+                        print_entry(&previous, false)?;
+                    }
+                    pending = Some((index, entry));
+                }
+                LogMsg::Stdout(text) if agent => {
+                    stdout_line.push_str(&text);
+                    while let Some(end) = stdout_line.find('\n') {
+                        if let Some(usage) = token_usage(&stdout_line[..end]) {
+                            self.tokens = Some(usage);
+                        }
+                        stdout_line.drain(..=end);
+                    }
+                }
+                LogMsg::Stdout(text) => {
+                    print!("{text}");
+                    std::io::stdout().flush()?;
+                }
+                LogMsg::Stderr(text) if !agent => eprint!("{text}"),
+                LogMsg::Finished => break,
+                _ => {}
+            }
+        }
+        if let Some((_, entry)) = pending {
+            print_entry(&entry, false)?;
+        }
+        Ok(())
+    }
+}
+
+/// Token totals from one line of agent output: the `usage` of Claude's final
+/// `result` message, or the `total_token_usage` of Codex's token counts
+fn token_usage(line: &str) -> Option<TokenUsage> {
+    fn find<'v>(value: &'v serde_json::Value, key: &str) -> Option<&'v serde_json::Value> {
+        match value {
+            serde_json::Value::Object(map) => map
+                .get(key)
+                .or_else(|| map.values().find_map(|v| find(v, key))),
+            serde_json::Value::Array(items) => items.iter().find_map(|v| find(v, key)),
+            _ => None,
+        }
+    }
+
+    let value: serde_json::Value = serde_json::from_str(line.trim()).ok()?;
+    let usage = if value.get("type").and_then(|t| t.as_str()) == Some("result") {
+        value.get("usage")?
+    } else {
+        find(&value, "total_token_usage")?
+    };
+    let count = |key: &str| usage.get(key).and_then(|v| v.as_u64());
+    Some(TokenUsage {
+        input_tokens: count("input_tokens")?,
+        output_tokens: count("output_tokens").unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_usage_reads_claude_results_and_codex_totals() {
+        let claude = r#"{"type":"result","subtype":"success","usage":{"input_tokens":12,"output_tokens":34}}"#;
+        let codex = r#"{"method":"codex/event/token_count","params":{"msg":{"type":"token_count","info":{"total_token_usage":{"input_tokens":5,"output_tokens":6}}}}}"#;
+        assert_eq!(
+            token_usage(claude),
+            Some(TokenUsage {
+                input_tokens: 12,
+                output_tokens: 34
+            })
+        );
+        assert_eq!(
+            token_usage(codex),
+            Some(TokenUsage {
+                input_tokens: 5,
+                output_tokens: 6
+            })
+        );
+        assert_eq!(token_usage(r#"{"type":"assistant"}"#), None);
+        assert_eq!(token_usage("plain text"), None);
+    }
+}
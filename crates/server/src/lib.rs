@@ -1,4 +1,6 @@
+pub mod cli;
 pub mod error;
+pub mod headless;
 pub mod mcp;
 pub mod middleware;
 pub mod routes;
@@ -1,7 +1,12 @@
 use anyhow::{self, Error as AnyhowError};
+use clap::Parser;
 use deployment::{Deployment, DeploymentError};
-use server::{DeploymentImpl, routes};
-use services::services::container::ContainerService;
+use server::{
+    DeploymentImpl,
+    headless::{self, HeadlessArgs},
+    routes,
+};
+use services::services::{api_auth::ApiAuth, container::ContainerService};
 use sqlx::Error as SqlxError;
 use strip_ansi_escapes::strip;
 use thiserror::Error;
@@ -35,6 +40,21 @@ async fn main() -> Result<(), VibeKanbanError> {
         level = log_level
     );
     let env_filter = EnvFilter::try_new(filter_string).expect("Failed to create tracing filter");
+
+    // `server headless <spec>` runs one task and exits; stdout carries its logs
+    if std::env::args().nth(1).as_deref() == Some("headless") {
+        tracing_subscriber::registry()
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(std::io::stderr)
+                    .with_filter(env_filter),
+            )
+            .with(sentry_layer())
+            .init();
+        let args = HeadlessArgs::parse_from(std::env::args().skip(1));
+        std::process::exit(headless::run(args).await);
+    }
+
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer().with_filter(env_filter))
         .with(sentry_layer())
@@ -63,10 +83,11 @@ async fn main() -> Result<(), VibeKanbanError> {
         .await
         .map_err(DeploymentError::from)?;
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_execution_dispatcher();
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
-    // Pre-warm file search cache for most active projects
+    // Pre-warm file and content search for most active projects
     let deployment_for_cache = deployment.clone();
     tokio::spawn(async move {
         if let Err(e) = deployment_for_cache
@@ -76,6 +97,13 @@ async fn main() -> Result<(), VibeKanbanError> {
         {
             tracing::warn!("Failed to warm file search cache: {}", e);
         }
+        if let Err(e) = deployment_for_cache
+            .content_search()
+            .warm_most_active(&deployment_for_cache.db().pool, 3)
+            .await
+        {
+            tracing::warn!("Failed to warm content search index: {}", e);
+        }
     });
 
     // Verify shared tasks in background
@@ -88,8 +116,6 @@ async fn main() -> Result<(), VibeKanbanError> {
         }
     });
 
-    let app_router = routes::router(deployment.clone());
-
     let port = std::env::var("BACKEND_PORT")
         .or_else(|_| std::env::var("PORT"))
         .ok()
@@ -105,9 +131,41 @@ async fn main() -> Result<(), VibeKanbanError> {
         }); // Use 0 to find free port if no specific port provided
 
     let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let api_auth = ApiAuth::init(&host).await?;
+    if api_auth.is_enabled() {
+        tracing::info!(
+            "API authentication enabled; the admin token is stored in {}",
+            asset_dir().display()
+        );
+    }
+    let app_router = routes::router(deployment.clone(), api_auth.clone());
     let listener = tokio::net::TcpListener::bind(format!("{host}:{port}")).await?;
     let actual_port = listener.local_addr()?.port(); // get → 53427 (example)
 
+    // Previews get their own port, so their pages are another origin than the
+    // app. That port is still the same site, so the session cookie reaches the
+    // API from there; `require_api_token` rejects the cross-origin requests.
+    let preview_port = std::env::var("PREVIEW_PORT")
+        .ok()
+        .and_then(|s| s.trim().parse::<u16>().ok())
+        .unwrap_or(0);
+    let preview_listener = tokio::net::TcpListener::bind(format!("{host}:{preview_port}")).await?;
+    let preview_port = preview_listener.local_addr()?.port();
+    deployment
+        .container()
+        .dev_servers()
+        .set_preview_port(preview_port);
+    let preview_router = routes::preview_router(deployment.clone(), api_auth);
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(preview_listener, preview_router)
+            .with_graceful_shutdown(shutdown_signal())
+            .await
+        {
+            tracing::error!("Preview server stopped: {}", e);
+        }
+    });
+    tracing::info!("Previews served on port {preview_port}");
+
     // Write port file for discovery if prod, warn on fail
     if let Err(e) = write_port_file(actual_port).await {
         tracing::warn!("Failed to write port file: {}", e);
@@ -0,0 +1,934 @@
+//! Authenticating front for the MCP servers' HTTP transport. The MCP server
+//! itself listens on a loopback port that only accepts requests carrying a
+//! per-process secret; the gateway checks bearer tokens and per-token tool
+//! and resource allow-lists, issues OAuth2 client-credentials tokens,
+//! terminates TLS and writes an audit log before proxying requests to it.
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    future::Future,
+    io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::{
+    Form, Json, Router,
+    body::{Body, Bytes},
+    extract::{ConnectInfo, DefaultBodyLimit, Request, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, Uri, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{any, get, post},
+    serve::Listener,
+};
+use base64::Engine;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use services::services::api_auth::{generate_token, hash_token};
+use tokio::{
+    fs::File,
+    io::AsyncWriteExt,
+    net::{TcpListener, TcpStream},
+    sync::{Mutex, mpsc},
+    time::Instant,
+};
+use tokio_rustls::{TlsAcceptor, rustls, server::TlsStream};
+
+/// A single token allowed to call every tool
+pub const AUTH_TOKEN_ENV: &str = "MCP_AUTH_TOKEN";
+/// JSON file with tokens, OAuth2 clients and their tool allow-lists
+pub const AUTH_CONFIG_ENV: &str = "MCP_AUTH_CONFIG";
+pub const TLS_CERT_ENV: &str = "MCP_TLS_CERT";
+pub const TLS_KEY_ENV: &str = "MCP_TLS_KEY";
+/// File the audit log is appended to, one JSON object per request
+pub const AUDIT_LOG_ENV: &str = "MCP_AUDIT_LOG";
+
+const DEFAULT_ACCESS_TOKEN_TTL_SECS: u64 = 3600;
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Base64 image uploads through `upload_task_image` may be up to 20MB
+const MAX_REQUEST_BYTES: usize = 32 * 1024 * 1024;
+/// Carries the secret that proves a request to the MCP server came through
+/// the gateway
+const UPSTREAM_SECRET_HEADER: &str = "x-mcp-gateway-secret";
+
+/// Which tools a token may call and which resources it may read or
+/// subscribe to. Patterns may contain `*` wildcards, e.g. `list_*` or
+/// `vibe://task/*`; denials win over allowances.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ToolAccess {
+    /// All tools when unset
+    #[serde(default)]
+    pub allow_tools: Option<Vec<String>>,
+    #[serde(default)]
+    pub deny_tools: Vec<String>,
+    /// Resource URIs. When unset, tokens that may call every tool may read
+    /// every resource and tokens limited by `allow_tools` may read none.
+    #[serde(default)]
+    pub allow_resources: Option<Vec<String>>,
+    #[serde(default)]
+    pub deny_resources: Vec<String>,
+}
+
+impl ToolAccess {
+    pub fn allows(&self, tool: &str) -> bool {
+        let allowed = self
+            .allow_tools
+            .as_ref()
+            .is_none_or(|patterns| patterns.iter().any(|p| glob_matches(p, tool)));
+        allowed && !self.deny_tools.iter().any(|p| glob_matches(p, tool))
+    }
+
+    pub fn allows_resource(&self, uri: &str) -> bool {
+        let allowed = match (&self.allow_resources, &self.allow_tools) {
+            (Some(patterns), _) => patterns.iter().any(|p| glob_matches(p, uri)),
+            (None, tools) => tools.is_none(),
+        };
+        allowed && !self.deny_resources.iter().any(|p| glob_matches(p, uri))
+    }
+
+    /// Whether any tool or resource is off limits
+    fn is_restricted(&self) -> bool {
+        self.allow_tools.is_some()
+            || self.allow_resources.is_some()
+            || !self.deny_tools.is_empty()
+            || !self.deny_resources.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenConfig {
+    /// Shown in the audit log
+    pub name: String,
+    #[serde(default)]
+    pub token: Option<String>,
+    /// SHA-256 of the token in hex, to keep the token itself out of the file
+    #[serde(default)]
+    pub token_sha256: Option<String>,
+    #[serde(flatten)]
+    pub access: ToolAccess,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OAuthClientConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    #[serde(flatten)]
+    pub access: ToolAccess,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuthConfig {
+    #[serde(default)]
+    pub tokens: Vec<TokenConfig>,
+    #[serde(default)]
+    pub oauth_clients: Vec<OAuthClientConfig>,
+    #[serde(default)]
+    pub access_token_ttl_seconds: Option<u64>,
+}
+
+impl AuthConfig {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        let config: Self = serde_json::from_str(&text)
+            .map_err(|e| anyhow::anyhow!("Invalid MCP auth config {}: {}", path.display(), e))?;
+        for token in &config.tokens {
+            if token.token.is_some() == token.token_sha256.is_some() {
+                anyhow::bail!(
+                    "Token '{}' needs exactly one of 'token' and 'token_sha256'",
+                    token.name
+                );
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// How the HTTP transport is secured, read from the environment
+#[derive(Debug, Clone, Default)]
+pub struct HttpSecurity {
+    pub auth: Option<AuthConfig>,
+    pub tls: Option<(PathBuf, PathBuf)>,
+    pub audit_log: Option<PathBuf>,
+}
+
+impl HttpSecurity {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let mut auth = match var(AUTH_CONFIG_ENV) {
+            Some(path) => Some(AuthConfig::load(Path::new(&path))?),
+            None => None,
+        };
+        if let Some(token) = var(AUTH_TOKEN_ENV) {
+            auth.get_or_insert_with(AuthConfig::default)
+                .tokens
+                .push(TokenConfig {
+                    name: AUTH_TOKEN_ENV.to_string(),
+                    token: Some(token.trim().to_string()),
+                    token_sha256: None,
+                    access: ToolAccess::default(),
+                });
+        }
+        let tls = match (var(TLS_CERT_ENV), var(TLS_KEY_ENV)) {
+            (Some(cert), Some(key)) => Some((PathBuf::from(cert), PathBuf::from(key))),
+            (None, None) => None,
+            _ => anyhow::bail!("Set both {TLS_CERT_ENV} and {TLS_KEY_ENV} to enable TLS"),
+        };
+        Ok(Self {
+            auth,
+            tls,
+            audit_log: var(AUDIT_LOG_ENV).map(PathBuf::from),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.auth.is_some() || self.tls.is_some() || self.audit_log.is_some()
+    }
+}
+
+/// Serve an MCP server over HTTP on `addr`. Without any security configured
+/// `run_direct` serves it there itself; otherwise `upstream` is served on a
+/// loopback port behind the gateway.
+pub async fn run_http<F, Fut>(
+    addr: &str,
+    upstream: Router,
+    run_direct: F,
+) -> Result<(), Box<dyn Error>>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<(), Box<dyn Error>>>,
+{
+    let security = HttpSecurity::from_env()?;
+    if !security.is_enabled() {
+        tracing::warn!(
+            "MCP HTTP transport is unauthenticated; set {} or {} to require tokens",
+            AUTH_TOKEN_ENV,
+            AUTH_CONFIG_ENV
+        );
+        return run_direct(addr.to_string()).await;
+    }
+
+    // The listener is handed over rather than its port, so nothing else can
+    // take the port in between
+    let upstream_listener = TcpListener::bind("127.0.0.1:0").await?;
+    let upstream_addr = upstream_listener.local_addr()?;
+    let secret = generate_token();
+    let listener = TcpListener::bind(addr).await?;
+    tokio::select! {
+        result = serve_upstream(upstream_listener, upstream, &secret) => result.map_err(Into::into),
+        result = serve(security, listener, upstream_addr, secret.clone()) => result.map_err(Into::into),
+    }
+}
+
+/// Serve the MCP server itself, refusing requests that did not come
+/// through the gateway
+pub async fn serve_upstream(listener: TcpListener, app: Router, secret: &str) -> io::Result<()> {
+    let app = app
+        .layer(middleware::from_fn_with_state(
+            Arc::new(hash_token(secret)),
+            require_upstream_secret,
+        ))
+        .into_make_service_with_connect_info::<SocketAddr>();
+    axum::serve(listener, app).await
+}
+
+async fn require_upstream_secret(
+    State(secret_hash): State<Arc<String>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let secret = request
+        .headers()
+        .get(UPSTREAM_SECRET_HEADER)
+        .and_then(|v| v.to_str().ok());
+    if secret.is_none_or(|secret| hash_token(secret) != *secret_hash) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+/// A caller the gateway authenticated
+#[derive(Debug, Clone)]
+struct Principal {
+    name: String,
+    access: ToolAccess,
+}
+
+struct Gateway {
+    upstream: SocketAddr,
+    upstream_secret: String,
+    client: reqwest::Client,
+    tls: bool,
+    /// Configured tokens by hash
+    tokens: HashMap<String, Principal>,
+    oauth_clients: Vec<OAuthClientConfig>,
+    access_token_ttl: Duration,
+    /// Issued OAuth2 access tokens by hash
+    access_tokens: Mutex<HashMap<String, (Principal, Instant)>>,
+    audit_log: Option<Mutex<File>>,
+}
+
+pub async fn serve(
+    security: HttpSecurity,
+    listener: TcpListener,
+    upstream: SocketAddr,
+    upstream_secret: String,
+) -> anyhow::Result<()> {
+    let auth = security.auth.unwrap_or_default();
+    let audit_log = match &security.audit_log {
+        Some(path) => Some(Mutex::new(
+            tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", path.display(), e))?,
+        )),
+        None => None,
+    };
+    let tokens: HashMap<String, Principal> = auth
+        .tokens
+        .into_iter()
+        .map(|t| {
+            let hash = match (t.token, t.token_sha256) {
+                (Some(token), _) => hash_token(&token),
+                (None, Some(hash)) => hash.to_lowercase(),
+                (None, None) => unreachable!("checked when loading the config"),
+            };
+            let principal = Principal {
+                name: t.name,
+                access: t.access,
+            };
+            (hash, principal)
+        })
+        .collect();
+    if tokens.is_empty() && auth.oauth_clients.is_empty() {
+        tracing::warn!(
+            "MCP HTTP transport has no tokens configured; requests are not authenticated"
+        );
+    }
+
+    let gateway = Arc::new(Gateway {
+        upstream,
+        upstream_secret,
+        client: reqwest::Client::new(),
+        tls: security.tls.is_some(),
+        tokens,
+        oauth_clients: auth.oauth_clients,
+        access_token_ttl: Duration::from_secs(
+            auth.access_token_ttl_seconds
+                .unwrap_or(DEFAULT_ACCESS_TOKEN_TTL_SECS),
+        ),
+        access_tokens: Mutex::new(HashMap::new()),
+        audit_log,
+    });
+
+    let app = Router::new()
+        .route(
+            "/.well-known/oauth-authorization-server",
+            get(authorization_server_metadata),
+        )
+        .route("/oauth/token", post(issue_access_token))
+        .fallback(any(proxy))
+        .layer(DefaultBodyLimit::max(MAX_REQUEST_BYTES))
+        .with_state(gateway)
+        .into_make_service_with_connect_info::<SocketAddr>();
+
+    let addr = listener.local_addr()?;
+    match &security.tls {
+        Some((cert, key)) => {
+            let acceptor = TlsAcceptor::from(Arc::new(load_tls_config(cert, key)?));
+            tracing::info!("MCP gateway listening on https://{}", addr);
+            axum::serve(TlsListener::new(listener, acceptor)?, app).await?;
+        }
+        None => {
+            tracing::info!("MCP gateway listening on http://{}", addr);
+            axum::serve(listener, app).await?;
+        }
+    }
+    Ok(())
+}
+
+impl Gateway {
+    fn auth_enabled(&self) -> bool {
+        !self.tokens.is_empty() || !self.oauth_clients.is_empty()
+    }
+
+    async fn authenticate(&self, headers: &HeaderMap) -> Option<Principal> {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))?
+            .trim();
+        let hash = hash_token(token);
+        if let Some(principal) = self.tokens.get(&hash) {
+            return Some(principal.clone());
+        }
+        let mut access_tokens = self.access_tokens.lock().await;
+        access_tokens.retain(|_, (_, expires_at)| *expires_at > Instant::now());
+        access_tokens
+            .get(&hash)
+            .map(|(principal, _)| principal.clone())
+    }
+
+    async fn audit(&self, event: AuditEvent) {
+        tracing::info!(
+            target: "mcp_audit",
+            client = event.client.as_deref().unwrap_or("-"),
+            remote = %event.remote,
+            method = %event.http_method,
+            path = %event.path,
+            rpc = ?event.rpc_methods,
+            tools = ?event.tools,
+            resources = ?event.resources,
+            outcome = event.outcome,
+            status = event.status,
+            duration_ms = event.duration_ms,
+            "MCP request"
+        );
+        if let Some(file) = &self.audit_log {
+            let mut line = serde_json::to_string(&event).unwrap_or_default();
+            line.push('\n');
+            if let Err(e) = file.lock().await.write_all(line.as_bytes()).await {
+                tracing::warn!("Failed to write MCP audit log: {}", e);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct AuditEvent {
+    timestamp: String,
+    remote: SocketAddr,
+    client: Option<String>,
+    http_method: String,
+    path: String,
+    rpc_methods: Vec<String>,
+    tools: Vec<String>,
+    resources: Vec<String>,
+    /// `allowed`, `denied` or `unauthenticated`
+    outcome: &'static str,
+    status: u16,
+    duration_ms: u64,
+}
+
+/// JSON-RPC methods in a request body, single or batched
+#[derive(Debug, Default, PartialEq)]
+struct RpcCalls {
+    methods: Vec<String>,
+    /// Tools it calls
+    tools: Vec<String>,
+    /// Resources it reads or subscribes to
+    resources: Vec<String>,
+}
+
+/// `None` for a body that isn't JSON, whose calls can't be checked
+fn rpc_calls(body: &[u8]) -> Option<RpcCalls> {
+    if body.trim_ascii().is_empty() {
+        return Some(RpcCalls::default());
+    }
+    let value = serde_json::from_slice::<Value>(body).ok()?;
+    let messages = match value {
+        Value::Array(messages) => messages,
+        message => vec![message],
+    };
+    let mut calls = RpcCalls::default();
+    for message in &messages {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            continue;
+        };
+        calls.methods.push(method.to_string());
+        match method {
+            "tools/call" => {
+                let name = message.pointer("/params/name").and_then(Value::as_str);
+                calls.tools.push(name.unwrap_or_default().to_string());
+            }
+            "resources/read" | "resources/subscribe" => {
+                let uri = message.pointer("/params/uri").and_then(Value::as_str);
+                calls.resources.push(uri.unwrap_or_default().to_string());
+            }
+            _ => {}
+        }
+    }
+    Some(calls)
+}
+
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+fn rpc_error(status: StatusCode, message: String) -> Response {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": { "code": -32001, "message": message },
+    });
+    let mut response = (status, Json(body)).into_response();
+    if status == StatusCode::UNAUTHORIZED {
+        response.headers_mut().insert(
+            header::WWW_AUTHENTICATE,
+            HeaderValue::from_static("Bearer realm=\"vibe-kanban-mcp\""),
+        );
+    }
+    response
+}
+
+async fn proxy(
+    State(gateway): State<Arc<Gateway>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let started = Instant::now();
+    let parsed = rpc_calls(&body);
+    let unparsed = parsed.is_none();
+    let calls = parsed.unwrap_or_default();
+    let mut event = AuditEvent {
+        timestamp: Utc::now().to_rfc3339(),
+        remote,
+        client: None,
+        http_method: method.to_string(),
+        path: uri.path().to_string(),
+        rpc_methods: calls.methods,
+        tools: calls.tools,
+        resources: calls.resources,
+        outcome: "allowed",
+        status: 0,
+        duration_ms: 0,
+    };
+
+    let response = 'response: {
+        if gateway.auth_enabled() {
+            let Some(principal) = gateway.authenticate(&headers).await else {
+                event.outcome = "unauthenticated";
+                break 'response rpc_error(
+                    StatusCode::UNAUTHORIZED,
+                    "Missing or invalid bearer token".to_string(),
+                );
+            };
+            event.client = Some(principal.name.clone());
+            if unparsed && principal.access.is_restricted() {
+                event.outcome = "denied";
+                break 'response rpc_error(
+                    StatusCode::BAD_REQUEST,
+                    "Request body is not JSON-RPC".to_string(),
+                );
+            }
+            if let Some(tool) = event.tools.iter().find(|t| !principal.access.allows(t)) {
+                event.outcome = "denied";
+                break 'response rpc_error(
+                    StatusCode::FORBIDDEN,
+                    format!("'{}' may not call the tool '{}'", principal.name, tool),
+                );
+            }
+            if let Some(resource) = event
+                .resources
+                .iter()
+                .find(|r| !principal.access.allows_resource(r))
+            {
+                event.outcome = "denied";
+                break 'response rpc_error(
+                    StatusCode::FORBIDDEN,
+                    format!(
+                        "'{}' may not read the resource '{}'",
+                        principal.name, resource
+                    ),
+                );
+            }
+        }
+        forward(&gateway, method, &uri, &headers, body).await
+    };
+
+    event.status = response.status().as_u16();
+    event.duration_ms = started.elapsed().as_millis() as u64;
+    gateway.audit(event).await;
+    response
+}
+
+/// Send a request on to the MCP server, streaming the response back so SSE
+/// keeps working
+async fn forward(
+    gateway: &Gateway,
+    method: Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> Response {
+    let path = uri.path_and_query().map_or("/", |p| p.as_str());
+    let mut request = gateway
+        .client
+        .request(method, format!("http://{}{}", gateway.upstream, path))
+        .header(UPSTREAM_SECRET_HEADER, &gateway.upstream_secret)
+        .body(body);
+    for (name, value) in headers {
+        if name != header::AUTHORIZATION
+            && name != header::HOST
+            && name != header::CONTENT_LENGTH
+            && name != UPSTREAM_SECRET_HEADER
+        {
+            request = request.header(name, value);
+        }
+    }
+    let upstream = match request.send().await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            tracing::error!("MCP server unreachable: {}", e);
+            return rpc_error(
+                StatusCode::BAD_GATEWAY,
+                "MCP server unreachable".to_string(),
+            );
+        }
+    };
+
+    let mut response = Response::builder().status(upstream.status());
+    for (name, value) in upstream.headers() {
+        if name != header::CONNECTION && name != header::TRANSFER_ENCODING {
+            response = response.header(name, value);
+        }
+    }
+    response
+        .body(Body::from_stream(upstream.bytes_stream()))
+        .unwrap_or_else(|_| StatusCode::BAD_GATEWAY.into_response())
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenRequest {
+    grant_type: String,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+}
+
+/// OAuth2 token endpoint for the client-credentials grant. Clients send
+/// their credentials in the form or with HTTP Basic auth.
+async fn issue_access_token(
+    State(gateway): State<Arc<Gateway>>,
+    headers: HeaderMap,
+    Form(request): Form<TokenRequest>,
+) -> Response {
+    let oauth_error =
+        |status: StatusCode, error: &str| (status, Json(json!({ "error": error }))).into_response();
+    if request.grant_type != "client_credentials" {
+        return oauth_error(StatusCode::BAD_REQUEST, "unsupported_grant_type");
+    }
+    let basic = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Basic "))
+        .and_then(|v| {
+            base64::engine::general_purpose::STANDARD
+                .decode(v.trim())
+                .ok()
+        })
+        .and_then(|v| String::from_utf8(v).ok())
+        .and_then(|v| {
+            v.split_once(':')
+                .map(|(id, secret)| (id.to_string(), secret.to_string()))
+        });
+    let (client_id, client_secret) = match (basic, request.client_id, request.client_secret) {
+        (Some(credentials), _, _) => credentials,
+        (None, Some(id), Some(secret)) => (id, secret),
+        _ => return oauth_error(StatusCode::UNAUTHORIZED, "invalid_client"),
+    };
+    let Some(client) = gateway.oauth_clients.iter().find(|c| {
+        c.client_id == client_id && hash_token(&c.client_secret) == hash_token(&client_secret)
+    }) else {
+        return oauth_error(StatusCode::UNAUTHORIZED, "invalid_client");
+    };
+
+    let access_token = generate_token();
+    let principal = Principal {
+        name: client.client_id.clone(),
+        access: client.access.clone(),
+    };
+    gateway.access_tokens.lock().await.insert(
+        hash_token(&access_token),
+        (principal, Instant::now() + gateway.access_token_ttl),
+    );
+    Json(json!({
+        "access_token": access_token,
+        "token_type": "Bearer",
+        "expires_in": gateway.access_token_ttl.as_secs(),
+    }))
+    .into_response()
+}
+
+async fn authorization_server_metadata(
+    State(gateway): State<Arc<Gateway>>,
+    headers: HeaderMap,
+) -> Response {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("localhost");
+    let issuer = format!("{}://{}", if gateway.tls { "https" } else { "http" }, host);
+    Json(json!({
+        "issuer": issuer,
+        "token_endpoint": format!("{issuer}/oauth/token"),
+        "grant_types_supported": ["client_credentials"],
+        "token_endpoint_auth_methods_supported": ["client_secret_basic", "client_secret_post"],
+    }))
+    .into_response()
+}
+
+fn load_tls_config(cert: &Path, key: &Path) -> anyhow::Result<rustls::ServerConfig> {
+    let open = |path: &Path| {
+        std::fs::File::open(path)
+            .map(io::BufReader::new)
+            .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", path.display(), e))
+    };
+    let certs = rustls_pemfile::certs(&mut open(cert)?).collect::<Result<Vec<_>, _>>()?;
+    let key = rustls_pemfile::private_key(&mut open(key)?)?
+        .ok_or_else(|| anyhow::anyhow!("No private key in {}", key.display()))?;
+    let config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()?
+    .with_no_client_auth()
+    .with_single_cert(certs, key)?;
+    Ok(config)
+}
+
+/// Accepts TCP connections and completes their TLS handshakes in tasks of
+/// their own, so a client that never sends a ClientHello holds up no one else
+struct TlsListener {
+    local_addr: SocketAddr,
+    handshaken: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+}
+
+impl TlsListener {
+    fn new(mut tcp: TcpListener, acceptor: TlsAcceptor) -> io::Result<Self> {
+        let local_addr = tcp.local_addr()?;
+        let (tx, handshaken) = mpsc::channel(64);
+        tokio::spawn(async move {
+            while !tx.is_closed() {
+                let (stream, addr) = Listener::accept(&mut tcp).await;
+                let acceptor = acceptor.clone();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await
+                    {
+                        Ok(Ok(stream)) => {
+                            let _ = tx.send((stream, addr)).await;
+                        }
+                        Ok(Err(e)) => tracing::debug!("TLS handshake with {} failed: {}", addr, e),
+                        Err(_) => tracing::debug!("TLS handshake with {} timed out", addr),
+                    }
+                });
+            }
+        });
+        Ok(Self {
+            local_addr,
+            handshaken,
+        })
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.handshaken.recv().await {
+            Some(connection) => connection,
+            // The accepting task only ends once this listener is dropped
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn globs_match_tool_names() {
+        assert!(glob_matches("*", "delete_project"));
+        assert!(glob_matches("list_*", "list_projects"));
+        assert!(glob_matches("*_project", "delete_project"));
+        assert!(glob_matches("get_*_logs", "get_process_raw_logs"));
+        assert!(glob_matches("update_config", "update_config"));
+        assert!(!glob_matches("list_*", "delete_project"));
+        assert!(!glob_matches("update_config", "update_config_now"));
+        assert!(!glob_matches("get_*_logs", "get_logs"));
+    }
+
+    #[test]
+    fn denials_win_over_allowances() {
+        let read_only = ToolAccess {
+            allow_tools: Some(vec!["list_*".to_string(), "get_*".to_string()]),
+            deny_tools: vec!["get_config".to_string()],
+            ..Default::default()
+        };
+        assert!(read_only.allows("list_tasks"));
+        assert!(!read_only.allows("delete_project"));
+        assert!(!read_only.allows("get_config"));
+        assert!(ToolAccess::default().allows("update_config"));
+        assert!(read_only.is_restricted());
+        assert!(!ToolAccess::default().is_restricted());
+    }
+
+    #[test]
+    fn resources_need_access_of_their_own() {
+        assert!(ToolAccess::default().allows_resource("vibe://task/1"));
+        let tasks_only = ToolAccess {
+            allow_tools: Some(vec!["list_tasks".to_string()]),
+            ..Default::default()
+        };
+        assert!(!tasks_only.allows_resource("vibe://task/1"));
+        let no_logs = ToolAccess {
+            allow_tools: Some(vec!["list_tasks".to_string()]),
+            allow_resources: Some(vec!["vibe://*".to_string()]),
+            deny_resources: vec!["vibe://attempt/*/logs".to_string()],
+            ..Default::default()
+        };
+        assert!(no_logs.allows_resource("vibe://task/1"));
+        assert!(!no_logs.allows_resource("vibe://attempt/1/logs"));
+    }
+
+    #[test]
+    fn finds_tool_calls_in_batches() {
+        let body = json!([
+            { "jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": { "name": "delete_project" } },
+            { "jsonrpc": "2.0", "method": "notifications/initialized" },
+            { "jsonrpc": "2.0", "id": 2, "result": {} },
+        ]);
+        let calls = rpc_calls(body.to_string().as_bytes()).unwrap();
+        assert_eq!(calls.methods, ["tools/call", "notifications/initialized"]);
+        assert_eq!(calls.tools, ["delete_project"]);
+        assert_eq!(rpc_calls(b""), Some(RpcCalls::default()));
+        assert_eq!(rpc_calls(b"not json"), None);
+        assert_eq!(
+            rpc_calls(br#"{"method": "tools/call", "params": {"name": "x"}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn finds_resource_reads_and_subscriptions() {
+        let body = json!([
+            { "jsonrpc": "2.0", "id": 1, "method": "resources/read", "params": { "uri": "vibe://task/1" } },
+            { "jsonrpc": "2.0", "id": 2, "method": "resources/subscribe", "params": { "uri": "vibe://attempt/2" } },
+            { "jsonrpc": "2.0", "id": 3, "method": "resources/list" },
+        ]);
+        let calls = rpc_calls(body.to_string().as_bytes()).unwrap();
+        assert_eq!(calls.resources, ["vibe://task/1", "vibe://attempt/2"]);
+        assert!(calls.tools.is_empty());
+    }
+
+    const TOKEN: &str = "dashboard-token";
+
+    /// A gateway allowing `TOKEN` to call `list_*` and read task resources,
+    /// in front of an upstream that echoes the requests it gets
+    async fn start_gateway() -> (SocketAddr, SocketAddr) {
+        let echo = Router::new().fallback(any(|body: Bytes| async move { body }));
+        let upstream_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream = upstream_listener.local_addr().unwrap();
+        let secret = generate_token();
+        let upstream_secret = secret.clone();
+        tokio::spawn(
+            async move { serve_upstream(upstream_listener, echo, &upstream_secret).await },
+        );
+
+        let security = HttpSecurity {
+            auth: Some(AuthConfig {
+                tokens: vec![TokenConfig {
+                    name: "dashboard".to_string(),
+                    token: Some(TOKEN.to_string()),
+                    token_sha256: None,
+                    access: ToolAccess {
+                        allow_tools: Some(vec!["list_*".to_string()]),
+                        allow_resources: Some(vec!["vibe://task/*".to_string()]),
+                        ..Default::default()
+                    },
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(security, listener, upstream, secret));
+        (addr, upstream)
+    }
+
+    async fn call(addr: SocketAddr, token: Option<&str>, body: Value) -> reqwest::Response {
+        let mut request = reqwest::Client::new()
+            .post(format!("http://{addr}/mcp"))
+            .json(&body);
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await.unwrap()
+    }
+
+    fn tool_call(tool: &str) -> Value {
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": { "name": tool } })
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_without_a_valid_token() {
+        let (addr, _) = start_gateway().await;
+        let response = call(addr, None, tool_call("list_projects")).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = call(addr, Some("wrong"), tool_call("list_projects")).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn forbids_calls_outside_the_allow_list() {
+        let (addr, _) = start_gateway().await;
+        let response = call(addr, Some(TOKEN), tool_call("delete_project")).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let read = json!({ "jsonrpc": "2.0", "id": 1, "method": "resources/read", "params": { "uri": "vibe://attempt/1/logs" } });
+        let response = call(addr, Some(TOKEN), read).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn proxies_allowed_calls() {
+        let (addr, _) = start_gateway().await;
+        let response = call(addr, Some(TOKEN), tool_call("list_projects")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.json::<Value>().await.unwrap(),
+            tool_call("list_projects")
+        );
+        let read = json!({ "jsonrpc": "2.0", "id": 1, "method": "resources/read", "params": { "uri": "vibe://task/1" } });
+        let response = call(addr, Some(TOKEN), read.clone()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.json::<Value>().await.unwrap(), read);
+    }
+
+    #[tokio::test]
+    async fn upstream_refuses_requests_bypassing_the_gateway() {
+        let (_, upstream) = start_gateway().await;
+        let response = call(upstream, Some(TOKEN), tool_call("list_projects")).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
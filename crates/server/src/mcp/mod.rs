@@ -1,2 +1,36 @@
+pub mod gateway;
+pub mod resources;
 pub mod system_server;
 pub mod task_server;
+
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
+use utils::port_file::read_token_file;
+
+/// Environment variable with the API token the MCP servers send to the backend
+pub const API_TOKEN_ENV: &str = "VIBE_API_TOKEN";
+
+/// Token for the backend: `VIBE_API_TOKEN`, or else the admin token of a
+/// local server with authentication enabled
+pub async fn backend_api_token() -> Option<String> {
+    if let Ok(token) = std::env::var(API_TOKEN_ENV)
+        && !token.trim().is_empty()
+    {
+        return Some(token.trim().to_string());
+    }
+    read_token_file("vibe-kanban").await.ok()
+}
+
+/// HTTP client for the backend API, sending `api_token` with every request
+pub fn backend_client(api_token: Option<&str>) -> reqwest::Client {
+    let mut headers = HeaderMap::new();
+    if let Some(token) = api_token
+        && let Ok(mut value) = HeaderValue::from_str(&format!("Bearer {token}"))
+    {
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap_or_default()
+}
@@ -0,0 +1,302 @@
+//! `vibe://` resources for projects, tasks and attempts, and subscriptions to
+//! them backed by the backend's live event streams.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use serde_json::Value;
+use tokio::{
+    sync::{Mutex, Notify},
+    task::JoinHandle,
+    time::Instant,
+};
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use crate::cli::client::LogStream;
+
+/// How long a log stream may stay quiet before its history counts as read
+const SNAPSHOT_IDLE: Duration = Duration::from_millis(750);
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum VibeResource {
+    /// `vibe://projects`
+    Projects,
+    /// `vibe://project/{id}/tasks`
+    ProjectTasks(Uuid),
+    /// `vibe://task/{id}`
+    Task(Uuid),
+    /// `vibe://attempt/{id}`
+    Attempt(Uuid),
+    /// `vibe://attempt/{id}/logs`
+    AttemptLogs(Uuid),
+}
+
+impl FromStr for VibeResource {
+    type Err = String;
+
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        let path = uri
+            .strip_prefix("vibe://")
+            .ok_or_else(|| format!("Not a vibe:// URI: {uri}"))?;
+        let id = |s: &str| Uuid::parse_str(s).map_err(|_| format!("Invalid ID '{s}' in {uri}"));
+        let parts: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+        match parts.as_slice() {
+            ["projects"] => Ok(Self::Projects),
+            ["project", project_id, "tasks"] => Ok(Self::ProjectTasks(id(project_id)?)),
+            ["task", task_id] => Ok(Self::Task(id(task_id)?)),
+            ["attempt", attempt_id] => Ok(Self::Attempt(id(attempt_id)?)),
+            ["attempt", attempt_id, "logs"] => Ok(Self::AttemptLogs(id(attempt_id)?)),
+            _ => Err(format!("Unknown resource {uri}")),
+        }
+    }
+}
+
+impl fmt::Display for VibeResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Projects => write!(f, "vibe://projects"),
+            Self::ProjectTasks(id) => write!(f, "vibe://project/{id}/tasks"),
+            Self::Task(id) => write!(f, "vibe://task/{id}"),
+            Self::Attempt(id) => write!(f, "vibe://attempt/{id}"),
+            Self::AttemptLogs(id) => write!(f, "vibe://attempt/{id}/logs"),
+        }
+    }
+}
+
+impl VibeResource {
+    /// Whether the operations of one event stream patch change this resource.
+    /// The stream's initial snapshot replaces the whole collection and never
+    /// counts as a change.
+    pub fn is_changed_by(&self, ops: &Value) -> bool {
+        ops.as_array().into_iter().flatten().any(|op| {
+            let path = op.get("path").and_then(Value::as_str).unwrap_or_default();
+            match self {
+                Self::Projects => path.starts_with("/projects/"),
+                Self::ProjectTasks(_) => path.starts_with("/tasks/"),
+                Self::Task(id) => path == format!("/tasks/{id}"),
+                Self::Attempt(_) => path.starts_with("/execution_processes/"),
+                // Logs change constantly while a run streams; report a new run
+                // and the end of one
+                Self::AttemptLogs(_) => {
+                    path.starts_with("/execution_processes/")
+                        && (op.get("op").and_then(Value::as_str) == Some("add")
+                            || op
+                                .pointer("/value/status")
+                                .and_then(Value::as_str)
+                                .is_some_and(|status| status != "running"))
+                }
+            }
+        })
+    }
+}
+
+/// The collection in the snapshot a `/stream/ws` endpoint sends first, e.g.
+/// the `execution_processes` of an attempt keyed by ID
+pub async fn read_snapshot(url: &str, api_token: Option<&str>) -> anyhow::Result<Value> {
+    let mut stream = LogStream::connect(url, api_token).await?;
+    while let Some(msg) = stream.next(Some(Duration::from_secs(10))).await? {
+        if let LogMsg::JsonPatch(patch) = msg {
+            let ops = serde_json::to_value(&patch)?;
+            return Ok(ops.pointer("/0/value").cloned().unwrap_or(Value::Null));
+        }
+    }
+    anyhow::bail!("The stream ended before sending a snapshot")
+}
+
+/// The conversation entries of a coding agent run so far, read from its
+/// normalized log stream
+pub async fn read_conversation(url: &str, api_token: Option<&str>) -> anyhow::Result<Vec<Value>> {
+    let mut stream = LogStream::connect(url, api_token).await?;
+    let mut entries = BTreeMap::new();
+    while let Some(msg) = stream.next(Some(SNAPSHOT_IDLE)).await? {
+        if let LogMsg::JsonPatch(patch) = msg {
+            apply_conversation_patch(&serde_json::to_value(&patch)?, &mut entries);
+        }
+    }
+    Ok(entries.into_values().collect())
+}
+
+/// Apply one patch of a normalized log stream to its conversation entries,
+/// keyed by index
+pub fn apply_conversation_patch(ops: &Value, entries: &mut BTreeMap<usize, Value>) {
+    for op in ops.as_array().into_iter().flatten() {
+        let Some(index) = op
+            .get("path")
+            .and_then(Value::as_str)
+            .and_then(|p| p.strip_prefix("/entries/"))
+            .and_then(|i| i.parse::<usize>().ok())
+        else {
+            continue;
+        };
+        if let Some(entry) = op
+            .get("value")
+            .filter(|v| v.get("type").and_then(Value::as_str) == Some("NORMALIZED_ENTRY"))
+            .and_then(|v| v.get("content"))
+        {
+            entries.insert(index, entry.clone());
+        }
+    }
+}
+
+/// Resources an MCP client subscribed to, each watched through one backend
+/// event stream, and the ones that changed since the client last asked
+pub struct ResourceSubscriptions {
+    base_url: String,
+    api_token: Option<String>,
+    watchers: Mutex<HashMap<VibeResource, JoinHandle<()>>>,
+    updated: Arc<Mutex<BTreeSet<VibeResource>>>,
+    notify: Arc<Notify>,
+}
+
+impl ResourceSubscriptions {
+    pub fn new(base_url: &str, api_token: Option<&str>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_token: api_token.map(str::to_string),
+            watchers: Mutex::new(HashMap::new()),
+            updated: Arc::new(Mutex::new(BTreeSet::new())),
+            notify: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Watch `stream_path`, a backend `/stream/ws` endpoint, for changes to
+    /// `resource`. Subscribing twice keeps the existing watcher.
+    pub async fn subscribe(&self, resource: VibeResource, stream_path: &str) {
+        let mut watchers = self.watchers.lock().await;
+        if watchers.get(&resource).is_some_and(|w| !w.is_finished()) {
+            return;
+        }
+        let url = format!("{}{}", self.base_url, stream_path);
+        let api_token = self.api_token.clone();
+        let updated = self.updated.clone();
+        let notify = self.notify.clone();
+        let watcher = tokio::spawn(async move {
+            let mut reconnect = false;
+            loop {
+                match LogStream::connect(&url, api_token.as_deref()).await {
+                    Ok(mut stream) => {
+                        // Changes may have been missed while disconnected
+                        if reconnect {
+                            updated.lock().await.insert(resource);
+                            notify.notify_waiters();
+                        }
+                        loop {
+                            match stream.next(None).await {
+                                Ok(Some(LogMsg::JsonPatch(patch))) => {
+                                    let Ok(ops) = serde_json::to_value(&patch) else {
+                                        continue;
+                                    };
+                                    if resource.is_changed_by(&ops) {
+                                        updated.lock().await.insert(resource);
+                                        notify.notify_waiters();
+                                    }
+                                }
+                                Ok(Some(_)) => {}
+                                Ok(None) => break,
+                                Err(e) => {
+                                    tracing::warn!("Event stream for {} failed: {}", resource, e);
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => tracing::warn!("Failed to watch {}: {}", resource, e),
+                }
+                reconnect = true;
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        });
+        watchers.insert(resource, watcher);
+    }
+
+    /// Returns whether `resource` was subscribed
+    pub async fn unsubscribe(&self, resource: VibeResource) -> bool {
+        self.updated.lock().await.remove(&resource);
+        match self.watchers.lock().await.remove(&resource) {
+            Some(watcher) => {
+                watcher.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub async fn subscribed(&self) -> Vec<VibeResource> {
+        let mut resources: Vec<_> = self.watchers.lock().await.keys().copied().collect();
+        resources.sort();
+        resources
+    }
+
+    /// Resources changed since the last call, waiting up to `timeout` for one
+    /// when none are pending
+    pub async fn wait_for_updates(&self, timeout: Duration) -> Vec<VibeResource> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            // Created before checking so a change in between still wakes us
+            let notified = self.notify.notified();
+            {
+                let mut updated = self.updated.lock().await;
+                if !updated.is_empty() {
+                    return std::mem::take(&mut *updated).into_iter().collect();
+                }
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return Vec::new();
+            }
+        }
+    }
+}
+
+impl Drop for ResourceSubscriptions {
+    fn drop(&mut self) {
+        for watcher in self.watchers.get_mut().values() {
+            watcher.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn uris_round_trip() {
+        let id = Uuid::new_v4();
+        for resource in [
+            VibeResource::Projects,
+            VibeResource::ProjectTasks(id),
+            VibeResource::Task(id),
+            VibeResource::Attempt(id),
+            VibeResource::AttemptLogs(id),
+        ] {
+            assert_eq!(resource.to_string().parse::<VibeResource>(), Ok(resource));
+        }
+        assert!("vibe://task/not-a-uuid".parse::<VibeResource>().is_err());
+        assert!("file:///tmp".parse::<VibeResource>().is_err());
+    }
+
+    #[test]
+    fn logs_change_when_a_run_starts_or_ends() {
+        let logs = VibeResource::AttemptLogs(Uuid::new_v4());
+        let process = |op: &str, status: &str| {
+            let value = json!({ "status": status });
+            json!([{ "op": op, "path": "/execution_processes/1", "value": value }])
+        };
+        assert!(logs.is_changed_by(&process("add", "running")));
+        assert!(logs.is_changed_by(&process("replace", "completed")));
+        assert!(!logs.is_changed_by(&process("replace", "running")));
+
+        let snapshot = json!([{ "op": "replace", "path": "/execution_processes", "value": {} }]);
+        assert!(!logs.is_changed_by(&snapshot));
+        assert!(!VibeResource::Attempt(Uuid::new_v4()).is_changed_by(&snapshot));
+    }
+}
@@ -170,6 +170,56 @@ pub struct ListDirectoryResponseWrapper {
     pub count: usize,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListSecretsRequest {
+    #[schemars(description = "List the secrets of this project")]
+    pub project_id: Option<String>,
+    #[schemars(description = "List the secrets of this repository. Without either id, global secrets are listed")]
+    pub repo_id: Option<String>,
+}
+
+/// A secret without its value; values are never returned
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SecretSummary {
+    pub id: String,
+    #[schemars(description = "Environment variable the secret is injected as")]
+    pub name: String,
+    pub project_id: Option<String>,
+    pub repo_id: Option<String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListSecretsResponse {
+    pub secrets: Vec<SecretSummary>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SetSecretRequest {
+    #[schemars(description = "Environment variable name, e.g. 'OPENAI_API_KEY'")]
+    pub name: String,
+    #[schemars(description = "The secret value. It is stored encrypted and cannot be read back")]
+    pub value: String,
+    #[schemars(description = "Scope the secret to this project")]
+    pub project_id: Option<String>,
+    #[schemars(description = "Scope the secret to this repository. Without either id, the secret is global")]
+    pub repo_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SetSecretResponse {
+    pub secret: SecretSummary,
+    #[schemars(description = "Whether a new secret was created rather than an existing value replaced")]
+    pub created: bool,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteSecretRequest {
+    #[schemars(description = "The ID of the secret to delete")]
+    pub secret_id: String,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct HealthCheckResponse {
     pub status: String,
@@ -177,6 +227,13 @@ pub struct HealthCheckResponse {
     pub uptime_seconds: Option<u64>,
 }
 
+#[derive(Deserialize)]
+struct ApiResponseEnvelope<T> {
+    success: bool,
+    data: Option<T>,
+    message: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SystemServer {
     client: Arc<reqwest::Client>,
@@ -185,9 +242,9 @@ pub struct SystemServer {
 }
 
 impl SystemServer {
-    pub fn new(base_url: &str) -> Self {
+    pub fn new(base_url: &str, api_token: Option<&str>) -> Self {
         Self {
-            client: Arc::new(reqwest::Client::new()),
+            client: Arc::new(super::backend_client(api_token)),
             base_url: Arc::new(base_url.to_string()),
             start_time: std::time::Instant::now(),
         }
@@ -201,10 +258,11 @@ impl SystemServer {
         McpError::internal(error_msg)
     }
 
-    async fn send_json<T: DeserializeOwned>(
+    /// Send a request and check the API's success flag
+    async fn send_envelope<T: DeserializeOwned>(
         &self,
         rb: reqwest::RequestBuilder,
-    ) -> Result<T, McpError> {
+    ) -> Result<ApiResponseEnvelope<T>, McpError> {
         let resp = rb
             .send()
             .await
@@ -212,19 +270,18 @@ impl SystemServer {
 
         if !resp.status().is_success() {
             let status = resp.status();
+            // Surface the API's reason, e.g. why a secret name was rejected
+            let message = resp
+                .json::<ApiResponseEnvelope<serde_json::Value>>()
+                .await
+                .ok()
+                .and_then(|body| body.message);
             return Err(Self::err_str(
                 &format!("VK API returned error status: {}", status),
-                None,
+                message.as_deref(),
             ));
         }
 
-        #[derive(Deserialize)]
-        struct ApiResponseEnvelope<T> {
-            success: bool,
-            data: Option<T>,
-            message: Option<String>,
-        }
-
         let api_response = resp
             .json::<ApiResponseEnvelope<T>>()
             .await
@@ -235,11 +292,23 @@ impl SystemServer {
             return Err(Self::err_str("VK API returned error", Some(msg)));
         }
 
-        api_response
+        Ok(api_response)
+    }
+
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        rb: reqwest::RequestBuilder,
+    ) -> Result<T, McpError> {
+        self.send_envelope(rb)
+            .await?
             .data
             .ok_or_else(|| Self::err_str("VK API response missing data field", None))
     }
 
+    async fn send_no_data(&self, rb: reqwest::RequestBuilder) -> Result<(), McpError> {
+        self.send_envelope::<serde_json::Value>(rb).await.map(|_| ())
+    }
+
     fn url(&self, path: &str) -> String {
         format!(
             "{}/{}",
@@ -248,6 +317,18 @@ impl SystemServer {
         )
     }
 
+    async fn list_secrets_from_api(&self, project_id: Option<&str>, repo_id: Option<&str>) -> Result<Vec<SecretSummary>, McpError> {
+        let mut query = Vec::new();
+        if let Some(project_id) = project_id {
+            query.push(("project_id", project_id));
+        }
+        if let Some(repo_id) = repo_id {
+            query.push(("repo_id", repo_id));
+        }
+        let url = self.url("/api/secrets");
+        self.send_json(self.client.get(&url).query(&query)).await
+    }
+
     async fn get_config_from_api(&self) -> Result<serde_json::Value, McpError> {
         let url = self.url("/api/config/info");
         self.send_json(self.client.get(&url)).await
@@ -296,7 +377,7 @@ impl SystemServer {
 #[turbomcp::server(
     name = "vibe-kanban-system",
     version = "1.0.0",
-    description = "System configuration and discovery tools for Vibe Kanban. TOOLS: 'get_system_info', 'get_config', 'update_config', 'list_mcp_servers', 'update_mcp_servers', 'list_executor_profiles', 'list_git_repos', 'list_directory', 'health_check'. Use these tools to inspect system state, manage configuration, discover resources, and monitor health."
+    description = "System configuration and discovery tools for Vibe Kanban. TOOLS: 'get_system_info', 'get_config', 'update_config', 'list_mcp_servers', 'update_mcp_servers', 'list_executor_profiles', 'list_git_repos', 'list_directory', 'list_secrets', 'set_secret', 'delete_secret', 'health_check'. Use these tools to inspect system state, manage configuration and secrets, discover resources, and monitor health."
 )]
 impl SystemServer {
     #[tool(description = "Get system information including OS details and key directories")]
@@ -435,6 +516,63 @@ impl SystemServer {
         Ok(serde_json::to_string_pretty(&data).unwrap())
     }
 
+    #[tool(description = "List the names of global, project or repository secrets. Secret values are never returned")]
+    async fn list_secrets(&self, request: ListSecretsRequest) -> McpResult<String> {
+        let secrets = self
+            .list_secrets_from_api(request.project_id.as_deref(), request.repo_id.as_deref())
+            .await?;
+        let response = ListSecretsResponse {
+            count: secrets.len(),
+            secrets,
+        };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Create a secret, or replace the value of the secret with the same name and scope. Secrets are injected as environment variables into coding agents and scripts, and masked in their logs"
+    )]
+    async fn set_secret(&self, request: SetSecretRequest) -> McpResult<String> {
+        let existing = self
+            .list_secrets_from_api(request.project_id.as_deref(), request.repo_id.as_deref())
+            .await?
+            .into_iter()
+            .find(|s| s.name == request.name);
+
+        let (secret, created) = match existing {
+            Some(existing) => {
+                let url = self.url(&format!("/api/secrets/{}", existing.id));
+                let payload = serde_json::json!({ "value": request.value });
+                let secret: SecretSummary = self.send_json(self.client.put(&url).json(&payload)).await?;
+                (secret, false)
+            }
+            None => {
+                let url = self.url("/api/secrets");
+                let payload = serde_json::json!({
+                    "name": request.name,
+                    "value": request.value,
+                    "project_id": request.project_id,
+                    "repo_id": request.repo_id,
+                });
+                let secret: SecretSummary = self.send_json(self.client.post(&url).json(&payload)).await?;
+                (secret, true)
+            }
+        };
+
+        let response = SetSecretResponse { secret, created };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(description = "Delete a secret")]
+    async fn delete_secret(&self, request: DeleteSecretRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/secrets/{}", request.secret_id));
+        self.send_no_data(self.client.delete(&url)).await?;
+        Ok(serde_json::to_string_pretty(&serde_json::json!({
+            "deleted": true,
+            "secret_id": request.secret_id,
+        }))
+        .unwrap())
+    }
+
     #[tool(description = "Check if Vibe Kanban is healthy and get version information")]
     async fn health_check(&self) -> McpResult<String> {
         let url = self.url("/api/health");
@@ -471,6 +609,12 @@ impl SystemServer {
         self.clone().run_http_with_path(addr, "/mcp").await?;
         Ok(())
     }
+
+    /// The `/mcp` endpoint as a router, for serving behind the gateway
+    #[cfg(feature = "http")]
+    pub fn http_router(&self) -> axum::Router {
+        self.clone().into_router_with_path("/mcp")
+    }
 }
 
 
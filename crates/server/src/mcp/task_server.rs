@@ -1,15 +1,30 @@
-use std::{str::FromStr, sync::Arc};
+use std::{collections::BTreeMap, str::FromStr, sync::Arc, time::Duration};
 
 use db::models::{
+    coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_queue::QueuedExecution,
+    merge::Merge,
+    project_script::ProjectScript,
+    session::Session,
+    tag::Tag,
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
     workspace::Workspace,
 };
+use services::services::{
+    conflict_resolution::ConflictResolution, dev_server::DevServerInfo, queued_message::QueueStatus,
+};
 use turbomcp::prelude::*;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json;
+use base64::Engine;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use tokio::time::Instant;
+use utils::log_msg::LogMsg;
+
+use super::resources::{ResourceSubscriptions, VibeResource, apply_conversation_patch, read_conversation, read_snapshot};
+use crate::cli::{client::LogStream, read_diffs};
 
 /// API response struct for Project that matches the NPX backend schema
 /// This is separate from db::models::Project to handle schema differences
@@ -30,7 +45,10 @@ use crate::routes::task_attempts::{
     WorkspaceRepoInput,
     RebaseTaskAttemptRequest as ApiRebaseRequest,
     GitOperationError,
+    review_comments::{ReviewCommentWithRepo, SendReviewResponse},
+    sparse_checkout::RepoSparseCheckout,
 };
+use crate::routes::images::ImageResponse;
 
 // Minimal copy of ExecutorProfileId to avoid depending on executors crate
 // which has codex-protocol compilation issues
@@ -221,6 +239,52 @@ pub struct SearchProjectFilesResponse {
     pub count: usize,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchCodeRequest {
+    #[schemars(description = "Search all repositories of this project. Provide either `project_id` or `attempt_id`")]
+    pub project_id: Option<Uuid>,
+    #[schemars(description = "Search the worktrees of this task attempt instead, including uncommitted changes")]
+    pub attempt_id: Option<Uuid>,
+    #[schemars(description = "Text to find in file contents")]
+    pub query: String,
+    #[schemars(description = "Treat the query as a regular expression. Default: false")]
+    pub regex: Option<bool>,
+    #[schemars(description = "Match case exactly. Default: false")]
+    pub case_sensitive: Option<bool>,
+    #[schemars(description = "Search mode: 'settings' (includes ignored files) or 'task_form' (respects .gitignore). Default: 'task_form'")]
+    pub mode: Option<String>,
+    #[schemars(description = "Lines of context around each match (max 10). Default: 2")]
+    pub context_lines: Option<usize>,
+    #[schemars(description = "Maximum number of matching lines (max 500). Default: 100")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct CodeMatch {
+    #[schemars(description = "File path prefixed with the repository name")]
+    pub path: String,
+    #[schemars(description = "1-based line number of the match")]
+    pub line_number: usize,
+    #[schemars(description = "The matching line")]
+    pub line: String,
+    #[schemars(description = "Lines before the match")]
+    pub before: Vec<String>,
+    #[schemars(description = "Lines after the match")]
+    pub after: Vec<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SearchCodeResponse {
+    #[schemars(description = "Matching lines with context")]
+    pub matches: Vec<CodeMatch>,
+    #[schemars(description = "Number of matches returned")]
+    pub count: usize,
+    #[schemars(description = "Whether more lines matched than the limit allowed")]
+    pub truncated: bool,
+    #[schemars(description = "Number of files read")]
+    pub files_searched: usize,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ListTasksRequest {
     #[schemars(description = "The ID of the project to list tasks from")]
@@ -249,6 +313,8 @@ pub struct TaskSummary {
     pub updated_at: String,
     #[schemars(description = "Whether the task has an in-progress execution attempt")]
     pub has_in_progress_attempt: Option<bool>,
+    #[schemars(description = "Whether a coding agent run is waiting for a free slot")]
+    pub has_queued_attempt: Option<bool>,
     #[schemars(description = "Whether the last execution attempt failed")]
     pub last_attempt_failed: Option<bool>,
     #[schemars(description = "The executor used for the task")]
@@ -264,6 +330,7 @@ impl TaskSummary {
             created_at: task.created_at.to_rfc3339(),
             updated_at: task.updated_at.to_rfc3339(),
             has_in_progress_attempt: Some(task.has_in_progress_attempt),
+            has_queued_attempt: Some(task.has_queued_attempt),
             last_attempt_failed: Some(task.last_attempt_failed),
             executor: Some(task.executor),
         }
@@ -546,7 +613,7 @@ pub struct ExecutionProcessSummary {
     pub session_id: String,
     #[schemars(description = "Why this process was run (e.g., SetupScript, CodingAgent, DevServer)")]
     pub run_reason: String,
-    #[schemars(description = "Current execution status (Running, Completed, Failed, Killed)")]
+    #[schemars(description = "Current execution status (Queued, Running, Completed, Failed, Killed)")]
     pub status: String,
     #[schemars(description = "Exit code if the process has completed")]
     pub exit_code: Option<i64>,
@@ -613,6 +680,444 @@ pub struct StopExecutionProcessResponse {
     pub process_id: String,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct QueuedRunSummary {
+    #[schemars(description = "The ID of the queued execution process")]
+    pub process_id: String,
+    #[schemars(description = "Zero-based place in the queue; 0 starts next")]
+    pub position: usize,
+    pub task_id: String,
+    pub task_title: String,
+    pub project_id: String,
+    pub attempt_id: String,
+    pub executor: Option<String>,
+    #[schemars(description = "Higher priorities start first")]
+    pub priority: i64,
+    pub queued_at: String,
+}
+
+impl QueuedRunSummary {
+    fn from_queue(queue: Vec<QueuedExecution>) -> Vec<Self> {
+        queue
+            .into_iter()
+            .enumerate()
+            .map(|(position, q)| Self {
+                process_id: q.execution_process_id.to_string(),
+                position,
+                task_id: q.task_id.to_string(),
+                task_title: q.task_title,
+                project_id: q.project_id.to_string(),
+                attempt_id: q.workspace_id.to_string(),
+                executor: q.executor,
+                priority: q.priority,
+                queued_at: q.created_at.to_rfc3339(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListQueuedRunsResponse {
+    pub count: usize,
+    pub queue: Vec<QueuedRunSummary>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ReorderQueuedRunRequest {
+    #[schemars(description = "The ID of the queued execution process")]
+    pub process_id: Uuid,
+    #[schemars(description = "New priority; higher priorities start first")]
+    pub priority: Option<i64>,
+    #[schemars(description = "Zero-based place to move the run to; takes precedence over priority")]
+    pub position: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CancelQueuedRunRequest {
+    #[schemars(description = "The ID of the queued execution process to cancel")]
+    pub process_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ResourceUriRequest {
+    #[schemars(description = "Resource URI: 'vibe://projects', 'vibe://project/{id}/tasks', 'vibe://task/{id}', 'vibe://attempt/{id}' or 'vibe://attempt/{id}/logs'")]
+    pub uri: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ResourceSubscriptionsResponse {
+    #[schemars(description = "All resources this client is subscribed to")]
+    pub subscribed: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct WaitForResourceUpdatesRequest {
+    #[schemars(description = "How long to wait for a change, in seconds (default 30, max 300)")]
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct WaitForResourceUpdatesResponse {
+    #[schemars(description = "URIs of the subscribed resources that changed since the last call; empty on timeout")]
+    pub updated: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListPendingApprovalsRequest {
+    #[schemars(description = "The ID of the task attempt whose latest coding agent run to check")]
+    pub attempt_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct PendingApprovalSummary {
+    #[schemars(description = "Pass to 'respond_to_approval'")]
+    pub approval_id: String,
+    #[schemars(description = "Pass to 'respond_to_approval'")]
+    pub execution_process_id: String,
+    pub tool_name: String,
+    #[schemars(description = "What the tool call will do, as shown in the UI")]
+    pub content: String,
+    #[schemars(description = "The full tool action, e.g. the command or file edits")]
+    pub action: serde_json::Value,
+    #[schemars(description = "When the request times out and counts as denied")]
+    pub timeout_at: Option<String>,
+}
+
+impl PendingApprovalSummary {
+    /// The approval a conversation entry waits for, if it is a tool call
+    /// pending approval
+    fn from_entry(entry: &serde_json::Value, execution_process_id: Uuid) -> Option<Self> {
+        let entry_type = entry.get("entry_type").filter(|t| t["type"] == "tool_use")?;
+        let status = entry_type.get("status").filter(|s| s["status"] == "pending_approval")?;
+        Some(Self {
+            approval_id: status["approval_id"].as_str()?.to_string(),
+            execution_process_id: execution_process_id.to_string(),
+            tool_name: entry_type["tool_name"].as_str().unwrap_or_default().to_string(),
+            content: entry["content"].as_str().unwrap_or_default().to_string(),
+            action: entry_type["action_type"].clone(),
+            timeout_at: status["timeout_at"].as_str().map(str::to_string),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListPendingApprovalsResponse {
+    pub count: usize,
+    pub approvals: Vec<PendingApprovalSummary>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RespondToApprovalRequest {
+    #[schemars(description = "The ID of the pending approval, from 'list_pending_approvals'")]
+    pub approval_id: String,
+    #[schemars(description = "The ID of the execution process that requested the approval")]
+    pub execution_process_id: Uuid,
+    #[schemars(description = "true to let the tool call run, false to deny it")]
+    pub approve: bool,
+    #[schemars(description = "Optional reason for a denial; the agent sees it")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct WaitForAttemptRequest {
+    #[schemars(description = "The ID of the task attempt to wait for")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "How long to wait, in seconds (default 600, max 3600)")]
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AttemptDiffStats {
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct WaitForAttemptResponse {
+    pub attempt_id: String,
+    #[schemars(description = "Why the wait ended: 'finished', 'approval_requested' or 'timed_out'")]
+    pub outcome: String,
+    #[schemars(description = "The attempt's latest coding agent run")]
+    pub process: ExecutionProcessSummary,
+    #[schemars(description = "The agent's final message, once the run has finished")]
+    pub summary: Option<String>,
+    #[schemars(description = "The tool call waiting for 'respond_to_approval' when outcome is 'approval_requested'")]
+    pub pending_approval: Option<PendingApprovalSummary>,
+    #[schemars(description = "Uncommitted and committed changes of the attempt against its target branch")]
+    pub diff_stats: Option<AttemptDiffStats>,
+}
+
+/// Sends the progress notifications of `wait_for_attempt`, skipping repeats
+#[derive(Default)]
+struct ProgressReporter {
+    sent: u64,
+    last: Option<String>,
+}
+
+impl ProgressReporter {
+    /// The progress count to send `message` with, or `None` if it repeats the
+    /// last message
+    fn next(&mut self, message: &str) -> Option<u64> {
+        if self.last.as_deref() == Some(message) {
+            return None;
+        }
+        self.sent += 1;
+        self.last = Some(message.to_string());
+        Some(self.sent)
+    }
+
+    async fn report(&mut self, ctx: &Context, message: String) {
+        let Some(progress) = self.next(&message) else {
+            return;
+        };
+        // A run's length is unknown, so progress has no total
+        if let Err(e) = ctx.report_progress(progress, None, Some(&message)).await {
+            tracing::debug!("Failed to send progress notification: {}", e);
+        }
+    }
+}
+
+/// Why `wait_for_attempt` stops after a look at the run, or `None` to keep
+/// waiting
+fn wait_outcome(status: &ExecutionProcessStatus, approval_requested: bool, deadline: Instant) -> Option<&'static str> {
+    if approval_requested {
+        Some("approval_requested")
+    } else if !matches!(status, ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running) {
+        Some("finished")
+    } else if Instant::now() >= deadline {
+        Some("timed_out")
+    } else {
+        None
+    }
+}
+
+/// What a conversation entry tells about a run's progress: an assistant
+/// message, or the agent's todo list
+fn progress_message(entry: &serde_json::Value) -> Option<String> {
+    let entry_type = entry.get("entry_type")?;
+    match entry_type["type"].as_str()? {
+        "assistant_message" => entry["content"].as_str().map(str::to_string),
+        "tool_use" if entry_type["action_type"]["action"] == "todo_management" => {
+            let todos = entry_type["action_type"]["todos"].as_array()?;
+            Some(
+                todos
+                    .iter()
+                    .map(|todo| format!("[{}] {}", todo["status"].as_str().unwrap_or_default(), todo["content"].as_str().unwrap_or_default()))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListSessionsRequest {
+    #[schemars(description = "The ID of the task attempt to list sessions for")]
+    pub attempt_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SessionSummary {
+    pub id: String,
+    pub attempt_id: String,
+    pub executor: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl SessionSummary {
+    fn from_session(session: Session) -> Self {
+        Self {
+            id: session.id.to_string(),
+            attempt_id: session.workspace_id.to_string(),
+            executor: session.executor,
+            created_at: session.created_at.to_rfc3339(),
+            updated_at: session.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListSessionsResponse {
+    pub count: usize,
+    pub sessions: Vec<SessionSummary>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SendFollowUpRequest {
+    #[schemars(description = "The ID of the session to continue")]
+    pub session_id: Uuid,
+    #[schemars(description = "The follow-up instructions for the coding agent")]
+    pub prompt: String,
+    #[schemars(description = "Optional executor variant to use")]
+    pub variant: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SendFollowUpResponse {
+    pub session_id: String,
+    pub process: ExecutionProcessSummary,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct QueueFollowUpRequest {
+    #[schemars(description = "The ID of the session to queue the follow-up for")]
+    pub session_id: Uuid,
+    #[schemars(description = "The follow-up instructions to send when the current run finishes")]
+    pub message: String,
+    #[schemars(description = "Optional executor variant to use")]
+    pub variant: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SessionFollowUpQueueRequest {
+    #[schemars(description = "The ID of the session")]
+    pub session_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct FollowUpQueueResponse {
+    pub session_id: String,
+    #[schemars(description = "Whether a follow-up is waiting for the current run to finish")]
+    pub queued: bool,
+    pub message: Option<String>,
+    pub variant: Option<String>,
+    pub queued_at: Option<String>,
+}
+
+impl FollowUpQueueResponse {
+    fn from_status(session_id: Uuid, status: QueueStatus) -> Self {
+        match status {
+            QueueStatus::Queued { message } => Self {
+                session_id: session_id.to_string(),
+                queued: true,
+                message: Some(message.data.message),
+                variant: message.data.variant,
+                queued_at: Some(message.queued_at.to_rfc3339()),
+            },
+            QueueStatus::Empty => Self {
+                session_id: session_id.to_string(),
+                queued: false,
+                message: None,
+                variant: None,
+                queued_at: None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListTagsRequest {
+    #[schemars(description = "Optional case-insensitive filter on the tag name")]
+    pub search: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct TagSummary {
+    pub id: String,
+    #[schemars(description = "Name used to insert the tag, as @name")]
+    pub tag_name: String,
+    #[schemars(description = "Text the tag expands to")]
+    pub content: String,
+    pub updated_at: String,
+}
+
+impl TagSummary {
+    fn from_tag(tag: Tag) -> Self {
+        Self {
+            id: tag.id.to_string(),
+            tag_name: tag.tag_name,
+            content: tag.content,
+            updated_at: tag.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListTagsResponse {
+    pub count: usize,
+    pub tags: Vec<TagSummary>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateTagRequest {
+    #[schemars(description = "Tag name, without the leading @")]
+    pub tag_name: String,
+    #[schemars(description = "Text the tag expands to")]
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct UpdateTagRequest {
+    #[schemars(description = "The ID of the tag to update")]
+    pub tag_id: Uuid,
+    #[schemars(description = "New tag name")]
+    pub tag_name: Option<String>,
+    #[schemars(description = "New content")]
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteTagRequest {
+    #[schemars(description = "The ID of the tag to delete")]
+    pub tag_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct UploadTaskImageRequest {
+    #[schemars(description = "The ID of the task to attach the image to")]
+    pub task_id: Uuid,
+    #[schemars(description = "File name including its extension, e.g. 'screenshot.png'")]
+    pub filename: String,
+    #[schemars(description = "The image bytes, base64 encoded; a 'data:image/...;base64,' URL also works. At most 20MB.")]
+    pub data: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ImageSummary {
+    pub id: String,
+    #[schemars(description = "Path of the image inside attempt worktrees")]
+    pub file_path: String,
+    #[schemars(description = "Markdown that shows the image; add it to the task description or a prompt")]
+    pub markdown: String,
+    pub original_name: String,
+    pub mime_type: Option<String>,
+    pub size_bytes: i64,
+}
+
+impl ImageSummary {
+    fn from_image(image: ImageResponse) -> Self {
+        Self {
+            id: image.id.to_string(),
+            markdown: format!("![{}]({})", image.original_name, image.file_path),
+            file_path: image.file_path,
+            original_name: image.original_name,
+            mime_type: image.mime_type,
+            size_bytes: image.size_bytes,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListTaskImagesRequest {
+    #[schemars(description = "The ID of the task")]
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListTaskImagesResponse {
+    pub count: usize,
+    pub images: Vec<ImageSummary>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteImageRequest {
+    #[schemars(description = "The ID of the image to delete")]
+    pub image_id: Uuid,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ReplaceExecutionProcessRequest {
     #[schemars(description = "The ID of the task attempt containing the process")]
@@ -708,6 +1213,71 @@ pub struct StartDevServerResponse {
     pub success: bool,
     pub message: String,
     pub attempt_id: String,
+    #[schemars(description = "The port the dev server was given in `PORT`")]
+    pub port: Option<u16>,
+    #[schemars(description = "Port of the Vibe Kanban preview listener, separate from the API port")]
+    pub preview_port: Option<u16>,
+    #[schemars(description = "URL to open the dev server's preview at")]
+    pub preview_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListScriptsRequest {
+    #[schemars(description = "The ID of the project whose scripts to list")]
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ScriptSummary {
+    pub id: String,
+    #[schemars(description = "Name to pass to run_script, e.g. 'test' or 'db:migrate'")]
+    pub name: String,
+    #[schemars(description = "Bash, Python or Node")]
+    pub language: String,
+    #[schemars(description = "Names of the scripts that run first")]
+    pub depends_on: Vec<String>,
+    #[schemars(description = "Pipeline the script belongs to: setup, cleanup or verification")]
+    pub pipeline: Option<String>,
+    pub working_dir: Option<String>,
+    pub timeout_seconds: Option<i64>,
+}
+
+impl ScriptSummary {
+    fn from_script(script: ProjectScript) -> Self {
+        Self {
+            id: script.id.to_string(),
+            name: script.name,
+            language: format!("{:?}", script.language),
+            depends_on: script.depends_on.0,
+            pipeline: script.pipeline.map(|p| format!("{:?}", p).to_lowercase()),
+            working_dir: script.working_dir,
+            timeout_seconds: script.timeout_seconds,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListScriptsResponse {
+    pub count: usize,
+    pub scripts: Vec<ScriptSummary>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RunScriptRequest {
+    #[schemars(description = "The ID of the task attempt to run the script in")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Name of the script to run after the scripts it depends on")]
+    pub name: Option<String>,
+    #[schemars(description = "Run a whole pipeline instead: 'setup', 'cleanup' or 'verification'")]
+    pub pipeline: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct RunScriptResponse {
+    pub attempt_id: String,
+    #[schemars(description = "Process of the first step; every step runs as its own execution process")]
+    pub execution_process_id: String,
+    pub status: String,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -750,21 +1320,25 @@ pub struct GetAttemptArtifactsResponse {
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
-pub struct CreateGitHubPrRequest {
+pub struct CreatePullRequestRequest {
     #[schemars(description = "The ID of the task attempt to create a PR for")]
     pub attempt_id: Uuid,
+    #[schemars(description = "The ID of the repo to open the PR for")]
+    pub repo_id: Uuid,
     #[schemars(description = "The title of the pull request")]
     pub title: String,
     #[schemars(description = "Optional description/body for the pull request")]
     pub body: Option<String>,
     #[schemars(description = "Optional target branch (defaults to attempt's target branch)")]
     pub target_branch: Option<String>,
+    #[schemars(description = "Open the PR as a draft")]
+    pub draft: Option<bool>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
-pub struct CreateGitHubPrResponse {
+pub struct CreatePullRequestResponse {
     pub success: bool,
-    pub pr_url: String,
+    pub pr_url: Option<String>,
     pub message: String,
     pub attempt_id: String,
 }
@@ -825,6 +1399,16 @@ pub struct GetBranchStatusResponse {
     pub suggested_actions: Vec<String>,
 }
 
+/// URL of a dev server's preview: the host of the Vibe Kanban server at
+/// `base_url`, on the preview port when previews have their own
+fn preview_url(base_url: &str, info: &DevServerInfo) -> Option<String> {
+    let mut url = url::Url::parse(base_url).ok()?;
+    if let Some(port) = info.preview_port {
+        url.set_port(Some(port)).ok()?;
+    }
+    url.join(&info.preview_path).ok().map(String::from)
+}
+
 /// Determine the overall sync status based on branch state
 fn determine_sync_status(
     commits_ahead: Option<usize>,
@@ -898,7 +1482,7 @@ fn suggest_actions(
             actions.push("Push changes to remote using 'push_attempt_branch'".to_string());
         }
         actions.push("Branch is ready to merge or create a PR".to_string());
-        actions.push("Use 'create_github_pr' to create a pull request".to_string());
+        actions.push("Use 'create_pull_request' to create a pull request".to_string());
     }
 
     if ahead == 0 && behind == 0 && !dirty {
@@ -984,34 +1568,275 @@ pub struct AbortConflictsResponse {
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
-pub struct ChangeTargetBranchRequest {
-    #[schemars(description = "The ID of the task attempt to update")]
+pub struct ResolveConflictsRequest {
+    #[schemars(description = "The ID of the task attempt with conflicts to resolve")]
     pub attempt_id: Uuid,
-    #[schemars(description = "The new target branch name (must exist in the repository)")]
-    pub new_target_branch: String,
+    #[schemars(description = "The ID of the repo whose rebase/merge stopped with conflicts")]
+    pub repo_id: Uuid,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
-pub struct ChangeTargetBranchResponse {
-    #[schemars(description = "Whether the operation succeeded")]
+pub struct ResolveConflictsResponse {
+    #[schemars(description = "Whether the resolution was started")]
     pub success: bool,
     #[schemars(description = "Status message")]
     pub message: String,
     #[schemars(description = "The attempt ID")]
     pub attempt_id: String,
-    #[schemars(description = "The new target branch")]
-    pub new_target_branch: String,
-    #[schemars(description = "Number of commits ahead of target")]
-    pub commits_ahead: usize,
-    #[schemars(description = "Number of commits behind target")]
-    pub commits_behind: usize,
+    #[schemars(description = "Operation being resolved (rebase, merge, cherry_pick, revert)")]
+    pub operation: Option<String>,
+    #[schemars(description = "The coding agent execution process resolving the conflicts")]
+    pub execution_process_id: Option<String>,
+    #[schemars(description = "Files with conflicts handed to the agent")]
+    pub conflicted_files: Vec<String>,
 }
 
-/// Main Vibe Kanban Task MCP Server
-#[derive(Clone)]
-pub struct TaskServer {
-    client: Arc<reqwest::Client>,
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetConflictResolutionRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetConflictResolutionResponse {
+    #[schemars(description = "The attempt ID")]
+    pub attempt_id: String,
+    #[schemars(description = "State of the latest resolution: 'resolving', 'verifying', 'resolved', 'aborted', or null if none was started")]
+    pub state: Option<String>,
+    #[schemars(description = "Number of agent turns used so far")]
+    pub round: Option<u32>,
+    #[schemars(description = "Outcome message, if finished")]
+    pub message: Option<String>,
+    #[schemars(description = "Tail of the test script output, if it ran")]
+    pub test_output: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetPrStatusRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct PrCheckSummary {
+    #[schemars(description = "Name of the check, job or status context")]
+    pub name: String,
+    #[schemars(description = "'pending', 'success' or 'failure'")]
+    pub status: String,
+    #[schemars(description = "Link to the check on the git host or CI system")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct PrStatusSummary {
+    #[schemars(description = "The repository the pull request belongs to")]
+    pub repo_id: String,
+    #[schemars(description = "Name of the repository")]
+    pub repo_name: String,
+    #[schemars(description = "Pull request number")]
+    pub pr_number: i64,
+    #[schemars(description = "Pull request URL")]
+    pub pr_url: String,
+    #[schemars(description = "Pull request state: 'open', 'merged', 'closed' or 'unknown'")]
+    pub pr_status: String,
+    #[schemars(description = "Combined CI status of the head commit ('pending', 'success', 'failure'), or null if no CI results were seen yet")]
+    pub ci_status: Option<String>,
+    #[schemars(description = "Head commit the CI results are for")]
+    pub ci_head_sha: Option<String>,
+    #[schemars(description = "When the CI results were last refreshed")]
+    pub ci_updated_at: Option<String>,
+    #[schemars(description = "Individual checks of the head commit")]
+    pub checks: Vec<PrCheckSummary>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetPrStatusResponse {
+    #[schemars(description = "The attempt ID")]
+    pub attempt_id: String,
+    #[schemars(description = "Latest pull request of each repository of the attempt")]
+    pub pull_requests: Vec<PrStatusSummary>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListReviewCommentsRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Also return resolved comments (default false)")]
+    pub include_resolved: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ReviewCommentSummary {
+    #[schemars(description = "The comment ID")]
+    pub id: String,
+    #[schemars(description = "The repository the file belongs to")]
+    pub repo_id: String,
+    #[schemars(description = "Name of the repository")]
+    pub repo_name: String,
+    #[schemars(description = "Path of the file relative to the repository root")]
+    pub file_path: String,
+    #[schemars(description = "'new' for lines of the attempt's version, 'old' for lines of the base version")]
+    pub side: String,
+    #[schemars(description = "First commented line (1-based)")]
+    pub line_start: i64,
+    #[schemars(description = "Last commented line (inclusive)")]
+    pub line_end: i64,
+    #[schemars(description = "The comment text")]
+    pub body: String,
+    #[schemars(description = "The commented lines")]
+    pub code: Option<String>,
+    #[schemars(description = "Commit the line numbers refer to")]
+    pub anchor_commit: String,
+    #[schemars(description = "True if the commented lines were changed or removed since")]
+    pub outdated: bool,
+    #[schemars(description = "Whether the comment was already sent to the agent")]
+    pub sent: bool,
+    #[schemars(description = "Whether the comment is resolved")]
+    pub resolved: bool,
+}
+
+impl From<ReviewCommentWithRepo> for ReviewCommentSummary {
+    fn from(item: ReviewCommentWithRepo) -> Self {
+        let comment = item.comment;
+        Self {
+            id: comment.id.to_string(),
+            repo_id: comment.repo_id.to_string(),
+            repo_name: item.repo_name,
+            file_path: comment.file_path,
+            side: serde_json::to_value(comment.side).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default(),
+            line_start: comment.line_start,
+            line_end: comment.line_end,
+            body: comment.body,
+            code: comment.anchor_text,
+            anchor_commit: comment.anchor_commit,
+            outdated: comment.outdated,
+            sent: comment.sent_at.is_some(),
+            resolved: comment.resolved_at.is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListReviewCommentsResponse {
+    #[schemars(description = "The attempt ID")]
+    pub attempt_id: String,
+    pub comments: Vec<ReviewCommentSummary>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateReviewCommentRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "The repository of the file. Can be omitted if `file_path` starts with the repository name")]
+    pub repo_id: Option<Uuid>,
+    #[schemars(description = "Path of the file relative to the repository root")]
+    pub file_path: String,
+    #[schemars(description = "'new' (default) to comment on the attempt's version of the file, 'old' to comment on the base version")]
+    pub side: Option<String>,
+    #[schemars(description = "First line to comment on (1-based)")]
+    pub line_start: i64,
+    #[schemars(description = "Last line to comment on (inclusive). Defaults to `line_start`")]
+    pub line_end: Option<i64>,
+    #[schemars(description = "The comment text")]
+    pub body: String,
+    #[schemars(description = "Anchor the line numbers to the commit this execution process left behind instead of the current worktree HEAD")]
+    pub execution_process_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ResolveReviewCommentRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "The ID of the review comment")]
+    pub comment_id: Uuid,
+    #[schemars(description = "Set to false to reopen a resolved comment (default true)")]
+    pub resolved: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ResolveReviewCommentResponse {
+    pub success: bool,
+    pub comment_id: String,
+    pub resolved: bool,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SendReviewRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Optional extra instructions to append after the comments")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SendReviewToolResponse {
+    #[schemars(description = "Number of comments sent to the agent")]
+    pub sent: usize,
+    #[schemars(description = "True if the agent was busy and the review was queued as its next follow-up")]
+    pub queued: bool,
+    #[schemars(description = "The coding agent execution process started for the review, if not queued")]
+    pub execution_process_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ExpandSparseCheckoutRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Repository to expand. If omitted, every sparse repository of the attempt is expanded")]
+    pub repo_id: Option<Uuid>,
+    #[schemars(description = "Directories to check out, relative to the repository root (e.g. 'libs/common')")]
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SparseCheckoutSummary {
+    #[schemars(description = "The repository ID")]
+    pub repo_id: String,
+    #[schemars(description = "Name of the repository")]
+    pub repo_name: String,
+    #[schemars(description = "Directories now checked out. Empty if the whole tree is checked out")]
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ExpandSparseCheckoutResponse {
+    #[schemars(description = "The attempt ID")]
+    pub attempt_id: String,
+    pub repos: Vec<SparseCheckoutSummary>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ChangeTargetBranchRequest {
+    #[schemars(description = "The ID of the task attempt to update")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "The new target branch name (must exist in the repository)")]
+    pub new_target_branch: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ChangeTargetBranchResponse {
+    #[schemars(description = "Whether the operation succeeded")]
+    pub success: bool,
+    #[schemars(description = "Status message")]
+    pub message: String,
+    #[schemars(description = "The attempt ID")]
+    pub attempt_id: String,
+    #[schemars(description = "The new target branch")]
+    pub new_target_branch: String,
+    #[schemars(description = "Number of commits ahead of target")]
+    pub commits_ahead: usize,
+    #[schemars(description = "Number of commits behind target")]
+    pub commits_behind: usize,
+}
+
+/// Main Vibe Kanban Task MCP Server
+#[derive(Clone)]
+pub struct TaskServer {
+    client: Arc<reqwest::Client>,
     base_url: Arc<String>,
+    api_token: Arc<Option<String>>,
+    subscriptions: Arc<ResourceSubscriptions>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1022,10 +1847,12 @@ struct ApiResponseEnvelope<T> {
 }
 
 impl TaskServer {
-    pub fn new(base_url: &str) -> Self {
+    pub fn new(base_url: &str, api_token: Option<&str>) -> Self {
         Self {
-            client: Arc::new(reqwest::Client::new()),
+            client: Arc::new(super::backend_client(api_token)),
             base_url: Arc::new(base_url.to_string()),
+            api_token: Arc::new(api_token.map(str::to_string)),
+            subscriptions: Arc::new(ResourceSubscriptions::new(base_url, api_token)),
         }
     }
 
@@ -1106,12 +1933,180 @@ impl TaskServer {
             path.trim_start_matches('/')
         )
     }
+
+    fn parse_resource(uri: &str) -> Result<VibeResource, McpError> {
+        VibeResource::from_str(uri).map_err(McpError::invalid_request)
+    }
+
+    /// An attempt's execution processes, oldest first, from the snapshot the
+    /// process stream starts with
+    async fn attempt_processes(&self, attempt_id: Uuid) -> Result<Vec<ExecutionProcess>, McpError> {
+        let url = self.url(&format!("/api/execution-processes/stream/ws?workspace_id={}", attempt_id));
+        let snapshot = read_snapshot(&url, self.api_token.as_deref())
+            .await
+            .map_err(|e| Self::err_str("Failed to read execution processes", Some(&e.to_string())))?;
+        let mut processes: Vec<ExecutionProcess> = serde_json::from_value::<std::collections::HashMap<String, ExecutionProcess>>(snapshot)
+            .map_err(|e| Self::err_str("Failed to parse execution processes", Some(&e.to_string())))?
+            .into_values()
+            .collect();
+        processes.sort_by_key(|p| p.created_at);
+        Ok(processes)
+    }
+
+    /// The attempt's latest coding agent run that was not dropped
+    async fn latest_coding_agent_process(&self, attempt_id: Uuid) -> Result<Option<ExecutionProcess>, McpError> {
+        Ok(self
+            .attempt_processes(attempt_id)
+            .await?
+            .into_iter()
+            .rfind(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent && !p.dropped))
+    }
+
+    /// Follow a running coding agent's conversation until the run ends, it
+    /// waits for an approval, or `deadline` passes, reporting its latest
+    /// message or todo list as progress
+    async fn watch_agent_run(
+        &self,
+        ctx: &Context,
+        process_id: Uuid,
+        deadline: Instant,
+        progress: &mut ProgressReporter,
+    ) -> Option<PendingApprovalSummary> {
+        let url = self.url(&format!("/api/execution-processes/{}/normalized-logs/ws", process_id));
+        let mut stream = match LogStream::connect(&url, self.api_token.as_deref()).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                // The run may have just finished; the caller checks again
+                tracing::debug!("Failed to follow execution process {}: {}", process_id, e);
+                tokio::time::sleep_until(deadline.min(Instant::now() + Duration::from_secs(1))).await;
+                return None;
+            }
+        };
+        let mut entries = BTreeMap::new();
+        let mut changed = false;
+        loop {
+            let idle = deadline.saturating_duration_since(Instant::now()).min(Duration::from_millis(750));
+            match tokio::time::timeout(idle, stream.next(None)).await {
+                Ok(Ok(Some(LogMsg::JsonPatch(patch)))) => {
+                    if let Ok(ops) = serde_json::to_value(&patch) {
+                        apply_conversation_patch(&ops, &mut entries);
+                        changed = true;
+                    }
+                }
+                Ok(Ok(Some(_))) => {}
+                // The run finished or the stream broke
+                Ok(Ok(None)) | Ok(Err(_)) => return None,
+                // Judge the conversation only once the stream goes quiet, as
+                // a reconnect replays approvals that were answered since
+                Err(_) => {
+                    if changed {
+                        changed = false;
+                        if let Some(approval) = entries.values().find_map(|e| PendingApprovalSummary::from_entry(e, process_id)) {
+                            return Some(approval);
+                        }
+                        if let Some(message) = entries.values().rev().find_map(progress_message) {
+                            progress.report(ctx, message).await;
+                        }
+                    }
+                    if Instant::now() >= deadline {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Total changes of an attempt, read from its live diff stream
+    async fn attempt_diff_stats(&self, attempt_id: Uuid) -> anyhow::Result<AttemptDiffStats> {
+        let url = self.url(&format!("/api/task-attempts/{}/diff/ws?stats_only=true", attempt_id));
+        let mut stream = LogStream::connect(&url, self.api_token.as_deref()).await?;
+        let diffs = read_diffs(&mut stream).await?;
+        Ok(AttemptDiffStats {
+            files_changed: diffs.len(),
+            additions: diffs.values().map(|d| d.additions.unwrap_or_default()).sum(),
+            deletions: diffs.values().map(|d| d.deletions.unwrap_or_default()).sum(),
+        })
+    }
+
+    async fn read_resource(&self, uri: &str) -> McpResult<String> {
+        let value = match Self::parse_resource(uri)? {
+            VibeResource::Projects => {
+                let projects: Vec<ApiProject> = self.send_json(self.client.get(&self.url("/api/projects"))).await?;
+                let projects: Vec<ProjectSummary> = projects.into_iter().map(ProjectSummary::from_api_project).collect();
+                serde_json::to_value(ListProjectsResponse { count: projects.len(), projects })
+            }
+            VibeResource::ProjectTasks(project_id) => {
+                let url = self.url(&format!("/api/tasks?project_id={}", project_id));
+                let tasks: Vec<TaskWithAttemptStatus> = self.send_json(self.client.get(&url)).await?;
+                let tasks: Vec<TaskSummary> = tasks.into_iter().map(TaskSummary::from_task_with_status).collect();
+                serde_json::to_value(tasks)
+            }
+            VibeResource::Task(task_id) => {
+                let task: Task = self.send_json(self.client.get(&self.url(&format!("/api/tasks/{}", task_id)))).await?;
+                let attempts_url = self.url(&format!("/api/task-attempts?task_id={}", task_id));
+                let workspaces: Vec<Workspace> = self.send_json(self.client.get(&attempts_url)).await?;
+                serde_json::to_value(GetTaskResponse {
+                    task: TaskDetails::from_task(task),
+                    attempts: Some(workspaces.into_iter().map(TaskAttemptSummary::from_workspace).collect()),
+                })
+            }
+            VibeResource::Attempt(attempt_id) => {
+                let url = self.url(&format!("/api/task-attempts/{}", attempt_id));
+                let workspace: Workspace = self.send_json(self.client.get(&url)).await?;
+                let processes = self.attempt_processes(attempt_id).await?;
+                serde_json::to_value(GetTaskAttemptResponse {
+                    attempt: TaskAttemptSummary::from_workspace(workspace),
+                    processes: Some(processes.into_iter().map(ExecutionProcessSummary::from_execution_process).collect()),
+                })
+            }
+            VibeResource::AttemptLogs(attempt_id) => {
+                // The conversation of the attempt's latest coding agent run
+                let latest = self.latest_coding_agent_process(attempt_id).await?;
+                let entries = match &latest {
+                    Some(process) => {
+                        let url = self.url(&format!("/api/execution-processes/{}/normalized-logs/ws", process.id));
+                        read_conversation(&url, self.api_token.as_deref())
+                            .await
+                            .map_err(|e| Self::err_str("Failed to read logs", Some(&e.to_string())))?
+                    }
+                    None => Vec::new(),
+                };
+                Ok(serde_json::json!({
+                    "execution_process_id": latest.as_ref().map(|p| p.id.to_string()),
+                    "status": latest.as_ref().map(|p| p.status.clone()),
+                    "entries": entries,
+                }))
+            }
+        };
+        let value = value.map_err(|e| Self::err_str("Failed to serialize resource", Some(&e.to_string())))?;
+        Ok(serde_json::to_string_pretty(&value).unwrap())
+    }
+
+    /// The backend event stream that reports changes to `resource`
+    async fn resource_stream_path(&self, resource: VibeResource) -> Result<String, McpError> {
+        Ok(match resource {
+            VibeResource::Projects => "/api/projects/stream/ws".to_string(),
+            VibeResource::ProjectTasks(project_id) => format!("/api/tasks/stream/ws?project_id={}", project_id),
+            VibeResource::Task(task_id) => {
+                let task: Task = self.send_json(self.client.get(&self.url(&format!("/api/tasks/{}", task_id)))).await?;
+                format!("/api/tasks/stream/ws?project_id={}", task.project_id)
+            }
+            VibeResource::Attempt(attempt_id) | VibeResource::AttemptLogs(attempt_id) => {
+                format!("/api/execution-processes/stream/ws?workspace_id={}", attempt_id)
+            }
+        })
+    }
+
+    async fn subscriptions_response(&self) -> String {
+        let subscribed = self.subscriptions.subscribed().await.iter().map(ToString::to_string).collect();
+        serde_json::to_string_pretty(&ResourceSubscriptionsResponse { subscribed }).unwrap()
+    }
 }
 
 #[turbomcp::server(
     name = "vibe-kanban",
     version = "1.0.0",
-    description = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. This should be provided to you. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project. TOOLS: 'list_projects', 'get_project', 'create_project', 'update_project', 'delete_project', 'get_project_branches', 'search_project_files', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task', 'list_task_attempts', 'get_task_attempt', 'create_followup_attempt', 'merge_task_attempt', 'get_branch_status', 'get_attempt_commits', 'compare_commit_to_head', 'abort_conflicts', 'list_execution_processes', 'get_execution_process', 'stop_execution_process', 'replace_execution_process', 'get_process_raw_logs', 'get_process_normalized_logs', 'start_dev_server', 'create_github_pr', 'push_attempt_branch', 'rebase_task_attempt', 'get_attempt_artifacts', 'change_target_branch'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids."
+    description = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. This should be provided to you. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project. TOOLS: 'list_projects', 'get_project', 'create_project', 'update_project', 'delete_project', 'get_project_branches', 'search_project_files', 'search_code', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task', 'list_task_attempts', 'get_task_attempt', 'create_followup_attempt', 'merge_task_attempt', 'get_branch_status', 'get_attempt_commits', 'compare_commit_to_head', 'abort_conflicts', 'resolve_conflicts', 'get_conflict_resolution', 'list_execution_processes', 'get_execution_process', 'stop_execution_process', 'list_queued_runs', 'reorder_queued_run', 'cancel_queued_run', 'replace_execution_process', 'get_process_raw_logs', 'get_process_normalized_logs', 'subscribe_resource', 'unsubscribe_resource', 'wait_for_resource_updates', 'wait_for_attempt', 'list_pending_approvals', 'respond_to_approval', 'list_sessions', 'send_follow_up', 'queue_follow_up', 'get_queued_follow_up', 'cancel_queued_follow_up', 'list_tags', 'create_tag', 'update_tag', 'delete_tag', 'upload_task_image', 'list_task_images', 'delete_image', 'start_dev_server', 'list_scripts', 'run_script', 'create_pull_request', 'get_pr_status', 'list_review_comments', 'create_review_comment', 'resolve_review_comment', 'send_review', 'expand_sparse_checkout', 'push_attempt_branch', 'rebase_task_attempt', 'get_attempt_artifacts', 'change_target_branch'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids. RESOURCES: 'vibe://projects', 'vibe://project/{id}/tasks', 'vibe://task/{id}', 'vibe://attempt/{id}', 'vibe://attempt/{id}/logs'; subscribe to them with 'subscribe_resource' and wait for changes with 'wait_for_resource_updates' instead of polling."
 )]
 impl TaskServer {
     #[tool(
@@ -1292,6 +2287,55 @@ impl TaskServer {
         Ok(serde_json::to_string_pretty(&response).unwrap())
     }
 
+    #[tool(description = "Search file contents across a project's repositories or an attempt's worktrees. Returns matching lines with surrounding context; use it to find where a symbol or string is used.")]
+    async fn search_code(&self, request: SearchCodeRequest) -> McpResult<String> {
+        let base = match (request.project_id, request.attempt_id) {
+            (Some(project_id), None) => format!("/api/projects/{}/search/content", project_id),
+            (None, Some(attempt_id)) => format!("/api/task-attempts/{}/search/content", attempt_id),
+            _ => {
+                return Err(McpError::invalid_request(
+                    "Provide exactly one of 'project_id' or 'attempt_id'.",
+                ));
+            }
+        };
+        let mode = match request.mode.as_deref() {
+            Some("settings") => "settings",
+            _ => "taskform",
+        };
+        let mut url = self.url(&format!(
+            "{}?q={}&mode={}&regex={}&case_sensitive={}",
+            base,
+            urlencoding::encode(&request.query),
+            mode,
+            request.regex.unwrap_or(false),
+            request.case_sensitive.unwrap_or(false)
+        ));
+        if let Some(context) = request.context_lines {
+            url.push_str(&format!("&context={}", context));
+        }
+        if let Some(limit) = request.limit {
+            url.push_str(&format!("&limit={}", limit));
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct ApiContentSearch {
+            matches: Vec<CodeMatch>,
+            truncated: bool,
+            files_searched: usize,
+        }
+
+        let result: ApiContentSearch = self.send_json(self.client.get(&url)).await?;
+
+        let response = SearchCodeResponse {
+            count: result.matches.len(),
+            matches: result.matches,
+            truncated: result.truncated,
+            files_searched: result.files_searched,
+        };
+
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
     #[tool(
         description = "List all the task/tickets in a project with optional filtering by status and search. `project_id` is required!"
     )]
@@ -1670,9 +2714,9 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "Create a GitHub pull request for a completed task attempt. The PR will be created from the attempt's branch to the target branch, with the task details included in the PR description. Returns the PR URL on success. `attempt_id` and `title` are required!"
+        description = "Create a pull request (merge request on GitLab) for a completed task attempt on the repo's git host: GitHub, GitLab or Gitea, picked from the project's git host setting, the `git_hosts` config or the remote URL. Pushes the attempt's branch and opens the PR against the target branch. Returns the PR URL on success. `attempt_id`, `repo_id` and `title` are required!"
     )]
-    async fn create_github_pr(&self, request: CreateGitHubPrRequest) -> McpResult<String> {
+    async fn create_pull_request(&self, request: CreatePullRequestRequest) -> McpResult<String> {
         let url = self.url(&format!("/api/task-attempts/{}/pr", request.attempt_id));
 
         #[derive(Serialize)]
@@ -1680,22 +2724,71 @@ impl TaskServer {
             title: String,
             body: Option<String>,
             target_branch: Option<String>,
+            draft: Option<bool>,
+            repo_id: Uuid,
+        }
+
+        // Define response structure that matches the API's error-with-data pattern
+        #[derive(Debug, Deserialize)]
+        struct ApiPrResponse {
+            success: bool,
+            data: Option<String>,
+            error_data: Option<serde_json::Value>,
+            message: Option<String>,
         }
 
         let payload = PrPayload {
             title: request.title.clone(),
             body: request.body.clone(),
             target_branch: request.target_branch.clone(),
+            draft: request.draft,
+            repo_id: request.repo_id,
         };
 
-        // POST to PR endpoint returns ApiResponse<String> where String is the PR URL
-        let pr_url: String = self.send_json(self.client.post(&url).json(&payload)).await?;
+        let resp = self.client.post(&url).json(&payload).send().await
+            .map_err(|e| Self::err_str("Failed to connect to VK API", Some(&e.to_string())))?;
+        let status = resp.status();
+        let api_response = resp.json::<ApiPrResponse>().await
+            .map_err(|e| Self::err_str("Failed to parse VK API response", Some(&e.to_string())))?;
 
-        let response = CreateGitHubPrResponse {
-            success: true,
-            pr_url: pr_url.clone(),
-            message: format!("GitHub PR created successfully: {}", pr_url),
-            attempt_id: request.attempt_id.to_string(),
+        let response = match api_response.data {
+            Some(pr_url) if status.is_success() && api_response.success => CreatePullRequestResponse {
+                success: true,
+                message: format!("Pull request created successfully: {}", pr_url),
+                pr_url: Some(pr_url),
+                attempt_id: request.attempt_id.to_string(),
+            },
+            _ => {
+                let error_data = api_response.error_data.as_ref();
+                let field = |name: &str| {
+                    error_data
+                        .and_then(|d| d.get(name))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                let reason = error_data
+                    .and_then(|d| d.get("type"))
+                    .and_then(|t| t.as_str())
+                    .map(|t| match t {
+                        "github_cli_not_installed" => "GitHub CLI (gh) is not installed".to_string(),
+                        "github_cli_not_logged_in" => "GitHub CLI is not logged in; run 'gh auth login'".to_string(),
+                        "git_cli_not_installed" => "Git is not installed".to_string(),
+                        "git_cli_not_logged_in" => "Git is not authenticated with the remote".to_string(),
+                        "target_branch_not_found" => format!("Target branch '{}' does not exist on the remote", field("branch")),
+                        "git_host_not_logged_in" => format!("Authentication with {} failed; store a token for it in a secret and name it in the git host settings", field("host")),
+                        "unsupported_git_host" => format!("Unsupported git host '{}'; set the project's git host or add it to `git_hosts` to choose a provider", field("host")),
+                        other => other.to_string(),
+                    })
+                    .or(api_response.message)
+                    .unwrap_or_else(|| format!("VK API returned error status: {}", status));
+                CreatePullRequestResponse {
+                    success: false,
+                    pr_url: None,
+                    message: reason,
+                    attempt_id: request.attempt_id.to_string(),
+                }
+            }
         };
 
         Ok(serde_json::to_string_pretty(&response).unwrap())
@@ -1809,6 +2902,50 @@ impl TaskServer {
         Ok(serde_json::to_string_pretty(&response).unwrap())
     }
 
+    #[tool(
+        description = "List coding agent runs waiting for a free slot because of the concurrency limits, in the order they will start."
+    )]
+    async fn list_queued_runs(&self) -> McpResult<String> {
+        let url = self.url("/api/execution-queue");
+        let queue: Vec<QueuedExecution> = self.send_json(self.client.get(&url)).await?;
+        let queue = QueuedRunSummary::from_queue(queue);
+        let response = ListQueuedRunsResponse {
+            count: queue.len(),
+            queue,
+        };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Change the priority of a queued coding agent run or move it to another place in the queue. Returns the updated queue. `process_id` is required!"
+    )]
+    async fn reorder_queued_run(&self, request: ReorderQueuedRunRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/execution-queue/{}", request.process_id));
+        let payload = serde_json::json!({
+            "priority": request.priority,
+            "position": request.position,
+        });
+        let queue: Vec<QueuedExecution> = self.send_json(self.client.put(&url).json(&payload)).await?;
+        let queue = QueuedRunSummary::from_queue(queue);
+        let response = ListQueuedRunsResponse {
+            count: queue.len(),
+            queue,
+        };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Cancel a queued coding agent run before it starts. The process is marked 'Killed'. `process_id` is required!"
+    )]
+    async fn cancel_queued_run(&self, request: CancelQueuedRunRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/execution-queue/{}", request.process_id));
+        self.send_no_data(self.client.delete(&url)).await?;
+        Ok(serde_json::json!({
+            "success": true,
+            "process_id": request.process_id.to_string(),
+        }).to_string())
+    }
+
     #[tool(
         description = "Replace an execution process by deleting it and all later processes, resetting the Git worktree to the state before that process, and starting a new execution with the given prompt. Useful for retrying a failed execution from a clean state or trying a different approach. `attempt_id`, `process_id`, and `prompt` are required!"
     )]
@@ -2021,7 +3158,333 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "Start a development server for a task attempt. This will execute the project's dev script (e.g., 'npm run dev') in the attempt's worktree. Only one dev server can run per project at a time - starting a new one will stop any existing dev server for the project. `attempt_id` is required!"
+        description = "Subscribe to changes of a vibe:// resource (see the server's resources). Changes are reported by 'wait_for_resource_updates'. `uri` is required!"
+    )]
+    async fn subscribe_resource(&self, request: ResourceUriRequest) -> McpResult<String> {
+        let resource = Self::parse_resource(&request.uri)?;
+        let stream_path = self.resource_stream_path(resource).await?;
+        self.subscriptions.subscribe(resource, &stream_path).await;
+        Ok(self.subscriptions_response().await)
+    }
+
+    #[tool(description = "Stop reporting changes of a subscribed vibe:// resource. `uri` is required!")]
+    async fn unsubscribe_resource(&self, request: ResourceUriRequest) -> McpResult<String> {
+        let resource = Self::parse_resource(&request.uri)?;
+        if !self.subscriptions.unsubscribe(resource).await {
+            return Err(McpError::invalid_request(format!("Not subscribed to {}", resource)));
+        }
+        Ok(self.subscriptions_response().await)
+    }
+
+    #[tool(
+        description = "Wait until subscribed resources change, e.g. a task changes status or an execution finishes, and return the URIs that changed since the last call. Returns immediately when changes are pending, or an empty list after the timeout. Read the URIs to get the new state."
+    )]
+    async fn wait_for_resource_updates(&self, request: WaitForResourceUpdatesRequest) -> McpResult<String> {
+        if self.subscriptions.subscribed().await.is_empty() {
+            return Err(McpError::invalid_request("No resources subscribed; call 'subscribe_resource' first".to_string()));
+        }
+        let timeout = Duration::from_secs(request.timeout_seconds.unwrap_or(30).clamp(1, 300));
+        let updated = self.subscriptions.wait_for_updates(timeout).await;
+        let response = WaitForResourceUpdatesResponse {
+            updated: updated.iter().map(ToString::to_string).collect(),
+        };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[resource("vibe://projects")]
+    async fn projects_resource(&self, uri: String) -> McpResult<String> {
+        self.read_resource(&uri).await
+    }
+
+    #[resource("vibe://project/{project_id}/tasks")]
+    async fn project_tasks_resource(&self, uri: String) -> McpResult<String> {
+        self.read_resource(&uri).await
+    }
+
+    #[resource("vibe://task/{task_id}")]
+    async fn task_resource(&self, uri: String) -> McpResult<String> {
+        self.read_resource(&uri).await
+    }
+
+    #[resource("vibe://attempt/{attempt_id}")]
+    async fn attempt_resource(&self, uri: String) -> McpResult<String> {
+        self.read_resource(&uri).await
+    }
+
+    #[resource("vibe://attempt/{attempt_id}/logs")]
+    async fn attempt_logs_resource(&self, uri: String) -> McpResult<String> {
+        self.read_resource(&uri).await
+    }
+
+    #[tool(
+        description = "List tool calls of an attempt's coding agent that are waiting for approval. Answer them with 'respond_to_approval'. `attempt_id` is required!"
+    )]
+    async fn list_pending_approvals(&self, request: ListPendingApprovalsRequest) -> McpResult<String> {
+        let latest = self.latest_coding_agent_process(request.attempt_id).await?;
+        let mut approvals = Vec::new();
+        if let Some(process) = latest.filter(|p| p.status == ExecutionProcessStatus::Running) {
+            let url = self.url(&format!("/api/execution-processes/{}/normalized-logs/ws", process.id));
+            let entries = read_conversation(&url, self.api_token.as_deref())
+                .await
+                .map_err(|e| Self::err_str("Failed to read logs", Some(&e.to_string())))?;
+            approvals.extend(entries.iter().filter_map(|entry| PendingApprovalSummary::from_entry(entry, process.id)));
+        }
+        let response = ListPendingApprovalsResponse {
+            count: approvals.len(),
+            approvals,
+        };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Wait until a task attempt's coding agent run finishes, asks for a tool approval, or the timeout passes, instead of polling 'get_task_attempt'. Sends progress notifications with the agent's latest message or todo list while waiting. Returns the run's status, the agent's final summary and diff stats. Answer a requested approval with 'respond_to_approval', then wait again. `attempt_id` is required!"
+    )]
+    async fn wait_for_attempt(&self, ctx: Context, request: WaitForAttemptRequest) -> McpResult<String> {
+        let deadline = Instant::now() + Duration::from_secs(request.timeout_seconds.unwrap_or(600).clamp(1, 3600));
+        let mut progress = ProgressReporter::default();
+        let (process, outcome, pending_approval) = loop {
+            let Some(process) = self.latest_coding_agent_process(request.attempt_id).await? else {
+                return Err(McpError::invalid_request(format!("Attempt {} has no coding agent run", request.attempt_id)));
+            };
+            let pending_approval = match process.status {
+                ExecutionProcessStatus::Queued => {
+                    progress.report(&ctx, "Waiting for a free slot to start the coding agent".to_string()).await;
+                    tokio::time::sleep_until(deadline.min(Instant::now() + Duration::from_secs(2))).await;
+                    None
+                }
+                ExecutionProcessStatus::Running => self.watch_agent_run(&ctx, process.id, deadline, &mut progress).await,
+                _ => None,
+            };
+            if let Some(outcome) = wait_outcome(&process.status, pending_approval.is_some(), deadline) {
+                break (process, outcome, pending_approval);
+            }
+        };
+
+        // The watched run may have ended since it was listed
+        let url = self.url(&format!("/api/execution-processes/{}", process.id));
+        let process: ExecutionProcess = self.send_json(self.client.get(&url)).await?;
+        let summary = if outcome == "finished" {
+            let url = self.url(&format!("/api/execution-processes/{}/coding-agent-turn", process.id));
+            let turn: Option<CodingAgentTurn> = self.send_json(self.client.get(&url)).await?;
+            turn.and_then(|t| t.summary)
+        } else {
+            None
+        };
+        let diff_stats = match self.attempt_diff_stats(request.attempt_id).await {
+            Ok(stats) => Some(stats),
+            Err(e) => {
+                tracing::warn!("Failed to read diff stats of attempt {}: {}", request.attempt_id, e);
+                None
+            }
+        };
+
+        let response = WaitForAttemptResponse {
+            attempt_id: request.attempt_id.to_string(),
+            outcome: outcome.to_string(),
+            process: ExecutionProcessSummary::from_execution_process(process),
+            summary,
+            pending_approval,
+            diff_stats,
+        };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Approve or deny a coding agent's pending tool call. Returns the resulting approval status. `approval_id`, `execution_process_id` and `approve` are required!"
+    )]
+    async fn respond_to_approval(&self, request: RespondToApprovalRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/approvals/{}/respond", request.approval_id));
+        let status = if request.approve {
+            serde_json::json!({ "status": "approved" })
+        } else {
+            serde_json::json!({ "status": "denied", "reason": request.reason })
+        };
+        let payload = serde_json::json!({
+            "execution_process_id": request.execution_process_id,
+            "status": status,
+        });
+
+        // This endpoint returns the bare approval status, not an ApiResponse
+        let resp = self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| Self::err_str("Failed to connect to VK API", Some(&e.to_string())))?;
+        if !resp.status().is_success() {
+            return Err(Self::err_str(
+                &format!("VK API returned error status: {}", resp.status()),
+                Some("the approval may have timed out or already been answered"),
+            ));
+        }
+        let status: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| Self::err_str("Failed to parse VK API response", Some(&e.to_string())))?;
+
+        Ok(serde_json::to_string_pretty(&serde_json::json!({
+            "approval_id": request.approval_id,
+            "status": status,
+        }))
+        .unwrap())
+    }
+
+    #[tool(
+        description = "List the sessions of a task attempt, oldest first. A session is one coding agent conversation; follow-ups continue it. `attempt_id` is required!"
+    )]
+    async fn list_sessions(&self, request: ListSessionsRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/sessions?workspace_id={}", request.attempt_id));
+        let sessions: Vec<Session> = self.send_json(self.client.get(&url)).await?;
+        let sessions: Vec<SessionSummary> = sessions.into_iter().map(SessionSummary::from_session).collect();
+        let response = ListSessionsResponse {
+            count: sessions.len(),
+            sessions,
+        };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Send follow-up instructions to the coding agent of a session, continuing its conversation in the same worktree. Fails while the agent is still running; use 'queue_follow_up' then. `session_id` and `prompt` are required!"
+    )]
+    async fn send_follow_up(&self, request: SendFollowUpRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/sessions/{}/follow-up", request.session_id));
+        let payload = serde_json::json!({
+            "prompt": request.prompt,
+            "variant": request.variant,
+        });
+        let process: ExecutionProcess = self.send_json(self.client.post(&url).json(&payload)).await?;
+        let response = SendFollowUpResponse {
+            session_id: request.session_id.to_string(),
+            process: ExecutionProcessSummary::from_execution_process(process),
+        };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Queue follow-up instructions that are sent automatically when the session's current coding agent run finishes. A session holds one queued follow-up; queuing again replaces it. `session_id` and `message` are required!"
+    )]
+    async fn queue_follow_up(&self, request: QueueFollowUpRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/sessions/{}/queue", request.session_id));
+        let payload = serde_json::json!({
+            "message": request.message,
+            "variant": request.variant,
+        });
+        let status: QueueStatus = self.send_json(self.client.post(&url).json(&payload)).await?;
+        let response = FollowUpQueueResponse::from_status(request.session_id, status);
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(description = "Get the follow-up queued for a session, if any. `session_id` is required!")]
+    async fn get_queued_follow_up(&self, request: SessionFollowUpQueueRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/sessions/{}/queue", request.session_id));
+        let status: QueueStatus = self.send_json(self.client.get(&url)).await?;
+        let response = FollowUpQueueResponse::from_status(request.session_id, status);
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(description = "Cancel the follow-up queued for a session before it is sent. `session_id` is required!")]
+    async fn cancel_queued_follow_up(&self, request: SessionFollowUpQueueRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/sessions/{}/queue", request.session_id));
+        let status: QueueStatus = self.send_json(self.client.delete(&url)).await?;
+        let response = FollowUpQueueResponse::from_status(request.session_id, status);
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "List tags: reusable snippets of text that expand in place of @tag_name in task descriptions and prompts."
+    )]
+    async fn list_tags(&self, request: ListTagsRequest) -> McpResult<String> {
+        let mut rb = self.client.get(&self.url("/api/tags"));
+        if let Some(search) = &request.search {
+            rb = rb.query(&[("search", search)]);
+        }
+        let tags: Vec<Tag> = self.send_json(rb).await?;
+        let tags: Vec<TagSummary> = tags.into_iter().map(TagSummary::from_tag).collect();
+        let response = ListTagsResponse {
+            count: tags.len(),
+            tags,
+        };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(description = "Create a tag. `tag_name` and `content` are required!")]
+    async fn create_tag(&self, request: CreateTagRequest) -> McpResult<String> {
+        let payload = serde_json::json!({
+            "tag_name": request.tag_name,
+            "content": request.content,
+        });
+        let tag: Tag = self.send_json(self.client.post(&self.url("/api/tags")).json(&payload)).await?;
+        Ok(serde_json::to_string_pretty(&TagSummary::from_tag(tag)).unwrap())
+    }
+
+    #[tool(description = "Rename a tag or change its content. Only provided fields are updated. `tag_id` is required!")]
+    async fn update_tag(&self, request: UpdateTagRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/tags/{}", request.tag_id));
+        let payload = serde_json::json!({
+            "tag_name": request.tag_name,
+            "content": request.content,
+        });
+        let tag: Tag = self.send_json(self.client.put(&url).json(&payload)).await?;
+        Ok(serde_json::to_string_pretty(&TagSummary::from_tag(tag)).unwrap())
+    }
+
+    #[tool(description = "Delete a tag. `tag_id` is required!")]
+    async fn delete_tag(&self, request: DeleteTagRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/tags/{}", request.tag_id));
+        self.send_no_data(self.client.delete(&url)).await?;
+        Ok(serde_json::json!({
+            "success": true,
+            "tag_id": request.tag_id.to_string(),
+        }).to_string())
+    }
+
+    #[tool(
+        description = "Attach an image, e.g. a screenshot or mockup, to a task. Attempts started afterwards get a copy in their worktree; put the returned `markdown` in the task description or a prompt so the agent looks at it. `task_id`, `filename` and base64 `data` are required!"
+    )]
+    async fn upload_task_image(&self, request: UploadTaskImageRequest) -> McpResult<String> {
+        let encoded: String = request
+            .data
+            .split_once(";base64,")
+            .map_or(request.data.as_str(), |(_, data)| data)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| McpError::invalid_request(format!("Image data is not valid base64: {}", e)))?;
+
+        let url = self.url(&format!("/api/images/task/{}/upload", request.task_id));
+        let part = reqwest::multipart::Part::bytes(bytes).file_name(request.filename);
+        let form = reqwest::multipart::Form::new().part("image", part);
+        let image: ImageResponse = self.send_json(self.client.post(&url).multipart(form)).await?;
+        Ok(serde_json::to_string_pretty(&ImageSummary::from_image(image)).unwrap())
+    }
+
+    #[tool(description = "List the images attached to a task. `task_id` is required!")]
+    async fn list_task_images(&self, request: ListTaskImagesRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/images/task/{}", request.task_id));
+        let images: Vec<ImageResponse> = self.send_json(self.client.get(&url)).await?;
+        let images: Vec<ImageSummary> = images.into_iter().map(ImageSummary::from_image).collect();
+        let response = ListTaskImagesResponse {
+            count: images.len(),
+            images,
+        };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(description = "Delete an image and detach it from its tasks. `image_id` is required!")]
+    async fn delete_image(&self, request: DeleteImageRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/images/{}", request.image_id));
+        self.send_no_data(self.client.delete(&url)).await?;
+        Ok(serde_json::json!({
+            "success": true,
+            "image_id": request.image_id.to_string(),
+        }).to_string())
+    }
+
+    #[tool(
+        description = "Start a development server for a task attempt. This will execute the project's dev script (e.g., 'npm run dev') in the attempt's worktree. Each attempt runs its own dev server on its own `PORT`, previewed at `/preview/{attempt_id}/` on Vibe Kanban's separate preview port; the response includes the port and the full `preview_url`. Starting a new one only replaces the attempt's existing dev server. `attempt_id` is required!"
     )]
     async fn start_dev_server(&self, request: StartDevServerRequest) -> McpResult<String> {
         let url = self.url(&format!("/api/task-attempts/{}/start-dev-server", request.attempt_id));
@@ -2029,15 +3492,56 @@ impl TaskServer {
         // POST to start-dev-server endpoint returns ApiResponse<()>
         self.send_json::<serde_json::Value>(self.client.post(&url)).await?;
 
+        // The ports are reserved when the dev server starts
+        let url = self.url(&format!("/api/task-attempts/{}/dev-server", request.attempt_id));
+        let info: Option<DevServerInfo> = self.send_json(self.client.get(&url)).await?;
+
         let response = StartDevServerResponse {
             success: true,
             message: "Development server started successfully".to_string(),
             attempt_id: request.attempt_id.to_string(),
+            port: info.as_ref().map(|i| i.port),
+            preview_port: info.as_ref().and_then(|i| i.preview_port),
+            preview_url: info.as_ref().and_then(|i| preview_url(&self.base_url, i)),
         };
 
         Ok(serde_json::to_string_pretty(&response).unwrap())
     }
 
+    #[tool(
+        description = "List the named scripts of a project (e.g. 'test', 'lint', 'db:migrate') with their dependencies and pipelines. `project_id` is required!"
+    )]
+    async fn list_scripts(&self, request: ListScriptsRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/projects/{}/scripts", request.project_id));
+        let scripts: Vec<ProjectScript> = self.send_json(self.client.get(&url)).await?;
+        let scripts: Vec<ScriptSummary> =
+            scripts.into_iter().map(ScriptSummary::from_script).collect();
+        let response = ListScriptsResponse {
+            count: scripts.len(),
+            scripts,
+        };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Run a named project script in a task attempt's worktree, after the scripts it depends on, or a whole 'setup', 'cleanup' or 'verification' pipeline. Each step runs as its own execution process; follow them with list_execution_processes. `attempt_id` and either `name` or `pipeline` are required!"
+    )]
+    async fn run_script(&self, request: RunScriptRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/task-attempts/{}/scripts/run", request.attempt_id));
+        let payload = serde_json::json!({
+            "name": request.name,
+            "pipeline": request.pipeline,
+        });
+        let process: ExecutionProcess =
+            self.send_json(self.client.post(&url).json(&payload)).await?;
+        let response = RunScriptResponse {
+            attempt_id: request.attempt_id.to_string(),
+            execution_process_id: process.id.to_string(),
+            status: format!("{:?}", process.status).to_lowercase(),
+        };
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
     #[tool(
         description = "Get the git branch synchronization status for a task attempt. Shows how many commits the attempt branch is ahead/behind the target branch, uncommitted changes, conflict status, and remote sync information if a PR is open. Useful for understanding if a branch needs rebasing or is ready to merge. `attempt_id` is required!"
     )]
@@ -2210,7 +3714,275 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "Push a task attempt's branch to the remote GitHub repository. This validates GitHub authentication, ensures the worktree is clean, and pushes all commits to remote. Use this before creating a pull request. `attempt_id` is required!"
+        description = "Resolve the merge conflicts of a stopped rebase/merge with a coding agent. Starts a follow-up in the conflicted worktree with the base/ours/theirs versions of each file, then continues the operation once the conflict markers are gone and verifies it with the repo's test script. Rolls back (aborts) on failure. Poll `get_conflict_resolution` for the outcome. `attempt_id` and `repo_id` are required!"
+    )]
+    async fn resolve_conflicts(&self, request: ResolveConflictsRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/task-attempts/{}/conflicts/resolve", request.attempt_id));
+
+        #[derive(Serialize)]
+        struct Payload {
+            repo_id: Uuid,
+        }
+
+        // Define response structure that matches the API's error-with-data pattern
+        #[derive(Debug, Deserialize)]
+        struct ApiResolveResponse {
+            success: bool,
+            data: Option<serde_json::Value>,
+            error_data: Option<serde_json::Value>,
+            message: Option<String>,
+        }
+
+        let resp = self.client.post(&url).json(&Payload { repo_id: request.repo_id }).send().await
+            .map_err(|e| Self::err_str("Failed to connect to VK API", Some(&e.to_string())))?;
+        if !resp.status().is_success() {
+            return Err(Self::err_str(&format!("VK API returned error status: {}", resp.status()), None));
+        }
+        let api_response = resp.json::<ApiResolveResponse>().await
+            .map_err(|e| Self::err_str("Failed to parse VK API response", Some(&e.to_string())))?;
+
+        let response = if api_response.success {
+            let resolution: ConflictResolution = api_response
+                .data
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| Self::err_str("Failed to parse conflict resolution", Some(&e.to_string())))?
+                .ok_or_else(|| Self::err_str("VK API response missing data field", None))?;
+            ResolveConflictsResponse {
+                success: true,
+                message: "Coding agent started resolving conflicts".to_string(),
+                attempt_id: request.attempt_id.to_string(),
+                operation: Some(format!("{:?}", resolution.op)),
+                execution_process_id: resolution.execution_process_id.map(|id| id.to_string()),
+                conflicted_files: resolution.files,
+            }
+        } else {
+            let reason = api_response
+                .error_data
+                .as_ref()
+                .and_then(|d| d.get("type"))
+                .and_then(|t| t.as_str())
+                .map(|t| match t {
+                    "no_conflicts" => "No conflicted rebase or merge in progress for this repo".to_string(),
+                    "resolution_in_progress" => "A conflict resolution is already running for this attempt".to_string(),
+                    "process_already_running" => "Another execution process is running; wait for it or stop it first".to_string(),
+                    other => other.to_string(),
+                })
+                .or(api_response.message)
+                .unwrap_or_else(|| "Unknown error".to_string());
+            ResolveConflictsResponse {
+                success: false,
+                message: reason,
+                attempt_id: request.attempt_id.to_string(),
+                operation: None,
+                execution_process_id: None,
+                conflicted_files: vec![],
+            }
+        };
+
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Get the state of the latest agent-assisted conflict resolution for a task attempt (resolving, verifying, resolved or aborted) with its outcome message and test output. `attempt_id` is required!"
+    )]
+    async fn get_conflict_resolution(&self, request: GetConflictResolutionRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/task-attempts/{}/conflicts/resolution", request.attempt_id));
+        // `data` is null when no resolution was started, so parse the envelope directly
+        let resp = self.client.get(&url).send().await
+            .map_err(|e| Self::err_str("Failed to connect to VK API", Some(&e.to_string())))?;
+        if !resp.status().is_success() {
+            return Err(Self::err_str(&format!("VK API returned error status: {}", resp.status()), None));
+        }
+        let api_response = resp.json::<ApiResponseEnvelope<ConflictResolution>>().await
+            .map_err(|e| Self::err_str("Failed to parse VK API response", Some(&e.to_string())))?;
+        if !api_response.success {
+            let msg = api_response.message.as_deref().unwrap_or("Unknown error");
+            return Err(Self::err_str("VK API returned error", Some(msg)));
+        }
+        let resolution = api_response.data;
+
+        let response = match resolution {
+            Some(r) => GetConflictResolutionResponse {
+                attempt_id: request.attempt_id.to_string(),
+                state: serde_json::to_value(r.state).ok().and_then(|v| v.as_str().map(str::to_string)),
+                round: Some(r.round),
+                message: r.message,
+                test_output: r.test_output,
+            },
+            None => GetConflictResolutionResponse {
+                attempt_id: request.attempt_id.to_string(),
+                state: None,
+                round: None,
+                message: None,
+                test_output: None,
+            },
+        };
+
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Get the pull request state and CI check results (refreshed every minute while the PR is open) for each repository of a task attempt. `attempt_id` is required!"
+    )]
+    async fn get_pr_status(&self, request: GetPrStatusRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/task-attempts/{}/branch-status", request.attempt_id));
+
+        #[derive(Debug, Deserialize)]
+        struct ApiRepoBranchStatus {
+            repo_id: Uuid,
+            repo_name: String,
+            merges: Vec<Merge>,
+        }
+
+        let repos: Vec<ApiRepoBranchStatus> = self.send_json(self.client.get(&url)).await?;
+        let as_str = |value: serde_json::Value| value.as_str().map(str::to_string).unwrap_or_default();
+
+        let pull_requests = repos
+            .into_iter()
+            .filter_map(|repo| {
+                // Merges are sorted newest first
+                let pr = repo.merges.into_iter().find_map(|merge| match merge {
+                    Merge::Pr(pr) => Some(pr),
+                    Merge::Direct(_) => None,
+                })?;
+                let ci = pr.ci;
+                Some(PrStatusSummary {
+                    repo_id: repo.repo_id.to_string(),
+                    repo_name: repo.repo_name,
+                    pr_number: pr.pr_info.number,
+                    pr_url: pr.pr_info.url,
+                    pr_status: as_str(serde_json::to_value(&pr.pr_info.status).unwrap_or_default()),
+                    ci_status: ci.as_ref().map(|ci| as_str(serde_json::to_value(ci.status).unwrap_or_default())),
+                    ci_head_sha: ci.as_ref().map(|ci| ci.head_sha.clone()),
+                    ci_updated_at: ci.as_ref().map(|ci| ci.updated_at.to_rfc3339()),
+                    checks: ci
+                        .map(|ci| {
+                            ci.checks
+                                .into_iter()
+                                .map(|check| PrCheckSummary {
+                                    name: check.name,
+                                    status: as_str(serde_json::to_value(check.status).unwrap_or_default()),
+                                    url: check.url,
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                })
+            })
+            .collect();
+
+        let response = GetPrStatusResponse {
+            attempt_id: request.attempt_id.to_string(),
+            pull_requests,
+        };
+
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "List the inline review comments left on a task attempt's diff. Unresolved comments are re-anchored to the latest commit first, so line numbers are current; comments whose lines were changed are marked `outdated`. `attempt_id` is required!"
+    )]
+    async fn list_review_comments(&self, request: ListReviewCommentsRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/task-attempts/{}/review-comments", request.attempt_id));
+        let comments: Vec<ReviewCommentWithRepo> = self.send_json(self.client.get(&url)).await?;
+        let include_resolved = request.include_resolved.unwrap_or(false);
+        let comments: Vec<ReviewCommentSummary> = comments
+            .into_iter()
+            .filter(|c| include_resolved || c.comment.resolved_at.is_none())
+            .map(ReviewCommentSummary::from)
+            .collect();
+
+        let response = ListReviewCommentsResponse {
+            attempt_id: request.attempt_id.to_string(),
+            count: comments.len(),
+            comments,
+        };
+
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Leave a review comment on a line range of a task attempt's diff, like a pull request line comment. Comments are collected until `send_review` hands them to the coding agent. `attempt_id`, `file_path`, `line_start` and `body` are required!"
+    )]
+    async fn create_review_comment(&self, request: CreateReviewCommentRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/task-attempts/{}/review-comments", request.attempt_id));
+        let payload = serde_json::json!({
+            "repo_id": request.repo_id,
+            "file_path": request.file_path,
+            "side": request.side.as_deref().unwrap_or("new"),
+            "line_start": request.line_start,
+            "line_end": request.line_end,
+            "body": request.body,
+            "execution_process_id": request.execution_process_id,
+        });
+        let comment: ReviewCommentWithRepo = self.send_json(self.client.post(&url).json(&payload)).await?;
+
+        Ok(serde_json::to_string_pretty(&ReviewCommentSummary::from(comment)).unwrap())
+    }
+
+    #[tool(
+        description = "Resolve a review comment on a task attempt's diff, or reopen it with `resolved: false`. `attempt_id` and `comment_id` are required!"
+    )]
+    async fn resolve_review_comment(&self, request: ResolveReviewCommentRequest) -> McpResult<String> {
+        let url = self.url(&format!(
+            "/api/task-attempts/{}/review-comments/{}/resolve",
+            request.attempt_id, request.comment_id
+        ));
+        let resolved = request.resolved.unwrap_or(true);
+        self.send_json::<serde_json::Value>(self.client.post(&url).json(&serde_json::json!({ "resolved": resolved })))
+            .await?;
+
+        let response = ResolveReviewCommentResponse {
+            success: true,
+            comment_id: request.comment_id.to_string(),
+            resolved,
+        };
+
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Send all unresolved review comments of a task attempt that were not sent yet to its coding agent as one structured follow-up prompt. If the agent is busy, the review is queued as its next follow-up. `attempt_id` is required!"
+    )]
+    async fn send_review(&self, request: SendReviewRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/task-attempts/{}/review-comments/send", request.attempt_id));
+        let sent: SendReviewResponse = self.send_json(self.client.post(&url).json(&serde_json::json!({ "message": request.message }))).await?;
+
+        let response = SendReviewToolResponse {
+            sent: sent.sent,
+            queued: sent.queued,
+            execution_process_id: sent.execution_process_id.map(|id| id.to_string()),
+        };
+
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Check out more directories in a task attempt's sparse-checkout worktrees. Use this when the attempt needs files outside the directories it was created with. Returns the checked out directories per repository. `attempt_id` and `paths` are required!"
+    )]
+    async fn expand_sparse_checkout(&self, request: ExpandSparseCheckoutRequest) -> McpResult<String> {
+        let url = self.url(&format!("/api/task-attempts/{}/sparse-checkout", request.attempt_id));
+        let payload = serde_json::json!({ "repo_id": request.repo_id, "paths": request.paths });
+        let repos: Vec<RepoSparseCheckout> = self.send_json(self.client.post(&url).json(&payload)).await?;
+
+        let response = ExpandSparseCheckoutResponse {
+            attempt_id: request.attempt_id.to_string(),
+            repos: repos
+                .into_iter()
+                .map(|r| SparseCheckoutSummary {
+                    repo_id: r.repo_id.to_string(),
+                    repo_name: r.repo_name,
+                    paths: r.paths,
+                })
+                .collect(),
+        };
+
+        Ok(serde_json::to_string_pretty(&response).unwrap())
+    }
+
+    #[tool(
+        description = "Push a task attempt's branch to the remote repository. For GitHub remotes this validates GitHub CLI authentication first. Pushes all commits to remote. Use this before creating a pull request. `attempt_id` is required!"
     )]
     async fn push_attempt_branch(&self, request: PushAttemptBranchRequest) -> McpResult<String> {
         let url = self.url(&format!("/api/task-attempts/{}/push", request.attempt_id));
@@ -2286,4 +4058,146 @@ impl TaskServer {
         self.run_http_with_config(addr, config).await?;
         Ok(())
     }
+
+    /// The `/mcp` endpoint as a router, for serving behind the gateway
+    #[cfg(feature = "http")]
+    pub fn http_router(&self) -> axum::Router {
+        self.clone().into_router_with_path("/mcp")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn tool_use(status: serde_json::Value) -> serde_json::Value {
+        json!({
+            "entry_type": {
+                "type": "tool_use",
+                "tool_name": "Bash",
+                "action_type": { "action": "command_run", "command": "rm -rf target" },
+                "status": status,
+            },
+            "content": "rm -rf target",
+        })
+    }
+
+    fn conversation(entries: Vec<serde_json::Value>) -> BTreeMap<usize, serde_json::Value> {
+        let ops: Vec<_> = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let value = json!({ "type": "NORMALIZED_ENTRY", "content": entry });
+                json!({ "op": "add", "path": format!("/entries/{}", i), "value": value })
+            })
+            .collect();
+        let mut entries = BTreeMap::new();
+        apply_conversation_patch(&json!(ops), &mut entries);
+        entries
+    }
+
+    #[test]
+    fn finds_the_pending_approval_of_a_conversation() {
+        let process_id = Uuid::new_v4();
+        let entries = conversation(vec![
+            json!({ "entry_type": { "type": "assistant_message" }, "content": "Cleaning up" }),
+            tool_use(json!({ "status": "approved" })),
+            tool_use(json!({
+                "status": "pending_approval",
+                "approval_id": "approval-1",
+                "timeout_at": "2026-01-01T00:00:00Z",
+            })),
+        ]);
+
+        let approval = entries
+            .values()
+            .find_map(|e| PendingApprovalSummary::from_entry(e, process_id))
+            .unwrap();
+        assert_eq!(approval.approval_id, "approval-1");
+        assert_eq!(approval.execution_process_id, process_id.to_string());
+        assert_eq!(approval.tool_name, "Bash");
+        assert_eq!(approval.content, "rm -rf target");
+        assert_eq!(approval.action["command"], "rm -rf target");
+        assert_eq!(approval.timeout_at.as_deref(), Some("2026-01-01T00:00:00Z"));
+
+        let answered = conversation(vec![tool_use(json!({ "status": "denied", "reason": "no" }))]);
+        assert!(answered.values().all(|e| PendingApprovalSummary::from_entry(e, process_id).is_none()));
+    }
+
+    #[test]
+    fn progress_is_the_latest_message_or_todo_list() {
+        let todos = json!({
+            "entry_type": {
+                "type": "tool_use",
+                "tool_name": "TodoWrite",
+                "action_type": {
+                    "action": "todo_management",
+                    "todos": [
+                        { "content": "Write the parser", "status": "completed" },
+                        { "content": "Test the parser", "status": "in_progress" },
+                    ],
+                },
+                "status": { "status": "success" },
+            },
+            "content": "",
+        });
+        let entries = conversation(vec![
+            json!({ "entry_type": { "type": "assistant_message" }, "content": "Starting" }),
+            todos,
+            tool_use(json!({ "status": "success" })),
+        ]);
+
+        let message = entries.values().rev().find_map(progress_message);
+        assert_eq!(message.as_deref(), Some("[completed] Write the parser\n[in_progress] Test the parser"));
+
+        let user = json!({ "entry_type": { "type": "user_message" }, "content": "Hi" });
+        assert_eq!(progress_message(&user), None);
+    }
+
+    #[test]
+    fn repeated_progress_messages_are_skipped() {
+        let mut progress = ProgressReporter::default();
+        assert_eq!(progress.next("Starting"), Some(1));
+        assert_eq!(progress.next("Starting"), None);
+        assert_eq!(progress.next("[in_progress] Test the parser"), Some(2));
+        assert_eq!(progress.next("Starting"), Some(3));
+    }
+
+    #[test]
+    fn waiting_times_out_only_while_the_run_is_active() {
+        let later = Instant::now() + Duration::from_secs(60);
+        let passed = Instant::now();
+
+        assert_eq!(wait_outcome(&ExecutionProcessStatus::Running, false, later), None);
+        assert_eq!(wait_outcome(&ExecutionProcessStatus::Queued, false, later), None);
+        assert_eq!(wait_outcome(&ExecutionProcessStatus::Running, false, passed), Some("timed_out"));
+        assert_eq!(wait_outcome(&ExecutionProcessStatus::Queued, false, passed), Some("timed_out"));
+        assert_eq!(wait_outcome(&ExecutionProcessStatus::Completed, false, passed), Some("finished"));
+        assert_eq!(wait_outcome(&ExecutionProcessStatus::Running, true, passed), Some("approval_requested"));
+    }
+    #[test]
+    fn preview_url_uses_the_preview_port() {
+        let workspace_id = Uuid::new_v4();
+        let mut info = DevServerInfo {
+            workspace_id,
+            execution_process_id: Uuid::new_v4(),
+            port: 5173,
+            ports: BTreeMap::from([("PORT".to_string(), 5173)]),
+            ready: true,
+            preview_path: format!("/preview/{workspace_id}/"),
+            preview_port: Some(4100),
+        };
+        assert_eq!(
+            preview_url("http://127.0.0.1:3000", &info),
+            Some(format!("http://127.0.0.1:4100/preview/{workspace_id}/"))
+        );
+
+        info.preview_port = None;
+        assert_eq!(
+            preview_url("http://127.0.0.1:3000/", &info),
+            Some(format!("http://127.0.0.1:3000/preview/{workspace_id}/"))
+        );
+    }
 }
@@ -0,0 +1,141 @@
+use axum::{
+    Extension,
+    extract::{Request, State},
+    http::{HeaderMap, Method, StatusCode, header},
+    middleware::Next,
+    response::Response,
+};
+use deployment::Deployment;
+use services::services::api_auth::{ApiAuth, TOKEN_COOKIE, is_public_path, required_scope};
+
+use crate::DeploymentImpl;
+
+/// Token of a request: an `Authorization: Bearer` header, or the session
+/// cookie set by the login endpoint (browsers cannot set headers on
+/// websocket and SSE connections).
+pub fn request_token(headers: &HeaderMap) -> Option<String> {
+    if let Some(token) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    {
+        return Some(token.trim().to_string());
+    }
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .find_map(|cookie| {
+            let (name, value) = cookie.trim().split_once('=')?;
+            (name == TOKEN_COOKIE && !value.is_empty()).then(|| value.to_string())
+        })
+}
+
+/// Whether a browser sent the request from a page of another origin. Previews
+/// are served from another port of the same host, which browsers count as the
+/// same site, so `SameSite` doesn't keep the session cookie off requests that
+/// agent-written pages send to the API. Clients outside a browser send
+/// neither header.
+fn is_cross_origin(headers: &HeaderMap) -> bool {
+    if let Some(site) = headers.get("sec-fetch-site").and_then(|v| v.to_str().ok()) {
+        return !matches!(site, "same-origin" | "none");
+    }
+    let Some(origin) = headers.get(header::ORIGIN).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    let host = headers.get(header::HOST).and_then(|v| v.to_str().ok());
+    origin.split_once("://").map(|(_, authority)| authority) != host
+}
+
+/// Reject `/api` requests without a token allowed to make them, and those
+/// from other origins that change something or open a websocket. REST calls,
+/// websocket upgrades and SSE streams all pass through here.
+pub async fn require_api_token(
+    State(deployment): State<DeploymentImpl>,
+    Extension(auth): Extension<ApiAuth>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let path = request.uri().path().to_string();
+    let is_read = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    // Checked with auth off too, where no cookie is needed at all
+    let changes_or_streams = !is_read || request.headers().contains_key(header::UPGRADE);
+    if path.starts_with("/api/") && changes_or_streams && is_cross_origin(request.headers()) {
+        tracing::warn!("Rejected cross-origin {} {}", request.method(), path);
+        return Err(StatusCode::FORBIDDEN);
+    }
+    if !auth.is_enabled() || is_public_path(&path) {
+        return Ok(next.run(request).await);
+    }
+
+    let Some(token) = request_token(request.headers()) else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+    let grant = match auth.authenticate(&deployment.db().pool, &token).await {
+        Ok(Some(grant)) => grant,
+        Ok(None) => return Err(StatusCode::UNAUTHORIZED),
+        Err(e) => {
+            tracing::error!("Failed to look up API token: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let scope = required_scope(&path, is_read);
+    if !grant.allows(scope) {
+        tracing::warn!("API token lacks the {:?} scope for {}", scope, path);
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    request.extensions_mut().insert(grant);
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn preview_pages_are_cross_origin() {
+        assert!(!is_cross_origin(&headers(&[(
+            "sec-fetch-site",
+            "same-origin"
+        )])));
+        assert!(is_cross_origin(&headers(&[(
+            "sec-fetch-site",
+            "same-site"
+        )])));
+        assert!(is_cross_origin(&headers(&[(
+            "sec-fetch-site",
+            "cross-site"
+        )])));
+
+        // Without Fetch Metadata, the origin has to match the host
+        let api = ("host", "127.0.0.1:3001");
+        assert!(!is_cross_origin(&headers(&[
+            api,
+            ("origin", "http://127.0.0.1:3001")
+        ])));
+        assert!(is_cross_origin(&headers(&[
+            api,
+            ("origin", "http://127.0.0.1:4002")
+        ])));
+        assert!(is_cross_origin(&headers(&[api, ("origin", "null")])));
+
+        // CLI and MCP clients
+        assert!(!is_cross_origin(&headers(&[api])));
+    }
+}
@@ -18,10 +18,14 @@ use executors::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use services::services::config::{
-    Config, ConfigError, SoundFile,
-    editor::{EditorConfig, EditorType},
-    save_config_to_file,
+use services::services::{
+    config::{
+        Config, ConfigError, SoundFile,
+        editor::{EditorConfig, EditorType},
+        save_config_to_file,
+    },
+    container::ContainerService,
+    git_host::{move_tokens_to_secrets, validate_token_secret},
 };
 use tokio::fs;
 use ts_rs::TS;
@@ -86,11 +90,16 @@ pub struct UserSystemInfo {
 async fn get_user_system_info(
     State(deployment): State<DeploymentImpl>,
 ) -> ResponseJson<ApiResponse<UserSystemInfo>> {
-    let config = deployment.config().read().await;
+    let mut config = deployment.config().read().await.clone();
     let login_status = deployment.get_login_status().await;
 
+    // Tokens that could not be moved to the secrets store stay server-side
+    for host in &mut config.git_hosts {
+        host.token = None;
+    }
+
     let user_system_info = UserSystemInfo {
-        config: config.clone(),
+        config,
         analytics_user_id: deployment.user_id().to_string(),
         login_status,
         profiles: ExecutorConfigs::get_cached(),
@@ -112,7 +121,7 @@ async fn get_user_system_info(
 
 async fn update_config(
     State(deployment): State<DeploymentImpl>,
-    Json(new_config): Json<Config>,
+    Json(mut new_config): Json<Config>,
 ) -> ResponseJson<ApiResponse<Config>> {
     let config_path = config_path();
 
@@ -123,6 +132,24 @@ async fn update_config(
         ));
     }
 
+    if let Some(Err(e)) = new_config
+        .git_hosts
+        .iter()
+        .filter_map(|host| host.token_secret.as_deref())
+        .map(validate_token_secret)
+        .find(Result::is_err)
+    {
+        return ResponseJson(ApiResponse::error(&e.to_string()));
+    }
+
+    // Git host tokens belong in the secrets store, not the config file
+    move_tokens_to_secrets(
+        &mut new_config.git_hosts,
+        deployment.container().secrets(),
+        &deployment.db().pool,
+    )
+    .await;
+
     // Get old config state before updating
     let old_config = deployment.config().read().await.clone();
 
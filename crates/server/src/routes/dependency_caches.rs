@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{delete, get, post},
+};
+use db::models::repo::Repo;
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::{
+    container::ContainerService,
+    dependency_cache::{DependencyCacheEntry, PruneDependencyCaches, PruneDependencyCachesResult},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// A stored dependency cache with the name of its repository
+#[derive(Debug, Serialize, TS)]
+pub struct DependencyCacheInfo {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub entry: DependencyCacheEntry,
+    /// `None` once the repository was removed
+    pub repo_name: Option<String>,
+}
+
+pub async fn list_dependency_caches(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DependencyCacheInfo>>>, ApiError> {
+    let entries = deployment.container().dependency_caches().list().await?;
+
+    let mut names: HashMap<_, Option<String>> = HashMap::new();
+    let mut caches = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if !names.contains_key(&entry.repo_id) {
+            let repo = Repo::find_by_id(&deployment.db().pool, entry.repo_id).await?;
+            names.insert(entry.repo_id, repo.map(|repo| repo.display_name));
+        }
+        caches.push(DependencyCacheInfo {
+            repo_name: names[&entry.repo_id].clone(),
+            entry,
+        });
+    }
+    Ok(ResponseJson(ApiResponse::success(caches)))
+}
+
+pub async fn prune_dependency_caches(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<PruneDependencyCaches>,
+) -> Result<ResponseJson<ApiResponse<PruneDependencyCachesResult>>, ApiError> {
+    let result = deployment
+        .container()
+        .dependency_caches()
+        .prune(payload)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "dependency_caches_pruned",
+            serde_json::json!({
+                "removed": result.removed.len(),
+                "freed_bytes": result.freed_bytes,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
+pub async fn delete_dependency_cache(
+    State(deployment): State<DeploymentImpl>,
+    Path(cache_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    deployment
+        .container()
+        .dependency_caches()
+        .delete(&cache_id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/dependency-caches", get(list_dependency_caches))
+        .route("/dependency-caches/prune", post(prune_dependency_caches))
+        .route(
+            "/dependency-caches/{cache_id}",
+            delete(delete_dependency_cache),
+        )
+}
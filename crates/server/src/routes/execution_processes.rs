@@ -10,6 +10,7 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus},
     execution_process_repo_state::ExecutionProcessRepoState,
 };
@@ -17,7 +18,7 @@ use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::container::ContainerService;
-use utils::{log_msg::LogMsg, response::ApiResponse};
+use utils::{log_msg::LogMsg, metrics, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware};
@@ -52,6 +53,7 @@ pub async fn stream_raw_logs_ws(
         })?;
 
     Ok(ws.on_upgrade(move |socket| async move {
+        let _subscriber = metrics::WEBSOCKET_SUBSCRIBERS.track(&["raw_logs"]);
         if let Err(e) = handle_raw_logs_ws(socket, deployment, exec_id).await {
             tracing::warn!("raw logs WS closed: {}", e);
         }
@@ -137,6 +139,7 @@ pub async fn stream_normalized_logs_ws(
     let stream = stream.err_into::<anyhow::Error>().into_stream();
 
     Ok(ws.on_upgrade(move |socket| async move {
+        let _subscriber = metrics::WEBSOCKET_SUBSCRIBERS.track(&["normalized_logs"]);
         if let Err(e) = handle_normalized_logs_ws(socket, stream).await {
             tracing::warn!("normalized logs WS closed: {}", e);
         }
@@ -170,10 +173,17 @@ pub async fn stop_execution_process(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
-    deployment
-        .container()
-        .stop_execution(&execution_process, ExecutionProcessStatus::Killed)
-        .await?;
+    if execution_process.status == ExecutionProcessStatus::Queued {
+        deployment
+            .container()
+            .cancel_queued_execution(execution_process.id)
+            .await?;
+    } else {
+        deployment
+            .container()
+            .stop_execution(&execution_process, ExecutionProcessStatus::Killed)
+            .await?;
+    }
 
     Ok(ResponseJson(ApiResponse::success(())))
 }
@@ -184,6 +194,7 @@ pub async fn stream_execution_processes_ws(
     Query(query): Query<ExecutionProcessQuery>,
 ) -> impl IntoResponse {
     ws.on_upgrade(move |socket| async move {
+        let _subscriber = metrics::WEBSOCKET_SUBSCRIBERS.track(&["execution_processes"]);
         if let Err(e) = handle_execution_processes_ws(
             socket,
             deployment,
@@ -243,11 +254,27 @@ pub async fn get_execution_process_repo_states(
     Ok(ResponseJson(ApiResponse::success(repo_states)))
 }
 
+/// The agent turn of a coding agent process, with its final summary once the
+/// process has finished
+pub async fn get_execution_process_coding_agent_turn(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<CodingAgentTurn>>>, ApiError> {
+    let turn =
+        CodingAgentTurn::find_by_execution_process_id(&deployment.db().pool, execution_process.id)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(turn)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let workspace_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
         .route("/repo-states", get(get_execution_process_repo_states))
+        .route(
+            "/coding-agent-turn",
+            get(get_execution_process_coding_agent_turn),
+        )
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
@@ -0,0 +1,65 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::execution_queue::{QueuedExecution, UpdateQueuedExecution};
+use deployment::Deployment;
+use services::services::container::ContainerService;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Agent runs waiting for a slot, in the order they will start
+pub async fn list_queue(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueuedExecution>>>, ApiError> {
+    let queue = QueuedExecution::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(queue)))
+}
+
+pub async fn update_queued_execution(
+    State(deployment): State<DeploymentImpl>,
+    Path(execution_process_id): Path<Uuid>,
+    Json(payload): Json<UpdateQueuedExecution>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueuedExecution>>>, ApiError> {
+    let found = deployment
+        .container()
+        .scheduler()
+        .reorder(execution_process_id, &payload)
+        .await?;
+    if !found {
+        return Err(ApiError::BadRequest(
+            "Execution process is not queued".to_string(),
+        ));
+    }
+    let queue = QueuedExecution::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(queue)))
+}
+
+pub async fn cancel_queued_execution(
+    State(deployment): State<DeploymentImpl>,
+    Path(execution_process_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let found = deployment
+        .container()
+        .cancel_queued_execution(execution_process_id)
+        .await?;
+    if !found {
+        return Err(ApiError::BadRequest(
+            "Execution process is not queued".to_string(),
+        ));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/execution-queue", get(list_queue))
+        .route(
+            "/execution-queue/{execution_process_id}",
+            put(update_queued_execution).delete(cancel_queued_execution),
+        )
+}
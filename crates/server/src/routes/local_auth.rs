@@ -0,0 +1,169 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, header},
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::{delete, get, post},
+};
+use db::models::api_token::{ApiToken, ApiTokenScope};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::api_auth::{
+    ApiAuth, ApiGrant, TOKEN_COOKIE, generate_token, hash_token, token_display_prefix,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::request_token};
+
+/// Browser sessions last a year; revoking the token ends them earlier
+const SESSION_COOKIE_MAX_AGE_SECS: u64 = 365 * 24 * 60 * 60;
+
+#[derive(Debug, Serialize, TS)]
+pub struct LocalAuthStatus {
+    /// The server requires a token
+    pub enabled: bool,
+    /// The request carried a valid token (always true when auth is disabled)
+    pub authenticated: bool,
+    pub scopes: Vec<ApiTokenScope>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct LocalLoginRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    pub scopes: Vec<ApiTokenScope>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct CreateApiTokenResponse {
+    pub api_token: ApiToken,
+    /// The token itself; it cannot be retrieved again
+    pub token: String,
+}
+
+fn session_cookie(value: &str, max_age: u64) -> HeaderValue {
+    HeaderValue::from_str(&format!(
+        "{TOKEN_COOKIE}={value}; Path=/; HttpOnly; SameSite=Lax; Max-Age={max_age}"
+    ))
+    .expect("token cookie is a valid header value")
+}
+
+pub async fn get_status(
+    State(deployment): State<DeploymentImpl>,
+    Extension(auth): Extension<ApiAuth>,
+    headers: HeaderMap,
+) -> Result<ResponseJson<ApiResponse<LocalAuthStatus>>, ApiError> {
+    if !auth.is_enabled() {
+        return Ok(ResponseJson(ApiResponse::success(LocalAuthStatus {
+            enabled: false,
+            authenticated: true,
+            scopes: ApiGrant::Admin.scopes(),
+        })));
+    }
+    let grant = match request_token(&headers) {
+        Some(token) => auth.authenticate(&deployment.db().pool, &token).await?,
+        None => None,
+    };
+    Ok(ResponseJson(ApiResponse::success(LocalAuthStatus {
+        enabled: true,
+        authenticated: grant.is_some(),
+        scopes: grant.map(|g| g.scopes()).unwrap_or_default(),
+    })))
+}
+
+/// Check a token and keep it in a cookie for the browser session
+pub async fn login(
+    State(deployment): State<DeploymentImpl>,
+    Extension(auth): Extension<ApiAuth>,
+    Json(payload): Json<LocalLoginRequest>,
+) -> Result<Response, ApiError> {
+    let token = payload.token.trim();
+    if !auth.is_enabled() {
+        return Ok(ResponseJson(ApiResponse::<()>::success(())).into_response());
+    }
+    if auth
+        .authenticate(&deployment.db().pool, token)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::Unauthorized);
+    }
+    let mut response = ResponseJson(ApiResponse::<()>::success(())).into_response();
+    response.headers_mut().insert(
+        header::SET_COOKIE,
+        session_cookie(token, SESSION_COOKIE_MAX_AGE_SECS),
+    );
+    Ok(response)
+}
+
+pub async fn logout() -> Response {
+    let mut response = ResponseJson(ApiResponse::<()>::success(())).into_response();
+    response
+        .headers_mut()
+        .insert(header::SET_COOKIE, session_cookie("", 0));
+    response
+}
+
+pub async fn list_api_tokens(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApiToken>>>, ApiError> {
+    let tokens = ApiToken::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(tokens)))
+}
+
+pub async fn create_api_token(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateApiTokenRequest>,
+) -> Result<ResponseJson<ApiResponse<CreateApiTokenResponse>>, ApiError> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest("Token name is empty".to_string()));
+    }
+    let mut scopes = payload.scopes;
+    scopes.sort();
+    scopes.dedup();
+
+    let token = generate_token();
+    let api_token = ApiToken::create(
+        &deployment.db().pool,
+        name,
+        &hash_token(&token),
+        &token_display_prefix(&token),
+        &scopes,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed("api_token_created", serde_json::json!({ "scopes": scopes }))
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(CreateApiTokenResponse {
+        api_token,
+        token,
+    })))
+}
+
+pub async fn delete_api_token(
+    State(deployment): State<DeploymentImpl>,
+    Path(token_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if ApiToken::delete(&deployment.db().pool, token_id).await? == 0 {
+        return Err(ApiError::BadRequest("API token not found".to_string()));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/local-auth/status", get(get_status))
+        .route("/local-auth/login", post(login))
+        .route("/local-auth/logout", post(logout))
+        .route("/api-tokens", get(list_api_tokens).post(create_api_token))
+        .route("/api-tokens/{token_id}", delete(delete_api_token))
+}
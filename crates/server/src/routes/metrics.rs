@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    Router,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use db::models::execution_process::{ExecutionProcess, ExecutionProcessRunReason};
+use deployment::Deployment;
+use services::services::container::ContainerService;
+use utils::metrics;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Set the gauges that are sampled at scrape time rather than tracked
+async fn refresh_gauges(deployment: &DeploymentImpl) -> Result<(), ApiError> {
+    let running = ExecutionProcess::find_running(&deployment.db().pool).await?;
+    let mut by_reason: BTreeMap<String, i64> = BTreeMap::new();
+    for process in &running {
+        *by_reason
+            .entry(metrics::label(&process.run_reason))
+            .or_default() += 1;
+    }
+    metrics::RUNNING_EXECUTION_PROCESSES.reset(
+        by_reason
+            .iter()
+            .map(|(reason, count)| (vec![reason.as_str()], *count)),
+    );
+    let agents = running
+        .iter()
+        .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent)
+        .count();
+    metrics::RUNNING_AGENTS.set(&[], agents as i64);
+
+    let queued = deployment.queued_message_service().queued_count();
+    metrics::QUEUED_FOLLOW_UPS.set(&[], queued as i64);
+
+    let execution_bytes: usize = deployment
+        .container()
+        .msg_stores()
+        .read()
+        .await
+        .values()
+        .map(|store| store.history_bytes())
+        .sum();
+    metrics::MSG_STORE_BYTES.set(&["executions"], execution_bytes as i64);
+    let event_bytes = deployment.events().msg_store().history_bytes();
+    metrics::MSG_STORE_BYTES.set(&["events"], event_bytes as i64);
+
+    Ok(())
+}
+
+pub async fn get_metrics(State(deployment): State<DeploymentImpl>) -> Result<Response, ApiError> {
+    refresh_gauges(&deployment).await?;
+    Ok(([(header::CONTENT_TYPE, CONTENT_TYPE)], metrics::render()).into_response())
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/metrics", get(get_metrics))
+}
@@ -1,32 +1,42 @@
 use axum::{
-    Router,
+    Extension, Router,
+    middleware::from_fn_with_state,
     routing::{IntoMakeService, get},
 };
+use services::services::api_auth::ApiAuth;
 
-use crate::DeploymentImpl;
+use crate::{DeploymentImpl, middleware::require_api_token};
 
 pub mod approvals;
 pub mod config;
 pub mod containers;
+pub mod dependency_caches;
 pub mod filesystem;
 // pub mod github;
 pub mod events;
 pub mod execution_processes;
+pub mod execution_queue;
 pub mod frontend;
 pub mod health;
 pub mod images;
+pub mod local_auth;
+pub mod metrics;
+pub mod notifications;
 pub mod oauth;
 pub mod organizations;
+pub mod preview;
 pub mod projects;
 pub mod repo;
 pub mod scratch;
+pub mod secrets;
 pub mod sessions;
 pub mod shared_tasks;
 pub mod tags;
 pub mod task_attempts;
 pub mod tasks;
+pub mod webhooks;
 
-pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
+pub fn router(deployment: DeploymentImpl, auth: ApiAuth) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
     let base_routes = Router::new()
         .route("/health", get(health::health_check))
@@ -37,8 +47,10 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(shared_tasks::router())
         .merge(task_attempts::router(&deployment))
         .merge(execution_processes::router(&deployment))
+        .merge(execution_queue::router())
         .merge(tags::router(&deployment))
         .merge(oauth::router())
+        .merge(local_auth::router())
         .merge(organizations::router())
         .merge(filesystem::router())
         .merge(repo::router())
@@ -46,12 +58,29 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(approvals::router())
         .merge(scratch::router(&deployment))
         .merge(sessions::router(&deployment))
+        .merge(webhooks::router())
+        .merge(secrets::router())
+        .merge(dependency_caches::router())
+        .merge(metrics::router())
+        .merge(notifications::router())
         .nest("/images", images::routes())
-        .with_state(deployment);
+        .with_state(deployment.clone());
 
     Router::new()
         .route("/", get(frontend::serve_frontend_root))
         .route("/{*path}", get(frontend::serve_frontend))
         .nest("/api", base_routes)
+        .layer(from_fn_with_state(deployment, require_api_token))
+        .layer(Extension(auth))
+        .into_make_service()
+}
+
+/// Dev server previews, served on their own port so preview pages are a
+/// different origin from the app and its API
+pub fn preview_router(deployment: DeploymentImpl, auth: ApiAuth) -> IntoMakeService<Router> {
+    preview::router()
+        .with_state(deployment.clone())
+        .layer(from_fn_with_state(deployment, require_api_token))
+        .layer(Extension(auth))
         .into_make_service()
 }
@@ -0,0 +1,24 @@
+use axum::{Json, Router, extract::State, response::Json as ResponseJson, routing::post};
+use deployment::Deployment;
+use services::services::{container::ContainerService, notification::NotificationChannel};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Send a sample notification through `channel`, which need not be saved yet
+pub async fn test_channel(
+    State(deployment): State<DeploymentImpl>,
+    Json(channel): Json<NotificationChannel>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    deployment
+        .container()
+        .notification_service()
+        .send_test(&channel)
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Test notification failed: {e}")))?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/notifications/test", post(test_channel))
+}
@@ -0,0 +1,315 @@
+//! Reverse proxy giving every workspace's dev server a stable URL at
+//! `/preview/{workspace_id}/`. Paths are forwarded unchanged, so dev servers
+//! serve under `VK_PREVIEW_PATH` (e.g. Vite's `--base`) for absolute asset
+//! URLs and HMR to resolve through the proxy.
+//!
+//! Dev servers run agent-written code, so this server's credentials are never
+//! forwarded to them, and they cannot set its session cookie.
+
+use std::sync::LazyLock;
+
+use axum::{
+    Router,
+    body::Body,
+    extract::{
+        FromRequestParts, Path, Request, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::{HeaderMap, HeaderValue, StatusCode, header, request::Parts},
+    response::{IntoResponse, Redirect, Response},
+    routing::{any, get},
+};
+use deployment::Deployment;
+use futures_util::{SinkExt, StreamExt};
+use services::services::{
+    api_auth::TOKEN_COOKIE, container::ContainerService, dev_server::DevServerInfo,
+};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{Message as UpstreamMessage, client::IntoClientRequest},
+};
+use uuid::Uuid;
+
+use crate::DeploymentImpl;
+
+type UpstreamSocket = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Redirects are passed through to the browser rather than followed
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("failed to build preview proxy client")
+});
+
+/// Request headers that only concern the hop to this server
+const HOP_HEADERS: [header::HeaderName; 4] = [
+    header::HOST,
+    header::CONNECTION,
+    header::CONTENT_LENGTH,
+    header::TRANSFER_ENCODING,
+];
+
+pub async fn redirect_to_root(Path(workspace_id): Path<Uuid>) -> Redirect {
+    Redirect::permanent(&format!("/preview/{workspace_id}/"))
+}
+
+pub async fn proxy_root(
+    State(deployment): State<DeploymentImpl>,
+    Path(workspace_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    proxy(deployment, workspace_id, request).await
+}
+
+pub async fn proxy_path(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, _path)): Path<(Uuid, String)>,
+    request: Request,
+) -> Response {
+    proxy(deployment, workspace_id, request).await
+}
+
+async fn proxy(deployment: DeploymentImpl, workspace_id: Uuid, request: Request) -> Response {
+    let dev_servers = deployment.container().dev_servers();
+    let Some(info) = dev_servers.get(workspace_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            "No dev server is running for this workspace",
+        )
+            .into_response();
+    };
+    if !info.ready {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, "2")],
+            "The dev server is starting",
+        )
+            .into_response();
+    }
+    dev_servers.touch(workspace_id);
+
+    let (mut parts, body) = request.into_parts();
+    if is_websocket_upgrade(&parts.headers) {
+        return match WebSocketUpgrade::from_request_parts(&mut parts, &()).await {
+            Ok(ws) => bridge_websocket(deployment, ws, &info, &parts).await,
+            Err(rejection) => rejection.into_response(),
+        };
+    }
+
+    let path = parts.uri.path_and_query().map_or("/", |p| p.as_str());
+    let mut upstream = CLIENT
+        .request(
+            parts.method.clone(),
+            format!("http://127.0.0.1:{}{}", info.port, path),
+        )
+        .body(reqwest::Body::wrap_stream(body.into_data_stream()));
+    for (name, value) in &parts.headers {
+        if HOP_HEADERS.contains(name) || name == header::AUTHORIZATION {
+            continue;
+        }
+        if name == header::COOKIE {
+            if let Some(cookies) = without_token_cookie(value) {
+                upstream = upstream.header(name, cookies);
+            }
+            continue;
+        }
+        upstream = upstream.header(name, value);
+    }
+    if let Some(host) = parts.headers.get(header::HOST) {
+        upstream = upstream.header("x-forwarded-host", host);
+    }
+    upstream = upstream.header(
+        "x-forwarded-prefix",
+        info.preview_path.trim_end_matches('/'),
+    );
+
+    let upstream = match upstream.send().await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            tracing::warn!(
+                "Dev server of workspace {} unreachable: {}",
+                workspace_id,
+                e
+            );
+            return (StatusCode::BAD_GATEWAY, "The dev server is unreachable").into_response();
+        }
+    };
+
+    let mut response = Response::builder().status(upstream.status());
+    for (name, value) in upstream.headers() {
+        if name == header::CONNECTION
+            || name == header::TRANSFER_ENCODING
+            || (name == header::SET_COOKIE && sets_token_cookie(value))
+        {
+            continue;
+        }
+        response = response.header(name, value);
+    }
+    response
+        .body(Body::from_stream(upstream.bytes_stream()))
+        .unwrap_or_else(|_| StatusCode::BAD_GATEWAY.into_response())
+}
+
+/// `Cookie` header without this server's session cookie; `None` when nothing
+/// is left to forward
+fn without_token_cookie(value: &HeaderValue) -> Option<String> {
+    let kept: Vec<&str> = value
+        .to_str()
+        .ok()?
+        .split(';')
+        .map(str::trim)
+        .filter(|cookie| {
+            let name = cookie.split_once('=').map_or(*cookie, |(name, _)| name);
+            !cookie.is_empty() && name.trim() != TOKEN_COOKIE
+        })
+        .collect();
+    (!kept.is_empty()).then(|| kept.join("; "))
+}
+
+fn sets_token_cookie(value: &HeaderValue) -> bool {
+    let name = value
+        .as_bytes()
+        .split(|b| *b == b'=')
+        .next()
+        .unwrap_or_default();
+    name.trim_ascii() == TOKEN_COOKIE.as_bytes()
+}
+
+fn is_websocket_upgrade(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"))
+}
+
+/// Connect to the dev server first so its chosen subprotocol (e.g. `vite-hmr`)
+/// can be offered to the browser, then pump frames both ways.
+async fn bridge_websocket(
+    deployment: DeploymentImpl,
+    ws: WebSocketUpgrade,
+    info: &DevServerInfo,
+    parts: &Parts,
+) -> Response {
+    let path = parts.uri.path_and_query().map_or("/", |p| p.as_str());
+    let mut request = match format!("ws://127.0.0.1:{}{}", info.port, path).into_client_request() {
+        Ok(request) => request,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+    for name in [header::SEC_WEBSOCKET_PROTOCOL, header::ORIGIN] {
+        if let Some(value) = parts.headers.get(&name) {
+            request.headers_mut().insert(name, value.clone());
+        }
+    }
+    if let Some(cookies) = parts
+        .headers
+        .get(header::COOKIE)
+        .and_then(without_token_cookie)
+        .and_then(|cookies| HeaderValue::from_str(&cookies).ok())
+    {
+        request.headers_mut().insert(header::COOKIE, cookies);
+    }
+
+    let (upstream, handshake) = match connect_async(request).await {
+        Ok(connected) => connected,
+        Err(e) => {
+            tracing::warn!(
+                "Dev server websocket of workspace {} unreachable: {}",
+                info.workspace_id,
+                e
+            );
+            return (StatusCode::BAD_GATEWAY, "The dev server is unreachable").into_response();
+        }
+    };
+    let protocols: Vec<String> = handshake
+        .headers()
+        .get(header::SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| vec![v.to_string()])
+        .unwrap_or_default();
+
+    let workspace_id = info.workspace_id;
+    ws.protocols(protocols)
+        .on_upgrade(move |socket| async move {
+            pump(deployment, workspace_id, socket, upstream).await;
+        })
+}
+
+async fn pump(
+    deployment: DeploymentImpl,
+    workspace_id: Uuid,
+    socket: WebSocket,
+    upstream: UpstreamSocket,
+) {
+    let (mut client_tx, mut client_rx) = socket.split();
+    let (mut upstream_tx, mut upstream_rx) = upstream.split();
+
+    let to_upstream = async {
+        while let Some(Ok(msg)) = client_rx.next().await {
+            let msg = match msg {
+                Message::Text(text) => UpstreamMessage::text(text.to_string()),
+                Message::Binary(data) => UpstreamMessage::Binary(data),
+                Message::Ping(data) => UpstreamMessage::Ping(data),
+                Message::Pong(data) => UpstreamMessage::Pong(data),
+                Message::Close(_) => break,
+            };
+            if upstream_tx.send(msg).await.is_err() {
+                break;
+            }
+        }
+        let _ = upstream_tx.close().await;
+    };
+    let to_client = async {
+        while let Some(Ok(msg)) = upstream_rx.next().await {
+            deployment.container().dev_servers().touch(workspace_id);
+            let msg = match msg {
+                UpstreamMessage::Text(text) => Message::Text(text.to_string().into()),
+                UpstreamMessage::Binary(data) => Message::Binary(data),
+                UpstreamMessage::Ping(data) => Message::Ping(data),
+                UpstreamMessage::Pong(data) => Message::Pong(data),
+                UpstreamMessage::Close(_) => break,
+                UpstreamMessage::Frame(_) => continue,
+            };
+            if client_tx.send(msg).await.is_err() {
+                break;
+            }
+        }
+        let _ = client_tx.close().await;
+    };
+
+    tokio::select! {
+        _ = to_upstream => {}
+        _ = to_client => {}
+    }
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/preview/{workspace_id}", get(redirect_to_root))
+        .route("/preview/{workspace_id}/", any(proxy_root))
+        .route("/preview/{workspace_id}/{*path}", any(proxy_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_cookie_is_not_shared_with_dev_servers() {
+        let cookies =
+            HeaderValue::from_str(&format!("theme=dark; {TOKEN_COOKIE}=vk_secret; sid=1")).unwrap();
+        assert_eq!(
+            without_token_cookie(&cookies).as_deref(),
+            Some("theme=dark; sid=1")
+        );
+        let only_token = HeaderValue::from_str(&format!("{TOKEN_COOKIE}=vk_secret")).unwrap();
+        assert_eq!(without_token_cookie(&only_token), None);
+
+        let set_token =
+            HeaderValue::from_str(&format!("{TOKEN_COOKIE}=evil; Path=/; HttpOnly")).unwrap();
+        assert!(sets_token_cookie(&set_token));
+        assert!(!sets_token_cookie(&HeaderValue::from_static(
+            "sid=1; Path=/"
+        )));
+    }
+}
@@ -10,23 +10,30 @@ use axum::{
     http::StatusCode,
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use db::models::{
-    project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
-    project_repo::{CreateProjectRepo, ProjectRepo, UpdateProjectRepo},
+    project::{CreateProject, Project, ProjectError, ProjectGitHost, SearchResult, UpdateProject},
+    project_repo::{CreateProjectRepo, ProjectRepo, SettingsFileMode, UpdateProjectRepo},
+    project_script::{CreateProjectScript, ProjectScript, UpdateProjectScript},
     repo::Repo,
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::{
-    file_search_cache::SearchQuery, project::ProjectServiceError,
+    content_search::{ContentSearchQuery, ContentSearchResponse, SearchRoot},
+    file_search_cache::SearchQuery,
+    git_host::validate_token_secret,
+    project::ProjectServiceError,
+    project_scripts,
     remote_client::CreateRemoteProjectPayload,
+    settings_file::RepoSettingsFileStatus,
 };
 use ts_rs::TS;
 use utils::{
     api::projects::{RemoteProject, RemoteProjectMembersResponse},
+    metrics,
     response::ApiResponse,
 };
 use uuid::Uuid;
@@ -38,6 +45,11 @@ pub struct LinkToExistingRequest {
     pub remote_project_id: Uuid,
 }
 
+#[derive(Deserialize, TS)]
+pub struct SetSettingsFileModeRequest {
+    pub mode: SettingsFileMode,
+}
+
 #[derive(Deserialize, TS)]
 pub struct CreateRemoteProjectRequest {
     pub organization_id: Uuid,
@@ -56,6 +68,7 @@ pub async fn stream_projects_ws(
     State(deployment): State<DeploymentImpl>,
 ) -> impl IntoResponse {
     ws.on_upgrade(move |socket| async move {
+        let _subscriber = metrics::WEBSOCKET_SUBSCRIBERS.track(&["projects"]);
         if let Err(e) = handle_projects_ws(socket, deployment).await {
             tracing::warn!("projects WS closed: {}", e);
         }
@@ -224,7 +237,12 @@ pub async fn create_project(
 
     match deployment
         .project()
-        .create_project(&deployment.db().pool, deployment.repo(), payload)
+        .create_project(
+            &deployment.db().pool,
+            deployment.repo(),
+            deployment.git(),
+            payload,
+        )
         .await
     {
         Ok(project) => {
@@ -279,6 +297,22 @@ pub async fn update_project(
     }
 }
 
+/// Set the git host used for the project's repositories, or clear it to fall
+/// back to the `git_hosts` config and remote URL detection
+pub async fn set_project_git_host(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(git_host): Json<Option<ProjectGitHost>>,
+) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
+    if let Some(name) = git_host.as_ref().and_then(|h| h.token_secret.as_deref()) {
+        validate_token_secret(name)?;
+    }
+    let project = Project::set_git_host(&deployment.db().pool, project.id, git_host.as_ref())
+        .await?
+        .ok_or(ProjectError::ProjectNotFound)?;
+    Ok(ResponseJson(ApiResponse::success(project)))
+}
+
 pub async fn delete_project(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
@@ -403,11 +437,24 @@ pub async fn search_project_files(
         }
     };
 
+    let sparse_cones = match deployment
+        .project()
+        .get_sparse_checkout_cones(&deployment.db().pool, &project)
+        .await
+    {
+        Ok(cones) => cones,
+        Err(e) => {
+            tracing::error!("Failed to get sparse-checkout cones: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
     match deployment
         .project()
         .search_files(
             deployment.file_search_cache().as_ref(),
             &repositories,
+            &sparse_cones,
             &search_query,
         )
         .await
@@ -420,6 +467,37 @@ pub async fn search_project_files(
     }
 }
 
+pub async fn search_project_contents(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(query): Query<ContentSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<ContentSearchResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let repositories = deployment
+        .project()
+        .get_repositories(pool, project.id)
+        .await?;
+    let mut sparse_cones = deployment
+        .project()
+        .get_sparse_checkout_cones(pool, &project)
+        .await?;
+
+    let roots = repositories
+        .into_iter()
+        .map(|repo| SearchRoot {
+            sparse_paths: sparse_cones.remove(&repo.id).unwrap_or_default(),
+            name: repo.name,
+            path: repo.path,
+        })
+        .collect();
+
+    let response = deployment
+        .content_search()
+        .search(roots, &query, true)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
 pub async fn get_project_repositories(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
@@ -448,6 +526,7 @@ pub async fn add_project_repository(
         .add_repository(
             &deployment.db().pool,
             deployment.repo(),
+            deployment.git(),
             project.id,
             &payload,
         )
@@ -582,6 +661,189 @@ pub async fn update_project_repository(
     }
 }
 
+pub async fn get_repository_settings_file(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, repo_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<RepoSettingsFileStatus>>, ApiError> {
+    let repo = Repo::find_by_id(&deployment.db().pool, repo_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Repository not found".to_string()))?;
+    let status = deployment
+        .project()
+        .settings_file_status(&deployment.db().pool, deployment.git(), project_id, &repo)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+pub async fn set_repository_settings_file_mode(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, repo_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<SetSettingsFileModeRequest>,
+) -> Result<ResponseJson<ApiResponse<RepoSettingsFileStatus>>, ApiError> {
+    let repo = Repo::find_by_id(&deployment.db().pool, repo_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Repository not found".to_string()))?;
+    let status = deployment
+        .project()
+        .set_settings_file_mode(
+            &deployment.db().pool,
+            deployment.git(),
+            project_id,
+            &repo,
+            payload.mode,
+        )
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_settings_file_mode_changed",
+            serde_json::json!({
+                "project_id": project_id.to_string(),
+                "repository_id": repo_id.to_string(),
+                "mode": payload.mode,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+pub async fn get_project_scripts(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectScript>>>, ApiError> {
+    let scripts = ProjectScript::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(scripts)))
+}
+
+pub async fn create_project_script(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateProjectScript>,
+) -> Result<ResponseJson<ApiResponse<ProjectScript>>, ApiError> {
+    let pool = &deployment.db().pool;
+    validate_project_script(
+        pool,
+        project.id,
+        None,
+        ScriptCandidate {
+            name: &payload.name,
+            script: &payload.script,
+            repo_id: payload.repo_id,
+            timeout_seconds: payload.timeout_seconds,
+            depends_on: payload.depends_on.as_deref().unwrap_or_default(),
+        },
+    )
+    .await?;
+
+    let script = ProjectScript::create(pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(script)))
+}
+
+pub async fn update_project_script(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, script_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateProjectScript>,
+) -> Result<ResponseJson<ApiResponse<ProjectScript>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let existing = ProjectScript::find_by_id(pool, script_id)
+        .await?
+        .filter(|s| s.project_id == project_id)
+        .ok_or_else(|| ApiError::BadRequest("Script not found in project".to_string()))?;
+    validate_project_script(
+        pool,
+        project_id,
+        Some(script_id),
+        ScriptCandidate {
+            name: payload.name.as_ref().unwrap_or(&existing.name),
+            script: payload.script.as_ref().unwrap_or(&existing.script),
+            repo_id: payload.repo_id,
+            timeout_seconds: payload.timeout_seconds,
+            depends_on: payload
+                .depends_on
+                .as_deref()
+                .unwrap_or(existing.depends_on.as_slice()),
+        },
+    )
+    .await?;
+
+    let script = ProjectScript::update(pool, script_id, &payload)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Script not found in project".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(script)))
+}
+
+pub async fn delete_project_script(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, script_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let scripts = ProjectScript::find_by_project_id(pool, project_id).await?;
+    if !scripts.iter().any(|s| s.id == script_id) {
+        return Err(ApiError::BadRequest(
+            "Script not found in project".to_string(),
+        ));
+    }
+    // Scripts depending on the deleted one would be left with an unknown dependency
+    project_scripts::check_dependencies(
+        scripts
+            .iter()
+            .filter(|s| s.id != script_id)
+            .map(|s| (s.name.as_str(), s.depends_on.as_slice())),
+    )
+    .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    ProjectScript::delete(pool, script_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// A project script as it would be saved
+struct ScriptCandidate<'a> {
+    name: &'a str,
+    script: &'a str,
+    repo_id: Option<Uuid>,
+    timeout_seconds: Option<i64>,
+    depends_on: &'a [String],
+}
+
+/// Check a script about to be saved against the other scripts of the project
+async fn validate_project_script(
+    pool: &sqlx::SqlitePool,
+    project_id: Uuid,
+    script_id: Option<Uuid>,
+    candidate: ScriptCandidate<'_>,
+) -> Result<(), ApiError> {
+    project_scripts::validate_name(candidate.name)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if candidate.script.trim().is_empty() {
+        return Err(ApiError::BadRequest("Script must not be empty".to_string()));
+    }
+    if candidate.timeout_seconds.is_some_and(|t| t <= 0) {
+        return Err(ApiError::BadRequest(
+            "Timeout must be a positive number of seconds".to_string(),
+        ));
+    }
+    if let Some(repo_id) = candidate.repo_id
+        && ProjectRepo::find_by_project_and_repo(pool, project_id, repo_id)
+            .await?
+            .is_none()
+    {
+        return Err(ApiError::BadRequest(
+            "Repository not found in project".to_string(),
+        ));
+    }
+
+    let others = ProjectScript::find_by_project_id(pool, project_id).await?;
+    project_scripts::check_dependencies(
+        others
+            .iter()
+            .filter(|s| Some(s.id) != script_id)
+            .map(|s| (s.name.as_str(), s.depends_on.as_slice()))
+            .chain([(candidate.name, candidate.depends_on)]),
+    )
+    .map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let project_id_router = Router::new()
         .route(
@@ -590,7 +852,9 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         )
         .route("/remote/members", get(get_project_remote_members))
         .route("/search", get(search_project_files))
+        .route("/search/content", get(search_project_contents))
         .route("/open-editor", post(open_project_in_editor))
+        .route("/git-host", put(set_project_git_host))
         .route(
             "/link",
             post(link_project_to_existing_remote).delete(unlink_project),
@@ -600,6 +864,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
         )
+        .route(
+            "/scripts",
+            get(get_project_scripts).post(create_project_script),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
@@ -613,6 +881,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .put(update_project_repository)
                 .delete(delete_project_repository),
         )
+        .route(
+            "/{project_id}/repositories/{repo_id}/settings-file",
+            get(get_repository_settings_file).put(set_repository_settings_file_mode),
+        )
+        .route(
+            "/{project_id}/scripts/{script_id}",
+            put(update_project_script).delete(delete_project_script),
+        )
         .route("/stream/ws", get(stream_projects_ws))
         .nest("/{id}", project_id_router);
 
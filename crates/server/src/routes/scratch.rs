@@ -11,7 +11,7 @@ use db::models::scratch::{CreateScratch, Scratch, ScratchType, UpdateScratch};
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
-use utils::response::ApiResponse;
+use utils::{metrics, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
@@ -106,6 +106,7 @@ pub async fn stream_scratch_ws(
     Path(ScratchPath { scratch_type, id }): Path<ScratchPath>,
 ) -> impl IntoResponse {
     ws.on_upgrade(move |socket| async move {
+        let _subscriber = metrics::WEBSOCKET_SUBSCRIBERS.track(&["scratch"]);
         if let Err(e) = handle_scratch_ws(socket, deployment, id, scratch_type).await {
             tracing::warn!("scratch WS closed: {}", e);
         }
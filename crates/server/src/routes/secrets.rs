@@ -0,0 +1,90 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::secret::{CreateSecret, Secret, UpdateSecret};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::container::ContainerService;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Scope to list; global secrets when neither id is given
+#[derive(Debug, Deserialize)]
+pub struct SecretScopeQuery {
+    pub project_id: Option<Uuid>,
+    pub repo_id: Option<Uuid>,
+}
+
+pub async fn list_secrets(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SecretScopeQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Secret>>>, ApiError> {
+    let secrets = deployment
+        .container()
+        .secrets()
+        .list(&deployment.db().pool, query.project_id, query.repo_id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(secrets)))
+}
+
+pub async fn create_secret(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateSecret>,
+) -> Result<ResponseJson<ApiResponse<Secret>>, ApiError> {
+    let secret = deployment
+        .container()
+        .secrets()
+        .create(&deployment.db().pool, &payload)
+        .await?;
+
+    let scope = match (secret.project_id, secret.repo_id) {
+        (Some(_), _) => "project",
+        (None, Some(_)) => "repo",
+        (None, None) => "global",
+    };
+    deployment
+        .track_if_analytics_allowed("secret_created", serde_json::json!({ "scope": scope }))
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(secret)))
+}
+
+/// Replace the value of a secret; its name and scope stay the same
+pub async fn update_secret(
+    State(deployment): State<DeploymentImpl>,
+    Path(secret_id): Path<Uuid>,
+    Json(payload): Json<UpdateSecret>,
+) -> Result<ResponseJson<ApiResponse<Secret>>, ApiError> {
+    let secret = deployment
+        .container()
+        .secrets()
+        .update(&deployment.db().pool, secret_id, &payload.value)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(secret)))
+}
+
+pub async fn delete_secret(
+    State(deployment): State<DeploymentImpl>,
+    Path(secret_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    deployment
+        .container()
+        .secrets()
+        .delete(&deployment.db().pool, secret_id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/secrets", get(list_secrets).post(create_secret))
+        .route(
+            "/secrets/{secret_id}",
+            put(update_secret).delete(delete_secret),
+        )
+}
@@ -10,6 +10,7 @@ use axum::{
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     project_repo::ProjectRepo,
+    project_script::ProjectScript,
     scratch::{Scratch, ScratchType},
     session::{CreateSession, Session},
     workspace::{Workspace, WorkspaceError},
@@ -177,9 +178,10 @@ pub async fn follow_up(
     let prompt = payload.prompt;
 
     let project_repos = ProjectRepo::find_by_project_id_with_names(pool, project.id).await?;
+    let project_scripts = ProjectScript::find_by_project_id(pool, project.id).await?;
     let cleanup_action = deployment
         .container()
-        .cleanup_actions_for_repos(&project_repos);
+        .cleanup_actions_for_repos(&project_repos, &project_scripts);
 
     let working_dir = workspace
         .agent_working_dir
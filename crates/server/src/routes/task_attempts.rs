@@ -3,6 +3,9 @@ pub mod cursor_setup;
 pub mod gh_cli_setup;
 pub mod images;
 pub mod pr;
+pub mod review_comments;
+pub mod search;
+pub mod sparse_checkout;
 pub mod util;
 
 use std::{
@@ -19,12 +22,13 @@ use axum::{
     http::StatusCode,
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post},
+    routing::{get, patch, post},
 };
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
     project_repo::ProjectRepo,
+    project_script::{ProjectScript, ScriptPipeline},
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{Task, TaskRelationships, TaskStatus},
@@ -43,13 +47,17 @@ use executors::{
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
+    conflict_resolution::ConflictResolution,
     container::ContainerService,
+    dev_server::DevServerInfo,
     git::{ConflictOp, GitCliError, GitServiceError},
+    git_host::{GitHostKind, resolve_kind},
     github::GitHubService,
+    project_scripts,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{metrics, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
@@ -69,6 +77,20 @@ pub struct AbortConflictsRequest {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ResolveConflictsRequest {
+    pub repo_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum ResolveConflictsError {
+    NoConflicts,
+    ResolutionInProgress,
+    ProcessAlreadyRunning,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
@@ -247,6 +269,7 @@ pub async fn stream_task_attempt_diff_ws(
 ) -> impl IntoResponse {
     let stats_only = params.stats_only;
     ws.on_upgrade(move |socket| async move {
+        let _subscriber = metrics::WEBSOCKET_SUBSCRIBERS.track(&["diff"]);
         if let Err(e) = handle_task_attempt_diff_ws(socket, deployment, workspace, stats_only).await
         {
             tracing::warn!("diff WS closed: {}", e);
@@ -423,6 +446,32 @@ pub async fn merge_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Pushing to GitHub has always required a logged-in `gh`; other hosts rely on
+/// git credentials alone
+async fn ensure_gh_auth_for_github_remote(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_path: &Path,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    let project = match workspace.parent_task(pool).await? {
+        Some(task) => task.parent_project(pool).await?,
+        None => None,
+    };
+    let project_host = project.as_ref().and_then(|p| p.git_host.as_deref());
+    let git_hosts = deployment.config().read().await.git_hosts.clone();
+    let is_github = deployment
+        .git()
+        .get_git_host_repo_info(repo_path)
+        .is_ok_and(|info| {
+            resolve_kind(&info, &git_hosts, project_host) == Some(GitHostKind::GitHub)
+        });
+    if is_github {
+        GitHubService::new()?.check_token().await?;
+    }
+    Ok(())
+}
+
 pub async fn push_task_attempt_branch(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -430,9 +479,6 @@ pub async fn push_task_attempt_branch(
 ) -> Result<ResponseJson<ApiResponse<(), PushError>>, ApiError> {
     let pool = &deployment.db().pool;
 
-    let github_service = GitHubService::new()?;
-    github_service.check_token().await?;
-
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -442,6 +488,8 @@ pub async fn push_task_attempt_branch(
         .await?
         .ok_or(RepoError::NotFound)?;
 
+    ensure_gh_auth_for_github_remote(&deployment, &workspace, &repo.path).await?;
+
     let container_ref = deployment
         .container()
         .ensure_container_exists(&workspace)
@@ -468,9 +516,6 @@ pub async fn force_push_task_attempt_branch(
 ) -> Result<ResponseJson<ApiResponse<(), PushError>>, ApiError> {
     let pool = &deployment.db().pool;
 
-    let github_service = GitHubService::new()?;
-    github_service.check_token().await?;
-
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -480,6 +525,8 @@ pub async fn force_push_task_attempt_branch(
         .await?
         .ok_or(RepoError::NotFound)?;
 
+    ensure_gh_auth_for_github_remote(&deployment, &workspace, &repo.path).await?;
+
     let container_ref = deployment
         .container()
         .ensure_container_exists(&workspace)
@@ -802,6 +849,26 @@ pub async fn change_target_branch(
 
     WorkspaceRepo::update_target_branch(pool, workspace.id, repo_id, &new_target_branch).await?;
 
+    // The settings file committed on the new target branch may differ
+    if let Some(task) = workspace.parent_task(pool).await?
+        && let Err(e) = deployment
+            .project()
+            .sync_settings_file(
+                pool,
+                deployment.git(),
+                task.project_id,
+                &repo,
+                Some(&new_target_branch),
+            )
+            .await
+    {
+        tracing::warn!(
+            "Failed to sync the settings file of repository {}: {}",
+            repo.id,
+            e
+        );
+    }
+
     let status =
         deployment
             .git()
@@ -1083,10 +1150,90 @@ pub async fn abort_conflicts_task_attempt(
     let worktree_path = workspace_path.join(&repo.name);
 
     deployment.git().abort_conflicts(&worktree_path)?;
+    deployment
+        .container()
+        .conflict_resolution_service()
+        .clear(workspace.id);
 
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+#[axum::debug_handler]
+pub async fn resolve_conflicts_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResolveConflictsRequest>,
+) -> Result<ResponseJson<ApiResponse<ConflictResolution, ResolveConflictsError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    if deployment
+        .container()
+        .conflict_resolution_service()
+        .is_active(workspace.id)
+    {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            ResolveConflictsError::ResolutionInProgress,
+        )));
+    }
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            ResolveConflictsError::ProcessAlreadyRunning,
+        )));
+    }
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+
+    let op = deployment.git().detect_conflict_op(&worktree_path)?;
+    let conflicted_files = deployment.git().get_conflicted_files(&worktree_path)?;
+    let Some(op) = op.filter(|_| !conflicted_files.is_empty()) else {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            ResolveConflictsError::NoConflicts,
+        )));
+    };
+
+    let resolution = deployment
+        .container()
+        .start_conflict_resolution(&workspace, &repo)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "conflict_resolution_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
+                "op": op,
+                "conflicted_files": conflicted_files.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(resolution)))
+}
+
+pub async fn get_conflict_resolution(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ConflictResolution>>>, ApiError> {
+    let resolution = deployment
+        .container()
+        .conflict_resolution_service()
+        .get(workspace.id);
+
+    Ok(ResponseJson(ApiResponse::success(resolution)))
+}
+
 #[axum::debug_handler]
 pub async fn start_dev_server(
     Extension(workspace): Extension<Workspace>,
@@ -1106,14 +1253,14 @@ pub async fn start_dev_server(
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
-    // Stop any existing dev servers for this project
+    // Stop any existing dev server for this workspace; other workspaces keep theirs
     let existing_dev_servers =
-        match ExecutionProcess::find_running_dev_servers_by_project(pool, project.id).await {
+        match ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace.id).await {
             Ok(servers) => servers,
             Err(e) => {
                 tracing::error!(
-                    "Failed to find running dev servers for project {}: {}",
-                    project.id,
+                    "Failed to find running dev servers for workspace {}: {}",
+                    workspace.id,
                     e
                 );
                 return Err(ApiError::Workspace(WorkspaceError::ValidationError(
@@ -1124,9 +1271,9 @@ pub async fn start_dev_server(
 
     for dev_server in existing_dev_servers {
         tracing::info!(
-            "Stopping existing dev server {} for project {}",
+            "Stopping existing dev server {} for workspace {}",
             dev_server.id,
-            project.id
+            workspace.id
         );
 
         if let Err(e) = deployment
@@ -1160,6 +1307,9 @@ pub async fn start_dev_server(
             language: ScriptRequestLanguage::Bash,
             context: ScriptContext::DevServer,
             working_dir,
+            script_name: None,
+            env: Default::default(),
+            timeout_seconds: None,
         }),
         None,
     );
@@ -1204,6 +1354,15 @@ pub async fn start_dev_server(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+pub async fn get_dev_server(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<DevServerInfo>>>, ApiError> {
+    let info = deployment.container().dev_servers().get(workspace.id);
+
+    Ok(ResponseJson(ApiResponse::success(info)))
+}
+
 pub async fn get_task_attempt_children(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -1292,9 +1451,10 @@ pub async fn run_setup_script(
         .await?
         .ok_or(SqlxError::RowNotFound)?;
     let project_repos = ProjectRepo::find_by_project_id_with_names(pool, project.id).await?;
+    let project_scripts = ProjectScript::find_by_project_id(pool, project.id).await?;
     let executor_action = match deployment
         .container()
-        .setup_actions_for_repos(&project_repos)
+        .setup_actions_for_repos(&project_repos, &project_scripts)
     {
         Some(action) => action,
         None => {
@@ -1376,9 +1536,10 @@ pub async fn run_cleanup_script(
         .await?
         .ok_or(SqlxError::RowNotFound)?;
     let project_repos = ProjectRepo::find_by_project_id_with_names(pool, project.id).await?;
+    let project_scripts = ProjectScript::find_by_project_id(pool, project.id).await?;
     let executor_action = match deployment
         .container()
-        .cleanup_actions_for_repos(&project_repos)
+        .cleanup_actions_for_repos(&project_repos, &project_scripts)
     {
         Some(action) => action,
         None => {
@@ -1428,6 +1589,108 @@ pub async fn run_cleanup_script(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct RunProjectScriptRequest {
+    /// Script to run after the scripts it depends on
+    pub name: Option<String>,
+    /// Pipeline to run instead of a single script
+    pub pipeline: Option<ScriptPipeline>,
+}
+
+/// Run a named project script or a script pipeline, each step as its own process
+#[axum::debug_handler]
+pub async fn run_project_script(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RunProjectScriptRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess, RunScriptError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    // Check if any non-dev-server processes are already running for this workspace
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            RunScriptError::ProcessAlreadyRunning,
+        )));
+    }
+
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let project_repos = ProjectRepo::find_by_project_id_with_names(pool, task.project_id).await?;
+    let scripts = ProjectScript::find_by_project_id(pool, task.project_id).await?;
+
+    let executor_action = match (&payload.name, payload.pipeline) {
+        (Some(name), None) => Some(project_scripts::run_action(&scripts, &project_repos, name)),
+        (None, Some(pipeline)) => {
+            project_scripts::pipeline_action(&scripts, &project_repos, pipeline, None).transpose()
+        }
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Pass either a script name or a pipeline".to_string(),
+            ));
+        }
+    };
+    let executor_action = match executor_action {
+        Some(Ok(action)) => action,
+        Some(Err(e)) => return Err(ApiError::BadRequest(e.to_string())),
+        None => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                RunScriptError::NoScriptConfigured,
+            )));
+        }
+    };
+
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    let session = match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+        Some(s) => s,
+        None => {
+            Session::create(
+                pool,
+                &CreateSession {
+                    executor: Some("script".to_string()),
+                },
+                Uuid::new_v4(),
+                workspace.id,
+            )
+            .await?
+        }
+    };
+
+    let run_reason = match executor_action.typ() {
+        ExecutorActionType::ScriptRequest(request) => match request.context {
+            ScriptContext::SetupScript => ExecutionProcessRunReason::SetupScript,
+            ScriptContext::CleanupScript => ExecutionProcessRunReason::CleanupScript,
+            _ => ExecutionProcessRunReason::Script,
+        },
+        _ => ExecutionProcessRunReason::Script,
+    };
+    let execution_process = deployment
+        .container()
+        .start_execution(&workspace, &session, &executor_action, &run_reason)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_script_executed",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": task.project_id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "pipeline": payload.pipeline,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
 #[axum::debug_handler]
 pub async fn gh_cli_setup_handler(
     Extension(workspace): Extension<Workspace>,
@@ -1483,8 +1746,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/run-agent-setup", post(run_agent_setup))
         .route("/gh-cli-setup", post(gh_cli_setup_handler))
         .route("/start-dev-server", post(start_dev_server))
+        .route("/dev-server", get(get_dev_server))
         .route("/run-setup-script", post(run_setup_script))
         .route("/run-cleanup-script", post(run_cleanup_script))
+        .route("/scripts/run", post(run_project_script))
         .route("/branch-status", get(get_task_attempt_branch_status))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
         .route("/merge", post(merge_task_attempt))
@@ -1492,9 +1757,30 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/push/force", post(force_push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
         .route("/conflicts/abort", post(abort_conflicts_task_attempt))
+        .route("/conflicts/resolve", post(resolve_conflicts_task_attempt))
+        .route("/conflicts/resolution", get(get_conflict_resolution))
         .route("/pr", post(pr::create_github_pr))
         .route("/pr/attach", post(pr::attach_existing_pr))
         .route("/pr/comments", get(pr::get_pr_comments))
+        .route(
+            "/review-comments",
+            get(review_comments::list_review_comments).post(review_comments::create_review_comment),
+        )
+        .route("/review-comments/send", post(review_comments::send_review))
+        .route(
+            "/review-comments/{comment_id}",
+            patch(review_comments::update_review_comment)
+                .delete(review_comments::delete_review_comment),
+        )
+        .route(
+            "/review-comments/{comment_id}/resolve",
+            post(review_comments::resolve_review_comment),
+        )
+        .route(
+            "/sparse-checkout",
+            get(sparse_checkout::get_sparse_checkout).post(sparse_checkout::expand_sparse_checkout),
+        )
+        .route("/search/content", get(search::search_contents))
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/children", get(get_task_attempt_children))
         .route("/stop", post(stop_task_attempt_execution))
@@ -92,6 +92,9 @@ async fn get_setup_helper_action(codex: &Codex) -> Result<ExecutorAction, ApiErr
         language: ScriptRequestLanguage::Bash,
         context: ScriptContext::ToolInstallScript,
         working_dir: None,
+        script_name: None,
+        env: Default::default(),
+        timeout_seconds: None,
     };
 
     Ok(ExecutorAction::new(
@@ -109,6 +109,9 @@ fi"#
             language: ScriptRequestLanguage::Bash,
             context: ScriptContext::ToolInstallScript,
             working_dir: None,
+            script_name: None,
+            env: Default::default(),
+            timeout_seconds: None,
         };
         // Second action (chained): Login
         let login_script = format!(
@@ -123,6 +126,9 @@ export PATH="$HOME/.local/bin:$PATH"
             language: ScriptRequestLanguage::Bash,
             context: ScriptContext::ToolInstallScript,
             working_dir: None,
+            script_name: None,
+            env: Default::default(),
+            timeout_seconds: None,
         };
 
         // Chain them: install → login
@@ -96,6 +96,9 @@ fi"#
             language: ScriptRequestLanguage::Bash,
             context: ScriptContext::ToolInstallScript,
             working_dir: None,
+            script_name: None,
+            env: Default::default(),
+            timeout_seconds: None,
         };
 
         // Auth script
@@ -111,6 +114,9 @@ gh auth login --web --git-protocol https --skip-ssh-key
             language: ScriptRequestLanguage::Bash,
             context: ScriptContext::ToolInstallScript,
             working_dir: None,
+            script_name: None,
+            env: Default::default(),
+            timeout_seconds: None,
         };
 
         // Chain them: install → auth
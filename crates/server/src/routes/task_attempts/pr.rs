@@ -24,7 +24,8 @@ use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
     git::{GitCliError, GitServiceError},
-    github::{CreatePrRequest, GitHubService, GitHubServiceError, UnifiedPrComment},
+    git_host::{CreatePrRequest, GitHostError, GitHostKind, UnifiedPrComment},
+    github::GitHubServiceError,
 };
 use ts_rs::TS;
 use utils::response::ApiResponse;
@@ -52,6 +53,28 @@ pub enum CreatePrError {
     GitCliNotLoggedIn,
     GitCliNotInstalled,
     TargetBranchNotFound { branch: String },
+    GitHostNotLoggedIn { host: String },
+    UnsupportedGitHost { host: String },
+}
+
+impl CreatePrError {
+    fn from_git_host_error(err: &GitHostError) -> Option<Self> {
+        match err {
+            GitHostError::GitHub(GitHubServiceError::GhCliNotInstalled(_)) => {
+                Some(Self::GithubCliNotInstalled)
+            }
+            GitHostError::GitHub(GitHubServiceError::AuthFailed(_)) => {
+                Some(Self::GithubCliNotLoggedIn)
+            }
+            GitHostError::AuthFailed { host, .. } => {
+                Some(Self::GitHostNotLoggedIn { host: host.clone() })
+            }
+            GitHostError::UnsupportedHost(host) => {
+                Some(Self::UnsupportedGitHost { host: host.clone() })
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -79,6 +102,28 @@ pub enum GetPrCommentsError {
     NoPrAttached,
     GithubCliNotInstalled,
     GithubCliNotLoggedIn,
+    GitHostNotLoggedIn { host: String },
+    UnsupportedGitHost { host: String },
+}
+
+impl GetPrCommentsError {
+    fn from_git_host_error(err: &GitHostError) -> Option<Self> {
+        match err {
+            GitHostError::GitHub(GitHubServiceError::GhCliNotInstalled(_)) => {
+                Some(Self::GithubCliNotInstalled)
+            }
+            GitHostError::GitHub(GitHubServiceError::AuthFailed(_)) => {
+                Some(Self::GithubCliNotLoggedIn)
+            }
+            GitHostError::AuthFailed { host, .. } => {
+                Some(Self::GitHostNotLoggedIn { host: host.clone() })
+            }
+            GitHostError::UnsupportedHost(host) => {
+                Some(Self::UnsupportedGitHost { host: host.clone() })
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -102,6 +147,7 @@ Use `gh pr edit` to update the PR."#;
 async fn trigger_pr_description_follow_up(
     deployment: &DeploymentImpl,
     workspace: &Workspace,
+    host_kind: GitHostKind,
     pr_number: i64,
     pr_url: &str,
 ) -> Result<(), ApiError> {
@@ -116,6 +162,13 @@ async fn trigger_pr_description_follow_up(
     let prompt = prompt_template
         .replace("{pr_number}", &pr_number.to_string())
         .replace("{pr_url}", pr_url);
+    // The default prompt tells the agent to use `gh`
+    let prompt = match host_kind {
+        GitHostKind::GitHub => prompt,
+        kind => format!(
+            "{prompt}\n\nThis repository is hosted on {kind}, not GitHub: update the pull request with the {kind} CLI or API instead of `gh`."
+        ),
+    };
 
     drop(config); // Release the lock before async operations
 
@@ -190,6 +243,11 @@ pub async fn create_github_pr(
 ) -> Result<ResponseJson<ApiResponse<String, CreatePrError>>, ApiError> {
     let pool = &deployment.db().pool;
 
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -213,6 +271,22 @@ pub async fn create_github_pr(
     let workspace_path = PathBuf::from(&container_ref);
     let worktree_path = workspace_path.join(repo.name);
 
+    // Resolve the hosting provider before pushing anything
+    let repo_info = deployment.git().get_git_host_repo_info(&repo_path)?;
+    let provider = match deployment
+        .container()
+        .git_host_provider(Some(task.project_id), &repo_info)
+        .await
+    {
+        Ok(provider) => provider,
+        Err(e) => {
+            return match CreatePrError::from_git_host_error(&e) {
+                Some(data) => Ok(ResponseJson(ApiResponse::error_with_data(data))),
+                None => Err(ApiError::GitHost(e)),
+            };
+        }
+    };
+
     match deployment
         .git()
         .check_remote_branch_exists(&repo_path, &target_branch)
@@ -238,12 +312,12 @@ pub async fn create_github_pr(
         Ok(true) => {}
     }
 
-    // Push the branch to GitHub first
+    // Push the branch to the remote first
     if let Err(e) = deployment
         .git()
         .push_to_github(&worktree_path, &workspace.branch, false)
     {
-        tracing::error!("Failed to push branch to GitHub: {}", e);
+        tracing::error!("Failed to push branch to remote: {}", e);
         match e {
             GitServiceError::GitCLI(GitCliError::AuthFailed(_)) => {
                 return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -278,7 +352,6 @@ pub async fn create_github_pr(
     } else {
         target_branch
     };
-    // Create the PR using GitHub service
     let pr_request = CreatePrRequest {
         title: request.title.clone(),
         body: request.body.clone(),
@@ -286,12 +359,7 @@ pub async fn create_github_pr(
         base_branch: norm_target_branch_name.clone(),
         draft: request.draft,
     };
-    // Use GitService to get the remote URL, then create GitHubRepoInfo
-    let repo_info = deployment.git().get_github_repo_info(&repo_path)?;
-
-    // Use GitHubService to create the PR
-    let github_service = GitHubService::new()?;
-    match github_service.create_pr(&repo_info, &pr_request).await {
+    match provider.create_pr(&repo_info, &pr_request).await {
         Ok(pr_info) => {
             // Update the workspace with PR information
             if let Err(e) = Merge::create_pr(
@@ -306,6 +374,11 @@ pub async fn create_github_pr(
             {
                 tracing::error!("Failed to update workspace PR status: {}", e);
             }
+            deployment
+                .container()
+                .webhooks()
+                .pr_opened(workspace.id, pr_info.number, &pr_info.url)
+                .await;
 
             // Auto-open PR in browser
             if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
@@ -316,6 +389,7 @@ pub async fn create_github_pr(
                     "github_pr_created",
                     serde_json::json!({
                         "workspace_id": workspace.id.to_string(),
+                        "git_host": provider.kind(),
                     }),
                 )
                 .await;
@@ -325,6 +399,7 @@ pub async fn create_github_pr(
                 && let Err(e) = trigger_pr_description_follow_up(
                     &deployment,
                     &workspace,
+                    provider.kind(),
                     pr_info.number,
                     &pr_info.url,
                 )
@@ -341,18 +416,14 @@ pub async fn create_github_pr(
         }
         Err(e) => {
             tracing::error!(
-                "Failed to create GitHub PR for attempt {}: {}",
+                "Failed to create {} PR for attempt {}: {}",
+                provider.kind(),
                 workspace.id,
                 e
             );
-            match &e {
-                GitHubServiceError::GhCliNotInstalled(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::GithubCliNotInstalled),
-                )),
-                GitHubServiceError::AuthFailed(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::GithubCliNotLoggedIn),
-                )),
-                _ => Err(ApiError::GitHubService(e)),
+            match CreatePrError::from_git_host_error(&e) {
+                Some(data) => Ok(ResponseJson(ApiResponse::error_with_data(data))),
+                None => Err(ApiError::GitHost(e)),
             }
         }
     }
@@ -390,12 +461,15 @@ pub async fn attach_existing_pr(
         })));
     }
 
-    let github_service = GitHubService::new()?;
-    let repo_info = deployment.git().get_github_repo_info(&repo.path)?;
+    let repo_info = deployment.git().get_git_host_repo_info(&repo.path)?;
+    let provider = deployment
+        .container()
+        .git_host_provider(Some(task.project_id), &repo_info)
+        .await?;
 
     // List all PRs for branch (open, closed, and merged)
-    let prs = github_service
-        .list_all_prs_for_branch(&repo_info, &workspace.branch)
+    let prs = provider
+        .list_prs_for_branch(&repo_info, &workspace.branch)
         .await?;
 
     // Take the first PR (prefer open, but also accept merged/closed)
@@ -466,6 +540,11 @@ pub async fn get_pr_comments(
 ) -> Result<ResponseJson<ApiResponse<PrCommentsResponse, GetPrCommentsError>>, ApiError> {
     let pool = &deployment.db().pool;
 
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+
     // Look up the specific repo using the multi-repo pattern
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
@@ -489,14 +568,18 @@ pub async fn get_pr_comments(
         }
     };
 
-    let github_service = GitHubService::new()?;
-    let repo_info = deployment.git().get_github_repo_info(&repo.path)?;
+    let repo_info = deployment.git().get_git_host_repo_info(&repo.path)?;
 
-    // Fetch comments from GitHub
-    match github_service
-        .get_pr_comments(&repo_info, pr_info.number)
+    // Fetch comments from the git host
+    let comments = match deployment
+        .container()
+        .git_host_provider(Some(task.project_id), &repo_info)
         .await
     {
+        Ok(provider) => provider.get_pr_comments(&repo_info, pr_info.number).await,
+        Err(e) => Err(e),
+    };
+    match comments {
         Ok(comments) => Ok(ResponseJson(ApiResponse::success(PrCommentsResponse {
             comments,
         }))),
@@ -507,14 +590,9 @@ pub async fn get_pr_comments(
                 pr_info.number,
                 e
             );
-            match &e {
-                GitHubServiceError::GhCliNotInstalled(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotInstalled),
-                )),
-                GitHubServiceError::AuthFailed(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotLoggedIn),
-                )),
-                _ => Err(ApiError::GitHubService(e)),
+            match GetPrCommentsError::from_git_host_error(&e) {
+                Some(data) => Ok(ResponseJson(ApiResponse::error_with_data(data))),
+                None => Err(ApiError::GitHost(e)),
             }
         }
     }
@@ -0,0 +1,388 @@
+use std::path::{Path as StdPath, PathBuf};
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    execution_process::ExecutionProcess,
+    execution_process_repo_state::ExecutionProcessRepoState,
+    repo::Repo,
+    review_comment::{CreateReviewComment, ReviewComment, ReviewSide},
+    scratch::DraftFollowUpData,
+    session::Session,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    review_comments::{ReviewCommentError, build_review_prompt, extract_lines, reanchor_comments},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CreateReviewCommentRequest {
+    /// Repository of the file. When omitted, `file_path` must start with the
+    /// repository name, as paths are shown in the diff view.
+    pub repo_id: Option<Uuid>,
+    pub file_path: String,
+    #[serde(default)]
+    pub side: ReviewSide,
+    pub line_start: u32,
+    /// Defaults to `line_start`
+    pub line_end: Option<u32>,
+    pub body: String,
+    /// Anchor to the commit this process left behind instead of the current
+    /// HEAD of the worktree
+    pub execution_process_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct UpdateReviewCommentRequest {
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ResolveReviewCommentRequest {
+    /// Pass `false` to reopen the comment
+    pub resolved: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct SendReviewRequest {
+    /// Extra instructions appended after the comments
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct ReviewCommentWithRepo {
+    #[serde(flatten)]
+    pub comment: ReviewComment,
+    pub repo_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct SendReviewResponse {
+    /// Number of comments included in the prompt
+    pub sent: usize,
+    /// The agent was busy, so the prompt was queued as the next follow-up
+    pub queued: bool,
+    pub execution_process_id: Option<Uuid>,
+}
+
+async fn load_comment(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    comment_id: Uuid,
+) -> Result<ReviewComment, ApiError> {
+    ReviewComment::find_by_id(&deployment.db().pool, comment_id)
+        .await?
+        .filter(|c| c.workspace_id == workspace.id)
+        .ok_or_else(|| ApiError::BadRequest("Review comment not found".to_string()))
+}
+
+/// Load the workspace's comments, re-anchored to the current worktree HEADs.
+async fn load_comments(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<(Vec<ReviewComment>, Vec<Repo>), ApiError> {
+    let pool = &deployment.db().pool;
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let mut comments = ReviewComment::find_by_workspace_id(pool, workspace.id).await?;
+    if comments.iter().any(|c| !c.is_resolved()) {
+        let container_ref = deployment
+            .container()
+            .ensure_container_exists(workspace)
+            .await?;
+        reanchor_comments(
+            pool,
+            deployment.git(),
+            StdPath::new(&container_ref),
+            &repos,
+            &mut comments,
+        )
+        .await?;
+    }
+    Ok((comments, repos))
+}
+
+fn with_repo_names(comments: Vec<ReviewComment>, repos: &[Repo]) -> Vec<ReviewCommentWithRepo> {
+    comments
+        .into_iter()
+        .map(|comment| {
+            let repo_name = repos
+                .iter()
+                .find(|r| r.id == comment.repo_id)
+                .map(|r| r.name.clone())
+                .unwrap_or_default();
+            ReviewCommentWithRepo { comment, repo_name }
+        })
+        .collect()
+}
+
+pub async fn list_review_comments(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ReviewCommentWithRepo>>>, ApiError> {
+    let (comments, repos) = load_comments(&deployment, &workspace).await?;
+    Ok(ResponseJson(ApiResponse::success(with_repo_names(
+        comments, &repos,
+    ))))
+}
+
+pub async fn create_review_comment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateReviewCommentRequest>,
+) -> Result<ResponseJson<ApiResponse<ReviewCommentWithRepo>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let body = payload.body.trim();
+    if body.is_empty() {
+        return Err(ApiError::BadRequest("Comment body is empty".to_string()));
+    }
+    let line_start = i64::from(payload.line_start);
+    let line_end = payload.line_end.map(i64::from).unwrap_or(line_start);
+    if line_start < 1 || line_end < line_start {
+        return Err(ReviewCommentError::InvalidRange(line_start, line_end).into());
+    }
+
+    let repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+    let (repo, file_path) = match payload.repo_id {
+        Some(repo_id) => {
+            let repo = repos
+                .iter()
+                .find(|r| r.repo.id == repo_id)
+                .ok_or_else(|| ApiError::BadRequest("Repo is not part of this workspace".into()))?;
+            (repo, payload.file_path.trim_start_matches('/'))
+        }
+        None => repos
+            .iter()
+            .find_map(|r| {
+                payload
+                    .file_path
+                    .strip_prefix(&format!("{}/", r.repo.name))
+                    .map(|path| (r, path))
+            })
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Cannot tell which repository '{}' belongs to",
+                    payload.file_path
+                ))
+            })?,
+    };
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = PathBuf::from(container_ref).join(&repo.repo.name);
+    let git = deployment.git();
+
+    let anchor_commit = match payload.execution_process_id {
+        Some(process_id) => {
+            ExecutionProcessRepoState::find_by_execution_process_id(pool, process_id)
+                .await?
+                .into_iter()
+                .find(|state| state.repo_id == repo.repo.id)
+                .and_then(|state| state.after_head_commit)
+                .ok_or_else(|| {
+                    ApiError::BadRequest("Process has no recorded commit for this repo".into())
+                })?
+        }
+        None => git.get_head_info(&worktree_path)?.oid,
+    };
+
+    // Remember the commented lines so the comment can follow them around
+    let content = match payload.side {
+        ReviewSide::New => {
+            match git.read_file_at_commit(&worktree_path, &anchor_commit, file_path)? {
+                Some(content) => Some(content),
+                // Not committed yet; the diff shows the worktree
+                None => tokio::fs::read_to_string(worktree_path.join(file_path))
+                    .await
+                    .ok(),
+            }
+        }
+        ReviewSide::Old => {
+            let base =
+                git.get_base_commit(&repo.repo.path, &workspace.branch, &repo.target_branch)?;
+            git.read_file_at_commit(&repo.repo.path, &base.to_string(), file_path)?
+        }
+    };
+    let anchor_text = match content {
+        Some(content) => Some(
+            extract_lines(&content, line_start, line_end)
+                .ok_or(ReviewCommentError::InvalidRange(line_start, line_end))?,
+        ),
+        None => None,
+    };
+
+    let comment = ReviewComment::create(
+        pool,
+        &CreateReviewComment {
+            workspace_id: workspace.id,
+            repo_id: repo.repo.id,
+            execution_process_id: payload.execution_process_id,
+            file_path: file_path.to_string(),
+            side: payload.side,
+            line_start,
+            line_end,
+            body: body.to_string(),
+            anchor_commit,
+            anchor_text,
+        },
+    )
+    .await?;
+
+    Ok(ResponseJson(ApiResponse::success(ReviewCommentWithRepo {
+        comment,
+        repo_name: repo.repo.name.clone(),
+    })))
+}
+
+pub async fn update_review_comment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_id, comment_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateReviewCommentRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let comment = load_comment(&deployment, &workspace, comment_id).await?;
+    let body = payload.body.trim();
+    if body.is_empty() {
+        return Err(ApiError::BadRequest("Comment body is empty".to_string()));
+    }
+    ReviewComment::update_body(&deployment.db().pool, comment.id, body).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn resolve_review_comment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_id, comment_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<ResolveReviewCommentRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let comment = load_comment(&deployment, &workspace, comment_id).await?;
+    ReviewComment::set_resolved(
+        &deployment.db().pool,
+        comment.id,
+        payload.resolved.unwrap_or(true),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn delete_review_comment(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let comment = load_comment(&deployment, &workspace, comment_id).await?;
+    ReviewComment::delete(&deployment.db().pool, comment.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Send all unresolved comments that were not sent yet to the workspace's
+/// session as one follow-up. Queued behind the running agent, if any.
+pub async fn send_review(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SendReviewRequest>,
+) -> Result<ResponseJson<ApiResponse<SendReviewResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if deployment
+        .container()
+        .conflict_resolution_service()
+        .is_active(workspace.id)
+    {
+        return Err(ApiError::Conflict(
+            "A conflict resolution is running in this workspace".to_string(),
+        ));
+    }
+
+    let (comments, repos) = load_comments(&deployment, &workspace).await?;
+    let pending: Vec<(&str, &ReviewComment)> = comments
+        .iter()
+        .filter(|c| !c.is_resolved() && c.sent_at.is_none())
+        .map(|c| {
+            let repo_name = repos
+                .iter()
+                .find(|r| r.id == c.repo_id)
+                .map(|r| r.name.as_str())
+                .unwrap_or_default();
+            (repo_name, c)
+        })
+        .collect();
+    if pending.is_empty() {
+        return Err(ApiError::BadRequest(
+            "There are no unsent review comments".to_string(),
+        ));
+    }
+    let mut prompt = build_review_prompt(&pending);
+    if let Some(message) = payload.message.as_deref().map(str::trim)
+        && !message.is_empty()
+    {
+        prompt.push('\n');
+        prompt.push_str(message);
+    }
+
+    let session = Session::find_latest_by_workspace_id(pool, workspace.id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Workspace has no session to continue".into()))?;
+    let running =
+        ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+            .await?;
+
+    let mut execution_process_id = None;
+    if running {
+        let queue = deployment.queued_message_service();
+        let (message, variant) = match queue.get_queued(session.id) {
+            // Keep what the user already queued and add the review after it
+            Some(existing) => (
+                format!("{}\n\n{prompt}", existing.data.message),
+                existing.data.variant,
+            ),
+            None => (
+                prompt,
+                ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+                    .await
+                    .ok()
+                    .and_then(|profile| profile.variant),
+            ),
+        };
+        queue.queue_message(session.id, DraftFollowUpData { message, variant });
+    } else {
+        let process = deployment
+            .container()
+            .start_agent_turn(&workspace, prompt)
+            .await?;
+        execution_process_id = Some(process.id);
+    }
+
+    let ids: Vec<Uuid> = pending.iter().map(|(_, c)| c.id).collect();
+    ReviewComment::mark_sent(pool, &ids).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "review_comments_sent",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "comment_count": ids.len(),
+                "queued": running,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(SendReviewResponse {
+        sent: ids.len(),
+        queued: running,
+        execution_process_id,
+    })))
+}
@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+
+use axum::{
+    Extension,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{workspace::Workspace, workspace_repo::WorkspaceRepo};
+use deployment::Deployment;
+use services::services::{
+    container::ContainerService,
+    content_search::{ContentSearchQuery, ContentSearchResponse, SearchRoot},
+};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Search file contents in the attempt's worktrees. Worktrees change too
+/// often to be worth indexing, so they are always scanned.
+pub async fn search_contents(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ContentSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<ContentSearchResponse>>, ApiError> {
+    let repos =
+        WorkspaceRepo::find_repos_for_workspace(&deployment.db().pool, workspace.id).await?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let root = PathBuf::from(container_ref);
+
+    let roots = repos
+        .into_iter()
+        .map(|repo| SearchRoot {
+            path: root.join(&repo.name),
+            name: repo.name,
+            // Sparse worktrees only contain the checked-out cone already
+            sparse_paths: Vec::new(),
+        })
+        .collect();
+
+    let response = deployment
+        .content_search()
+        .search(roots, &query, false)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
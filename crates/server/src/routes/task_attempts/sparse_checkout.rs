@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{repo::Repo, workspace::Workspace, workspace_repo::WorkspaceRepo};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{container::ContainerService, sparse_checkout::normalize_cone_path};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct RepoSparseCheckout {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    /// Checked out directories; empty when the whole tree is checked out
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ExpandSparseCheckoutRequest {
+    /// Repository to expand. When omitted, every sparse repository of the
+    /// workspace is expanded.
+    pub repo_id: Option<Uuid>,
+    /// Directories relative to the repository root
+    pub paths: Vec<String>,
+}
+
+async fn worktree_root(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<PathBuf, ApiError> {
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    Ok(PathBuf::from(container_ref))
+}
+
+fn list_repos(
+    deployment: &DeploymentImpl,
+    root: &std::path::Path,
+    repos: &[Repo],
+) -> Result<Vec<RepoSparseCheckout>, ApiError> {
+    repos
+        .iter()
+        .map(|repo| {
+            let paths = deployment
+                .git()
+                .get_sparse_checkout(&root.join(&repo.name))?;
+            Ok(RepoSparseCheckout {
+                repo_id: repo.id,
+                repo_name: repo.name.clone(),
+                paths,
+            })
+        })
+        .collect()
+}
+
+pub async fn get_sparse_checkout(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoSparseCheckout>>>, ApiError> {
+    let repos =
+        WorkspaceRepo::find_repos_for_workspace(&deployment.db().pool, workspace.id).await?;
+    let root = worktree_root(&deployment, &workspace).await?;
+    Ok(ResponseJson(ApiResponse::success(list_repos(
+        &deployment,
+        &root,
+        &repos,
+    )?)))
+}
+
+/// Check out more directories in the workspace's sparse worktrees
+pub async fn expand_sparse_checkout(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ExpandSparseCheckoutRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoSparseCheckout>>>, ApiError> {
+    let mut paths = Vec::with_capacity(payload.paths.len());
+    for path in &payload.paths {
+        let normalized = normalize_cone_path(path)
+            .ok_or_else(|| ApiError::BadRequest(format!("Invalid directory '{path}'")))?;
+        paths.push(normalized);
+    }
+    if paths.is_empty() {
+        return Err(ApiError::BadRequest("No directories given".to_string()));
+    }
+
+    let mut repos =
+        WorkspaceRepo::find_repos_for_workspace(&deployment.db().pool, workspace.id).await?;
+    if let Some(repo_id) = payload.repo_id {
+        repos.retain(|r| r.id == repo_id);
+        if repos.is_empty() {
+            return Err(ApiError::BadRequest(
+                "Repo is not part of this workspace".to_string(),
+            ));
+        }
+    }
+
+    let root = worktree_root(&deployment, &workspace).await?;
+    for repo in &repos {
+        deployment
+            .git()
+            .expand_sparse_checkout(&root.join(&repo.name), &paths)?;
+    }
+
+    Ok(ResponseJson(ApiResponse::success(list_repos(
+        &deployment,
+        &root,
+        &repos,
+    )?)))
+}
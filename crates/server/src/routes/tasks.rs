@@ -13,6 +13,7 @@ use axum::{
     routing::{delete, get, post, put},
 };
 use db::models::{
+    execution_process::ExecutionProcessStatus,
     image::TaskImage,
     project::{Project, ProjectError},
     repo::Repo,
@@ -29,7 +30,7 @@ use services::services::{
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
-use utils::{api::oauth::LoginStatus, response::ApiResponse};
+use utils::{api::oauth::LoginStatus, metrics, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
@@ -59,6 +60,7 @@ pub async fn stream_tasks_ws(
     Query(query): Query<TaskQuery>,
 ) -> impl IntoResponse {
     ws.on_upgrade(move |socket| async move {
+        let _subscriber = metrics::WEBSOCKET_SUBSCRIBERS.track(&["tasks"]);
         if let Err(e) = handle_tasks_ws(socket, deployment, query.project_id).await {
             tracing::warn!("tasks WS closed: {}", e);
         }
@@ -215,12 +217,16 @@ pub async fn create_task_and_start(
         .collect();
     WorkspaceRepo::create_many(&deployment.db().pool, workspace.id, &workspace_repos).await?;
 
-    let is_attempt_running = deployment
+    let started = deployment
         .container()
         .start_workspace(&workspace, payload.executor_profile_id.clone())
         .await
         .inspect_err(|err| tracing::error!("Failed to start task attempt: {}", err))
-        .is_ok();
+        .ok();
+    let is_attempt_queued = started
+        .as_ref()
+        .is_some_and(|process| process.status == ExecutionProcessStatus::Queued);
+    let is_attempt_running = started.is_some() && !is_attempt_queued;
     deployment
         .track_if_analytics_allowed(
             "task_attempt_started",
@@ -241,6 +247,7 @@ pub async fn create_task_and_start(
     Ok(ResponseJson(ApiResponse::success(TaskWithAttemptStatus {
         task,
         has_in_progress_attempt: is_attempt_running,
+        has_queued_attempt: is_attempt_queued,
         last_attempt_failed: false,
         executor: payload.executor_profile_id.executor.to_string(),
     })))
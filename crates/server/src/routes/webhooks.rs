@@ -0,0 +1,148 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use db::models::{
+    webhook::{CreateWebhook, UpdateWebhook, Webhook},
+    webhook_delivery::WebhookDelivery,
+};
+use deployment::Deployment;
+use services::services::{container::ContainerService, webhooks::generate_secret};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Deliveries returned by the delivery log
+const DELIVERY_LOG_LIMIT: i64 = 50;
+
+fn validate_url(url: &str) -> Result<(), ApiError> {
+    match url::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+        _ => Err(ApiError::BadRequest(format!(
+            "Webhook URL must be an http(s) URL: {url}"
+        ))),
+    }
+}
+
+async fn find_webhook(deployment: &DeploymentImpl, id: Uuid) -> Result<Webhook, ApiError> {
+    Webhook::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Webhook not found".to_string()))
+}
+
+pub async fn list_webhooks(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Webhook>>>, ApiError> {
+    let webhooks = Webhook::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(webhooks)))
+}
+
+pub async fn create_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateWebhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest("Webhook name is empty".to_string()));
+    }
+    validate_url(&payload.url)?;
+    let secret = payload
+        .secret
+        .clone()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(generate_secret);
+    let webhook = Webhook::create(&deployment.db().pool, &payload, &secret).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "webhook_created",
+            serde_json::json!({ "events": webhook.events.0 }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+pub async fn update_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Path(webhook_id): Path<Uuid>,
+    Json(payload): Json<UpdateWebhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    if let Some(url) = &payload.url {
+        validate_url(url)?;
+    }
+    if payload.secret.as_deref() == Some("") {
+        return Err(ApiError::BadRequest("Webhook secret is empty".to_string()));
+    }
+    let webhook = Webhook::update(&deployment.db().pool, webhook_id, &payload)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Webhook not found".to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+pub async fn delete_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if Webhook::delete(&deployment.db().pool, webhook_id).await? == 0 {
+        return Err(ApiError::NotFound("Webhook not found".to_string()));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn list_deliveries(
+    State(deployment): State<DeploymentImpl>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookDelivery>>>, ApiError> {
+    let deliveries =
+        WebhookDelivery::find_by_webhook_id(&deployment.db().pool, webhook_id, DELIVERY_LOG_LIMIT)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(deliveries)))
+}
+
+/// Send a test event and return its delivery, including the response
+pub async fn send_test_event(
+    State(deployment): State<DeploymentImpl>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<WebhookDelivery>>, ApiError> {
+    let webhook = find_webhook(&deployment, webhook_id).await?;
+    let delivery = deployment
+        .container()
+        .webhooks()
+        .send_test(&webhook)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(delivery)))
+}
+
+pub async fn redeliver(
+    State(deployment): State<DeploymentImpl>,
+    Path((webhook_id, delivery_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    match WebhookDelivery::find_by_id(&deployment.db().pool, delivery_id).await? {
+        Some(delivery) if delivery.webhook_id == webhook_id => {}
+        _ => return Err(ApiError::NotFound("Delivery not found".to_string())),
+    }
+    deployment
+        .container()
+        .webhooks()
+        .redeliver(delivery_id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/webhooks", get(list_webhooks).post(create_webhook))
+        .route(
+            "/webhooks/{webhook_id}",
+            put(update_webhook).delete(delete_webhook),
+        )
+        .route("/webhooks/{webhook_id}/deliveries", get(list_deliveries))
+        .route("/webhooks/{webhook_id}/test", post(send_test_event))
+        .route(
+            "/webhooks/{webhook_id}/deliveries/{delivery_id}/redeliver",
+            post(redeliver),
+        )
+}
@@ -73,6 +73,12 @@ impl TestFixture {
                 dev_script    TEXT DEFAULT '',
                 dev_script_working_dir TEXT DEFAULT '',
                 default_agent_working_dir TEXT DEFAULT '',
+                auto_review_follow_ups BOOLEAN NOT NULL DEFAULT FALSE,
+                auto_fix_ci BOOLEAN NOT NULL DEFAULT FALSE,
+                dev_server_ports TEXT,
+                dev_server_ready_pattern TEXT,
+                default_executor_profile TEXT,
+                mcp_servers TEXT,
                 remote_project_id TEXT DEFAULT NULL,
                 created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
                 updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
@@ -963,3 +963,388 @@ mod cross_server_tests {
         );
     }
 }
+
+// ============================================================================
+// Agent Interaction Tool Tests
+// ============================================================================
+
+#[cfg(test)]
+mod agent_interaction_tests {
+    use super::*;
+
+    /// 1x1 transparent PNG (68 bytes)
+    const PIXEL_PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=";
+
+    /// An id that matches nothing in the database
+    const UNKNOWN_ID: &str = "00000000-0000-0000-0000-000000000000";
+
+    async fn call_tool(url: &str, name: &str, arguments: Value) -> Value {
+        mcp_request(
+            url,
+            "tools/call",
+            json!({
+                "name": name,
+                "arguments": arguments
+            }),
+        )
+        .await
+        .expect("Request failed")
+    }
+
+    /// The error of a failed call, from either the JSON-RPC error or an
+    /// `isError` result
+    fn error_message(response: &Value) -> Option<String> {
+        if let Some(error) = response.get("error") {
+            return Some(error["message"].as_str().unwrap_or_default().to_string());
+        }
+        if response["result"]["isError"].as_bool() == Some(true) {
+            return Some(
+                response["result"]["content"][0]["text"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            );
+        }
+        None
+    }
+
+    /// The JSON payload of a successful call
+    fn tool_result(response: &Value) -> Value {
+        if let Some(message) = error_message(response) {
+            panic!("Tool call failed: {}", message);
+        }
+        let text = response["result"]["content"][0]["text"]
+            .as_str()
+            .expect("Should have text");
+        serde_json::from_str(text).expect("Content text should be valid JSON")
+    }
+
+    /// Some session of the first project that has one, for tests that need
+    /// real data
+    async fn find_session(url: &str) -> Option<String> {
+        let projects = tool_result(&call_tool(url, "list_projects", json!({})).await);
+        for project in projects["projects"].as_array()? {
+            let tasks = tool_result(
+                &call_tool(url, "list_tasks", json!({ "project_id": project["id"] })).await,
+            );
+            for task in tasks["tasks"].as_array()? {
+                let attempts = tool_result(
+                    &call_tool(url, "list_task_attempts", json!({ "task_id": task["id"] })).await,
+                );
+                for attempt in attempts["attempts"].as_array()? {
+                    let sessions = tool_result(
+                        &call_tool(url, "list_sessions", json!({ "attempt_id": attempt["id"] }))
+                            .await,
+                    );
+                    if let Some(id) = sessions["sessions"][0]["id"].as_str() {
+                        return Some(id.to_string());
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    #[tokio::test]
+    async fn test_agent_interaction_tools_are_listed() {
+        let url = task_server_url();
+        require_server!(&url, "Task server");
+
+        let response = mcp_request(&url, "tools/list", json!({}))
+            .await
+            .expect("Request failed");
+        let tool_names: Vec<&str> = response["result"]["tools"]
+            .as_array()
+            .expect("tools should be array")
+            .iter()
+            .filter_map(|t| t["name"].as_str())
+            .collect();
+
+        for name in [
+            "respond_to_approval",
+            "queue_follow_up",
+            "get_queued_follow_up",
+            "cancel_queued_follow_up",
+            "upload_task_image",
+            "list_task_images",
+            "delete_image",
+        ] {
+            assert!(
+                tool_names.contains(&name),
+                "Task server should have '{}' tool. Found: {:?}",
+                name,
+                tool_names
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_respond_to_unknown_approval_is_error() {
+        let url = task_server_url();
+        require_server!(&url, "Task server");
+
+        let response = call_tool(
+            &url,
+            "respond_to_approval",
+            json!({
+                "approval_id": "no-such-approval",
+                "execution_process_id": UNKNOWN_ID,
+                "approve": false,
+                "reason": "protocol test"
+            }),
+        )
+        .await;
+
+        assert!(
+            error_message(&response).is_some(),
+            "Answering an unknown approval should fail: {}",
+            response
+        );
+    }
+
+    #[tokio::test]
+    async fn test_respond_to_approval_requires_decision() {
+        let url = task_server_url();
+        require_server!(&url, "Task server");
+
+        let response = call_tool(
+            &url,
+            "respond_to_approval",
+            json!({
+                "approval_id": "no-such-approval",
+                "execution_process_id": UNKNOWN_ID
+            }),
+        )
+        .await;
+
+        assert!(
+            error_message(&response).is_some(),
+            "Missing 'approve' should be rejected: {}",
+            response
+        );
+    }
+
+    #[tokio::test]
+    async fn test_queue_follow_up_for_unknown_session_is_error() {
+        let url = task_server_url();
+        require_server!(&url, "Task server");
+
+        let response = call_tool(
+            &url,
+            "queue_follow_up",
+            json!({
+                "session_id": UNKNOWN_ID,
+                "message": "Run the tests"
+            }),
+        )
+        .await;
+        assert!(
+            error_message(&response).is_some(),
+            "Queuing for an unknown session should fail: {}",
+            response
+        );
+
+        let response = call_tool(
+            &url,
+            "cancel_queued_follow_up",
+            json!({ "session_id": UNKNOWN_ID }),
+        )
+        .await;
+        assert!(
+            error_message(&response).is_some(),
+            "Cancelling for an unknown session should fail: {}",
+            response
+        );
+    }
+
+    /// Note: This briefly queues a follow-up on a real session
+    #[tokio::test]
+    async fn test_queue_and_cancel_follow_up() {
+        let url = task_server_url();
+        require_server!(&url, "Task server");
+
+        let Some(session_id) = find_session(&url).await else {
+            eprintln!("SKIPPED: No sessions available");
+            return;
+        };
+
+        let queued = tool_result(
+            &call_tool(
+                &url,
+                "queue_follow_up",
+                json!({
+                    "session_id": session_id,
+                    "message": "Protocol test follow-up"
+                }),
+            )
+            .await,
+        );
+        assert_eq!(queued["session_id"].as_str(), Some(session_id.as_str()));
+        assert_eq!(queued["queued"].as_bool(), Some(true));
+        assert_eq!(queued["message"].as_str(), Some("Protocol test follow-up"));
+
+        let current = tool_result(
+            &call_tool(
+                &url,
+                "get_queued_follow_up",
+                json!({ "session_id": session_id }),
+            )
+            .await,
+        );
+        assert_eq!(current["queued"].as_bool(), Some(true));
+
+        let cancelled = tool_result(
+            &call_tool(
+                &url,
+                "cancel_queued_follow_up",
+                json!({ "session_id": session_id }),
+            )
+            .await,
+        );
+        assert_eq!(cancelled["queued"].as_bool(), Some(false));
+        assert!(cancelled["message"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_upload_task_image_rejects_invalid_base64() {
+        let url = task_server_url();
+        require_server!(&url, "Task server");
+
+        let response = call_tool(
+            &url,
+            "upload_task_image",
+            json!({
+                "task_id": UNKNOWN_ID,
+                "filename": "broken.png",
+                "data": "not base64!"
+            }),
+        )
+        .await;
+
+        let message = error_message(&response).expect("Invalid base64 should be rejected");
+        assert!(
+            message.contains("base64"),
+            "Error should name the bad encoding: {}",
+            message
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_task_image_rejects_invalid_base64_in_data_url() {
+        let url = task_server_url();
+        require_server!(&url, "Task server");
+
+        let response = call_tool(
+            &url,
+            "upload_task_image",
+            json!({
+                "task_id": UNKNOWN_ID,
+                "filename": "broken.png",
+                "data": "data:image/png;base64,@@@@"
+            }),
+        )
+        .await;
+
+        let message = error_message(&response).expect("Invalid base64 should be rejected");
+        assert!(
+            message.contains("base64"),
+            "Error should name the bad encoding: {}",
+            message
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_task_image_decodes_data_url() {
+        let url = task_server_url();
+        require_server!(&url, "Task server");
+
+        // Line-wrapped like `base64` output; the prefix and whitespace are
+        // dropped, so only the unknown task makes the upload fail
+        let (head, tail) = PIXEL_PNG.split_at(40);
+        let response = call_tool(
+            &url,
+            "upload_task_image",
+            json!({
+                "task_id": UNKNOWN_ID,
+                "filename": "pixel.png",
+                "data": format!("data:image/png;base64,{}\n{}\n", head, tail)
+            }),
+        )
+        .await;
+
+        let message = error_message(&response).expect("Unknown task should be rejected");
+        assert!(
+            !message.contains("base64"),
+            "Data URL should decode: {}",
+            message
+        );
+    }
+
+    /// Note: This creates a task and an image, and deletes both again
+    #[tokio::test]
+    async fn test_upload_list_and_delete_task_image() {
+        let url = task_server_url();
+        require_server!(&url, "Task server");
+
+        let projects = tool_result(&call_tool(&url, "list_projects", json!({})).await);
+        let Some(project_id) = projects["projects"][0]["id"].as_str() else {
+            eprintln!("SKIPPED: No projects available");
+            return;
+        };
+        let task = tool_result(
+            &call_tool(
+                &url,
+                "create_task",
+                json!({
+                    "project_id": project_id,
+                    "title": format!("Image Protocol Test {}", next_id())
+                }),
+            )
+            .await,
+        );
+        let task_id = task["task_id"].as_str().expect("Should return task_id");
+
+        let image = tool_result(
+            &call_tool(
+                &url,
+                "upload_task_image",
+                json!({
+                    "task_id": task_id,
+                    "filename": "pixel.png",
+                    "data": format!("data:image/png;base64,{}", PIXEL_PNG)
+                }),
+            )
+            .await,
+        );
+        let image_id = image["id"].as_str().expect("Should return image id");
+        assert_eq!(image["size_bytes"].as_i64(), Some(68));
+        assert_eq!(image["original_name"].as_str(), Some("pixel.png"));
+        let file_path = image["file_path"].as_str().expect("Should have file_path");
+        assert!(
+            image["markdown"]
+                .as_str()
+                .is_some_and(|markdown| markdown.contains(file_path)),
+            "Markdown should show the image: {}",
+            image
+        );
+
+        let listed =
+            tool_result(&call_tool(&url, "list_task_images", json!({ "task_id": task_id })).await);
+        assert!(
+            listed["images"]
+                .as_array()
+                .expect("images should be array")
+                .iter()
+                .any(|i| i["id"].as_str() == Some(image_id)),
+            "Uploaded image should be listed: {}",
+            listed
+        );
+
+        tool_result(&call_tool(&url, "delete_image", json!({ "image_id": image_id })).await);
+        let listed =
+            tool_result(&call_tool(&url, "list_task_images", json!({ "task_id": task_id })).await);
+        assert_eq!(listed["count"].as_u64(), Some(0));
+
+        tool_result(&call_tool(&url, "delete_task", json!({ "task_id": task_id })).await);
+    }
+}
@@ -0,0 +1,281 @@
+//! Optional token authentication for the local server's API.
+//!
+//! When enabled, every `/api` request must carry either the admin token,
+//! generated at startup and kept in the per-user asset dir, or a named API
+//! token.
+//! Named tokens are limited to their scopes.
+
+use std::{net::IpAddr, sync::Arc};
+
+use chrono::{Duration, Utc};
+use db::models::api_token::{ApiToken, ApiTokenScope};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use tracing::warn;
+use utils::port_file::{read_token_file, remove_token_file, write_token_file};
+use uuid::Uuid;
+
+/// Environment variable turning auth on (`1`, `true`, `on`) or off (`0`,
+/// `false`, `off`). Without it, auth is on when the server listens on a
+/// non-loopback host.
+pub const AUTH_ENV: &str = "VIBE_AUTH";
+
+/// Cookie holding the token of a browser session
+pub const TOKEN_COOKIE: &str = "vk_token";
+
+/// Name of the admin token file, `<name>.token` in the asset dir
+const TOKEN_APP_NAME: &str = "vibe-kanban";
+
+/// Prefix of generated tokens
+const TOKEN_PREFIX: &str = "vk_";
+
+/// Leading characters of a token kept for display
+const DISPLAY_PREFIX_LEN: usize = 10;
+
+/// `last_used_at` is only updated when older than this, to avoid a write per
+/// request
+const TOUCH_INTERVAL_SECS: i64 = 60;
+
+/// API paths reachable without a token
+const PUBLIC_PATHS: &[&str] = &[
+    "/api/health",
+    "/api/local-auth/status",
+    "/api/local-auth/login",
+    "/api/local-auth/logout",
+];
+
+/// Who a request was authenticated as
+#[derive(Debug, Clone)]
+pub enum ApiGrant {
+    /// The admin token; may do everything
+    Admin,
+    Token(ApiToken),
+}
+
+impl ApiGrant {
+    pub fn allows(&self, scope: ApiTokenScope) -> bool {
+        match self {
+            ApiGrant::Admin => true,
+            // Every token can read
+            ApiGrant::Token(token) => scope == ApiTokenScope::Read || token.scopes.contains(&scope),
+        }
+    }
+
+    pub fn scopes(&self) -> Vec<ApiTokenScope> {
+        match self {
+            ApiGrant::Admin => vec![
+                ApiTokenScope::Read,
+                ApiTokenScope::Tasks,
+                ApiTokenScope::Execute,
+                ApiTokenScope::Config,
+            ],
+            ApiGrant::Token(token) => token.scopes.0.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ApiAuth {
+    /// Hash of the admin token; `None` when auth is disabled
+    admin_token_hash: Option<Arc<str>>,
+}
+
+impl ApiAuth {
+    pub fn disabled() -> Self {
+        Self {
+            admin_token_hash: None,
+        }
+    }
+
+    /// Enable auth as configured by [`AUTH_ENV`] for a server listening on
+    /// `host`. The admin token from a previous run is reused, so MCP servers
+    /// configured with it keep working across restarts.
+    pub async fn init(host: &str) -> std::io::Result<Self> {
+        if !auth_enabled(std::env::var(AUTH_ENV).ok().as_deref(), host) {
+            return Ok(Self::disabled());
+        }
+        let token = match read_token_file(TOKEN_APP_NAME).await {
+            Ok(token) => token,
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!("Replacing unusable admin token file: {}", e);
+                    remove_token_file(TOKEN_APP_NAME).await?;
+                }
+                let token = generate_token();
+                write_token_file(TOKEN_APP_NAME, &token).await?;
+                token
+            }
+        };
+        Ok(Self {
+            admin_token_hash: Some(hash_token(&token).into()),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.admin_token_hash.is_some()
+    }
+
+    /// Resolve a token to its grant; `None` for unknown tokens
+    pub async fn authenticate(
+        &self,
+        pool: &SqlitePool,
+        token: &str,
+    ) -> Result<Option<ApiGrant>, sqlx::Error> {
+        let hash = hash_token(token);
+        if self.admin_token_hash.as_deref() == Some(hash.as_str()) {
+            return Ok(Some(ApiGrant::Admin));
+        }
+        let Some(api_token) = ApiToken::find_by_token_hash(pool, &hash).await? else {
+            return Ok(None);
+        };
+        let stale = api_token
+            .last_used_at
+            .is_none_or(|t| Utc::now() - t > Duration::seconds(TOUCH_INTERVAL_SECS));
+        if stale && let Err(e) = ApiToken::touch(pool, api_token.id).await {
+            warn!(
+                "Failed to update last use of API token {}: {}",
+                api_token.id, e
+            );
+        }
+        Ok(Some(ApiGrant::Token(api_token)))
+    }
+}
+
+fn auth_enabled(setting: Option<&str>, host: &str) -> bool {
+    match setting.map(|s| s.trim().to_ascii_lowercase()).as_deref() {
+        Some("1" | "true" | "on" | "yes") => true,
+        Some("0" | "false" | "off" | "no") => false,
+        _ => !is_loopback_host(host),
+    }
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_matches(['[', ']'])
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+pub fn generate_token() -> String {
+    format!(
+        "{TOKEN_PREFIX}{}{}",
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+pub fn hash_token(token: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub fn token_display_prefix(token: &str) -> String {
+    token.chars().take(DISPLAY_PREFIX_LEN).collect()
+}
+
+/// Dev server previews are proxied under this prefix and need a token like the API
+pub const PREVIEW_PREFIX: &str = "/preview/";
+
+pub fn is_public_path(path: &str) -> bool {
+    !(path.starts_with("/api/") || path.starts_with(PREVIEW_PREFIX)) || PUBLIC_PATHS.contains(&path)
+}
+
+/// Scope needed for a request to `path` (starting with `/api/`). Reads need
+/// no scope beyond a valid token, except for the few endpoints that expose
+/// credentials or the host's file system: `info` and `mcp-config` return the
+/// config with its tokens and webhook URLs, and `webhooks` the signing
+/// secrets. Previews only need a valid token.
+pub fn required_scope(path: &str, is_read: bool) -> ApiTokenScope {
+    if path.starts_with(PREVIEW_PREFIX) {
+        return ApiTokenScope::Read;
+    }
+    let mut segments = path.trim_start_matches("/api/").split('/');
+    let first = segments.next().unwrap_or_default();
+    match first {
+        "auth" | "api-tokens" | "filesystem" | "info" | "mcp-config" | "webhooks" => {
+            ApiTokenScope::Config
+        }
+        _ if is_read => ApiTokenScope::Read,
+        "tasks" if segments.next() == Some("create-and-start") => ApiTokenScope::Execute,
+        "tasks" | "tags" | "shared-tasks" | "scratch" | "images" => ApiTokenScope::Tasks,
+        "task-attempts"
+        | "execution-processes"
+        | "execution-queue"
+        | "sessions"
+        | "approvals"
+        | "containers" => ApiTokenScope::Execute,
+        _ => ApiTokenScope::Config,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auth_defaults_to_on_for_non_loopback_hosts() {
+        assert!(!auth_enabled(None, "127.0.0.1"));
+        assert!(!auth_enabled(None, "localhost"));
+        assert!(!auth_enabled(None, "::1"));
+        assert!(auth_enabled(None, "0.0.0.0"));
+        assert!(auth_enabled(Some("true"), "127.0.0.1"));
+        assert!(!auth_enabled(Some("off"), "0.0.0.0"));
+    }
+
+    #[test]
+    fn scopes_follow_the_api_area() {
+        use ApiTokenScope::*;
+        assert_eq!(required_scope("/api/tasks", true), Read);
+        assert_eq!(required_scope("/api/events", true), Read);
+        assert_eq!(required_scope("/api/tasks", false), Tasks);
+        assert_eq!(
+            required_scope("/api/tasks/create-and-start", false),
+            Execute
+        );
+        assert_eq!(
+            required_scope("/api/task-attempts/123/follow-up", false),
+            Execute
+        );
+        assert_eq!(required_scope("/api/execution-queue/123", false), Execute);
+        assert_eq!(required_scope("/api/config", false), Config);
+        assert_eq!(required_scope("/api/auth/token", true), Config);
+        assert_eq!(required_scope("/api/filesystem/directory", true), Config);
+        assert_eq!(required_scope("/api/webhooks", true), Config);
+        assert_eq!(required_scope("/api/something-new", false), Config);
+        assert_eq!(required_scope("/preview/123/api/save", false), Read);
+    }
+
+    #[test]
+    fn read_tokens_cannot_see_config_credentials() {
+        let read_only = ApiGrant::Token(ApiToken {
+            id: Uuid::new_v4(),
+            name: "ci".to_string(),
+            token_prefix: "vk_ci".to_string(),
+            scopes: sqlx::types::Json(vec![ApiTokenScope::Read]),
+            last_used_at: None,
+            created_at: Utc::now(),
+        });
+        for path in ["/api/info", "/api/mcp-config"] {
+            assert!(!read_only.allows(required_scope(path, true)), "{path}");
+            assert!(ApiGrant::Admin.allows(required_scope(path, true)));
+        }
+        assert!(read_only.allows(required_scope("/api/tasks", true)));
+    }
+
+    #[test]
+    fn tokens_are_hashed_and_public_paths_skip_auth() {
+        let token = generate_token();
+        assert!(token.starts_with(TOKEN_PREFIX));
+        assert_ne!(token, generate_token());
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_eq!(hash_token(&token).len(), 64);
+        assert_eq!(token_display_prefix(&token).len(), DISPLAY_PREFIX_LEN);
+
+        assert!(is_public_path("/api/health"));
+        assert!(is_public_path("/projects/123"));
+        assert!(!is_public_path("/api/tasks"));
+        assert!(!is_public_path("/api/health/../tasks"));
+        assert!(!is_public_path("/preview/123/"));
+    }
+}
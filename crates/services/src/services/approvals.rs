@@ -21,6 +21,7 @@ use tokio::sync::{RwLock, oneshot};
 use utils::{
     approvals::{ApprovalRequest, ApprovalResponse, ApprovalStatus},
     log_msg::LogMsg,
+    metrics,
     msg_store::MsgStore,
 };
 use uuid::Uuid;
@@ -129,7 +130,12 @@ impl Approvals {
             );
         }
 
-        self.spawn_timeout_watcher(req_id.clone(), request.timeout_at, waiter.clone());
+        self.spawn_timeout_watcher(
+            req_id.clone(),
+            request.created_at,
+            request.timeout_at,
+            waiter.clone(),
+        );
         Ok((request, waiter))
     }
 
@@ -189,10 +195,11 @@ impl Approvals {
         }
     }
 
-    #[tracing::instrument(skip(self, id, timeout_at, waiter))]
+    #[tracing::instrument(skip(self, id, requested_at, timeout_at, waiter))]
     fn spawn_timeout_watcher(
         &self,
         id: String,
+        requested_at: chrono::DateTime<chrono::Utc>,
         timeout_at: chrono::DateTime<chrono::Utc>,
         waiter: ApprovalWaiter,
     ) {
@@ -215,6 +222,15 @@ impl Approvals {
             };
 
             let is_timeout = matches!(&status, ApprovalStatus::TimedOut);
+            if let Ok(waited) = (chrono::Utc::now() - requested_at).to_std() {
+                let outcome = match &status {
+                    ApprovalStatus::Pending => "pending",
+                    ApprovalStatus::Approved => "approved",
+                    ApprovalStatus::Denied { .. } => "denied",
+                    ApprovalStatus::TimedOut => "timed_out",
+                };
+                metrics::APPROVAL_WAIT.observe_duration(&[outcome], waited);
+            }
             completed.insert(id.clone(), status.clone());
 
             if is_timeout && let Some((_, pending_approval)) = pending.remove(&id) {
@@ -7,12 +7,17 @@ use serde_json::Value;
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::Approvals,
+    notification::{Notification, NotificationEvent, NotificationService},
+    webhooks::WebhookService,
+};
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
     db: DBService,
     notification_service: NotificationService,
+    webhooks: WebhookService,
     execution_process_id: Uuid,
 }
 
@@ -21,12 +26,14 @@ impl ExecutorApprovalBridge {
         approvals: Approvals,
         db: DBService,
         notification_service: NotificationService,
+        webhooks: WebhookService,
         execution_process_id: Uuid,
     ) -> Arc<Self> {
         Arc::new(Self {
             approvals,
             db,
             notification_service,
+            webhooks,
             execution_process_id,
         })
     }
@@ -51,7 +58,7 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             self.execution_process_id,
         );
 
-        let (_, waiter) = self
+        let (request, waiter) = self
             .approvals
             .create_with_waiter(request)
             .await
@@ -59,11 +66,13 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
 
         // Play notification sound when approval is needed
         self.notification_service
-            .notify(
-                "Approval Needed",
-                &format!("Tool '{}' requires approval", tool_name),
-            )
+            .notify(Notification::new(
+                NotificationEvent::ApprovalNeeded,
+                "Approval Needed".to_string(),
+                format!("Tool '{}' requires approval", tool_name),
+            ))
             .await;
+        self.webhooks.approval_requested(&request).await;
 
         let status = waiter.clone().await;
 
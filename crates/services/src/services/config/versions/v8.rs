@@ -3,11 +3,13 @@ use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 pub use v7::{
-    EditorConfig, EditorType, GitHubConfig, NotificationConfig, ShowcaseState, SoundFile,
-    ThemeMode, UiLanguage,
+    EditorConfig, EditorType, GitHubConfig, ShowcaseState, SoundFile, ThemeMode, UiLanguage,
 };
 
-use crate::services::config::versions::v7;
+use crate::services::{
+    config::versions::v7, dev_server::DevServerSettings, git_host::GitHostConfig,
+    notification::NotificationChannel, scheduler::ConcurrencyLimits,
+};
 
 fn default_git_branch_prefix() -> String {
     "vk".to_string()
@@ -17,6 +19,33 @@ fn default_pr_auto_description_enabled() -> bool {
     true
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct NotificationConfig {
+    pub sound_enabled: bool,
+    pub push_enabled: bool,
+    pub sound_file: SoundFile,
+    /// Remote channels notified alongside the local sound and push notification
+    #[serde(default)]
+    pub channels: Vec<NotificationChannel>,
+}
+
+impl From<v7::NotificationConfig> for NotificationConfig {
+    fn from(old: v7::NotificationConfig) -> Self {
+        Self {
+            sound_enabled: old.sound_enabled,
+            push_enabled: old.push_enabled,
+            sound_file: old.sound_file,
+            channels: Vec::new(),
+        }
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        v7::NotificationConfig::default().into()
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -41,6 +70,17 @@ pub struct Config {
     pub pr_auto_description_enabled: bool,
     #[serde(default)]
     pub pr_auto_description_prompt: Option<String>,
+    #[serde(default)]
+    pub git_hosts: Vec<GitHostConfig>,
+    #[serde(default)]
+    pub concurrency: ConcurrencyLimits,
+    #[serde(default)]
+    pub dev_servers: DevServerSettings,
+    /// Users whose PR comments the agent acts on besides the repository's
+    /// owners, members and collaborators. Needed on GitLab and Gitea, which
+    /// don't report how an author relates to the repository.
+    #[serde(default)]
+    pub pr_review_authors: Vec<String>,
 }
 
 impl Config {
@@ -54,7 +94,7 @@ impl Config {
             executor_profile: old_config.executor_profile,
             disclaimer_acknowledged: old_config.disclaimer_acknowledged,
             onboarding_acknowledged: old_config.onboarding_acknowledged,
-            notifications: old_config.notifications,
+            notifications: old_config.notifications.into(),
             editor: old_config.editor,
             github: old_config.github,
             analytics_enabled,
@@ -66,6 +106,10 @@ impl Config {
             showcases: old_config.showcases,
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
+            git_hosts: Vec::new(),
+            concurrency: ConcurrencyLimits::default(),
+            dev_servers: DevServerSettings::default(),
+            pr_review_authors: Vec::new(),
         }
     }
 
@@ -116,6 +160,10 @@ impl Default for Config {
             showcases: ShowcaseState::default(),
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
+            git_hosts: Vec::new(),
+            concurrency: ConcurrencyLimits::default(),
+            dev_servers: DevServerSettings::default(),
+            pr_review_authors: Vec::new(),
         }
     }
 }
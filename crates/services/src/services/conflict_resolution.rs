@@ -0,0 +1,630 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use dashmap::{DashMap, mapref::entry::Entry};
+use db::models::conflict_resolution::ConflictResolutionRecord;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::sync::Mutex;
+use ts_rs::TS;
+use utils::{shell::get_shell_command, text::truncate_to_char_boundary};
+use uuid::Uuid;
+
+use crate::services::git::{ConflictOp, ConflictSides};
+
+/// Give up (and abort) after this many agent turns without a clean continue.
+pub const MAX_RESOLUTION_ROUNDS: u32 = 5;
+
+/// Per-side cap for file contents embedded in the prompt.
+const MAX_SIDE_BYTES: usize = 16 * 1024;
+
+/// Only the tail of the test script output is kept for display.
+const MAX_TEST_OUTPUT_BYTES: usize = 8 * 1024;
+
+const TEST_SCRIPT_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Error)]
+pub enum ConflictResolutionError {
+    #[error("A conflict resolution is already running for this workspace")]
+    AlreadyInProgress,
+    #[error("Failed to run test script: {0}")]
+    TestScript(String),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ConflictResolutionState {
+    /// A coding agent is editing the conflicted files
+    Resolving,
+    /// The operation was continued and the test script is running
+    Verifying,
+    /// Conflicts were resolved and verification passed
+    Resolved,
+    /// Resolution failed and the operation was rolled back
+    Aborted,
+}
+
+/// Tracks one agent-assisted conflict resolution in a workspace repo.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ConflictResolution {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub op: ConflictOp,
+    pub state: ConflictResolutionState,
+    /// Number of agent turns started so far (starts at 1)
+    pub round: u32,
+    /// The coding agent process working on the current round
+    pub execution_process_id: Option<Uuid>,
+    /// Files that were conflicted at the start of the current round
+    pub files: Vec<String>,
+    /// Branch tip before the operation started; restored if verification fails
+    #[serde(skip)]
+    #[ts(skip)]
+    pub restore_oid: Option<String>,
+    pub message: Option<String>,
+    pub test_output: Option<String>,
+    #[ts(type = "Date")]
+    pub started_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ConflictResolution {
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self.state,
+            ConflictResolutionState::Resolving | ConflictResolutionState::Verifying
+        )
+    }
+}
+
+/// A conflicted file with the index stages used to build the agent prompt
+#[derive(Debug, Clone)]
+pub struct ConflictedFile {
+    pub path: String,
+    pub sides: ConflictSides,
+}
+
+#[derive(Debug, Clone)]
+pub struct TestScriptOutcome {
+    pub success: bool,
+    pub output: String,
+}
+
+/// Registry of conflict resolutions, one per workspace.
+/// Finished resolutions are kept so their outcome can be reported.
+/// When backed by the database every change is saved, so a resolution in
+/// progress survives a restart.
+#[derive(Clone)]
+pub struct ConflictResolutionService {
+    resolutions: Arc<DashMap<Uuid, ConflictResolution>>,
+    store: Option<SqlitePool>,
+    /// Serializes saves so an older value never overwrites a newer one
+    save_lock: Arc<Mutex<()>>,
+}
+
+impl ConflictResolutionService {
+    /// A registry that is only kept in memory
+    pub fn new() -> Self {
+        Self {
+            resolutions: Arc::new(DashMap::new()),
+            store: None,
+            save_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Restore the resolutions saved in the database and keep saving changes there
+    pub async fn load(pool: SqlitePool) -> Result<Self, sqlx::Error> {
+        let resolutions = DashMap::new();
+        for record in ConflictResolutionRecord::find_all(&pool).await? {
+            match serde_json::from_str::<ConflictResolution>(&record.resolution) {
+                Ok(mut resolution) => {
+                    resolution.restore_oid = record.restore_oid;
+                    resolutions.insert(record.workspace_id, resolution);
+                }
+                Err(e) => tracing::warn!(
+                    "Skipping unreadable conflict resolution for workspace {}: {}",
+                    record.workspace_id,
+                    e
+                ),
+            }
+        }
+        Ok(Self {
+            resolutions: Arc::new(resolutions),
+            store: Some(pool),
+            save_lock: Arc::new(Mutex::new(())),
+        })
+    }
+
+    /// Save the workspace's current resolution in the background, or delete
+    /// the saved one if it is gone. Each save reads the latest value under the
+    /// lock, so the last save to run always writes the newest state.
+    fn persist(&self, workspace_id: Uuid) {
+        let Some(pool) = self.store.clone() else {
+            return;
+        };
+        let resolutions = self.resolutions.clone();
+        let save_lock = self.save_lock.clone();
+        tokio::spawn(async move {
+            let _guard = save_lock.lock().await;
+            let current = resolutions.get(&workspace_id).map(|r| r.clone());
+            let result = match current {
+                Some(resolution) => match serde_json::to_string(&resolution) {
+                    Ok(json) => {
+                        ConflictResolutionRecord::upsert(
+                            &pool,
+                            workspace_id,
+                            &json,
+                            resolution.restore_oid.as_deref(),
+                        )
+                        .await
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to serialize conflict resolution: {}", e);
+                        return;
+                    }
+                },
+                None => ConflictResolutionRecord::delete(&pool, workspace_id).await,
+            };
+            if let Err(e) = result {
+                tracing::warn!(
+                    "Failed to save conflict resolution for workspace {}: {}",
+                    workspace_id,
+                    e
+                );
+            }
+        });
+    }
+
+    /// Register a new resolution. Fails if one is still active for the workspace.
+    pub fn begin(
+        &self,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        op: ConflictOp,
+        files: Vec<String>,
+        restore_oid: Option<String>,
+    ) -> Result<ConflictResolution, ConflictResolutionError> {
+        // Check and insert under the entry's lock so concurrent calls can't
+        // both start a resolution
+        let entry = self.resolutions.entry(workspace_id);
+        if let Entry::Occupied(existing) = &entry
+            && existing.get().is_active()
+        {
+            return Err(ConflictResolutionError::AlreadyInProgress);
+        }
+        let now = Utc::now();
+        let resolution = ConflictResolution {
+            workspace_id,
+            repo_id,
+            op,
+            state: ConflictResolutionState::Resolving,
+            round: 1,
+            execution_process_id: None,
+            files,
+            restore_oid,
+            message: None,
+            test_output: None,
+            started_at: now,
+            updated_at: now,
+        };
+        entry.insert(resolution.clone());
+        self.persist(workspace_id);
+        Ok(resolution)
+    }
+
+    pub fn get(&self, workspace_id: Uuid) -> Option<ConflictResolution> {
+        self.resolutions.get(&workspace_id).map(|r| r.clone())
+    }
+
+    pub fn is_active(&self, workspace_id: Uuid) -> bool {
+        self.resolutions
+            .get(&workspace_id)
+            .is_some_and(|r| r.is_active())
+    }
+
+    /// Every resolution that is still resolving or verifying
+    pub fn active(&self) -> Vec<ConflictResolution> {
+        self.resolutions
+            .iter()
+            .filter(|r| r.is_active())
+            .map(|r| r.clone())
+            .collect()
+    }
+
+    /// Return the workspace's resolution if the given agent process is working
+    /// on its current round. The agent can exit before the process that started
+    /// it records its id, so a round without one is claimed by the process.
+    pub fn claim_for_process(
+        &self,
+        workspace_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Option<ConflictResolution> {
+        let claimed = {
+            let mut r = self.resolutions.get_mut(&workspace_id)?;
+            if r.state != ConflictResolutionState::Resolving {
+                return None;
+            }
+            match r.execution_process_id {
+                Some(id) if id == execution_process_id => return Some(r.clone()),
+                Some(_) => return None,
+                None => {
+                    r.execution_process_id = Some(execution_process_id);
+                    r.updated_at = Utc::now();
+                    r.clone()
+                }
+            }
+        };
+        self.persist(workspace_id);
+        Some(claimed)
+    }
+
+    /// Record the agent process started for `round`, unless the round moved on
+    /// or the process already claimed it, and return the current resolution
+    pub fn set_round_process(
+        &self,
+        workspace_id: Uuid,
+        round: u32,
+        execution_process_id: Uuid,
+    ) -> Option<ConflictResolution> {
+        self.update(workspace_id, |r| {
+            if r.round == round && r.execution_process_id.is_none() {
+                r.execution_process_id = Some(execution_process_id);
+            }
+        })
+    }
+
+    /// Apply an update to a tracked resolution and return the new value
+    pub fn update(
+        &self,
+        workspace_id: Uuid,
+        f: impl FnOnce(&mut ConflictResolution),
+    ) -> Option<ConflictResolution> {
+        let updated = self.resolutions.get_mut(&workspace_id).map(|mut r| {
+            f(&mut r);
+            r.updated_at = Utc::now();
+            r.clone()
+        });
+        if updated.is_some() {
+            self.persist(workspace_id);
+        }
+        updated
+    }
+
+    /// Mark a resolution as finished with the given terminal state
+    pub fn finish(
+        &self,
+        workspace_id: Uuid,
+        state: ConflictResolutionState,
+        message: impl Into<String>,
+    ) -> Option<ConflictResolution> {
+        let message = message.into();
+        self.update(workspace_id, |r| {
+            r.state = state;
+            r.message = Some(message);
+        })
+    }
+
+    /// Forget a resolution (e.g. when the user aborts conflicts manually)
+    pub fn clear(&self, workspace_id: Uuid) -> Option<ConflictResolution> {
+        let removed = self.resolutions.remove(&workspace_id).map(|(_, v)| v);
+        if removed.is_some() {
+            self.persist(workspace_id);
+        }
+        removed
+    }
+}
+
+impl Default for ConflictResolutionService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn op_label(op: &ConflictOp) -> &'static str {
+    match op {
+        ConflictOp::Rebase => "rebase",
+        ConflictOp::Merge => "merge",
+        ConflictOp::CherryPick => "cherry-pick",
+        ConflictOp::Revert => "revert",
+    }
+}
+
+/// Describe what "ours" and "theirs" mean for the given operation, since
+/// the sides are swapped during a rebase compared to a merge.
+fn side_names(op: &ConflictOp) -> (&'static str, &'static str) {
+    match op {
+        ConflictOp::Rebase => (
+            "ours: the new base the task branch is being replayed onto",
+            "theirs: the task branch commit being replayed",
+        ),
+        ConflictOp::Merge => (
+            "ours: the current branch",
+            "theirs: the branch being merged in",
+        ),
+        ConflictOp::CherryPick => (
+            "ours: the current branch",
+            "theirs: the commit being cherry-picked",
+        ),
+        ConflictOp::Revert => (
+            "ours: the current branch",
+            "theirs: the inverse of the commit being reverted",
+        ),
+    }
+}
+
+fn push_side(prompt: &mut String, label: &str, content: &Option<String>) {
+    prompt.push_str(&format!("#### {label}\n"));
+    match content {
+        Some(text) => {
+            let truncated = truncate_to_char_boundary(text, MAX_SIDE_BYTES);
+            prompt.push_str("```\n");
+            prompt.push_str(truncated);
+            if !truncated.ends_with('\n') {
+                prompt.push('\n');
+            }
+            if truncated.len() < text.len() {
+                prompt.push_str("... (truncated)\n");
+            }
+            prompt.push_str("```\n");
+        }
+        None => prompt.push_str("(file does not exist on this side)\n"),
+    }
+}
+
+/// Build the structured prompt asking a coding agent to resolve conflicts.
+pub fn build_resolution_prompt(
+    op: &ConflictOp,
+    repo_name: &str,
+    files: &[ConflictedFile],
+) -> String {
+    let (ours, theirs) = side_names(op);
+    let mut prompt = format!(
+        "A git {} in the `{repo_name}` repository stopped with merge conflicts. \
+Resolve every conflict so that the intent of both sides is preserved.\n\n\
+Rules:\n\
+- Edit the conflicted files in place and remove all conflict markers (<<<<<<<, =======, >>>>>>>).\n\
+- Do not run `git {} --continue`, `--abort`, commit, reset or checkout; the operation is continued for you.\n\
+- Keep changes limited to resolving the conflicts.\n\n\
+Sides ({ours}; {theirs}).\n\n## Conflicted files\n",
+        op_label(op),
+        op_label(op),
+    );
+    for file in files {
+        prompt.push_str(&format!("\n### {}\n", file.path));
+        push_side(&mut prompt, "Base (common ancestor)", &file.sides.base);
+        push_side(&mut prompt, "Ours", &file.sides.ours);
+        push_side(&mut prompt, "Theirs", &file.sides.theirs);
+    }
+    prompt
+}
+
+/// Prompt for a further round when conflict markers are still present.
+pub fn build_retry_prompt(op: &ConflictOp, remaining: &[String]) -> String {
+    format!(
+        "Conflict markers are still present in the following files, so the {} cannot be continued:\n{}\n\n\
+Remove every remaining conflict marker while preserving the intent of both sides.",
+        op_label(op),
+        remaining
+            .iter()
+            .map(|f| format!("- {f}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )
+}
+
+fn has_conflict_markers(content: &str) -> bool {
+    let mut saw_start = false;
+    let mut saw_separator = false;
+    for line in content.lines() {
+        if line.starts_with("<<<<<<< ") || line == "<<<<<<<" {
+            saw_start = true;
+        } else if saw_start && line.starts_with("=======") {
+            saw_separator = true;
+        } else if saw_separator && (line.starts_with(">>>>>>> ") || line == ">>>>>>>") {
+            return true;
+        }
+    }
+    false
+}
+
+/// Return the subset of `files` that still contain conflict markers.
+/// Missing files are treated as resolved (the agent may have deleted them).
+pub fn files_with_conflict_markers(worktree_path: &Path, files: &[String]) -> Vec<String> {
+    files
+        .iter()
+        .filter(|f| {
+            std::fs::read(worktree_path.join(f))
+                .map(|bytes| has_conflict_markers(&String::from_utf8_lossy(&bytes)))
+                .unwrap_or(false)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Run a repo's test script in the worktree and capture its combined output.
+pub async fn run_test_script(
+    worktree_path: PathBuf,
+    script: &str,
+) -> Result<TestScriptOutcome, ConflictResolutionError> {
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let child = tokio::process::Command::new(shell_cmd)
+        .arg(shell_arg)
+        .arg(script)
+        .current_dir(&worktree_path)
+        .kill_on_drop(true)
+        .output();
+
+    let output = tokio::time::timeout(TEST_SCRIPT_TIMEOUT, child)
+        .await
+        .map_err(|_| {
+            ConflictResolutionError::TestScript(format!(
+                "timed out after {}s",
+                TEST_SCRIPT_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(|e| ConflictResolutionError::TestScript(e.to_string()))?;
+
+    let mut combined = String::from_utf8_lossy(&output.stdout).to_string();
+    combined.push_str(&String::from_utf8_lossy(&output.stderr));
+    let mut start = combined.len().saturating_sub(MAX_TEST_OUTPUT_BYTES);
+    while !combined.is_char_boundary(start) {
+        start += 1;
+    }
+
+    Ok(TestScriptOutcome {
+        success: output.status.success(),
+        output: combined[start..].to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_complete_marker_blocks_only() {
+        let conflicted = "a\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\nb\n";
+        assert!(has_conflict_markers(conflicted));
+
+        let resolved = "a\nours\ntheirs\nb\n";
+        assert!(!has_conflict_markers(resolved));
+
+        // A lone separator (e.g. markdown heading underline) is not a conflict
+        let markdown = "Title\n=======\nbody\n";
+        assert!(!has_conflict_markers(markdown));
+    }
+
+    #[test]
+    fn prompt_includes_all_sides() {
+        let files = vec![ConflictedFile {
+            path: "src/lib.rs".to_string(),
+            sides: ConflictSides {
+                base: Some("base\n".to_string()),
+                ours: Some("ours\n".to_string()),
+                theirs: None,
+            },
+        }];
+        let prompt = build_resolution_prompt(&ConflictOp::Rebase, "repo", &files);
+        assert!(prompt.contains("git rebase"));
+        assert!(prompt.contains("### src/lib.rs"));
+        assert!(prompt.contains("base\n"));
+        assert!(prompt.contains("ours\n"));
+        assert!(prompt.contains("(file does not exist on this side)"));
+    }
+
+    #[test]
+    fn only_one_active_resolution_per_workspace() {
+        let service = ConflictResolutionService::new();
+        let workspace_id = Uuid::new_v4();
+        service
+            .begin(
+                workspace_id,
+                Uuid::new_v4(),
+                ConflictOp::Merge,
+                vec![],
+                None,
+            )
+            .unwrap();
+        assert!(matches!(
+            service.begin(
+                workspace_id,
+                Uuid::new_v4(),
+                ConflictOp::Merge,
+                vec![],
+                None
+            ),
+            Err(ConflictResolutionError::AlreadyInProgress)
+        ));
+
+        service.finish(workspace_id, ConflictResolutionState::Aborted, "gave up");
+        assert!(!service.is_active(workspace_id));
+        assert!(
+            service
+                .begin(
+                    workspace_id,
+                    Uuid::new_v4(),
+                    ConflictOp::Merge,
+                    vec![],
+                    None
+                )
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn concurrent_begins_start_one_resolution() {
+        let service = ConflictResolutionService::new();
+        let workspace_id = Uuid::new_v4();
+        let started = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        service.begin(
+                            workspace_id,
+                            Uuid::new_v4(),
+                            ConflictOp::Rebase,
+                            vec![],
+                            None,
+                        )
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .filter(Result::is_ok)
+                .count()
+        });
+        assert_eq!(started, 1);
+    }
+
+    #[test]
+    fn agent_exiting_before_registration_claims_the_round() {
+        let service = ConflictResolutionService::new();
+        let workspace_id = Uuid::new_v4();
+        service
+            .begin(
+                workspace_id,
+                Uuid::new_v4(),
+                ConflictOp::Rebase,
+                vec![],
+                None,
+            )
+            .unwrap();
+
+        // The agent exits before its starter records the process id
+        let agent = Uuid::new_v4();
+        let claimed = service.claim_for_process(workspace_id, agent).unwrap();
+        assert_eq!(claimed.execution_process_id, Some(agent));
+
+        // The late registration doesn't replace it, and other processes don't match
+        let current = service
+            .set_round_process(workspace_id, 1, Uuid::new_v4())
+            .unwrap();
+        assert_eq!(current.execution_process_id, Some(agent));
+        assert!(
+            service
+                .claim_for_process(workspace_id, Uuid::new_v4())
+                .is_none()
+        );
+        assert!(service.claim_for_process(workspace_id, agent).is_some());
+
+        // A registration for a round that already moved on is ignored
+        service.update(workspace_id, |r| {
+            r.round = 2;
+            r.execution_process_id = None;
+        });
+        let current = service
+            .set_round_process(workspace_id, 1, Uuid::new_v4())
+            .unwrap();
+        assert_eq!(current.execution_process_id, None);
+    }
+}
@@ -18,9 +18,13 @@ use db::{
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        execution_queue::QueuedExecution,
+        merge::PrMerge,
         project::{Project, UpdateProject},
         project_repo::{ProjectRepo, ProjectRepoWithName},
+        project_script::{ProjectScript, ScriptPipeline},
         repo::Repo,
+        scratch::DraftFollowUpData,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
         workspace::{Workspace, WorkspaceError},
@@ -30,6 +34,7 @@ use db::{
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
@@ -43,17 +48,36 @@ use thiserror::Error;
 use tokio::{sync::RwLock, task::JoinHandle};
 use utils::{
     log_msg::LogMsg,
+    metrics,
     msg_store::MsgStore,
     text::{git_branch_id, short_uuid},
 };
 use uuid::Uuid;
 
 use crate::services::{
-    git::{GitService, GitServiceError},
-    notification::NotificationService,
+    conflict_resolution::{
+        ConflictResolution, ConflictResolutionError, ConflictResolutionService,
+        ConflictResolutionState, ConflictedFile, MAX_RESOLUTION_ROUNDS, build_resolution_prompt,
+        build_retry_prompt, files_with_conflict_markers, run_test_script,
+    },
+    dependency_cache::DependencyCacheService,
+    dev_server::DevServerService,
+    git::{ConflictSides, GitService, GitServiceError},
+    git_host::{
+        GitHostConfig, GitHostError, GitHostProvider, GitHostRepoInfo, host_settings,
+        resolve_provider, validate_token_secret,
+    },
+    notification::{Notification, NotificationEvent, NotificationService},
+    pr_review::{PrFollowUpKind, PrReviewFollowUp, PrReviewService, build_reply},
+    project_scripts,
+    queued_message::QueuedMessageService,
+    scheduler::ExecutionScheduler,
+    secrets::SecretsService,
     share::SharePublisher,
+    webhooks::WebhookService,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
+    worktree_pool::WorktreePool,
 };
 pub type ContainerRef = String;
 
@@ -73,6 +97,10 @@ pub enum ContainerError {
     WorkspaceManager(#[from] WorkspaceManagerError),
     #[error(transparent)]
     Session(#[from] SessionError),
+    #[error(transparent)]
+    ConflictResolution(#[from] ConflictResolutionError),
+    #[error(transparent)]
+    GitHost(#[from] GitHostError),
     #[error("Io error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Failed to kill process: {0}")]
@@ -93,6 +121,24 @@ pub trait ContainerService {
 
     fn notification_service(&self) -> &NotificationService;
 
+    fn webhooks(&self) -> &WebhookService;
+
+    fn conflict_resolution_service(&self) -> &ConflictResolutionService;
+
+    fn queued_message_service(&self) -> &QueuedMessageService;
+
+    fn pr_review_service(&self) -> &PrReviewService;
+
+    fn worktree_pool(&self) -> &WorktreePool;
+
+    fn scheduler(&self) -> &ExecutionScheduler;
+
+    fn dev_servers(&self) -> &DevServerService;
+
+    fn secrets(&self) -> &SecretsService;
+
+    fn dependency_caches(&self) -> &DependencyCacheService;
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf;
 
     async fn create(&self, workspace: &Workspace) -> Result<ContainerRef, ContainerError>;
@@ -101,7 +147,7 @@ pub trait ContainerService {
 
     async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError>;
 
-    /// Check if a task has any running execution processes
+    /// Check if a task has any running or queued execution processes
     async fn has_running_processes(&self, task_id: Uuid) -> Result<bool, ContainerError> {
         let workspaces = Workspace::fetch_all(&self.db().pool, Some(task_id)).await?;
 
@@ -112,7 +158,10 @@ pub trait ContainerService {
                     ExecutionProcess::find_by_session_id(&self.db().pool, session.id, false).await
                 {
                     for process in processes {
-                        if process.status == ExecutionProcessStatus::Running {
+                        if matches!(
+                            process.status,
+                            ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+                        ) {
                             return Ok(true);
                         }
                     }
@@ -125,14 +174,14 @@ pub trait ContainerService {
 
     /// A context is finalized when
     /// - Always when the execution process has failed or been killed
-    /// - Never when the run reason is DevServer
+    /// - Never when the run reason is DevServer or Script
     /// - Never when a setup script has no next_action (parallel mode)
     /// - The next action is None (no follow-up actions)
     fn should_finalize(&self, ctx: &ExecutionContext) -> bool {
-        // Never finalize DevServer processes
+        // Never finalize DevServer processes or scripts run on demand
         if matches!(
             ctx.execution_process.run_reason,
-            ExecutionProcessRunReason::DevServer
+            ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::Script
         ) {
             return false;
         }
@@ -190,14 +239,20 @@ pub trait ContainerService {
         }
 
         let title = format!("Task Complete: {}", ctx.task.title);
-        let message = match ctx.execution_process.status {
-            ExecutionProcessStatus::Completed => format!(
-                "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {:?}",
-                ctx.task.title, ctx.workspace.branch, ctx.session.executor
+        let (event, message) = match ctx.execution_process.status {
+            ExecutionProcessStatus::Completed => (
+                NotificationEvent::AttemptFinished,
+                format!(
+                    "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {:?}",
+                    ctx.task.title, ctx.workspace.branch, ctx.session.executor
+                ),
             ),
-            ExecutionProcessStatus::Failed => format!(
-                "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {:?}",
-                ctx.task.title, ctx.workspace.branch, ctx.session.executor
+            ExecutionProcessStatus::Failed => (
+                NotificationEvent::AttemptFailed,
+                format!(
+                    "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {:?}",
+                    ctx.task.title, ctx.workspace.branch, ctx.session.executor
+                ),
             ),
             _ => {
                 tracing::warn!(
@@ -207,7 +262,12 @@ pub trait ContainerService {
                 return;
             }
         };
-        self.notification_service().notify(&title, &message).await;
+        self.notification_service()
+            .notify(
+                Notification::new(event, title, message)
+                    .with_task(&ctx.task.title, &ctx.workspace.branch),
+            )
+            .await;
     }
 
     /// Cleanup executions marked as running in the db, call at startup
@@ -262,6 +322,14 @@ pub trait ContainerService {
             }
             // Process marked as failed
             tracing::info!("Marked orphaned execution process {} as failed", process.id);
+            // Roll back a conflict resolution the agent was working on
+            if matches!(process.run_reason, ExecutionProcessRunReason::CodingAgent)
+                && let Ok(Some(session)) =
+                    Session::find_by_id(&self.db().pool, process.session_id).await
+            {
+                self.advance_conflict_resolution(session.workspace_id, process.id)
+                    .await;
+            }
             // Update task status to InReview for coding agent and setup script failures
             if matches!(
                 process.run_reason,
@@ -295,9 +363,52 @@ pub trait ContainerService {
                 }
             }
         }
+        self.abort_interrupted_conflict_resolutions().await;
         Ok(())
     }
 
+    /// Roll back conflict resolutions that were still verifying (or waiting
+    /// for an agent that never started) when the server stopped
+    async fn abort_interrupted_conflict_resolutions(&self) {
+        let service = self.conflict_resolution_service();
+        for resolution in service.active() {
+            let ctx = match resolution.execution_process_id {
+                Some(id) => ExecutionProcess::load_context(&self.db().pool, id)
+                    .await
+                    .ok(),
+                None => None,
+            };
+            let worktree = ctx.as_ref().and_then(|ctx| {
+                ctx.repos
+                    .iter()
+                    .find(|r| r.id == resolution.repo_id)
+                    .map(|repo| {
+                        self.workspace_to_current_dir(&ctx.workspace)
+                            .join(&repo.name)
+                    })
+            });
+            match (ctx, worktree) {
+                (Some(ctx), Some(worktree_path)) => {
+                    self.abort_conflict_resolution(
+                        &ctx,
+                        &resolution,
+                        &worktree_path,
+                        "Interrupted by a restart".to_string(),
+                        None,
+                    )
+                    .await;
+                }
+                _ => {
+                    service.finish(
+                        resolution.workspace_id,
+                        ConflictResolutionState::Aborted,
+                        "Interrupted by a restart; abort the operation to roll back",
+                    );
+                }
+            }
+        }
+    }
+
     /// Backfill before_head_commit for legacy execution processes.
     /// Rules:
     /// - If a process has after_head_commit and missing before_head_commit,
@@ -413,6 +524,12 @@ pub trait ContainerService {
                                 } else {
                                     project.default_agent_working_dir.clone()
                                 },
+                                auto_review_follow_ups: None,
+                                auto_fix_ci: None,
+                                dev_server_ports: None,
+                                dev_server_ready_pattern: None,
+                                default_executor_profile: None,
+                                mcp_servers: None,
                             },
                         )
                         .await?;
@@ -424,14 +541,20 @@ pub trait ContainerService {
         Ok(())
     }
 
-    fn cleanup_actions_for_repos(&self, repos: &[ProjectRepoWithName]) -> Option<ExecutorAction> {
+    /// Cleanup scripts of the repos followed by the cleanup pipeline of the project scripts
+    fn cleanup_actions_for_repos(
+        &self,
+        repos: &[ProjectRepoWithName],
+        scripts: &[ProjectScript],
+    ) -> Option<ExecutorAction> {
+        let pipeline = Self::script_pipeline_action(repos, scripts, ScriptPipeline::Cleanup);
         let repos_with_cleanup: Vec<_> = repos
             .iter()
             .filter(|r| r.cleanup_script.is_some())
             .collect();
 
         if repos_with_cleanup.is_empty() {
-            return None;
+            return pipeline;
         }
 
         let mut iter = repos_with_cleanup.iter();
@@ -442,6 +565,9 @@ pub trait ContainerService {
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::CleanupScript,
                 working_dir: Some(first.repo_name.clone()),
+                script_name: None,
+                env: Default::default(),
+                timeout_seconds: None,
             }),
             None,
         );
@@ -453,19 +579,31 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::CleanupScript,
                     working_dir: Some(repo.repo_name.clone()),
+                    script_name: None,
+                    env: Default::default(),
+                    timeout_seconds: None,
                 }),
                 None,
             ));
         }
 
-        Some(root_action)
+        Some(match pipeline {
+            Some(pipeline) => root_action.append_action(pipeline),
+            None => root_action,
+        })
     }
 
-    fn setup_actions_for_repos(&self, repos: &[ProjectRepoWithName]) -> Option<ExecutorAction> {
+    /// Setup scripts of the repos followed by the setup pipeline of the project scripts
+    fn setup_actions_for_repos(
+        &self,
+        repos: &[ProjectRepoWithName],
+        scripts: &[ProjectScript],
+    ) -> Option<ExecutorAction> {
+        let pipeline = Self::script_pipeline_action(repos, scripts, ScriptPipeline::Setup);
         let repos_with_setup: Vec<_> = repos.iter().filter(|r| r.setup_script.is_some()).collect();
 
         if repos_with_setup.is_empty() {
-            return None;
+            return pipeline;
         }
 
         let mut iter = repos_with_setup.iter();
@@ -476,6 +614,9 @@ pub trait ContainerService {
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::SetupScript,
                 working_dir: Some(first.repo_name.clone()),
+                script_name: None,
+                env: Default::default(),
+                timeout_seconds: None,
             }),
             None,
         );
@@ -487,12 +628,30 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::SetupScript,
                     working_dir: Some(repo.repo_name.clone()),
+                    script_name: None,
+                    env: Default::default(),
+                    timeout_seconds: None,
                 }),
                 None,
             ));
         }
 
-        Some(root_action)
+        Some(match pipeline {
+            Some(pipeline) => root_action.append_action(pipeline),
+            None => root_action,
+        })
+    }
+
+    /// Steps of a project script pipeline; a broken dependency graph skips the pipeline
+    fn script_pipeline_action(
+        repos: &[ProjectRepoWithName],
+        scripts: &[ProjectScript],
+        pipeline: ScriptPipeline,
+    ) -> Option<ExecutorAction> {
+        project_scripts::pipeline_action(scripts, repos, pipeline, None).unwrap_or_else(|e| {
+            tracing::warn!("Skipping the {:?} script pipeline: {}", pipeline, e);
+            None
+        })
     }
 
     fn setup_action_for_repo(repo: &ProjectRepoWithName) -> Option<ExecutorAction> {
@@ -503,6 +662,9 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::SetupScript,
                     working_dir: Some(repo.repo_name.clone()),
+                    script_name: None,
+                    env: Default::default(),
+                    timeout_seconds: None,
                 }),
                 None,
             )
@@ -522,6 +684,9 @@ pub trait ContainerService {
                         language: ScriptRequestLanguage::Bash,
                         context: ScriptContext::SetupScript,
                         working_dir: Some(repo.repo_name.clone()),
+                        script_name: None,
+                        env: Default::default(),
+                        timeout_seconds: None,
                     }),
                     Some(Box::new(chained)),
                 );
@@ -548,7 +713,15 @@ pub trait ContainerService {
                     {
                         continue;
                     }
-                    if process.status == ExecutionProcessStatus::Running {
+                    if process.status == ExecutionProcessStatus::Queued {
+                        if let Err(e) = self.cancel_queued_execution(process.id).await {
+                            tracing::debug!(
+                                "Failed to cancel queued execution process {}: {}",
+                                process.id,
+                                e
+                            );
+                        }
+                    } else if process.status == ExecutionProcessStatus::Running {
                         self.stop_execution(&process, ExecutionProcessStatus::Killed)
                             .await
                             .unwrap_or_else(|e| {
@@ -609,6 +782,41 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
+    async fn git_hosts(&self) -> Vec<GitHostConfig>;
+
+    async fn pr_review_authors(&self) -> Vec<String>;
+
+    /// Provider for a repository of `project_id`, with its token read from
+    /// the secrets store
+    async fn git_host_provider(
+        &self,
+        project_id: Option<Uuid>,
+        repo_info: &GitHostRepoInfo,
+    ) -> Result<Arc<dyn GitHostProvider>, GitHostError> {
+        let pool = &self.db().pool;
+        let unavailable = |e: &dyn std::fmt::Display| GitHostError::TokenUnavailable(e.to_string());
+        let project = match project_id {
+            Some(id) => Project::find_by_id(pool, id)
+                .await
+                .map_err(|e| unavailable(&e))?,
+            None => None,
+        };
+        let project_host = project.as_ref().and_then(|p| p.git_host.as_deref());
+        let settings = host_settings(repo_info, &self.git_hosts().await, project_host);
+        let token = match &settings.token_secret {
+            Some(name) => Some({
+                validate_token_secret(name)?;
+                self.secrets()
+                    .resolve_named(pool, name, project_id)
+                    .await
+                    .map_err(|e| unavailable(&e))?
+                    .ok_or_else(|| GitHostError::MissingTokenSecret(name.clone()))?
+            }),
+            None => None,
+        };
+        resolve_provider(repo_info, &settings, token)
+    }
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         let prefix = self.git_branch_prefix().await;
@@ -900,6 +1108,8 @@ pub trait ContainerService {
 
         let project_repos =
             ProjectRepo::find_by_project_id_with_names(&self.db().pool, project.id).await?;
+        let project_scripts =
+            ProjectScript::find_by_project_id(&self.db().pool, project.id).await?;
 
         let workspace = Workspace::find_by_id(&self.db().pool, workspace.id)
             .await?
@@ -918,14 +1128,16 @@ pub trait ContainerService {
 
         let prompt = task.to_prompt();
 
+        // Pooled worktrees had their setup script run when they were prepared
+        let prepared = self.worktree_pool().take_prepared(workspace.id);
         let repos_with_setup: Vec<_> = project_repos
             .iter()
-            .filter(|pr| pr.setup_script.is_some())
+            .filter(|pr| pr.setup_script.is_some() && !prepared.contains(&pr.repo_id))
             .collect();
 
         let all_parallel = repos_with_setup.iter().all(|pr| pr.parallel_setup_script);
 
-        let cleanup_action = self.cleanup_actions_for_repos(&project_repos);
+        let cleanup_action = self.cleanup_actions_for_repos(&project_repos, &project_scripts);
 
         let working_dir = workspace
             .agent_working_dir
@@ -942,6 +1154,20 @@ pub trait ContainerService {
             cleanup_action.map(Box::new),
         );
 
+        // The setup pipeline of the project scripts runs after the repo setup scripts
+        let coding_action = match Self::script_pipeline_action(
+            &project_repos,
+            &project_scripts,
+            ScriptPipeline::Setup,
+        ) {
+            Some(pipeline) => pipeline.append_action(coding_action),
+            None => coding_action,
+        };
+        let main_run_reason = match coding_action.typ() {
+            ExecutorActionType::ScriptRequest(_) => ExecutionProcessRunReason::SetupScript,
+            _ => ExecutionProcessRunReason::CodingAgent,
+        };
+
         let execution_process = if all_parallel {
             // All parallel: start each setup independently, then start coding agent
             for repo in &repos_with_setup {
@@ -958,13 +1184,8 @@ pub trait ContainerService {
                     tracing::warn!(?e, "Failed to start setup script in parallel mode");
                 }
             }
-            self.start_execution(
-                &workspace,
-                &session,
-                &coding_action,
-                &ExecutionProcessRunReason::CodingAgent,
-            )
-            .await?
+            self.start_execution(&workspace, &session, &coding_action, &main_run_reason)
+                .await?
         } else {
             // Any sequential: chain ALL setups → coding agent via next_action
             let main_action = Self::build_sequential_setup_chain(&repos_with_setup, coding_action);
@@ -993,7 +1214,10 @@ pub trait ContainerService {
             .await?
             .ok_or(SqlxError::RowNotFound)?;
         if task.status != TaskStatus::InProgress
-            && run_reason != &ExecutionProcessRunReason::DevServer
+            && !matches!(
+                run_reason,
+                ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::Script
+            )
         {
             Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
 
@@ -1040,10 +1264,30 @@ pub trait ContainerService {
             run_reason: run_reason.clone(),
         };
 
+        // Coding agents wait in the execution queue while the concurrency limits
+        // are reached. The scheduler lock is held until the process is either
+        // running or fully queued, so the dispatcher never sees half of it.
+        let scheduler_guard = if run_reason == &ExecutionProcessRunReason::CodingAgent {
+            Some(self.scheduler().lock().await)
+        } else {
+            None
+        };
+        let status = if scheduler_guard.is_some()
+            && !self
+                .scheduler()
+                .can_start_now(session.executor.as_deref(), task.project_id)
+                .await?
+        {
+            ExecutionProcessStatus::Queued
+        } else {
+            ExecutionProcessStatus::Running
+        };
+
         let execution_process = ExecutionProcess::create(
             &self.db().pool,
             &create_execution_process,
             Uuid::new_v4(),
+            status,
             &repo_states,
         )
         .await?;
@@ -1072,8 +1316,44 @@ pub trait ContainerService {
             .await?;
         }
 
+        if execution_process.status == ExecutionProcessStatus::Queued {
+            QueuedExecution::enqueue(&self.db().pool, execution_process.id, 0).await?;
+            tracing::info!(
+                "Queued execution process {} until an agent slot is free",
+                execution_process.id
+            );
+            return Ok(execution_process);
+        }
+        drop(scheduler_guard);
+
+        self.launch_execution(
+            workspace,
+            session,
+            &task,
+            &execution_process,
+            executor_action,
+        )
+        .await?;
+        Ok(execution_process)
+    }
+
+    /// Spawn a created execution process and start processing its logs. On
+    /// failure the process is marked failed and the task goes back to review.
+    async fn launch_execution(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        task: &Task,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) -> Result<(), ContainerError> {
+        metrics::EXECUTION_PROCESSES_STARTED.inc(&[
+            session.executor.as_deref().unwrap_or("none"),
+            &metrics::label(&execution_process.run_reason),
+        ]);
+
         if let Err(start_error) = self
-            .start_execution_inner(workspace, &execution_process, executor_action)
+            .start_execution_inner(workspace, execution_process, executor_action)
             .await
         {
             // Mark process as failed
@@ -1155,7 +1435,145 @@ pub trait ContainerService {
         }
 
         self.spawn_stream_raw_logs_to_db(&execution_process.id);
-        Ok(execution_process)
+        Ok(())
+    }
+
+    /// Start queued coding agent runs while the concurrency limits allow
+    async fn dispatch_queued_executions(&self) -> Result<(), ContainerError> {
+        let _guard = self.scheduler().lock().await;
+        while let Some(process_id) = self.scheduler().next_ready().await? {
+            QueuedExecution::dequeue(&self.db().pool, process_id).await?;
+            // A run that cannot be started fails on its own instead of
+            // stopping the runs queued behind it
+            let ctx = match ExecutionProcess::load_context(&self.db().pool, process_id).await {
+                Ok(ctx) => ctx,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to load queued execution process {}: {}",
+                        process_id,
+                        e
+                    );
+                    self.fail_queued_execution(process_id, None).await;
+                    continue;
+                }
+            };
+            let executor_action = match ctx.execution_process.executor_action() {
+                Ok(action) => action.clone(),
+                Err(e) => {
+                    tracing::error!(
+                        "Queued execution process {} has no valid action: {}",
+                        process_id,
+                        e
+                    );
+                    self.fail_queued_execution(process_id, Some(ctx.task.id))
+                        .await;
+                    continue;
+                }
+            };
+            // Runs ahead in the queue may have committed since this one was queued
+            if let Err(e) = self
+                .refresh_before_head_commits(&ctx.workspace, process_id)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to refresh the starting commits of execution process {}: {}",
+                    process_id,
+                    e
+                );
+            }
+            ExecutionProcess::mark_started(&self.db().pool, process_id).await?;
+            tracing::info!("Starting queued execution process {}", process_id);
+            if let Err(e) = self
+                .launch_execution(
+                    &ctx.workspace,
+                    &ctx.session,
+                    &ctx.task,
+                    &ctx.execution_process,
+                    &executor_action,
+                )
+                .await
+            {
+                tracing::error!(
+                    "Failed to start queued execution process {}: {}",
+                    process_id,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Record each repository's current HEAD as the "before" commit of a
+    /// queued run that is about to start
+    async fn refresh_before_head_commits(
+        &self,
+        workspace: &Workspace,
+        process_id: Uuid,
+    ) -> Result<(), ContainerError> {
+        let Some(workspace_root) = workspace.container_ref.as_ref().map(PathBuf::from) else {
+            return Ok(());
+        };
+        let repositories =
+            WorkspaceRepo::find_repos_for_workspace(&self.db().pool, workspace.id).await?;
+        for repo in repositories {
+            let head = self.git().get_head_info(&workspace_root.join(&repo.name))?;
+            ExecutionProcessRepoState::update_before_head_commit(
+                &self.db().pool,
+                process_id,
+                repo.id,
+                &head.oid,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Mark a dequeued run that could not be started as failed and send its
+    /// task back to review
+    async fn fail_queued_execution(&self, process_id: Uuid, task_id: Option<Uuid>) {
+        if let Err(e) = ExecutionProcess::update_completion(
+            &self.db().pool,
+            process_id,
+            ExecutionProcessStatus::Failed,
+            None,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to mark queued execution process {} as failed: {}",
+                process_id,
+                e
+            );
+        }
+        if let Some(task_id) = task_id
+            && let Err(e) =
+                Task::update_status(&self.db().pool, task_id, TaskStatus::InReview).await
+        {
+            tracing::error!("Failed to move task {} back to review: {}", task_id, e);
+        }
+    }
+
+    /// Remove a queued run and mark it killed, as if it had been stopped
+    async fn cancel_queued_execution(&self, process_id: Uuid) -> Result<bool, ContainerError> {
+        let guard = self.scheduler().lock().await;
+        if !QueuedExecution::dequeue(&self.db().pool, process_id).await? {
+            return Ok(false);
+        }
+        ExecutionProcess::update_completion(
+            &self.db().pool,
+            process_id,
+            ExecutionProcessStatus::Killed,
+            None,
+        )
+        .await?;
+        drop(guard);
+
+        let ctx = ExecutionProcess::load_context(&self.db().pool, process_id).await?;
+        if !self.has_running_processes(ctx.task.id).await? {
+            Task::update_status(&self.db().pool, ctx.task.id, TaskStatus::InReview).await?;
+        }
+        self.scheduler().wake();
+        Ok(true)
     }
 
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
@@ -1169,8 +1587,12 @@ pub trait ContainerService {
 
         // Determine the run reason of the next action
         let next_run_reason = match (action.typ(), next_action.typ()) {
-            (ExecutorActionType::ScriptRequest(_), ExecutorActionType::ScriptRequest(_)) => {
-                ExecutionProcessRunReason::SetupScript
+            (ExecutorActionType::ScriptRequest(_), ExecutorActionType::ScriptRequest(next)) => {
+                match next.context {
+                    ScriptContext::CleanupScript => ExecutionProcessRunReason::CleanupScript,
+                    ScriptContext::NamedScript => ExecutionProcessRunReason::Script,
+                    _ => ExecutionProcessRunReason::SetupScript,
+                }
             }
             (
                 ExecutorActionType::CodingAgentInitialRequest(_)
@@ -1190,4 +1612,669 @@ pub trait ContainerService {
         tracing::debug!("Started next action: {:?}", next_action);
         Ok(())
     }
+
+    /// Start a coding agent turn that resolves the conflicts of the rebase/merge
+    /// currently stopped in `repo`'s worktree. The outcome is driven by
+    /// `advance_conflict_resolution` once the agent exits.
+    async fn start_conflict_resolution(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+    ) -> Result<ConflictResolution, ContainerError> {
+        let worktree_path = self.workspace_to_current_dir(workspace).join(&repo.name);
+        let op = self
+            .git()
+            .detect_conflict_op(&worktree_path)?
+            .ok_or_else(|| anyhow!("No rebase or merge is in progress for '{}'", repo.name))?;
+        let files = self.git().get_conflicted_files(&worktree_path)?;
+        let restore_oid = self
+            .git()
+            .get_branch_oid(&worktree_path, &workspace.branch)
+            .ok();
+
+        let mut conflicted = Vec::with_capacity(files.len());
+        for path in &files {
+            let sides = self.git().get_conflict_sides(&worktree_path, path)?;
+            conflicted.push(ConflictedFile {
+                path: path.clone(),
+                sides,
+            });
+        }
+        let prompt = build_resolution_prompt(&op, &repo.name, &conflicted);
+
+        let service = self.conflict_resolution_service();
+        let resolution = service.begin(workspace.id, repo.id, op, files, restore_oid)?;
+
+        match self.start_agent_turn(workspace, prompt).await {
+            Ok(execution_process) => Ok(service
+                .set_round_process(workspace.id, resolution.round, execution_process.id)
+                .ok_or_else(|| anyhow!("Conflict resolution disappeared"))?),
+            Err(e) => {
+                service.clear(workspace.id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Start a follow-up in the workspace's latest session with the given prompt.
+    /// No cleanup script is chained (the worktree may be mid-operation).
+    async fn start_agent_turn(
+        &self,
+        workspace: &Workspace,
+        prompt: String,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let session = Session::find_latest_by_workspace_id(pool, workspace.id)
+            .await?
+            .ok_or_else(|| anyhow!("Workspace has no session to continue"))?;
+
+        let executor_profile_id =
+            ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+                .await
+                .map_err(|e| anyhow!("Failed to get executor profile: {e}"))?;
+        let latest_agent_session_id =
+            ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, session.id).await?;
+
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+
+        let action_type = if let Some(agent_session_id) = latest_agent_session_id {
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt,
+                session_id: agent_session_id,
+                executor_profile_id,
+                working_dir,
+            })
+        } else {
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt,
+                executor_profile_id,
+                working_dir,
+            })
+        };
+        let action = ExecutorAction::new(action_type, None);
+
+        self.start_execution(
+            workspace,
+            &session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
+    }
+
+    /// Called when a coding agent process of the workspace exits. If it was
+    /// working on the workspace's conflict resolution, check the markers,
+    /// continue the operation and verify it with the repo's test script,
+    /// rolling back on failure.
+    async fn advance_conflict_resolution(&self, workspace_id: Uuid, execution_process_id: Uuid) {
+        let service = self.conflict_resolution_service();
+        let Some(resolution) = service.claim_for_process(workspace_id, execution_process_id) else {
+            return;
+        };
+
+        let ctx = match ExecutionProcess::load_context(&self.db().pool, execution_process_id).await
+        {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                tracing::error!("Failed to load context for conflict resolution: {}", e);
+                service.finish(
+                    workspace_id,
+                    ConflictResolutionState::Aborted,
+                    format!("Failed to load execution context: {e}"),
+                );
+                return;
+            }
+        };
+        let Some(repo) = ctx.repos.iter().find(|r| r.id == resolution.repo_id) else {
+            service.finish(
+                workspace_id,
+                ConflictResolutionState::Aborted,
+                "Repository is no longer part of this workspace",
+            );
+            return;
+        };
+        let worktree_path = self
+            .workspace_to_current_dir(&ctx.workspace)
+            .join(&repo.name);
+
+        if !matches!(
+            ctx.execution_process.status,
+            ExecutionProcessStatus::Completed
+        ) {
+            self.abort_conflict_resolution(
+                &ctx,
+                &resolution,
+                &worktree_path,
+                "Coding agent did not complete successfully".to_string(),
+                None,
+            )
+            .await;
+            return;
+        }
+
+        if let Ok(Some(op)) = self.git().detect_conflict_op(&worktree_path) {
+            let remaining = files_with_conflict_markers(&worktree_path, &resolution.files);
+            if !remaining.is_empty() {
+                let prompt = build_retry_prompt(&op, &remaining);
+                self.retry_conflict_resolution(
+                    &ctx,
+                    &resolution,
+                    &worktree_path,
+                    remaining,
+                    prompt,
+                )
+                .await;
+                return;
+            }
+
+            match self
+                .git()
+                .continue_conflicts(&worktree_path, &resolution.files)
+            {
+                Ok(()) => {}
+                Err(GitServiceError::MergeConflicts(_)) => {
+                    // e.g. the next commit of a rebase conflicts as well
+                    let files = self
+                        .git()
+                        .get_conflicted_files(&worktree_path)
+                        .unwrap_or_default();
+                    let conflicted = files
+                        .iter()
+                        .map(|path| ConflictedFile {
+                            path: path.clone(),
+                            sides: self
+                                .git()
+                                .get_conflict_sides(&worktree_path, path)
+                                .unwrap_or(ConflictSides {
+                                    base: None,
+                                    ours: None,
+                                    theirs: None,
+                                }),
+                        })
+                        .collect::<Vec<_>>();
+                    let prompt = build_resolution_prompt(&op, &repo.name, &conflicted);
+                    self.retry_conflict_resolution(
+                        &ctx,
+                        &resolution,
+                        &worktree_path,
+                        files,
+                        prompt,
+                    )
+                    .await;
+                    return;
+                }
+                Err(e) => {
+                    self.abort_conflict_resolution(
+                        &ctx,
+                        &resolution,
+                        &worktree_path,
+                        format!("Failed to continue {op:?}: {e}"),
+                        None,
+                    )
+                    .await;
+                    return;
+                }
+            }
+
+            if let Ok(Some(op)) = self.git().detect_conflict_op(&worktree_path) {
+                self.abort_conflict_resolution(
+                    &ctx,
+                    &resolution,
+                    &worktree_path,
+                    format!("{op:?} is still in progress after continuing"),
+                    None,
+                )
+                .await;
+                return;
+            }
+        } else if resolution.restore_oid.is_some()
+            && self
+                .git()
+                .get_branch_oid(&worktree_path, &ctx.workspace.branch)
+                .ok()
+                == resolution.restore_oid
+        {
+            // Nothing in progress and the branch never moved: it was aborted elsewhere
+            service.finish(
+                workspace_id,
+                ConflictResolutionState::Aborted,
+                "The operation was aborted before it could be continued",
+            );
+            return;
+        }
+
+        let test_script = ProjectRepo::find_by_project_and_repo(
+            &self.db().pool,
+            ctx.project.id,
+            resolution.repo_id,
+        )
+        .await
+        .ok()
+        .flatten()
+        .and_then(|pr| pr.test_script)
+        .filter(|script| !script.trim().is_empty());
+
+        let Some(script) = test_script else {
+            service.finish(
+                workspace_id,
+                ConflictResolutionState::Resolved,
+                "Conflicts resolved (no test script configured)",
+            );
+            self.notify_conflict_resolution(&ctx, true).await;
+            return;
+        };
+
+        service.update(workspace_id, |r| {
+            r.state = ConflictResolutionState::Verifying
+        });
+        match run_test_script(worktree_path.clone(), &script).await {
+            Ok(outcome) if outcome.success => {
+                service.update(workspace_id, |r| {
+                    r.state = ConflictResolutionState::Resolved;
+                    r.message = Some("Conflicts resolved and test script passed".to_string());
+                    r.test_output = Some(outcome.output);
+                });
+                self.notify_conflict_resolution(&ctx, true).await;
+            }
+            Ok(outcome) => {
+                self.abort_conflict_resolution(
+                    &ctx,
+                    &resolution,
+                    &worktree_path,
+                    "Test script failed after resolving conflicts".to_string(),
+                    Some(outcome.output),
+                )
+                .await;
+            }
+            Err(e) => {
+                self.abort_conflict_resolution(
+                    &ctx,
+                    &resolution,
+                    &worktree_path,
+                    e.to_string(),
+                    None,
+                )
+                .await;
+            }
+        }
+    }
+
+    /// Start another agent turn for an unfinished resolution, or abort once
+    /// the round limit is reached.
+    async fn retry_conflict_resolution(
+        &self,
+        ctx: &ExecutionContext,
+        resolution: &ConflictResolution,
+        worktree_path: &Path,
+        files: Vec<String>,
+        prompt: String,
+    ) {
+        if resolution.round >= MAX_RESOLUTION_ROUNDS {
+            self.abort_conflict_resolution(
+                ctx,
+                resolution,
+                worktree_path,
+                format!("Conflicts still unresolved after {MAX_RESOLUTION_ROUNDS} attempts"),
+                None,
+            )
+            .await;
+            return;
+        }
+
+        // Move to the next round before the agent starts so that it can claim
+        // the round if it exits before its id is recorded
+        let service = self.conflict_resolution_service();
+        let round = resolution.round + 1;
+        service.update(resolution.workspace_id, |r| {
+            r.round = round;
+            r.files = files;
+            r.execution_process_id = None;
+        });
+
+        match self.start_agent_turn(&ctx.workspace, prompt).await {
+            Ok(execution_process) => {
+                service.set_round_process(resolution.workspace_id, round, execution_process.id);
+            }
+            Err(e) => {
+                self.abort_conflict_resolution(
+                    ctx,
+                    resolution,
+                    worktree_path,
+                    format!("Failed to start coding agent: {e}"),
+                    None,
+                )
+                .await;
+            }
+        }
+    }
+
+    /// Roll the worktree back to its state before the operation started:
+    /// abort the operation if it is still in progress, otherwise reset the
+    /// branch to the recorded tip.
+    async fn abort_conflict_resolution(
+        &self,
+        ctx: &ExecutionContext,
+        resolution: &ConflictResolution,
+        worktree_path: &Path,
+        reason: String,
+        test_output: Option<String>,
+    ) {
+        let in_progress = matches!(self.git().detect_conflict_op(worktree_path), Ok(Some(_)));
+        let rollback = if in_progress {
+            self.git().abort_conflicts(worktree_path)
+        } else if let Some(oid) = &resolution.restore_oid {
+            self.git()
+                .reset_worktree_to_commit(worktree_path, oid, true)
+        } else {
+            Ok(())
+        };
+        let message = match rollback {
+            Ok(()) => reason,
+            Err(e) => format!("{reason}; rollback failed: {e}"),
+        };
+        tracing::warn!(
+            "Conflict resolution for workspace {} aborted: {}",
+            resolution.workspace_id,
+            message
+        );
+
+        self.conflict_resolution_service()
+            .update(resolution.workspace_id, |r| {
+                r.state = ConflictResolutionState::Aborted;
+                r.message = Some(message);
+                if test_output.is_some() {
+                    r.test_output = test_output;
+                }
+            });
+        self.notify_conflict_resolution(ctx, false).await;
+    }
+
+    async fn notify_conflict_resolution(&self, ctx: &ExecutionContext, success: bool) {
+        let (title, message) = if success {
+            (
+                format!("Conflicts Resolved: {}", ctx.task.title),
+                format!(
+                    "✅ '{}' conflicts were resolved\nBranch: {:?}",
+                    ctx.task.title, ctx.workspace.branch
+                ),
+            )
+        } else {
+            (
+                format!("Conflict Resolution Failed: {}", ctx.task.title),
+                format!(
+                    "❌ '{}' conflicts could not be resolved and were rolled back\nBranch: {:?}",
+                    ctx.task.title, ctx.workspace.branch
+                ),
+            )
+        };
+        let event = if success {
+            NotificationEvent::AttemptFinished
+        } else {
+            NotificationEvent::AttemptFailed
+        };
+        self.notification_service()
+            .notify(
+                Notification::new(event, title, message)
+                    .with_task(&ctx.task.title, &ctx.workspace.branch),
+            )
+            .await;
+    }
+
+    /// Hand PR feedback (new review comments or failing CI) to the coding agent
+    /// in the workspace's latest session: started right away when the
+    /// workspace is idle, queued behind the running process otherwise. Returns
+    /// `false` if nothing was started (a follow-up is already pending, or the
+    /// user has a message queued), so the feedback is picked up again on the
+    /// next poll.
+    async fn start_pr_follow_up(
+        &self,
+        workspace: &Workspace,
+        pr_merge: &PrMerge,
+        prompt: String,
+        kind: PrFollowUpKind,
+    ) -> Result<bool, ContainerError> {
+        let pool = &self.db().pool;
+        let session = Session::find_latest_by_workspace_id(pool, workspace.id)
+            .await?
+            .ok_or_else(|| anyhow!("Workspace has no session to continue"))?;
+        let running = ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+            pool,
+            workspace.id,
+        )
+        .await?;
+        let queued = self.queued_message_service().has_queued(session.id);
+
+        let service = self.pr_review_service();
+        if service.is_pending(workspace.id) {
+            if running || queued {
+                return Ok(false);
+            }
+            // The follow-up was queued but never ran (e.g. the user removed it)
+            service.clear(workspace.id);
+        }
+        if queued || self.conflict_resolution_service().is_active(workspace.id) {
+            return Ok(false);
+        }
+
+        let repo = Repo::find_by_id(pool, pr_merge.repo_id)
+            .await?
+            .ok_or_else(|| anyhow!("Repository {} not found", pr_merge.repo_id))?;
+        let container_ref = self.ensure_container_exists(workspace).await?;
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+
+        service.begin(PrReviewFollowUp {
+            workspace_id: workspace.id,
+            session_id: session.id,
+            repo_id: repo.id,
+            pr_number: pr_merge.pr_info.number,
+            pr_url: pr_merge.pr_info.url.clone(),
+            prompt: prompt.clone(),
+            kind,
+            base_oid: self
+                .git()
+                .get_branch_oid(&worktree_path, &workspace.branch)
+                .ok(),
+            agent_process_id: None,
+            started_at: chrono::Utc::now(),
+        });
+
+        if running {
+            let variant = ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+                .await
+                .ok()
+                .and_then(|profile| profile.variant);
+            self.queued_message_service().queue_message(
+                session.id,
+                DraftFollowUpData {
+                    message: prompt,
+                    variant,
+                },
+            );
+        } else if let Err(e) = self.start_agent_turn(workspace, prompt).await {
+            service.clear(workspace.id);
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    /// Called when any process of a session exits. Once the agent turn of a PR
+    /// follow-up and its chained scripts are done, push the branch (and reply
+    /// on the PR with what changed, for review comments).
+    async fn advance_pr_follow_up(&self, execution_process_id: Uuid) {
+        let service = self.pr_review_service();
+        if service.is_empty() {
+            return;
+        }
+        let Ok(ctx) = ExecutionProcess::load_context(&self.db().pool, execution_process_id).await
+        else {
+            return;
+        };
+        let Some(mut follow_up) = service.find_by_session(ctx.session.id) else {
+            return;
+        };
+
+        if follow_up.agent_process_id.is_none() {
+            let prompt =
+                ctx.execution_process
+                    .executor_action()
+                    .ok()
+                    .and_then(|action| match action.typ() {
+                        ExecutorActionType::CodingAgentInitialRequest(request) => {
+                            Some(request.prompt.as_str())
+                        }
+                        ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                            Some(request.prompt.as_str())
+                        }
+                        ExecutorActionType::ScriptRequest(_) => None,
+                    });
+            if prompt != Some(follow_up.prompt.as_str()) {
+                return;
+            }
+            if !matches!(
+                ctx.execution_process.status,
+                ExecutionProcessStatus::Completed
+            ) {
+                service.clear(follow_up.workspace_id);
+                self.notify_pr_follow_up(
+                    &ctx,
+                    &follow_up,
+                    Err("the coding agent did not complete".to_string()),
+                )
+                .await;
+                return;
+            }
+            service.mark_agent_done(follow_up.workspace_id, ctx.execution_process.id);
+            follow_up.agent_process_id = Some(ctx.execution_process.id);
+        }
+        if !self.should_finalize(&ctx) {
+            return;
+        }
+
+        service.clear(follow_up.workspace_id);
+        let result = self
+            .publish_pr_follow_up(&ctx, &follow_up)
+            .await
+            .map_err(|e| e.to_string());
+        if let Err(e) = &result {
+            tracing::warn!(
+                "PR follow-up for PR #{} in workspace {} failed: {}",
+                follow_up.pr_number,
+                follow_up.workspace_id,
+                e
+            );
+        }
+        self.notify_pr_follow_up(&ctx, &follow_up, result).await;
+    }
+
+    /// Push the commits made for a PR follow-up and, for review comments,
+    /// reply on the PR.
+    async fn publish_pr_follow_up(
+        &self,
+        ctx: &ExecutionContext,
+        follow_up: &PrReviewFollowUp,
+    ) -> Result<(), ContainerError> {
+        let repo = ctx
+            .repos
+            .iter()
+            .find(|r| r.id == follow_up.repo_id)
+            .ok_or_else(|| anyhow!("Repository is no longer part of this workspace"))?;
+        let worktree_path = self
+            .workspace_to_current_dir(&ctx.workspace)
+            .join(&repo.name);
+
+        let head_oid = self
+            .git()
+            .get_branch_oid(&worktree_path, &ctx.workspace.branch)?;
+        let commits = match &follow_up.base_oid {
+            Some(base_oid) if *base_oid != head_oid => {
+                self.git()
+                    .get_commit_subjects_between(&worktree_path, base_oid, &head_oid)?
+            }
+            _ => Vec::new(),
+        };
+        if !commits.is_empty() {
+            self.git()
+                .push_to_github(&worktree_path, &ctx.workspace.branch, false)?;
+        }
+        let PrFollowUpKind::ReviewComments { count } = follow_up.kind else {
+            if commits.is_empty() {
+                return Err(anyhow!("the coding agent made no commits").into());
+            }
+            return Ok(());
+        };
+
+        let summary = match follow_up.agent_process_id {
+            Some(id) => CodingAgentTurn::find_by_execution_process_id(&self.db().pool, id)
+                .await?
+                .and_then(|turn| turn.summary),
+            None => None,
+        };
+        let reply = build_reply(count, &commits, summary.as_deref());
+
+        let repo_info = GitHostRepoInfo::from_remote_url(&follow_up.pr_url)?;
+        let provider = self
+            .git_host_provider(Some(ctx.project.id), &repo_info)
+            .await?;
+        provider
+            .post_pr_comment(&repo_info, follow_up.pr_number, &reply)
+            .await?;
+        Ok(())
+    }
+
+    async fn notify_pr_follow_up(
+        &self,
+        ctx: &ExecutionContext,
+        follow_up: &PrReviewFollowUp,
+        result: Result<(), String>,
+    ) {
+        let event = if result.is_ok() {
+            NotificationEvent::AttemptFinished
+        } else {
+            NotificationEvent::AttemptFailed
+        };
+        let (title, message) = match (&follow_up.kind, result) {
+            (PrFollowUpKind::ReviewComments { count }, Ok(())) => (
+                format!("Review Comments Addressed: {}", ctx.task.title),
+                format!(
+                    "✅ '{}' addressed {} review comment(s) on PR #{} and replied\nBranch: {:?}",
+                    ctx.task.title, count, follow_up.pr_number, ctx.workspace.branch
+                ),
+            ),
+            (PrFollowUpKind::ReviewComments { .. }, Err(reason)) => (
+                format!("Review Follow-up Failed: {}", ctx.task.title),
+                format!(
+                    "❌ '{}' could not address the review comments on PR #{}: {}\nBranch: {:?}",
+                    ctx.task.title, follow_up.pr_number, reason, ctx.workspace.branch
+                ),
+            ),
+            (PrFollowUpKind::CiFix { checks }, Ok(())) => (
+                format!("CI Fix Pushed: {}", ctx.task.title),
+                format!(
+                    "✅ '{}' pushed a fix for {} on PR #{}\nBranch: {:?}",
+                    ctx.task.title,
+                    checks.join(", "),
+                    follow_up.pr_number,
+                    ctx.workspace.branch
+                ),
+            ),
+            (PrFollowUpKind::CiFix { .. }, Err(reason)) => (
+                format!("CI Fix Failed: {}", ctx.task.title),
+                format!(
+                    "❌ '{}' could not fix CI on PR #{}: {}\nBranch: {:?}",
+                    ctx.task.title, follow_up.pr_number, reason, ctx.workspace.branch
+                ),
+            ),
+        };
+        self.notification_service()
+            .notify(
+                Notification::new(event, title, message)
+                    .with_task(&ctx.task.title, &ctx.workspace.branch)
+                    .with_url(&follow_up.pr_url),
+            )
+            .await;
+    }
 }
@@ -0,0 +1,760 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use db::models::{project::Project, project_repo::ProjectRepo};
+use futures::StreamExt;
+use ignore::WalkBuilder;
+use moka::future::Cache;
+use regex::{Regex, RegexBuilder};
+use regex_syntax::hir::{Hir, HirKind};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::{sync::mpsc, task::JoinHandle};
+use tracing::{error, info, warn};
+use ts_rs::TS;
+
+use super::{file_search_cache::SearchMode, filesystem_watcher, sparse_checkout::in_cone};
+
+/// Files larger than this are neither indexed nor searched
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// Files with a NUL byte in this many leading bytes are treated as binary
+const BINARY_SNIFF_BYTES: usize = 8000;
+/// Matched lines are cut to this many characters (minified bundles)
+const MAX_LINE_CHARS: usize = 300;
+const DEFAULT_CONTEXT_LINES: usize = 2;
+const MAX_CONTEXT_LINES: usize = 10;
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 500;
+/// Directories skipped even when ignored files are included
+const SKIP_DIRS: &[&str] = &[".git", "node_modules", "target", "dist", "build"];
+
+/// Content search query parameters for typed Axum extraction
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContentSearchQuery {
+    pub q: String,
+    #[serde(default)]
+    pub mode: SearchMode,
+    /// Treat `q` as a regular expression instead of a literal string
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Lines of context around each match (default 2, at most 10)
+    pub context: Option<usize>,
+    /// Maximum number of matching lines (default 100, at most 500)
+    pub limit: Option<usize>,
+}
+
+/// A matching line with its surrounding context
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ContentMatch {
+    /// Path prefixed with the repository name
+    pub path: String,
+    /// 1-based line number
+    pub line_number: usize,
+    pub line: String,
+    /// Byte ranges of the matches within `line`
+    pub ranges: Vec<(usize, usize)>,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ContentSearchResponse {
+    pub matches: Vec<ContentMatch>,
+    /// More lines matched than the limit allowed
+    pub truncated: bool,
+    pub files_searched: usize,
+    /// Every repository was answered from a warm trigram index
+    pub indexed: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum ContentSearchError {
+    #[error("Invalid search pattern: {0}")]
+    InvalidPattern(String),
+    #[error("Search query cannot be empty")]
+    EmptyQuery,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Walk(#[from] ignore::Error),
+    #[error("Search task failed: {0}")]
+    Task(String),
+}
+
+/// A directory tree to search, usually a repository or a worktree of one
+#[derive(Debug, Clone)]
+pub struct SearchRoot {
+    /// Prefix for result paths
+    pub name: String,
+    pub path: PathBuf,
+    /// Sparse-checkout cone; empty searches everything
+    pub sparse_paths: Vec<String>,
+}
+
+/// Lists searchable files under `dir` relative to `root`, flagging the ones
+/// excluded by `.gitignore`. Uses the same superset walk as `FileSearchCache`.
+fn walk_files(root: &Path, dir: &Path) -> Result<Vec<(String, bool)>, ContentSearchError> {
+    let relative = |path: &Path| {
+        path.strip_prefix(root)
+            .ok()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .filter(|p| !p.is_empty())
+    };
+
+    let mut non_ignored = HashSet::new();
+    let ignore_walker = WalkBuilder::new(dir)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+    for entry in ignore_walker.flatten() {
+        if entry.file_type().is_some_and(|ft| ft.is_file())
+            && let Some(path) = relative(entry.path())
+        {
+            non_ignored.insert(path);
+        }
+    }
+
+    let mut files = Vec::new();
+    let walker = WalkBuilder::new(dir)
+        .git_ignore(false)
+        .git_global(false)
+        .git_exclude(false)
+        .hidden(false)
+        .filter_entry(|entry| !SKIP_DIRS.iter().any(|skip| entry.file_name() == *skip))
+        .build();
+    for result in walker {
+        let entry = result?;
+        if entry.file_type().is_some_and(|ft| ft.is_file())
+            && let Some(path) = relative(entry.path())
+        {
+            let ignored = !non_ignored.contains(&path);
+            files.push((path, ignored));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Reads a file for searching, skipping large and binary files
+fn read_text(path: &Path) -> Option<Vec<u8>> {
+    let file = fs::File::open(path).ok()?;
+    if file.metadata().ok()?.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    file.take(MAX_FILE_BYTES).read_to_end(&mut bytes).ok()?;
+    let sniff = &bytes[..bytes.len().min(BINARY_SNIFF_BYTES)];
+    (!sniff.contains(&0)).then_some(bytes)
+}
+
+fn trigram(window: &[u8]) -> u32 {
+    (window[0].to_ascii_lowercase() as u32) << 16
+        | (window[1].to_ascii_lowercase() as u32) << 8
+        | window[2].to_ascii_lowercase() as u32
+}
+
+fn file_trigrams(bytes: &[u8]) -> Vec<u32> {
+    let mut trigrams: Vec<u32> = bytes.windows(3).map(trigram).collect();
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    trigrams
+}
+
+/// Collects literal runs that every match must contain. Anything that is not
+/// a literal, a concatenation, a capture or a mandatory repetition ends the
+/// current run, which keeps the extraction conservative.
+fn required_literals(hir: &Hir, runs: &mut Vec<Vec<u8>>, current: &mut Vec<u8>) {
+    match hir.kind() {
+        HirKind::Literal(literal) => current.extend_from_slice(&literal.0),
+        HirKind::Concat(subs) => {
+            for sub in subs {
+                required_literals(sub, runs, current);
+            }
+        }
+        HirKind::Capture(capture) => required_literals(&capture.sub, runs, current),
+        // Zero-width assertions don't separate the bytes around them
+        HirKind::Look(_) | HirKind::Empty => {}
+        HirKind::Repetition(repetition) if repetition.min >= 1 => {
+            runs.push(std::mem::take(current));
+            required_literals(&repetition.sub, runs, current);
+            runs.push(std::mem::take(current));
+        }
+        _ => runs.push(std::mem::take(current)),
+    }
+}
+
+/// Trigrams a file must contain to possibly match the query
+fn query_trigrams(query: &ContentSearchQuery) -> Vec<u32> {
+    let mut runs = Vec::new();
+    if query.regex {
+        let Ok(hir) = regex_syntax::parse(&query.q) else {
+            return Vec::new();
+        };
+        let mut current = Vec::new();
+        required_literals(&hir, &mut runs, &mut current);
+        runs.push(current);
+    } else {
+        runs.push(query.q.as_bytes().to_vec());
+    }
+
+    let mut trigrams = Vec::new();
+    for run in runs {
+        // Case-insensitive matching folds non-ASCII characters the index
+        // doesn't, so only the ASCII parts of a run are safe to require.
+        let parts: Vec<&[u8]> = if query.case_sensitive {
+            vec![&run]
+        } else {
+            run.split(|byte| !byte.is_ascii()).collect()
+        };
+        for part in parts {
+            trigrams.extend(part.windows(3).map(trigram));
+        }
+    }
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    trigrams
+}
+
+fn build_regex(query: &ContentSearchQuery) -> Result<Regex, ContentSearchError> {
+    let pattern = if query.regex {
+        query.q.clone()
+    } else {
+        regex::escape(&query.q)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!query.case_sensitive)
+        .build()
+        .map_err(|e| ContentSearchError::InvalidPattern(e.to_string()))
+}
+
+struct IndexedContent {
+    path: String,
+    ignored: bool,
+    trigrams: Vec<u32>,
+}
+
+/// Trigram index over the text files of one repository
+struct TrigramIndex {
+    root: PathBuf,
+    ids: HashMap<String, u32>,
+    files: Vec<Option<IndexedContent>>,
+    free: Vec<u32>,
+    /// Sorted file ids per trigram
+    postings: HashMap<u32, Vec<u32>>,
+}
+
+impl TrigramIndex {
+    fn build(root: &Path) -> Result<Self, ContentSearchError> {
+        let mut index = Self {
+            root: root.to_path_buf(),
+            ids: HashMap::new(),
+            files: Vec::new(),
+            free: Vec::new(),
+            postings: HashMap::new(),
+        };
+        for (path, ignored) in walk_files(root, root)? {
+            index.index_file(path, ignored);
+        }
+        Ok(index)
+    }
+
+    fn index_file(&mut self, path: String, ignored: bool) {
+        self.remove_file(&path);
+        let Some(bytes) = read_text(&self.root.join(&path)) else {
+            return;
+        };
+        let trigrams = file_trigrams(&bytes);
+
+        let id = match self.free.pop() {
+            Some(id) => id,
+            None => {
+                self.files.push(None);
+                (self.files.len() - 1) as u32
+            }
+        };
+        for trigram in &trigrams {
+            let posting = self.postings.entry(*trigram).or_default();
+            if let Err(pos) = posting.binary_search(&id) {
+                posting.insert(pos, id);
+            }
+        }
+        self.ids.insert(path.clone(), id);
+        self.files[id as usize] = Some(IndexedContent {
+            path,
+            ignored,
+            trigrams,
+        });
+    }
+
+    fn remove_file(&mut self, path: &str) {
+        let Some(id) = self.ids.remove(path) else {
+            return;
+        };
+        if let Some(file) = self.files[id as usize].take() {
+            for trigram in file.trigrams {
+                if let Some(posting) = self.postings.get_mut(&trigram) {
+                    if let Ok(pos) = posting.binary_search(&id) {
+                        posting.remove(pos);
+                    }
+                    if posting.is_empty() {
+                        self.postings.remove(&trigram);
+                    }
+                }
+            }
+        }
+        self.free.push(id);
+    }
+
+    /// Re-reads the given paths (relative to the root) after they changed
+    fn refresh(&mut self, paths: &[String]) {
+        for path in paths {
+            let full = self.root.join(path);
+            if full.is_file() {
+                let ignored = self
+                    .ids
+                    .get(path)
+                    .and_then(|id| self.files[*id as usize].as_ref())
+                    .is_some_and(|file| file.ignored);
+                self.index_file(path.clone(), ignored);
+            } else if full.is_dir() {
+                // A directory was created or moved in
+                match walk_files(&self.root, &full) {
+                    Ok(files) => {
+                        for (path, ignored) in files {
+                            self.index_file(path, ignored);
+                        }
+                    }
+                    Err(e) => warn!("Failed to index {:?}: {}", full, e),
+                }
+            } else {
+                let prefix = format!("{path}/");
+                let removed: Vec<String> = self
+                    .ids
+                    .keys()
+                    .filter(|candidate| *candidate == path || candidate.starts_with(&prefix))
+                    .cloned()
+                    .collect();
+                for path in removed {
+                    self.remove_file(&path);
+                }
+            }
+        }
+    }
+
+    /// Files containing all the trigrams, sorted by path
+    fn candidates(&self, trigrams: &[u32]) -> Vec<(String, bool)> {
+        let mut files: Vec<(String, bool)> = if trigrams.is_empty() {
+            self.files
+                .iter()
+                .flatten()
+                .map(|file| (file.path.clone(), file.ignored))
+                .collect()
+        } else {
+            let mut postings = Vec::with_capacity(trigrams.len());
+            for trigram in trigrams {
+                match self.postings.get(trigram) {
+                    Some(posting) => postings.push(posting),
+                    None => return Vec::new(),
+                }
+            }
+            postings.sort_by_key(|posting| posting.len());
+            let (smallest, rest) = postings.split_first().expect("trigrams is not empty");
+            smallest
+                .iter()
+                .copied()
+                .filter(|id| rest.iter().all(|posting| posting.binary_search(id).is_ok()))
+                .filter_map(|id| self.files[id as usize].as_ref())
+                .map(|file| (file.path.clone(), file.ignored))
+                .collect()
+        };
+        files.sort();
+        files
+    }
+}
+
+/// Index of a repository kept current by a filesystem watcher
+struct RepoIndex {
+    index: Arc<RwLock<TrigramIndex>>,
+    watcher: JoinHandle<()>,
+}
+
+impl Drop for RepoIndex {
+    fn drop(&mut self) {
+        self.watcher.abort();
+    }
+}
+
+struct Collector {
+    matches: Vec<ContentMatch>,
+    limit: usize,
+    context: usize,
+    truncated: bool,
+    files_searched: usize,
+}
+
+impl Collector {
+    fn search_file(&mut self, regex: &Regex, full_path: &Path, display_path: &str) {
+        let Some(bytes) = read_text(full_path) else {
+            return;
+        };
+        self.files_searched += 1;
+
+        let text = String::from_utf8_lossy(&bytes);
+        let lines: Vec<&str> = text.lines().collect();
+        for (idx, line) in lines.iter().enumerate() {
+            let shown = truncate_line(line);
+            let ranges: Vec<(usize, usize)> = regex
+                .find_iter(line)
+                .map(|m| (m.start(), m.end()))
+                .filter(|(start, _)| *start < shown.len())
+                .map(|(start, end)| (start, end.min(shown.len())))
+                .collect();
+            if ranges.is_empty() {
+                continue;
+            }
+            if self.matches.len() >= self.limit {
+                self.truncated = true;
+                return;
+            }
+            let before_start = idx.saturating_sub(self.context);
+            let after_end = (idx + 1 + self.context).min(lines.len());
+            self.matches.push(ContentMatch {
+                path: display_path.to_string(),
+                line_number: idx + 1,
+                line: shown.to_string(),
+                ranges,
+                before: lines[before_start..idx]
+                    .iter()
+                    .map(|l| truncate_line(l).to_string())
+                    .collect(),
+                after: lines[idx + 1..after_end]
+                    .iter()
+                    .map(|l| truncate_line(l).to_string())
+                    .collect(),
+            });
+        }
+    }
+}
+
+fn truncate_line(line: &str) -> &str {
+    match line.char_indices().nth(MAX_LINE_CHARS) {
+        Some((end, _)) => &line[..end],
+        None => line,
+    }
+}
+
+/// Content search over repositories with incremental trigram indexes for
+/// the most active ones
+pub struct ContentSearchService {
+    indexes: Cache<PathBuf, Arc<RepoIndex>>,
+    build_queue: mpsc::UnboundedSender<PathBuf>,
+}
+
+impl ContentSearchService {
+    pub fn new() -> Self {
+        let (build_sender, build_receiver) = mpsc::unbounded_channel();
+
+        // Indexes stay current through their watchers, so evict on idleness
+        let indexes = Cache::builder()
+            .max_capacity(8)
+            .time_to_idle(Duration::from_secs(3600))
+            .build();
+
+        tokio::spawn(Self::background_worker(build_receiver, indexes.clone()));
+
+        Self {
+            indexes,
+            build_queue: build_sender,
+        }
+    }
+
+    /// Search file contents under the given roots. With `use_index`, warm
+    /// indexes narrow the files to read and missing ones are built in the
+    /// background; otherwise every file is scanned.
+    pub async fn search(
+        &self,
+        roots: Vec<SearchRoot>,
+        query: &ContentSearchQuery,
+        use_index: bool,
+    ) -> Result<ContentSearchResponse, ContentSearchError> {
+        if query.q.trim().is_empty() {
+            return Err(ContentSearchError::EmptyQuery);
+        }
+        let regex = build_regex(query)?;
+        let trigrams = query_trigrams(query);
+
+        let mut indexed = true;
+        let mut targets = Vec::with_capacity(roots.len());
+        for root in roots {
+            let index = if use_index {
+                let index = self.indexes.get(&root.path).await;
+                if index.is_none()
+                    && let Err(e) = self.build_queue.send(root.path.clone())
+                {
+                    warn!("Failed to enqueue content index build: {}", e);
+                }
+                index
+            } else {
+                None
+            };
+            indexed &= index.is_some();
+            targets.push((root, index));
+        }
+
+        let mode = query.mode.clone();
+        let mut collector = Collector {
+            matches: Vec::new(),
+            limit: query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+            context: query
+                .context
+                .unwrap_or(DEFAULT_CONTEXT_LINES)
+                .min(MAX_CONTEXT_LINES),
+            truncated: false,
+            files_searched: 0,
+        };
+
+        let collector = tokio::task::spawn_blocking(move || {
+            for (root, index) in targets {
+                let files = match &index {
+                    Some(index) => index
+                        .index
+                        .read()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .candidates(&trigrams),
+                    None => walk_files(&root.path, &root.path)?,
+                };
+                for (path, ignored) in files {
+                    if ignored && matches!(mode, SearchMode::TaskForm) {
+                        continue;
+                    }
+                    if !in_cone(&path, true, &root.sparse_paths) {
+                        continue;
+                    }
+                    let display_path = if root.name.is_empty() {
+                        path.clone()
+                    } else {
+                        format!("{}/{}", root.name, path)
+                    };
+                    collector.search_file(&regex, &root.path.join(&path), &display_path);
+                    if collector.truncated {
+                        return Ok(collector);
+                    }
+                }
+            }
+            Ok::<_, ContentSearchError>(collector)
+        })
+        .await
+        .map_err(|e| ContentSearchError::Task(e.to_string()))??;
+
+        Ok(ContentSearchResponse {
+            matches: collector.matches,
+            truncated: collector.truncated,
+            files_searched: collector.files_searched,
+            indexed,
+        })
+    }
+
+    /// Build and watch indexes for the given repositories
+    pub fn warm_repos(&self, repo_paths: Vec<PathBuf>) {
+        for repo_path in repo_paths {
+            if let Err(e) = self.build_queue.send(repo_path.clone()) {
+                error!(
+                    "Failed to enqueue repo for content indexing: {:?} - {}",
+                    repo_path, e
+                );
+            }
+        }
+    }
+
+    /// Pre-warm indexes for the repositories of the most active projects
+    pub async fn warm_most_active(&self, db_pool: &SqlitePool, limit: i32) -> Result<(), String> {
+        let active_projects = Project::find_most_active(db_pool, limit)
+            .await
+            .map_err(|e| format!("Failed to fetch active projects: {e}"))?;
+
+        let mut repo_paths = Vec::new();
+        for project in &active_projects {
+            let repos = ProjectRepo::find_repos_for_project(db_pool, project.id)
+                .await
+                .map_err(|e| format!("Failed to fetch repositories for project: {e}"))?;
+            repo_paths.extend(repos.into_iter().map(|repo| repo.path));
+        }
+
+        info!(
+            "Warming content search index for {} repositories",
+            repo_paths.len()
+        );
+        self.warm_repos(repo_paths);
+        Ok(())
+    }
+
+    async fn background_worker(
+        mut build_receiver: mpsc::UnboundedReceiver<PathBuf>,
+        indexes: Cache<PathBuf, Arc<RepoIndex>>,
+    ) {
+        while let Some(repo_path) = build_receiver.recv().await {
+            if indexes.contains_key(&repo_path) {
+                continue;
+            }
+            match Self::build_repo_index(repo_path.clone()).await {
+                Ok(index) => {
+                    indexes.insert(repo_path.clone(), Arc::new(index)).await;
+                    info!("Indexed repo contents: {:?}", repo_path);
+                }
+                Err(e) => error!("Failed to index repo contents {:?}: {}", repo_path, e),
+            }
+        }
+    }
+
+    async fn build_repo_index(repo_path: PathBuf) -> Result<RepoIndex, String> {
+        let root = repo_path.clone();
+        // Start watching before the walk so changes made during it aren't lost
+        let (debouncer, mut watcher_rx, canonical_root, index) =
+            tokio::task::spawn_blocking(move || {
+                let (debouncer, watcher_rx, canonical_root) =
+                    filesystem_watcher::async_watcher(repo_path.clone())
+                        .map_err(|e| format!("Failed to watch repo: {e}"))?;
+                let index = TrigramIndex::build(&repo_path)
+                    .map_err(|e| format!("Failed to build index: {e}"))?;
+                Ok::<_, String>((debouncer, watcher_rx, canonical_root, index))
+            })
+            .await
+            .map_err(|e| format!("Index task failed: {e}"))??;
+
+        let index = Arc::new(RwLock::new(index));
+        let watched_index = index.clone();
+        let watcher = tokio::spawn(async move {
+            let _debouncer_guard = debouncer;
+            while let Some(result) = watcher_rx.next().await {
+                let events = match result {
+                    Ok(events) => events,
+                    Err(errors) => {
+                        warn!("Content index watcher error: {:?}", errors);
+                        continue;
+                    }
+                };
+                let mut changed: Vec<String> = events
+                    .iter()
+                    .flat_map(|event| &event.paths)
+                    .filter_map(|path| {
+                        path.strip_prefix(&canonical_root)
+                            .or_else(|_| path.strip_prefix(&root))
+                            .ok()
+                            .map(|p| p.to_string_lossy().replace('\\', "/"))
+                    })
+                    .filter(|path| !path.is_empty())
+                    .collect();
+                changed.sort();
+                changed.dedup();
+                if changed.is_empty() {
+                    continue;
+                }
+
+                let index = watched_index.clone();
+                if let Err(e) = tokio::task::spawn_blocking(move || {
+                    index
+                        .write()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .refresh(&changed)
+                })
+                .await
+                {
+                    error!("Failed to refresh content index: {}", e);
+                }
+            }
+        });
+
+        Ok(RepoIndex { index, watcher })
+    }
+}
+
+impl Default for ContentSearchService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(q: &str, regex: bool) -> ContentSearchQuery {
+        ContentSearchQuery {
+            q: q.to_string(),
+            mode: SearchMode::TaskForm,
+            regex,
+            case_sensitive: false,
+            context: Some(1),
+            limit: None,
+        }
+    }
+
+    #[test]
+    fn regex_trigrams_only_use_required_literals() {
+        let literal = query_trigrams(&query("Hello", false));
+        assert_eq!(literal, file_trigrams(b"hello"));
+
+        // Both alternatives are optional, so only "fn " and "(" runs count
+        let regex = query_trigrams(&query(r"fn (foo|bar)\(", true));
+        assert_eq!(regex, file_trigrams(b"fn "));
+
+        assert!(query_trigrams(&query(r"a.b", true)).is_empty());
+    }
+
+    #[test]
+    fn index_narrows_candidates_and_tracks_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.rs"), "fn parse_config() {}\n").unwrap();
+        fs::write(dir.path().join("b.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.path().join("bin.dat"), b"parse\0config").unwrap();
+
+        let mut index = TrigramIndex::build(dir.path()).unwrap();
+        let trigrams = query_trigrams(&query("parse_config", false));
+        assert_eq!(
+            index.candidates(&trigrams),
+            vec![("a.rs".to_string(), false)]
+        );
+
+        fs::write(dir.path().join("b.rs"), "parse_config();\n").unwrap();
+        fs::remove_file(dir.path().join("a.rs")).unwrap();
+        index.refresh(&["a.rs".to_string(), "b.rs".to_string()]);
+        assert_eq!(
+            index.candidates(&trigrams),
+            vec![("b.rs".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn collects_matches_with_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        fs::write(&path, "one\nlet Foo = 1;\nthree\nfour\n").unwrap();
+
+        let mut collector = Collector {
+            matches: Vec::new(),
+            limit: 10,
+            context: 1,
+            truncated: false,
+            files_searched: 0,
+        };
+        let regex = build_regex(&query("foo", false)).unwrap();
+        collector.search_file(&regex, &path, "repo/lib.rs");
+
+        assert_eq!(collector.matches.len(), 1);
+        let found = &collector.matches[0];
+        assert_eq!(found.path, "repo/lib.rs");
+        assert_eq!(found.line_number, 2);
+        assert_eq!(found.ranges, vec![(4, 7)]);
+        assert_eq!(found.before, vec!["one"]);
+        assert_eq!(found.after, vec!["three"]);
+    }
+}
@@ -0,0 +1,713 @@
+//! Dependency directories shared between attempt worktrees.
+//!
+//! A repository declares directories such as `node_modules`, `target` or
+//! `.venv` that are expensive to rebuild. Once a setup script succeeds, each of
+//! them is stored under the asset directory, keyed by the hash of the lockfiles
+//! it was installed from. A new worktree whose lockfiles hash the same gets a
+//! clone of it before its setup script runs, which then only has incremental
+//! work left. When a lockfile changes the key changes with it, so a stale cache
+//! is never provisioned.
+//!
+//! Clones are reflinks where the filesystem supports them (APFS, Btrfs, XFS),
+//! which share blocks copy-on-write, and plain copies otherwise. Hard links can
+//! be enabled with `DEPENDENCY_CACHE_LINK=hardlink`; they are the cheapest on
+//! filesystems without reflinks, but a tool that writes a file in place then
+//! modifies the cached copy as well.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::sparse_checkout::normalize_cone_path;
+
+/// Lockfiles looked for next to a cache directory that declares none
+pub const DEFAULT_KEY_FILES: [&str; 13] = [
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "yarn.lock",
+    "bun.lock",
+    "bun.lockb",
+    "Cargo.lock",
+    "uv.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "requirements.txt",
+    "Gemfile.lock",
+    "go.sum",
+    "composer.lock",
+];
+
+const ENTRY_FILE: &str = "entry.json";
+const DATA_DIR: &str = "data";
+const STAGING_PREFIX: &str = ".staging-";
+
+#[derive(Debug, Error)]
+pub enum DependencyCacheError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Dependency cache not found")]
+    NotFound,
+    #[error("Dependency cache task failed: {0}")]
+    Task(String),
+}
+
+/// A cache directory declared for a repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheSpec {
+    /// Directory relative to the repository root
+    pub path: String,
+    /// Files whose contents key the cache, relative to the repository root.
+    /// Empty to use the [`DEFAULT_KEY_FILES`] next to the directory.
+    pub key_files: Vec<String>,
+}
+
+/// Parse cache declarations from a project repo setting, one per line:
+/// `node_modules: package-lock.json` or just `target`. Entries escaping the
+/// repository are dropped.
+pub fn parse_specs(value: &str) -> Vec<CacheSpec> {
+    value
+        .lines()
+        .filter_map(|line| {
+            let (path, keys) = line.split_once(':').unwrap_or((line, ""));
+            let path = normalize_cone_path(path)?;
+            if path == ".git" || path.starts_with(".git/") {
+                return None;
+            }
+            let key_files = keys.split(',').filter_map(normalize_cone_path).collect();
+            Some(CacheSpec { path, key_files })
+        })
+        .collect()
+}
+
+/// How cached files are placed into worktrees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkMode {
+    /// Reflink, or copy where the filesystem has no reflinks
+    Reflink,
+    /// Reflink, or hard link where the filesystem has no reflinks
+    Hardlink,
+    Copy,
+}
+
+impl LinkMode {
+    fn from_env() -> Self {
+        match std::env::var("DEPENDENCY_CACHE_LINK").as_deref() {
+            Ok("hardlink") => Self::Hardlink,
+            Ok("copy") => Self::Copy,
+            _ => Self::Reflink,
+        }
+    }
+}
+
+/// A stored dependency cache
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DependencyCacheEntry {
+    #[serde(default)]
+    pub id: String,
+    pub repo_id: Uuid,
+    /// Cached directory, relative to the repository root
+    pub path: String,
+    /// Files the cache is keyed by
+    pub key_files: Vec<String>,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub last_used_at: DateTime<Utc>,
+    /// A newer cache exists for the same repository and directory
+    #[serde(default)]
+    pub superseded: bool,
+}
+
+/// Which caches to remove. Criteria add up; with none, nothing is removed.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct PruneDependencyCaches {
+    /// Only consider the caches of this repository
+    pub repo_id: Option<Uuid>,
+    /// Remove caches superseded by a newer one
+    #[serde(default)]
+    pub superseded: bool,
+    /// Remove caches not provisioned for this many days
+    #[ts(type = "number | null")]
+    pub unused_for_days: Option<i64>,
+    /// Then remove the least recently used caches until the rest fit
+    #[ts(type = "number | null")]
+    pub max_total_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct PruneDependencyCachesResult {
+    pub removed: Vec<String>,
+    #[ts(type = "number")]
+    pub freed_bytes: u64,
+}
+
+/// Stores dependency directories and provisions them into new worktrees
+#[derive(Debug, Clone)]
+pub struct DependencyCacheService {
+    root: PathBuf,
+    link_mode: LinkMode,
+}
+
+impl DependencyCacheService {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            link_mode: LinkMode::from_env(),
+        }
+    }
+
+    /// Clone the matching cache into every declared directory the worktree
+    /// does not have yet. Returns the number of directories provisioned.
+    pub async fn provision(&self, repo_id: Uuid, worktree: &Path, specs: &str) -> usize {
+        let specs = parse_specs(specs);
+        if specs.is_empty() {
+            return 0;
+        }
+        let service = self.clone();
+        let worktree = worktree.to_path_buf();
+        self.blocking(move || Ok(service.provision_sync(repo_id, &worktree, &specs)))
+            .await
+            .unwrap_or(0)
+    }
+
+    /// Store every declared directory of the worktree that has no cache for
+    /// its current lockfiles yet. Returns the number of caches created.
+    pub async fn save(&self, repo_id: Uuid, worktree: &Path, specs: &str) -> usize {
+        let specs = parse_specs(specs);
+        if specs.is_empty() {
+            return 0;
+        }
+        let service = self.clone();
+        let worktree = worktree.to_path_buf();
+        self.blocking(move || Ok(service.save_sync(repo_id, &worktree, &specs)))
+            .await
+            .unwrap_or(0)
+    }
+
+    /// All stored caches, most recently used first
+    pub async fn list(&self) -> Result<Vec<DependencyCacheEntry>, DependencyCacheError> {
+        let service = self.clone();
+        self.blocking(move || service.list_sync()).await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<(), DependencyCacheError> {
+        if !is_entry_id(id) {
+            return Err(DependencyCacheError::NotFound);
+        }
+        let dir = self.root.join(id);
+        self.blocking(move || match fs::remove_dir_all(&dir) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Err(DependencyCacheError::NotFound),
+            other => Ok(other?),
+        })
+        .await
+    }
+
+    pub async fn prune(
+        &self,
+        request: PruneDependencyCaches,
+    ) -> Result<PruneDependencyCachesResult, DependencyCacheError> {
+        let service = self.clone();
+        self.blocking(move || service.prune_sync(&request)).await
+    }
+
+    async fn blocking<T, F>(&self, f: F) -> Result<T, DependencyCacheError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T, DependencyCacheError> + Send + 'static,
+    {
+        tokio::task::spawn_blocking(f)
+            .await
+            .map_err(|e| DependencyCacheError::Task(e.to_string()))?
+    }
+
+    fn provision_sync(&self, repo_id: Uuid, worktree: &Path, specs: &[CacheSpec]) -> usize {
+        let mut provisioned = 0;
+        for spec in specs {
+            let target = worktree.join(&spec.path);
+            if target.symlink_metadata().is_ok() {
+                continue;
+            }
+            let Some((id, _)) = cache_key(repo_id, worktree, spec) else {
+                continue;
+            };
+            let dir = self.root.join(&id);
+            let Ok(mut entry) = read_entry(&dir) else {
+                continue;
+            };
+            if let Some(parent) = target.parent()
+                && let Err(e) = fs::create_dir_all(parent)
+            {
+                tracing::warn!("Failed to create {}: {}", parent.display(), e);
+                continue;
+            }
+            match clone_tree(&dir.join(DATA_DIR), &target, self.link_mode) {
+                Ok(_) => {
+                    provisioned += 1;
+                    entry.last_used_at = Utc::now();
+                    if let Err(e) = write_entry(&dir, &entry) {
+                        tracing::debug!("Failed to record use of dependency cache {}: {}", id, e);
+                    }
+                    tracing::debug!("Provisioned '{}' from dependency cache {}", spec.path, id);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to provision '{}' from dependency cache {}: {}",
+                        spec.path,
+                        id,
+                        e
+                    );
+                    let _ = fs::remove_dir_all(&target);
+                }
+            }
+        }
+        provisioned
+    }
+
+    fn save_sync(&self, repo_id: Uuid, worktree: &Path, specs: &[CacheSpec]) -> usize {
+        let mut saved = 0;
+        for spec in specs {
+            let source = worktree.join(&spec.path);
+            if !source.symlink_metadata().is_ok_and(|m| m.is_dir()) {
+                continue;
+            }
+            let Some((id, key_files)) = cache_key(repo_id, worktree, spec) else {
+                tracing::debug!(
+                    "Not caching '{}': none of its key files exist in {}",
+                    spec.path,
+                    worktree.display()
+                );
+                continue;
+            };
+            let dir = self.root.join(&id);
+            if dir.exists() {
+                continue;
+            }
+            match self.store(&source, &dir, repo_id, spec, key_files) {
+                Ok(()) => {
+                    saved += 1;
+                    tracing::info!("Stored '{}' as dependency cache {}", spec.path, id);
+                }
+                Err(e) => tracing::warn!("Failed to store dependency cache '{}': {}", spec.path, e),
+            }
+        }
+        saved
+    }
+
+    /// Copy into a staging directory first, so a cache only becomes visible
+    /// once complete and concurrent saves of the same key do not collide
+    fn store(
+        &self,
+        source: &Path,
+        dir: &Path,
+        repo_id: Uuid,
+        spec: &CacheSpec,
+        key_files: Vec<String>,
+    ) -> Result<(), DependencyCacheError> {
+        let staging = self
+            .root
+            .join(format!("{STAGING_PREFIX}{}", Uuid::new_v4()));
+        fs::create_dir_all(&staging)?;
+        let result = (|| {
+            // Never hard link from a worktree: its files keep changing
+            let mode = match self.link_mode {
+                LinkMode::Hardlink => LinkMode::Reflink,
+                mode => mode,
+            };
+            let size_bytes = clone_tree(source, &staging.join(DATA_DIR), mode)?;
+            let now = Utc::now();
+            write_entry(
+                &staging,
+                &DependencyCacheEntry {
+                    id: String::new(),
+                    repo_id,
+                    path: spec.path.clone(),
+                    key_files,
+                    size_bytes,
+                    created_at: now,
+                    last_used_at: now,
+                    superseded: false,
+                },
+            )?;
+            match fs::rename(&staging, dir) {
+                // Another worktree stored the same key first
+                Err(_) if dir.exists() => fs::remove_dir_all(&staging).map_err(Into::into),
+                other => other.map_err(Into::into),
+            }
+        })();
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&staging);
+        }
+        result
+    }
+
+    fn list_sync(&self) -> Result<Vec<DependencyCacheEntry>, DependencyCacheError> {
+        let read_dir = match fs::read_dir(&self.root) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut entries = Vec::new();
+        for dir in read_dir {
+            let dir = dir?;
+            let Some(id) = dir.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if !is_entry_id(&id) {
+                continue;
+            }
+            match read_entry(&dir.path()) {
+                Ok(mut entry) => {
+                    entry.id = id;
+                    entries.push(entry);
+                }
+                Err(e) => tracing::debug!("Skipping unreadable dependency cache {}: {}", id, e),
+            }
+        }
+
+        let mut newest: HashMap<(Uuid, String), DateTime<Utc>> = HashMap::new();
+        for entry in &entries {
+            let created = newest
+                .entry((entry.repo_id, entry.path.clone()))
+                .or_insert(entry.created_at);
+            *created = (*created).max(entry.created_at);
+        }
+        for entry in &mut entries {
+            entry.superseded = newest[&(entry.repo_id, entry.path.clone())] > entry.created_at;
+        }
+        entries.sort_by(|a, b| b.last_used_at.cmp(&a.last_used_at));
+        Ok(entries)
+    }
+
+    fn prune_sync(
+        &self,
+        request: &PruneDependencyCaches,
+    ) -> Result<PruneDependencyCachesResult, DependencyCacheError> {
+        self.remove_abandoned_staging();
+
+        let unused_before = request
+            .unused_for_days
+            .map(|days| Utc::now() - Duration::days(days.max(0)));
+        let (mut remove, mut keep): (Vec<_>, Vec<_>) = self
+            .list_sync()?
+            .into_iter()
+            .filter(|entry| request.repo_id.is_none_or(|id| id == entry.repo_id))
+            .partition(|entry| {
+                (request.superseded && entry.superseded)
+                    || unused_before.is_some_and(|before| entry.last_used_at < before)
+            });
+
+        if let Some(max_total_bytes) = request.max_total_bytes {
+            // `keep` is most recently used first
+            let mut total: u64 = keep.iter().map(|entry| entry.size_bytes).sum();
+            while total > max_total_bytes
+                && let Some(entry) = keep.pop()
+            {
+                total -= entry.size_bytes;
+                remove.push(entry);
+            }
+        }
+
+        let mut result = PruneDependencyCachesResult {
+            removed: Vec::new(),
+            freed_bytes: 0,
+        };
+        for entry in remove {
+            match fs::remove_dir_all(self.root.join(&entry.id)) {
+                Ok(()) => {
+                    result.freed_bytes += entry.size_bytes;
+                    result.removed.push(entry.id);
+                }
+                Err(e) => tracing::warn!("Failed to remove dependency cache {}: {}", entry.id, e),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Staging directories left behind by an interrupted save
+    fn remove_abandoned_staging(&self) {
+        let Ok(read_dir) = fs::read_dir(&self.root) else {
+            return;
+        };
+        let cutoff = std::time::SystemTime::now() - std::time::Duration::from_secs(24 * 60 * 60);
+        for dir in read_dir.flatten() {
+            let abandoned = dir
+                .file_name()
+                .to_string_lossy()
+                .starts_with(STAGING_PREFIX)
+                && dir
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .is_ok_and(|modified| modified < cutoff);
+            if abandoned {
+                let _ = fs::remove_dir_all(dir.path());
+            }
+        }
+    }
+}
+
+/// Id of the cache matching the worktree's key files, with the key files
+/// found. `None` when none of them exist, as the cache could not be told
+/// apart from a stale one.
+fn cache_key(repo_id: Uuid, worktree: &Path, spec: &CacheSpec) -> Option<(String, Vec<String>)> {
+    let candidates: Vec<String> = if spec.key_files.is_empty() {
+        let parent = spec
+            .path
+            .rsplit_once('/')
+            .map(|(parent, _)| format!("{parent}/"))
+            .unwrap_or_default();
+        DEFAULT_KEY_FILES
+            .iter()
+            .map(|name| format!("{parent}{name}"))
+            .collect()
+    } else {
+        spec.key_files.clone()
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(repo_id.as_bytes());
+    hasher.update(spec.path.as_bytes());
+    let mut found = Vec::new();
+    for key_file in candidates {
+        let Ok(contents) = fs::read(worktree.join(&key_file)) else {
+            continue;
+        };
+        hasher.update([0]);
+        hasher.update(key_file.as_bytes());
+        hasher.update([0]);
+        hasher.update(Sha256::digest(&contents));
+        found.push(key_file);
+    }
+    if found.is_empty() {
+        return None;
+    }
+    let id = hasher.finalize()[..16]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    Some((id, found))
+}
+
+fn is_entry_id(id: &str) -> bool {
+    id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn read_entry(dir: &Path) -> Result<DependencyCacheEntry, DependencyCacheError> {
+    Ok(serde_json::from_slice(&fs::read(dir.join(ENTRY_FILE))?)?)
+}
+
+fn write_entry(dir: &Path, entry: &DependencyCacheEntry) -> Result<(), DependencyCacheError> {
+    let tmp = dir.join(format!("{ENTRY_FILE}.{}", Uuid::new_v4()));
+    fs::write(&tmp, serde_json::to_vec_pretty(entry)?)?;
+    fs::rename(&tmp, dir.join(ENTRY_FILE))?;
+    Ok(())
+}
+
+/// Recreate the tree at `source` as `target`, which must not exist, keeping
+/// symlinks as they are. Returns the size of the files cloned.
+fn clone_tree(source: &Path, target: &Path, mode: LinkMode) -> io::Result<u64> {
+    let mut reflinks = mode != LinkMode::Copy;
+    let mut size = 0;
+    let mut dirs = vec![(source.to_path_buf(), target.to_path_buf())];
+    while let Some((from_dir, to_dir)) = dirs.pop() {
+        fs::create_dir(&to_dir)?;
+        for entry in fs::read_dir(&from_dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let from = entry.path();
+            let to = to_dir.join(entry.file_name());
+            if file_type.is_symlink() {
+                copy_symlink(&from, &to)?;
+            } else if file_type.is_dir() {
+                dirs.push((from, to));
+            } else {
+                size += clone_file(&from, &to, mode, &mut reflinks)?;
+            }
+        }
+    }
+    Ok(size)
+}
+
+/// `reflinks` is cleared on the first failed reflink, so filesystems without
+/// them only pay for one attempt
+fn clone_file(from: &Path, to: &Path, mode: LinkMode, reflinks: &mut bool) -> io::Result<u64> {
+    let len = fs::symlink_metadata(from)?.len();
+    if *reflinks {
+        match reflink(from, to) {
+            Ok(()) => return Ok(len),
+            Err(e) => {
+                tracing::debug!("Reflinks unavailable, falling back: {}", e);
+                *reflinks = false;
+                let _ = fs::remove_file(to);
+            }
+        }
+    }
+    if mode == LinkMode::Hardlink && fs::hard_link(from, to).is_ok() {
+        return Ok(len);
+    }
+    fs::copy(from, to)
+}
+
+#[cfg(target_os = "linux")]
+fn reflink(from: &Path, to: &Path) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    const FICLONE: libc::c_ulong = 0x4004_9409;
+    let source = fs::File::open(from)?;
+    let target = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(to)?;
+    // SAFETY: both descriptors are open for the duration of the call
+    if unsafe { libc::ioctl(target.as_raw_fd(), FICLONE as _, source.as_raw_fd()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    target.set_permissions(source.metadata()?.permissions())
+}
+
+#[cfg(target_os = "macos")]
+fn reflink(from: &Path, to: &Path) -> io::Result<()> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let from = CString::new(from.as_os_str().as_bytes())?;
+    let to = CString::new(to.as_os_str().as_bytes())?;
+    // SAFETY: both paths are NUL-terminated and outlive the call
+    if unsafe { libc::clonefile(from.as_ptr(), to.as_ptr(), 0) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink(_from: &Path, _to: &Path) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    let link = fs::read_link(from)?;
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(link, to)
+    }
+    #[cfg(windows)]
+    {
+        if from.is_dir() {
+            std::os::windows::fs::symlink_dir(link, to)
+        } else {
+            std::os::windows::fs::symlink_file(link, to)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn worktree_with_lockfile(lockfile: &str) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("package-lock.json"), lockfile).unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/left-pad")).unwrap();
+        fs::write(dir.path().join("node_modules/left-pad/index.js"), "pad").unwrap();
+        dir
+    }
+
+    #[test]
+    fn parses_specs() {
+        assert_eq!(
+            parse_specs("node_modules\nweb/target: web/Cargo.lock, rust-toolchain\n../up\n.git"),
+            vec![
+                CacheSpec {
+                    path: "node_modules".to_string(),
+                    key_files: vec![],
+                },
+                CacheSpec {
+                    path: "web/target".to_string(),
+                    key_files: vec!["web/Cargo.lock".to_string(), "rust-toolchain".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn provisions_only_matching_lockfiles() {
+        let root = TempDir::new().unwrap();
+        let service = DependencyCacheService {
+            root: root.path().to_path_buf(),
+            link_mode: LinkMode::Reflink,
+        };
+        let repo_id = Uuid::new_v4();
+
+        let first = worktree_with_lockfile("v1");
+        assert_eq!(service.save(repo_id, first.path(), "node_modules").await, 1);
+        assert_eq!(service.save(repo_id, first.path(), "node_modules").await, 0);
+
+        let same = TempDir::new().unwrap();
+        fs::write(same.path().join("package-lock.json"), "v1").unwrap();
+        assert_eq!(
+            service
+                .provision(repo_id, same.path(), "node_modules")
+                .await,
+            1
+        );
+        assert_eq!(
+            fs::read_to_string(same.path().join("node_modules/left-pad/index.js")).unwrap(),
+            "pad"
+        );
+
+        let changed = TempDir::new().unwrap();
+        fs::write(changed.path().join("package-lock.json"), "v2").unwrap();
+        assert_eq!(
+            service
+                .provision(repo_id, changed.path(), "node_modules")
+                .await,
+            0
+        );
+        assert!(!changed.path().join("node_modules").exists());
+    }
+
+    #[tokio::test]
+    async fn prunes_superseded_caches() {
+        let root = TempDir::new().unwrap();
+        let service = DependencyCacheService {
+            root: root.path().to_path_buf(),
+            link_mode: LinkMode::Copy,
+        };
+        let repo_id = Uuid::new_v4();
+        for lockfile in ["v1", "v2"] {
+            let worktree = worktree_with_lockfile(lockfile);
+            service.save(repo_id, worktree.path(), "node_modules").await;
+        }
+
+        let entries = service.list().await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries.iter().filter(|e| e.superseded).count(), 1);
+        assert_eq!(entries[0].size_bytes, 3);
+
+        let result = service
+            .prune(PruneDependencyCaches {
+                superseded: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.removed.len(), 1);
+        assert_eq!(result.freed_bytes, 3);
+        let remaining = service.list().await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert!(!remaining[0].superseded);
+    }
+}
@@ -0,0 +1,334 @@
+//! Per-workspace dev servers.
+//!
+//! Every workspace may run its own dev server. Before one starts, free ports
+//! are reserved for `PORT` and any extra variables the project names, so dev
+//! servers of different attempts never compete for the same port. The server
+//! reverse-proxies `/preview/{workspace_id}/` to the reserved `PORT`, giving
+//! each attempt a stable preview URL, and records traffic there so idle dev
+//! servers can be shut down. Previews are served from their own port, so
+//! agent-written pages are another origin than the API. Browsers still send
+//! the session cookie from there, so the API refuses cross-origin requests
+//! that change something or open a websocket.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    net::{Ipv4Addr, TcpListener},
+    sync::{Arc, LazyLock, OnceLock},
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use futures::StreamExt;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+/// Variable every dev server receives its main port in
+pub const PORT_VAR: &str = "PORT";
+
+/// How often the port is probed while waiting for readiness
+const PROBE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Output kept for matching the ready pattern across chunk boundaries
+const MAX_MATCH_BUFFER: usize = 4096;
+
+const MAX_ALLOCATION_ATTEMPTS: usize = 32;
+
+static ANSI_ESCAPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\x1b\[[0-9;?]*[ -/]*[@-~]").unwrap());
+
+#[derive(Debug, Error)]
+pub enum DevServerError {
+    #[error("Failed to allocate a port: {0}")]
+    PortAllocation(#[from] std::io::Error),
+    #[error("No free port found after {0} attempts")]
+    PortsExhausted(usize),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct DevServerSettings {
+    /// Stop a dev server after this many minutes without preview traffic; unset keeps it running
+    #[serde(default)]
+    pub idle_shutdown_minutes: Option<u32>,
+}
+
+impl DevServerSettings {
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_shutdown_minutes
+            .filter(|m| *m > 0)
+            .map(|m| Duration::from_secs(u64::from(m) * 60))
+    }
+}
+
+/// Ports and readiness of the dev server running in a workspace
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DevServerInfo {
+    pub workspace_id: Uuid,
+    pub execution_process_id: Uuid,
+    /// The port passed in `PORT`
+    pub port: u16,
+    /// Every reserved port by variable name, including `PORT`
+    pub ports: BTreeMap<String, u16>,
+    pub ready: bool,
+    /// Path of the reverse proxy in front of `port`
+    pub preview_path: String,
+    /// Port of the preview listener serving `preview_path`; unset when
+    /// previews share the app's port
+    pub preview_port: Option<u16>,
+}
+
+impl DevServerInfo {
+    /// Variables injected into the dev server environment
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        self.ports
+            .iter()
+            .map(|(name, port)| (name.clone(), port.to_string()))
+            .chain([("VK_PREVIEW_PATH".to_string(), self.preview_path.clone())])
+            .collect()
+    }
+}
+
+struct DevServerEntry {
+    info: DevServerInfo,
+    last_activity: Instant,
+}
+
+#[derive(Clone, Default)]
+pub struct DevServerService {
+    servers: Arc<DashMap<Uuid, DevServerEntry>>,
+    preview_port: Arc<OnceLock<u16>>,
+}
+
+impl DevServerService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the port the preview listener is bound to
+    pub fn set_preview_port(&self, port: u16) {
+        let _ = self.preview_port.set(port);
+    }
+
+    pub fn preview_path(workspace_id: Uuid) -> String {
+        format!("/preview/{workspace_id}/")
+    }
+
+    /// Reserve `PORT` plus one port per name in `extra_ports` for a new dev server.
+    /// Replaces any previous reservation of the workspace.
+    pub fn allocate(
+        &self,
+        workspace_id: Uuid,
+        execution_process_id: Uuid,
+        extra_ports: &[String],
+    ) -> Result<DevServerInfo, DevServerError> {
+        self.servers.remove(&workspace_id);
+
+        let mut taken: HashSet<u16> = self
+            .servers
+            .iter()
+            .flat_map(|e| e.info.ports.values().copied().collect::<Vec<_>>())
+            .collect();
+        let mut ports = BTreeMap::new();
+        for name in std::iter::once(PORT_VAR).chain(extra_ports.iter().map(String::as_str)) {
+            if ports.contains_key(name) {
+                continue;
+            }
+            let port = free_port(&taken)?;
+            taken.insert(port);
+            ports.insert(name.to_string(), port);
+        }
+
+        let info = DevServerInfo {
+            workspace_id,
+            execution_process_id,
+            port: ports[PORT_VAR],
+            ports,
+            ready: false,
+            preview_path: Self::preview_path(workspace_id),
+            preview_port: self.preview_port.get().copied(),
+        };
+        self.servers.insert(
+            workspace_id,
+            DevServerEntry {
+                info: info.clone(),
+                last_activity: Instant::now(),
+            },
+        );
+        Ok(info)
+    }
+
+    pub fn get(&self, workspace_id: Uuid) -> Option<DevServerInfo> {
+        self.servers.get(&workspace_id).map(|e| e.info.clone())
+    }
+
+    pub fn mark_ready(&self, workspace_id: Uuid, execution_process_id: Uuid) {
+        if let Some(mut entry) = self.servers.get_mut(&workspace_id)
+            && entry.info.execution_process_id == execution_process_id
+        {
+            entry.info.ready = true;
+        }
+    }
+
+    /// Record preview traffic, postponing idle shutdown
+    pub fn touch(&self, workspace_id: Uuid) {
+        if let Some(mut entry) = self.servers.get_mut(&workspace_id) {
+            entry.last_activity = Instant::now();
+        }
+    }
+
+    pub fn idle_for(&self, workspace_id: Uuid) -> Option<Duration> {
+        self.servers
+            .get(&workspace_id)
+            .map(|e| e.last_activity.elapsed())
+    }
+
+    /// Free the ports of a finished dev server, unless a newer one took over
+    pub fn release(&self, workspace_id: Uuid, execution_process_id: Uuid) {
+        self.servers.remove_if(&workspace_id, |_, e| {
+            e.info.execution_process_id == execution_process_id
+        });
+    }
+}
+
+/// Port variable names from the project setting, e.g. `API_PORT, HMR_PORT`
+pub fn parse_port_names(setting: Option<&str>) -> Vec<String> {
+    let mut names = Vec::new();
+    for name in setting
+        .unwrap_or_default()
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+    {
+        let valid = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !name.starts_with(|c: char| c.is_ascii_digit());
+        if !valid {
+            tracing::warn!("Ignoring invalid dev server port variable '{}'", name);
+            continue;
+        }
+        if name != PORT_VAR && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Compile the project ready pattern; an invalid one falls back to port probing
+pub fn ready_pattern(setting: Option<&str>) -> Option<Regex> {
+    let pattern = setting.map(str::trim).filter(|p| !p.is_empty())?;
+    match Regex::new(pattern) {
+        Ok(re) => Some(re),
+        Err(e) => {
+            tracing::warn!("Invalid dev server ready pattern '{}': {}", pattern, e);
+            None
+        }
+    }
+}
+
+/// Wait until the dev server output matches `pattern` or, without a pattern,
+/// until `port` accepts connections. Returns false if the process finished first.
+pub async fn wait_until_ready(store: Arc<MsgStore>, port: u16, pattern: Option<Regex>) -> bool {
+    let mut stream = store.history_plus_stream();
+    let mut probe = tokio::time::interval(PROBE_INTERVAL);
+    let mut buffer = String::new();
+
+    loop {
+        tokio::select! {
+            msg = stream.next() => match msg {
+                Some(Ok(LogMsg::Stdout(chunk) | LogMsg::Stderr(chunk))) => {
+                    let Some(pattern) = &pattern else { continue };
+                    buffer.push_str(&ANSI_ESCAPE.replace_all(&chunk, ""));
+                    if pattern.is_match(&buffer) {
+                        return true;
+                    }
+                    if buffer.len() > MAX_MATCH_BUFFER {
+                        let mut cut = buffer.len() - MAX_MATCH_BUFFER;
+                        while !buffer.is_char_boundary(cut) {
+                            cut += 1;
+                        }
+                        buffer.drain(..cut);
+                    }
+                }
+                Some(Ok(LogMsg::Finished)) | None => return false,
+                _ => {}
+            },
+            _ = probe.tick(), if pattern.is_none() => {
+                if tokio::net::TcpStream::connect((Ipv4Addr::LOCALHOST, port)).await.is_ok() {
+                    return true;
+                }
+            }
+        }
+    }
+}
+
+fn free_port(taken: &HashSet<u16>) -> Result<u16, DevServerError> {
+    for _ in 0..MAX_ALLOCATION_ATTEMPTS {
+        let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+            .local_addr()?
+            .port();
+        if !taken.contains(&port) {
+            return Ok(port);
+        }
+    }
+    Err(DevServerError::PortsExhausted(MAX_ALLOCATION_ATTEMPTS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_port_names() {
+        assert_eq!(
+            parse_port_names(Some("API_PORT, HMR_PORT PORT,API_PORT 1BAD bad-name")),
+            vec!["API_PORT".to_string(), "HMR_PORT".to_string()]
+        );
+        assert!(parse_port_names(None).is_empty());
+    }
+
+    #[test]
+    fn allocates_distinct_ports_per_workspace() {
+        let service = DevServerService::new();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let first = service
+            .allocate(a, Uuid::new_v4(), &["API_PORT".to_string()])
+            .unwrap();
+        let second = service.allocate(b, Uuid::new_v4(), &[]).unwrap();
+
+        assert_eq!(first.ports.len(), 2);
+        assert_eq!(first.port, first.ports[PORT_VAR]);
+        assert!(!first.ports.values().any(|p| *p == second.port));
+        assert_eq!(second.preview_path, format!("/preview/{b}/"));
+    }
+
+    #[test]
+    fn release_ignores_superseded_process() {
+        let service = DevServerService::new();
+        let workspace_id = Uuid::new_v4();
+        let old = service.allocate(workspace_id, Uuid::new_v4(), &[]).unwrap();
+        let new = service.allocate(workspace_id, Uuid::new_v4(), &[]).unwrap();
+
+        service.release(workspace_id, old.execution_process_id);
+        service.mark_ready(workspace_id, new.execution_process_id);
+        assert!(service.get(workspace_id).unwrap().ready);
+
+        service.release(workspace_id, new.execution_process_id);
+        assert!(service.get(workspace_id).is_none());
+    }
+
+    #[tokio::test]
+    async fn ready_pattern_matches_across_chunks() {
+        let store = Arc::new(MsgStore::new());
+        store.push_stdout("\x1b[32mLocal:\x1b[0m http://local");
+        store.push_stdout("host:5173/\n");
+        assert!(wait_until_ready(store, 1, ready_pattern(Some(r"Local:\s+http"))).await);
+
+        let store = Arc::new(MsgStore::new());
+        store.push_stdout("starting");
+        store.push_finished();
+        assert!(!wait_until_ready(store, 1, ready_pattern(Some("ready"))).await);
+    }
+}
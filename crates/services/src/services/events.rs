@@ -10,7 +10,7 @@ use db::{
 use serde_json::json;
 use sqlx::{Error as SqlxError, Sqlite, SqlitePool, decode::Decode, sqlite::SqliteOperation};
 use tokio::sync::RwLock;
-use utils::msg_store::MsgStore;
+use utils::{metrics, msg_store::MsgStore};
 use uuid::Uuid;
 
 #[path = "events/patches.rs"]
@@ -182,6 +182,7 @@ impl EventService {
                                         },
                                         Err(e) => {
                                             tracing::error!("Failed to fetch task: {:?}", e);
+                                            metrics::DB_QUERY_ERRORS.inc(&["events"]);
                                             return;
                                         }
                                     }
@@ -195,6 +196,7 @@ impl EventService {
                                         },
                                         Err(e) => {
                                             tracing::error!("Failed to fetch project: {:?}", e);
+                                            metrics::DB_QUERY_ERRORS.inc(&["events"]);
                                             return;
                                         }
                                     }
@@ -211,6 +213,7 @@ impl EventService {
                                                 "Failed to fetch workspace: {:?}",
                                                 e
                                             );
+                                            metrics::DB_QUERY_ERRORS.inc(&["events"]);
                                             return;
                                         }
                                     }
@@ -228,6 +231,7 @@ impl EventService {
                                                 "Failed to fetch execution_process: {:?}",
                                                 e
                                             );
+                                            metrics::DB_QUERY_ERRORS.inc(&["events"]);
                                             return;
                                         }
                                     }
@@ -242,6 +246,7 @@ impl EventService {
                                         },
                                         Err(e) => {
                                             tracing::error!("Failed to fetch scratch: {:?}", e);
+                                            metrics::DB_QUERY_ERRORS.inc(&["events"]);
                                             return;
                                         }
                                     }
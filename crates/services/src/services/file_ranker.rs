@@ -8,12 +8,13 @@ use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use db::models::project::{SearchMatchType, SearchResult};
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use tokio::task;
 
 use super::git::{GitService, GitServiceError};
 
 /// Statistics for a single file based on git history
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FileStat {
     /// Index in the commit history (0 = HEAD, 1 = parent of HEAD, ...)
     pub last_index: usize,
@@ -82,6 +83,14 @@ impl FileRanker {
         Ok(stats)
     }
 
+    /// Use stats computed earlier (e.g. loaded from disk) for `head_sha`
+    pub fn seed_stats(&self, repo_path: &Path, head_sha: String, stats: Arc<FileStats>) {
+        FILE_STATS_CACHE.insert(
+            repo_path.to_path_buf(),
+            RepoHistoryCache { head_sha, stats },
+        );
+    }
+
     /// Re-rank search results based on git history statistics
     pub fn rerank(&self, results: &mut [SearchResult], stats: &FileStats) {
         results.sort_by(|a, b| {
@@ -1,31 +1,48 @@
 use std::{
+    collections::{BTreeMap, HashSet},
     path::{Path, PathBuf},
     sync::Arc,
     time::{Duration, Instant},
 };
 
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use db::models::{
     project::{Project, SearchMatchType, SearchResult},
     project_repo::ProjectRepo,
 };
 use fst::{Map, MapBuilder};
+use futures::StreamExt;
 use ignore::WalkBuilder;
 use moka::future::Cache;
 use notify::{RecommendedWatcher, RecursiveMode};
-use notify_debouncer_full::{DebounceEventResult, new_debouncer};
+use notify_debouncer_full::{DebounceEventResult, Debouncer, RecommendedCache, new_debouncer};
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use thiserror::Error;
-use tokio::sync::mpsc;
+use tokio::{sync::mpsc, task::JoinHandle};
 use tracing::{error, info, warn};
 use ts_rs::TS;
+use utils::assets::file_search_index_dir;
 
 use super::{
     file_ranker::{FileRanker, FileStats},
+    file_search_snapshot::{
+        IndexSnapshot, SNAPSHOT_VERSION, SnapshotFile, SnapshotHeader, SnapshotStore,
+    },
+    filesystem_watcher,
     git::GitService,
+    sparse_checkout::in_cone,
 };
 
+/// Directories left out of the index even when ignored files are included
+const SKIP_DIRS: &[&str] = &[".git", "node_modules", "target", "dist", "build"];
+/// Filesystem timestamps can lag the wall clock and watcher events arrive
+/// debounced, so sync times are recorded this much earlier than observed
+const SYNC_MARGIN: Duration = Duration::from_secs(5);
+/// Index changes are written to disk at most this often per repo
+const PERSIST_DELAY: Duration = Duration::from_secs(10);
+
 /// Search mode for different use cases
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "lowercase")]
@@ -82,6 +99,8 @@ pub struct CachedRepo {
     pub indexed_files: Vec<IndexedFile>,
     pub stats: Arc<FileStats>,
     pub build_ts: Instant,
+    /// The index reflects the filesystem up to this time
+    pub synced_at: DateTime<Utc>,
 }
 
 /// Cache miss error
@@ -91,18 +110,33 @@ pub enum CacheError {
     BuildError(String),
 }
 
+/// Keeps a repository's watchers alive; dropping it stops them
+pub struct RepoWatcher {
+    _head_watcher: Debouncer<RecommendedWatcher, RecommendedCache>,
+    updates: JoinHandle<()>,
+}
+
+impl Drop for RepoWatcher {
+    fn drop(&mut self) {
+        self.updates.abort();
+    }
+}
+
 /// File search cache with FST indexing
 pub struct FileSearchCache {
     cache: Cache<PathBuf, CachedRepo>,
     git_service: GitService,
     file_ranker: FileRanker,
     build_queue: mpsc::UnboundedSender<PathBuf>,
-    watchers: DashMap<PathBuf, RecommendedWatcher>,
+    watchers: DashMap<PathBuf, RepoWatcher>,
+    snapshots: Arc<SnapshotStore>,
+    persist_queue: mpsc::UnboundedSender<PathBuf>,
 }
 
 impl FileSearchCache {
     pub fn new() -> Self {
         let (build_sender, build_receiver) = mpsc::unbounded_channel();
+        let (persist_sender, persist_receiver) = mpsc::unbounded_channel();
 
         // Create cache with 100MB limit and 1 hour TTL
         let cache = Cache::builder()
@@ -113,19 +147,29 @@ impl FileSearchCache {
         let cache_for_worker = cache.clone();
         let git_service = GitService::new();
         let file_ranker = FileRanker::new();
+        let snapshots = Arc::new(SnapshotStore::new(file_search_index_dir()));
 
         // Spawn background worker
         let worker_git_service = git_service.clone();
         let worker_file_ranker = file_ranker.clone();
+        let worker_snapshots = snapshots.clone();
+        let worker_persist_queue = persist_sender.clone();
         tokio::spawn(async move {
             Self::background_worker(
                 build_receiver,
                 cache_for_worker,
                 worker_git_service,
                 worker_file_ranker,
+                worker_snapshots,
+                worker_persist_queue,
             )
             .await;
         });
+        tokio::spawn(Self::persist_worker(
+            persist_receiver,
+            cache.clone(),
+            snapshots.clone(),
+        ));
 
         Self {
             cache,
@@ -133,15 +177,19 @@ impl FileSearchCache {
             file_ranker,
             build_queue: build_sender,
             watchers: DashMap::new(),
+            snapshots,
+            persist_queue: persist_sender,
         }
     }
 
-    /// Search files in repository using cache
+    /// Search files in repository using cache. With a sparse-checkout cone,
+    /// only paths that attempt worktrees check out are returned.
     pub async fn search(
         &self,
         repo_path: &Path,
         query: &str,
         mode: SearchMode,
+        sparse_paths: &[String],
     ) -> Result<Vec<SearchResult>, CacheError> {
         let repo_path_buf = repo_path.to_path_buf();
 
@@ -151,7 +199,9 @@ impl FileSearchCache {
             && head_info.oid == cached.head_sha
         {
             // Cache hit - perform fast search with mode-based filtering
-            return Ok(self.search_in_cache(&cached, query, mode).await);
+            return Ok(self
+                .search_in_cache(&cached, query, mode, sparse_paths)
+                .await);
         }
 
         // Cache miss - trigger background refresh and return error
@@ -175,10 +225,28 @@ impl FileSearchCache {
         Ok(())
     }
 
+    /// Load every repository with a persisted index. Loading only lists the
+    /// directories changed since the index was saved.
+    pub async fn warm_persisted(&self) -> Result<(), String> {
+        let snapshots = self.snapshots.clone();
+        let repo_paths = tokio::task::spawn_blocking(move || snapshots.repo_paths())
+            .await
+            .map_err(|e| format!("Failed to list file search snapshots: {e}"))?;
+        info!(
+            "Loading persisted file search indexes for {} repositories",
+            repo_paths.len()
+        );
+        self.warm_repos(repo_paths).await
+    }
+
     /// Pre-warm cache for most active projects
     pub async fn warm_most_active(&self, db_pool: &SqlitePool, limit: i32) -> Result<(), String> {
         info!("Starting file search cache warming...");
 
+        if let Err(e) = self.warm_persisted().await {
+            warn!("Failed to load persisted file search indexes: {}", e);
+        }
+
         // Get most active projects
         let active_projects = Project::find_most_active(db_pool, limit)
             .await
@@ -233,13 +301,16 @@ impl FileSearchCache {
         cached: &CachedRepo,
         query: &str,
         mode: SearchMode,
+        sparse_paths: &[String],
     ) -> Vec<SearchResult> {
         let query_lower = query.to_lowercase();
         let mut results = Vec::new();
 
         // Search through indexed files with mode-based filtering
         for indexed_file in &cached.indexed_files {
-            if indexed_file.path_lowercase.contains(&query_lower) {
+            if indexed_file.path_lowercase.contains(&query_lower)
+                && in_cone(&indexed_file.path, indexed_file.is_file, sparse_paths)
+            {
                 // Apply mode-based filtering
                 match mode {
                     SearchMode::TaskForm => {
@@ -282,6 +353,31 @@ impl FileSearchCache {
             .get_head_info(&repo_path_buf)
             .map_err(|e| format!("Failed to get HEAD info: {e}"))?;
 
+        // A snapshot for the current HEAD only needs the changes made since
+        // it was saved. After HEAD moves, stats and paths are rebuilt.
+        match self.snapshots.load(repo_path) {
+            Ok(Some(snapshot)) if snapshot.header.head_sha == head_info.oid => {
+                match Self::restore_snapshot(repo_path, snapshot) {
+                    Ok(cached) => {
+                        self.file_ranker.seed_stats(
+                            repo_path,
+                            cached.head_sha.clone(),
+                            cached.stats.clone(),
+                        );
+                        return Ok(cached);
+                    }
+                    Err(e) => warn!("Discarding file search snapshot for {:?}: {}", repo_path, e),
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!(
+                "Failed to load file search snapshot for {:?}: {}",
+                repo_path, e
+            ),
+        }
+
+        let synced_at = Utc::now() - SYNC_MARGIN;
+
         // Get git stats
         let stats = self
             .file_ranker
@@ -299,152 +395,85 @@ impl FileSearchCache {
             indexed_files: file_index.files,
             stats,
             build_ts: Instant::now(),
+            synced_at,
         })
     }
 
-    /// Build FST index from filesystem traversal using superset approach
-    fn build_file_index(repo_path: &Path) -> Result<FileIndex, FileIndexError> {
-        let mut indexed_files = Vec::new();
-        let mut fst_keys = Vec::new();
-
-        // Build superset walker - include ignored files but exclude .git and performance killers
-        let mut builder = WalkBuilder::new(repo_path);
-        builder
-            .git_ignore(false) // Include all files initially
-            .git_global(false)
-            .git_exclude(false)
-            .hidden(false) // Show hidden files like .env
-            .filter_entry(|entry| {
-                let name = entry.file_name().to_string_lossy();
-                // Always exclude .git directories
-                if name == ".git" {
-                    return false;
-                }
-                // Exclude performance killers even when including ignored files
-                if name == "node_modules" || name == "target" || name == "dist" || name == "build" {
-                    return false;
-                }
-                true
-            });
-
-        let walker = builder.build();
-
-        // Create a second walker for checking ignore status
-        let ignore_walker = WalkBuilder::new(repo_path)
-            .git_ignore(true) // This will tell us what's ignored
-            .git_global(true)
-            .git_exclude(true)
-            .hidden(false)
-            .filter_entry(|entry| {
-                let name = entry.file_name().to_string_lossy();
-                name != ".git"
-            })
-            .build();
-
-        // Collect paths from ignore-aware walker to know what's NOT ignored
-        let mut non_ignored_paths = std::collections::HashSet::new();
-        for result in ignore_walker {
-            if let Ok(entry) = result
-                && let Ok(relative_path) = entry.path().strip_prefix(repo_path)
-            {
-                non_ignored_paths.insert(relative_path.to_path_buf());
-            }
-        }
-
-        // Now walk all files and determine their ignore status
-        for result in walker {
-            let entry = result?;
-            let path = entry.path();
-
-            if path == repo_path {
-                continue;
-            }
-
-            let relative_path = path.strip_prefix(repo_path)?;
-            let relative_path_str = relative_path.to_string_lossy().to_string();
-            let relative_path_lower = relative_path_str.to_lowercase();
-
-            // Skip empty paths
-            if relative_path_lower.is_empty() {
-                continue;
-            }
-
-            // Determine if this file is ignored
-            let is_ignored = !non_ignored_paths.contains(relative_path);
-
-            let file_name = path
-                .file_name()
-                .map(|name| name.to_string_lossy().to_lowercase())
-                .unwrap_or_default();
-
-            // Determine match type
-            let match_type = if !file_name.is_empty() {
-                SearchMatchType::FileName
-            } else if path
-                .parent()
-                .and_then(|p| p.file_name())
-                .map(|name| name.to_string_lossy().to_lowercase())
-                .unwrap_or_default()
-                != relative_path_lower
-            {
-                SearchMatchType::DirectoryName
-            } else {
-                SearchMatchType::FullPath
-            };
-
-            let indexed_file = IndexedFile {
-                path: relative_path_str,
-                is_file: path.is_file(),
-                match_type,
-                path_lowercase: Arc::from(relative_path_lower.as_str()),
-                is_ignored,
-            };
-
-            // Store the key for FST along with file index
-            let file_index = indexed_files.len() as u64;
-            fst_keys.push((relative_path_lower, file_index));
-            indexed_files.push(indexed_file);
-        }
-
-        // Sort keys for FST (required for building)
-        fst_keys.sort_by(|a, b| a.0.cmp(&b.0));
-
-        // Remove duplicates (keep first occurrence)
-        fst_keys.dedup_by(|a, b| a.0 == b.0);
-
-        // Build FST
-        let mut fst_builder = MapBuilder::memory();
-        for (key, value) in fst_keys {
-            fst_builder.insert(&key, value)?;
-        }
+    /// Rebuild a cache entry from a snapshot, catching up with changes made
+    /// while nothing was watching the repo
+    fn restore_snapshot(
+        repo_path: &Path,
+        snapshot: IndexSnapshot,
+    ) -> Result<CachedRepo, FileIndexError> {
+        let header = snapshot.header;
+        let reconciled_at = Utc::now() - SYNC_MARGIN;
+
+        let files = header
+            .files
+            .into_iter()
+            .map(|file| indexed_file(file.path, file.is_file, file.is_ignored))
+            .collect();
+        let mut tree = FileTree::new(repo_path, files);
+        tree.reconcile(header.synced_at)?;
+
+        let changed = tree.changed;
+        let indexed_files = tree.into_files();
+        let fst_index = match Map::new(snapshot.fst) {
+            Ok(map) if !changed => map,
+            _ => build_fst(&indexed_files)?,
+        };
 
-        let fst_map = fst_builder.into_map();
-        Ok(FileIndex {
-            files: indexed_files,
-            map: fst_map,
+        Ok(CachedRepo {
+            head_sha: header.head_sha,
+            fst_index,
+            indexed_files,
+            stats: Arc::new(header.stats),
+            build_ts: Instant::now(),
+            synced_at: reconciled_at,
         })
     }
 
+    /// Build FST index from filesystem traversal using superset approach
+    fn build_file_index(repo_path: &Path) -> Result<FileIndex, FileIndexError> {
+        let files = scan(repo_path, repo_path, None)?;
+        let map = build_fst(&files)?;
+        Ok(FileIndex { files, map })
+    }
+
     /// Background worker for cache building
     async fn background_worker(
         mut build_receiver: mpsc::UnboundedReceiver<PathBuf>,
         cache: Cache<PathBuf, CachedRepo>,
         git_service: GitService,
         file_ranker: FileRanker,
+        snapshots: Arc<SnapshotStore>,
+        persist_queue: mpsc::UnboundedSender<PathBuf>,
     ) {
         while let Some(repo_path) = build_receiver.recv().await {
+            // Skip repos queued more than once that are already current
+            if let Some(cached) = cache.get(&repo_path).await
+                && git_service
+                    .get_head_info(&repo_path)
+                    .is_ok_and(|head| head.oid == cached.head_sha)
+            {
+                continue;
+            }
+
             let cache_builder = FileSearchCache {
                 cache: cache.clone(),
                 git_service: git_service.clone(),
                 file_ranker: file_ranker.clone(),
                 build_queue: mpsc::unbounded_channel().0, // Dummy sender
                 watchers: DashMap::new(),
+                snapshots: snapshots.clone(),
+                persist_queue: persist_queue.clone(),
             };
 
             match cache_builder.build_repo_cache(&repo_path).await {
                 Ok(cached_repo) => {
                     cache.insert(repo_path.clone(), cached_repo).await;
                     info!("Successfully cached repo: {:?}", repo_path);
+                    let _ = persist_queue.send(repo_path);
                 }
                 Err(e) => {
                     error!("Failed to cache repo {:?}: {}", repo_path, e);
@@ -453,7 +482,54 @@ impl FileSearchCache {
         }
     }
 
-    /// Setup file watcher for repository
+    /// Write changed indexes to disk, batching bursts of changes
+    async fn persist_worker(
+        mut persist_receiver: mpsc::UnboundedReceiver<PathBuf>,
+        cache: Cache<PathBuf, CachedRepo>,
+        snapshots: Arc<SnapshotStore>,
+    ) {
+        while let Some(repo_path) = persist_receiver.recv().await {
+            tokio::time::sleep(PERSIST_DELAY).await;
+            let mut pending = HashSet::from([repo_path]);
+            while let Ok(repo_path) = persist_receiver.try_recv() {
+                pending.insert(repo_path);
+            }
+
+            for repo_path in pending {
+                let Some(cached) = cache.get(&repo_path).await else {
+                    continue;
+                };
+                let snapshots = snapshots.clone();
+                let snapshot = IndexSnapshot {
+                    header: SnapshotHeader {
+                        version: SNAPSHOT_VERSION,
+                        repo_path: repo_path.clone(),
+                        head_sha: cached.head_sha.clone(),
+                        synced_at: cached.synced_at,
+                        files: cached
+                            .indexed_files
+                            .iter()
+                            .map(|file| SnapshotFile {
+                                path: file.path.clone(),
+                                is_file: file.is_file,
+                                is_ignored: file.is_ignored,
+                            })
+                            .collect(),
+                        stats: (*cached.stats).clone(),
+                    },
+                    fst: cached.fst_index.as_fst().as_bytes().to_vec(),
+                };
+                match tokio::task::spawn_blocking(move || snapshots.save(&snapshot)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => warn!("Failed to persist file index for {:?}: {}", repo_path, e),
+                    Err(e) => error!("File index persist task failed: {}", e),
+                }
+            }
+        }
+    }
+
+    /// Setup file watcher for repository. HEAD changes rebuild the index,
+    /// other changes are applied to it in place.
     pub async fn setup_watcher(&self, repo_path: &Path) -> Result<(), String> {
         let repo_path_buf = repo_path.to_path_buf();
 
@@ -467,6 +543,7 @@ impl FileSearchCache {
         }
 
         let build_queue = self.build_queue.clone();
+        let snapshots = self.snapshots.clone();
         let watched_path = repo_path_buf.clone();
 
         let (tx, mut rx) = mpsc::unbounded_channel();
@@ -500,15 +577,107 @@ impl FileSearchCache {
         tokio::spawn(async move {
             while rx.recv().await.is_some() {
                 info!("HEAD changed for repo: {:?}", watched_path);
+                // The persisted index describes the old HEAD
+                snapshots.remove(&watched_path);
                 if let Err(e) = build_queue.send(watched_path.clone()) {
                     error!("Failed to enqueue cache refresh: {}", e);
                 }
             }
         });
 
+        let updates = self.spawn_update_watcher(repo_path_buf.clone()).await?;
+        self.watchers.insert(
+            repo_path_buf,
+            RepoWatcher {
+                _head_watcher: debouncer,
+                updates,
+            },
+        );
+
         info!("Setup file watcher for repo: {:?}", repo_path);
         Ok(())
     }
+
+    /// Apply created, removed and renamed paths to the cached index
+    async fn spawn_update_watcher(&self, repo_path: PathBuf) -> Result<JoinHandle<()>, String> {
+        let watch_root = repo_path.clone();
+        let (debouncer, mut watcher_rx, canonical_root) =
+            tokio::task::spawn_blocking(move || filesystem_watcher::async_watcher(watch_root))
+                .await
+                .map_err(|e| format!("Failed to spawn watcher setup: {e}"))?
+                .map_err(|e| format!("Failed to watch repo: {e}"))?;
+
+        let cache = self.cache.clone();
+        let git_service = self.git_service.clone();
+        let persist_queue = self.persist_queue.clone();
+
+        Ok(tokio::spawn(async move {
+            let _debouncer_guard = debouncer;
+            while let Some(result) = watcher_rx.next().await {
+                let events = match result {
+                    Ok(events) => events,
+                    Err(errors) => {
+                        warn!("File index watcher error for {:?}: {:?}", repo_path, errors);
+                        continue;
+                    }
+                };
+                let received_at = Utc::now();
+                let changed: Vec<String> = events
+                    .iter()
+                    .flat_map(|event| &event.paths)
+                    .filter_map(|path| {
+                        path.strip_prefix(&canonical_root)
+                            .or_else(|_| path.strip_prefix(&repo_path))
+                            .ok()
+                            .map(|p| p.to_string_lossy().replace('\\', "/"))
+                    })
+                    .filter(|path| !path.is_empty())
+                    .collect();
+                if changed.is_empty() {
+                    continue;
+                }
+
+                // Until the first build finishes there is nothing to update,
+                // and after HEAD moves the queued rebuild replaces the index
+                let Some(cached) = cache.get(&repo_path).await else {
+                    continue;
+                };
+                if !git_service
+                    .get_head_info(&repo_path)
+                    .is_ok_and(|head| head.oid == cached.head_sha)
+                {
+                    continue;
+                }
+
+                let root = repo_path.clone();
+                let updated = tokio::task::spawn_blocking(move || {
+                    let mut tree = FileTree::new(&root, cached.indexed_files.clone());
+                    tree.apply(&changed)?;
+                    if !tree.changed {
+                        return Ok(None);
+                    }
+                    let indexed_files = tree.into_files();
+                    Ok::<_, FileIndexError>(Some(CachedRepo {
+                        fst_index: build_fst(&indexed_files)?,
+                        indexed_files,
+                        synced_at: received_at - SYNC_MARGIN,
+                        ..cached
+                    }))
+                })
+                .await;
+
+                match updated {
+                    Ok(Ok(Some(updated))) => {
+                        cache.insert(repo_path.clone(), updated).await;
+                        let _ = persist_queue.send(repo_path.clone());
+                    }
+                    Ok(Ok(None)) => {}
+                    Ok(Err(e)) => warn!("Failed to update file index for {:?}: {}", repo_path, e),
+                    Err(e) => error!("File index update task failed: {}", e),
+                }
+            }
+        }))
+    }
 }
 
 impl Default for FileSearchCache {
@@ -516,3 +685,367 @@ impl Default for FileSearchCache {
         Self::new()
     }
 }
+
+fn indexed_file(relative_path: String, is_file: bool, is_ignored: bool) -> IndexedFile {
+    let relative_path_lower = relative_path.to_lowercase();
+    let path = Path::new(&relative_path);
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    // Determine match type
+    let match_type = if !file_name.is_empty() {
+        SearchMatchType::FileName
+    } else if path
+        .parent()
+        .and_then(|p| p.file_name())
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+        != relative_path_lower
+    {
+        SearchMatchType::DirectoryName
+    } else {
+        SearchMatchType::FullPath
+    };
+
+    IndexedFile {
+        path_lowercase: Arc::from(relative_path_lower.as_str()),
+        path: relative_path,
+        is_file,
+        match_type,
+        is_ignored,
+    }
+}
+
+fn is_skipped(name: &str) -> bool {
+    SKIP_DIRS.contains(&name)
+}
+
+/// Index the entries under `dir` (relative to `repo_path`), at most
+/// `max_depth` levels deep
+fn scan(
+    repo_path: &Path,
+    dir: &Path,
+    max_depth: Option<usize>,
+) -> Result<Vec<IndexedFile>, FileIndexError> {
+    let mut indexed_files = Vec::new();
+
+    // Build superset walker - include ignored files but exclude .git and performance killers
+    let mut builder = WalkBuilder::new(dir);
+    builder
+        .git_ignore(false) // Include all files initially
+        .git_global(false)
+        .git_exclude(false)
+        .hidden(false) // Show hidden files like .env
+        .max_depth(max_depth)
+        .filter_entry(|entry| !is_skipped(&entry.file_name().to_string_lossy()));
+
+    let walker = builder.build();
+
+    // Create a second walker for checking ignore status
+    let ignore_walker = WalkBuilder::new(dir)
+        .git_ignore(true) // This will tell us what's ignored
+        .git_global(true)
+        .git_exclude(true)
+        .hidden(false)
+        .max_depth(max_depth)
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            name != ".git"
+        })
+        .build();
+
+    // Collect paths from ignore-aware walker to know what's NOT ignored
+    let mut non_ignored_paths = HashSet::new();
+    for result in ignore_walker {
+        if let Ok(entry) = result
+            && let Ok(relative_path) = entry.path().strip_prefix(repo_path)
+        {
+            non_ignored_paths.insert(relative_path.to_path_buf());
+        }
+    }
+
+    // Now walk all files and determine their ignore status
+    for result in walker {
+        let entry = result?;
+        let path = entry.path();
+
+        if path == dir {
+            continue;
+        }
+
+        let relative_path = path.strip_prefix(repo_path)?;
+        let relative_path_str = relative_path.to_string_lossy().replace('\\', "/");
+
+        // Skip empty paths
+        if relative_path_str.is_empty() {
+            continue;
+        }
+
+        // Determine if this file is ignored
+        let is_ignored = !non_ignored_paths.contains(relative_path);
+
+        indexed_files.push(indexed_file(relative_path_str, path.is_file(), is_ignored));
+    }
+
+    Ok(indexed_files)
+}
+
+fn build_fst(indexed_files: &[IndexedFile]) -> Result<Map<Vec<u8>>, FileIndexError> {
+    let mut fst_keys: Vec<(&str, u64)> = indexed_files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| (&*file.path_lowercase, index as u64))
+        .collect();
+
+    // Sort keys for FST (required for building)
+    fst_keys.sort_by(|a, b| a.0.cmp(b.0));
+
+    // Remove duplicates (keep first occurrence)
+    fst_keys.dedup_by(|a, b| a.0 == b.0);
+
+    // Build FST
+    let mut fst_builder = MapBuilder::memory();
+    for (key, value) in fst_keys {
+        fst_builder.insert(key, value)?;
+    }
+
+    Ok(fst_builder.into_map())
+}
+
+/// Index entries keyed by path, for applying filesystem changes
+struct FileTree<'a> {
+    repo_path: &'a Path,
+    entries: BTreeMap<String, IndexedFile>,
+    changed: bool,
+}
+
+impl<'a> FileTree<'a> {
+    fn new(repo_path: &'a Path, files: Vec<IndexedFile>) -> Self {
+        Self {
+            repo_path,
+            entries: files
+                .into_iter()
+                .map(|file| (file.path.clone(), file))
+                .collect(),
+            changed: false,
+        }
+    }
+
+    fn into_files(self) -> Vec<IndexedFile> {
+        self.entries.into_values().collect()
+    }
+
+    fn descendants(&self, dir: &str) -> Vec<String> {
+        let prefix = format!("{dir}/");
+        self.entries
+            .range(prefix.clone()..)
+            .take_while(|(path, _)| path.starts_with(&prefix))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    fn remove(&mut self, path: &str) {
+        if self.entries.remove(path).is_some() {
+            self.changed = true;
+        }
+        for descendant in self.descendants(path) {
+            self.entries.remove(&descendant);
+        }
+    }
+
+    fn insert(&mut self, file: IndexedFile) {
+        let is_dir = !file.is_file;
+        let path = file.path.clone();
+        self.entries.insert(path.clone(), file);
+        self.changed = true;
+        if is_dir {
+            self.insert_subtree(&path);
+        }
+    }
+
+    fn insert_subtree(&mut self, dir: &str) {
+        match scan(self.repo_path, &self.repo_path.join(dir), None) {
+            Ok(files) => {
+                for file in files {
+                    self.entries.insert(file.path.clone(), file);
+                }
+            }
+            Err(e) => warn!("Failed to index {:?}: {}", dir, e),
+        }
+    }
+
+    /// List a directory again and apply the difference to its children
+    fn refresh_dir(&mut self, dir: &str) -> Result<(), FileIndexError> {
+        let fresh = scan(self.repo_path, &self.repo_path.join(dir), Some(1))?;
+        let fresh_paths: HashSet<&str> = fresh.iter().map(|file| file.path.as_str()).collect();
+
+        let children = if dir.is_empty() {
+            self.entries.keys().cloned().collect()
+        } else {
+            self.descendants(dir)
+        };
+        let prefix_len = if dir.is_empty() { 0 } else { dir.len() + 1 };
+        let stale: Vec<String> = children
+            .into_iter()
+            .filter(|path| {
+                !path[prefix_len..].contains('/') && !fresh_paths.contains(path.as_str())
+            })
+            .collect();
+        for path in stale {
+            self.remove(&path);
+        }
+
+        for file in fresh {
+            match self.entries.get(&file.path) {
+                Some(existing) if existing.is_file == file.is_file => {}
+                Some(_) => {
+                    // Replaced by an entry of the other kind
+                    self.remove(&file.path);
+                    self.insert(file);
+                }
+                None => self.insert(file),
+            }
+        }
+        Ok(())
+    }
+
+    /// Catch up with a filesystem that may have changed since `synced_at`.
+    /// Adding, removing or renaming an entry updates its directory's mtime,
+    /// so only directories modified since then are listed again.
+    fn reconcile(&mut self, synced_at: DateTime<Utc>) -> Result<(), FileIndexError> {
+        let dirs: Vec<String> = std::iter::once(String::new())
+            .chain(
+                self.entries
+                    .values()
+                    .filter(|file| !file.is_file)
+                    .map(|file| file.path.clone()),
+            )
+            .collect();
+
+        for dir in dirs {
+            if !dir.is_empty() && !self.entries.contains_key(&dir) {
+                continue; // Removed with its parent
+            }
+            let modified = std::fs::metadata(self.repo_path.join(&dir))
+                .and_then(|meta| meta.modified())
+                .map(DateTime::<Utc>::from);
+            match modified {
+                Ok(modified) if modified < synced_at => {}
+                Ok(_) => self.refresh_dir(&dir)?,
+                // Gone; its parent was modified and drops it
+                Err(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply paths reported by the filesystem watcher
+    fn apply(&mut self, changed: &[String]) -> Result<(), FileIndexError> {
+        for path in changed {
+            if path.split('/').any(is_skipped) {
+                continue;
+            }
+            let full = self.repo_path.join(path);
+            if full.is_dir() {
+                if self.entries.get(path).is_some_and(|entry| !entry.is_file) {
+                    self.refresh_dir(path)?;
+                } else {
+                    self.remove(path);
+                    self.insert_ancestors(path);
+                    self.insert(indexed_file(path.clone(), false, false));
+                }
+            } else if full.is_file() {
+                if !self.entries.get(path).is_some_and(|entry| entry.is_file) {
+                    self.remove(path);
+                    self.insert_ancestors(path);
+                    // Events for ignored paths are filtered by the watcher
+                    self.insert(indexed_file(path.clone(), true, false));
+                }
+            } else {
+                self.remove(path);
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_ancestors(&mut self, path: &str) {
+        let mut end = 0;
+        while let Some(offset) = path[end..].find('/') {
+            end += offset;
+            let ancestor = &path[..end];
+            if !self.entries.contains_key(ancestor) {
+                self.entries.insert(
+                    ancestor.to_string(),
+                    indexed_file(ancestor.to_string(), false, false),
+                );
+                self.changed = true;
+            }
+            end += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn paths(files: &[IndexedFile]) -> Vec<&str> {
+        let mut paths: Vec<&str> = files.iter().map(|file| file.path.as_str()).collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn reconcile_catches_up_with_modified_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src/old")).unwrap();
+        fs::write(root.join("src/lib.rs"), "").unwrap();
+        fs::write(root.join("src/old/mod.rs"), "").unwrap();
+        let files = scan(root, root, None).unwrap();
+
+        fs::remove_dir_all(root.join("src/old")).unwrap();
+        fs::create_dir_all(root.join("src/new")).unwrap();
+        fs::write(root.join("src/new/mod.rs"), "").unwrap();
+
+        // Nothing is listed again for a snapshot newer than the changes
+        let mut tree = FileTree::new(root, files.clone());
+        tree.reconcile(Utc::now() + chrono::Duration::hours(1))
+            .unwrap();
+        assert!(!tree.changed);
+
+        let mut tree = FileTree::new(root, files);
+        tree.reconcile(Utc::now() - chrono::Duration::hours(1))
+            .unwrap();
+        assert!(tree.changed);
+        assert_eq!(
+            paths(&tree.into_files()),
+            paths(&scan(root, root, None).unwrap())
+        );
+    }
+
+    #[test]
+    fn apply_adds_and_removes_watched_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("a.rs"), "").unwrap();
+        let files = scan(root, root, None).unwrap();
+
+        fs::remove_file(root.join("a.rs")).unwrap();
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::write(root.join("src/nested/b.rs"), "").unwrap();
+
+        let mut tree = FileTree::new(root, files);
+        tree.apply(&["a.rs".to_string(), "src/nested/b.rs".to_string()])
+            .unwrap();
+        assert_eq!(
+            paths(&tree.into_files()),
+            vec!["src", "src/nested", "src/nested/b.rs"]
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::file_ranker::FileStats;
+
+/// Bumped whenever the snapshot layout changes; older snapshots are dropped
+pub const SNAPSHOT_VERSION: u32 = 1;
+const SNAPSHOT_EXTENSION: &str = "idx";
+/// Snapshots not refreshed for this long are pruned on startup
+const MAX_SNAPSHOT_AGE_DAYS: i64 = 30;
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Unsupported snapshot version {0}")]
+    Version(u32),
+    #[error("Snapshot is truncated")]
+    Truncated,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotFile {
+    pub path: String,
+    pub is_file: bool,
+    pub is_ignored: bool,
+}
+
+/// Everything but the FST, stored as JSON in front of it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    pub version: u32,
+    pub repo_path: PathBuf,
+    /// HEAD the index and stats were built for; a different HEAD invalidates
+    /// the snapshot
+    pub head_sha: String,
+    /// The index reflects the filesystem up to this time. Directories modified
+    /// later are listed again when the snapshot is loaded.
+    pub synced_at: DateTime<Utc>,
+    pub files: Vec<SnapshotFile>,
+    pub stats: FileStats,
+}
+
+pub struct IndexSnapshot {
+    pub header: SnapshotHeader,
+    pub fst: Vec<u8>,
+}
+
+/// File search indexes persisted under the asset dir, one file per repo:
+/// an 8-byte little-endian header length, the JSON header, then the FST.
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path_for(&self, repo_path: &Path) -> PathBuf {
+        let digest = Sha256::digest(repo_path.to_string_lossy().as_bytes());
+        let name: String = digest[..16].iter().map(|b| format!("{b:02x}")).collect();
+        self.dir.join(format!("{name}.{SNAPSHOT_EXTENSION}"))
+    }
+
+    fn read_header(reader: &mut impl Read) -> Result<SnapshotHeader, SnapshotError> {
+        let mut len = [0u8; 8];
+        reader
+            .read_exact(&mut len)
+            .map_err(|_| SnapshotError::Truncated)?;
+        let mut header = vec![0u8; u64::from_le_bytes(len) as usize];
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| SnapshotError::Truncated)?;
+        let header: SnapshotHeader = serde_json::from_slice(&header)?;
+        if header.version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::Version(header.version));
+        }
+        Ok(header)
+    }
+
+    pub fn load(&self, repo_path: &Path) -> Result<Option<IndexSnapshot>, SnapshotError> {
+        let mut file = match fs::File::open(self.path_for(repo_path)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let header = Self::read_header(&mut file)?;
+        // Hash collisions are practically impossible, but a moved asset dir
+        // could pair a snapshot with the wrong repo
+        if header.repo_path != repo_path {
+            return Ok(None);
+        }
+        let mut fst = Vec::new();
+        file.read_to_end(&mut fst)?;
+        Ok(Some(IndexSnapshot { header, fst }))
+    }
+
+    /// Write the snapshot atomically, replacing any previous one
+    pub fn save(&self, snapshot: &IndexSnapshot) -> Result<(), SnapshotError> {
+        fs::create_dir_all(&self.dir)?;
+        let header = serde_json::to_vec(&snapshot.header)?;
+        let target = self.path_for(&snapshot.header.repo_path);
+        let staging = self.dir.join(format!(".staging-{}", Uuid::new_v4()));
+
+        let write = || -> Result<(), SnapshotError> {
+            let mut file = fs::File::create(&staging)?;
+            file.write_all(&(header.len() as u64).to_le_bytes())?;
+            file.write_all(&header)?;
+            file.write_all(&snapshot.fst)?;
+            file.sync_all()?;
+            fs::rename(&staging, &target)?;
+            Ok(())
+        };
+        let result = write();
+        if result.is_err() {
+            let _ = fs::remove_file(&staging);
+        }
+        result
+    }
+
+    pub fn remove(&self, repo_path: &Path) {
+        let _ = fs::remove_file(self.path_for(repo_path));
+    }
+
+    /// Repositories with a usable snapshot. Snapshots of removed repos, from
+    /// older versions, or unused for a long time are deleted.
+    pub fn repo_paths(&self) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let cutoff = Utc::now() - Duration::days(MAX_SNAPSHOT_AGE_DAYS);
+
+        let mut repo_paths = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_snapshot = path
+                .extension()
+                .is_some_and(|ext| ext == SNAPSHOT_EXTENSION);
+            let header = fs::File::open(&path)
+                .map_err(SnapshotError::from)
+                .and_then(|mut file| Self::read_header(&mut file));
+            match header {
+                Ok(header)
+                    if is_snapshot && header.synced_at > cutoff && header.repo_path.is_dir() =>
+                {
+                    repo_paths.push(header.repo_path);
+                }
+                _ => {
+                    tracing::debug!("Removing file search snapshot {:?}", path);
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+        repo_paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_round_trip_and_prune_removed_repos() {
+        let store_dir = tempfile::tempdir().unwrap();
+        let repo = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(store_dir.path().to_path_buf());
+
+        let snapshot = IndexSnapshot {
+            header: SnapshotHeader {
+                version: SNAPSHOT_VERSION,
+                repo_path: repo.path().to_path_buf(),
+                head_sha: "abc".to_string(),
+                synced_at: Utc::now(),
+                files: vec![SnapshotFile {
+                    path: "src/main.rs".to_string(),
+                    is_file: true,
+                    is_ignored: false,
+                }],
+                stats: FileStats::new(),
+            },
+            fst: vec![1, 2, 3],
+        };
+        store.save(&snapshot).unwrap();
+
+        let loaded = store.load(repo.path()).unwrap().unwrap();
+        assert_eq!(loaded.header.head_sha, "abc");
+        assert_eq!(loaded.header.files[0].path, "src/main.rs");
+        assert_eq!(loaded.fst, vec![1, 2, 3]);
+        assert_eq!(store.repo_paths(), vec![repo.path().to_path_buf()]);
+
+        let repo_path = repo.path().to_path_buf();
+        drop(repo);
+        assert!(store.repo_paths().is_empty());
+        assert!(store.load(&repo_path).unwrap().is_none());
+    }
+}
@@ -8,7 +8,10 @@ use git2::{
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ts_rs::TS;
-use utils::diff::{Diff, DiffChangeKind, FileDiffDetails, compute_line_change_counts};
+use utils::{
+    diff::{Diff, DiffChangeKind, FileDiffDetails, compute_line_change_counts},
+    metrics,
+};
 
 mod cli;
 
@@ -16,7 +19,7 @@ use cli::{ChangeType, StatusDiffEntry, StatusDiffOptions};
 pub use cli::{GitCli, GitCliError};
 
 use super::file_ranker::FileStat;
-use crate::services::github::GitHubRepoInfo;
+use crate::services::git_host::GitHostRepoInfo;
 
 #[derive(Debug, Error)]
 pub enum GitServiceError {
@@ -57,7 +60,7 @@ pub enum ConflictOp {
     Revert,
 }
 
-#[derive(Debug, Serialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct GitBranch {
     pub name: String,
     pub is_current: bool,
@@ -66,6 +69,14 @@ pub struct GitBranch {
     pub last_commit_date: DateTime<Utc>,
 }
 
+/// The three index stages of a conflicted path; `None` when a side is absent.
+#[derive(Debug, Clone)]
+pub struct ConflictSides {
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct HeadInfo {
     pub branch: String,
@@ -271,6 +282,7 @@ impl GitService {
     }
 
     pub fn commit(&self, path: &Path, message: &str) -> Result<bool, GitServiceError> {
+        let _timer = metrics::GIT_OPERATION_DURATION.start_timer(&["git_service", "commit"]);
         // Use Git CLI to respect sparse-checkout semantics for staging and commit
         let git = GitCli::new();
         let has_changes = git
@@ -296,6 +308,7 @@ impl GitService {
         target: DiffTarget,
         path_filter: Option<&[&str]>,
     ) -> Result<Vec<Diff>, GitServiceError> {
+        let _timer = metrics::GIT_OPERATION_DURATION.start_timer(&["git_service", "get_diffs"]);
         match target {
             DiffTarget::Worktree {
                 worktree_path,
@@ -790,6 +803,7 @@ impl GitService {
         base_branch_name: &str,
         commit_message: &str,
     ) -> Result<String, GitServiceError> {
+        let _timer = metrics::GIT_OPERATION_DURATION.start_timer(&["git_service", "merge_changes"]);
         // Open the repositories
         let task_repo = self.open_repo(task_worktree_path)?;
         let base_repo = self.open_repo(base_worktree_path)?;
@@ -906,6 +920,8 @@ impl GitService {
         branch_name: &str,
         base_branch_name: &str,
     ) -> Result<(usize, usize), GitServiceError> {
+        let _timer =
+            metrics::GIT_OPERATION_DURATION.start_timer(&["git_service", "get_branch_status"]);
         let repo = Repository::open(repo_path)?;
         let branch = Self::find_branch(&repo, branch_name)?;
         let base_branch = Self::find_branch(&repo, base_branch_name)?;
@@ -957,6 +973,8 @@ impl GitService {
     }
 
     pub fn is_worktree_clean(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
+        let _timer =
+            metrics::GIT_OPERATION_DURATION.start_timer(&["git_service", "is_worktree_clean"]);
         let repo = self.open_repo(worktree_path)?;
         match self.check_worktree_clean(&repo) {
             Ok(()) => Ok(true),
@@ -1068,6 +1086,55 @@ impl GitService {
         Ok(commit.summary().unwrap_or("(no subject)").to_string())
     }
 
+    /// Text contents of `path` at the given commit. `None` if the file does not
+    /// exist there or is binary.
+    pub fn read_file_at_commit(
+        &self,
+        repo_path: &Path,
+        commit_sha: &str,
+        path: &str,
+    ) -> Result<Option<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let oid = git2::Oid::from_str(commit_sha)
+            .map_err(|_| GitServiceError::InvalidRepository("Invalid commit SHA".into()))?;
+        let tree = repo.find_commit(oid)?.tree()?;
+        let Ok(entry) = tree.get_path(Path::new(path)) else {
+            return Ok(None);
+        };
+        let Ok(blob) = repo.find_blob(entry.id()) else {
+            return Ok(None);
+        };
+        if blob.is_binary() {
+            return Ok(None);
+        }
+        Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+    }
+
+    /// Subjects of the commits reachable from `to_oid` but not `from_oid`,
+    /// oldest first
+    pub fn get_commit_subjects_between(
+        &self,
+        repo_path: &Path,
+        from_oid: &str,
+        to_oid: &str,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let from = git2::Oid::from_str(from_oid)
+            .map_err(|_| GitServiceError::InvalidRepository("Invalid from OID".into()))?;
+        let to = git2::Oid::from_str(to_oid)
+            .map_err(|_| GitServiceError::InvalidRepository("Invalid to OID".into()))?;
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(to)?;
+        revwalk.hide(from)?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        revwalk
+            .map(|oid| -> Result<String, GitServiceError> {
+                let commit = repo.find_commit(oid?)?;
+                Ok(commit.summary().unwrap_or("(no subject)").to_string())
+            })
+            .collect()
+    }
+
     /// Compare two OIDs and return (ahead, behind) counts: how many commits
     /// `from_oid` is ahead of and behind `to_oid`.
     pub fn ahead_behind_commits_by_oid(
@@ -1090,6 +1157,8 @@ impl GitService {
         &self,
         worktree_path: &Path,
     ) -> Result<(usize, usize), GitServiceError> {
+        let _timer = metrics::GIT_OPERATION_DURATION
+            .start_timer(&["git_service", "get_worktree_change_counts"]);
         let cli = GitCli::new();
         let st = cli
             .get_worktree_status(worktree_path)
@@ -1175,6 +1244,73 @@ impl GitService {
         Ok(())
     }
 
+    /// Add a worktree for an existing branch, checking out only the
+    /// sparse-checkout cone `sparse_paths`
+    pub fn add_sparse_worktree(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        branch: &str,
+        sparse_paths: &[String],
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.worktree_add_sparse(repo_path, worktree_path, branch, sparse_paths)
+            .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Add a worktree with a detached HEAD at `commit`, optionally sparse
+    pub fn add_detached_worktree(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        commit: &str,
+        sparse_paths: &[String],
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.worktree_add_detached(repo_path, worktree_path, commit, sparse_paths)
+            .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Cone directories of a sparse worktree; empty when the whole tree is checked out
+    pub fn get_sparse_checkout(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let git = GitCli::new();
+        git.sparse_checkout_list(worktree_path)
+            .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))
+    }
+
+    /// Check out more directories in a worktree. A worktree with the whole tree
+    /// checked out is left as is.
+    pub fn expand_sparse_checkout(
+        &self,
+        worktree_path: &Path,
+        paths: &[String],
+    ) -> Result<Vec<String>, GitServiceError> {
+        let git = GitCli::new();
+        if !git.is_sparse_checkout(worktree_path) {
+            return Ok(Vec::new());
+        }
+        git.sparse_checkout_add(worktree_path, paths)
+            .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
+        self.get_sparse_checkout(worktree_path)
+    }
+
+    /// Create a branch at the worktree's HEAD and check it out
+    pub fn switch_new_branch(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.switch_new_branch(worktree_path, branch_name)
+            .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
+        Ok(())
+    }
+
     /// Remove a worktree
     pub fn remove_worktree(
         &self,
@@ -1209,6 +1345,8 @@ impl GitService {
     }
 
     pub fn get_all_branches(&self, repo_path: &Path) -> Result<Vec<GitBranch>, git2::Error> {
+        let _timer =
+            metrics::GIT_OPERATION_DURATION.start_timer(&["git_service", "get_all_branches"]);
         let repo = Repository::open(repo_path)?;
         let current_branch = self.get_current_branch(repo_path).unwrap_or_default();
         let mut branches = Vec::new();
@@ -1326,6 +1464,7 @@ impl GitService {
         old_base_branch: &str,
         task_branch: &str,
     ) -> Result<String, GitServiceError> {
+        let _timer = metrics::GIT_OPERATION_DURATION.start_timer(&["git_service", "rebase_branch"]);
         let worktree_repo = Repository::open(worktree_path)?;
         let main_repo = self.open_repo(repo_path)?;
 
@@ -1578,6 +1717,68 @@ impl GitService {
         Ok(())
     }
 
+    /// Return the base/ours/theirs versions of a conflicted path (stages 1-3).
+    pub fn get_conflict_sides(
+        &self,
+        worktree_path: &Path,
+        path: &str,
+    ) -> Result<ConflictSides, GitServiceError> {
+        let git = GitCli::new();
+        let read = |stage: u8| {
+            git.show_conflict_stage(worktree_path, path, stage)
+                .map_err(|e| GitServiceError::InvalidRepository(format!("git show failed: {e}")))
+        };
+        Ok(ConflictSides {
+            base: read(1)?,
+            ours: read(2)?,
+            theirs: read(3)?,
+        })
+    }
+
+    /// Stage the resolved `conflicted` files along with changes to tracked
+    /// files, and continue whichever operation is in progress. Other untracked
+    /// files, such as build output, are not committed. Returns
+    /// `MergeConflicts` if continuing stops on a further conflict (e.g. the
+    /// next commit of a rebase).
+    pub fn continue_conflicts(
+        &self,
+        worktree_path: &Path,
+        conflicted: &[String],
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        let Some(op) = self.detect_conflict_op(worktree_path)? else {
+            return Ok(());
+        };
+        git.add_paths_and_tracked(worktree_path, conflicted)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
+        self.ensure_cli_commit_identity(worktree_path)?;
+        let result = match op {
+            ConflictOp::Rebase => git.continue_rebase(worktree_path),
+            ConflictOp::Merge => git.continue_merge(worktree_path),
+            ConflictOp::CherryPick => git.continue_cherry_pick(worktree_path),
+            ConflictOp::Revert => git.continue_revert(worktree_path),
+        };
+        match result {
+            Ok(()) => Ok(()),
+            Err(GitCliError::CommandFailed(stderr)) => {
+                let conflicts = git.get_conflicted_files(worktree_path).unwrap_or_default();
+                if conflicts.is_empty() {
+                    Err(GitServiceError::InvalidRepository(format!(
+                        "continuing {op:?} failed: {stderr}"
+                    )))
+                } else {
+                    Err(GitServiceError::MergeConflicts(format!(
+                        "Continuing stopped on new conflicts in: {}",
+                        conflicts.join(", ")
+                    )))
+                }
+            }
+            Err(e) => Err(GitServiceError::InvalidRepository(format!(
+                "continuing {op:?} failed: {e}"
+            ))),
+        }
+    }
+
     pub fn find_branch<'a>(
         repo: &'a Repository,
         branch_name: &str,
@@ -1595,11 +1796,11 @@ impl GitService {
         }
     }
 
-    /// Extract GitHub owner and repo name from git repo path
-    pub fn get_github_repo_info(
+    /// Locate the repository on its git host from the default remote's URL
+    pub fn get_git_host_repo_info(
         &self,
         repo_path: &Path,
-    ) -> Result<GitHubRepoInfo, GitServiceError> {
+    ) -> Result<GitHostRepoInfo, GitServiceError> {
         let repo = self.open_repo(repo_path)?;
         let remote_name = self.default_remote_name(&repo);
         let remote = repo.find_remote(&remote_name).map_err(|_| {
@@ -1609,7 +1810,7 @@ impl GitService {
         let url = remote
             .url()
             .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))?;
-        GitHubRepoInfo::from_remote_url(url).map_err(|e| {
+        GitHostRepoInfo::from_remote_url(url).map_err(|e| {
             GitServiceError::InvalidRepository(format!("Failed to parse remote URL: {e}"))
         })
     }
@@ -1657,6 +1858,8 @@ impl GitService {
         branch_name: &str,
         force: bool,
     ) -> Result<(), GitServiceError> {
+        let _timer =
+            metrics::GIT_OPERATION_DURATION.start_timer(&["git_service", "push_to_github"]);
         let repo = Repository::open(worktree_path)?;
         self.check_worktree_clean(&repo)?;
 
@@ -1747,6 +1950,8 @@ impl GitService {
         target_path: &Path,
         token: Option<&str>,
     ) -> Result<Repository, GitServiceError> {
+        let _timer =
+            metrics::GIT_OPERATION_DURATION.start_timer(&["git_service", "clone_repository"]);
         use git2::{Cred, FetchOptions, RemoteCallbacks};
 
         if let Some(parent) = target_path.parent() {
@@ -23,7 +23,7 @@ use std::{
 };
 
 use thiserror::Error;
-use utils::shell::resolve_executable_path_blocking; // TODO: make GitCli async
+use utils::{metrics, shell::resolve_executable_path_blocking}; // TODO: make GitCli async
 
 use crate::services::{filesystem_watcher::ALWAYS_SKIP_DIRS, git::Commit};
 
@@ -107,6 +107,106 @@ impl GitCli {
         Ok(())
     }
 
+    /// Run `git -C <repo> worktree add --detach <path> <commit>`. With a
+    /// sparse-checkout cone, only the cone is checked out.
+    pub fn worktree_add_detached(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        commit: &str,
+        sparse_paths: &[String],
+    ) -> Result<(), GitCliError> {
+        self.ensure_available()?;
+        let mut args: Vec<OsString> = vec!["worktree".into(), "add".into(), "--detach".into()];
+        if !sparse_paths.is_empty() {
+            args.push("--no-checkout".into());
+        }
+        args.push(worktree_path.as_os_str().into());
+        args.push(OsString::from(commit));
+        self.git(repo_path, args)?;
+        if !sparse_paths.is_empty() {
+            return self.sparse_checkout_set(worktree_path, sparse_paths);
+        }
+        let _ = self.git(worktree_path, ["sparse-checkout", "reapply"]);
+        Ok(())
+    }
+
+    /// Run `git -C <repo> worktree add --no-checkout <path> <branch>` and check
+    /// out only the sparse-checkout cone `sparse_paths`
+    pub fn worktree_add_sparse(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        branch: &str,
+        sparse_paths: &[String],
+    ) -> Result<(), GitCliError> {
+        self.ensure_available()?;
+        let args: Vec<OsString> = vec![
+            "worktree".into(),
+            "add".into(),
+            "--no-checkout".into(),
+            worktree_path.as_os_str().into(),
+            OsString::from(branch),
+        ];
+        self.git(repo_path, args)?;
+        self.sparse_checkout_set(worktree_path, sparse_paths)
+    }
+
+    /// Restrict a worktree to the cone directories `paths` and populate the
+    /// index and working tree from HEAD, skipping everything outside the cone
+    pub fn sparse_checkout_set(
+        &self,
+        worktree_path: &Path,
+        paths: &[String],
+    ) -> Result<(), GitCliError> {
+        let mut args: Vec<OsString> = vec!["sparse-checkout".into(), "set".into(), "--cone".into()];
+        args.extend(paths.iter().map(OsString::from));
+        self.git(worktree_path, args)?;
+        self.git(worktree_path, ["read-tree", "-mu", "HEAD"])?;
+        Ok(())
+    }
+
+    /// Add directories to the sparse-checkout cone of a worktree and check them out
+    pub fn sparse_checkout_add(
+        &self,
+        worktree_path: &Path,
+        paths: &[String],
+    ) -> Result<(), GitCliError> {
+        let mut args: Vec<OsString> = vec!["sparse-checkout".into(), "add".into()];
+        args.extend(paths.iter().map(OsString::from));
+        self.git(worktree_path, args)?;
+        Ok(())
+    }
+
+    /// Whether the worktree has sparse checkout enabled
+    pub fn is_sparse_checkout(&self, worktree_path: &Path) -> bool {
+        // `git config --get` exits non-zero when the key is unset
+        self.git(worktree_path, ["config", "--get", "core.sparseCheckout"])
+            .map(|value| value.trim() == "true")
+            .unwrap_or(false)
+    }
+
+    /// Cone directories of a sparse worktree; empty when the whole tree is checked out
+    pub fn sparse_checkout_list(&self, worktree_path: &Path) -> Result<Vec<String>, GitCliError> {
+        if !self.is_sparse_checkout(worktree_path) {
+            return Ok(Vec::new());
+        }
+        let out = self.git(worktree_path, ["sparse-checkout", "list"])?;
+        Ok(out
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Create `branch` at the worktree's HEAD and switch to it, leaving the working tree as is
+    pub fn switch_new_branch(&self, worktree_path: &Path, branch: &str) -> Result<(), GitCliError> {
+        self.ensure_available()?;
+        self.git(worktree_path, ["switch", "-c", branch])?;
+        Ok(())
+    }
+
     /// Run `git -C <repo> worktree remove <path>`
     pub fn worktree_remove(
         &self,
@@ -204,12 +304,16 @@ impl GitCli {
                 input.extend_from_slice(&p);
                 input.push(0);
             }
-            let args = vec![
+            let mut args = vec![
                 OsString::from("add"),
                 OsString::from("-A"),
                 OsString::from("--pathspec-from-file=-"),
                 OsString::from("--pathspec-file-nul"),
             ];
+            // Files changed outside the sparse-checkout cone are part of the diff too
+            if self.is_sparse_checkout(worktree_path) {
+                args.push(OsString::from("--sparse"));
+            }
             self.git_with_stdin(worktree_path, args, Some(&envs), &input)?;
         }
         // git diff --cached
@@ -289,10 +393,38 @@ impl GitCli {
 
     /// Stage all changes in the working tree (respects sparse-checkout semantics).
     pub fn add_all(&self, worktree_path: &Path) -> Result<(), GitCliError> {
-        self.git(
-            worktree_path,
-            Self::apply_default_excludes(vec!["add", "-A"]),
-        )?;
+        let mut args = vec!["add", "-A"];
+        // Also stage files the agent created outside the sparse-checkout cone
+        if self.is_sparse_checkout(worktree_path) {
+            args.push("--sparse");
+        }
+        self.git(worktree_path, Self::apply_default_excludes(args))?;
+        Ok(())
+    }
+
+    /// Stage `paths`, deletions included, and every change to tracked files.
+    /// Untracked files outside `paths` are left alone.
+    pub fn add_paths_and_tracked(
+        &self,
+        worktree_path: &Path,
+        paths: &[String],
+    ) -> Result<(), GitCliError> {
+        let sparse = self.is_sparse_checkout(worktree_path);
+        let mut args = vec!["add", "-u"];
+        if sparse {
+            args.push("--sparse");
+        }
+        self.git(worktree_path, Self::apply_default_excludes(args))?;
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut args = vec!["add", "-A"];
+        if sparse {
+            args.push("--sparse");
+        }
+        args.push("--");
+        args.extend(paths.iter().map(String::as_str));
+        self.git(worktree_path, args)?;
         Ok(())
     }
 
@@ -640,6 +772,57 @@ impl GitCli {
         }
         Ok(files)
     }
+
+    /// Read one side of a conflicted path from the index.
+    /// Stage 1 is the common ancestor, 2 is "ours" and 3 is "theirs".
+    /// Returns None when that side does not exist (e.g. added/deleted on one side).
+    pub fn show_conflict_stage(
+        &self,
+        worktree_path: &Path,
+        path: &str,
+        stage: u8,
+    ) -> Result<Option<String>, GitCliError> {
+        let spec = format!(":{stage}:{path}");
+        match self.git(worktree_path, ["show", spec.as_str()]) {
+            Ok(out) => Ok(Some(out)),
+            Err(GitCliError::CommandFailed(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Continue an in-progress rebase after conflicts were resolved and staged.
+    pub fn continue_rebase(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["rebase", "--continue"],
+            &Self::non_interactive_editor_env(),
+        )?;
+        Ok(())
+    }
+
+    /// Conclude an in-progress merge using the prepared merge message.
+    pub fn continue_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["commit", "--no-edit"])?;
+        Ok(())
+    }
+
+    pub fn continue_cherry_pick(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["cherry-pick", "--continue"],
+            &Self::non_interactive_editor_env(),
+        )?;
+        Ok(())
+    }
+
+    pub fn continue_revert(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["revert", "--continue"],
+            &Self::non_interactive_editor_env(),
+        )?;
+        Ok(())
+    }
 }
 
 // Private methods
@@ -711,9 +894,25 @@ impl GitCli {
             }
         }
 
+        // The subcommand is the first argument that is neither a global flag
+        // nor the value of `-c`
+        let mut operation = None;
+        let mut after_config_flag = false;
         for a in args {
+            let arg = a.as_ref().to_string_lossy();
+            if operation.is_none() {
+                if after_config_flag {
+                    after_config_flag = false;
+                } else if arg == "-c" {
+                    after_config_flag = true;
+                } else if !arg.starts_with('-') {
+                    operation = Some(arg.into_owned());
+                }
+            }
             cmd.arg(a);
         }
+        let _timer = metrics::GIT_OPERATION_DURATION
+            .start_timer(&["git_cli", operation.as_deref().unwrap_or("unknown")]);
 
         if stdin.is_some() {
             cmd.stdin(Stdio::piped());
@@ -804,6 +1003,11 @@ impl GitCli {
         Ok(String::from_utf8_lossy(&out).to_string())
     }
 
+    /// Accept the default commit message for `--continue` without opening an editor.
+    fn non_interactive_editor_env() -> Vec<(OsString, OsString)> {
+        vec![(OsString::from("GIT_EDITOR"), OsString::from("true"))]
+    }
+
     fn apply_default_excludes<I, S>(args: I) -> Vec<OsString>
     where
         I: IntoIterator<Item = S>,
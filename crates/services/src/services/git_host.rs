@@ -0,0 +1,439 @@
+//! Git hosting providers (GitHub, GitLab, Gitea) behind a common interface.
+//!
+//! The provider for a repository is picked from its remote URL. Hosts that
+//! can't be recognised by name (e.g. a self-hosted GitLab on `git.example.com`)
+//! are mapped to a provider through the `git_hosts` config entries or the
+//! project's own git host, which can also override the API URL and name the
+//! secret holding an access token.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::{
+    merge::{CiCheck, PullRequestInfo},
+    project::ProjectGitHost,
+    secret::GIT_HOST_TOKEN_PREFIX,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use url::Url;
+
+use crate::services::{github::GitHubServiceError, secrets::SecretsService};
+
+mod gitea;
+mod github;
+mod gitlab;
+mod http;
+
+pub use db::models::project::GitHostKind;
+pub use gitea::GiteaProvider;
+pub use github::GitHubProvider;
+pub use gitlab::GitLabProvider;
+
+/// Maps a git host to a provider
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct GitHostConfig {
+    /// Hostname as it appears in remote URLs, e.g. `gitlab.example.com`
+    pub host: String,
+    pub provider: GitHostKind,
+    /// API base URL, derived from the host when unset
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// Name of the secret holding the access token, starting with `GIT_HOST_`
+    /// so it is never injected into agents. For GitHub, a token switches from
+    /// the `gh` CLI to the REST API; GitLab and Gitea fall back to
+    /// `GITLAB_TOKEN` / `GITEA_TOKEN`.
+    #[serde(default)]
+    pub token_secret: Option<String>,
+    /// Plaintext token of older configs, moved to the secrets store on load
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(skip)]
+    pub token: Option<String>,
+}
+
+impl GitHostConfig {
+    /// Name of the global secret a plaintext token is moved to
+    pub fn default_token_secret(&self) -> String {
+        let host: String = self
+            .host
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{GIT_HOST_TOKEN_PREFIX}{host}_TOKEN")
+    }
+}
+
+/// Token secrets need the `GIT_HOST_` prefix, which keeps them out of the
+/// environments of agents and scripts
+pub fn validate_token_secret(name: &str) -> Result<(), GitHostError> {
+    if !name.starts_with(GIT_HOST_TOKEN_PREFIX) {
+        return Err(GitHostError::InvalidTokenSecret(name.to_string()));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum GitHostError {
+    #[error(transparent)]
+    GitHub(#[from] GitHubServiceError),
+    #[error("Invalid remote URL: {0}")]
+    InvalidRemoteUrl(String),
+    #[error(
+        "Unsupported git host '{0}'. Add it to `git_hosts` in the config or set the project's git host to choose a provider."
+    )]
+    UnsupportedHost(String),
+    #[error("Authentication with {host} failed: {message}")]
+    AuthFailed { host: String, message: String },
+    #[error("Insufficient permissions: {0}")]
+    InsufficientPermissions(String),
+    #[error("Repository or pull request not found: {0}")]
+    NotFound(String),
+    #[error("Git host request failed: {0}")]
+    Transport(String),
+    #[error("Git host returned {status}: {body}")]
+    Http { status: u16, body: String },
+    #[error("The secret '{0}' holding the git host token does not exist")]
+    MissingTokenSecret(String),
+    #[error(
+        "Git host token secret '{0}' must be named GIT_HOST_..., which keeps it out of agent environments"
+    )]
+    InvalidTokenSecret(String),
+    #[error("Failed to read the git host token: {0}")]
+    TokenUnavailable(String),
+    #[error("Unexpected response from git host: {0}")]
+    UnexpectedResponse(String),
+}
+
+impl GitHostError {
+    pub fn should_retry(&self) -> bool {
+        match self {
+            Self::GitHub(err) => err.should_retry(),
+            Self::Transport(_) => true,
+            Self::Http { status, .. } => (500..=599).contains(status),
+            _ => false,
+        }
+    }
+}
+
+/// Repository location on a git host, parsed from a remote or pull request URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHostRepoInfo {
+    pub host: String,
+    /// Owner, or the full group path for GitLab subgroups
+    pub owner: String,
+    pub repo_name: String,
+    /// `scheme://host[:port]` of the web UI
+    pub web_base: String,
+}
+
+impl GitHostRepoInfo {
+    /// Supports HTTPS, SSH and scp-style remotes as well as pull/merge request
+    /// URLs of all providers. See tests for examples.
+    pub fn from_remote_url(remote_url: &str) -> Result<Self, GitHostError> {
+        let invalid = || GitHostError::InvalidRemoteUrl(remote_url.to_string());
+        let trimmed = remote_url.trim();
+
+        let (host, web_base, path) = if trimmed.contains("://") {
+            let url = Url::parse(trimmed).map_err(|_| invalid())?;
+            let host = url.host_str().ok_or_else(invalid)?.to_string();
+            let web_base = match (url.scheme(), url.port()) {
+                ("http" | "https", Some(port)) => format!("{}://{host}:{port}", url.scheme()),
+                ("http" | "https", None) => format!("{}://{host}", url.scheme()),
+                // ssh/git ports say nothing about the web UI
+                _ => format!("https://{host}"),
+            };
+            (host, web_base, url.path().to_string())
+        } else {
+            // scp-like syntax: [user@]host:path
+            let (host_part, path) = trimmed.split_once(':').ok_or_else(invalid)?;
+            let host = host_part
+                .rsplit('@')
+                .next()
+                .unwrap_or(host_part)
+                .to_string();
+            let web_base = format!("https://{host}");
+            (host, web_base, path.to_string())
+        };
+
+        let mut path = path.trim_matches('/');
+        // Strip pull/merge request suffixes (GitLab, GitHub, Gitea)
+        for marker in ["/-/", "/pull/", "/pulls/"] {
+            if let Some(idx) = path.find(marker) {
+                path = &path[..idx];
+            }
+        }
+        let path = path.trim_end_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+
+        let (owner, repo_name) = path.rsplit_once('/').ok_or_else(invalid)?;
+        if host.is_empty() || owner.is_empty() || repo_name.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            host,
+            owner: owner.to_string(),
+            repo_name: repo_name.to_string(),
+            web_base,
+        })
+    }
+
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.repo_name)
+    }
+
+    pub fn web_url(&self) -> String {
+        format!("{}/{}", self.web_base, self.full_name())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CreatePrRequest {
+    pub title: String,
+    pub body: Option<String>,
+    pub head_branch: String,
+    pub base_branch: String,
+    pub draft: Option<bool>,
+}
+
+/// Unified PR comment that can be either a general comment or review comment
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "comment_type", rename_all = "snake_case")]
+#[ts(tag = "comment_type", rename_all = "snake_case")]
+pub enum UnifiedPrComment {
+    /// General PR comment (conversation)
+    General {
+        id: String,
+        author: String,
+        author_association: String,
+        body: String,
+        created_at: DateTime<Utc>,
+        url: String,
+    },
+    /// Inline review comment (on code)
+    Review {
+        id: i64,
+        author: String,
+        author_association: String,
+        body: String,
+        created_at: DateTime<Utc>,
+        url: String,
+        path: String,
+        line: Option<i64>,
+        diff_hunk: String,
+    },
+}
+
+impl UnifiedPrComment {
+    pub(crate) fn created_at(&self) -> DateTime<Utc> {
+        match self {
+            UnifiedPrComment::General { created_at, .. } => *created_at,
+            UnifiedPrComment::Review { created_at, .. } => *created_at,
+        }
+    }
+}
+
+/// CI results reported for the head commit of a pull request
+#[derive(Debug, Clone)]
+pub struct PrChecks {
+    pub head_sha: String,
+    pub checks: Vec<CiCheck>,
+}
+
+/// Operations on pull requests (merge requests on GitLab) of a hosted repository
+#[async_trait]
+pub trait GitHostProvider: Send + Sync {
+    fn kind(&self) -> GitHostKind;
+
+    async fn create_pr(
+        &self,
+        repo: &GitHostRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError>;
+
+    async fn get_pr_status(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+    ) -> Result<PullRequestInfo, GitHostError>;
+
+    /// List all pull requests opened from a branch (including closed/merged),
+    /// used to attach an existing PR
+    async fn list_prs_for_branch(
+        &self,
+        repo: &GitHostRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError>;
+
+    /// General and inline review comments, oldest first
+    async fn get_pr_comments(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError>;
+
+    /// Post a general (conversation) comment on a pull request
+    async fn post_pr_comment(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError>;
+
+    /// Check runs, commit statuses or pipeline jobs of the PR's head commit
+    async fn get_pr_checks(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+    ) -> Result<PrChecks, GitHostError>;
+
+    /// Log of a CI job, or `None` if the host doesn't expose logs for it
+    async fn get_check_log(
+        &self,
+        repo: &GitHostRepoInfo,
+        check: &CiCheck,
+    ) -> Result<Option<String>, GitHostError>;
+}
+
+/// Move plaintext tokens of `git_hosts` entries into global secrets and
+/// refer to them by name. Returns whether any entry changed; a token that
+/// can't be stored stays where it is.
+pub async fn move_tokens_to_secrets(
+    hosts: &mut [GitHostConfig],
+    secrets: &SecretsService,
+    pool: &SqlitePool,
+) -> bool {
+    let mut changed = false;
+    for entry in hosts.iter_mut() {
+        let Some(token) = entry.token.take() else {
+            continue;
+        };
+        if token.is_empty() {
+            changed = true;
+            continue;
+        }
+        let name = entry
+            .token_secret
+            .clone()
+            .filter(|name| validate_token_secret(name).is_ok())
+            .unwrap_or_else(|| entry.default_token_secret());
+        match secrets.upsert_global(pool, &name, &token).await {
+            Ok(_) => {
+                tracing::info!("Moved the token for {} to the secret {}", entry.host, name);
+                entry.token_secret = Some(name);
+                changed = true;
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to move the token for {} to a secret: {}",
+                    entry.host,
+                    e
+                );
+                entry.token = Some(token);
+            }
+        }
+    }
+    changed
+}
+
+/// Where a repository's host is configured
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HostSettings {
+    pub kind: Option<GitHostKind>,
+    pub api_url: Option<String>,
+    pub token_secret: Option<String>,
+}
+
+/// The project's git host, else the `git_hosts` entry for the repository's
+/// host. The provider is detected from the hostname when neither names one.
+pub fn host_settings(
+    repo: &GitHostRepoInfo,
+    hosts: &[GitHostConfig],
+    project: Option<&ProjectGitHost>,
+) -> HostSettings {
+    let settings = match project {
+        Some(project) => HostSettings {
+            kind: Some(project.provider),
+            api_url: project.api_url.clone(),
+            token_secret: project.token_secret.clone(),
+        },
+        None => hosts
+            .iter()
+            .find(|entry| entry.host.eq_ignore_ascii_case(&repo.host))
+            .map(|entry| HostSettings {
+                kind: Some(entry.provider),
+                api_url: entry.api_url.clone(),
+                token_secret: entry.token_secret.clone(),
+            })
+            .unwrap_or_default(),
+    };
+    HostSettings {
+        kind: settings.kind.or_else(|| GitHostKind::detect(&repo.host)),
+        api_url: settings.api_url.filter(|url| !url.is_empty()),
+        token_secret: settings.token_secret.filter(|name| !name.is_empty()),
+    }
+}
+
+/// Provider kind of a repository's host
+pub fn resolve_kind(
+    repo: &GitHostRepoInfo,
+    hosts: &[GitHostConfig],
+    project: Option<&ProjectGitHost>,
+) -> Option<GitHostKind> {
+    host_settings(repo, hosts, project).kind
+}
+
+/// Build the provider for a repository's host. `token` is the value of the
+/// settings' token secret.
+pub fn resolve_provider(
+    repo: &GitHostRepoInfo,
+    settings: &HostSettings,
+    token: Option<String>,
+) -> Result<Arc<dyn GitHostProvider>, GitHostError> {
+    let kind = settings
+        .kind
+        .ok_or_else(|| GitHostError::UnsupportedHost(repo.host.clone()))?;
+
+    let api_url = settings.api_url.clone();
+    let token = token.filter(|token| !token.is_empty()).or_else(|| {
+        kind.token_env_var()
+            .and_then(|var| std::env::var(var).ok())
+            .filter(|token| !token.is_empty())
+    });
+
+    Ok(match kind {
+        GitHostKind::GitHub => match token {
+            Some(token) => {
+                let api_url = api_url.unwrap_or_else(|| default_github_api_url(repo));
+                Arc::new(GitHubProvider::with_token(&api_url, token)?)
+            }
+            None => Arc::new(GitHubProvider::cli()?),
+        },
+        GitHostKind::GitLab => {
+            let api_url = api_url.unwrap_or_else(|| format!("{}/api/v4", repo.web_base));
+            Arc::new(GitLabProvider::new(&api_url, token)?)
+        }
+        GitHostKind::Gitea => {
+            let api_url = api_url.unwrap_or_else(|| format!("{}/api/v1", repo.web_base));
+            Arc::new(GiteaProvider::new(&api_url, token)?)
+        }
+    })
+}
+
+fn default_github_api_url(repo: &GitHostRepoInfo) -> String {
+    if repo.host.eq_ignore_ascii_case("github.com") {
+        "https://api.github.com".to_string()
+    } else {
+        // GitHub Enterprise Server
+        format!("{}/api/v3", repo.web_base)
+    }
+}
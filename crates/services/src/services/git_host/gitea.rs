@@ -0,0 +1,342 @@
+//! Gitea (and Forgejo/Codeberg) provider using the v1 REST API.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::merge::{CiCheck, CiStatus, MergeStatus, PullRequestInfo};
+use serde::{Deserialize, Serialize, de::IgnoredAny};
+
+use super::{
+    CreatePrRequest, GitHostError, GitHostKind, GitHostProvider, GitHostRepoInfo, PrChecks,
+    UnifiedPrComment, http::HostApi,
+};
+
+const PAGE_SIZE: usize = 50;
+
+/// Gitea has no author association; use GitHub's value for "none"
+const NO_ASSOCIATION: &str = "NONE";
+
+#[derive(Debug, Clone)]
+pub struct GiteaProvider {
+    api: HostApi,
+}
+
+impl GiteaProvider {
+    /// `api_url` is the v1 API root, e.g. `https://gitea.example.com/api/v1`
+    pub fn new(api_url: &str, token: Option<String>) -> Result<Self, GitHostError> {
+        Ok(Self {
+            api: HostApi::new(
+                api_url,
+                token.map(|token| ("Authorization", format!("token {token}"))),
+            )?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CommentBody<'a> {
+    body: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct CreatePullBody<'a> {
+    title: String,
+    body: &'a str,
+    head: &'a str,
+    base: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullBranch {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    #[serde(default)]
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pull {
+    number: i64,
+    html_url: String,
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    head: PullBranch,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitStatus {
+    context: String,
+    status: String,
+    target_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CombinedStatus {
+    #[serde(default)]
+    statuses: Vec<CommitStatus>,
+}
+
+impl From<CommitStatus> for CiCheck {
+    fn from(status: CommitStatus) -> Self {
+        CiCheck {
+            name: status.context,
+            status: match status.status.as_str() {
+                "pending" => CiStatus::Pending,
+                "error" | "failure" => CiStatus::Failure,
+                // success, warning
+                _ => CiStatus::Success,
+            },
+            url: status.target_url.filter(|url| !url.is_empty()),
+            job_id: None,
+        }
+    }
+}
+
+impl From<Pull> for PullRequestInfo {
+    fn from(pull: Pull) -> Self {
+        let status = if pull.merged {
+            MergeStatus::Merged
+        } else {
+            match pull.state.as_str() {
+                "open" => MergeStatus::Open,
+                "closed" => MergeStatus::Closed,
+                _ => MergeStatus::Unknown,
+            }
+        };
+        PullRequestInfo {
+            number: pull.number,
+            url: pull.html_url,
+            status,
+            merged_at: pull.merged_at,
+            merge_commit_sha: pull.merge_commit_sha.filter(|_| pull.merged),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueComment {
+    id: i64,
+    user: User,
+    body: String,
+    created_at: DateTime<Utc>,
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Review {
+    id: i64,
+    #[serde(default)]
+    comments_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewComment {
+    id: i64,
+    user: User,
+    body: String,
+    created_at: DateTime<Utc>,
+    html_url: String,
+    path: String,
+    #[serde(default)]
+    position: i64,
+    #[serde(default)]
+    original_position: i64,
+    #[serde(default)]
+    diff_hunk: String,
+}
+
+#[async_trait]
+impl GitHostProvider for GiteaProvider {
+    fn kind(&self) -> GitHostKind {
+        GitHostKind::Gitea
+    }
+
+    async fn create_pr(
+        &self,
+        repo: &GitHostRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        // Gitea treats a WIP prefix as draft
+        let title = if request.draft.unwrap_or(false) {
+            format!("WIP: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let body = CreatePullBody {
+            title,
+            body: request.body.as_deref().unwrap_or(""),
+            head: &request.head_branch,
+            base: &request.base_branch,
+        };
+        let pull: Pull = self
+            .api
+            .post_once(&format!("repos/{}/pulls", repo.full_name()), &body)
+            .await?;
+        tracing::info!(
+            "Created Gitea PR #{} for branch {} in {}",
+            pull.number,
+            request.head_branch,
+            repo.full_name()
+        );
+        Ok(pull.into())
+    }
+
+    async fn get_pr_status(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let pull: Pull = self
+            .api
+            .get(
+                &format!("repos/{}/pulls/{pr_number}", repo.full_name()),
+                &[],
+            )
+            .await?;
+        Ok(pull.into())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        repo: &GitHostRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        // The list endpoint can't filter by head branch, so filter here
+        let pulls: Vec<Pull> = self
+            .api
+            .get_all(
+                &format!("repos/{}/pulls", repo.full_name()),
+                &[("state", "all".to_string())],
+                "limit",
+                PAGE_SIZE,
+            )
+            .await?;
+        Ok(pulls
+            .into_iter()
+            .filter(|pull| pull.head.ref_name == branch)
+            .map(Into::into)
+            .collect())
+    }
+
+    async fn get_pr_comments(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let full_name = repo.full_name();
+        let (general, reviews) = tokio::join!(
+            self.api.get_all::<IssueComment>(
+                &format!("repos/{full_name}/issues/{pr_number}/comments"),
+                &[],
+                "limit",
+                PAGE_SIZE,
+            ),
+            self.api.get_all::<Review>(
+                &format!("repos/{full_name}/pulls/{pr_number}/reviews"),
+                &[],
+                "limit",
+                PAGE_SIZE,
+            )
+        );
+
+        let mut unified: Vec<UnifiedPrComment> = general?
+            .into_iter()
+            .map(|c| UnifiedPrComment::General {
+                id: c.id.to_string(),
+                author: c.user.login,
+                author_association: NO_ASSOCIATION.to_string(),
+                body: c.body,
+                created_at: c.created_at,
+                url: c.html_url,
+            })
+            .collect();
+
+        for review in reviews?.into_iter().filter(|r| r.comments_count > 0) {
+            let comments: Vec<ReviewComment> = self
+                .api
+                .get(
+                    &format!(
+                        "repos/{full_name}/pulls/{pr_number}/reviews/{}/comments",
+                        review.id
+                    ),
+                    &[],
+                )
+                .await?;
+            unified.extend(comments.into_iter().map(|c| {
+                // 0 means the comment isn't anchored on that side of the diff
+                let line = [c.position, c.original_position]
+                    .into_iter()
+                    .find(|line| *line > 0);
+                UnifiedPrComment::Review {
+                    id: c.id,
+                    author: c.user.login,
+                    author_association: NO_ASSOCIATION.to_string(),
+                    body: c.body,
+                    created_at: c.created_at,
+                    url: c.html_url,
+                    path: c.path,
+                    line,
+                    diff_hunk: c.diff_hunk,
+                }
+            }));
+        }
+
+        unified.sort_by_key(|c| c.created_at());
+        Ok(unified)
+    }
+
+    async fn post_pr_comment(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        let _: IgnoredAny = self
+            .api
+            .post_once(
+                &format!("repos/{}/issues/{pr_number}/comments", repo.full_name()),
+                &CommentBody { body },
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_pr_checks(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+    ) -> Result<PrChecks, GitHostError> {
+        let full_name = repo.full_name();
+        let pull: Pull = self
+            .api
+            .get(&format!("repos/{full_name}/pulls/{pr_number}"), &[])
+            .await?;
+        let combined: CombinedStatus = self
+            .api
+            .get(
+                &format!("repos/{full_name}/commits/{}/status", pull.head.sha),
+                &[],
+            )
+            .await?;
+        Ok(PrChecks {
+            head_sha: pull.head.sha,
+            checks: combined.statuses.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    async fn get_check_log(
+        &self,
+        _repo: &GitHostRepoInfo,
+        _check: &CiCheck,
+    ) -> Result<Option<String>, GitHostError> {
+        // Commit statuses only link to the CI system
+        Ok(None)
+    }
+}
@@ -0,0 +1,422 @@
+//! GitHub provider: the `gh` CLI by default, or the REST API when a token is
+//! configured (required for GitHub Enterprise hosts `gh` isn't logged into).
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::merge::{CiCheck, CiStatus, MergeStatus, PullRequestInfo};
+use serde::{
+    Deserialize, Serialize,
+    de::{DeserializeOwned, IgnoredAny},
+};
+
+use super::{
+    CreatePrRequest, GitHostError, GitHostKind, GitHostProvider, GitHostRepoInfo, PrChecks,
+    UnifiedPrComment, http::HostApi,
+};
+use crate::services::github::{GitHubRepoInfo, GitHubService, ReviewCommentUser};
+
+const PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Clone)]
+enum Backend {
+    Cli(GitHubService),
+    Api(HostApi),
+}
+
+#[derive(Debug, Clone)]
+pub struct GitHubProvider {
+    backend: Backend,
+}
+
+impl GitHubProvider {
+    /// Use the `gh` CLI and its stored credentials
+    pub fn cli() -> Result<Self, GitHostError> {
+        Ok(Self {
+            backend: Backend::Cli(GitHubService::new()?),
+        })
+    }
+
+    /// Use the REST API at `api_url` (e.g. `https://api.github.com`)
+    pub fn with_token(api_url: &str, token: String) -> Result<Self, GitHostError> {
+        Ok(Self {
+            backend: Backend::Api(HostApi::new(
+                api_url,
+                Some(("Authorization", format!("Bearer {token}"))),
+            )?),
+        })
+    }
+}
+
+impl GitHubProvider {
+    /// GET a REST API path through whichever backend is configured
+    async fn get_json<T>(&self, path: &str) -> Result<T, GitHostError>
+    where
+        T: DeserializeOwned,
+    {
+        match &self.backend {
+            Backend::Cli(service) => {
+                let raw = service.api_get(path).await?;
+                serde_json::from_str(&raw)
+                    .map_err(|e| GitHostError::UnexpectedResponse(format!("{path}: {e}")))
+            }
+            Backend::Api(api) => api.get(path, &[]).await,
+        }
+    }
+}
+
+fn cli_repo_info(repo: &GitHostRepoInfo) -> GitHubRepoInfo {
+    GitHubRepoInfo {
+        owner: repo.owner.clone(),
+        repo_name: repo.repo_name.clone(),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CreatePullBody<'a> {
+    title: &'a str,
+    body: &'a str,
+    head: &'a str,
+    base: &'a str,
+    draft: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct CommentBody<'a> {
+    body: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pull {
+    number: i64,
+    html_url: String,
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+}
+
+impl From<Pull> for PullRequestInfo {
+    fn from(pull: Pull) -> Self {
+        let status = if pull.merged_at.is_some() {
+            MergeStatus::Merged
+        } else {
+            match pull.state.as_str() {
+                "open" => MergeStatus::Open,
+                "closed" => MergeStatus::Closed,
+                _ => MergeStatus::Unknown,
+            }
+        };
+        // GitHub reports a test-merge commit for open PRs; only keep real ones
+        let merge_commit_sha = pull.merge_commit_sha.filter(|_| pull.merged_at.is_some());
+        PullRequestInfo {
+            number: pull.number,
+            url: pull.html_url,
+            status,
+            merged_at: pull.merged_at,
+            merge_commit_sha,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PullHeadRef {
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullHead {
+    head: PullHeadRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRunApp {
+    slug: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRun {
+    id: i64,
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+    html_url: Option<String>,
+    app: Option<CheckRunApp>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRunList {
+    check_runs: Vec<CheckRun>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitStatus {
+    context: String,
+    state: String,
+    target_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CombinedStatus {
+    statuses: Vec<CommitStatus>,
+}
+
+impl From<CheckRun> for CiCheck {
+    fn from(run: CheckRun) -> Self {
+        let status = match (run.status.as_str(), run.conclusion.as_deref()) {
+            ("completed", Some("failure" | "cancelled" | "timed_out" | "action_required")) => {
+                CiStatus::Failure
+            }
+            // success, neutral, skipped, stale
+            ("completed", _) => CiStatus::Success,
+            _ => CiStatus::Pending,
+        };
+        // Only GitHub Actions check runs are jobs with a log behind them
+        let is_actions_job = run.app.is_some_and(|app| app.slug == "github-actions");
+        CiCheck {
+            name: run.name,
+            status,
+            url: run.html_url,
+            job_id: is_actions_job.then(|| run.id.to_string()),
+        }
+    }
+}
+
+impl From<CommitStatus> for CiCheck {
+    fn from(status: CommitStatus) -> Self {
+        CiCheck {
+            name: status.context,
+            status: match status.state.as_str() {
+                "pending" => CiStatus::Pending,
+                "success" => CiStatus::Success,
+                // failure, error
+                _ => CiStatus::Failure,
+            },
+            url: status.target_url,
+            job_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueComment {
+    id: i64,
+    user: ReviewCommentUser,
+    author_association: String,
+    body: String,
+    created_at: DateTime<Utc>,
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewComment {
+    id: i64,
+    user: ReviewCommentUser,
+    author_association: String,
+    body: String,
+    created_at: DateTime<Utc>,
+    html_url: String,
+    path: String,
+    line: Option<i64>,
+    diff_hunk: String,
+}
+
+#[async_trait]
+impl GitHostProvider for GitHubProvider {
+    fn kind(&self) -> GitHostKind {
+        GitHostKind::GitHub
+    }
+
+    async fn create_pr(
+        &self,
+        repo: &GitHostRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        match &self.backend {
+            Backend::Cli(service) => Ok(service.create_pr(&cli_repo_info(repo), request).await?),
+            Backend::Api(api) => {
+                let body = CreatePullBody {
+                    title: &request.title,
+                    body: request.body.as_deref().unwrap_or(""),
+                    head: &request.head_branch,
+                    base: &request.base_branch,
+                    draft: request.draft.unwrap_or(false),
+                };
+                let pull: Pull = api
+                    .post_once(&format!("repos/{}/pulls", repo.full_name()), &body)
+                    .await?;
+                tracing::info!(
+                    "Created GitHub PR #{} for branch {} in {}",
+                    pull.number,
+                    request.head_branch,
+                    repo.full_name()
+                );
+                Ok(pull.into())
+            }
+        }
+    }
+
+    async fn get_pr_status(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        match &self.backend {
+            Backend::Cli(service) => Ok(service
+                .update_pr_status(&cli_repo_info(repo), pr_number)
+                .await?),
+            Backend::Api(api) => {
+                let pull: Pull = api
+                    .get(
+                        &format!("repos/{}/pulls/{pr_number}", repo.full_name()),
+                        &[],
+                    )
+                    .await?;
+                Ok(pull.into())
+            }
+        }
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        repo: &GitHostRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        match &self.backend {
+            Backend::Cli(service) => Ok(service
+                .list_all_prs_for_branch(&cli_repo_info(repo), branch)
+                .await?),
+            Backend::Api(api) => {
+                let pulls: Vec<Pull> = api
+                    .get_all(
+                        &format!("repos/{}/pulls", repo.full_name()),
+                        &[
+                            ("state", "all".to_string()),
+                            ("head", format!("{}:{branch}", repo.owner)),
+                        ],
+                        "per_page",
+                        PAGE_SIZE,
+                    )
+                    .await?;
+                Ok(pulls.into_iter().map(Into::into).collect())
+            }
+        }
+    }
+
+    async fn get_pr_comments(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let api = match &self.backend {
+            Backend::Cli(service) => {
+                return Ok(service
+                    .get_pr_comments(&cli_repo_info(repo), pr_number)
+                    .await?);
+            }
+            Backend::Api(api) => api,
+        };
+
+        let full_name = repo.full_name();
+        let (general, review) = tokio::join!(
+            api.get_all::<IssueComment>(
+                &format!("repos/{full_name}/issues/{pr_number}/comments"),
+                &[],
+                "per_page",
+                PAGE_SIZE,
+            ),
+            api.get_all::<ReviewComment>(
+                &format!("repos/{full_name}/pulls/{pr_number}/comments"),
+                &[],
+                "per_page",
+                PAGE_SIZE,
+            )
+        );
+
+        let mut unified: Vec<UnifiedPrComment> = general?
+            .into_iter()
+            .map(|c| UnifiedPrComment::General {
+                id: c.id.to_string(),
+                author: c.user.login,
+                author_association: c.author_association,
+                body: c.body,
+                created_at: c.created_at,
+                url: c.html_url,
+            })
+            .collect();
+        unified.extend(review?.into_iter().map(|c| UnifiedPrComment::Review {
+            id: c.id,
+            author: c.user.login,
+            author_association: c.author_association,
+            body: c.body,
+            created_at: c.created_at,
+            url: c.html_url,
+            path: c.path,
+            line: c.line,
+            diff_hunk: c.diff_hunk,
+        }));
+        unified.sort_by_key(|c| c.created_at());
+        Ok(unified)
+    }
+
+    async fn post_pr_comment(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        match &self.backend {
+            Backend::Cli(service) => Ok(service
+                .comment_on_pr(&cli_repo_info(repo), pr_number, body)
+                .await?),
+            Backend::Api(api) => {
+                let _: IgnoredAny = api
+                    .post_once(
+                        &format!("repos/{}/issues/{pr_number}/comments", repo.full_name()),
+                        &CommentBody { body },
+                    )
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    async fn get_pr_checks(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+    ) -> Result<PrChecks, GitHostError> {
+        let full_name = repo.full_name();
+        let pull: PullHead = self
+            .get_json(&format!("repos/{full_name}/pulls/{pr_number}"))
+            .await?;
+        let sha = pull.head.sha;
+        let (runs, combined) = tokio::join!(
+            self.get_json::<CheckRunList>(&format!(
+                "repos/{full_name}/commits/{sha}/check-runs?per_page={PAGE_SIZE}"
+            )),
+            self.get_json::<CombinedStatus>(&format!("repos/{full_name}/commits/{sha}/status"))
+        );
+
+        let mut checks: Vec<CiCheck> = runs?.check_runs.into_iter().map(Into::into).collect();
+        checks.extend(combined?.statuses.into_iter().map(CiCheck::from));
+        Ok(PrChecks {
+            head_sha: sha,
+            checks,
+        })
+    }
+
+    async fn get_check_log(
+        &self,
+        repo: &GitHostRepoInfo,
+        check: &CiCheck,
+    ) -> Result<Option<String>, GitHostError> {
+        let Some(job_id) = &check.job_id else {
+            return Ok(None);
+        };
+        let path = format!("repos/{}/actions/jobs/{job_id}/logs", repo.full_name());
+        let log = match &self.backend {
+            Backend::Cli(service) => service.api_get(&path).await?,
+            Backend::Api(api) => api.get_text(&path).await?,
+        };
+        Ok(Some(log))
+    }
+}
@@ -0,0 +1,339 @@
+//! GitLab provider (gitlab.com and self-hosted) using the v4 REST API.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::merge::{CiCheck, CiStatus, MergeStatus, PullRequestInfo};
+use serde::{Deserialize, Serialize, de::IgnoredAny};
+
+use super::{
+    CreatePrRequest, GitHostError, GitHostKind, GitHostProvider, GitHostRepoInfo, PrChecks,
+    UnifiedPrComment,
+    http::{HostApi, encode_segment},
+};
+
+const PAGE_SIZE: usize = 100;
+
+/// GitLab has no author association; use GitHub's value for "none"
+const NO_ASSOCIATION: &str = "NONE";
+
+#[derive(Debug, Clone)]
+pub struct GitLabProvider {
+    api: HostApi,
+}
+
+impl GitLabProvider {
+    /// `api_url` is the v4 API root, e.g. `https://gitlab.example.com/api/v4`
+    pub fn new(api_url: &str, token: Option<String>) -> Result<Self, GitHostError> {
+        Ok(Self {
+            api: HostApi::new(api_url, token.map(|token| ("PRIVATE-TOKEN", token)))?,
+        })
+    }
+
+    fn project_path(repo: &GitHostRepoInfo) -> String {
+        format!("projects/{}", encode_segment(&repo.full_name()))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CreateMergeRequestBody<'a> {
+    source_branch: &'a str,
+    target_branch: &'a str,
+    title: String,
+    description: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct NoteBody<'a> {
+    body: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct MergeRequest {
+    iid: i64,
+    web_url: String,
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    squash_commit_sha: Option<String>,
+}
+
+impl From<MergeRequest> for PullRequestInfo {
+    fn from(mr: MergeRequest) -> Self {
+        PullRequestInfo {
+            number: mr.iid,
+            url: mr.web_url,
+            status: match mr.state.as_str() {
+                "opened" | "locked" => MergeStatus::Open,
+                "merged" => MergeStatus::Merged,
+                "closed" => MergeStatus::Closed,
+                _ => MergeStatus::Unknown,
+            },
+            merged_at: mr.merged_at,
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PipelineRef {
+    id: i64,
+}
+
+/// The fields of a merge request needed to find its CI pipeline
+#[derive(Debug, Deserialize)]
+struct MergeRequestHead {
+    sha: String,
+    head_pipeline: Option<PipelineRef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Job {
+    id: i64,
+    name: String,
+    status: String,
+    web_url: Option<String>,
+    #[serde(default)]
+    allow_failure: bool,
+}
+
+impl From<Job> for CiCheck {
+    fn from(job: Job) -> Self {
+        let status = match job.status.as_str() {
+            "created"
+            | "waiting_for_resource"
+            | "preparing"
+            | "pending"
+            | "running"
+            | "scheduled" => CiStatus::Pending,
+            "failed" if job.allow_failure => CiStatus::Success,
+            "failed" | "canceled" => CiStatus::Failure,
+            // success, skipped, manual
+            _ => CiStatus::Success,
+        };
+        CiCheck {
+            name: job.name,
+            status,
+            url: job.web_url,
+            job_id: Some(job.id.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NoteAuthor {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotePosition {
+    new_path: Option<String>,
+    old_path: Option<String>,
+    new_line: Option<i64>,
+    old_line: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Note {
+    id: i64,
+    body: String,
+    author: NoteAuthor,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    system: bool,
+    position: Option<NotePosition>,
+}
+
+#[async_trait]
+impl GitHostProvider for GitLabProvider {
+    fn kind(&self) -> GitHostKind {
+        GitHostKind::GitLab
+    }
+
+    async fn create_pr(
+        &self,
+        repo: &GitHostRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        // The title prefix is how GitLab marks drafts across all versions
+        let title = if request.draft.unwrap_or(false) {
+            format!("Draft: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let body = CreateMergeRequestBody {
+            source_branch: &request.head_branch,
+            target_branch: &request.base_branch,
+            title,
+            description: request.body.as_deref().unwrap_or(""),
+        };
+        let mr: MergeRequest = self
+            .api
+            .post_once(
+                &format!("{}/merge_requests", Self::project_path(repo)),
+                &body,
+            )
+            .await?;
+        tracing::info!(
+            "Created GitLab MR !{} for branch {} in {}",
+            mr.iid,
+            request.head_branch,
+            repo.full_name()
+        );
+        Ok(mr.into())
+    }
+
+    async fn get_pr_status(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let mr: MergeRequest = self
+            .api
+            .get(
+                &format!("{}/merge_requests/{pr_number}", Self::project_path(repo)),
+                &[],
+            )
+            .await?;
+        Ok(mr.into())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        repo: &GitHostRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let mrs: Vec<MergeRequest> = self
+            .api
+            .get_all(
+                &format!("{}/merge_requests", Self::project_path(repo)),
+                &[
+                    ("state", "all".to_string()),
+                    ("source_branch", branch.to_string()),
+                ],
+                "per_page",
+                PAGE_SIZE,
+            )
+            .await?;
+        Ok(mrs.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_pr_comments(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let notes: Vec<Note> = self
+            .api
+            .get_all(
+                &format!(
+                    "{}/merge_requests/{pr_number}/notes",
+                    Self::project_path(repo)
+                ),
+                &[
+                    ("sort", "asc".to_string()),
+                    ("order_by", "created_at".to_string()),
+                ],
+                "per_page",
+                PAGE_SIZE,
+            )
+            .await?;
+
+        let mr_url = format!("{}/-/merge_requests/{pr_number}", repo.web_url());
+        let mut unified: Vec<UnifiedPrComment> = notes
+            .into_iter()
+            // System notes are activity ("added 1 commit"), not comments
+            .filter(|note| !note.system)
+            .map(|note| {
+                let url = format!("{mr_url}#note_{}", note.id);
+                match note.position {
+                    Some(position) => UnifiedPrComment::Review {
+                        id: note.id,
+                        author: note.author.username,
+                        author_association: NO_ASSOCIATION.to_string(),
+                        body: note.body,
+                        created_at: note.created_at,
+                        url,
+                        path: position.new_path.or(position.old_path).unwrap_or_default(),
+                        line: position.new_line.or(position.old_line),
+                        diff_hunk: String::new(),
+                    },
+                    None => UnifiedPrComment::General {
+                        id: note.id.to_string(),
+                        author: note.author.username,
+                        author_association: NO_ASSOCIATION.to_string(),
+                        body: note.body,
+                        created_at: note.created_at,
+                        url,
+                    },
+                }
+            })
+            .collect();
+        unified.sort_by_key(|c| c.created_at());
+        Ok(unified)
+    }
+
+    async fn post_pr_comment(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        let _: IgnoredAny = self
+            .api
+            .post_once(
+                &format!(
+                    "{}/merge_requests/{pr_number}/notes",
+                    Self::project_path(repo)
+                ),
+                &NoteBody { body },
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_pr_checks(
+        &self,
+        repo: &GitHostRepoInfo,
+        pr_number: i64,
+    ) -> Result<PrChecks, GitHostError> {
+        let project = Self::project_path(repo);
+        let mr: MergeRequestHead = self
+            .api
+            .get(&format!("{project}/merge_requests/{pr_number}"), &[])
+            .await?;
+        let checks = match mr.head_pipeline {
+            Some(pipeline) => self
+                .api
+                .get_all::<Job>(
+                    &format!("{project}/pipelines/{}/jobs", pipeline.id),
+                    &[],
+                    "per_page",
+                    PAGE_SIZE,
+                )
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            None => Vec::new(),
+        };
+        Ok(PrChecks {
+            head_sha: mr.sha,
+            checks,
+        })
+    }
+
+    async fn get_check_log(
+        &self,
+        repo: &GitHostRepoInfo,
+        check: &CiCheck,
+    ) -> Result<Option<String>, GitHostError> {
+        let Some(job_id) = &check.job_id else {
+            return Ok(None);
+        };
+        let log = self
+            .api
+            .get_text(&format!("{}/jobs/{job_id}/trace", Self::project_path(repo)))
+            .await?;
+        Ok(Some(log))
+    }
+}
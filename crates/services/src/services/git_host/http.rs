@@ -0,0 +1,238 @@
+//! Small JSON client shared by the REST-based providers, with retries.
+
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::{Serialize, de::DeserializeOwned};
+use tracing::warn;
+
+use super::GitHostError;
+
+/// Upper bound on pages fetched for a single listing
+const MAX_PAGES: usize = 20;
+
+#[derive(Debug, Clone)]
+pub(super) struct HostApi {
+    base: String,
+    http: Client,
+    /// Header name and value carrying the access token
+    auth: Option<(&'static str, String)>,
+}
+
+impl HostApi {
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+    pub(super) fn new(
+        base_url: &str,
+        auth: Option<(&'static str, String)>,
+    ) -> Result<Self, GitHostError> {
+        let http = Client::builder()
+            .timeout(Self::REQUEST_TIMEOUT)
+            .user_agent(concat!("vibe-kanban/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| GitHostError::Transport(e.to_string()))?;
+        Ok(Self {
+            base: base_url.trim_end_matches('/').to_string(),
+            http,
+            auth,
+        })
+    }
+
+    /// Host part of the base URL, used in error messages
+    fn host(&self) -> String {
+        url::Url::parse(&self.base)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| self.base.clone())
+    }
+
+    pub(super) async fn get<T>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, GitHostError>
+    where
+        T: DeserializeOwned,
+    {
+        self.send(Method::GET, path, query, None::<&()>).await
+    }
+
+    /// Fetch every page of a listing endpoint. `page_size_param` differs per
+    /// provider (`per_page` vs `limit`).
+    pub(super) async fn get_all<T>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        page_size_param: &str,
+        page_size: usize,
+    ) -> Result<Vec<T>, GitHostError>
+    where
+        T: DeserializeOwned,
+    {
+        let mut items = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let mut page_query = query.to_vec();
+            page_query.push((page_size_param, page_size.to_string()));
+            page_query.push(("page", page.to_string()));
+            let batch: Vec<T> = self.get(path, &page_query).await?;
+            let done = batch.len() < page_size;
+            items.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    /// GET a plain-text resource such as a CI job log. Redirects (e.g. to log
+    /// storage) are followed.
+    pub(super) async fn get_text(&self, path: &str) -> Result<String, GitHostError> {
+        let url = self.url(path);
+
+        (|| async {
+            self.execute(&Method::GET, &url, &[], None::<&()>, "*/*")
+                .await?
+                .text()
+                .await
+                .map_err(|e| GitHostError::Transport(e.to_string()))
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|e, dur| {
+            warn!(
+                "Git host API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                e
+            )
+        })
+        .await
+    }
+
+    /// POST without retries; nothing posted is idempotent (pull requests,
+    /// comments)
+    pub(super) async fn post_once<B, T>(&self, path: &str, body: &B) -> Result<T, GitHostError>
+    where
+        B: Serialize,
+        T: DeserializeOwned,
+    {
+        self.send_once(&Method::POST, &self.url(path), &[], Some(body))
+            .await
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base, path.trim_start_matches('/'))
+    }
+
+    async fn send<B, T>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<&B>,
+    ) -> Result<T, GitHostError>
+    where
+        B: Serialize,
+        T: DeserializeOwned,
+    {
+        let url = self.url(path);
+
+        (|| async { self.send_once(&method, &url, query, body).await })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e: &GitHostError| e.should_retry())
+            .notify(|e, dur| {
+                warn!(
+                    "Git host API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    e
+                )
+            })
+            .await
+    }
+
+    async fn send_once<B, T>(
+        &self,
+        method: &Method,
+        url: &str,
+        query: &[(&str, String)],
+        body: Option<&B>,
+    ) -> Result<T, GitHostError>
+    where
+        B: Serialize,
+        T: DeserializeOwned,
+    {
+        self.execute(method, url, query, body, "application/json")
+            .await?
+            .json::<T>()
+            .await
+            .map_err(|e| GitHostError::UnexpectedResponse(format!("{url}: {e}")))
+    }
+
+    /// Send a request and map error statuses, leaving the body to the caller
+    async fn execute<B>(
+        &self,
+        method: &Method,
+        url: &str,
+        query: &[(&str, String)],
+        body: Option<&B>,
+        accept: &str,
+    ) -> Result<Response, GitHostError>
+    where
+        B: Serialize,
+    {
+        let mut req = self
+            .http
+            .request(method.clone(), url)
+            .header("Accept", accept)
+            .query(query);
+        if let Some((header, value)) = &self.auth {
+            req = req.header(*header, value);
+        }
+        if let Some(b) = body {
+            req = req.json(b);
+        }
+
+        let res = req
+            .send()
+            .await
+            .map_err(|e| GitHostError::Transport(e.to_string()))?;
+
+        let status = res.status();
+        if status.is_success() {
+            return Ok(res);
+        }
+
+        let body = res.text().await.unwrap_or_default();
+        Err(match status {
+            StatusCode::UNAUTHORIZED => GitHostError::AuthFailed {
+                host: self.host(),
+                message: body,
+            },
+            StatusCode::FORBIDDEN => GitHostError::InsufficientPermissions(body),
+            StatusCode::NOT_FOUND => GitHostError::NotFound(url.to_string()),
+            s => GitHostError::Http {
+                status: s.as_u16(),
+                body,
+            },
+        })
+    }
+}
+
+/// Percent-encode a single path segment (GitLab project paths contain `/`)
+pub(super) fn encode_segment(segment: &str) -> String {
+    url::form_urlencoded::byte_serialize(segment.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
+}
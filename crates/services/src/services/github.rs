@@ -1,56 +1,18 @@
 use std::time::Duration;
 
 use backon::{ExponentialBuilder, Retryable};
-use chrono::{DateTime, Utc};
 use db::models::merge::PullRequestInfo;
 use regex::Regex;
-use serde::Serialize;
 use thiserror::Error;
 use tokio::task;
 use tracing::info;
-use ts_rs::TS;
 
 mod cli;
 
 use cli::{GhCli, GhCliError, PrComment, PrReviewComment};
 pub use cli::{PrCommentAuthor, ReviewCommentUser};
 
-/// Unified PR comment that can be either a general comment or review comment
-#[derive(Debug, Clone, Serialize, TS)]
-#[serde(tag = "comment_type", rename_all = "snake_case")]
-#[ts(tag = "comment_type", rename_all = "snake_case")]
-pub enum UnifiedPrComment {
-    /// General PR comment (conversation)
-    General {
-        id: String,
-        author: String,
-        author_association: String,
-        body: String,
-        created_at: DateTime<Utc>,
-        url: String,
-    },
-    /// Inline review comment (on code)
-    Review {
-        id: i64,
-        author: String,
-        author_association: String,
-        body: String,
-        created_at: DateTime<Utc>,
-        url: String,
-        path: String,
-        line: Option<i64>,
-        diff_hunk: String,
-    },
-}
-
-impl UnifiedPrComment {
-    fn created_at(&self) -> DateTime<Utc> {
-        match self {
-            UnifiedPrComment::General { created_at, .. } => *created_at,
-            UnifiedPrComment::Review { created_at, .. } => *created_at,
-        }
-    }
-}
+use crate::services::git_host::{CreatePrRequest, UnifiedPrComment};
 
 #[derive(Debug, Error)]
 pub enum GitHubServiceError {
@@ -143,15 +105,6 @@ impl GitHubRepoInfo {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct CreatePrRequest {
-    pub title: String,
-    pub body: Option<String>,
-    pub head_branch: String,
-    pub base_branch: String,
-    pub draft: Option<bool>,
-}
-
 #[derive(Debug, Clone)]
 pub struct GitHubService {
     gh_cli: GhCli,
@@ -371,6 +324,64 @@ impl GitHubService {
         Ok(unified)
     }
 
+    /// Post a general comment on a pull request. Not retried, since a retry
+    /// after a timeout could post the comment twice.
+    pub async fn comment_on_pr(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHubServiceError> {
+        let cli = self.gh_cli.clone();
+        let owner = repo_info.owner.clone();
+        let repo = repo_info.repo_name.clone();
+        let body = body.to_string();
+        task::spawn_blocking(move || cli.comment_on_pr(&owner, &repo, pr_number, &body))
+            .await
+            .map_err(|err| {
+                GitHubServiceError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for commenting on PR #{pr_number}: {err}"
+                ))
+            })?
+            .map_err(GitHubServiceError::from)
+    }
+
+    /// GET a REST API path through the CLI, for endpoints without a dedicated
+    /// `gh` command (CI checks, job logs)
+    pub async fn api_get(&self, path: &str) -> Result<String, GitHubServiceError> {
+        (|| async {
+            let cli = self.gh_cli.clone();
+            let path = path.to_string();
+            task::spawn_blocking({
+                let path = path.clone();
+                move || cli.api_get(&path)
+            })
+            .await
+            .map_err(|err| {
+                GitHubServiceError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for GET {path}: {err}"
+                ))
+            })?
+            .map_err(GitHubServiceError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHubServiceError| e.should_retry())
+        .notify(|err: &GitHubServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
     async fn fetch_general_comments(
         &self,
         repo_info: &GitHubRepoInfo,
@@ -17,7 +17,7 @@ use thiserror::Error;
 use ts_rs::TS;
 use utils::shell::resolve_executable_path_blocking;
 
-use crate::services::github::{CreatePrRequest, GitHubRepoInfo};
+use crate::services::{git_host::CreatePrRequest, github::GitHubRepoInfo};
 
 /// Author information for a PR comment
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -232,6 +232,32 @@ impl GhCli {
         Self::parse_pr_comments(&raw)
     }
 
+    /// Post a general comment on a pull request.
+    pub fn comment_on_pr(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GhCliError> {
+        self.run([
+            "pr",
+            "comment",
+            &pr_number.to_string(),
+            "--repo",
+            &format!("{owner}/{repo}"),
+            "--body",
+            body,
+        ])?;
+        Ok(())
+    }
+
+    /// GET a REST API path (optionally with a query string) and return the raw
+    /// response body.
+    pub fn api_get(&self, path: &str) -> Result<String, GhCliError> {
+        self.run(["api", path])
+    }
+
     /// Fetch inline review comments for a pull request via API.
     pub fn get_pr_review_comments(
         &self,
@@ -1,24 +1,40 @@
 pub mod analytics;
+pub mod api_auth;
 pub mod approvals;
 pub mod auth;
 pub mod config;
+pub mod conflict_resolution;
 pub mod container;
+pub mod content_search;
+pub mod dependency_cache;
+pub mod dev_server;
 pub mod diff_stream;
 pub mod events;
 pub mod file_ranker;
 pub mod file_search_cache;
+pub mod file_search_snapshot;
 pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git;
+pub mod git_host;
 pub mod github;
 pub mod image;
 pub mod notification;
 pub mod oauth_credentials;
 pub mod pr_monitor;
+pub mod pr_review;
 pub mod project;
+pub mod project_scripts;
 pub mod queued_message;
 pub mod remote_client;
 pub mod repo;
+pub mod review_comments;
+pub mod scheduler;
+pub mod secrets;
+pub mod settings_file;
 pub mod share;
+pub mod sparse_checkout;
+pub mod webhooks;
 pub mod workspace_manager;
 pub mod worktree_manager;
+pub mod worktree_pool;
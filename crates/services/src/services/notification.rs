@@ -1,14 +1,241 @@
-use std::sync::{Arc, OnceLock};
+use std::{
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
 
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use thiserror::Error;
 use tokio::sync::RwLock;
-use utils;
+use ts_rs::TS;
+use utils::{self, shell::get_shell_command, text::truncate_to_char_boundary};
 
 use crate::services::config::{Config, NotificationConfig, SoundFile};
 
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest a command hook may run before it is killed
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+/// Discord rejects messages longer than this many characters
+const DISCORD_MESSAGE_LIMIT: usize = 2000;
+/// Bytes of a failed response or command output kept in the error
+const ERROR_OUTPUT_LIMIT: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    AttemptFinished,
+    AttemptFailed,
+    ApprovalNeeded,
+    PrMerged,
+    CiFailed,
+}
+
+impl NotificationEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AttemptFinished => "attempt_finished",
+            Self::AttemptFailed => "attempt_failed",
+            Self::ApprovalNeeded => "approval_needed",
+            Self::PrMerged => "pr_merged",
+            Self::CiFailed => "ci_failed",
+        }
+    }
+
+    /// ntfy tag, shown as an emoji next to the title
+    fn ntfy_tag(&self) -> &'static str {
+        match self {
+            Self::AttemptFinished => "white_check_mark",
+            Self::AttemptFailed => "x",
+            Self::ApprovalNeeded => "raising_hand",
+            Self::PrMerged => "tada",
+            Self::CiFailed => "rotating_light",
+        }
+    }
+
+    /// ntfy priority: events that need attention are raised above the default
+    fn ntfy_priority(&self) -> u8 {
+        match self {
+            Self::AttemptFailed | Self::ApprovalNeeded | Self::CiFailed => 4,
+            Self::AttemptFinished | Self::PrMerged => 3,
+        }
+    }
+}
+
+/// Shape of the JSON body posted by a webhook channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookPreset {
+    /// All notification fields as a flat JSON object
+    #[default]
+    Generic,
+    Slack,
+    Discord,
+    Mattermost,
+}
+
+fn default_ntfy_server() -> String {
+    "https://ntfy.sh".to_string()
+}
+
+fn default_channel_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum NotificationChannelKind {
+    /// POST a JSON payload to an incoming webhook URL
+    Webhook {
+        url: String,
+        #[serde(default)]
+        preset: WebhookPreset,
+    },
+    /// Publish to a topic on an ntfy server
+    Ntfy {
+        #[serde(default = "default_ntfy_server")]
+        server: String,
+        topic: String,
+        /// Access token for protected topics
+        #[serde(default)]
+        token: Option<String>,
+    },
+    /// Run a shell command with the notification in `VK_*` environment variables
+    Command { command: String },
+}
+
+/// A destination that receives notifications in addition to the local sound
+/// and push notification
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct NotificationChannel {
+    pub name: String,
+    #[serde(default = "default_channel_enabled")]
+    pub enabled: bool,
+    /// Events sent to this channel; every event when empty
+    #[serde(default)]
+    pub events: Vec<NotificationEvent>,
+    /// Replaces the default title. Supports `{{event}}`, `{{title}}`,
+    /// `{{message}}`, `{{task}}`, `{{branch}}` and `{{url}}`.
+    #[serde(default)]
+    pub title_template: Option<String>,
+    /// Replaces the default message; same placeholders as the title
+    #[serde(default)]
+    pub message_template: Option<String>,
+    pub kind: NotificationChannelKind,
+}
+
+impl NotificationChannel {
+    fn accepts(&self, event: NotificationEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum NotificationError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("Endpoint responded with {status}: {body}")]
+    Status {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    #[error("Failed to run command: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Command timed out after {}s", COMMAND_TIMEOUT.as_secs())]
+    CommandTimeout,
+    #[error("Command exited with {status}: {output}")]
+    CommandFailed {
+        status: std::process::ExitStatus,
+        output: String,
+    },
+}
+
+/// A notification and the details channel templates can refer to
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub event: NotificationEvent,
+    pub title: String,
+    pub message: String,
+    pub task_title: Option<String>,
+    pub branch: Option<String>,
+    /// Pull request or other page the notification links to
+    pub url: Option<String>,
+}
+
+impl Notification {
+    pub fn new(event: NotificationEvent, title: String, message: String) -> Self {
+        Self {
+            event,
+            title,
+            message,
+            task_title: None,
+            branch: None,
+            url: None,
+        }
+    }
+
+    pub fn with_task(mut self, task_title: &str, branch: &str) -> Self {
+        self.task_title = Some(task_title.to_string());
+        self.branch = Some(branch.to_string());
+        self
+    }
+
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    /// Substitute `{{placeholder}}`s in `template`; missing values render empty
+    fn render(&self, template: &str) -> String {
+        let placeholders = [
+            ("{{event}}", self.event.as_str()),
+            ("{{title}}", self.title.as_str()),
+            ("{{message}}", self.message.as_str()),
+            ("{{task}}", self.task_title.as_deref().unwrap_or_default()),
+            ("{{branch}}", self.branch.as_deref().unwrap_or_default()),
+            ("{{url}}", self.url.as_deref().unwrap_or_default()),
+        ];
+        // Single pass, so values containing placeholders are left alone
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            rendered.push_str(&rest[..start]);
+            rest = &rest[start..];
+            match placeholders.iter().find(|(key, _)| rest.starts_with(key)) {
+                Some((key, value)) => {
+                    rendered.push_str(value);
+                    rest = &rest[key.len()..];
+                }
+                None => {
+                    rendered.push_str("{{");
+                    rest = &rest[2..];
+                }
+            }
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+
+    /// Title and message as configured for `channel`
+    fn for_channel(&self, channel: &NotificationChannel) -> (String, String) {
+        let title = match &channel.title_template {
+            Some(template) if !template.trim().is_empty() => self.render(template),
+            _ => self.title.clone(),
+        };
+        let message = match &channel.message_template {
+            Some(template) if !template.trim().is_empty() => self.render(template),
+            _ => self.message.clone(),
+        };
+        (title, message)
+    }
+}
+
 /// Service for handling cross-platform notifications including sound alerts and push notifications
 #[derive(Debug, Clone)]
 pub struct NotificationService {
     config: Arc<RwLock<Config>>,
+    client: reqwest::Client,
 }
 
 /// Cache for WSL root path from PowerShell
@@ -16,13 +243,48 @@ static WSL_ROOT_PATH_CACHE: OnceLock<Option<String>> = OnceLock::new();
 
 impl NotificationService {
     pub fn new(config: Arc<RwLock<Config>>) -> Self {
-        Self { config }
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("vibe-kanban/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { config, client }
     }
 
-    /// Send both sound and push notifications if enabled
-    pub async fn notify(&self, title: &str, message: &str) {
+    /// Send sound and push notifications if enabled, and deliver to every
+    /// channel routed for the event. Channel deliveries run in the background;
+    /// failures are logged.
+    pub async fn notify(&self, notification: Notification) {
         let config = self.config.read().await.notifications.clone();
-        Self::send_notification(&config, title, message).await;
+        Self::send_notification(&config, &notification.title, &notification.message).await;
+
+        for channel in config.channels {
+            if !channel.accepts(notification.event) {
+                continue;
+            }
+            let client = self.client.clone();
+            let notification = notification.clone();
+            tokio::spawn(async move {
+                if let Err(e) = deliver(&client, &channel, &notification).await {
+                    tracing::warn!(
+                        "Failed to send notification to channel '{}': {}",
+                        channel.name,
+                        e
+                    );
+                }
+            });
+        }
+    }
+
+    /// Send a sample notification to `channel`, regardless of its routing
+    pub async fn send_test(&self, channel: &NotificationChannel) -> Result<(), NotificationError> {
+        let notification = Notification::new(
+            NotificationEvent::AttemptFinished,
+            "Test Notification".to_string(),
+            "✅ Notifications from Vibe Kanban reach this channel".to_string(),
+        )
+        .with_task("Example task", "vk/example-branch");
+        deliver(&self.client, channel, &notification).await
     }
 
     /// Internal method to send notifications with a given config
@@ -236,3 +498,238 @@ impl NotificationService {
         }
     }
 }
+
+async fn deliver(
+    client: &reqwest::Client,
+    channel: &NotificationChannel,
+    notification: &Notification,
+) -> Result<(), NotificationError> {
+    let (title, message) = notification.for_channel(channel);
+    match &channel.kind {
+        NotificationChannelKind::Webhook { url, preset } => {
+            let payload = webhook_payload(*preset, notification, &title, &message);
+            let response = client.post(url).json(&payload).send().await?;
+            check_response(response).await
+        }
+        NotificationChannelKind::Ntfy {
+            server,
+            topic,
+            token,
+        } => {
+            let payload = ntfy_payload(topic, notification, &title, &message);
+            let mut request = client.post(server.trim_end_matches('/')).json(&payload);
+            if let Some(token) = token.as_deref().filter(|t| !t.is_empty()) {
+                request = request.bearer_auth(token);
+            }
+            check_response(request.send().await?).await
+        }
+        NotificationChannelKind::Command { command } => {
+            run_command(command, notification, &title, &message).await
+        }
+    }
+}
+
+async fn check_response(response: reqwest::Response) -> Result<(), NotificationError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(NotificationError::Status {
+        status,
+        body: truncate_to_char_boundary(&body, ERROR_OUTPUT_LIMIT).to_string(),
+    })
+}
+
+fn webhook_payload(
+    preset: WebhookPreset,
+    notification: &Notification,
+    title: &str,
+    message: &str,
+) -> Value {
+    match preset {
+        WebhookPreset::Generic => json!({
+            "event": notification.event,
+            "title": title,
+            "message": message,
+            "task": notification.task_title,
+            "branch": notification.branch,
+            "url": notification.url,
+            "timestamp": Utc::now(),
+        }),
+        WebhookPreset::Slack => json!({ "text": format!("*{title}*\n{message}") }),
+        WebhookPreset::Discord => {
+            let content = format!("**{title}**\n{message}");
+            let content = match content.char_indices().nth(DISCORD_MESSAGE_LIMIT) {
+                Some((end, _)) => &content[..end],
+                None => content.as_str(),
+            };
+            json!({ "content": content })
+        }
+        WebhookPreset::Mattermost => json!({ "text": format!("**{title}**\n{message}") }),
+    }
+}
+
+/// JSON publish body; ntfy reads the topic from the body when posting to the
+/// server root
+fn ntfy_payload(topic: &str, notification: &Notification, title: &str, message: &str) -> Value {
+    let mut payload = Map::new();
+    payload.insert("topic".to_string(), json!(topic));
+    payload.insert("title".to_string(), json!(title));
+    payload.insert("message".to_string(), json!(message));
+    payload.insert("tags".to_string(), json!([notification.event.ntfy_tag()]));
+    payload.insert(
+        "priority".to_string(),
+        json!(notification.event.ntfy_priority()),
+    );
+    if let Some(url) = &notification.url {
+        payload.insert("click".to_string(), json!(url));
+    }
+    Value::Object(payload)
+}
+
+async fn run_command(
+    command: &str,
+    notification: &Notification,
+    title: &str,
+    message: &str,
+) -> Result<(), NotificationError> {
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let child = tokio::process::Command::new(shell_cmd)
+        .arg(shell_arg)
+        .arg(command)
+        .env("VK_NOTIFICATION_EVENT", notification.event.as_str())
+        .env("VK_NOTIFICATION_TITLE", title)
+        .env("VK_NOTIFICATION_MESSAGE", message)
+        .env(
+            "VK_TASK_TITLE",
+            notification.task_title.as_deref().unwrap_or_default(),
+        )
+        .env(
+            "VK_BRANCH",
+            notification.branch.as_deref().unwrap_or_default(),
+        )
+        .env("VK_URL", notification.url.as_deref().unwrap_or_default())
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let output = tokio::time::timeout(COMMAND_TIMEOUT, child)
+        .await
+        .map_err(|_| NotificationError::CommandTimeout)??;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(NotificationError::CommandFailed {
+        status: output.status,
+        output: truncate_to_char_boundary(stderr.trim(), ERROR_OUTPUT_LIMIT).to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Notification {
+        Notification::new(
+            NotificationEvent::CiFailed,
+            "CI Failed: Fix login".to_string(),
+            "❌ CI checks failed".to_string(),
+        )
+        .with_task("Fix login", "vk/fix-login")
+        .with_url("https://github.com/acme/app/pull/7")
+    }
+
+    fn channel(kind: NotificationChannelKind) -> NotificationChannel {
+        NotificationChannel {
+            name: "test".to_string(),
+            enabled: true,
+            events: vec![NotificationEvent::CiFailed],
+            title_template: Some("[{{event}}] {{task}}".to_string()),
+            message_template: Some("{{branch}} {{unknown}} {{url}}".to_string()),
+            kind,
+        }
+    }
+
+    #[test]
+    fn templates_substitute_placeholders_and_routing_filters_events() {
+        let channel = channel(NotificationChannelKind::Command {
+            command: "true".to_string(),
+        });
+        let (title, message) = sample().for_channel(&channel);
+        assert_eq!(title, "[ci_failed] Fix login");
+        assert_eq!(
+            message,
+            "vk/fix-login {{unknown}} https://github.com/acme/app/pull/7"
+        );
+
+        assert!(channel.accepts(NotificationEvent::CiFailed));
+        assert!(!channel.accepts(NotificationEvent::PrMerged));
+        let all_events = NotificationChannel {
+            events: Vec::new(),
+            ..channel.clone()
+        };
+        assert!(all_events.accepts(NotificationEvent::PrMerged));
+        let disabled = NotificationChannel {
+            enabled: false,
+            ..channel
+        };
+        assert!(!disabled.accepts(NotificationEvent::CiFailed));
+    }
+
+    #[test]
+    fn presets_shape_payloads() {
+        let notification = sample();
+        let slack = webhook_payload(WebhookPreset::Slack, &notification, "T", "M");
+        assert_eq!(slack, json!({ "text": "*T*\nM" }));
+        let mattermost = webhook_payload(WebhookPreset::Mattermost, &notification, "T", "M");
+        assert_eq!(mattermost, json!({ "text": "**T**\nM" }));
+
+        let long = "é".repeat(3000);
+        let discord = webhook_payload(WebhookPreset::Discord, &notification, "T", &long);
+        let content = discord["content"].as_str().unwrap();
+        assert_eq!(content.chars().count(), DISCORD_MESSAGE_LIMIT);
+
+        let generic = webhook_payload(WebhookPreset::Generic, &notification, "T", "M");
+        assert_eq!(generic["event"], "ci_failed");
+        assert_eq!(generic["branch"], "vk/fix-login");
+
+        let ntfy = ntfy_payload("builds", &notification, "T", "M");
+        assert_eq!(ntfy["topic"], "builds");
+        assert_eq!(ntfy["tags"], json!(["rotating_light"]));
+        assert_eq!(ntfy["priority"], 4);
+        assert_eq!(ntfy["click"], "https://github.com/acme/app/pull/7");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_hooks_receive_notification_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out.txt");
+        let channel = channel(NotificationChannelKind::Command {
+            command: format!(
+                "printf '%s|%s' \"$VK_NOTIFICATION_EVENT\" \"$VK_BRANCH\" > '{}'",
+                out.display()
+            ),
+        });
+        deliver(&reqwest::Client::new(), &channel, &sample())
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "ci_failed|vk/fix-login"
+        );
+
+        let failing = NotificationChannel {
+            kind: NotificationChannelKind::Command {
+                command: "echo boom >&2; exit 3".to_string(),
+            },
+            ..channel
+        };
+        let err = deliver(&reqwest::Client::new(), &failing, &sample())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("boom"));
+    }
+}
@@ -1,9 +1,12 @@
-use std::time::Duration;
+use std::{collections::BTreeSet, time::Duration};
 
+use chrono::Utc;
 use db::{
     DBService,
     models::{
-        merge::{Merge, MergeStatus, PrMerge},
+        merge::{CiStatus, Merge, MergeStatus, PrCiStatus, PrMerge},
+        project::Project,
+        repo::Repo,
         task::{Task, TaskStatus},
         workspace::{Workspace, WorkspaceError},
     },
@@ -12,40 +15,57 @@ use serde_json::json;
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
 use tokio::time::interval;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::services::{
     analytics::AnalyticsContext,
-    github::{GitHubRepoInfo, GitHubService, GitHubServiceError},
+    container::{ContainerError, ContainerService},
+    git_host::{GitHostError, GitHostProvider, GitHostRepoInfo},
+    notification::{Notification, NotificationEvent},
+    pr_review::{
+        PrFollowUpKind, build_ci_fix_prompt, build_review_prompt, comment_key,
+        from_trusted_authors, unseen_comments, without_replies,
+    },
     share::SharePublisher,
 };
 
 #[derive(Debug, Error)]
 enum PrMonitorError {
     #[error(transparent)]
-    GitHubServiceError(#[from] GitHubServiceError),
+    GitHost(#[from] GitHostError),
     #[error(transparent)]
     WorkspaceError(#[from] WorkspaceError),
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
 }
 
-/// Service to monitor GitHub PRs and update task status when they are merged
-pub struct PrMonitorService {
+/// Fixes handed to the agent in a row before CI has to pass again
+const MAX_CI_FIX_ATTEMPTS: i64 = 3;
+
+/// Service to monitor PRs on all git hosts and update task status when they are
+/// merged. The CI status of open PRs is tracked as well. For projects that opt
+/// in, new review comments and failing CI are handed to the coding agent as
+/// follow-ups.
+pub struct PrMonitorService<C> {
     db: DBService,
+    container: C,
     poll_interval: Duration,
     analytics: Option<AnalyticsContext>,
     publisher: Option<SharePublisher>,
 }
 
-impl PrMonitorService {
+impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
     pub async fn spawn(
         db: DBService,
+        container: C,
         analytics: Option<AnalyticsContext>,
         publisher: Option<SharePublisher>,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
+            container,
             poll_interval: Duration::from_secs(60), // Check every minute
             analytics,
             publisher,
@@ -71,7 +91,7 @@ impl PrMonitorService {
         }
     }
 
-    /// Check all open PRs for updates with the provided GitHub token
+    /// Check all open PRs for updates
     async fn check_all_open_prs(&self) -> Result<(), PrMonitorError> {
         let open_prs = Merge::get_open_prs(&self.db.pool).await?;
 
@@ -95,12 +115,19 @@ impl PrMonitorService {
 
     /// Check the status of a specific PR
     async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
-        // GitHubService now uses gh CLI, no token needed
-        let github_service = GitHubService::new()?;
-        let repo_info = GitHubRepoInfo::from_remote_url(&pr_merge.pr_info.url)?;
+        // The PR URL identifies both the host and the repository
+        let repo_info = GitHostRepoInfo::from_remote_url(&pr_merge.pr_info.url)?;
+        let project_id = self
+            .load_project(pr_merge)
+            .await?
+            .map(|(_, project)| project.id);
+        let provider = self
+            .container
+            .git_host_provider(project_id, &repo_info)
+            .await?;
 
-        let pr_status = github_service
-            .update_pr_status(&repo_info, pr_merge.pr_info.number)
+        let pr_status = provider
+            .get_pr_status(&repo_info, pr_merge.pr_info.number)
             .await?;
 
         debug!(
@@ -108,9 +135,28 @@ impl PrMonitorService {
             pr_merge.pr_info.number, pr_status.status
         );
 
-        // Update the PR status in the database
-        if !matches!(&pr_status.status, MergeStatus::Open) {
-            // Update merge status with the latest information from GitHub
+        if matches!(&pr_status.status, MergeStatus::Open) {
+            if let Err(e) = self
+                .check_ci_status(pr_merge, provider.as_ref(), &repo_info)
+                .await
+            {
+                warn!(
+                    "Failed to check CI status of PR #{} for workspace {}: {}",
+                    pr_merge.pr_info.number, pr_merge.workspace_id, e
+                );
+            }
+            if let Err(e) = self
+                .check_review_comments(pr_merge, provider.as_ref(), &repo_info)
+                .await
+            {
+                warn!(
+                    "Failed to check review comments on PR #{} for workspace {}: {}",
+                    pr_merge.pr_info.number, pr_merge.workspace_id, e
+                );
+            }
+        } else {
+            // Update the PR status in the database
+            // Update merge status with the latest information from the git host
             Merge::update_status(
                 &self.db.pool,
                 pr_merge.id,
@@ -129,10 +175,36 @@ impl PrMonitorService {
                     pr_merge.pr_info.number, workspace.task_id
                 );
                 Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                self.container
+                    .webhooks()
+                    .pr_merged(workspace.id, pr_merge.pr_info.number, &pr_merge.pr_info.url)
+                    .await;
+
+                let task = Task::find_by_id(&self.db.pool, workspace.task_id)
+                    .await
+                    .ok()
+                    .flatten();
+                if let Some(task) = &task {
+                    self.container
+                        .notification_service()
+                        .notify(
+                            Notification::new(
+                                NotificationEvent::PrMerged,
+                                format!("PR Merged: {}", task.title),
+                                format!(
+                                    "🎉 PR #{} for '{}' was merged\nBranch: {:?}",
+                                    pr_merge.pr_info.number, task.title, workspace.branch
+                                ),
+                            )
+                            .with_task(&task.title, &workspace.branch)
+                            .with_url(&pr_merge.pr_info.url),
+                        )
+                        .await;
+                }
 
                 // Track analytics event
                 if let Some(analytics) = &self.analytics
-                    && let Ok(Some(task)) = Task::find_by_id(&self.db.pool, workspace.task_id).await
+                    && let Some(task) = &task
                 {
                     analytics.analytics_service.track_event(
                         &analytics.user_id,
@@ -159,4 +231,270 @@ impl PrMonitorService {
 
         Ok(())
     }
+
+    /// Start a follow-up for review comments posted since the last poll, if the
+    /// project opted in. The first poll of a PR only records a baseline.
+    async fn check_review_comments(
+        &self,
+        pr_merge: &PrMerge,
+        provider: &dyn GitHostProvider,
+        repo_info: &GitHostRepoInfo,
+    ) -> Result<(), PrMonitorError> {
+        let pool = &self.db.pool;
+        let Some((workspace, project)) = self.load_project(pr_merge).await? else {
+            return Ok(());
+        };
+        if !project.auto_review_follow_ups {
+            return Ok(());
+        }
+
+        let comments = without_replies(
+            provider
+                .get_pr_comments(repo_info, pr_merge.pr_info.number)
+                .await?,
+        );
+        let keys: BTreeSet<String> = comments.iter().map(comment_key).collect();
+
+        let Some(seen) = Merge::get_seen_comment_ids(pool, pr_merge.id).await? else {
+            // Comments from before the mode was enabled are not acted on
+            Merge::set_seen_comment_ids(pool, pr_merge.id, &keys).await?;
+            return Ok(());
+        };
+        // Comments of outsiders are never handed over, only marked seen
+        let trusted = from_trusted_authors(comments, &self.container.pr_review_authors().await);
+        let unseen = unseen_comments(&trusted, &seen);
+        if unseen.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "{} new review comment(s) on PR #{} for workspace {}",
+            unseen.len(),
+            pr_merge.pr_info.number,
+            workspace.id
+        );
+        let repo = Repo::find_by_id(pool, pr_merge.repo_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let prompt = build_review_prompt(
+            &repo.name,
+            pr_merge.pr_info.number,
+            &pr_merge.pr_info.url,
+            &unseen,
+        );
+        let kind = PrFollowUpKind::ReviewComments {
+            count: unseen.len(),
+        };
+        if self
+            .container
+            .start_pr_follow_up(&workspace, pr_merge, prompt, kind)
+            .await?
+        {
+            let seen: BTreeSet<String> = seen.into_iter().chain(keys).collect();
+            Merge::set_seen_comment_ids(pool, pr_merge.id, &seen).await?;
+
+            if let Some(analytics) = &self.analytics {
+                analytics.analytics_service.track_event(
+                    &analytics.user_id,
+                    "pr_review_follow_up_started",
+                    Some(json!({
+                        "workspace_id": workspace.id.to_string(),
+                        "project_id": project.id.to_string(),
+                        "git_host": provider.kind(),
+                        "comment_count": unseen.len(),
+                    })),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Refresh the CI results of an open PR's head commit. Notifies when the
+    /// head commit starts failing and, if the project opted in, hands the
+    /// failing jobs to the agent once no check is pending anymore.
+    async fn check_ci_status(
+        &self,
+        pr_merge: &PrMerge,
+        provider: &dyn GitHostProvider,
+        repo_info: &GitHostRepoInfo,
+    ) -> Result<(), PrMonitorError> {
+        let pool = &self.db.pool;
+        let pr_checks = provider
+            .get_pr_checks(repo_info, pr_merge.pr_info.number)
+            .await?;
+        let Some(status) = CiStatus::combine(&pr_checks.checks) else {
+            // No CI configured for this repository
+            return Ok(());
+        };
+        let ci = PrCiStatus {
+            status,
+            head_sha: pr_checks.head_sha,
+            checks: pr_checks.checks,
+            updated_at: Utc::now(),
+        };
+
+        let previous = pr_merge.ci.as_ref();
+        let unchanged = previous.is_some_and(|prev| {
+            prev.head_sha == ci.head_sha && prev.status == ci.status && prev.checks == ci.checks
+        });
+        if !unchanged {
+            Merge::update_ci_status(pool, pr_merge.id, &ci).await?;
+        }
+
+        match ci.status {
+            CiStatus::Pending => return Ok(()),
+            CiStatus::Success => {
+                if !previous.is_some_and(|prev| prev.status == CiStatus::Success) {
+                    Merge::set_ci_fix_state(pool, pr_merge.id, None, 0).await?;
+                }
+                return Ok(());
+            }
+            CiStatus::Failure => {}
+        }
+
+        let newly_failing = !previous
+            .is_some_and(|prev| prev.head_sha == ci.head_sha && prev.status == CiStatus::Failure);
+        if newly_failing {
+            self.notify_ci_failure(pr_merge, &ci).await?;
+        }
+        if ci.checks.iter().any(|c| c.status == CiStatus::Pending) {
+            return Ok(());
+        }
+        self.start_ci_fix(pr_merge, provider, repo_info, &ci).await
+    }
+
+    async fn notify_ci_failure(
+        &self,
+        pr_merge: &PrMerge,
+        ci: &PrCiStatus,
+    ) -> Result<(), PrMonitorError> {
+        let Some(workspace) = Workspace::find_by_id(&self.db.pool, pr_merge.workspace_id).await?
+        else {
+            return Ok(());
+        };
+        let Some(task) = Task::find_by_id(&self.db.pool, workspace.task_id).await? else {
+            return Ok(());
+        };
+        let failing: Vec<&str> = ci
+            .checks
+            .iter()
+            .filter(|c| c.status == CiStatus::Failure)
+            .map(|c| c.name.as_str())
+            .collect();
+        self.container
+            .notification_service()
+            .notify(
+                Notification::new(
+                    NotificationEvent::CiFailed,
+                    format!("CI Failed: {}", task.title),
+                    format!(
+                        "❌ CI failed on PR #{} for '{}': {}\nBranch: {:?}",
+                        pr_merge.pr_info.number,
+                        task.title,
+                        failing.join(", "),
+                        workspace.branch
+                    ),
+                )
+                .with_task(&task.title, &workspace.branch)
+                .with_url(&pr_merge.pr_info.url),
+            )
+            .await;
+        Ok(())
+    }
+
+    /// Hand the failing checks of the head commit to the agent, once per head
+    /// commit and at most `MAX_CI_FIX_ATTEMPTS` times in a row
+    async fn start_ci_fix(
+        &self,
+        pr_merge: &PrMerge,
+        provider: &dyn GitHostProvider,
+        repo_info: &GitHostRepoInfo,
+        ci: &PrCiStatus,
+    ) -> Result<(), PrMonitorError> {
+        let pool = &self.db.pool;
+        let (fix_sha, attempts) = Merge::get_ci_fix_state(pool, pr_merge.id).await?;
+        if fix_sha.as_deref() == Some(ci.head_sha.as_str()) || attempts >= MAX_CI_FIX_ATTEMPTS {
+            return Ok(());
+        }
+        let Some((workspace, project)) = self.load_project(pr_merge).await? else {
+            return Ok(());
+        };
+        if !project.auto_fix_ci {
+            return Ok(());
+        }
+        let repo = Repo::find_by_id(pool, pr_merge.repo_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        let mut failures = Vec::new();
+        for check in ci.checks.iter().filter(|c| c.status == CiStatus::Failure) {
+            let log = match provider.get_check_log(repo_info, check).await {
+                Ok(log) => log,
+                Err(e) => {
+                    warn!(
+                        "Failed to fetch the log of CI check '{}': {}",
+                        check.name, e
+                    );
+                    None
+                }
+            };
+            failures.push((check.clone(), log));
+        }
+        let prompt = build_ci_fix_prompt(
+            &repo.name,
+            pr_merge.pr_info.number,
+            &pr_merge.pr_info.url,
+            &ci.head_sha,
+            &failures,
+        );
+        let kind = PrFollowUpKind::CiFix {
+            checks: failures.iter().map(|(c, _)| c.name.clone()).collect(),
+        };
+
+        info!(
+            "Handing {} failing CI check(s) of PR #{} to the agent in workspace {}",
+            failures.len(),
+            pr_merge.pr_info.number,
+            workspace.id
+        );
+        if self
+            .container
+            .start_pr_follow_up(&workspace, pr_merge, prompt, kind)
+            .await?
+        {
+            Merge::set_ci_fix_state(pool, pr_merge.id, Some(&ci.head_sha), attempts + 1).await?;
+
+            if let Some(analytics) = &self.analytics {
+                analytics.analytics_service.track_event(
+                    &analytics.user_id,
+                    "pr_ci_fix_started",
+                    Some(json!({
+                        "workspace_id": workspace.id.to_string(),
+                        "project_id": project.id.to_string(),
+                        "git_host": provider.kind(),
+                        "failing_checks": failures.len(),
+                        "attempt": attempts + 1,
+                    })),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// The workspace of a PR and the project it belongs to
+    async fn load_project(
+        &self,
+        pr_merge: &PrMerge,
+    ) -> Result<Option<(Workspace, Project)>, PrMonitorError> {
+        let pool = &self.db.pool;
+        let Some(workspace) = Workspace::find_by_id(pool, pr_merge.workspace_id).await? else {
+            return Ok(None);
+        };
+        let Some(task) = Task::find_by_id(pool, workspace.task_id).await? else {
+            return Ok(None);
+        };
+        Ok(Project::find_by_id(pool, task.project_id)
+            .await?
+            .map(|project| (workspace, project)))
+    }
 }
@@ -1,11 +1,11 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
 use db::models::{
     project::{CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject},
-    project_repo::{CreateProjectRepo, ProjectRepo},
+    project_repo::{CreateProjectRepo, ProjectRepo, SettingsFileMode},
     repo::Repo,
     task::Task,
 };
@@ -18,8 +18,11 @@ use uuid::Uuid;
 use super::{
     file_ranker::FileRanker,
     file_search_cache::{CacheError, FileSearchCache, SearchMode, SearchQuery},
+    git::GitService,
     repo::{RepoError, RepoService},
+    settings_file::{self, RepoSettingsFileStatus},
     share::ShareError,
+    sparse_checkout::{cone_for_repo, in_cone},
 };
 
 #[derive(Debug, Error)]
@@ -77,6 +80,7 @@ impl ProjectService {
         &self,
         pool: &SqlitePool,
         repo_service: &RepoService,
+        git: &GitService,
         payload: CreateProject,
     ) -> Result<Project> {
         // Validate all repository paths and check for duplicates within the payload
@@ -110,16 +114,15 @@ impl ProjectService {
             .await
             .map_err(|e| ProjectServiceError::Project(ProjectError::CreateFailed(e.to_string())))?;
 
-        let mut created_repo: Option<Repo> = None;
+        let mut created_repos: Vec<Repo> = Vec::new();
         for repo in &normalized_repos {
             let repo_entity =
                 Repo::find_or_create(pool, Path::new(&repo.git_repo_path), &repo.display_name)
                     .await?;
             ProjectRepo::create(pool, project.id, repo_entity.id).await?;
-            if created_repo.is_none() {
-                created_repo = Some(repo_entity);
-            }
+            created_repos.push(repo_entity);
         }
+        let created_repo = created_repos.first().cloned();
 
         if normalized_repos.len() == 1
             && let Some(repo) = created_repo
@@ -132,11 +135,30 @@ impl ProjectService {
                     dev_script: None,
                     dev_script_working_dir: None,
                     default_agent_working_dir: Some(repo.name),
+                    auto_review_follow_ups: None,
+                    auto_fix_ci: None,
+                    dev_server_ports: None,
+                    dev_server_ready_pattern: None,
+                    default_executor_profile: None,
+                    mcp_servers: None,
                 },
             )
             .await?;
         }
 
+        for repo in &created_repos {
+            if let Err(e) = self
+                .sync_settings_file(pool, git, project.id, repo, None)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to read the settings file of repository {}: {}",
+                    repo.id,
+                    e
+                );
+            }
+        }
+
         Ok(project)
     }
 
@@ -192,6 +214,7 @@ impl ProjectService {
         &self,
         pool: &SqlitePool,
         repo_service: &RepoService,
+        git: &GitService,
         project_id: Uuid,
         payload: &CreateProjectRepo,
     ) -> Result<Repo> {
@@ -239,9 +262,139 @@ impl ProjectService {
             repository.path.display()
         );
 
+        if let Err(e) = self
+            .sync_settings_file(pool, git, project_id, &repository, None)
+            .await
+        {
+            tracing::warn!(
+                "Failed to read the settings file of repository {}: {}",
+                repository.id,
+                e
+            );
+        }
+
         Ok(repository)
     }
 
+    /// Compare the settings file of `repo` against the local settings. It is
+    /// read from the branch it was last read from, or the checked out one.
+    pub async fn settings_file_status(
+        &self,
+        pool: &SqlitePool,
+        git: &GitService,
+        project_id: Uuid,
+        repo: &Repo,
+    ) -> Result<RepoSettingsFileStatus> {
+        self.load_settings_file(pool, git, project_id, repo, None, false)
+            .await
+    }
+
+    /// Re-read the settings file of `repo` from `branch` (by default the one it
+    /// was last read from) and apply it when the repo file wins
+    pub async fn sync_settings_file(
+        &self,
+        pool: &SqlitePool,
+        git: &GitService,
+        project_id: Uuid,
+        repo: &Repo,
+        branch: Option<&str>,
+    ) -> Result<RepoSettingsFileStatus> {
+        let status = self
+            .load_settings_file(pool, git, project_id, repo, branch, true)
+            .await?;
+        if !status.differences.is_empty() {
+            tracing::info!(
+                "Settings file of repository {} in project {} differs from the local settings in {} key(s)",
+                repo.id,
+                project_id,
+                status.differences.len()
+            );
+        }
+        Ok(status)
+    }
+
+    /// Choose which side wins; choosing the repo file applies it right away
+    pub async fn set_settings_file_mode(
+        &self,
+        pool: &SqlitePool,
+        git: &GitService,
+        project_id: Uuid,
+        repo: &Repo,
+        mode: SettingsFileMode,
+    ) -> Result<RepoSettingsFileStatus> {
+        ProjectRepo::set_settings_file_mode(pool, project_id, repo.id, mode).await?;
+        self.load_settings_file(pool, git, project_id, repo, None, true)
+            .await
+    }
+
+    async fn load_settings_file(
+        &self,
+        pool: &SqlitePool,
+        git: &GitService,
+        project_id: Uuid,
+        repo: &Repo,
+        branch: Option<&str>,
+        sync: bool,
+    ) -> Result<RepoSettingsFileStatus> {
+        let mut project = Project::find_by_id(pool, project_id)
+            .await?
+            .ok_or(ProjectError::ProjectNotFound)?;
+        let mut project_repo = ProjectRepo::find_by_project_and_repo(pool, project_id, repo.id)
+            .await?
+            .ok_or(ProjectServiceError::RepositoryNotFound)?;
+
+        let branch = match branch.or(project_repo.settings_file_branch.as_deref()) {
+            Some(branch) if git.check_branch_exists(&repo.path, branch).unwrap_or(false) => {
+                branch.to_string()
+            }
+            _ => git
+                .get_current_branch(&repo.path)
+                .map_err(|e| ProjectServiceError::GitError(e.to_string()))?,
+        };
+        if sync && project_repo.settings_file_branch.as_deref() != Some(branch.as_str()) {
+            ProjectRepo::set_settings_file_branch(pool, project_id, repo.id, &branch).await?;
+        }
+
+        let mut status = RepoSettingsFileStatus {
+            repo_id: repo.id,
+            branch,
+            file_name: None,
+            mode: project_repo.settings_file_mode,
+            differences: Vec::new(),
+            error: None,
+        };
+        let (name, file) = match settings_file::read(git, &repo.path, &status.branch) {
+            Ok(Some(found)) => found,
+            Ok(None) => return Ok(status),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to read the settings file of repository {}: {}",
+                    repo.id,
+                    e
+                );
+                status.error = Some(e.to_string());
+                return Ok(status);
+            }
+        };
+        status.file_name = Some(name.to_string());
+
+        if sync && project_repo.settings_file_mode == Some(SettingsFileMode::RepoFile) {
+            let (update_project, update_repo) =
+                settings_file::apply(&file, &project, &project_repo);
+            project = Project::update(pool, project_id, &update_project).await?;
+            project_repo = ProjectRepo::update(pool, project_id, repo.id, &update_repo)
+                .await
+                .map_err(|e| match e {
+                    db::models::project_repo::ProjectRepoError::Database(e) => {
+                        ProjectServiceError::Database(e)
+                    }
+                    _ => ProjectServiceError::RepositoryNotFound,
+                })?;
+        }
+        status.differences = settings_file::differences(&file, &project, &project_repo);
+        Ok(status)
+    }
+
     pub async fn delete_repository(
         &self,
         pool: &SqlitePool,
@@ -290,10 +443,33 @@ impl ProjectService {
         Ok(repos)
     }
 
+    /// Sparse-checkout cones of the project's repos, for those that have one.
+    /// The project's default agent working directory stands in for the task's.
+    pub async fn get_sparse_checkout_cones(
+        &self,
+        pool: &SqlitePool,
+        project: &Project,
+    ) -> Result<HashMap<Uuid, Vec<String>>> {
+        let project_repos = ProjectRepo::find_by_project_id_with_names(pool, project.id).await?;
+        Ok(project_repos
+            .into_iter()
+            .filter_map(|pr| {
+                let cone = cone_for_repo(
+                    pr.sparse_checkout_paths.as_deref(),
+                    pr.sparse_checkout_agent_working_dir,
+                    &pr.repo_name,
+                    project.default_agent_working_dir.as_deref(),
+                );
+                (!cone.is_empty()).then_some((pr.repo_id, cone))
+            })
+            .collect())
+    }
+
     pub async fn search_files(
         &self,
         cache: &FileSearchCache,
         repositories: &[Repo],
+        sparse_cones: &HashMap<Uuid, Vec<String>>,
         query: &SearchQuery,
     ) -> Result<Vec<SearchResult>> {
         let query_str = query.q.trim();
@@ -308,9 +484,10 @@ impl ProjectService {
                 let repo_name = repo.name.clone();
                 let repo_path = repo.path.clone();
                 let query = query.clone();
+                let cone = sparse_cones.get(&repo.id).cloned().unwrap_or_default();
                 async move {
                     let results = self
-                        .search_single_repo(cache, &repo_path, &query)
+                        .search_single_repo(cache, &repo_path, &query, &cone)
                         .await
                         .unwrap_or_else(|e| {
                             tracing::warn!("Search failed for repo {}: {}", repo_name, e);
@@ -354,6 +531,7 @@ impl ProjectService {
         cache: &FileSearchCache,
         repo_path: &Path,
         query: &SearchQuery,
+        sparse_paths: &[String],
     ) -> Result<Vec<SearchResult>> {
         let query_str = query.q.trim();
         if query_str.is_empty() {
@@ -361,11 +539,14 @@ impl ProjectService {
         }
 
         // Try cache first
-        match cache.search(repo_path, query_str, query.mode.clone()).await {
+        match cache
+            .search(repo_path, query_str, query.mode.clone(), sparse_paths)
+            .await
+        {
             Ok(results) => Ok(results),
             Err(CacheError::Miss) | Err(CacheError::BuildError(_)) => {
                 // Fall back to filesystem search
-                self.search_files_in_repo(repo_path, query_str, query.mode.clone())
+                self.search_files_in_repo(repo_path, query_str, query.mode.clone(), sparse_paths)
                     .await
             }
         }
@@ -376,6 +557,7 @@ impl ProjectService {
         repo_path: &Path,
         query: &str,
         mode: SearchMode,
+        sparse_paths: &[String],
     ) -> Result<Vec<SearchResult>> {
         if !repo_path.exists() {
             return Err(ProjectServiceError::PathNotFound(repo_path.to_path_buf()));
@@ -426,6 +608,13 @@ impl ProjectService {
             let relative_path = path
                 .strip_prefix(repo_path)
                 .map_err(std::io::Error::other)?;
+            if !in_cone(
+                &relative_path.to_string_lossy(),
+                path.is_file(),
+                sparse_paths,
+            ) {
+                continue;
+            }
             let relative_path_str = relative_path.to_string_lossy().to_lowercase();
 
             let file_name = path
@@ -0,0 +1,342 @@
+//! Named project scripts.
+//!
+//! Projects define scripts such as `db:migrate`, `test` or `lint` that may
+//! depend on each other. Running a script first runs everything it depends on,
+//! in dependency order, each step as its own execution process. Scripts
+//! assigned to a pipeline are its targets: setup runs before the coding agent,
+//! cleanup after it, and verification on demand.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use db::models::{
+    project_repo::ProjectRepoWithName,
+    project_script::{ProjectScript, ScriptPipeline},
+};
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest},
+};
+use thiserror::Error;
+
+const MAX_NAME_LENGTH: usize = 64;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ProjectScriptError {
+    #[error("Unknown script '{0}'")]
+    UnknownScript(String),
+    #[error("Script '{script}' depends on unknown script '{dependency}'")]
+    UnknownDependency { script: String, dependency: String },
+    #[error("Scripts depend on each other in a cycle: {0}")]
+    Cycle(String),
+    #[error("Invalid script name '{0}': use up to 64 letters, digits, ':', '-', '_' or '.'")]
+    InvalidName(String),
+    #[error("A script named '{0}' already exists")]
+    DuplicateName(String),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+pub fn validate_name(name: &str) -> Result<(), ProjectScriptError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ':' | '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(ProjectScriptError::InvalidName(name.to_string()))
+    }
+}
+
+/// Check that every dependency of the `(name, depends_on)` pairs exists and
+/// that no script depends on itself, directly or transitively
+pub fn check_dependencies<'a>(
+    scripts: impl IntoIterator<Item = (&'a str, &'a [String])>,
+) -> Result<(), ProjectScriptError> {
+    let mut graph = BTreeMap::new();
+    for (name, depends_on) in scripts {
+        if graph.insert(name, depends_on).is_some() {
+            return Err(ProjectScriptError::DuplicateName(name.to_string()));
+        }
+    }
+    let targets: Vec<&str> = graph.keys().copied().collect();
+    topological_order(&graph, &targets).map(|_| ())
+}
+
+/// `targets` preceded by everything they depend on, each script once
+pub fn resolve_order<'a>(
+    scripts: &'a [ProjectScript],
+    targets: &[&str],
+) -> Result<Vec<&'a ProjectScript>, ProjectScriptError> {
+    let by_name: HashMap<&str, &ProjectScript> =
+        scripts.iter().map(|s| (s.name.as_str(), s)).collect();
+    let graph: BTreeMap<&str, &[String]> = scripts
+        .iter()
+        .map(|s| (s.name.as_str(), s.depends_on.as_slice()))
+        .collect();
+    Ok(topological_order(&graph, targets)?
+        .into_iter()
+        .map(|name| by_name[name])
+        .collect())
+}
+
+/// Steps of `pipeline`: its scripts by name, with their dependencies first
+pub fn pipeline_steps(
+    scripts: &[ProjectScript],
+    pipeline: ScriptPipeline,
+) -> Result<Vec<&ProjectScript>, ProjectScriptError> {
+    let targets: Vec<&str> = scripts
+        .iter()
+        .filter(|s| s.pipeline == Some(pipeline))
+        .map(|s| s.name.as_str())
+        .collect();
+    resolve_order(scripts, &targets)
+}
+
+/// Chain the steps of `pipeline` in front of `next`; `None` without steps and no `next`
+pub fn pipeline_action(
+    scripts: &[ProjectScript],
+    repos: &[ProjectRepoWithName],
+    pipeline: ScriptPipeline,
+    next: Option<ExecutorAction>,
+) -> Result<Option<ExecutorAction>, ProjectScriptError> {
+    let context = match pipeline {
+        ScriptPipeline::Setup => ScriptContext::SetupScript,
+        ScriptPipeline::Cleanup => ScriptContext::CleanupScript,
+        ScriptPipeline::Verification => ScriptContext::NamedScript,
+    };
+    let steps = pipeline_steps(scripts, pipeline)?;
+    Ok(chain(&steps, repos, context, next))
+}
+
+/// Run `name` on demand, after the scripts it depends on
+pub fn run_action(
+    scripts: &[ProjectScript],
+    repos: &[ProjectRepoWithName],
+    name: &str,
+) -> Result<ExecutorAction, ProjectScriptError> {
+    let steps = resolve_order(scripts, &[name])?;
+    chain(&steps, repos, ScriptContext::NamedScript, None)
+        .ok_or_else(|| ProjectScriptError::UnknownScript(name.to_string()))
+}
+
+pub fn script_request(
+    script: &ProjectScript,
+    repos: &[ProjectRepoWithName],
+    context: ScriptContext,
+) -> ScriptRequest {
+    let repo_dir = script.repo_id.and_then(|repo_id| {
+        let repo = repos.iter().find(|r| r.repo_id == repo_id);
+        if repo.is_none() {
+            tracing::warn!(
+                "Repository of script '{}' is not part of the project, using the workspace root",
+                script.name
+            );
+        }
+        repo.map(|r| PathBuf::from(&r.repo_name))
+    });
+    let sub_dir = script.working_dir.as_deref().filter(|d| !d.is_empty());
+    let working_dir = match (repo_dir, sub_dir) {
+        (Some(repo_dir), Some(sub_dir)) => Some(repo_dir.join(sub_dir)),
+        (repo_dir, sub_dir) => repo_dir.or_else(|| sub_dir.map(PathBuf::from)),
+    };
+
+    ScriptRequest {
+        script: script.script.clone(),
+        language: script.language,
+        context,
+        working_dir: working_dir.map(|d| d.to_string_lossy().into_owned()),
+        script_name: Some(script.name.clone()),
+        env: script
+            .env
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+        timeout_seconds: script
+            .timeout_seconds
+            .and_then(|t| u64::try_from(t).ok())
+            .filter(|t| *t > 0),
+    }
+}
+
+fn chain(
+    steps: &[&ProjectScript],
+    repos: &[ProjectRepoWithName],
+    context: ScriptContext,
+    next: Option<ExecutorAction>,
+) -> Option<ExecutorAction> {
+    steps.iter().rev().fold(next, |next, script| {
+        Some(ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(script_request(script, repos, context.clone())),
+            next.map(Box::new),
+        ))
+    })
+}
+
+fn topological_order<'a>(
+    graph: &BTreeMap<&'a str, &'a [String]>,
+    targets: &[&str],
+) -> Result<Vec<&'a str>, ProjectScriptError> {
+    let mut visits: HashMap<&str, Visit> = HashMap::new();
+    let mut order = Vec::new();
+    for target in targets {
+        let (name, _) = graph
+            .get_key_value(*target)
+            .ok_or_else(|| ProjectScriptError::UnknownScript(target.to_string()))?;
+        let mut path = Vec::new();
+        visit(graph, name, &mut visits, &mut path, &mut order)?;
+    }
+    Ok(order)
+}
+
+fn visit<'a>(
+    graph: &BTreeMap<&'a str, &'a [String]>,
+    name: &'a str,
+    visits: &mut HashMap<&'a str, Visit>,
+    path: &mut Vec<&'a str>,
+    order: &mut Vec<&'a str>,
+) -> Result<(), ProjectScriptError> {
+    match visits.get(name) {
+        Some(Visit::Done) => return Ok(()),
+        Some(Visit::InProgress) => {
+            let start = path.iter().position(|n| *n == name).unwrap_or_default();
+            let mut cycle = path[start..].to_vec();
+            cycle.push(name);
+            return Err(ProjectScriptError::Cycle(cycle.join(" -> ")));
+        }
+        None => {}
+    }
+
+    visits.insert(name, Visit::InProgress);
+    path.push(name);
+    for dependency in graph[name] {
+        let (dependency, _) = graph.get_key_value(dependency.as_str()).ok_or_else(|| {
+            ProjectScriptError::UnknownDependency {
+                script: name.to_string(),
+                dependency: dependency.clone(),
+            }
+        })?;
+        visit(graph, dependency, visits, path, order)?;
+    }
+    path.pop();
+    visits.insert(name, Visit::Done);
+    order.push(name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use executors::actions::script::ScriptRequestLanguage;
+    use sqlx::types::Json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn script(name: &str, depends_on: &[&str], pipeline: Option<ScriptPipeline>) -> ProjectScript {
+        ProjectScript {
+            id: Uuid::new_v4(),
+            project_id: Uuid::nil(),
+            repo_id: None,
+            name: name.to_string(),
+            script: format!("echo {name}"),
+            language: ScriptRequestLanguage::Bash,
+            working_dir: None,
+            env: Json(BTreeMap::new()),
+            timeout_seconds: None,
+            depends_on: Json(depends_on.iter().map(|d| d.to_string()).collect()),
+            pipeline,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn names(steps: &[&ProjectScript]) -> Vec<String> {
+        steps.iter().map(|s| s.name.clone()).collect()
+    }
+
+    #[test]
+    fn resolves_dependencies_once_in_order() {
+        let scripts = vec![
+            script("test", &["db:migrate", "build"], None),
+            script("build", &["install"], None),
+            script("db:migrate", &["install"], None),
+            script("install", &[], None),
+        ];
+        let steps = resolve_order(&scripts, &["test"]).unwrap();
+        assert_eq!(names(&steps), ["install", "db:migrate", "build", "test"]);
+    }
+
+    #[test]
+    fn rejects_cycles_and_unknown_dependencies() {
+        let cyclic = [
+            script("a", &["b"], None),
+            script("b", &["c"], None),
+            script("c", &["a"], None),
+        ];
+        assert_eq!(
+            check_dependencies(
+                cyclic
+                    .iter()
+                    .map(|s| (s.name.as_str(), s.depends_on.as_slice()))
+            ),
+            Err(ProjectScriptError::Cycle("a -> b -> c -> a".to_string()))
+        );
+
+        let dangling = [script("lint", &["install"], None)];
+        assert_eq!(
+            resolve_order(&dangling, &["lint"]).map(|s| names(&s)),
+            Err(ProjectScriptError::UnknownDependency {
+                script: "lint".to_string(),
+                dependency: "install".to_string(),
+            })
+        );
+        assert_eq!(
+            resolve_order(&dangling, &["missing"]).map(|s| names(&s)),
+            Err(ProjectScriptError::UnknownScript("missing".to_string()))
+        );
+    }
+
+    #[test]
+    fn pipeline_chains_steps_before_next_action() {
+        let scripts = vec![
+            script("install", &[], None),
+            script("lint", &["install"], Some(ScriptPipeline::Verification)),
+            script("test", &["install"], Some(ScriptPipeline::Verification)),
+            script("seed", &[], Some(ScriptPipeline::Setup)),
+        ];
+        let steps = pipeline_steps(&scripts, ScriptPipeline::Verification).unwrap();
+        assert_eq!(names(&steps), ["install", "lint", "test"]);
+
+        let action = pipeline_action(&scripts, &[], ScriptPipeline::Setup, None)
+            .unwrap()
+            .unwrap();
+        let ExecutorActionType::ScriptRequest(request) = action.typ() else {
+            panic!("expected a script request");
+        };
+        assert_eq!(request.script_name.as_deref(), Some("seed"));
+        assert_eq!(request.context, ScriptContext::SetupScript);
+        assert!(action.next_action().is_none());
+        assert!(
+            pipeline_action(&scripts, &[], ScriptPipeline::Cleanup, None)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn validates_names() {
+        assert!(validate_name("db:migrate").is_ok());
+        assert!(validate_name("storybook.build_v2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("run tests").is_err());
+    }
+}
@@ -76,6 +76,11 @@ impl QueuedMessageService {
         self.queue.contains_key(&session_id)
     }
 
+    /// Number of sessions with a queued message
+    pub fn queued_count(&self) -> usize {
+        self.queue.len()
+    }
+
     /// Get queue status for frontend display
     pub fn get_status(&self, session_id: Uuid) -> QueueStatus {
         match self.get_queued(session_id) {
@@ -0,0 +1,269 @@
+use std::path::Path;
+
+use db::models::{
+    repo::Repo,
+    review_comment::{ReviewComment, ReviewSide},
+};
+use git2::{DiffOptions, Patch};
+use sqlx::SqlitePool;
+use thiserror::Error;
+
+use crate::services::git::{GitService, GitServiceError};
+
+#[derive(Debug, Error)]
+pub enum ReviewCommentError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error("Line range {0}-{1} is not part of the file")]
+    InvalidRange(i64, i64),
+}
+
+/// Lines `start..=end` (1-based) of `content`, or `None` if out of range.
+pub fn extract_lines(content: &str, start: i64, end: i64) -> Option<String> {
+    if start < 1 || end < start {
+        return None;
+    }
+    let lines: Vec<&str> = content.lines().collect();
+    let (start, end) = (start as usize, end as usize);
+    if end > lines.len() {
+        return None;
+    }
+    Some(lines[start - 1..end].join("\n"))
+}
+
+/// Where the commented range `start..=end` of `old` ended up in `new`.
+///
+/// Ranges untouched by the diff are shifted by the lines added or removed
+/// above them. Ranges that were edited are looked up by their text (closest
+/// match wins); `None` means they are gone and the comment is outdated.
+pub fn remap_lines(
+    old: &str,
+    new: &str,
+    start: i64,
+    end: i64,
+    anchor_text: Option<&str>,
+) -> Option<(i64, i64)> {
+    let mut opts = DiffOptions::new();
+    opts.context_lines(0);
+    let patch =
+        Patch::from_buffers(old.as_bytes(), None, new.as_bytes(), None, Some(&mut opts)).ok()?;
+
+    let mut delta: i64 = 0;
+    let mut touched = false;
+    for idx in 0..patch.num_hunks() {
+        let Ok((hunk, _)) = patch.hunk(idx) else {
+            return None;
+        };
+        let old_start = hunk.old_start() as i64;
+        let old_lines = hunk.old_lines() as i64;
+        let new_lines = hunk.new_lines() as i64;
+        if old_lines == 0 {
+            // Pure insertion after line `old_start`
+            if old_start < start {
+                delta += new_lines;
+                continue;
+            }
+            if old_start >= end {
+                break;
+            }
+        } else {
+            let old_last = old_start + old_lines - 1;
+            if old_last < start {
+                delta += new_lines - old_lines;
+                continue;
+            }
+            if old_start > end {
+                break;
+            }
+        }
+        touched = true;
+        break;
+    }
+
+    let expected = start + delta;
+    if !touched {
+        return Some((expected, end + delta));
+    }
+    find_lines(new, anchor_text?, expected)
+}
+
+/// Find the block of `needle` lines in `haystack` starting closest to `near`.
+/// Trailing whitespace is ignored.
+fn find_lines(haystack: &str, needle: &str, near: i64) -> Option<(i64, i64)> {
+    let needle: Vec<&str> = needle.lines().map(str::trim_end).collect();
+    if needle.is_empty() || needle.iter().all(|line| line.is_empty()) {
+        return None;
+    }
+    let lines: Vec<&str> = haystack.lines().map(str::trim_end).collect();
+    let len = needle.len();
+    (0..lines.len().saturating_sub(len - 1))
+        .filter(|&i| lines[i..i + len] == needle[..])
+        .map(|i| i as i64 + 1)
+        .min_by_key(|&line| (line - near).abs())
+        .map(|line| (line, line + len as i64 - 1))
+}
+
+/// Move unresolved comments on the workspace side to the current HEAD of their
+/// repo's worktree, marking them outdated when their lines were changed.
+/// Comments on the base side and outdated comments are left as they are.
+pub async fn reanchor_comments(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace_root: &Path,
+    repos: &[Repo],
+    comments: &mut [ReviewComment],
+) -> Result<(), ReviewCommentError> {
+    for comment in comments.iter_mut() {
+        if comment.side != ReviewSide::New || comment.outdated || comment.is_resolved() {
+            continue;
+        }
+        let Some(repo) = repos.iter().find(|r| r.id == comment.repo_id) else {
+            continue;
+        };
+        let worktree_path = workspace_root.join(&repo.name);
+        let Ok(head) = git.get_head_info(&worktree_path) else {
+            continue;
+        };
+        if head.oid == comment.anchor_commit {
+            continue;
+        }
+
+        // The anchor commit may be gone after a reset or rebase
+        let read = |commit: &str| {
+            git.read_file_at_commit(&worktree_path, commit, &comment.file_path)
+                .ok()
+                .flatten()
+        };
+        let (old, new) = (read(&comment.anchor_commit), read(&head.oid));
+        let remapped = match (old, new) {
+            (Some(old), Some(new)) => remap_lines(
+                &old,
+                &new,
+                comment.line_start,
+                comment.line_end,
+                comment.anchor_text.as_deref(),
+            ),
+            _ => None,
+        };
+
+        match remapped {
+            Some((line_start, line_end)) => {
+                ReviewComment::update_anchor(
+                    pool, comment.id, &head.oid, line_start, line_end, false,
+                )
+                .await?;
+                comment.anchor_commit = head.oid;
+                comment.line_start = line_start;
+                comment.line_end = line_end;
+            }
+            None => {
+                ReviewComment::update_anchor(
+                    pool,
+                    comment.id,
+                    &comment.anchor_commit,
+                    comment.line_start,
+                    comment.line_end,
+                    true,
+                )
+                .await?;
+                comment.outdated = true;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn fence_for(code: &str) -> String {
+    let mut fence = "```".to_string();
+    while code.contains(&fence) {
+        fence.push('`');
+    }
+    fence
+}
+
+/// Build one follow-up prompt from review comments, each paired with the name
+/// of its repository.
+pub fn build_review_prompt(comments: &[(&str, &ReviewComment)]) -> String {
+    let mut prompt = String::from(
+        "Your changes were reviewed and the comments below were left on specific lines. \
+Address each of them in the code.\n\n\
+Rules:\n\
+- Make the requested changes; if you disagree with a comment, leave the code as is and explain why in your final message.\n\
+- Comments marked \"original version\" refer to the file as it was before your changes.\n\
+- End with a short summary of what you changed per comment.\n\n## Comments\n",
+    );
+    for (repo_name, comment) in comments {
+        let lines = if comment.line_start == comment.line_end {
+            format!("line {}", comment.line_start)
+        } else {
+            format!("lines {}-{}", comment.line_start, comment.line_end)
+        };
+        let side = match comment.side {
+            ReviewSide::Old => ", original version",
+            ReviewSide::New => "",
+        };
+        prompt.push_str(&format!(
+            "\n### `{repo_name}/{}` ({lines}{side})\n",
+            comment.file_path
+        ));
+        if comment.outdated {
+            prompt.push_str("_These lines have changed since the comment was left._\n");
+        }
+        if let Some(code) = comment
+            .anchor_text
+            .as_deref()
+            .filter(|c| !c.trim().is_empty())
+        {
+            let fence = fence_for(code);
+            prompt.push_str(&format!("{fence}\n{}\n{fence}\n", code.trim_end()));
+        }
+        prompt.push_str(comment.body.trim());
+        prompt.push('\n');
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n";
+
+    #[test]
+    fn untouched_lines_shift_with_edits_above() {
+        let new = "// header\n// more\nfn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n";
+        assert_eq!(
+            remap_lines(OLD, new, 3, 4, Some("fn c() {}\nfn d() {}")),
+            Some((5, 6))
+        );
+
+        let new = "fn b() {}\nfn c() {}\nfn d() {}\n";
+        assert_eq!(remap_lines(OLD, new, 3, 3, Some("fn c() {}")), Some((2, 2)));
+
+        let new = "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\nfn e() {}\n";
+        assert_eq!(remap_lines(OLD, new, 2, 3, None), Some((2, 3)));
+    }
+
+    #[test]
+    fn edited_lines_are_found_by_text_or_outdated() {
+        // b() moved below d(); the range was touched but the text still exists
+        let new = "fn a() {}\nfn c() {}\nfn d() {}\nfn b() {}\n";
+        assert_eq!(remap_lines(OLD, new, 2, 2, Some("fn b() {}")), Some((4, 4)));
+
+        let new = "fn a() {}\nfn b(x: u8) {}\nfn c() {}\nfn d() {}\n";
+        assert_eq!(remap_lines(OLD, new, 2, 2, Some("fn b() {}")), None);
+        assert_eq!(remap_lines(OLD, new, 2, 2, None), None);
+    }
+
+    #[test]
+    fn extract_lines_checks_bounds() {
+        assert_eq!(
+            extract_lines(OLD, 2, 3).as_deref(),
+            Some("fn b() {}\nfn c() {}")
+        );
+        assert_eq!(extract_lines(OLD, 4, 5), None);
+        assert_eq!(extract_lines(OLD, 0, 1), None);
+    }
+}
@@ -0,0 +1,339 @@
+//! Admission control for coding agent runs.
+//!
+//! A coding agent run starts right away when the configured concurrency
+//! limits allow it and nothing eligible is waiting; otherwise its execution
+//! process is created as `queued` and added to the persistent execution queue.
+//! Whenever a slot may have freed up, the container dispatches the next
+//! runnable entry: highest priority first, then the project with the fewest
+//! running agents, then the oldest entry.
+
+use std::{cmp::Reverse, collections::HashMap, sync::Arc, time::Duration};
+
+use db::{
+    DBService,
+    models::{
+        execution_process::{ExecutionProcess, RunningAgentSlot},
+        execution_queue::{QueuedExecution, UpdateQueuedExecution},
+    },
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, MutexGuard, Notify, RwLock};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::config::Config;
+
+/// How often the queue is checked when nothing wakes the dispatcher earlier
+pub const DISPATCH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Caps on coding agents running at the same time; unset means unlimited
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct ConcurrencyLimits {
+    #[serde(default)]
+    pub max_agents: Option<u32>,
+    #[serde(default)]
+    pub max_agents_per_project: Option<u32>,
+    /// Keyed by executor, e.g. `CLAUDE_CODE`
+    #[serde(default)]
+    pub max_agents_per_executor: HashMap<String, u32>,
+}
+
+impl ConcurrencyLimits {
+    /// Whether one more agent for `executor` in `project_id` fits next to `running`
+    pub fn admits(
+        &self,
+        running: &[RunningAgentSlot],
+        executor: Option<&str>,
+        project_id: Uuid,
+    ) -> bool {
+        let under = |limit: Option<u32>, count: usize| limit.is_none_or(|l| count < l as usize);
+
+        under(self.max_agents, running.len())
+            && under(
+                self.max_agents_per_project,
+                running
+                    .iter()
+                    .filter(|r| r.project_id == project_id)
+                    .count(),
+            )
+            && under(
+                executor.and_then(|e| self.max_agents_per_executor.get(e).copied()),
+                running
+                    .iter()
+                    .filter(|r| r.executor.as_deref() == executor)
+                    .count(),
+            )
+    }
+}
+
+/// The queued run to start next, if any fits the limits
+pub fn pick_next<'a>(
+    limits: &ConcurrencyLimits,
+    running: &[RunningAgentSlot],
+    queue: &'a [QueuedExecution],
+) -> Option<&'a QueuedExecution> {
+    queue
+        .iter()
+        .filter(|q| limits.admits(running, q.executor.as_deref(), q.project_id))
+        .min_by_key(|q| {
+            let in_project = running
+                .iter()
+                .filter(|r| r.project_id == q.project_id)
+                .count();
+            (Reverse(q.priority), in_project, q.sequence)
+        })
+}
+
+/// New `(id, priority)` order after moving `id`. A `position` wins over
+/// `priority`: the run takes a priority between its new neighbours so the
+/// queue stays sorted.
+pub fn reorder(
+    mut order: Vec<(Uuid, i64)>,
+    id: Uuid,
+    update: &UpdateQueuedExecution,
+) -> Option<Vec<(Uuid, i64)>> {
+    let index = order.iter().position(|(q, _)| *q == id)?;
+    let (_, mut priority) = order.remove(index);
+    if let Some(p) = update.priority {
+        priority = p;
+    }
+    let position = match update.position {
+        Some(position) => {
+            let position = position.min(order.len());
+            if position > 0 {
+                priority = priority.min(order[position - 1].1);
+            }
+            if let Some((_, below)) = order.get(position) {
+                priority = priority.max(*below);
+            }
+            position
+        }
+        None if update.priority.is_some() => {
+            order.iter().take_while(|(_, p)| *p >= priority).count()
+        }
+        None => index,
+    };
+    order.insert(position, (id, priority));
+    Some(order)
+}
+
+#[derive(Clone)]
+pub struct ExecutionScheduler {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+    /// Held while deciding whether to start or queue, and while dispatching
+    lock: Arc<Mutex<()>>,
+    wake: Arc<Notify>,
+}
+
+impl ExecutionScheduler {
+    pub fn new(db: DBService, config: Arc<RwLock<Config>>) -> Self {
+        Self {
+            db,
+            config,
+            lock: Arc::new(Mutex::new(())),
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    pub async fn lock(&self) -> MutexGuard<'_, ()> {
+        self.lock.lock().await
+    }
+
+    /// Ask the dispatcher to look at the queue
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    /// Wait for `wake` or the dispatch interval
+    pub async fn wait(&self) {
+        let _ = tokio::time::timeout(DISPATCH_INTERVAL, self.wake.notified()).await;
+    }
+
+    async fn limits(&self) -> ConcurrencyLimits {
+        self.config.read().await.concurrency.clone()
+    }
+
+    /// Whether a new run may start now instead of queueing. Runs that are
+    /// already waiting and could start go first. Call with `lock` held.
+    pub async fn can_start_now(
+        &self,
+        executor: Option<&str>,
+        project_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let limits = self.limits().await;
+        let running = ExecutionProcess::find_running_agent_slots(&self.db.pool).await?;
+        if !limits.admits(&running, executor, project_id) {
+            return Ok(false);
+        }
+        let queue = QueuedExecution::find_all(&self.db.pool).await?;
+        Ok(pick_next(&limits, &running, &queue).is_none())
+    }
+
+    /// The execution process to start next. Call with `lock` held.
+    pub async fn next_ready(&self) -> Result<Option<Uuid>, sqlx::Error> {
+        let queue = QueuedExecution::find_all(&self.db.pool).await?;
+        if queue.is_empty() {
+            return Ok(None);
+        }
+        let limits = self.limits().await;
+        let running = ExecutionProcess::find_running_agent_slots(&self.db.pool).await?;
+        Ok(pick_next(&limits, &running, &queue).map(|q| q.execution_process_id))
+    }
+
+    /// Change a queued run's priority or position; `false` when it is not queued
+    pub async fn reorder(
+        &self,
+        execution_process_id: Uuid,
+        update: &UpdateQueuedExecution,
+    ) -> Result<bool, sqlx::Error> {
+        let guard = self.lock().await;
+        let queue = QueuedExecution::find_all(&self.db.pool).await?;
+        let order = queue
+            .iter()
+            .map(|q| (q.execution_process_id, q.priority))
+            .collect();
+        let Some(order) = reorder(order, execution_process_id, update) else {
+            return Ok(false);
+        };
+        let mut tx = self.db.pool.begin().await?;
+        for (sequence, (id, priority)) in order.into_iter().enumerate() {
+            QueuedExecution::set_order(&mut *tx, id, priority, sequence as i64 + 1).await?;
+        }
+        tx.commit().await?;
+        drop(guard);
+        self.wake();
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn slot(executor: &str, project_id: Uuid) -> RunningAgentSlot {
+        RunningAgentSlot {
+            executor: Some(executor.to_string()),
+            project_id,
+        }
+    }
+
+    fn queued(executor: &str, project_id: Uuid, priority: i64, sequence: i64) -> QueuedExecution {
+        QueuedExecution {
+            execution_process_id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            task_title: String::new(),
+            project_id,
+            executor: Some(executor.to_string()),
+            priority,
+            sequence,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn limits_cap_global_project_and_executor() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let running = vec![slot("CLAUDE_CODE", a), slot("CODEX", a)];
+
+        assert!(ConcurrencyLimits::default().admits(&running, Some("CLAUDE_CODE"), a));
+
+        let global = ConcurrencyLimits {
+            max_agents: Some(2),
+            ..Default::default()
+        };
+        assert!(!global.admits(&running, Some("AMP"), b));
+
+        let per_project = ConcurrencyLimits {
+            max_agents_per_project: Some(2),
+            ..Default::default()
+        };
+        assert!(!per_project.admits(&running, Some("AMP"), a));
+        assert!(per_project.admits(&running, Some("AMP"), b));
+
+        let per_executor = ConcurrencyLimits {
+            max_agents_per_executor: HashMap::from([("CODEX".to_string(), 1)]),
+            ..Default::default()
+        };
+        assert!(!per_executor.admits(&running, Some("CODEX"), b));
+        assert!(per_executor.admits(&running, Some("CLAUDE_CODE"), b));
+    }
+
+    #[test]
+    fn pick_prefers_priority_then_quiet_projects_then_age() {
+        let (busy, quiet) = (Uuid::new_v4(), Uuid::new_v4());
+        let running = vec![slot("CLAUDE_CODE", busy)];
+        let limits = ConcurrencyLimits::default();
+
+        let queue = vec![
+            queued("CLAUDE_CODE", busy, 0, 1),
+            queued("CLAUDE_CODE", quiet, 0, 2),
+        ];
+        let next = pick_next(&limits, &running, &queue).unwrap();
+        assert_eq!(next.execution_process_id, queue[1].execution_process_id);
+
+        let queue = vec![
+            queued("CLAUDE_CODE", busy, 5, 3),
+            queued("CLAUDE_CODE", quiet, 0, 2),
+        ];
+        let next = pick_next(&limits, &running, &queue).unwrap();
+        assert_eq!(next.execution_process_id, queue[0].execution_process_id);
+    }
+
+    #[test]
+    fn pick_skips_runs_over_their_limit() {
+        let project = Uuid::new_v4();
+        let running = vec![slot("CODEX", project)];
+        let limits = ConcurrencyLimits {
+            max_agents_per_executor: HashMap::from([("CODEX".to_string(), 1)]),
+            ..Default::default()
+        };
+        let queue = vec![queued("CODEX", project, 9, 1), queued("AMP", project, 0, 2)];
+        let next = pick_next(&limits, &running, &queue).unwrap();
+        assert_eq!(next.execution_process_id, queue[1].execution_process_id);
+    }
+
+    #[test]
+    fn reorder_keeps_queue_sorted() {
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let order = vec![(ids[0], 2), (ids[1], 1), (ids[2], 0), (ids[3], 0)];
+
+        let moved = reorder(
+            order.clone(),
+            ids[3],
+            &UpdateQueuedExecution {
+                priority: None,
+                position: Some(0),
+            },
+        )
+        .unwrap();
+        assert_eq!(moved[0], (ids[3], 2));
+
+        let moved = reorder(
+            order.clone(),
+            ids[0],
+            &UpdateQueuedExecution {
+                priority: Some(0),
+                position: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(moved[3], (ids[0], 0));
+
+        assert!(
+            reorder(
+                order,
+                Uuid::new_v4(),
+                &UpdateQueuedExecution {
+                    priority: Some(1),
+                    position: None,
+                },
+            )
+            .is_none()
+        );
+    }
+}
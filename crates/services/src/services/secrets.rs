@@ -0,0 +1,405 @@
+//! Encrypted secrets injected into the environment of agents and scripts.
+//!
+//! Values are encrypted with AES-256-GCM under a key kept in the macOS
+//! Keychain or in a key file only the current user can read. They are
+//! decrypted when an execution process is spawned and never returned by the
+//! API. Output of the processes they are injected into is masked.
+
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, KeyInit},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use db::models::secret::{CreateSecret, EncryptedSecret, Secret};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+const MAX_NAME_LENGTH: usize = 128;
+
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(
+        "Invalid secret name '{0}': use up to 128 letters, digits or '_', not starting with a digit"
+    )]
+    InvalidName(String),
+    #[error("Secret names starting with VK_ are reserved")]
+    ReservedName,
+    #[error("A secret named '{0}' already exists in this scope")]
+    DuplicateName(String),
+    #[error("Secret value is empty")]
+    EmptyValue,
+    #[error("A secret belongs to a project or a repository, not both")]
+    AmbiguousScope,
+    #[error("Secret not found")]
+    NotFound,
+    #[error("Secrets key unavailable: {0}")]
+    Key(#[from] std::io::Error),
+    #[error("Failed to encrypt secret")]
+    Encrypt,
+    #[error("Failed to decrypt secret '{0}'; was the secrets key replaced?")]
+    Decrypt(String),
+}
+
+pub fn validate_name(name: &str) -> Result<(), SecretError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LENGTH
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(SecretError::InvalidName(name.to_string()));
+    }
+    if name.to_ascii_uppercase().starts_with("VK_") {
+        return Err(SecretError::ReservedName);
+    }
+    Ok(())
+}
+
+/// Stores secrets encrypted and resolves them for execution processes.
+/// The key is loaded, or created, on first use.
+#[derive(Clone)]
+pub struct SecretsService {
+    backend: Arc<KeyBackend>,
+    cipher: Arc<OnceCell<Aes256Gcm>>,
+}
+
+impl SecretsService {
+    pub fn new(key_path: PathBuf) -> Self {
+        Self {
+            backend: Arc::new(KeyBackend::detect(key_path)),
+            cipher: Arc::new(OnceCell::new()),
+        }
+    }
+
+    async fn cipher(&self) -> Result<&Aes256Gcm, SecretError> {
+        self.cipher
+            .get_or_try_init(|| async {
+                let key = self.backend.load_or_create().await?;
+                Ok::<_, SecretError>(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+            })
+            .await
+    }
+
+    /// The nonce followed by the ciphertext
+    async fn encrypt(&self, value: &str) -> Result<Vec<u8>, SecretError> {
+        let nonce: [u8; NONCE_LEN] = rand::random();
+        let ciphertext = self
+            .cipher()
+            .await?
+            .encrypt(Nonce::from_slice(&nonce), value.as_bytes())
+            .map_err(|_| SecretError::Encrypt)?;
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    async fn decrypt(&self, name: &str, data: &[u8]) -> Result<String, SecretError> {
+        let decrypt_error = || SecretError::Decrypt(name.to_string());
+        if data.len() < NONCE_LEN {
+            return Err(decrypt_error());
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher()
+            .await?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| decrypt_error())?;
+        String::from_utf8(plaintext).map_err(|_| decrypt_error())
+    }
+
+    pub async fn list(
+        &self,
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+        repo_id: Option<Uuid>,
+    ) -> Result<Vec<Secret>, SecretError> {
+        if project_id.is_some() && repo_id.is_some() {
+            return Err(SecretError::AmbiguousScope);
+        }
+        Ok(Secret::find_by_scope(pool, project_id, repo_id).await?)
+    }
+
+    pub async fn create(
+        &self,
+        pool: &SqlitePool,
+        data: &CreateSecret,
+    ) -> Result<Secret, SecretError> {
+        validate_name(&data.name)?;
+        if data.value.is_empty() {
+            return Err(SecretError::EmptyValue);
+        }
+        if data.project_id.is_some() && data.repo_id.is_some() {
+            return Err(SecretError::AmbiguousScope);
+        }
+        if Secret::find_by_name(pool, &data.name, data.project_id, data.repo_id)
+            .await?
+            .is_some()
+        {
+            return Err(SecretError::DuplicateName(data.name.clone()));
+        }
+        let encrypted = self.encrypt(&data.value).await?;
+        Ok(Secret::create(pool, data, &encrypted).await?)
+    }
+
+    pub async fn update(
+        &self,
+        pool: &SqlitePool,
+        id: Uuid,
+        value: &str,
+    ) -> Result<Secret, SecretError> {
+        if value.is_empty() {
+            return Err(SecretError::EmptyValue);
+        }
+        let encrypted = self.encrypt(value).await?;
+        Secret::update_value(pool, id, &encrypted)
+            .await?
+            .ok_or(SecretError::NotFound)
+    }
+
+    pub async fn delete(&self, pool: &SqlitePool, id: Uuid) -> Result<(), SecretError> {
+        if Secret::delete(pool, id).await? == 0 {
+            return Err(SecretError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Decrypted value of the project's secret `name`, else the global one
+    pub async fn resolve_named(
+        &self,
+        pool: &SqlitePool,
+        name: &str,
+        project_id: Option<Uuid>,
+    ) -> Result<Option<String>, SecretError> {
+        match EncryptedSecret::find_by_name(pool, name, project_id).await? {
+            Some(secret) => Ok(Some(
+                self.decrypt(&secret.name, &secret.encrypted_value).await?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Create the global secret `name`, or replace its value
+    pub async fn upsert_global(
+        &self,
+        pool: &SqlitePool,
+        name: &str,
+        value: &str,
+    ) -> Result<Secret, SecretError> {
+        match Secret::find_by_name(pool, name, None, None).await? {
+            Some(existing) => self.update(pool, existing.id, value).await,
+            None => {
+                let data = CreateSecret {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                    project_id: None,
+                    repo_id: None,
+                };
+                self.create(pool, &data).await
+            }
+        }
+    }
+
+    /// Decrypted secrets for an execution in `workspace_id`. Repository
+    /// secrets override project secrets, which override global ones.
+    pub async fn resolve_for_workspace(
+        &self,
+        pool: &SqlitePool,
+        project_id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<BTreeMap<String, String>, SecretError> {
+        let mut secrets =
+            EncryptedSecret::find_for_workspace(pool, project_id, workspace_id).await?;
+        if secrets.is_empty() {
+            return Ok(BTreeMap::new());
+        }
+        secrets.sort_by_key(|s| (precedence(s), s.repo_id));
+
+        let mut resolved = BTreeMap::new();
+        for secret in secrets {
+            let value = self.decrypt(&secret.name, &secret.encrypted_value).await?;
+            resolved.insert(secret.name, value);
+        }
+        Ok(resolved)
+    }
+}
+
+fn precedence(secret: &EncryptedSecret) -> u8 {
+    match (secret.project_id, secret.repo_id) {
+        (None, None) => 0,
+        (Some(_), _) => 1,
+        (None, Some(_)) => 2,
+    }
+}
+
+enum KeyBackend {
+    File(PathBuf),
+    #[cfg(target_os = "macos")]
+    Keychain,
+}
+
+impl KeyBackend {
+    fn detect(path: PathBuf) -> Self {
+        #[cfg(target_os = "macos")]
+        {
+            let use_file = match std::env::var("SECRETS_KEY_BACKEND") {
+                Ok(v) if v.eq_ignore_ascii_case("file") => true,
+                Ok(v) if v.eq_ignore_ascii_case("keychain") => false,
+                _ => cfg!(debug_assertions),
+            };
+            if use_file {
+                KeyBackend::File(path)
+            } else {
+                KeyBackend::Keychain
+            }
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            KeyBackend::File(path)
+        }
+    }
+
+    async fn load_or_create(&self) -> std::io::Result<[u8; KEY_LEN]> {
+        if let Some(key) = self.load()? {
+            return Ok(key);
+        }
+        let key: [u8; KEY_LEN] = rand::random();
+        self.save(&STANDARD.encode(key))?;
+        tracing::info!("Created a new secrets key");
+        Ok(key)
+    }
+
+    fn load(&self) -> std::io::Result<Option<[u8; KEY_LEN]>> {
+        let encoded = match self {
+            KeyBackend::File(path) => match std::fs::read_to_string(path) {
+                Ok(encoded) => encoded,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+                Err(e) => return Err(e),
+            },
+            #[cfg(target_os = "macos")]
+            KeyBackend::Keychain => match keychain::load()? {
+                Some(encoded) => encoded,
+                None => return Ok(None),
+            },
+        };
+        // An unreadable key is an error rather than a reason to create a new
+        // one, which would make every stored secret undecryptable
+        STANDARD
+            .decode(encoded.trim())
+            .ok()
+            .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+            .map(Some)
+            .ok_or_else(|| std::io::Error::other("the secrets key is malformed"))
+    }
+
+    fn save(&self, encoded: &str) -> std::io::Result<()> {
+        match self {
+            KeyBackend::File(path) => {
+                let mut opts = std::fs::OpenOptions::new();
+                opts.create_new(true).write(true);
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::OpenOptionsExt;
+                    opts.mode(0o600);
+                }
+                let mut file = opts.open(path)?;
+                std::io::Write::write_all(&mut file, encoded.as_bytes())?;
+                file.sync_all()
+            }
+            #[cfg(target_os = "macos")]
+            KeyBackend::Keychain => keychain::save(encoded),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod keychain {
+    use security_framework::passwords::{get_generic_password, set_generic_password};
+
+    const SERVICE_NAME: &str = concat!(env!("CARGO_PKG_NAME"), ":secrets");
+    const ACCOUNT_NAME: &str = "default";
+    const ERR_SEC_ITEM_NOT_FOUND: i32 = -25300;
+
+    pub fn load() -> std::io::Result<Option<String>> {
+        match get_generic_password(SERVICE_NAME, ACCOUNT_NAME) {
+            Ok(bytes) => String::from_utf8(bytes)
+                .map(Some)
+                .map_err(std::io::Error::other),
+            Err(e) if e.code() == ERR_SEC_ITEM_NOT_FOUND => Ok(None),
+            Err(e) => Err(std::io::Error::other(e)),
+        }
+    }
+
+    pub fn save(encoded: &str) -> std::io::Result<()> {
+        set_generic_password(SERVICE_NAME, ACCOUNT_NAME, encoded.as_bytes())
+            .map_err(std::io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn encrypts_with_a_persisted_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("secrets.key");
+
+        let service = SecretsService::new(key_path.clone());
+        let first = service.encrypt("sk-live-1234").await.unwrap();
+        let second = service.encrypt("sk-live-1234").await.unwrap();
+        assert_ne!(first, second, "every value gets its own nonce");
+        assert!(!String::from_utf8_lossy(&first).contains("sk-live-1234"));
+
+        // A new service reads the same key back
+        let reloaded = SecretsService::new(key_path.clone());
+        assert_eq!(
+            reloaded.decrypt("API_KEY", &first).await.unwrap(),
+            "sk-live-1234"
+        );
+
+        let other = SecretsService::new(dir.path().join("other.key"));
+        assert!(matches!(
+            other.decrypt("API_KEY", &first).await,
+            Err(SecretError::Decrypt(name)) if name == "API_KEY"
+        ));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&key_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn validates_names() {
+        assert!(validate_name("OPENAI_API_KEY").is_ok());
+        assert!(validate_name("_token2").is_ok());
+        assert!(validate_name("2FA").is_err());
+        assert!(validate_name("API-KEY").is_err());
+        assert!(matches!(
+            validate_name("VK_PROJECT_ID"),
+            Err(SecretError::ReservedName)
+        ));
+    }
+
+    #[test]
+    fn repository_secrets_take_precedence() {
+        let secret = |project_id, repo_id| EncryptedSecret {
+            name: "TOKEN".to_string(),
+            project_id,
+            repo_id,
+            encrypted_value: Vec::new(),
+        };
+        let global = secret(None, None);
+        let project = secret(Some(Uuid::new_v4()), None);
+        let repo = secret(None, Some(Uuid::new_v4()));
+        assert!(precedence(&global) < precedence(&project));
+        assert!(precedence(&project) < precedence(&repo));
+    }
+}
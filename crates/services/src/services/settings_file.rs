@@ -0,0 +1,352 @@
+//! Project settings committed to a repository.
+//!
+//! A `.vibe-kanban.toml` (or `.vibe-kanban.json`) at the root of a repository
+//! declares the scripts and defaults that would otherwise be configured by hand
+//! on every machine. The file only governs the keys it declares. Whether it
+//! overwrites the local settings or is merely compared against them is chosen
+//! per repository.
+
+use std::{collections::BTreeMap, path::Path};
+
+use db::models::{
+    project::{Project, UpdateProject},
+    project_repo::{ProjectRepo, SettingsFileMode, UpdateProjectRepo},
+};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::git::{GitService, GitServiceError};
+
+/// Looked up in this order at the repository root
+pub const SETTINGS_FILE_NAMES: [&str; 2] = [".vibe-kanban.toml", ".vibe-kanban.json"];
+
+#[derive(Debug, Error)]
+pub enum SettingsFileError {
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error("Invalid {file}: {message}")]
+    Parse { file: String, message: String },
+}
+
+/// Contents of a settings file. Unknown keys are rejected so typos surface
+/// instead of being ignored.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RepoSettingsFile {
+    pub setup_script: Option<String>,
+    pub cleanup_script: Option<String>,
+    pub dev_script: Option<String>,
+    pub dev_script_working_dir: Option<String>,
+    /// Files copied from the repository into every attempt worktree
+    pub copy_files: Option<Vec<String>>,
+    pub agent_working_dir: Option<String>,
+    /// Run after the coding agent resolved conflicts
+    pub verification_command: Option<String>,
+    pub executor_profile: Option<ExecutorProfileId>,
+    pub mcp_servers: Option<BTreeMap<String, Value>>,
+}
+
+/// A key whose value in the settings file differs from the local one
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct SettingsFileDifference {
+    pub key: String,
+    pub repo_file: String,
+    /// `None` when the key is not set locally
+    pub local: Option<String>,
+}
+
+/// The settings file of a project repository compared against the local settings
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RepoSettingsFileStatus {
+    pub repo_id: Uuid,
+    /// Branch the file was read from
+    pub branch: String,
+    /// Name of the file found at the repository root, if any
+    pub file_name: Option<String>,
+    pub mode: Option<SettingsFileMode>,
+    pub differences: Vec<SettingsFileDifference>,
+    /// Why the file could not be read or parsed
+    pub error: Option<String>,
+}
+
+/// The first settings file found on `branch`, with its name
+pub fn read(
+    git: &GitService,
+    repo_path: &Path,
+    branch: &str,
+) -> Result<Option<(&'static str, RepoSettingsFile)>, SettingsFileError> {
+    let commit = git.get_branch_oid(repo_path, branch)?;
+    for name in SETTINGS_FILE_NAMES {
+        if let Some(contents) = git.read_file_at_commit(repo_path, &commit, name)? {
+            return parse(name, &contents).map(|file| Some((name, file)));
+        }
+    }
+    Ok(None)
+}
+
+pub fn parse(name: &str, contents: &str) -> Result<RepoSettingsFile, SettingsFileError> {
+    let parsed = if name.ends_with(".json") {
+        serde_json::from_str(contents).map_err(|e| e.to_string())
+    } else {
+        toml::from_str(contents).map_err(|e| e.to_string())
+    };
+    parsed.map_err(|message| SettingsFileError::Parse {
+        file: name.to_string(),
+        message,
+    })
+}
+
+/// Keys the file declares with a value other than the local one
+pub fn differences(
+    file: &RepoSettingsFile,
+    project: &Project,
+    repo: &ProjectRepo,
+) -> Vec<SettingsFileDifference> {
+    let copy_files = |value: &str| {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let entries = [
+        (
+            "setup_script",
+            file.setup_script.clone(),
+            repo.setup_script.clone(),
+        ),
+        (
+            "cleanup_script",
+            file.cleanup_script.clone(),
+            repo.cleanup_script.clone(),
+        ),
+        (
+            "dev_script",
+            file.dev_script.clone(),
+            project.dev_script.clone(),
+        ),
+        (
+            "dev_script_working_dir",
+            file.dev_script_working_dir.clone(),
+            project.dev_script_working_dir.clone(),
+        ),
+        (
+            "copy_files",
+            file.copy_files
+                .as_ref()
+                .map(|files| copy_files(&files.join(","))),
+            repo.copy_files.as_deref().map(copy_files),
+        ),
+        (
+            "agent_working_dir",
+            file.agent_working_dir.clone(),
+            project.default_agent_working_dir.clone(),
+        ),
+        (
+            "verification_command",
+            file.verification_command.clone(),
+            repo.test_script.clone(),
+        ),
+        (
+            "executor_profile",
+            file.executor_profile.as_ref().map(to_json),
+            project
+                .default_executor_profile
+                .as_ref()
+                .map(|p| to_json(&p.0)),
+        ),
+        (
+            "mcp_servers",
+            file.mcp_servers.as_ref().map(to_json),
+            project.mcp_servers.as_ref().map(|s| to_json(&s.0)),
+        ),
+    ];
+
+    entries
+        .into_iter()
+        .filter_map(|(key, repo_file, local)| {
+            let repo_file = repo_file?;
+            let local = local.filter(|v| !v.is_empty());
+            (local.as_deref() != Some(repo_file.as_str())).then(|| SettingsFileDifference {
+                key: key.to_string(),
+                repo_file,
+                local,
+            })
+        })
+        .collect()
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// Updates that make the local settings match the keys the file declares
+pub fn apply(
+    file: &RepoSettingsFile,
+    project: &Project,
+    repo: &ProjectRepo,
+) -> (UpdateProject, UpdateProjectRepo) {
+    let or = |declared: &Option<String>, local: &Option<String>| declared.clone().or(local.clone());
+
+    let update_project = UpdateProject {
+        name: None,
+        dev_script: or(&file.dev_script, &project.dev_script),
+        dev_script_working_dir: or(
+            &file.dev_script_working_dir,
+            &project.dev_script_working_dir,
+        ),
+        default_agent_working_dir: or(&file.agent_working_dir, &project.default_agent_working_dir),
+        auto_review_follow_ups: None,
+        auto_fix_ci: None,
+        dev_server_ports: None,
+        dev_server_ready_pattern: None,
+        default_executor_profile: file.executor_profile.clone(),
+        mcp_servers: file.mcp_servers.clone(),
+    };
+    let update_repo = UpdateProjectRepo {
+        setup_script: or(&file.setup_script, &repo.setup_script),
+        cleanup_script: or(&file.cleanup_script, &repo.cleanup_script),
+        copy_files: file
+            .copy_files
+            .as_ref()
+            .map(|files| files.join(", "))
+            .or(repo.copy_files.clone()),
+        parallel_setup_script: None,
+        test_script: or(&file.verification_command, &repo.test_script),
+        worktree_pool_size: None,
+        worktree_pool_max_age_minutes: None,
+        sparse_checkout_paths: repo.sparse_checkout_paths.clone(),
+        sparse_checkout_agent_working_dir: None,
+        dependency_caches: repo.dependency_caches.clone(),
+    };
+    (update_project, update_repo)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use executors::executors::BaseCodingAgent;
+    use sqlx::types::Json;
+
+    use super::*;
+
+    const SETTINGS: &str = r#"
+setup_script = "pnpm install"
+copy_files = [".env", "config/local.json"]
+verification_command = "pnpm test"
+
+[executor_profile]
+executor = "CLAUDE_CODE"
+variant = "PLAN"
+
+[mcp_servers.playwright]
+command = "npx"
+args = ["@playwright/mcp@latest"]
+"#;
+
+    fn project() -> Project {
+        Project {
+            id: Uuid::new_v4(),
+            name: "web".to_string(),
+            dev_script: Some("pnpm dev".to_string()),
+            dev_script_working_dir: None,
+            default_agent_working_dir: None,
+            remote_project_id: None,
+            auto_review_follow_ups: false,
+            auto_fix_ci: false,
+            dev_server_ports: None,
+            dev_server_ready_pattern: None,
+            default_executor_profile: None,
+            mcp_servers: None,
+            git_host: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn project_repo() -> ProjectRepo {
+        ProjectRepo {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            repo_id: Uuid::new_v4(),
+            setup_script: Some("npm ci".to_string()),
+            cleanup_script: None,
+            copy_files: Some(".env,config/local.json".to_string()),
+            parallel_setup_script: false,
+            test_script: None,
+            worktree_pool_size: 0,
+            worktree_pool_max_age_minutes: 60,
+            sparse_checkout_paths: Some("web".to_string()),
+            sparse_checkout_agent_working_dir: false,
+            dependency_caches: None,
+            settings_file_mode: None,
+            settings_file_branch: None,
+        }
+    }
+
+    #[test]
+    fn parses_toml_and_json() {
+        let file = parse(".vibe-kanban.toml", SETTINGS).unwrap();
+        assert_eq!(file.setup_script.as_deref(), Some("pnpm install"));
+        assert_eq!(
+            file.executor_profile,
+            Some(ExecutorProfileId {
+                executor: BaseCodingAgent::ClaudeCode,
+                variant: Some("PLAN".to_string()),
+            })
+        );
+        assert!(file.mcp_servers.unwrap().contains_key("playwright"));
+
+        let file = parse(".vibe-kanban.json", r#"{"dev_script": "npm start"}"#).unwrap();
+        assert_eq!(file.dev_script.as_deref(), Some("npm start"));
+
+        assert!(matches!(
+            parse(".vibe-kanban.toml", "setup_scirpt = \"npm ci\""),
+            Err(SettingsFileError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn differences_cover_declared_keys_only() {
+        let file = parse(".vibe-kanban.toml", SETTINGS).unwrap();
+        let keys: Vec<String> = differences(&file, &project(), &project_repo())
+            .into_iter()
+            .map(|d| d.key)
+            .collect();
+        // copy_files only differs in formatting and dev_script is not declared
+        assert_eq!(
+            keys,
+            [
+                "setup_script",
+                "verification_command",
+                "executor_profile",
+                "mcp_servers"
+            ]
+        );
+    }
+
+    #[test]
+    fn applied_settings_match_the_file() {
+        let file = parse(".vibe-kanban.toml", SETTINGS).unwrap();
+        let (update_project, update_repo) = apply(&file, &project(), &project_repo());
+        assert_eq!(update_project.dev_script.as_deref(), Some("pnpm dev"));
+        assert_eq!(update_repo.setup_script.as_deref(), Some("pnpm install"));
+        assert_eq!(update_repo.test_script.as_deref(), Some("pnpm test"));
+        assert_eq!(update_repo.sparse_checkout_paths.as_deref(), Some("web"));
+
+        let mut project = project();
+        project.default_executor_profile = update_project.default_executor_profile.map(Json);
+        project.mcp_servers = update_project.mcp_servers.map(Json);
+        let mut repo = project_repo();
+        repo.setup_script = update_repo.setup_script;
+        repo.copy_files = update_repo.copy_files;
+        repo.test_script = update_repo.test_script;
+        assert!(differences(&file, &project, &repo).is_empty());
+    }
+}
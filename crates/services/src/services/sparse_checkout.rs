@@ -0,0 +1,141 @@
+//! Sparse-checkout cones for attempt worktrees of large repositories.
+//!
+//! A cone is a list of directories relative to the repository root. In cone
+//! mode git checks out everything below those directories, plus the files
+//! directly inside the root and inside each of their parent directories.
+
+/// Parse cone directories from a project repo setting (one per line, commas
+/// also accepted). Entries escaping the repository are dropped.
+pub fn parse_cone_paths(spec: &str) -> Vec<String> {
+    let mut paths: Vec<String> = spec
+        .split(['\n', ','])
+        .filter_map(normalize_cone_path)
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Normalize one cone directory: forward slashes, no leading `./` or `/`, no
+/// trailing `/`. `None` for the repository root and for paths with `..`.
+pub fn normalize_cone_path(path: &str) -> Option<String> {
+    let path = path.trim().replace('\\', "/");
+    let parts: Vec<&str> = path
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect();
+    if parts.is_empty()
+        || parts
+            .iter()
+            .any(|part| *part == ".." || part.starts_with('-'))
+    {
+        return None;
+    }
+    Some(parts.join("/"))
+}
+
+/// Cone of one repository of a workspace: the configured directories, plus the
+/// agent working directory when enabled. The agent working directory is
+/// relative to the workspace root, so it only applies to the repo it is in;
+/// when it is the repo itself the whole tree is needed. An empty result means
+/// no sparse checkout.
+pub fn cone_for_repo(
+    sparse_checkout_paths: Option<&str>,
+    include_agent_working_dir: bool,
+    repo_name: &str,
+    agent_working_dir: Option<&str>,
+) -> Vec<String> {
+    let mut paths = sparse_checkout_paths
+        .map(parse_cone_paths)
+        .unwrap_or_default();
+    if include_agent_working_dir && let Some(dir) = agent_working_dir {
+        let dir = dir.trim().trim_matches('/');
+        if dir == repo_name {
+            return Vec::new();
+        }
+        if let Some(sub) = dir
+            .strip_prefix(repo_name)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .and_then(normalize_cone_path)
+        {
+            paths.push(sub);
+            paths.sort();
+            paths.dedup();
+        }
+    }
+    paths
+}
+
+/// Whether `path` (relative to the repository root) is checked out with the
+/// cone `cone`. Everything is with an empty cone.
+pub fn in_cone(path: &str, is_file: bool, cone: &[String]) -> bool {
+    if cone.is_empty() {
+        return true;
+    }
+    let path = path.trim_matches('/');
+    let parent = path
+        .rsplit_once('/')
+        .map(|(parent, _)| parent)
+        .unwrap_or("");
+    cone.iter().any(|dir| {
+        // Below a cone directory
+        path == dir
+            || path.starts_with(&format!("{dir}/"))
+            // A parent directory of a cone directory
+            || dir.starts_with(&format!("{path}/"))
+            // A file directly inside the root or a parent directory
+            || (is_file && (parent.is_empty() || dir.starts_with(&format!("{parent}/"))))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_normalizes_cone_paths() {
+        assert_eq!(
+            parse_cone_paths("services/api/\n./libs/common, /tools\n\n../secrets\n."),
+            vec!["libs/common", "services/api", "tools"]
+        );
+        assert!(parse_cone_paths("").is_empty());
+    }
+
+    #[test]
+    fn agent_working_dir_extends_the_cone_of_its_repo() {
+        let spec = Some("libs/common");
+        assert_eq!(
+            cone_for_repo(spec, true, "mono", Some("mono/services/api")),
+            vec!["libs/common", "services/api"]
+        );
+        assert_eq!(
+            cone_for_repo(spec, true, "mono", Some("other/services/api")),
+            vec!["libs/common"]
+        );
+        assert_eq!(
+            cone_for_repo(spec, false, "mono", Some("mono/services/api")),
+            vec!["libs/common"]
+        );
+        // The whole repo is the working directory
+        assert!(cone_for_repo(spec, true, "mono", Some("mono")).is_empty());
+        assert_eq!(
+            cone_for_repo(None, true, "mono", Some("mono/services")),
+            vec!["services"]
+        );
+        assert!(cone_for_repo(None, false, "mono", Some("mono/services")).is_empty());
+    }
+
+    #[test]
+    fn cone_mode_membership() {
+        let cone = vec!["services/api".to_string()];
+        assert!(in_cone("services/api/src/main.rs", true, &cone));
+        assert!(in_cone("services/api", false, &cone));
+        assert!(in_cone("services", false, &cone));
+        assert!(in_cone("README.md", true, &cone));
+        assert!(in_cone("services/Cargo.toml", true, &cone));
+        assert!(!in_cone("services/web", false, &cone));
+        assert!(!in_cone("services/web/index.ts", true, &cone));
+        assert!(!in_cone("libs", false, &cone));
+        assert!(in_cone("libs/common/lib.rs", true, &[]));
+    }
+}
@@ -0,0 +1,585 @@
+//! Outgoing webhooks.
+//!
+//! Events are written to a persistent queue, one delivery per subscribed
+//! webhook, and posted by a background worker. Failed attempts are retried
+//! with exponential backoff, so deliveries survive restarts and endpoint
+//! outages. Each payload is signed with the webhook's secret:
+//! `X-Vibe-Kanban-Signature: sha256=<hex HMAC-SHA256 of the body>`.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use chrono::Utc;
+use db::{
+    DBService,
+    models::{
+        execution_process::{ExecutionContext, ExecutionProcess, ExecutionProcessStatus},
+        task::{Task, TaskStatus, TaskWithAttemptStatus},
+        webhook::{Webhook, WebhookEventType},
+        webhook_delivery::{WebhookAttempt, WebhookDelivery},
+        workspace::Workspace,
+    },
+};
+use hmac::{Hmac, Mac};
+use json_patch::PatchOperation;
+use serde_json::{Value, json};
+use sha2::Sha256;
+use tokio::sync::{Notify, broadcast::error::RecvError};
+use utils::{
+    approvals::ApprovalRequest, log_msg::LogMsg, msg_store::MsgStore,
+    text::truncate_to_char_boundary,
+};
+use uuid::Uuid;
+
+pub const EVENT_HEADER: &str = "X-Vibe-Kanban-Event";
+pub const DELIVERY_HEADER: &str = "X-Vibe-Kanban-Delivery";
+pub const SIGNATURE_HEADER: &str = "X-Vibe-Kanban-Signature";
+
+/// Attempts before a delivery is given up
+const MAX_ATTEMPTS: i64 = 8;
+/// Delay before the first retry; doubled for each further retry
+const RETRY_BASE_DELAY_SECS: i64 = 30;
+const RETRY_MAX_DELAY_SECS: i64 = 60 * 60;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often the queue is checked when nothing wakes the worker earlier
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+/// Deliveries sent per pass over the queue
+const BATCH_SIZE: i64 = 20;
+/// Bytes of each response body kept in the delivery log
+const RESPONSE_BODY_LIMIT: usize = 2048;
+
+#[derive(Clone)]
+pub struct WebhookService {
+    db: DBService,
+    client: reqwest::Client,
+    wake: Arc<Notify>,
+}
+
+impl WebhookService {
+    pub fn new(db: DBService) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("vibe-kanban/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self {
+            db,
+            client,
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Queue `event` for every enabled webhook subscribed to it. Failures are
+    /// logged; an event that cannot be queued is dropped.
+    pub async fn emit(&self, event_type: WebhookEventType, data: Value) {
+        if let Err(e) = self.enqueue(event_type, data).await {
+            tracing::error!("Failed to queue {:?} webhook event: {}", event_type, e);
+        }
+    }
+
+    async fn enqueue(&self, event_type: WebhookEventType, data: Value) -> Result<(), sqlx::Error> {
+        let webhooks = Webhook::find_all(&self.db.pool).await?;
+        let mut queued = false;
+        for webhook in webhooks.iter().filter(|w| w.subscribes_to(event_type)) {
+            let delivery_id = Uuid::new_v4();
+            let payload = build_payload(delivery_id, event_type, &data);
+            WebhookDelivery::create(&self.db.pool, delivery_id, webhook.id, event_type, &payload)
+                .await?;
+            queued = true;
+        }
+        if queued {
+            self.wake.notify_one();
+        }
+        Ok(())
+    }
+
+    /// Send a test event to `webhook` right away, whether or not it is
+    /// enabled, and return the logged delivery
+    pub async fn send_test(&self, webhook: &Webhook) -> Result<WebhookDelivery, sqlx::Error> {
+        let data = json!({
+            "webhook_id": webhook.id,
+            "message": "Test event from Vibe Kanban",
+        });
+        let delivery_id = Uuid::new_v4();
+        let payload = build_payload(delivery_id, WebhookEventType::Test, &data);
+        let delivery = WebhookDelivery::create(
+            &self.db.pool,
+            delivery_id,
+            webhook.id,
+            WebhookEventType::Test,
+            &payload,
+        )
+        .await?;
+        let attempt = self.post(webhook, &delivery).await;
+        // Test events are not retried
+        WebhookDelivery::record_attempt(&self.db.pool, delivery.id, &attempt, None).await?;
+        Ok(WebhookDelivery::find_by_id(&self.db.pool, delivery.id)
+            .await?
+            .unwrap_or(delivery))
+    }
+
+    /// Queue a delivery again and wake the worker
+    pub async fn redeliver(&self, delivery_id: Uuid) -> Result<(), sqlx::Error> {
+        WebhookDelivery::requeue(&self.db.pool, delivery_id).await?;
+        self.wake.notify_one();
+        Ok(())
+    }
+
+    /// Start the worker posting queued deliveries
+    pub fn spawn_delivery_worker(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = service.deliver_due().await {
+                    tracing::error!("Failed to process webhook deliveries: {}", e);
+                }
+                tokio::select! {
+                    _ = service.wake.notified() => {}
+                    _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                }
+            }
+        })
+    }
+
+    async fn deliver_due(&self) -> Result<(), sqlx::Error> {
+        loop {
+            let due = WebhookDelivery::find_due(&self.db.pool, Utc::now(), BATCH_SIZE).await?;
+            if due.is_empty() {
+                return Ok(());
+            }
+            for delivery in due {
+                self.deliver(&delivery).await?;
+            }
+        }
+    }
+
+    async fn deliver(&self, delivery: &WebhookDelivery) -> Result<(), sqlx::Error> {
+        let attempt = match Webhook::find_by_id(&self.db.pool, delivery.webhook_id).await? {
+            Some(webhook) if webhook.enabled => self.post(&webhook, delivery).await,
+            _ => {
+                let attempt = WebhookAttempt {
+                    response_status: None,
+                    response_body: None,
+                    error: Some("Webhook is disabled".to_string()),
+                };
+                return WebhookDelivery::record_attempt(&self.db.pool, delivery.id, &attempt, None)
+                    .await;
+            }
+        };
+        let attempts = delivery.attempts + 1;
+        let next_attempt_at = match &attempt.error {
+            Some(error) if attempts < MAX_ATTEMPTS => {
+                let delay = retry_delay(attempts);
+                tracing::debug!(
+                    "Webhook delivery {} failed ({}), retrying in {}s",
+                    delivery.id,
+                    error,
+                    delay.num_seconds()
+                );
+                Some(Utc::now() + delay)
+            }
+            Some(error) => {
+                tracing::warn!(
+                    "Giving up webhook delivery {} after {} attempts: {}",
+                    delivery.id,
+                    attempts,
+                    error
+                );
+                None
+            }
+            None => None,
+        };
+        WebhookDelivery::record_attempt(&self.db.pool, delivery.id, &attempt, next_attempt_at).await
+    }
+
+    async fn post(&self, webhook: &Webhook, delivery: &WebhookDelivery) -> WebhookAttempt {
+        post_signed(
+            &self.client,
+            &webhook.url,
+            &webhook.secret,
+            delivery.event_type,
+            delivery.id,
+            &delivery.payload,
+        )
+        .await
+    }
+
+    /// Emit `task_status_changed` for status changes seen on the event stream
+    /// of [`EventService`](super::events::EventService). Catches every path
+    /// that updates a task without each of them having to report it.
+    pub fn spawn_task_status_watcher(
+        &self,
+        msg_store: Arc<MsgStore>,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = self.clone();
+        let mut rx = msg_store.get_receiver();
+        tokio::spawn(async move {
+            let mut statuses: HashMap<Uuid, TaskStatus> =
+                match Task::find_all_statuses(&service.db.pool).await {
+                    Ok(statuses) => statuses.into_iter().collect(),
+                    Err(e) => {
+                        tracing::error!("Failed to load task statuses for webhooks: {}", e);
+                        HashMap::new()
+                    }
+                };
+            loop {
+                let patch = match rx.recv().await {
+                    Ok(LogMsg::JsonPatch(patch)) => patch,
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Webhook task watcher skipped {} events", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+                for op in patch.0 {
+                    match op {
+                        PatchOperation::Add(op) if op.path.as_str().starts_with("/tasks/") => {
+                            if let Ok(task) =
+                                serde_json::from_value::<TaskWithAttemptStatus>(op.value)
+                            {
+                                statuses.insert(task.id, task.status.clone());
+                            }
+                        }
+                        PatchOperation::Replace(op) if op.path.as_str().starts_with("/tasks/") => {
+                            let Ok(task) =
+                                serde_json::from_value::<TaskWithAttemptStatus>(op.value)
+                            else {
+                                continue;
+                            };
+                            let previous = statuses.insert(task.id, task.status.clone());
+                            if let Some(previous) = previous
+                                && previous != task.status
+                            {
+                                service.task_status_changed(&task, &previous).await;
+                            }
+                        }
+                        PatchOperation::Remove(op) => {
+                            if let Some(id) = op.path.as_str().strip_prefix("/tasks/")
+                                && let Ok(id) = Uuid::parse_str(id)
+                            {
+                                statuses.remove(&id);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        })
+    }
+
+    pub async fn task_status_changed(&self, task: &Task, previous: &TaskStatus) {
+        self.emit(
+            WebhookEventType::TaskStatusChanged,
+            json!({
+                "task": task_data(task),
+                "previous_status": previous,
+            }),
+        )
+        .await;
+    }
+
+    /// Emit `execution_finished` or `execution_failed` for a process that
+    /// ended on its own; processes stopped by the user are skipped.
+    pub async fn execution_ended(&self, ctx: &ExecutionContext) {
+        let event_type = match ctx.execution_process.status {
+            ExecutionProcessStatus::Completed => WebhookEventType::ExecutionFinished,
+            ExecutionProcessStatus::Failed => WebhookEventType::ExecutionFailed,
+            ExecutionProcessStatus::Queued
+            | ExecutionProcessStatus::Running
+            | ExecutionProcessStatus::Killed => return,
+        };
+        self.emit(
+            event_type,
+            json!({
+                "task": task_data(&ctx.task),
+                "workspace": workspace_data(&ctx.workspace),
+                "execution_process": {
+                    "id": ctx.execution_process.id,
+                    "session_id": ctx.execution_process.session_id,
+                    "run_reason": ctx.execution_process.run_reason,
+                    "status": ctx.execution_process.status,
+                    "exit_code": ctx.execution_process.exit_code,
+                },
+                "executor": ctx.session.executor,
+            }),
+        )
+        .await;
+    }
+
+    pub async fn approval_requested(&self, request: &ApprovalRequest) {
+        let ctx = ExecutionProcess::load_context(&self.db.pool, request.execution_process_id)
+            .await
+            .ok();
+        self.emit(
+            WebhookEventType::ApprovalRequested,
+            json!({
+                "approval": {
+                    "id": request.id,
+                    "tool_name": request.tool_name,
+                    "tool_input": request.tool_input,
+                    "execution_process_id": request.execution_process_id,
+                    "created_at": request.created_at,
+                    "timeout_at": request.timeout_at,
+                },
+                "task": ctx.as_ref().map(|ctx| task_data(&ctx.task)),
+                "workspace": ctx.as_ref().map(|ctx| workspace_data(&ctx.workspace)),
+            }),
+        )
+        .await;
+    }
+
+    pub async fn pr_opened(&self, workspace_id: Uuid, pr_number: i64, pr_url: &str) {
+        self.emit_pr_event(WebhookEventType::PrOpened, workspace_id, pr_number, pr_url)
+            .await;
+    }
+
+    pub async fn pr_merged(&self, workspace_id: Uuid, pr_number: i64, pr_url: &str) {
+        self.emit_pr_event(WebhookEventType::PrMerged, workspace_id, pr_number, pr_url)
+            .await;
+    }
+
+    async fn emit_pr_event(
+        &self,
+        event_type: WebhookEventType,
+        workspace_id: Uuid,
+        pr_number: i64,
+        pr_url: &str,
+    ) {
+        let workspace = Workspace::find_by_id(&self.db.pool, workspace_id)
+            .await
+            .ok()
+            .flatten();
+        let task = match &workspace {
+            Some(workspace) => Task::find_by_id(&self.db.pool, workspace.task_id)
+                .await
+                .ok()
+                .flatten(),
+            None => None,
+        };
+        self.emit(
+            event_type,
+            json!({
+                "pull_request": { "number": pr_number, "url": pr_url },
+                "task": task.as_ref().map(task_data),
+                "workspace": workspace.as_ref().map(workspace_data),
+            }),
+        )
+        .await;
+    }
+}
+
+fn task_data(task: &Task) -> Value {
+    json!({
+        "id": task.id,
+        "project_id": task.project_id,
+        "title": task.title,
+        "status": task.status,
+    })
+}
+
+fn workspace_data(workspace: &Workspace) -> Value {
+    json!({
+        "id": workspace.id,
+        "task_id": workspace.task_id,
+        "branch": workspace.branch,
+    })
+}
+
+fn build_payload(delivery_id: Uuid, event_type: WebhookEventType, data: &Value) -> String {
+    json!({
+        "id": delivery_id,
+        "event": event_type,
+        "created_at": Utc::now(),
+        "data": data,
+    })
+    .to_string()
+}
+
+/// `sha256=<hex>` signature of `body`
+pub fn sign_payload(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+    format!("sha256={hex}")
+}
+
+/// A random secret for webhooks created without one
+pub fn generate_secret() -> String {
+    format!("whsec_{}", Uuid::new_v4().simple())
+}
+
+/// Delay before retry number `attempts`
+fn retry_delay(attempts: i64) -> chrono::Duration {
+    let exponent = (attempts - 1).clamp(0, 16) as u32;
+    let secs = RETRY_BASE_DELAY_SECS.saturating_mul(1 << exponent);
+    chrono::Duration::seconds(secs.min(RETRY_MAX_DELAY_SECS))
+}
+
+/// Post `payload` to `url`. Any response outside 2xx counts as a failure.
+async fn post_signed(
+    client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    event_type: WebhookEventType,
+    delivery_id: Uuid,
+    payload: &str,
+) -> WebhookAttempt {
+    let event = serde_json::to_value(event_type)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let result = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event)
+        .header(DELIVERY_HEADER, delivery_id.to_string())
+        .header(SIGNATURE_HEADER, sign_payload(secret, payload))
+        .body(payload.to_string())
+        .send()
+        .await;
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => {
+            return WebhookAttempt {
+                response_status: None,
+                response_body: None,
+                error: Some(e.to_string()),
+            };
+        }
+    };
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let body = truncate_to_char_boundary(&body, RESPONSE_BODY_LIMIT);
+    WebhookAttempt {
+        response_status: Some(status.as_u16() as i64),
+        response_body: (!body.is_empty()).then(|| body.to_string()),
+        error: (!status.is_success()).then(|| format!("Endpoint responded with {status}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Accept one request, reply with `status`, and return the raw request
+    async fn serve_once(listener: TcpListener, status: &'static str) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|l| {
+                        l.to_ascii_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if request.len() >= header_end + 4 + content_length {
+                    break;
+                }
+            }
+            if n == 0 {
+                break;
+            }
+        }
+        let response =
+            format!("HTTP/1.1 {status}\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+        stream.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8(request).unwrap()
+    }
+
+    #[test]
+    fn signature_matches_hmac_sha256() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            sign_payload("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6d042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn retries_back_off_exponentially_up_to_a_cap() {
+        assert_eq!(retry_delay(1).num_seconds(), 30);
+        assert_eq!(retry_delay(2).num_seconds(), 60);
+        assert_eq!(retry_delay(4).num_seconds(), 240);
+        assert_eq!(retry_delay(20).num_seconds(), RETRY_MAX_DELAY_SECS);
+    }
+
+    #[tokio::test]
+    async fn posts_signed_payload_to_endpoint() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_once(listener, "200 OK"));
+
+        let delivery_id = Uuid::new_v4();
+        let payload = build_payload(delivery_id, WebhookEventType::Test, &json!({ "a": 1 }));
+        let attempt = post_signed(
+            &reqwest::Client::new(),
+            &url,
+            "secret",
+            WebhookEventType::Test,
+            delivery_id,
+            &payload,
+        )
+        .await;
+        assert_eq!(attempt.response_status, Some(200));
+        assert_eq!(attempt.response_body.as_deref(), Some("ok"));
+        assert!(attempt.error.is_none());
+
+        let request = server.await.unwrap().to_ascii_lowercase();
+        assert!(request.starts_with("post /hook "));
+        assert!(request.contains("x-vibe-kanban-event: test"));
+        assert!(request.contains(&format!("x-vibe-kanban-delivery: {delivery_id}")));
+        assert!(request.contains(&format!(
+            "x-vibe-kanban-signature: {}",
+            sign_payload("secret", &payload)
+        )));
+        assert!(request.ends_with(&payload.to_ascii_lowercase()));
+    }
+
+    #[tokio::test]
+    async fn error_responses_and_unreachable_endpoints_fail() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve_once(listener, "500 Internal Server Error"));
+        let client = reqwest::Client::new();
+
+        let attempt = post_signed(
+            &client,
+            &format!("http://{addr}/"),
+            "secret",
+            WebhookEventType::Test,
+            Uuid::new_v4(),
+            "{}",
+        )
+        .await;
+        server.await.unwrap();
+        assert_eq!(attempt.response_status, Some(500));
+        assert!(attempt.error.is_some());
+
+        // The listener is gone, so the connection is refused
+        let attempt = post_signed(
+            &client,
+            &format!("http://{addr}/"),
+            "secret",
+            WebhookEventType::Test,
+            Uuid::new_v4(),
+            "{}",
+        )
+        .await;
+        assert_eq!(attempt.response_status, None);
+        assert!(attempt.error.is_some());
+    }
+}
@@ -1,4 +1,7 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use db::models::{repo::Repo, workspace::Workspace as DbWorkspace};
 use sqlx::{Pool, Sqlite};
@@ -6,12 +9,18 @@ use thiserror::Error;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use super::worktree_manager::{WorktreeCleanup, WorktreeError, WorktreeManager};
+use super::{
+    git::GitService,
+    worktree_manager::{WorktreeCleanup, WorktreeError, WorktreeManager},
+    worktree_pool::{PooledWorktree, WorktreePool},
+};
 
 #[derive(Debug, Clone)]
 pub struct RepoWorkspaceInput {
     pub repo: Repo,
     pub target_branch: String,
+    /// Sparse-checkout cone of the worktree; empty checks out the whole tree
+    pub sparse_paths: Vec<String>,
 }
 
 impl RepoWorkspaceInput {
@@ -19,8 +28,14 @@ impl RepoWorkspaceInput {
         Self {
             repo,
             target_branch,
+            sparse_paths: Vec::new(),
         }
     }
+
+    pub fn with_sparse_paths(mut self, sparse_paths: Vec<String>) -> Self {
+        self.sparse_paths = sparse_paths;
+        self
+    }
 }
 
 #[derive(Debug, Error)]
@@ -42,6 +57,8 @@ pub struct RepoWorktree {
     pub repo_name: String,
     pub source_repo_path: PathBuf,
     pub worktree_path: PathBuf,
+    /// The worktree was claimed from the worktree pool, with setup already run
+    pub from_pool: bool,
 }
 
 /// A container directory holding worktrees for all project repos
@@ -55,11 +72,14 @@ pub struct WorkspaceManager;
 
 impl WorkspaceManager {
     /// Create a workspace with worktrees for all repositories.
+    /// With a pool (and the id of the project it is claimed for), pre-created
+    /// worktrees are used where available instead of creating new ones.
     /// On failure, rolls back any already-created worktrees.
     pub async fn create_workspace(
         workspace_dir: &Path,
         repos: &[RepoWorkspaceInput],
         branch_name: &str,
+        pool: Option<(&WorktreePool, Uuid)>,
     ) -> Result<WorktreeContainer, WorkspaceError> {
         if repos.is_empty() {
             return Err(WorkspaceError::NoRepositories);
@@ -84,21 +104,34 @@ impl WorkspaceManager {
                 worktree_path.display()
             );
 
-            match WorktreeManager::create_worktree(
-                &input.repo.path,
-                branch_name,
-                &worktree_path,
-                &input.target_branch,
-                true,
-            )
-            .await
-            {
+            let pooled =
+                pool.and_then(|(pool, project_id)| Self::claim_pooled(pool, project_id, input));
+            let from_pool = match pooled {
+                Some(pooled) => Self::adopt_pooled(pooled, &worktree_path, branch_name).await,
+                None => false,
+            };
+            let result = if from_pool {
+                Ok(())
+            } else {
+                WorktreeManager::create_worktree(
+                    &input.repo.path,
+                    branch_name,
+                    &worktree_path,
+                    &input.target_branch,
+                    true,
+                    &input.sparse_paths,
+                )
+                .await
+            };
+
+            match result {
                 Ok(()) => {
                     created_worktrees.push(RepoWorktree {
                         repo_id: input.repo.id,
                         repo_name: input.repo.name.clone(),
                         source_repo_path: input.repo.path.clone(),
                         worktree_path,
+                        from_pool,
                     });
                 }
                 Err(e) => {
@@ -137,11 +170,61 @@ impl WorkspaceManager {
         })
     }
 
-    /// Ensure all worktrees in a workspace exist (for cold restart scenarios)
+    /// Take a pooled worktree on the current tip of the input's target branch
+    fn claim_pooled(
+        pool: &WorktreePool,
+        project_id: Uuid,
+        input: &RepoWorkspaceInput,
+    ) -> Option<PooledWorktree> {
+        let base_commit = GitService::new()
+            .get_branch_oid(&input.repo.path, &input.target_branch)
+            .ok()?;
+        pool.claim(
+            project_id,
+            input.repo.id,
+            &input.target_branch,
+            &base_commit,
+            &input.sparse_paths,
+        )
+    }
+
+    /// Move a pooled worktree into the workspace and check out the attempt
+    /// branch. Returns false (after discarding it) if that fails, so the
+    /// worktree is created from scratch instead.
+    async fn adopt_pooled(pooled: PooledWorktree, worktree_path: &Path, branch_name: &str) -> bool {
+        debug!(
+            "Claiming pooled worktree {} for {}",
+            pooled.path.display(),
+            worktree_path.display()
+        );
+        match WorktreeManager::adopt_detached_worktree(
+            &pooled.repo_path,
+            &pooled.path,
+            worktree_path,
+            branch_name,
+        )
+        .await
+        {
+            Ok(()) => true,
+            Err(e) => {
+                warn!(
+                    "Failed to claim pooled worktree {}, creating a new one: {}",
+                    pooled.path.display(),
+                    e
+                );
+                WorktreePool::discard(vec![pooled]).await;
+                false
+            }
+        }
+    }
+
+    /// Ensure all worktrees in a workspace exist (for cold restart scenarios).
+    /// Recreated worktrees use the sparse-checkout cone of their repo, if any.
     pub async fn ensure_workspace_exists(
         workspace_dir: &Path,
         repos: &[Repo],
         branch_name: &str,
+        sparse_paths: &HashMap<Uuid, Vec<String>>,
     ) -> Result<(), WorkspaceError> {
         if repos.is_empty() {
             return Err(WorkspaceError::NoRepositories);
@@ -166,8 +249,16 @@ impl WorkspaceManager {
                 worktree_path.display()
             );
 
-            WorktreeManager::ensure_worktree_exists(&repo.path, branch_name, &worktree_path)
-                .await?;
+            WorktreeManager::ensure_worktree_exists(
+                &repo.path,
+                branch_name,
+                &worktree_path,
+                sparse_paths
+                    .get(&repo.id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+            )
+            .await?;
         }
 
         Ok(())
@@ -54,13 +54,15 @@ pub enum WorktreeError {
 pub struct WorktreeManager;
 
 impl WorktreeManager {
-    /// Create a worktree with a new branch
+    /// Create a worktree with a new branch. A non-empty `sparse_paths` checks
+    /// out only that sparse-checkout cone.
     pub async fn create_worktree(
         repo_path: &Path,
         branch_name: &str,
         worktree_path: &Path,
         base_branch: &str,
         create_branch: bool,
+        sparse_paths: &[String],
     ) -> Result<(), WorktreeError> {
         if create_branch {
             let repo_path_owned = repo_path.to_path_buf();
@@ -82,7 +84,7 @@ impl WorktreeManager {
             .map_err(|e| WorktreeError::TaskJoin(format!("Task join error: {e}")))??;
         }
 
-        Self::ensure_worktree_exists(repo_path, branch_name, worktree_path).await
+        Self::ensure_worktree_exists(repo_path, branch_name, worktree_path, sparse_paths).await
     }
 
     /// Ensure worktree exists, recreating if necessary with proper synchronization
@@ -91,6 +93,7 @@ impl WorktreeManager {
         repo_path: &Path,
         branch_name: &str,
         worktree_path: &Path,
+        sparse_paths: &[String],
     ) -> Result<(), WorktreeError> {
         let path_str = worktree_path.to_string_lossy().to_string();
 
@@ -114,7 +117,7 @@ impl WorktreeManager {
 
         // If worktree doesn't exist or isn't properly set up, recreate it
         info!("Worktree needs recreation at path: {}", path_str);
-        Self::recreate_worktree_internal(repo_path, branch_name, worktree_path).await
+        Self::recreate_worktree_internal(repo_path, branch_name, worktree_path, sparse_paths).await
     }
 
     /// Internal worktree recreation function (always recreates)
@@ -122,6 +125,7 @@ impl WorktreeManager {
         repo_path: &Path,
         branch_name: &str,
         worktree_path: &Path,
+        sparse_paths: &[String],
     ) -> Result<(), WorktreeError> {
         let path_str = worktree_path.to_string_lossy().to_string();
         let branch_name_owned = branch_name.to_string();
@@ -150,6 +154,7 @@ impl WorktreeManager {
             &branch_name_owned,
             &worktree_path_owned,
             &path_str,
+            sparse_paths,
         )
         .await
     }
@@ -299,16 +304,30 @@ impl WorktreeManager {
         branch_name: &str,
         worktree_path: &Path,
         path_str: &str,
+        sparse_paths: &[String],
     ) -> Result<(), WorktreeError> {
         let git_repo_path = git_repo_path.to_path_buf();
         let branch_name = branch_name.to_string();
         let worktree_path = worktree_path.to_path_buf();
         let path_str = path_str.to_string();
+        let sparse_paths = sparse_paths.to_vec();
 
         tokio::task::spawn_blocking(move || -> Result<(), WorktreeError> {
             // Prefer git CLI for worktree add to inherit sparse-checkout semantics
             let git_service = GitService::new();
-            match git_service.add_worktree(&git_repo_path, &worktree_path, &branch_name, false) {
+            let add_worktree = || {
+                if sparse_paths.is_empty() {
+                    git_service.add_worktree(&git_repo_path, &worktree_path, &branch_name, false)
+                } else {
+                    git_service.add_sparse_worktree(
+                        &git_repo_path,
+                        &worktree_path,
+                        &branch_name,
+                        &sparse_paths,
+                    )
+                }
+            };
+            match add_worktree() {
                 Ok(()) => {
                     if !worktree_path.exists() {
                         return Err(WorktreeError::Repository(format!(
@@ -333,12 +352,7 @@ impl WorktreeManager {
                     if worktree_path.exists() {
                         std::fs::remove_dir_all(&worktree_path).map_err(WorktreeError::Io)?;
                     }
-                    if let Err(e2) = git_service.add_worktree(
-                        &git_repo_path,
-                        &worktree_path,
-                        &branch_name,
-                        false,
-                    ) {
+                    if let Err(e2) = add_worktree() {
                         return Err(WorktreeError::GitService(e2));
                     }
                     if !worktree_path.exists() {
@@ -514,6 +528,52 @@ impl WorktreeManager {
         .map_err(|e| WorktreeError::TaskJoin(format!("{e}")))?
     }
 
+    /// Create a worktree with a detached HEAD at `commit`, without creating a
+    /// branch. A non-empty `sparse_paths` checks out only that cone.
+    pub async fn create_detached_worktree(
+        repo_path: &Path,
+        worktree_path: &Path,
+        commit: &str,
+        sparse_paths: &[String],
+    ) -> Result<(), WorktreeError> {
+        let repo_path = repo_path.to_path_buf();
+        let worktree_path = worktree_path.to_path_buf();
+        let commit = commit.to_string();
+        let sparse_paths = sparse_paths.to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            if let Some(parent) = worktree_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            GitService::new()
+                .add_detached_worktree(&repo_path, &worktree_path, &commit, &sparse_paths)
+                .map_err(WorktreeError::GitService)
+        })
+        .await
+        .map_err(|e| WorktreeError::TaskJoin(format!("{e}")))?
+    }
+
+    /// Move a detached worktree to `new_path` and check out a new branch at its HEAD.
+    /// Untracked and ignored files (e.g. installed dependencies) are kept.
+    pub async fn adopt_detached_worktree(
+        repo_path: &Path,
+        old_path: &Path,
+        new_path: &Path,
+        branch_name: &str,
+    ) -> Result<(), WorktreeError> {
+        Self::move_worktree(repo_path, old_path, new_path).await?;
+
+        let new_path = new_path.to_path_buf();
+        let branch_name = branch_name.to_string();
+        tokio::task::spawn_blocking(move || {
+            GitService::new()
+                .switch_new_branch(&new_path, &branch_name)
+                .map_err(WorktreeError::GitService)
+        })
+        .await
+        .map_err(|e| WorktreeError::TaskJoin(format!("{e}")))?
+    }
+
     /// Get the base directory for vibe-kanban worktrees
     pub fn get_worktree_base_dir() -> std::path::PathBuf {
         utils::path::get_vibe_kanban_temp_dir().join("worktrees")
@@ -0,0 +1,337 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use thiserror::Error;
+use tracing::{debug, warn};
+use utils::{path::get_vibe_kanban_temp_dir, shell::get_shell_command, text::short_uuid};
+use uuid::Uuid;
+
+use super::worktree_manager::{WorktreeCleanup, WorktreeError, WorktreeManager};
+
+/// Longest a setup script may run while preparing a pooled worktree
+const SETUP_SCRIPT_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Tail of the setup script output kept in the error when it fails
+const MAX_SETUP_OUTPUT_BYTES: usize = 2000;
+
+#[derive(Debug, Error)]
+pub enum WorktreePoolError {
+    #[error(transparent)]
+    Worktree(#[from] WorktreeError),
+    #[error("Setup script failed: {0}")]
+    SetupScript(String),
+}
+
+/// Pools are kept per project repo, as setup scripts and copied files are
+/// configured per project: `(project_id, repo_id)`.
+pub type PoolKey = (Uuid, Uuid);
+
+/// A detached worktree on the tip of a target branch, with the project files
+/// copied and the setup script already run.
+#[derive(Debug, Clone)]
+pub struct PooledWorktree {
+    pub path: PathBuf,
+    pub project_id: Uuid,
+    pub repo_id: Uuid,
+    pub repo_path: PathBuf,
+    pub target_branch: String,
+    pub base_commit: String,
+    /// Setup script the worktree was prepared with
+    pub setup_script: Option<String>,
+    /// Sparse-checkout cone; empty for a full checkout
+    pub sparse_paths: Vec<String>,
+    pub created_at: Instant,
+    pub max_age: Duration,
+}
+
+impl PooledWorktree {
+    fn key(&self) -> PoolKey {
+        (self.project_id, self.repo_id)
+    }
+
+    fn is_usable(&self, target_branch: &str, base_commit: &str, sparse_paths: &[String]) -> bool {
+        self.target_branch == target_branch
+            && self.base_commit == base_commit
+            && self.sparse_paths == sparse_paths
+            && self.created_at.elapsed() < self.max_age
+    }
+}
+
+/// In-memory pool of pre-created worktrees, filled in the background by the
+/// container service and claimed when a workspace is created.
+#[derive(Clone)]
+pub struct WorktreePool {
+    ready: Arc<DashMap<PoolKey, Vec<PooledWorktree>>>,
+    /// Repos of new workspaces that got a pooled worktree, by workspace id.
+    /// Their setup script already ran and is skipped when the workspace starts.
+    prepared: Arc<DashMap<Uuid, HashSet<Uuid>>>,
+}
+
+impl WorktreePool {
+    pub fn new() -> Self {
+        Self {
+            ready: Arc::new(DashMap::new()),
+            prepared: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Directory holding pooled worktrees, outside the workspace base dir so
+    /// orphan cleanup never mistakes them for workspaces
+    pub fn base_dir() -> PathBuf {
+        get_vibe_kanban_temp_dir().join("worktree-pool")
+    }
+
+    pub fn new_worktree_path(repo_name: &str) -> PathBuf {
+        Self::base_dir().join(format!("{repo_name}-{}", short_uuid(&Uuid::new_v4())))
+    }
+
+    pub fn add(&self, worktree: PooledWorktree) {
+        self.ready.entry(worktree.key()).or_default().push(worktree);
+    }
+
+    pub fn ready_count(&self, key: PoolKey) -> usize {
+        self.ready.get(&key).map(|v| v.len()).unwrap_or(0)
+    }
+
+    /// Take a worktree of the project repo that is on `base_commit` of
+    /// `target_branch`, has the same sparse-checkout cone and is not older than
+    /// its max age.
+    pub fn claim(
+        &self,
+        project_id: Uuid,
+        repo_id: Uuid,
+        target_branch: &str,
+        base_commit: &str,
+        sparse_paths: &[String],
+    ) -> Option<PooledWorktree> {
+        let mut entries = self.ready.get_mut(&(project_id, repo_id))?;
+        let idx = entries
+            .iter()
+            .rposition(|w| w.is_usable(target_branch, base_commit, sparse_paths))?;
+        Some(entries.remove(idx))
+    }
+
+    /// Remove worktrees that are no longer on the tip of `target_branch`, were
+    /// prepared with a different setup script or sparse-checkout cone, or are
+    /// older than `max_age`. The remaining ones adopt `max_age`.
+    pub fn drain_stale(
+        &self,
+        key: PoolKey,
+        target_branch: &str,
+        base_commit: &str,
+        setup_script: Option<&str>,
+        sparse_paths: &[String],
+        max_age: Duration,
+    ) -> Vec<PooledWorktree> {
+        let Some(mut entries) = self.ready.get_mut(&key) else {
+            return Vec::new();
+        };
+        let (keep, stale): (Vec<_>, Vec<_>) = std::mem::take(&mut *entries)
+            .into_iter()
+            .map(|mut w| {
+                w.max_age = max_age;
+                w
+            })
+            .partition(|w| {
+                w.is_usable(target_branch, base_commit, sparse_paths)
+                    && w.setup_script.as_deref() == setup_script
+            });
+        *entries = keep;
+        stale
+    }
+
+    /// Remove the oldest worktrees beyond `size`
+    pub fn drain_excess(&self, key: PoolKey, size: usize) -> Vec<PooledWorktree> {
+        let Some(mut entries) = self.ready.get_mut(&key) else {
+            return Vec::new();
+        };
+        let excess = entries.len().saturating_sub(size);
+        entries.drain(..excess).collect()
+    }
+
+    /// Remove the pools of project repos that no longer have one configured
+    pub fn drain_unconfigured(&self, configured: &HashSet<PoolKey>) -> Vec<PooledWorktree> {
+        let keys: Vec<PoolKey> = self
+            .ready
+            .iter()
+            .map(|e| *e.key())
+            .filter(|key| !configured.contains(key))
+            .collect();
+        keys.into_iter()
+            .filter_map(|key| self.ready.remove(&key))
+            .flat_map(|(_, entries)| entries)
+            .collect()
+    }
+
+    pub fn mark_prepared(&self, workspace_id: Uuid, repo_ids: HashSet<Uuid>) {
+        if !repo_ids.is_empty() {
+            self.prepared.insert(workspace_id, repo_ids);
+        }
+    }
+
+    /// Repos of the workspace whose setup script already ran in the pool
+    pub fn take_prepared(&self, workspace_id: Uuid) -> HashSet<Uuid> {
+        self.prepared
+            .remove(&workspace_id)
+            .map(|(_, repo_ids)| repo_ids)
+            .unwrap_or_default()
+    }
+
+    /// Remove pooled worktrees from disk and from their repository
+    pub async fn discard(worktrees: Vec<PooledWorktree>) {
+        for worktree in worktrees {
+            debug!("Discarding pooled worktree {}", worktree.path.display());
+            let cleanup = WorktreeCleanup::new(worktree.path.clone(), Some(worktree.repo_path));
+            if let Err(e) = WorktreeManager::cleanup_worktree(&cleanup).await {
+                warn!(
+                    "Failed to remove pooled worktree {}: {}",
+                    worktree.path.display(),
+                    e
+                );
+            }
+        }
+    }
+
+    /// Remove worktrees left over from a previous run; the pool itself is not
+    /// persisted.
+    pub async fn clear_base_dir() {
+        let base_dir = Self::base_dir();
+        let Ok(entries) = std::fs::read_dir(&base_dir) else {
+            return;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.is_dir()
+                && let Err(e) = WorktreeManager::cleanup_suspected_worktree(&path).await
+            {
+                warn!(
+                    "Failed to clean up pooled worktree {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+        if let Err(e) = tokio::fs::remove_dir_all(&base_dir).await {
+            debug!("Could not remove worktree pool directory: {}", e);
+        }
+    }
+}
+
+impl Default for WorktreePool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run a repo's setup script in a pooled worktree
+pub async fn run_setup_script(worktree_path: &Path, script: &str) -> Result<(), WorktreePoolError> {
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let child = tokio::process::Command::new(shell_cmd)
+        .arg(shell_arg)
+        .arg(script)
+        .current_dir(worktree_path)
+        .kill_on_drop(true)
+        .output();
+
+    let output = tokio::time::timeout(SETUP_SCRIPT_TIMEOUT, child)
+        .await
+        .map_err(|_| {
+            WorktreePoolError::SetupScript(format!(
+                "timed out after {}s",
+                SETUP_SCRIPT_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(|e| WorktreePoolError::SetupScript(e.to_string()))?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut start = stderr.len().saturating_sub(MAX_SETUP_OUTPUT_BYTES);
+    while !stderr.is_char_boundary(start) {
+        start += 1;
+    }
+    Err(WorktreePoolError::SetupScript(format!(
+        "{}: {}",
+        output.status,
+        stderr[start..].trim()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pooled(key: PoolKey, commit: &str, age: Duration) -> PooledWorktree {
+        PooledWorktree {
+            path: PathBuf::from(format!("/tmp/pool/{commit}")),
+            project_id: key.0,
+            repo_id: key.1,
+            repo_path: PathBuf::from("/tmp/repo"),
+            target_branch: "main".to_string(),
+            base_commit: commit.to_string(),
+            setup_script: None,
+            sparse_paths: Vec::new(),
+            created_at: Instant::now() - age,
+            max_age: Duration::from_secs(3600),
+        }
+    }
+
+    #[test]
+    fn claim_only_returns_worktrees_on_the_current_tip() {
+        let pool = WorktreePool::new();
+        let key = (Uuid::new_v4(), Uuid::new_v4());
+        pool.add(pooled(key, "old", Duration::ZERO));
+        pool.add(pooled(key, "tip", Duration::from_secs(7200)));
+
+        assert!(pool.claim(key.0, key.1, "main", "tip", &[]).is_none());
+        assert!(pool.claim(key.0, key.1, "dev", "old", &[]).is_none());
+        assert!(
+            pool.claim(Uuid::new_v4(), key.1, "main", "old", &[])
+                .is_none()
+        );
+
+        let cone = vec!["src".to_string()];
+        assert!(pool.claim(key.0, key.1, "main", "old", &cone).is_none());
+
+        let claimed = pool.claim(key.0, key.1, "main", "old", &[]).unwrap();
+        assert_eq!(claimed.base_commit, "old");
+        assert_eq!(pool.ready_count(key), 1);
+    }
+
+    #[test]
+    fn refresh_drains_moved_expired_and_excess_worktrees() {
+        let pool = WorktreePool::new();
+        let key = (Uuid::new_v4(), Uuid::new_v4());
+        pool.add(pooled(key, "old", Duration::ZERO));
+        pool.add(pooled(key, "tip", Duration::from_secs(600)));
+        pool.add(pooled(key, "tip", Duration::ZERO));
+        pool.add(pooled(key, "tip", Duration::ZERO));
+
+        let stale = pool.drain_stale(key, "main", "tip", None, &[], Duration::from_secs(300));
+        assert_eq!(stale.len(), 2);
+        assert_eq!(pool.ready_count(key), 2);
+
+        assert_eq!(pool.drain_excess(key, 1).len(), 1);
+        assert_eq!(pool.ready_count(key), 1);
+
+        let stale = pool.drain_stale(
+            key,
+            "main",
+            "tip",
+            Some("npm ci"),
+            &[],
+            Duration::from_secs(300),
+        );
+        assert_eq!(stale.len(), 1);
+
+        pool.add(pooled(key, "tip", Duration::ZERO));
+        assert_eq!(pool.drain_unconfigured(&HashSet::new()).len(), 1);
+        assert_eq!(pool.ready_count(key), 0);
+    }
+}
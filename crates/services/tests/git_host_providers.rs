@@ -0,0 +1,710 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Json, Router,
+    extract::{Path, Request, State},
+    middleware::{self, Next},
+    response::Response,
+    routing::get,
+};
+use db::models::{
+    merge::{CiStatus, MergeStatus},
+    project::ProjectGitHost,
+};
+use serde_json::{Value, json};
+use services::services::git_host::{
+    CreatePrRequest, GitHostConfig, GitHostError, GitHostKind, GitHostProvider, GitHostRepoInfo,
+    GitLabProvider, GiteaProvider, HostSettings, UnifiedPrComment, host_settings, resolve_kind,
+    resolve_provider, validate_token_secret,
+};
+
+/// Requests seen by the stub as `METHOD path?query` plus the auth headers
+#[derive(Clone, Default)]
+struct Seen {
+    requests: Arc<Mutex<Vec<String>>>,
+    auth: Arc<Mutex<Vec<String>>>,
+    bodies: Arc<Mutex<Vec<Value>>>,
+}
+
+async fn record(State(seen): State<Seen>, req: Request, next: Next) -> Response {
+    seen.requests
+        .lock()
+        .unwrap()
+        .push(format!("{} {}", req.method(), req.uri()));
+    for name in ["authorization", "private-token"] {
+        if let Some(value) = req.headers().get(name) {
+            seen.auth
+                .lock()
+                .unwrap()
+                .push(format!("{name}: {}", value.to_str().unwrap()));
+        }
+    }
+    next.run(req).await
+}
+
+async fn spawn_stub(router: Router<Seen>, seen: Seen) -> String {
+    let app = router
+        .layer(middleware::from_fn_with_state(seen.clone(), record))
+        .with_state(seen);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn pr_request(draft: bool) -> CreatePrRequest {
+    CreatePrRequest {
+        title: "Add feature".to_string(),
+        body: Some("Details".to_string()),
+        head_branch: "vk/feature".to_string(),
+        base_branch: "main".to_string(),
+        draft: Some(draft),
+    }
+}
+
+#[test]
+fn git_host_repo_info_parses_remote_and_pr_urls() {
+    let info = GitHostRepoInfo::from_remote_url("https://github.com/owner/repo.git").unwrap();
+    assert_eq!(info.host, "github.com");
+    assert_eq!(info.full_name(), "owner/repo");
+    assert_eq!(info.web_base, "https://github.com");
+
+    let info = GitHostRepoInfo::from_remote_url("git@github.com:owner/repo.git").unwrap();
+    assert_eq!(info.host, "github.com");
+    assert_eq!(info.full_name(), "owner/repo");
+
+    // GitLab subgroups, with an SSH port that must not leak into the web URL
+    let info =
+        GitHostRepoInfo::from_remote_url("ssh://git@gitlab.example.com:2222/group/sub/repo.git")
+            .unwrap();
+    assert_eq!(info.host, "gitlab.example.com");
+    assert_eq!(info.owner, "group/sub");
+    assert_eq!(info.repo_name, "repo");
+    assert_eq!(info.web_base, "https://gitlab.example.com");
+
+    let info = GitHostRepoInfo::from_remote_url(
+        "https://gitlab.example.com/group/sub/repo/-/merge_requests/7",
+    )
+    .unwrap();
+    assert_eq!(info.full_name(), "group/sub/repo");
+
+    let info =
+        GitHostRepoInfo::from_remote_url("http://gitea.local:3000/owner/repo/pulls/3").unwrap();
+    assert_eq!(info.full_name(), "owner/repo");
+    assert_eq!(info.web_base, "http://gitea.local:3000");
+
+    let info = GitHostRepoInfo::from_remote_url("https://github.com/owner/repo/pull/123").unwrap();
+    assert_eq!(info.full_name(), "owner/repo");
+
+    assert!(matches!(
+        GitHostRepoInfo::from_remote_url("not a remote"),
+        Err(GitHostError::InvalidRemoteUrl(_))
+    ));
+}
+
+#[test]
+fn provider_kind_from_hostname_or_config() {
+    assert_eq!(GitHostKind::detect("github.com"), Some(GitHostKind::GitHub));
+    assert_eq!(GitHostKind::detect("gitlab.com"), Some(GitHostKind::GitLab));
+    assert_eq!(
+        GitHostKind::detect("gitlab.corp.example"),
+        Some(GitHostKind::GitLab)
+    );
+    assert_eq!(
+        GitHostKind::detect("codeberg.org"),
+        Some(GitHostKind::Gitea)
+    );
+    assert_eq!(GitHostKind::detect("git.example.com"), None);
+
+    let repo = GitHostRepoInfo::from_remote_url("git@git.example.com:team/app.git").unwrap();
+    assert_eq!(resolve_kind(&repo, &[], None), None);
+    assert!(matches!(
+        resolve_provider(&repo, &host_settings(&repo, &[], None), None),
+        Err(GitHostError::UnsupportedHost(host)) if host == "git.example.com"
+    ));
+
+    let hosts = vec![GitHostConfig {
+        host: "GIT.example.com".to_string(),
+        provider: GitHostKind::GitLab,
+        api_url: None,
+        token_secret: Some("GIT_HOST_GITLAB_CORP_TOKEN".to_string()),
+        token: None,
+    }];
+    assert_eq!(resolve_kind(&repo, &hosts, None), Some(GitHostKind::GitLab));
+    let settings = host_settings(&repo, &hosts, None);
+    assert_eq!(
+        settings.token_secret.as_deref(),
+        Some("GIT_HOST_GITLAB_CORP_TOKEN")
+    );
+    let provider = resolve_provider(&repo, &settings, Some("secret".to_string())).unwrap();
+    assert_eq!(provider.kind(), GitHostKind::GitLab);
+}
+
+#[test]
+fn project_git_host_wins_over_config_and_detection() {
+    let repo = GitHostRepoInfo::from_remote_url("git@gitlab.com:team/app.git").unwrap();
+    let hosts = vec![GitHostConfig {
+        host: "gitlab.com".to_string(),
+        provider: GitHostKind::GitLab,
+        api_url: Some("https://gitlab.com/api/v4".to_string()),
+        token_secret: Some("GIT_HOST_GITLAB_TOKEN".to_string()),
+        token: None,
+    }];
+    let project = ProjectGitHost {
+        provider: GitHostKind::Gitea,
+        api_url: Some("https://mirror.example.com/api/v1".to_string()),
+        token_secret: None,
+    };
+
+    // The override replaces the entry as a whole, token secret included
+    assert_eq!(
+        host_settings(&repo, &hosts, Some(&project)),
+        HostSettings {
+            kind: Some(GitHostKind::Gitea),
+            api_url: Some("https://mirror.example.com/api/v1".to_string()),
+            token_secret: None,
+        }
+    );
+    assert_eq!(
+        resolve_kind(&repo, &[], Some(&project)),
+        Some(GitHostKind::Gitea)
+    );
+    assert_eq!(
+        resolve_kind(&repo, &[], None),
+        Some(GitHostKind::GitLab),
+        "detected from the hostname without an override"
+    );
+}
+
+#[test]
+fn plaintext_tokens_move_to_a_secret_named_after_the_host() {
+    let entry = GitHostConfig {
+        host: "git.example.com:8443".to_string(),
+        provider: GitHostKind::Gitea,
+        api_url: None,
+        token_secret: None,
+        token: Some("legacy".to_string()),
+    };
+    assert_eq!(
+        entry.default_token_secret(),
+        "GIT_HOST_GIT_EXAMPLE_COM_8443_TOKEN"
+    );
+
+    // Older configs still load, but the token is never written back out
+    let legacy: GitHostConfig = serde_json::from_value(json!({
+        "host": "gitlab.example.com",
+        "provider": "gitlab",
+        "api_url": null,
+        "token": "glpat-legacy"
+    }))
+    .unwrap();
+    assert_eq!(legacy.token.as_deref(), Some("glpat-legacy"));
+    assert_eq!(legacy.token_secret, None);
+    let migrated = GitHostConfig {
+        token: None,
+        token_secret: Some(legacy.default_token_secret()),
+        ..legacy
+    };
+    let value = serde_json::to_value(&migrated).unwrap();
+    assert_eq!(value.get("token"), None);
+    assert_eq!(value["token_secret"], "GIT_HOST_GITLAB_EXAMPLE_COM_TOKEN");
+}
+
+#[test]
+fn token_secrets_need_the_git_host_prefix() {
+    assert!(validate_token_secret("GIT_HOST_GITLAB_CORP_TOKEN").is_ok());
+    assert!(matches!(
+        validate_token_secret("GITLAB_TOKEN"),
+        Err(GitHostError::InvalidTokenSecret(name)) if name == "GITLAB_TOKEN"
+    ));
+    assert!(validate_token_secret("git_host_lowercase").is_err());
+}
+
+#[tokio::test]
+async fn gitlab_provider_against_stub() {
+    let seen = Seen::default();
+    let router = Router::new()
+        .route(
+            "/api/v4/projects/{project}/merge_requests",
+            get(|Path(project): Path<String>| async move {
+                assert_eq!(project, "group/sub/repo");
+                Json(json!([
+                    {"iid": 4, "web_url": "https://gitlab.example.com/group/sub/repo/-/merge_requests/4",
+                     "state": "closed", "merged_at": null, "merge_commit_sha": null}
+                ]))
+            })
+            .post(
+                |State(seen): State<Seen>, Json(body): Json<Value>| async move {
+                    seen.bodies.lock().unwrap().push(body);
+                    Json(json!({
+                        "iid": 5,
+                        "web_url": "https://gitlab.example.com/group/sub/repo/-/merge_requests/5",
+                        "state": "opened",
+                        "merged_at": null,
+                        "merge_commit_sha": null
+                    }))
+                },
+            ),
+        )
+        .route(
+            "/api/v4/projects/{project}/merge_requests/{iid}",
+            get(|| async {
+                Json(json!({
+                    "iid": 5,
+                    "web_url": "https://gitlab.example.com/group/sub/repo/-/merge_requests/5",
+                    "state": "merged",
+                    "merged_at": "2025-01-02T03:04:05Z",
+                    "merge_commit_sha": null,
+                    "squash_commit_sha": "abc123"
+                }))
+            }),
+        )
+        .route(
+            "/api/v4/projects/{project}/merge_requests/{iid}/notes",
+            get(|| async {
+                Json(json!([
+                    {"id": 1, "body": "added 1 commit", "author": {"username": "bot"},
+                     "created_at": "2025-01-01T00:00:00Z", "system": true},
+                    {"id": 2, "body": "Looks good", "author": {"username": "alice"},
+                     "created_at": "2025-01-01T01:00:00Z", "system": false},
+                    {"id": 3, "body": "Rename this", "author": {"username": "bob"},
+                     "created_at": "2025-01-01T00:30:00Z", "system": false, "type": "DiffNote",
+                     "position": {"new_path": "src/lib.rs", "old_path": "src/lib.rs",
+                                  "new_line": 12, "old_line": null}}
+                ]))
+            })
+            .post(
+                |State(seen): State<Seen>, Json(body): Json<Value>| async move {
+                    seen.bodies.lock().unwrap().push(body);
+                    Json(json!({"id": 4}))
+                },
+            ),
+        );
+    let base = spawn_stub(router, seen.clone()).await;
+
+    let repo =
+        GitHostRepoInfo::from_remote_url("git@gitlab.example.com:group/sub/repo.git").unwrap();
+    let provider =
+        GitLabProvider::new(&format!("{base}/api/v4"), Some("glpat".to_string())).unwrap();
+
+    let created = provider.create_pr(&repo, &pr_request(true)).await.unwrap();
+    assert_eq!(created.number, 5);
+    assert!(matches!(created.status, MergeStatus::Open));
+    let body = seen.bodies.lock().unwrap()[0].clone();
+    assert_eq!(body["title"], "Draft: Add feature");
+    assert_eq!(body["source_branch"], "vk/feature");
+    assert_eq!(body["target_branch"], "main");
+    assert_eq!(body["description"], "Details");
+
+    let status = provider.get_pr_status(&repo, 5).await.unwrap();
+    assert!(matches!(status.status, MergeStatus::Merged));
+    assert_eq!(status.merge_commit_sha.as_deref(), Some("abc123"));
+
+    let listed = provider
+        .list_prs_for_branch(&repo, "vk/feature")
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert!(matches!(listed[0].status, MergeStatus::Closed));
+
+    let comments = provider.get_pr_comments(&repo, 5).await.unwrap();
+    assert_eq!(comments.len(), 2, "system notes are skipped");
+    match &comments[0] {
+        UnifiedPrComment::Review {
+            author,
+            path,
+            line,
+            url,
+            ..
+        } => {
+            assert_eq!(author, "bob");
+            assert_eq!(path, "src/lib.rs");
+            assert_eq!(*line, Some(12));
+            assert_eq!(
+                url,
+                "https://gitlab.example.com/group/sub/repo/-/merge_requests/5#note_3"
+            );
+        }
+        other => panic!("expected review comment first, got {other:?}"),
+    }
+    assert!(matches!(&comments[1], UnifiedPrComment::General { author, .. } if author == "alice"));
+
+    provider
+        .post_pr_comment(&repo, 5, "Addressed")
+        .await
+        .unwrap();
+    assert_eq!(seen.bodies.lock().unwrap()[1]["body"], "Addressed");
+
+    let requests = seen.requests.lock().unwrap().clone();
+    assert!(requests.iter().any(|r| {
+        r.starts_with("GET /api/v4/projects/group%2Fsub%2Frepo/merge_requests?")
+            && r.contains("source_branch=vk%2Ffeature")
+            && r.contains("state=all")
+    }));
+    assert!(
+        seen.auth
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|a| a == "private-token: glpat")
+    );
+}
+
+#[tokio::test]
+async fn gitea_provider_against_stub() {
+    let seen = Seen::default();
+    let router = Router::new()
+        .route(
+            "/api/v1/repos/{owner}/{repo}/pulls",
+            get(|| async {
+                Json(json!([
+                    {"number": 1, "html_url": "http://gitea.local/owner/repo/pulls/1", "state": "open",
+                     "merged": false, "merged_at": null, "merge_commit_sha": null, "head": {"ref": "other"}},
+                    {"number": 2, "html_url": "http://gitea.local/owner/repo/pulls/2", "state": "closed",
+                     "merged": true, "merged_at": "2025-01-02T03:04:05Z", "merge_commit_sha": "def456",
+                     "head": {"ref": "vk/feature"}}
+                ]))
+            })
+            .post(
+                |State(seen): State<Seen>, Json(body): Json<Value>| async move {
+                    seen.bodies.lock().unwrap().push(body);
+                    Json(json!({
+                        "number": 3, "html_url": "http://gitea.local/owner/repo/pulls/3",
+                        "state": "open", "merged": false, "merged_at": null,
+                        "merge_commit_sha": null, "head": {"ref": "vk/feature"}
+                    }))
+                },
+            ),
+        )
+        .route(
+            "/api/v1/repos/{owner}/{repo}/pulls/{number}",
+            get(|| async {
+                Json(json!({
+                    "number": 3, "html_url": "http://gitea.local/owner/repo/pulls/3",
+                    "state": "closed", "merged": false, "merged_at": null,
+                    "merge_commit_sha": "ignored", "head": {"ref": "vk/feature"}
+                }))
+            }),
+        )
+        .route(
+            "/api/v1/repos/{owner}/{repo}/issues/{number}/comments",
+            get(|| async {
+                Json(json!([
+                    {"id": 10, "user": {"login": "alice"}, "body": "Nice",
+                     "created_at": "2025-01-01T02:00:00Z", "html_url": "http://gitea.local/c/10"}
+                ]))
+            }),
+        )
+        .route(
+            "/api/v1/repos/{owner}/{repo}/pulls/{number}/reviews",
+            get(|| async {
+                Json(json!([
+                    {"id": 7, "comments_count": 1},
+                    {"id": 8, "comments_count": 0}
+                ]))
+            }),
+        )
+        .route(
+            "/api/v1/repos/{owner}/{repo}/pulls/{number}/reviews/{review}/comments",
+            get(|Path((_, _, _, review)): Path<(String, String, i64, i64)>| async move {
+                assert_eq!(review, 7, "reviews without comments are not fetched");
+                Json(json!([
+                    {"id": 11, "user": {"login": "bob"}, "body": "Typo",
+                     "created_at": "2025-01-01T01:00:00Z", "html_url": "http://gitea.local/c/11",
+                     "path": "README.md", "position": 0, "original_position": 4,
+                     "diff_hunk": "@@ -1 +1 @@"}
+                ]))
+            }),
+        );
+    let base = spawn_stub(router, seen.clone()).await;
+
+    let repo = GitHostRepoInfo::from_remote_url("http://gitea.local/owner/repo.git").unwrap();
+    let provider = GiteaProvider::new(&format!("{base}/api/v1"), Some("tok".to_string())).unwrap();
+
+    let created = provider.create_pr(&repo, &pr_request(true)).await.unwrap();
+    assert_eq!(created.number, 3);
+    let body = seen.bodies.lock().unwrap()[0].clone();
+    assert_eq!(body["title"], "WIP: Add feature");
+    assert_eq!(body["head"], "vk/feature");
+    assert_eq!(body["base"], "main");
+
+    let status = provider.get_pr_status(&repo, 3).await.unwrap();
+    assert!(matches!(status.status, MergeStatus::Closed));
+    assert_eq!(status.merge_commit_sha, None);
+
+    let listed = provider
+        .list_prs_for_branch(&repo, "vk/feature")
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].number, 2);
+    assert!(matches!(listed[0].status, MergeStatus::Merged));
+    assert_eq!(listed[0].merge_commit_sha.as_deref(), Some("def456"));
+
+    let comments = provider.get_pr_comments(&repo, 3).await.unwrap();
+    assert_eq!(comments.len(), 2);
+    match &comments[0] {
+        UnifiedPrComment::Review { id, path, line, .. } => {
+            assert_eq!(*id, 11);
+            assert_eq!(path, "README.md");
+            assert_eq!(*line, Some(4));
+        }
+        other => panic!("expected review comment first, got {other:?}"),
+    }
+
+    assert!(
+        seen.auth
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|a| a == "authorization: token tok")
+    );
+}
+
+#[tokio::test]
+async fn github_rest_provider_against_stub() {
+    let seen = Seen::default();
+    let router = Router::new()
+        .route(
+            "/repos/{owner}/{repo}/pulls",
+            get(|| async {
+                Json(json!([
+                    {"number": 9, "html_url": "https://github.com/owner/repo/pull/9", "state": "open",
+                     "merged_at": null, "merge_commit_sha": "test-merge"}
+                ]))
+            })
+            .post(
+                |State(seen): State<Seen>, Json(body): Json<Value>| async move {
+                    seen.bodies.lock().unwrap().push(body);
+                    Json(json!({
+                        "number": 9, "html_url": "https://github.com/owner/repo/pull/9",
+                        "state": "open", "merged_at": null, "merge_commit_sha": null
+                    }))
+                },
+            ),
+        )
+        .route(
+            "/repos/{owner}/{repo}/pulls/{number}",
+            get(|| async {
+                Json(json!({
+                    "number": 9, "html_url": "https://github.com/owner/repo/pull/9",
+                    "state": "closed", "merged_at": "2025-01-02T03:04:05Z",
+                    "merge_commit_sha": "789abc", "head": {"sha": "head123"}
+                }))
+            }),
+        )
+        .route(
+            "/repos/{owner}/{repo}/commits/{sha}/check-runs",
+            get(|Path((_, _, sha)): Path<(String, String, String)>| async move {
+                assert_eq!(sha, "head123");
+                Json(json!({"total_count": 2, "check_runs": [
+                    {"id": 31, "name": "test", "status": "completed", "conclusion": "failure",
+                     "html_url": "https://github.com/owner/repo/runs/31",
+                     "app": {"slug": "github-actions"}},
+                    {"id": 32, "name": "lint", "status": "in_progress", "conclusion": null,
+                     "html_url": null, "app": {"slug": "other-ci"}}
+                ]}))
+            }),
+        )
+        .route(
+            "/repos/{owner}/{repo}/commits/{sha}/status",
+            get(|| async {
+                Json(json!({"state": "success", "statuses": [
+                    {"context": "deploy/preview", "state": "success",
+                     "target_url": "https://ci.example.com/1"}
+                ]}))
+            }),
+        )
+        .route(
+            "/repos/{owner}/{repo}/actions/jobs/{job}/logs",
+            get(|Path((_, _, job)): Path<(String, String, String)>| async move {
+                assert_eq!(job, "31");
+                "error: test failed"
+            }),
+        )
+        .route(
+            "/repos/{owner}/{repo}/issues/{number}/comments",
+            get(|| async {
+                Json(json!([
+                    {"id": 100, "user": {"login": "alice"}, "author_association": "MEMBER",
+                     "body": "LGTM", "created_at": "2025-01-01T00:00:00Z",
+                     "html_url": "https://github.com/owner/repo/pull/9#issuecomment-100"}
+                ]))
+            }),
+        )
+        .route(
+            "/repos/{owner}/{repo}/pulls/{number}/comments",
+            get(|| async { Json(json!([])) }),
+        );
+    let base = spawn_stub(router, seen.clone()).await;
+
+    let repo = GitHostRepoInfo::from_remote_url("git@github.com:owner/repo.git").unwrap();
+    // A configured token switches GitHub from the gh CLI to the REST API
+    let hosts = vec![GitHostConfig {
+        host: "github.com".to_string(),
+        provider: GitHostKind::GitHub,
+        api_url: Some(base),
+        token_secret: Some("GIT_HOST_GITHUB_TOKEN".to_string()),
+        token: None,
+    }];
+    let settings = host_settings(&repo, &hosts, None);
+    let provider = resolve_provider(&repo, &settings, Some("ghp_test".to_string())).unwrap();
+
+    let created = provider.create_pr(&repo, &pr_request(true)).await.unwrap();
+    assert_eq!(created.number, 9);
+    let body = seen.bodies.lock().unwrap()[0].clone();
+    assert_eq!(body["draft"], true);
+    assert_eq!(body["title"], "Add feature");
+
+    let status = provider.get_pr_status(&repo, 9).await.unwrap();
+    assert!(matches!(status.status, MergeStatus::Merged));
+    assert_eq!(status.merge_commit_sha.as_deref(), Some("789abc"));
+
+    let listed = provider
+        .list_prs_for_branch(&repo, "vk/feature")
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(
+        listed[0].merge_commit_sha, None,
+        "open PRs have no merge commit"
+    );
+
+    let comments = provider.get_pr_comments(&repo, 9).await.unwrap();
+    assert!(matches!(
+        comments.as_slice(),
+        [UnifiedPrComment::General { id, author_association, .. }]
+            if id == "100" && author_association == "MEMBER"
+    ));
+
+    let checks = provider.get_pr_checks(&repo, 9).await.unwrap();
+    assert_eq!(checks.head_sha, "head123");
+    let summary: Vec<_> = checks
+        .checks
+        .iter()
+        .map(|c| (c.name.as_str(), c.status, c.job_id.as_deref()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("test", CiStatus::Failure, Some("31")),
+            ("lint", CiStatus::Pending, None),
+            ("deploy/preview", CiStatus::Success, None),
+        ]
+    );
+    assert_eq!(CiStatus::combine(&checks.checks), Some(CiStatus::Failure));
+    let log = provider
+        .get_check_log(&repo, &checks.checks[0])
+        .await
+        .unwrap();
+    assert_eq!(log.as_deref(), Some("error: test failed"));
+    assert_eq!(
+        provider
+            .get_check_log(&repo, &checks.checks[1])
+            .await
+            .unwrap(),
+        None,
+        "only GitHub Actions jobs have logs"
+    );
+
+    let requests = seen.requests.lock().unwrap().clone();
+    assert!(
+        requests
+            .iter()
+            .any(|r| r.starts_with("GET /repos/owner/repo/pulls?")
+                && r.contains("head=owner%3Avk%2Ffeature"))
+    );
+    assert!(
+        seen.auth
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|a| a == "authorization: Bearer ghp_test")
+    );
+}
+
+#[tokio::test]
+async fn gitlab_pipeline_jobs_against_stub() {
+    let seen = Seen::default();
+    let router = Router::new()
+        .route(
+            "/api/v4/projects/{project}/merge_requests/{iid}",
+            get(|| async { Json(json!({"sha": "abc123", "head_pipeline": {"id": 77}})) }),
+        )
+        .route(
+            "/api/v4/projects/{project}/pipelines/{pipeline}/jobs",
+            get(|Path((_, pipeline)): Path<(String, i64)>| async move {
+                assert_eq!(pipeline, 77);
+                Json(json!([
+                    {"id": 1, "name": "build", "status": "success", "web_url": "https://g/j/1"},
+                    {"id": 2, "name": "test", "status": "failed", "web_url": "https://g/j/2"},
+                    {"id": 3, "name": "flaky", "status": "failed", "allow_failure": true},
+                    {"id": 4, "name": "deploy", "status": "manual"}
+                ]))
+            }),
+        )
+        .route(
+            "/api/v4/projects/{project}/jobs/{job}/trace",
+            get(|| async { "\x1b[0K$ cargo test\nerror: 1 test failed\n" }),
+        );
+    let base = spawn_stub(router, seen.clone()).await;
+
+    let repo = GitHostRepoInfo::from_remote_url("https://gitlab.example.com/team/app").unwrap();
+    let provider = GitLabProvider::new(&format!("{base}/api/v4"), None).unwrap();
+
+    let checks = provider.get_pr_checks(&repo, 5).await.unwrap();
+    assert_eq!(checks.head_sha, "abc123");
+    let statuses: Vec<_> = checks.checks.iter().map(|c| c.status).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            CiStatus::Success,
+            CiStatus::Failure,
+            CiStatus::Success,
+            CiStatus::Success
+        ],
+        "allowed failures and manual jobs don't fail the pipeline"
+    );
+
+    let log = provider
+        .get_check_log(&repo, &checks.checks[1])
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(log.contains("error: 1 test failed"));
+    assert!(
+        seen.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|r| r == "GET /api/v4/projects/team%2Fapp/jobs/2/trace")
+    );
+}
+
+#[tokio::test]
+async fn unauthorized_responses_map_to_auth_failed() {
+    let seen = Seen::default();
+    let router = Router::new().route(
+        "/api/v4/projects/{project}/merge_requests/{iid}",
+        get(|| async {
+            (
+                axum::http::StatusCode::UNAUTHORIZED,
+                Json(json!({"message": "401 Unauthorized"})),
+            )
+        }),
+    );
+    let base = spawn_stub(router, seen.clone()).await;
+
+    let repo = GitHostRepoInfo::from_remote_url("https://gitlab.example.com/team/app").unwrap();
+    let provider = GitLabProvider::new(&format!("{base}/api/v4"), None).unwrap();
+    let err = provider.get_pr_status(&repo, 1).await.unwrap_err();
+    assert!(matches!(err, GitHostError::AuthFailed { .. }), "{err:?}");
+    assert!(!err.should_retry());
+    assert_eq!(
+        seen.requests.lock().unwrap().len(),
+        1,
+        "auth errors are not retried"
+    );
+}
@@ -713,6 +713,88 @@ fn rebase_refuses_to_abort_existing_rebase() {
     // Note: We do not auto-abort; user should resolve or abort explicitly
 }
 
+#[test]
+fn continue_conflicts_completes_resolved_rebase() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+
+    let svc = GitService::new();
+    let _ = svc
+        .rebase_branch(
+            &repo_path,
+            &worktree_path,
+            "new-base",
+            "old-base",
+            "feature",
+        )
+        .expect_err("rebase should stop on conflicts");
+    assert_eq!(
+        svc.get_conflicted_files(&worktree_path).unwrap(),
+        vec!["conflict.txt".to_string()]
+    );
+
+    // During a rebase "ours" is the new base and "theirs" the replayed commit
+    let sides = svc
+        .get_conflict_sides(&worktree_path, "conflict.txt")
+        .unwrap();
+    assert_eq!(sides.base.as_deref(), Some("old-base version\n"));
+    assert_eq!(sides.ours.as_deref(), Some("new-base version\n"));
+    assert_eq!(sides.theirs.as_deref(), Some("feature version\n"));
+
+    // Resolve without staging; continue_conflicts stages for us
+    write_file(&worktree_path, "conflict.txt", "resolved version\n");
+    // Leftovers of the agent's tooling must not end up in the commit
+    write_file(&worktree_path, "scratch.log", "build output\n");
+    svc.continue_conflicts(&worktree_path, &["conflict.txt".to_string()])
+        .expect("continue should succeed");
+
+    assert!(svc.detect_conflict_op(&worktree_path).unwrap().is_none());
+    let content = std::fs::read_to_string(worktree_path.join("conflict.txt")).unwrap();
+    assert_eq!(content, "resolved version\n");
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    let head = wt_repo.head().unwrap();
+    assert_eq!(head.shorthand(), Some("feature"));
+    let parent = head.peel_to_commit().unwrap().parent(0).unwrap();
+    let new_base = wt_repo
+        .find_branch("new-base", git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(parent.id(), new_base.id());
+    let tree = head.peel_to_tree().unwrap();
+    assert!(tree.get_name("scratch.log").is_none());
+    assert!(worktree_path.join("scratch.log").exists());
+}
+
+#[test]
+fn conflict_markers_detected_until_abort() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+
+    let svc = GitService::new();
+    let _ = svc
+        .rebase_branch(
+            &repo_path,
+            &worktree_path,
+            "new-base",
+            "old-base",
+            "feature",
+        )
+        .expect_err("rebase should stop on conflicts");
+
+    // git happily commits files that still contain markers once staged,
+    // so callers must check for leftover markers before continuing.
+    let markers = services::services::conflict_resolution::files_with_conflict_markers(
+        &worktree_path,
+        &["conflict.txt".to_string()],
+    );
+    assert_eq!(markers, vec!["conflict.txt".to_string()]);
+
+    svc.abort_conflicts(&worktree_path).unwrap();
+    assert!(svc.detect_conflict_op(&worktree_path).unwrap().is_none());
+}
+
 #[test]
 fn rebase_fast_forwards_when_no_unique_commits() {
     let td = TempDir::new().unwrap();
@@ -36,6 +36,18 @@ pub fn credentials_path() -> std::path::PathBuf {
     asset_dir().join("credentials.json")
 }
 
+pub fn secrets_key_path() -> std::path::PathBuf {
+    asset_dir().join("secrets.key")
+}
+
+pub fn dependency_cache_dir() -> std::path::PathBuf {
+    asset_dir().join("dependency-caches")
+}
+
+pub fn file_search_index_dir() -> std::path::PathBuf {
+    asset_dir().join("file-search-index")
+}
+
 #[derive(RustEmbed)]
 #[folder = "../../assets/sounds"]
 pub struct SoundAssets;
@@ -10,9 +10,11 @@ pub mod diff;
 pub mod git;
 pub mod jwt;
 pub mod log_msg;
+pub mod metrics;
 pub mod msg_store;
 pub mod path;
 pub mod port_file;
+pub mod redact;
 pub mod response;
 pub mod sentry;
 pub mod shell;
@@ -0,0 +1,451 @@
+//! Process-wide metrics rendered in the Prometheus text exposition format.
+//!
+//! Metrics are plain statics so any crate can record them without threading a
+//! registry through constructors. `render` encodes every metric listed in
+//! `ALL`; scrape-time gauges are set by the caller right before rendering.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+/// Buckets for short operations such as git commands, in seconds
+pub const FAST_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Buckets for agent runs and human waits, in seconds
+pub const SLOW_BUCKETS: &[f64] = &[
+    1.0, 5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1200.0, 1800.0, 3600.0, 7200.0,
+];
+
+type Labels = Vec<String>;
+
+fn key(values: &[&str]) -> Labels {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+pub struct CounterVec {
+    name: &'static str,
+    help: &'static str,
+    labels: &'static [&'static str],
+    values: Mutex<BTreeMap<Labels, u64>>,
+}
+
+impl CounterVec {
+    pub const fn new(
+        name: &'static str,
+        help: &'static str,
+        labels: &'static [&'static str],
+    ) -> Self {
+        Self {
+            name,
+            help,
+            labels,
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn inc(&self, values: &[&str]) {
+        *self.values.lock().unwrap().entry(key(values)).or_default() += 1;
+    }
+
+    fn encode(&self, out: &mut String) {
+        header(out, self.name, self.help, "counter");
+        for (values, count) in self.values.lock().unwrap().iter() {
+            sample(
+                out,
+                self.name,
+                self.labels,
+                values,
+                None,
+                &count.to_string(),
+            );
+        }
+    }
+}
+
+pub struct GaugeVec {
+    name: &'static str,
+    help: &'static str,
+    labels: &'static [&'static str],
+    values: Mutex<BTreeMap<Labels, i64>>,
+}
+
+impl GaugeVec {
+    pub const fn new(
+        name: &'static str,
+        help: &'static str,
+        labels: &'static [&'static str],
+    ) -> Self {
+        Self {
+            name,
+            help,
+            labels,
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn set(&self, values: &[&str], value: i64) {
+        self.values.lock().unwrap().insert(key(values), value);
+    }
+
+    /// Replace every series, dropping label sets that are gone
+    pub fn reset<'a>(&self, series: impl IntoIterator<Item = (Vec<&'a str>, i64)>) {
+        let mut map = self.values.lock().unwrap();
+        map.clear();
+        for (values, value) in series {
+            map.insert(key(&values), value);
+        }
+    }
+
+    pub fn add(&self, values: &[&str], delta: i64) {
+        *self.values.lock().unwrap().entry(key(values)).or_default() += delta;
+    }
+
+    /// Increment now and decrement when the guard drops
+    pub fn track(&'static self, values: &[&str]) -> GaugeGuard {
+        self.add(values, 1);
+        GaugeGuard {
+            gauge: self,
+            values: key(values),
+        }
+    }
+
+    fn encode(&self, out: &mut String) {
+        header(out, self.name, self.help, "gauge");
+        for (values, value) in self.values.lock().unwrap().iter() {
+            sample(
+                out,
+                self.name,
+                self.labels,
+                values,
+                None,
+                &value.to_string(),
+            );
+        }
+    }
+}
+
+pub struct GaugeGuard {
+    gauge: &'static GaugeVec,
+    values: Labels,
+}
+
+impl Drop for GaugeGuard {
+    fn drop(&mut self) {
+        if let Some(value) = self.gauge.values.lock().unwrap().get_mut(&self.values) {
+            *value -= 1;
+        }
+    }
+}
+
+#[derive(Default)]
+struct Histogram {
+    /// Non-cumulative count per bucket; the last slot is `+Inf`
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+pub struct HistogramVec {
+    name: &'static str,
+    help: &'static str,
+    labels: &'static [&'static str],
+    bounds: &'static [f64],
+    values: Mutex<BTreeMap<Labels, Histogram>>,
+}
+
+impl HistogramVec {
+    pub const fn new(
+        name: &'static str,
+        help: &'static str,
+        labels: &'static [&'static str],
+        bounds: &'static [f64],
+    ) -> Self {
+        Self {
+            name,
+            help,
+            labels,
+            bounds,
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn observe(&self, values: &[&str], value: f64) {
+        let mut map = self.values.lock().unwrap();
+        let histogram = map.entry(key(values)).or_default();
+        if histogram.buckets.is_empty() {
+            histogram.buckets = vec![0; self.bounds.len() + 1];
+        }
+        let slot = self
+            .bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(self.bounds.len());
+        histogram.buckets[slot] += 1;
+        histogram.sum += value;
+        histogram.count += 1;
+    }
+
+    pub fn observe_duration(&self, values: &[&str], duration: Duration) {
+        self.observe(values, duration.as_secs_f64());
+    }
+
+    /// Observe the time until the guard drops
+    pub fn start_timer(&'static self, values: &[&str]) -> HistogramTimer {
+        HistogramTimer {
+            histogram: self,
+            values: key(values),
+            start: Instant::now(),
+        }
+    }
+
+    fn encode(&self, out: &mut String) {
+        header(out, self.name, self.help, "histogram");
+        let bucket_name = format!("{}_bucket", self.name);
+        for (values, histogram) in self.values.lock().unwrap().iter() {
+            let mut cumulative = 0;
+            for (i, count) in histogram.buckets.iter().enumerate() {
+                cumulative += count;
+                let le = match self.bounds.get(i) {
+                    Some(bound) => bound.to_string(),
+                    None => "+Inf".to_string(),
+                };
+                sample(
+                    out,
+                    &bucket_name,
+                    self.labels,
+                    values,
+                    Some(&le),
+                    &cumulative.to_string(),
+                );
+            }
+            sample(
+                out,
+                &format!("{}_sum", self.name),
+                self.labels,
+                values,
+                None,
+                &histogram.sum.to_string(),
+            );
+            sample(
+                out,
+                &format!("{}_count", self.name),
+                self.labels,
+                values,
+                None,
+                &histogram.count.to_string(),
+            );
+        }
+    }
+}
+
+pub struct HistogramTimer {
+    histogram: &'static HistogramVec,
+    values: Labels,
+    start: Instant,
+}
+
+impl Drop for HistogramTimer {
+    fn drop(&mut self) {
+        let values: Vec<&str> = self.values.iter().map(String::as_str).collect();
+        self.histogram
+            .observe_duration(&values, self.start.elapsed());
+    }
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn sample(
+    out: &mut String,
+    name: &str,
+    labels: &[&str],
+    values: &[String],
+    le: Option<&str>,
+    value: &str,
+) {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .zip(values)
+        .map(|(label, value)| format!("{label}=\"{}\"", escape_label(value)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{le}\""));
+    }
+    if pairs.is_empty() {
+        let _ = writeln!(out, "{name} {value}");
+    } else {
+        let _ = writeln!(out, "{name}{{{}}} {value}", pairs.join(","));
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Label value for a serde enum, matching its API representation
+pub fn label<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => "unknown".to_string(),
+    }
+}
+
+pub static EXECUTION_PROCESSES_STARTED: CounterVec = CounterVec::new(
+    "vibe_kanban_execution_processes_started_total",
+    "Execution processes started",
+    &["executor", "run_reason"],
+);
+
+pub static EXECUTION_PROCESSES_FINISHED: CounterVec = CounterVec::new(
+    "vibe_kanban_execution_processes_finished_total",
+    "Execution processes that exited, by final status",
+    &["executor", "run_reason", "status"],
+);
+
+pub static EXECUTION_DURATION: HistogramVec = HistogramVec::new(
+    "vibe_kanban_execution_duration_seconds",
+    "Wall time of finished execution processes",
+    &["executor", "run_reason", "status"],
+    SLOW_BUCKETS,
+);
+
+pub static RUNNING_EXECUTION_PROCESSES: GaugeVec = GaugeVec::new(
+    "vibe_kanban_running_execution_processes",
+    "Execution processes currently running",
+    &["run_reason"],
+);
+
+pub static RUNNING_AGENTS: GaugeVec = GaugeVec::new(
+    "vibe_kanban_running_agents",
+    "Coding agents currently running",
+    &[],
+);
+
+pub static APPROVAL_WAIT: HistogramVec = HistogramVec::new(
+    "vibe_kanban_approval_wait_seconds",
+    "Time tool approvals waited for a response",
+    &["outcome"],
+    SLOW_BUCKETS,
+);
+
+pub static QUEUED_FOLLOW_UPS: GaugeVec = GaugeVec::new(
+    "vibe_kanban_queued_follow_ups",
+    "Follow-up messages waiting for the running agent to finish",
+    &[],
+);
+
+pub static GIT_OPERATION_DURATION: HistogramVec = HistogramVec::new(
+    "vibe_kanban_git_operation_duration_seconds",
+    "Latency of git operations",
+    &["backend", "operation"],
+    FAST_BUCKETS,
+);
+
+pub static MSG_STORE_BYTES: GaugeVec = GaugeVec::new(
+    "vibe_kanban_msg_store_bytes",
+    "Approximate bytes of history held by message stores",
+    &["store"],
+);
+
+pub static WEBSOCKET_SUBSCRIBERS: GaugeVec = GaugeVec::new(
+    "vibe_kanban_websocket_subscribers",
+    "Open websocket connections per stream",
+    &["stream"],
+);
+
+pub static DB_QUERY_ERRORS: CounterVec = CounterVec::new(
+    "vibe_kanban_db_query_errors_total",
+    "Database queries that returned an error",
+    &["source"],
+);
+
+enum Metric {
+    Counter(&'static CounterVec),
+    Gauge(&'static GaugeVec),
+    Histogram(&'static HistogramVec),
+}
+
+static ALL: &[Metric] = &[
+    Metric::Counter(&EXECUTION_PROCESSES_STARTED),
+    Metric::Counter(&EXECUTION_PROCESSES_FINISHED),
+    Metric::Histogram(&EXECUTION_DURATION),
+    Metric::Gauge(&RUNNING_EXECUTION_PROCESSES),
+    Metric::Gauge(&RUNNING_AGENTS),
+    Metric::Histogram(&APPROVAL_WAIT),
+    Metric::Gauge(&QUEUED_FOLLOW_UPS),
+    Metric::Histogram(&GIT_OPERATION_DURATION),
+    Metric::Gauge(&MSG_STORE_BYTES),
+    Metric::Gauge(&WEBSOCKET_SUBSCRIBERS),
+    Metric::Counter(&DB_QUERY_ERRORS),
+];
+
+/// Encode every metric in the Prometheus text format
+pub fn render() -> String {
+    let mut out = String::new();
+    for metric in ALL {
+        match metric {
+            Metric::Counter(m) => m.encode(&mut out),
+            Metric::Gauge(m) => m.encode(&mut out),
+            Metric::Histogram(m) => m.encode(&mut out),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_renders_labels() {
+        static COUNTER: CounterVec = CounterVec::new("test_total", "Test", &["kind"]);
+        COUNTER.inc(&["a\"b"]);
+        COUNTER.inc(&["a\"b"]);
+        let mut out = String::new();
+        COUNTER.encode(&mut out);
+        assert_eq!(
+            out,
+            "# HELP test_total Test\n# TYPE test_total counter\ntest_total{kind=\"a\\\"b\"} 2\n"
+        );
+    }
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        static HISTOGRAM: HistogramVec =
+            HistogramVec::new("test_seconds", "Test", &[], &[1.0, 5.0]);
+        HISTOGRAM.observe(&[], 0.5);
+        HISTOGRAM.observe(&[], 3.0);
+        HISTOGRAM.observe(&[], 10.0);
+        let mut out = String::new();
+        HISTOGRAM.encode(&mut out);
+        assert!(out.contains("test_seconds_bucket{le=\"1\"} 1\n"));
+        assert!(out.contains("test_seconds_bucket{le=\"5\"} 2\n"));
+        assert!(out.contains("test_seconds_bucket{le=\"+Inf\"} 3\n"));
+        assert!(out.contains("test_seconds_sum 13.5\n"));
+        assert!(out.contains("test_seconds_count 3\n"));
+    }
+
+    #[test]
+    fn gauge_guard_decrements_on_drop() {
+        static GAUGE: GaugeVec = GaugeVec::new("test_open", "Test", &["stream"]);
+        let first = GAUGE.track(&["logs"]);
+        let second = GAUGE.track(&["logs"]);
+        drop(first);
+        let mut out = String::new();
+        GAUGE.encode(&mut out);
+        assert!(out.contains("test_open{stream=\"logs\"} 1\n"));
+        drop(second);
+    }
+}
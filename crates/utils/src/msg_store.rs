@@ -8,7 +8,7 @@ use futures::{StreamExt, TryStreamExt, future};
 use tokio::{sync::broadcast, task::JoinHandle};
 use tokio_stream::wrappers::BroadcastStream;
 
-use crate::{log_msg::LogMsg, stream_lines::LinesStreamExt};
+use crate::{log_msg::LogMsg, redact::Redactor, stream_lines::LinesStreamExt};
 
 // 100 MB Limit
 const HISTORY_BYTES: usize = 100000 * 1024;
@@ -27,6 +27,8 @@ struct Inner {
 pub struct MsgStore {
     inner: RwLock<Inner>,
     sender: broadcast::Sender<LogMsg>,
+    /// Masks secrets before messages reach listeners or history
+    redactor: Option<Arc<Redactor>>,
 }
 
 impl Default for MsgStore {
@@ -44,10 +46,23 @@ impl MsgStore {
                 total_bytes: 0,
             }),
             sender,
+            redactor: None,
+        }
+    }
+
+    /// A store that masks the values known to `redactor` in everything pushed to it
+    pub fn with_redactor(redactor: Arc<Redactor>) -> Self {
+        Self {
+            redactor: (!redactor.is_empty()).then_some(redactor),
+            ..Self::new()
         }
     }
 
     pub fn push(&self, msg: LogMsg) {
+        let msg = match &self.redactor {
+            Some(redactor) => redactor.redact_log_msg(msg),
+            None => msg,
+        };
         let _ = self.sender.send(msg.clone()); // live listeners
         let bytes = msg.approx_bytes();
 
@@ -87,6 +102,11 @@ impl MsgStore {
         self.sender.subscribe()
     }
 
+    /// Approximate bytes held in history
+    pub fn history_bytes(&self) -> usize {
+        self.inner.read().unwrap().total_bytes
+    }
+
     pub fn get_history(&self) -> Vec<LogMsg> {
         self.inner
             .read()
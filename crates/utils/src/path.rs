@@ -1,4 +1,7 @@
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
 
 /// Directory name for storing images in worktrees
 pub const VIBE_IMAGES_DIR: &str = ".vibe-images";
@@ -101,7 +104,20 @@ pub fn normalize_macos_private_alias<P: AsRef<Path>>(p: P) -> PathBuf {
     p.to_path_buf()
 }
 
+static TEMP_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Keep worktrees and other temporary files under `dir` instead of the shared
+/// temp directory, so a short-lived run never touches a server's worktrees.
+/// Only the first call has any effect.
+pub fn override_vibe_kanban_temp_dir(dir: PathBuf) {
+    let _ = TEMP_DIR_OVERRIDE.set(dir);
+}
+
 pub fn get_vibe_kanban_temp_dir() -> std::path::PathBuf {
+    if let Some(dir) = TEMP_DIR_OVERRIDE.get() {
+        return dir.clone();
+    }
+
     let dir_name = if cfg!(debug_assertions) {
         "vibe-kanban-dev"
     } else {
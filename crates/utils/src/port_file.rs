@@ -1,6 +1,11 @@
 use std::{env, path::PathBuf};
 
-use tokio::fs;
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncWriteExt},
+};
+
+use crate::assets::asset_dir;
 
 pub async fn write_port_file(port: u16) -> std::io::Result<PathBuf> {
     let dir = env::temp_dir().join("vibe-kanban");
@@ -24,3 +29,88 @@ pub async fn read_port_file(app_name: &str) -> std::io::Result<u16> {
 
     Ok(port)
 }
+
+/// Admin tokens live in the per-user asset dir, not the shared temp dir
+fn token_path(app_name: &str) -> PathBuf {
+    asset_dir().join(format!("{app_name}.token"))
+}
+
+/// Write the local server's admin token. The file is created readable only by
+/// the current user; an existing file is never overwritten.
+pub async fn write_token_file(app_name: &str, token: &str) -> std::io::Result<PathBuf> {
+    let path = token_path(app_name);
+    tracing::debug!("Writing admin token to {:?}", path);
+    let mut opts = fs::OpenOptions::new();
+    opts.create_new(true).write(true);
+    #[cfg(unix)]
+    opts.mode(0o600);
+    let mut file = opts.open(&path).await?;
+    file.write_all(token.as_bytes()).await?;
+    file.sync_all().await?;
+    Ok(path)
+}
+
+pub async fn remove_token_file(app_name: &str) -> std::io::Result<()> {
+    fs::remove_file(token_path(app_name)).await
+}
+
+/// Read the admin token. Files owned by another user, or readable or writable
+/// by anyone else, are rejected so a planted token is never trusted.
+pub async fn read_token_file(app_name: &str) -> std::io::Result<String> {
+    let path = token_path(app_name);
+    tracing::debug!("Reading admin token from {:?}", path);
+
+    let mut file = fs::File::open(&path).await?;
+    ensure_private(&file.metadata().await?)?;
+    let mut token = String::new();
+    file.read_to_string(&mut token).await?;
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "empty token file",
+        ));
+    }
+    Ok(token)
+}
+
+#[cfg(unix)]
+fn ensure_private(metadata: &std::fs::Metadata) -> std::io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    // SAFETY: geteuid has no preconditions and cannot fail
+    let uid = unsafe { libc::geteuid() };
+    if metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "token file is not private to the current user",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn ensure_private(_metadata: &std::fs::Metadata) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    #[test]
+    fn token_files_must_be_private() {
+        let path = env::temp_dir().join(format!("vk-test-{}.token", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "vk_token").unwrap();
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let err = ensure_private(&std::fs::metadata(&path).unwrap()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        ensure_private(&std::fs::metadata(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,180 @@
+//! Masking of secret values in process output and logs.
+
+use std::{borrow::Cow, sync::Arc};
+
+use serde_json::Value;
+
+use crate::log_msg::LogMsg;
+
+/// Replacement for every occurrence of a secret value
+pub const MASK: &str = "********";
+
+/// Shorter values are not masked; they would hide ordinary output
+pub const MIN_SECRET_LEN: usize = 4;
+
+/// Replaces known secret values with [`MASK`]
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    /// Longest first, so a secret containing another one is masked whole
+    secrets: Vec<String>,
+}
+
+impl Redactor {
+    pub fn new<I, S>(values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut secrets: Vec<String> = values
+            .into_iter()
+            .map(Into::into)
+            .filter(|s| s.len() >= MIN_SECRET_LEN)
+            .collect();
+        secrets.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        secrets.dedup();
+        Self { secrets }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secrets.is_empty()
+    }
+
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for secret in &self.secrets {
+            if text.contains(secret.as_str()) {
+                text = Cow::Owned(text.replace(secret.as_str(), MASK));
+            }
+        }
+        text
+    }
+
+    /// Mask every string in `value`, object keys included
+    pub fn redact_json(&self, value: &mut Value) {
+        match value {
+            Value::String(s) => {
+                if let Cow::Owned(redacted) = self.redact(s) {
+                    *s = redacted;
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|v| self.redact_json(v)),
+            Value::Object(map) => {
+                let entries = std::mem::take(map);
+                for (key, mut v) in entries {
+                    self.redact_json(&mut v);
+                    map.insert(self.redact(&key).into_owned(), v);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn redact_log_msg(&self, msg: LogMsg) -> LogMsg {
+        if self.is_empty() {
+            return msg;
+        }
+        match msg {
+            LogMsg::Stdout(s) => LogMsg::Stdout(self.redact(&s).into_owned()),
+            LogMsg::Stderr(s) => LogMsg::Stderr(self.redact(&s).into_owned()),
+            LogMsg::JsonPatch(patch) => {
+                let Ok(mut value) = serde_json::to_value(&patch) else {
+                    return LogMsg::JsonPatch(patch);
+                };
+                self.redact_json(&mut value);
+                serde_json::from_value(value)
+                    .map(LogMsg::JsonPatch)
+                    .unwrap_or(LogMsg::JsonPatch(patch))
+            }
+            other => other,
+        }
+    }
+
+    /// Length of the longest tail of `text` that is the start of a secret
+    fn partial_match_len(&self, text: &str) -> usize {
+        self.secrets
+            .iter()
+            .filter_map(|secret| {
+                (1..secret.len().min(text.len() + 1))
+                    .rev()
+                    .find(|&n| secret.is_char_boundary(n) && text.ends_with(&secret[..n]))
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Masks secrets in text that arrives in chunks. The tail of a chunk is held
+/// back while it could be the start of a secret that continues in the next one.
+pub struct StreamRedactor {
+    redactor: Arc<Redactor>,
+    pending: String,
+}
+
+impl StreamRedactor {
+    pub fn new(redactor: Arc<Redactor>) -> Self {
+        Self {
+            redactor,
+            pending: String::new(),
+        }
+    }
+
+    /// The masked text that is safe to emit so far
+    pub fn push(&mut self, chunk: &str) -> String {
+        if self.redactor.is_empty() {
+            return chunk.to_string();
+        }
+        self.pending.push_str(chunk);
+        let mut redacted = self.redactor.redact(&self.pending).into_owned();
+        let held = self.redactor.partial_match_len(&redacted);
+        self.pending = redacted.split_off(redacted.len() - held);
+        redacted
+    }
+
+    /// Whatever is still held back, once no more chunks follow
+    pub fn finish(&mut self) -> String {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_longest_secret_first() {
+        let redactor = Redactor::new(["sk-live-1234", "1234", "ab"]);
+        assert_eq!(
+            redactor.redact("key=sk-live-1234 pin=1234 ab"),
+            "key=******** pin=******** ab"
+        );
+        assert!(matches!(redactor.redact("nothing here"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn masks_secrets_split_across_chunks() {
+        let mut stream = StreamRedactor::new(Arc::new(Redactor::new(["hunter22"])));
+        let mut out = stream.push("password: hun");
+        out.push_str(&stream.push("ter22\nnext hu"));
+        out.push_str(&stream.push("man\n"));
+        out.push_str(&stream.finish());
+        assert_eq!(out, "password: ********\nnext human\n");
+    }
+
+    #[test]
+    fn masks_json_patches() {
+        let redactor = Redactor::new(["s3cr3t-token"]);
+        let patch: json_patch::Patch = serde_json::from_value(serde_json::json!([
+            { "op": "add", "path": "/entries/0", "value": {
+                "content": "curl -H 'Authorization: s3cr3t-token'",
+                "metadata": { "s3cr3t-token": 1 }
+            }}
+        ]))
+        .unwrap();
+        let LogMsg::JsonPatch(patch) = redactor.redact_log_msg(LogMsg::JsonPatch(patch)) else {
+            panic!("expected a patch");
+        };
+        let text = serde_json::to_string(&patch).unwrap();
+        assert!(!text.contains("s3cr3t-token"));
+        assert!(text.contains("Authorization: ********"));
+    }
+}
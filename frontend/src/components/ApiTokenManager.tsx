@@ -0,0 +1,230 @@
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Copy, Loader2, Plus, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { apiTokensApi } from '@/lib/api';
+import type { ApiToken, ApiTokenScope } from 'shared/types';
+
+const SCOPES: ApiTokenScope[] = ['read', 'tasks', 'execute', 'config'];
+
+export function ApiTokenManager() {
+  const { t } = useTranslation('settings');
+  const [tokens, setTokens] = useState<ApiToken[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [name, setName] = useState('');
+  const [scopes, setScopes] = useState<ApiTokenScope[]>(['read']);
+  const [creating, setCreating] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [createdToken, setCreatedToken] = useState<string | null>(null);
+
+  const fetchTokens = useCallback(async () => {
+    setLoading(true);
+    try {
+      setTokens(await apiTokensApi.list());
+    } catch (err) {
+      console.error('Failed to fetch API tokens:', err);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    fetchTokens();
+  }, [fetchTokens]);
+
+  const toggleScope = (scope: ApiTokenScope, checked: boolean) => {
+    setScopes((prev) =>
+      checked ? [...prev, scope] : prev.filter((s) => s !== scope)
+    );
+  };
+
+  const handleCreate = async () => {
+    setCreating(true);
+    setError(null);
+    try {
+      const result = await apiTokensApi.create({ name: name.trim(), scopes });
+      setCreatedToken(result.token);
+      setName('');
+      setScopes(['read']);
+      await fetchTokens();
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.general.apiTokens.errors.createFailed')
+      );
+    } finally {
+      setCreating(false);
+    }
+  };
+
+  const handleDelete = async (token: ApiToken) => {
+    if (
+      !confirm(
+        t('settings.general.apiTokens.deleteConfirm', { name: token.name })
+      )
+    ) {
+      return;
+    }
+    try {
+      await apiTokensApi.delete(token.id);
+      await fetchTokens();
+    } catch (err) {
+      console.error('Failed to revoke API token:', err);
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-8">
+        <Loader2 className="h-8 w-8 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      {createdToken && (
+        <Alert>
+          <AlertDescription className="space-y-2">
+            <p>{t('settings.general.apiTokens.created')}</p>
+            <div className="flex items-center gap-2">
+              <code className="flex-1 break-all rounded bg-muted px-2 py-1 text-sm">
+                {createdToken}
+              </code>
+              <Button
+                variant="ghost"
+                size="icon"
+                className="h-7 w-7"
+                onClick={() => navigator.clipboard.writeText(createdToken)}
+                title={t('settings.general.apiTokens.copy')}
+              >
+                <Copy className="h-3 w-3" />
+              </Button>
+            </div>
+          </AlertDescription>
+        </Alert>
+      )}
+
+      {tokens.length === 0 ? (
+        <div className="text-center py-4 text-muted-foreground">
+          {t('settings.general.apiTokens.noTokens')}
+        </div>
+      ) : (
+        <div className="border rounded-lg overflow-hidden">
+          <table className="w-full">
+            <thead className="border-b bg-muted/50">
+              <tr>
+                <th className="text-left p-2 text-sm font-medium">
+                  {t('settings.general.apiTokens.table.name')}
+                </th>
+                <th className="text-left p-2 text-sm font-medium">
+                  {t('settings.general.apiTokens.table.scopes')}
+                </th>
+                <th className="text-left p-2 text-sm font-medium">
+                  {t('settings.general.apiTokens.table.lastUsed')}
+                </th>
+                <th className="p-2" />
+              </tr>
+            </thead>
+            <tbody>
+              {tokens.map((token) => (
+                <tr key={token.id} className="border-b">
+                  <td className="p-2 text-sm">
+                    <div className="font-medium">{token.name}</div>
+                    <div className="font-mono text-xs text-muted-foreground">
+                      {token.token_prefix}…
+                    </div>
+                  </td>
+                  <td className="p-2 text-sm">
+                    {token.scopes
+                      .map((scope) =>
+                        t(`settings.general.apiTokens.scopes.${scope}`)
+                      )
+                      .join(', ')}
+                  </td>
+                  <td className="p-2 text-sm text-muted-foreground">
+                    {token.last_used_at
+                      ? new Date(token.last_used_at).toLocaleString()
+                      : t('settings.general.apiTokens.neverUsed')}
+                  </td>
+                  <td className="p-2">
+                    <div className="flex justify-end">
+                      <Button
+                        variant="ghost"
+                        size="icon"
+                        className="h-7 w-7"
+                        onClick={() => handleDelete(token)}
+                        title={t('settings.general.apiTokens.revoke')}
+                      >
+                        <Trash2 className="h-3 w-3" />
+                      </Button>
+                    </div>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+      )}
+
+      <div className="space-y-3 rounded-lg border p-3">
+        <div className="space-y-2">
+          <Label htmlFor="api-token-name">
+            {t('settings.general.apiTokens.nameLabel')}
+          </Label>
+          <Input
+            id="api-token-name"
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+            placeholder={t('settings.general.apiTokens.namePlaceholder')}
+          />
+        </div>
+        <div className="flex flex-wrap gap-4">
+          {SCOPES.map((scope) => (
+            <div key={scope} className="flex items-center space-x-2">
+              <Checkbox
+                id={`api-token-scope-${scope}`}
+                checked={scope === 'read' || scopes.includes(scope)}
+                disabled={scope === 'read'}
+                onCheckedChange={(checked) =>
+                  toggleScope(scope, checked === true)
+                }
+              />
+              <Label
+                htmlFor={`api-token-scope-${scope}`}
+                className="text-sm font-normal cursor-pointer"
+              >
+                {t(`settings.general.apiTokens.scopes.${scope}`)}
+              </Label>
+            </div>
+          ))}
+        </div>
+        <p className="text-sm text-muted-foreground">
+          {t('settings.general.apiTokens.scopesHelper')}
+        </p>
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+        <Button
+          size="sm"
+          onClick={handleCreate}
+          disabled={!name.trim() || creating}
+        >
+          {creating ? (
+            <Loader2 className="h-4 w-4 mr-2 animate-spin" />
+          ) : (
+            <Plus className="h-4 w-4 mr-2" />
+          )}
+          {t('settings.general.apiTokens.create')}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,177 @@
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { HardDrive, Loader2, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Badge } from '@/components/ui/badge';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { dependencyCachesApi } from '@/lib/api';
+import { formatFileSize } from '@/lib/utils';
+import type { DependencyCacheInfo, PruneDependencyCaches } from 'shared/types';
+
+const UNUSED_DAYS = 30;
+
+export function DependencyCacheManager() {
+  const { t } = useTranslation('settings');
+  const [caches, setCaches] = useState<DependencyCacheInfo[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [pruning, setPruning] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const fetchCaches = useCallback(async () => {
+    setLoading(true);
+    try {
+      setCaches(await dependencyCachesApi.list());
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.dependencyCaches.errors.load')
+      );
+    } finally {
+      setLoading(false);
+    }
+  }, [t]);
+
+  useEffect(() => {
+    fetchCaches();
+  }, [fetchCaches]);
+
+  const handlePrune = async (criteria: Partial<PruneDependencyCaches>) => {
+    setPruning(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const result = await dependencyCachesApi.prune({
+        repo_id: null,
+        superseded: false,
+        unused_for_days: null,
+        max_total_bytes: null,
+        ...criteria,
+      });
+      setMessage(
+        t('settings.dependencyCaches.pruned', {
+          count: result.removed.length,
+          size: formatFileSize(result.freed_bytes) || '0 B',
+        })
+      );
+      await fetchCaches();
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.dependencyCaches.errors.prune')
+      );
+    } finally {
+      setPruning(false);
+    }
+  };
+
+  const handleDelete = async (cache: DependencyCacheInfo) => {
+    setError(null);
+    setMessage(null);
+    try {
+      await dependencyCachesApi.delete(cache.id);
+      await fetchCaches();
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.dependencyCaches.errors.delete')
+      );
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-5 w-5 animate-spin" />
+      </div>
+    );
+  }
+
+  const totalBytes = caches.reduce((sum, cache) => sum + cache.size_bytes, 0);
+
+  return (
+    <div className="space-y-4">
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {message && (
+        <Alert>
+          <AlertDescription>{message}</AlertDescription>
+        </Alert>
+      )}
+
+      {caches.length === 0 ? (
+        <div className="text-sm text-muted-foreground">
+          {t('settings.dependencyCaches.noCaches')}
+        </div>
+      ) : (
+        <div className="divide-y rounded-lg border">
+          {caches.map((cache) => (
+            <div key={cache.id} className="flex items-center gap-2 p-2">
+              <HardDrive className="h-3 w-3 shrink-0 text-muted-foreground" />
+              <div className="min-w-0 flex-1">
+                <div className="flex items-center gap-2">
+                  <span className="truncate font-mono text-sm">
+                    {cache.repo_name ??
+                      t('settings.dependencyCaches.removedRepo')}
+                    /{cache.path}
+                  </span>
+                  {cache.superseded && (
+                    <Badge variant="secondary">
+                      {t('settings.dependencyCaches.superseded')}
+                    </Badge>
+                  )}
+                </div>
+                <div className="truncate text-xs text-muted-foreground">
+                  {t('settings.dependencyCaches.details', {
+                    size: formatFileSize(cache.size_bytes) || '0 B',
+                    keys: cache.key_files.join(', '),
+                    date: new Date(cache.last_used_at).toLocaleString(),
+                  })}
+                </div>
+              </div>
+              <Button
+                variant="ghost"
+                size="icon"
+                className="h-7 w-7"
+                onClick={() => handleDelete(cache)}
+                title={t('settings.dependencyCaches.delete')}
+              >
+                <Trash2 className="h-3 w-3" />
+              </Button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      <div className="flex flex-wrap items-center gap-2">
+        <span className="mr-auto text-sm text-muted-foreground">
+          {t('settings.dependencyCaches.total', {
+            size: formatFileSize(totalBytes) || '0 B',
+          })}
+        </span>
+        <Button
+          variant="outline"
+          size="sm"
+          disabled={pruning || caches.length === 0}
+          onClick={() => handlePrune({ superseded: true })}
+        >
+          {t('settings.dependencyCaches.pruneSuperseded')}
+        </Button>
+        <Button
+          variant="outline"
+          size="sm"
+          disabled={pruning || caches.length === 0}
+          onClick={() => handlePrune({ unused_for_days: UNUSED_DAYS })}
+        >
+          {t('settings.dependencyCaches.pruneUnused', { days: UNUSED_DAYS })}
+        </Button>
+      </div>
+    </div>
+  );
+}
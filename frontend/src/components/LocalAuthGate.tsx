@@ -0,0 +1,104 @@
+import { FormEvent, ReactNode, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { useQuery, useQueryClient } from '@tanstack/react-query';
+import { Loader2 } from 'lucide-react';
+import { localAuthApi } from '@/lib/api';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Loader } from '@/components/ui/loader';
+
+const localAuthStatusKey = ['localAuth', 'status'] as const;
+
+/**
+ * Asks for an API token before rendering the app when the server requires
+ * authentication and the browser has no valid session yet.
+ */
+export function LocalAuthGate({ children }: { children: ReactNode }) {
+  const { t } = useTranslation('common');
+  const queryClient = useQueryClient();
+  const { data: status, isLoading } = useQuery({
+    queryKey: localAuthStatusKey,
+    queryFn: localAuthApi.status,
+    staleTime: Infinity,
+  });
+  const [token, setToken] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [submitting, setSubmitting] = useState(false);
+
+  if (isLoading) {
+    return (
+      <div className="min-h-screen bg-background flex items-center justify-center">
+        <Loader message="Loading..." size={32} />
+      </div>
+    );
+  }
+
+  if (!status || !status.enabled || status.authenticated) {
+    return <>{children}</>;
+  }
+
+  const handleSubmit = async (e: FormEvent) => {
+    e.preventDefault();
+    setSubmitting(true);
+    setError(null);
+    try {
+      await localAuthApi.login(token.trim());
+      await queryClient.invalidateQueries({ queryKey: localAuthStatusKey });
+    } catch {
+      setError(t('localAuth.invalidToken'));
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  return (
+    <div className="min-h-screen bg-background flex items-center justify-center p-4">
+      <Card className="w-full max-w-md">
+        <CardHeader>
+          <CardTitle>{t('localAuth.title')}</CardTitle>
+          <CardDescription>{t('localAuth.description')}</CardDescription>
+        </CardHeader>
+        <CardContent>
+          <form onSubmit={handleSubmit} className="space-y-4">
+            <div className="space-y-2">
+              <Label htmlFor="local-auth-token">
+                {t('localAuth.tokenLabel')}
+              </Label>
+              <Input
+                id="local-auth-token"
+                type="password"
+                autoComplete="off"
+                value={token}
+                onChange={(e) => setToken(e.target.value)}
+                placeholder="vk_..."
+                autoFocus
+              />
+            </div>
+            {error && (
+              <Alert variant="destructive">
+                <AlertDescription>{error}</AlertDescription>
+              </Alert>
+            )}
+            <Button
+              type="submit"
+              className="w-full"
+              disabled={!token.trim() || submitting}
+            >
+              {submitting && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+              {t('localAuth.submit')}
+            </Button>
+          </form>
+        </CardContent>
+      </Card>
+    </div>
+  );
+}
@@ -0,0 +1,402 @@
+import { useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Loader2, Plus, Send, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { notificationsApi } from '@/lib/api';
+import type {
+  NotificationChannel,
+  NotificationChannelKind,
+  NotificationEvent,
+  WebhookPreset,
+} from 'shared/types';
+
+const EVENTS: NotificationEvent[] = [
+  'attempt_finished',
+  'attempt_failed',
+  'approval_needed',
+  'pr_merged',
+  'ci_failed',
+];
+
+const PRESETS: WebhookPreset[] = ['generic', 'slack', 'discord', 'mattermost'];
+
+// Passed as a value so i18next leaves the braces alone
+const PLACEHOLDERS =
+  '{{event}}, {{title}}, {{message}}, {{task}}, {{branch}}, {{url}}';
+
+type ChannelType = NotificationChannelKind['type'];
+
+const CHANNEL_TYPES: ChannelType[] = ['webhook', 'ntfy', 'command'];
+
+function defaultKind(type: ChannelType): NotificationChannelKind {
+  switch (type) {
+    case 'webhook':
+      return { type, url: '', preset: 'generic' };
+    case 'ntfy':
+      return { type, server: 'https://ntfy.sh', topic: '', token: null };
+    case 'command':
+      return { type, command: '' };
+  }
+}
+
+type TestResult = { index: number; error: string | null };
+
+interface NotificationChannelsEditorProps {
+  channels: NotificationChannel[];
+  onChange: (channels: NotificationChannel[]) => void;
+}
+
+export function NotificationChannelsEditor({
+  channels,
+  onChange,
+}: NotificationChannelsEditorProps) {
+  const { t } = useTranslation('settings');
+  const [testing, setTesting] = useState<number | null>(null);
+  const [testResult, setTestResult] = useState<TestResult | null>(null);
+
+  const updateChannel = (index: number, patch: Partial<NotificationChannel>) =>
+    onChange(channels.map((c, i) => (i === index ? { ...c, ...patch } : c)));
+
+  const updateKind = (
+    index: number,
+    patch: Partial<NotificationChannelKind>
+  ) =>
+    updateChannel(index, {
+      kind: { ...channels[index].kind, ...patch } as NotificationChannelKind,
+    });
+
+  const toggleEvent = (
+    index: number,
+    event: NotificationEvent,
+    checked: boolean
+  ) => {
+    const events = channels[index].events;
+    updateChannel(index, {
+      events: checked ? [...events, event] : events.filter((e) => e !== event),
+    });
+  };
+
+  const handleAdd = () =>
+    onChange([
+      ...channels,
+      {
+        name: '',
+        enabled: true,
+        events: [],
+        title_template: null,
+        message_template: null,
+        kind: defaultKind('webhook'),
+      },
+    ]);
+
+  const handleRemove = (index: number) => {
+    setTestResult(null);
+    onChange(channels.filter((_, i) => i !== index));
+  };
+
+  const handleTest = async (index: number) => {
+    setTesting(index);
+    setTestResult(null);
+    try {
+      await notificationsApi.testChannel(channels[index]);
+      setTestResult({ index, error: null });
+    } catch (err) {
+      setTestResult({
+        index,
+        error: err instanceof Error ? err.message : String(err),
+      });
+    } finally {
+      setTesting(null);
+    }
+  };
+
+  return (
+    <div className="space-y-3">
+      {channels.length === 0 && (
+        <div className="text-sm text-muted-foreground">
+          {t('settings.general.notifications.channels.empty')}
+        </div>
+      )}
+      {channels.map((channel, index) => {
+        const { kind } = channel;
+        return (
+          <div key={index} className="space-y-3 rounded-lg border p-3">
+            <div className="flex items-end gap-2">
+              <div className="flex-1 space-y-2">
+                <Label htmlFor={`channel-name-${index}`}>
+                  {t('settings.general.notifications.channels.nameLabel')}
+                </Label>
+                <Input
+                  id={`channel-name-${index}`}
+                  value={channel.name}
+                  onChange={(e) =>
+                    updateChannel(index, { name: e.target.value })
+                  }
+                  placeholder={t(
+                    'settings.general.notifications.channels.namePlaceholder'
+                  )}
+                />
+              </div>
+              <div className="w-40 space-y-2">
+                <Label htmlFor={`channel-type-${index}`}>
+                  {t('settings.general.notifications.channels.typeLabel')}
+                </Label>
+                <Select
+                  value={kind.type}
+                  onValueChange={(type: ChannelType) =>
+                    updateChannel(index, { kind: defaultKind(type) })
+                  }
+                >
+                  <SelectTrigger id={`channel-type-${index}`}>
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {CHANNEL_TYPES.map((type) => (
+                      <SelectItem key={type} value={type}>
+                        {t(
+                          `settings.general.notifications.channels.types.${type}`
+                        )}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+              </div>
+              <div className="flex items-center space-x-2 pb-2">
+                <Checkbox
+                  id={`channel-enabled-${index}`}
+                  checked={channel.enabled}
+                  onCheckedChange={(checked) =>
+                    updateChannel(index, { enabled: checked === true })
+                  }
+                />
+                <Label
+                  htmlFor={`channel-enabled-${index}`}
+                  className="text-sm font-normal cursor-pointer"
+                >
+                  {t('settings.general.notifications.channels.enabled')}
+                </Label>
+              </div>
+              <Button
+                variant="ghost"
+                size="icon"
+                onClick={() => handleTest(index)}
+                disabled={testing !== null}
+                title={t('settings.general.notifications.channels.sendTest')}
+              >
+                {testing === index ? (
+                  <Loader2 className="h-4 w-4 animate-spin" />
+                ) : (
+                  <Send className="h-4 w-4" />
+                )}
+              </Button>
+              <Button
+                variant="ghost"
+                size="icon"
+                onClick={() => handleRemove(index)}
+                title={t('settings.general.notifications.channels.remove')}
+              >
+                <Trash2 className="h-4 w-4" />
+              </Button>
+            </div>
+
+            {kind.type === 'webhook' && (
+              <div className="grid gap-3 sm:grid-cols-[1fr_10rem]">
+                <div className="space-y-2">
+                  <Label htmlFor={`channel-url-${index}`}>
+                    {t('settings.general.notifications.channels.urlLabel')}
+                  </Label>
+                  <Input
+                    id={`channel-url-${index}`}
+                    value={kind.url}
+                    onChange={(e) => updateKind(index, { url: e.target.value })}
+                    placeholder="https://hooks.slack.com/services/..."
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor={`channel-preset-${index}`}>
+                    {t('settings.general.notifications.channels.presetLabel')}
+                  </Label>
+                  <Select
+                    value={kind.preset}
+                    onValueChange={(preset: WebhookPreset) =>
+                      updateKind(index, { preset })
+                    }
+                  >
+                    <SelectTrigger id={`channel-preset-${index}`}>
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      {PRESETS.map((preset) => (
+                        <SelectItem key={preset} value={preset}>
+                          {t(
+                            `settings.general.notifications.channels.presets.${preset}`
+                          )}
+                        </SelectItem>
+                      ))}
+                    </SelectContent>
+                  </Select>
+                </div>
+              </div>
+            )}
+
+            {kind.type === 'ntfy' && (
+              <div className="grid gap-3 sm:grid-cols-3">
+                <div className="space-y-2">
+                  <Label htmlFor={`channel-server-${index}`}>
+                    {t('settings.general.notifications.channels.serverLabel')}
+                  </Label>
+                  <Input
+                    id={`channel-server-${index}`}
+                    value={kind.server}
+                    onChange={(e) =>
+                      updateKind(index, { server: e.target.value })
+                    }
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor={`channel-topic-${index}`}>
+                    {t('settings.general.notifications.channels.topicLabel')}
+                  </Label>
+                  <Input
+                    id={`channel-topic-${index}`}
+                    value={kind.topic}
+                    onChange={(e) =>
+                      updateKind(index, { topic: e.target.value })
+                    }
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor={`channel-token-${index}`}>
+                    {t('settings.general.notifications.channels.tokenLabel')}
+                  </Label>
+                  <Input
+                    id={`channel-token-${index}`}
+                    type="password"
+                    value={kind.token ?? ''}
+                    onChange={(e) =>
+                      updateKind(index, { token: e.target.value || null })
+                    }
+                    placeholder={t(
+                      'settings.general.notifications.channels.tokenPlaceholder'
+                    )}
+                  />
+                </div>
+              </div>
+            )}
+
+            {kind.type === 'command' && (
+              <div className="space-y-2">
+                <Label htmlFor={`channel-command-${index}`}>
+                  {t('settings.general.notifications.channels.commandLabel')}
+                </Label>
+                <Input
+                  id={`channel-command-${index}`}
+                  className="font-mono"
+                  value={kind.command}
+                  onChange={(e) =>
+                    updateKind(index, { command: e.target.value })
+                  }
+                  placeholder='notify-send "$VK_NOTIFICATION_TITLE" "$VK_NOTIFICATION_MESSAGE"'
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.general.notifications.channels.commandHelper')}
+                </p>
+              </div>
+            )}
+
+            <div className="flex flex-wrap gap-4">
+              {EVENTS.map((event) => (
+                <div key={event} className="flex items-center space-x-2">
+                  <Checkbox
+                    id={`channel-${index}-event-${event}`}
+                    checked={channel.events.includes(event)}
+                    onCheckedChange={(checked) =>
+                      toggleEvent(index, event, checked === true)
+                    }
+                  />
+                  <Label
+                    htmlFor={`channel-${index}-event-${event}`}
+                    className="text-sm font-normal cursor-pointer"
+                  >
+                    {t(
+                      `settings.general.notifications.channels.events.${event}`
+                    )}
+                  </Label>
+                </div>
+              ))}
+            </div>
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.notifications.channels.eventsHelper')}
+            </p>
+
+            <div className="grid gap-3 sm:grid-cols-2">
+              <div className="space-y-2">
+                <Label htmlFor={`channel-title-template-${index}`}>
+                  {t(
+                    'settings.general.notifications.channels.titleTemplateLabel'
+                  )}
+                </Label>
+                <Input
+                  id={`channel-title-template-${index}`}
+                  value={channel.title_template ?? ''}
+                  onChange={(e) =>
+                    updateChannel(index, {
+                      title_template: e.target.value || null,
+                    })
+                  }
+                  placeholder="{{title}}"
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor={`channel-message-template-${index}`}>
+                  {t(
+                    'settings.general.notifications.channels.messageTemplateLabel'
+                  )}
+                </Label>
+                <Input
+                  id={`channel-message-template-${index}`}
+                  value={channel.message_template ?? ''}
+                  onChange={(e) =>
+                    updateChannel(index, {
+                      message_template: e.target.value || null,
+                    })
+                  }
+                  placeholder="{{message}}"
+                />
+              </div>
+            </div>
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.notifications.channels.templateHelper', {
+                placeholders: PLACEHOLDERS,
+              })}
+            </p>
+
+            {testResult?.index === index && (
+              <Alert variant={testResult.error ? 'destructive' : 'default'}>
+                <AlertDescription>
+                  {testResult.error ??
+                    t('settings.general.notifications.channels.testSucceeded')}
+                </AlertDescription>
+              </Alert>
+            )}
+          </div>
+        );
+      })}
+      <Button variant="outline" size="sm" onClick={handleAdd}>
+        <Plus className="h-4 w-4 mr-2" />
+        {t('settings.general.notifications.channels.add')}
+      </Button>
+    </div>
+  );
+}
@@ -0,0 +1,216 @@
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { KeyRound, Loader2, Plus, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { secretsApi } from '@/lib/api';
+import type { Secret } from 'shared/types';
+
+interface SecretManagerProps {
+  /** Manage the secrets of this project */
+  projectId?: string;
+  /** Manage the secrets of this repository; global secrets without either */
+  repoId?: string;
+}
+
+export function SecretManager({ projectId, repoId }: SecretManagerProps) {
+  const { t } = useTranslation('settings');
+  const [secrets, setSecrets] = useState<Secret[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [name, setName] = useState('');
+  const [value, setValue] = useState('');
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [replacing, setReplacing] = useState<string | null>(null);
+  const [replacement, setReplacement] = useState('');
+
+  const fetchSecrets = useCallback(async () => {
+    setLoading(true);
+    try {
+      setSecrets(await secretsApi.list({ projectId, repoId }));
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : t('settings.secrets.errors.load')
+      );
+    } finally {
+      setLoading(false);
+    }
+  }, [projectId, repoId, t]);
+
+  useEffect(() => {
+    setReplacing(null);
+    fetchSecrets();
+  }, [fetchSecrets]);
+
+  const handleCreate = async () => {
+    setSaving(true);
+    setError(null);
+    try {
+      await secretsApi.create({
+        name: name.trim(),
+        value,
+        project_id: projectId ?? null,
+        repo_id: repoId ?? null,
+      });
+      setName('');
+      setValue('');
+      await fetchSecrets();
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : t('settings.secrets.errors.save')
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleReplace = async (secret: Secret) => {
+    setSaving(true);
+    setError(null);
+    try {
+      await secretsApi.update(secret.id, { value: replacement });
+      setReplacing(null);
+      setReplacement('');
+      await fetchSecrets();
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : t('settings.secrets.errors.save')
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleDelete = async (secret: Secret) => {
+    if (!confirm(t('settings.secrets.deleteConfirm', { name: secret.name }))) {
+      return;
+    }
+    setError(null);
+    try {
+      await secretsApi.delete(secret.id);
+      await fetchSecrets();
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : t('settings.secrets.errors.delete')
+      );
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-5 w-5 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+
+      {secrets.length === 0 ? (
+        <div className="text-sm text-muted-foreground">
+          {t('settings.secrets.noSecrets')}
+        </div>
+      ) : (
+        <div className="divide-y rounded-lg border">
+          {secrets.map((secret) => (
+            <div key={secret.id} className="space-y-2 p-2">
+              <div className="flex items-center gap-2">
+                <KeyRound className="h-3 w-3 text-muted-foreground" />
+                <span className="font-mono text-sm">{secret.name}</span>
+                <span className="text-xs text-muted-foreground">
+                  {t('settings.secrets.updated', {
+                    date: new Date(secret.updated_at).toLocaleString(),
+                  })}
+                </span>
+                <div className="ml-auto flex gap-1">
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    onClick={() => {
+                      setReplacement('');
+                      setReplacing((prev) =>
+                        prev === secret.id ? null : secret.id
+                      );
+                    }}
+                  >
+                    {t('settings.secrets.replace')}
+                  </Button>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="h-7 w-7"
+                    onClick={() => handleDelete(secret)}
+                    title={t('settings.secrets.delete')}
+                  >
+                    <Trash2 className="h-3 w-3" />
+                  </Button>
+                </div>
+              </div>
+              {replacing === secret.id && (
+                <div className="flex gap-2">
+                  <Input
+                    type="password"
+                    autoComplete="off"
+                    value={replacement}
+                    onChange={(e) => setReplacement(e.target.value)}
+                    placeholder={t('settings.secrets.valuePlaceholder')}
+                  />
+                  <Button
+                    onClick={() => handleReplace(secret)}
+                    disabled={saving || !replacement}
+                  >
+                    {t('settings.secrets.save')}
+                  </Button>
+                </div>
+              )}
+            </div>
+          ))}
+        </div>
+      )}
+
+      <div className="grid gap-3 sm:grid-cols-[1fr_1fr_auto] sm:items-end">
+        <div className="space-y-2">
+          <Label>{t('settings.secrets.nameLabel')}</Label>
+          <Input
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+            placeholder="OPENAI_API_KEY"
+            className="font-mono"
+          />
+        </div>
+        <div className="space-y-2">
+          <Label>{t('settings.secrets.valueLabel')}</Label>
+          <Input
+            type="password"
+            autoComplete="off"
+            value={value}
+            onChange={(e) => setValue(e.target.value)}
+            placeholder={t('settings.secrets.valuePlaceholder')}
+          />
+        </div>
+        <Button
+          onClick={handleCreate}
+          disabled={saving || !name.trim() || !value}
+        >
+          {saving ? (
+            <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+          ) : (
+            <Plus className="mr-2 h-4 w-4" />
+          )}
+          {t('settings.secrets.add')}
+        </Button>
+      </div>
+      <p className="text-xs text-muted-foreground">
+        {t('settings.secrets.hint')}
+      </p>
+    </div>
+  );
+}
@@ -0,0 +1,435 @@
+import { Fragment, useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import {
+  ChevronDown,
+  ChevronRight,
+  Loader2,
+  Plus,
+  RotateCcw,
+  Send,
+  Trash2,
+} from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Badge } from '@/components/ui/badge';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { webhooksApi } from '@/lib/api';
+import type { Webhook, WebhookDelivery, WebhookEventType } from 'shared/types';
+
+const EVENTS: WebhookEventType[] = [
+  'task_status_changed',
+  'execution_finished',
+  'execution_failed',
+  'approval_requested',
+  'pr_opened',
+  'pr_merged',
+];
+
+function DeliveryLog({ webhook }: { webhook: Webhook }) {
+  const { t } = useTranslation('settings');
+  const [deliveries, setDeliveries] = useState<WebhookDelivery[] | null>(
+    null
+  );
+
+  const fetchDeliveries = useCallback(async () => {
+    try {
+      setDeliveries(await webhooksApi.deliveries(webhook.id));
+    } catch (err) {
+      console.error('Failed to fetch webhook deliveries:', err);
+      setDeliveries([]);
+    }
+  }, [webhook.id]);
+
+  useEffect(() => {
+    fetchDeliveries();
+  }, [fetchDeliveries]);
+
+  const handleRedeliver = async (delivery: WebhookDelivery) => {
+    try {
+      await webhooksApi.redeliver(webhook.id, delivery.id);
+      await fetchDeliveries();
+    } catch (err) {
+      console.error('Failed to redeliver webhook event:', err);
+    }
+  };
+
+  if (deliveries === null) {
+    return <Loader2 className="h-4 w-4 animate-spin" />;
+  }
+  if (deliveries.length === 0) {
+    return (
+      <div className="text-sm text-muted-foreground">
+        {t('settings.general.webhooks.noDeliveries')}
+      </div>
+    );
+  }
+  return (
+    <div className="space-y-1">
+      {deliveries.map((delivery) => (
+        <div
+          key={delivery.id}
+          className="flex items-center gap-2 text-xs font-mono"
+        >
+          <Badge
+            variant={delivery.status === 'failed' ? 'destructive' : 'secondary'}
+          >
+            {t(`settings.general.webhooks.status.${delivery.status}`)}
+          </Badge>
+          <span>{delivery.event_type}</span>
+          <span className="text-muted-foreground">
+            {new Date(delivery.created_at).toLocaleString()}
+          </span>
+          {delivery.response_status !== null && (
+            <span>HTTP {String(delivery.response_status)}</span>
+          )}
+          {delivery.last_error && delivery.status !== 'succeeded' && (
+            <span className="truncate text-destructive">
+              {delivery.last_error}
+            </span>
+          )}
+          {delivery.status === 'failed' && (
+            <Button
+              variant="ghost"
+              size="icon"
+              className="ml-auto h-6 w-6"
+              onClick={() => handleRedeliver(delivery)}
+              title={t('settings.general.webhooks.redeliver')}
+            >
+              <RotateCcw className="h-3 w-3" />
+            </Button>
+          )}
+        </div>
+      ))}
+    </div>
+  );
+}
+
+export function WebhookManager() {
+  const { t } = useTranslation('settings');
+  const [webhooks, setWebhooks] = useState<Webhook[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [name, setName] = useState('');
+  const [url, setUrl] = useState('');
+  const [secret, setSecret] = useState('');
+  const [events, setEvents] = useState<WebhookEventType[]>([]);
+  const [creating, setCreating] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [expanded, setExpanded] = useState<string | null>(null);
+  const [testing, setTesting] = useState<string | null>(null);
+  const [testResult, setTestResult] = useState<WebhookDelivery | null>(null);
+
+  const fetchWebhooks = useCallback(async () => {
+    setLoading(true);
+    try {
+      setWebhooks(await webhooksApi.list());
+    } catch (err) {
+      console.error('Failed to fetch webhooks:', err);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    fetchWebhooks();
+  }, [fetchWebhooks]);
+
+  const toggleEvent = (event: WebhookEventType, checked: boolean) => {
+    setEvents((prev) =>
+      checked ? [...prev, event] : prev.filter((e) => e !== event)
+    );
+  };
+
+  const handleCreate = async () => {
+    setCreating(true);
+    setError(null);
+    try {
+      await webhooksApi.create({
+        name: name.trim(),
+        url: url.trim(),
+        secret: secret.trim() || null,
+        events,
+        enabled: true,
+      });
+      setName('');
+      setUrl('');
+      setSecret('');
+      setEvents([]);
+      await fetchWebhooks();
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.general.webhooks.errors.createFailed')
+      );
+    } finally {
+      setCreating(false);
+    }
+  };
+
+  const handleToggleEnabled = async (webhook: Webhook, enabled: boolean) => {
+    try {
+      await webhooksApi.update(webhook.id, {
+        name: null,
+        url: null,
+        secret: null,
+        events: null,
+        enabled,
+      });
+      await fetchWebhooks();
+    } catch (err) {
+      console.error('Failed to update webhook:', err);
+    }
+  };
+
+  const handleTest = async (webhook: Webhook) => {
+    setTesting(webhook.id);
+    setTestResult(null);
+    try {
+      setTestResult(await webhooksApi.sendTest(webhook.id));
+    } catch (err) {
+      console.error('Failed to send test event:', err);
+    } finally {
+      setTesting(null);
+    }
+  };
+
+  const handleDelete = async (webhook: Webhook) => {
+    if (
+      !confirm(
+        t('settings.general.webhooks.deleteConfirm', { name: webhook.name })
+      )
+    ) {
+      return;
+    }
+    try {
+      await webhooksApi.delete(webhook.id);
+      await fetchWebhooks();
+    } catch (err) {
+      console.error('Failed to delete webhook:', err);
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-8">
+        <Loader2 className="h-8 w-8 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      {testResult && (
+        <Alert
+          variant={
+            testResult.status === 'succeeded' ? 'default' : 'destructive'
+          }
+        >
+          <AlertDescription>
+            {testResult.status === 'succeeded'
+              ? t('settings.general.webhooks.testSucceeded', {
+                  status: String(testResult.response_status),
+                })
+              : t('settings.general.webhooks.testFailed', {
+                  error: testResult.last_error ?? '',
+                })}
+          </AlertDescription>
+        </Alert>
+      )}
+
+      {webhooks.length === 0 ? (
+        <div className="text-center py-4 text-muted-foreground">
+          {t('settings.general.webhooks.noWebhooks')}
+        </div>
+      ) : (
+        <div className="border rounded-lg overflow-hidden">
+          <table className="w-full">
+            <thead className="border-b bg-muted/50">
+              <tr>
+                <th className="p-2 w-6" />
+                <th className="text-left p-2 text-sm font-medium">
+                  {t('settings.general.webhooks.table.name')}
+                </th>
+                <th className="text-left p-2 text-sm font-medium">
+                  {t('settings.general.webhooks.table.events')}
+                </th>
+                <th className="text-left p-2 text-sm font-medium">
+                  {t('settings.general.webhooks.table.enabled')}
+                </th>
+                <th className="p-2" />
+              </tr>
+            </thead>
+            <tbody>
+              {webhooks.map((webhook) => (
+                <Fragment key={webhook.id}>
+                  <tr className="border-b">
+                    <td className="p-2">
+                      <Button
+                        variant="ghost"
+                        size="icon"
+                        className="h-6 w-6"
+                        onClick={() =>
+                          setExpanded((prev) =>
+                            prev === webhook.id ? null : webhook.id
+                          )
+                        }
+                        title={t('settings.general.webhooks.deliveries')}
+                      >
+                        {expanded === webhook.id ? (
+                          <ChevronDown className="h-3 w-3" />
+                        ) : (
+                          <ChevronRight className="h-3 w-3" />
+                        )}
+                      </Button>
+                    </td>
+                    <td className="p-2 text-sm">
+                      <div className="font-medium">{webhook.name}</div>
+                      <div className="font-mono text-xs text-muted-foreground break-all">
+                        {webhook.url}
+                      </div>
+                    </td>
+                    <td className="p-2 text-sm">
+                      {webhook.events.length === 0
+                        ? t('settings.general.webhooks.allEvents')
+                        : webhook.events
+                            .map((event) =>
+                              t(`settings.general.webhooks.events.${event}`)
+                            )
+                            .join(', ')}
+                    </td>
+                    <td className="p-2">
+                      <Checkbox
+                        checked={webhook.enabled}
+                        onCheckedChange={(checked) =>
+                          handleToggleEnabled(webhook, checked === true)
+                        }
+                      />
+                    </td>
+                    <td className="p-2">
+                      <div className="flex justify-end gap-1">
+                        <Button
+                          variant="ghost"
+                          size="icon"
+                          className="h-7 w-7"
+                          onClick={() => handleTest(webhook)}
+                          disabled={testing === webhook.id}
+                          title={t('settings.general.webhooks.sendTest')}
+                        >
+                          {testing === webhook.id ? (
+                            <Loader2 className="h-3 w-3 animate-spin" />
+                          ) : (
+                            <Send className="h-3 w-3" />
+                          )}
+                        </Button>
+                        <Button
+                          variant="ghost"
+                          size="icon"
+                          className="h-7 w-7"
+                          onClick={() => handleDelete(webhook)}
+                          title={t('settings.general.webhooks.delete')}
+                        >
+                          <Trash2 className="h-3 w-3" />
+                        </Button>
+                      </div>
+                    </td>
+                  </tr>
+                  {expanded === webhook.id && (
+                    <tr className="border-b bg-muted/20">
+                      <td className="p-2" />
+                      <td className="p-2 space-y-2" colSpan={4}>
+                        <div className="text-xs text-muted-foreground">
+                          {t('settings.general.webhooks.secretLabel')}:{' '}
+                          <code>{webhook.secret}</code>
+                        </div>
+                        <DeliveryLog webhook={webhook} />
+                      </td>
+                    </tr>
+                  )}
+                </Fragment>
+              ))}
+            </tbody>
+          </table>
+        </div>
+      )}
+
+      <div className="space-y-3 rounded-lg border p-3">
+        <div className="grid gap-3 sm:grid-cols-2">
+          <div className="space-y-2">
+            <Label htmlFor="webhook-name">
+              {t('settings.general.webhooks.nameLabel')}
+            </Label>
+            <Input
+              id="webhook-name"
+              value={name}
+              onChange={(e) => setName(e.target.value)}
+              placeholder={t('settings.general.webhooks.namePlaceholder')}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="webhook-url">
+              {t('settings.general.webhooks.urlLabel')}
+            </Label>
+            <Input
+              id="webhook-url"
+              value={url}
+              onChange={(e) => setUrl(e.target.value)}
+              placeholder="https://example.com/hooks/vibe-kanban"
+            />
+          </div>
+        </div>
+        <div className="space-y-2">
+          <Label htmlFor="webhook-secret">
+            {t('settings.general.webhooks.secretLabel')}
+          </Label>
+          <Input
+            id="webhook-secret"
+            value={secret}
+            onChange={(e) => setSecret(e.target.value)}
+            placeholder={t('settings.general.webhooks.secretPlaceholder')}
+          />
+        </div>
+        <div className="flex flex-wrap gap-4">
+          {EVENTS.map((event) => (
+            <div key={event} className="flex items-center space-x-2">
+              <Checkbox
+                id={`webhook-event-${event}`}
+                checked={events.includes(event)}
+                onCheckedChange={(checked) =>
+                  toggleEvent(event, checked === true)
+                }
+              />
+              <Label
+                htmlFor={`webhook-event-${event}`}
+                className="text-sm font-normal cursor-pointer"
+              >
+                {t(`settings.general.webhooks.events.${event}`)}
+              </Label>
+            </div>
+          ))}
+        </div>
+        <p className="text-sm text-muted-foreground">
+          {t('settings.general.webhooks.eventsHelper')}
+        </p>
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+        <Button
+          size="sm"
+          onClick={handleCreate}
+          disabled={!name.trim() || !url.trim() || creating}
+        >
+          {creating ? (
+            <Loader2 className="h-4 w-4 mr-2 animate-spin" />
+          ) : (
+            <Plus className="h-4 w-4 mr-2" />
+          )}
+          {t('settings.general.webhooks.create')}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -36,7 +36,7 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
   ({ taskId }) => {
     const modal = useModal();
     const navigate = useNavigateWithSearch();
-    const { projectId } = useProject();
+    const { projectId, project } = useProject();
     const { t } = useTranslation('tasks');
     const { profiles, config } = useUserSystem();
     const { createAttempt, isCreating, error } = useAttemptCreation({
@@ -115,8 +115,14 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
           variant,
         };
       }
-      return config?.executor_profile ?? null;
-    }, [latestAttempt?.session?.executor, config?.executor_profile]);
+      return (
+        project?.default_executor_profile ?? config?.executor_profile ?? null
+      );
+    }, [
+      latestAttempt?.session?.executor,
+      project?.default_executor_profile,
+      config?.executor_profile,
+    ]);
 
     const effectiveProfile = userSelectedProfile ?? defaultProfile;
 
@@ -206,6 +206,18 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
           );
           setGhCliHelp(null);
           return;
+        } else if (
+          result.error.type === 'git_host_not_logged_in' ||
+          result.error.type === 'unsupported_git_host'
+        ) {
+          const gitHostErrorKey =
+            result.error.type === 'git_host_not_logged_in'
+              ? 'createPrDialog.errors.gitHostNotLoggedIn'
+              : 'createPrDialog.errors.unsupportedGitHost';
+
+          setError(t(gitHostErrorKey, { host: result.error.host }));
+          setGhCliHelp(null);
+          return;
         }
       }
 
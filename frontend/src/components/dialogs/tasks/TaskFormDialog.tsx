@@ -30,6 +30,7 @@ import BranchSelector from '@/components/tasks/BranchSelector';
 import RepoBranchSelector from '@/components/tasks/RepoBranchSelector';
 import { ExecutorProfileSelector } from '@/components/settings';
 import { useUserSystem } from '@/components/ConfigProvider';
+import { useProject } from '@/contexts/ProjectContext';
 import {
   useTaskImages,
   useImageUpload,
@@ -91,6 +92,9 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
   const { createTask, createAndStart, updateTask } =
     useTaskMutations(projectId);
   const { system, profiles, loading: userSystemLoading } = useUserSystem();
+  const { project } = useProject();
+  const projectProfile =
+    project?.id === projectId ? project.default_executor_profile : null;
   const { upload, uploadForTask } = useImageUpload();
   const { enableScope, disableScope } = useHotkeysContext();
 
@@ -125,7 +129,8 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
 
   // Get default form values based on mode
   const defaultValues = useMemo((): TaskFormValues => {
-    const baseProfile = system.config?.executor_profile || null;
+    const baseProfile =
+      projectProfile ?? system.config?.executor_profile ?? null;
 
     switch (mode) {
       case 'edit':
@@ -160,7 +165,13 @@ const TaskFormDialogImpl = NiceModal.create<TaskFormDialogProps>((props) => {
           autoStart: true,
         };
     }
-  }, [mode, props, system.config?.executor_profile, defaultRepoBranches]);
+  }, [
+    mode,
+    props,
+    projectProfile,
+    system.config?.executor_profile,
+    defaultRepoBranches,
+  ]);
 
   // Form submission handler
   const handleSubmit = async ({ value }: { value: TaskFormValues }) => {
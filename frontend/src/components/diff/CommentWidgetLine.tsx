@@ -36,15 +36,20 @@ export function CommentWidgetLine({
     onCancel();
   }, [setDraft, widgetKey, onCancel]);
 
-  const handleSave = useCallback(() => {
+  const handleSave = useCallback(async () => {
     if (value.trim()) {
-      addComment({
-        filePath: draft.filePath,
-        side: draft.side,
-        lineNumber: draft.lineNumber,
-        text: value.trim(),
-        codeLine: draft.codeLine,
-      });
+      try {
+        await addComment({
+          filePath: draft.filePath,
+          side: draft.side,
+          lineNumber: draft.lineNumber,
+          text: value.trim(),
+          codeLine: draft.codeLine,
+        });
+      } catch (error) {
+        console.error('Failed to save review comment', error);
+        return;
+      }
     }
     setDraft(widgetKey, null);
     onSave();
@@ -12,12 +12,20 @@ export function ReviewCommentRenderer({
   comment,
   projectId,
 }: ReviewCommentRendererProps) {
-  const { deleteComment, updateComment } = useReview();
+  const { deleteComment, updateComment, resolveComment } = useReview();
   const [isEditing, setIsEditing] = useState(false);
   const [editText, setEditText] = useState(comment.text);
 
   const handleDelete = () => {
-    deleteComment(comment.id);
+    deleteComment(comment.id).catch((error) =>
+      console.error('Failed to delete review comment', error)
+    );
+  };
+
+  const handleResolve = () => {
+    resolveComment(comment.id).catch((error) =>
+      console.error('Failed to resolve review comment', error)
+    );
   };
 
   const handleEdit = () => {
@@ -27,7 +35,9 @@ export function ReviewCommentRenderer({
 
   const handleSave = () => {
     if (editText.trim()) {
-      updateComment(comment.id, editText.trim());
+      updateComment(comment.id, editText.trim()).catch((error) =>
+        console.error('Failed to update review comment', error)
+      );
     }
     setIsEditing(false);
   };
@@ -68,13 +78,30 @@ export function ReviewCommentRenderer({
 
   return (
     <div className="border-y bg-background p-4">
+      {(comment.outdated || comment.sent) && (
+        <div className="mb-2 flex gap-2 text-xs text-muted-foreground">
+          {comment.outdated && (
+            <span className="rounded bg-muted px-2 py-0.5">Outdated</span>
+          )}
+          {comment.sent && (
+            <span className="rounded bg-muted px-2 py-0.5">
+              Sent to agent
+            </span>
+          )}
+        </div>
+      )}
       <WYSIWYGEditor
         value={comment.text}
         disabled={true}
         className="text-sm"
-        onEdit={handleEdit}
+        onEdit={comment.sent ? undefined : handleEdit}
         onDelete={handleDelete}
       />
+      <div className="mt-2">
+        <Button size="xs" variant="outline" onClick={handleResolve}>
+          Resolve
+        </Button>
+      </div>
     </div>
   );
 }
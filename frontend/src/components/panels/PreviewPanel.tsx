@@ -16,6 +16,13 @@ import { PreviewToolbar } from '@/components/tasks/TaskDetails/preview/PreviewTo
 import { NoServerContent } from '@/components/tasks/TaskDetails/preview/NoServerContent';
 import { ReadyContent } from '@/components/tasks/TaskDetails/preview/ReadyContent';
 
+// Previews are served from their own port, a different origin from the app
+function previewOrigin(previewPort: number | null) {
+  if (previewPort === null) return window.location.origin;
+  const { protocol, hostname } = window.location;
+  return `${protocol}//${hostname}:${previewPort}`;
+}
+
 export function PreviewPanel() {
   const [iframeError, setIframeError] = useState(false);
   const [isReady, setIsReady] = useState(false);
@@ -40,6 +47,7 @@ export function PreviewPanel() {
     isStopping: isStoppingDevServer,
     runningDevServer,
     latestDevServerProcess,
+    devServerInfo,
   } = useDevServer(attemptId);
 
   const logStream = useLogStream(latestDevServerProcess?.id ?? '');
@@ -49,6 +57,9 @@ export function PreviewPanel() {
     projectHasDevScript,
     projectId: projectId!,
     lastKnownUrl,
+    previewUrl: devServerInfo?.ready
+      ? `${previewOrigin(devServerInfo.preview_port)}${devServerInfo.preview_path}`
+      : undefined,
   });
 
   const handleRefresh = () => {
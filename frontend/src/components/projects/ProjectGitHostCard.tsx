@@ -0,0 +1,175 @@
+import { useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Loader2 } from 'lucide-react';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { projectsApi } from '@/lib/api';
+import type { GitHostKind, Project, ProjectGitHost } from 'shared/types';
+
+const PROVIDERS: GitHostKind[] = ['github', 'gitlab', 'gitea'];
+
+type ProviderChoice = GitHostKind | 'auto';
+
+interface ProjectGitHostCardProps {
+  project: Project;
+}
+
+export function ProjectGitHostCard({ project }: ProjectGitHostCardProps) {
+  const { t } = useTranslation('settings');
+  const [provider, setProvider] = useState<ProviderChoice>('auto');
+  const [apiUrl, setApiUrl] = useState('');
+  const [tokenSecret, setTokenSecret] = useState('');
+  const [saving, setSaving] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setProvider(project.git_host?.provider ?? 'auto');
+    setApiUrl(project.git_host?.api_url ?? '');
+    setTokenSecret(project.git_host?.token_secret ?? '');
+  }, [project.git_host]);
+
+  useEffect(() => {
+    setSaved(false);
+    setError(null);
+  }, [project.id]);
+
+  const handleSave = async () => {
+    const gitHost: ProjectGitHost | null =
+      provider === 'auto'
+        ? null
+        : {
+            provider,
+            api_url: apiUrl.trim() || null,
+            token_secret: tokenSecret.trim() || null,
+          };
+    setSaving(true);
+    setSaved(false);
+    setError(null);
+    try {
+      await projectsApi.setGitHost(project.id, gitHost);
+      setSaved(true);
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.projects.gitHost.errors.save')
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>{t('settings.projects.gitHost.title')}</CardTitle>
+        <CardDescription>
+          {t('settings.projects.gitHost.description')}
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+
+        <div className="space-y-2">
+          <Label htmlFor="git-host-provider">
+            {t('settings.projects.gitHost.providerLabel')}
+          </Label>
+          <Select
+            value={provider}
+            onValueChange={(value: ProviderChoice) => {
+              setProvider(value);
+              setSaved(false);
+            }}
+          >
+            <SelectTrigger id="git-host-provider">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="auto">
+                {t('settings.projects.gitHost.providers.auto')}
+              </SelectItem>
+              {PROVIDERS.map((kind) => (
+                <SelectItem key={kind} value={kind}>
+                  {t(`settings.projects.gitHost.providers.${kind}`)}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </div>
+
+        {provider !== 'auto' && (
+          <>
+            <div className="space-y-2">
+              <Label htmlFor="git-host-api-url">
+                {t('settings.projects.gitHost.apiUrlLabel')}
+              </Label>
+              <Input
+                id="git-host-api-url"
+                value={apiUrl}
+                onChange={(e) => {
+                  setApiUrl(e.target.value);
+                  setSaved(false);
+                }}
+                placeholder="https://gitlab.example.com/api/v4"
+              />
+              <p className="text-sm text-muted-foreground">
+                {t('settings.projects.gitHost.apiUrlHelper')}
+              </p>
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="git-host-token-secret">
+                {t('settings.projects.gitHost.tokenSecretLabel')}
+              </Label>
+              <Input
+                id="git-host-token-secret"
+                className="font-mono"
+                value={tokenSecret}
+                onChange={(e) => {
+                  setTokenSecret(e.target.value);
+                  setSaved(false);
+                }}
+                placeholder="GIT_HOST_GITLAB_TOKEN"
+              />
+              <p className="text-sm text-muted-foreground">
+                {t('settings.projects.gitHost.tokenSecretHelper')}
+              </p>
+            </div>
+          </>
+        )}
+
+        <div className="flex items-center gap-3">
+          <Button onClick={handleSave} disabled={saving}>
+            {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+            {t('settings.projects.gitHost.save')}
+          </Button>
+          {saved && (
+            <span className="text-sm text-muted-foreground">
+              {t('settings.projects.gitHost.saved')}
+            </span>
+          )}
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
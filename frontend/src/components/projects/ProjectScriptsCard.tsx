@@ -0,0 +1,481 @@
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Loader2, Pencil, Plus, Trash2 } from 'lucide-react';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { Label } from '@/components/ui/label';
+import { Input } from '@/components/ui/input';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { AutoExpandingTextarea } from '@/components/ui/auto-expanding-textarea';
+import { projectsApi } from '@/lib/api';
+import type {
+  CreateProjectScript,
+  ProjectScript,
+  Repo,
+  ScriptPipeline,
+  ScriptRequestLanguage,
+} from 'shared/types';
+
+const LANGUAGES: ScriptRequestLanguage[] = ['Bash', 'Python', 'Node'];
+const PIPELINES: ScriptPipeline[] = ['setup', 'cleanup', 'verification'];
+const NONE = 'none';
+
+interface ScriptFormState {
+  name: string;
+  script: string;
+  language: ScriptRequestLanguage;
+  repo_id: string;
+  working_dir: string;
+  env: string;
+  timeout_seconds: string;
+  depends_on: string;
+  pipeline: ScriptPipeline | typeof NONE;
+}
+
+const EMPTY_FORM: ScriptFormState = {
+  name: '',
+  script: '',
+  language: 'Bash',
+  repo_id: NONE,
+  working_dir: '',
+  env: '',
+  timeout_seconds: '',
+  depends_on: '',
+  pipeline: NONE,
+};
+
+function scriptToFormState(script: ProjectScript): ScriptFormState {
+  return {
+    name: script.name,
+    script: script.script,
+    language: script.language,
+    repo_id: script.repo_id ?? NONE,
+    working_dir: script.working_dir ?? '',
+    env: Object.entries(script.env)
+      .map(([key, value]) => `${key}=${value}`)
+      .join('\n'),
+    timeout_seconds: script.timeout_seconds?.toString() ?? '',
+    depends_on: script.depends_on.join(', '),
+    pipeline: script.pipeline ?? NONE,
+  };
+}
+
+function formStateToPayload(form: ScriptFormState): CreateProjectScript {
+  const env: Record<string, string> = {};
+  for (const line of form.env.split('\n')) {
+    const index = line.indexOf('=');
+    if (index > 0) {
+      env[line.slice(0, index).trim()] = line.slice(index + 1);
+    }
+  }
+  const timeout = parseInt(form.timeout_seconds, 10);
+  return {
+    name: form.name.trim(),
+    script: form.script,
+    language: form.language,
+    repo_id: form.repo_id === NONE ? null : form.repo_id,
+    working_dir: form.working_dir.trim(),
+    env,
+    timeout_seconds: Number.isNaN(timeout) ? null : timeout,
+    depends_on: form.depends_on
+      .split(/[,\s]+/)
+      .map((name) => name.trim())
+      .filter(Boolean),
+    pipeline: form.pipeline === NONE ? null : form.pipeline,
+  };
+}
+
+interface EditingScript {
+  // null while adding a new script
+  id: string | null;
+  form: ScriptFormState;
+}
+
+interface ProjectScriptsCardProps {
+  projectId: string;
+  repositories: Repo[];
+}
+
+export function ProjectScriptsCard({
+  projectId,
+  repositories,
+}: ProjectScriptsCardProps) {
+  const { t } = useTranslation('settings');
+  const [scripts, setScripts] = useState<ProjectScript[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [editing, setEditing] = useState<EditingScript | null>(null);
+  const [saving, setSaving] = useState(false);
+  const [deletingId, setDeletingId] = useState<string | null>(null);
+
+  const loadScripts = useCallback(async () => {
+    setLoading(true);
+    try {
+      setScripts(await projectsApi.getScripts(projectId));
+      setError(null);
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.projects.namedScripts.errors.load')
+      );
+    } finally {
+      setLoading(false);
+    }
+  }, [projectId, t]);
+
+  useEffect(() => {
+    setEditing(null);
+    loadScripts();
+  }, [loadScripts]);
+
+  const updateForm = (updates: Partial<ScriptFormState>) => {
+    setEditing((prev) =>
+      prev ? { ...prev, form: { ...prev.form, ...updates } } : prev
+    );
+  };
+
+  const handleSave = async () => {
+    if (!editing) return;
+    setSaving(true);
+    setError(null);
+    try {
+      const payload = formStateToPayload(editing.form);
+      if (editing.id) {
+        await projectsApi.updateScript(projectId, editing.id, payload);
+      } else {
+        await projectsApi.createScript(projectId, payload);
+      }
+      setEditing(null);
+      await loadScripts();
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.projects.namedScripts.errors.save')
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleDelete = async (scriptId: string) => {
+    setDeletingId(scriptId);
+    setError(null);
+    try {
+      await projectsApi.deleteScript(projectId, scriptId);
+      await loadScripts();
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.projects.namedScripts.errors.delete')
+      );
+    } finally {
+      setDeletingId(null);
+    }
+  };
+
+  const repoName = (repoId: string | null) =>
+    repositories.find((repo) => repo.id === repoId)?.display_name;
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>{t('settings.projects.namedScripts.title')}</CardTitle>
+        <CardDescription>
+          {t('settings.projects.namedScripts.description')}
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+
+        {loading ? (
+          <div className="flex items-center justify-center py-4">
+            <Loader2 className="h-5 w-5 animate-spin" />
+          </div>
+        ) : (
+          <div className="space-y-2">
+            {scripts.map((script) => (
+              <div
+                key={script.id}
+                className="flex items-center justify-between p-3 border rounded-md"
+              >
+                <div className="min-w-0 flex-1">
+                  <div className="font-medium font-mono">{script.name}</div>
+                  <div className="text-sm text-muted-foreground truncate">
+                    {[
+                      script.language,
+                      repoName(script.repo_id),
+                      script.pipeline &&
+                        t(
+                          `settings.projects.namedScripts.pipeline.${script.pipeline}`
+                        ),
+                      script.depends_on.length > 0 &&
+                        t('settings.projects.namedScripts.dependsOnSummary', {
+                          names: script.depends_on.join(', '),
+                        }),
+                    ]
+                      .filter(Boolean)
+                      .join(' · ')}
+                  </div>
+                </div>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  onClick={() =>
+                    setEditing({
+                      id: script.id,
+                      form: scriptToFormState(script),
+                    })
+                  }
+                  title={t('settings.projects.namedScripts.edit')}
+                >
+                  <Pencil className="h-4 w-4" />
+                </Button>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  onClick={() => handleDelete(script.id)}
+                  disabled={deletingId === script.id}
+                  title={t('settings.projects.namedScripts.delete')}
+                >
+                  {deletingId === script.id ? (
+                    <Loader2 className="h-4 w-4 animate-spin" />
+                  ) : (
+                    <Trash2 className="h-4 w-4" />
+                  )}
+                </Button>
+              </div>
+            ))}
+
+            {scripts.length === 0 && !editing && (
+              <div className="text-center py-4 text-sm text-muted-foreground">
+                {t('settings.projects.namedScripts.empty')}
+              </div>
+            )}
+
+            {!editing && (
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={() => setEditing({ id: null, form: EMPTY_FORM })}
+                className="w-full"
+              >
+                <Plus className="h-4 w-4 mr-2" />
+                {t('settings.projects.namedScripts.add')}
+              </Button>
+            )}
+          </div>
+        )}
+
+        {editing && (
+          <div className="space-y-4 p-4 border rounded-md">
+            <div className="grid grid-cols-2 gap-4">
+              <div className="space-y-2">
+                <Label htmlFor="named-script-name">
+                  {t('settings.projects.namedScripts.name.label')}
+                </Label>
+                <Input
+                  id="named-script-name"
+                  value={editing.form.name}
+                  onChange={(e) => updateForm({ name: e.target.value })}
+                  placeholder="db:migrate"
+                  className="font-mono"
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="named-script-language">
+                  {t('settings.projects.namedScripts.language')}
+                </Label>
+                <Select
+                  value={editing.form.language}
+                  onValueChange={(value) =>
+                    updateForm({ language: value as ScriptRequestLanguage })
+                  }
+                >
+                  <SelectTrigger id="named-script-language">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {LANGUAGES.map((language) => (
+                      <SelectItem key={language} value={language}>
+                        {language}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+              </div>
+            </div>
+
+            <div className="space-y-2">
+              <Label htmlFor="named-script-script">
+                {t('settings.projects.namedScripts.script')}
+              </Label>
+              <AutoExpandingTextarea
+                id="named-script-script"
+                value={editing.form.script}
+                onChange={(e) => updateForm({ script: e.target.value })}
+                placeholder="npm run db:migrate"
+                maxRows={12}
+                className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md focus:outline-none focus:ring-2 focus:ring-ring font-mono"
+              />
+            </div>
+
+            <div className="grid grid-cols-2 gap-4">
+              <div className="space-y-2">
+                <Label htmlFor="named-script-repo">
+                  {t('settings.projects.namedScripts.repository')}
+                </Label>
+                <Select
+                  value={editing.form.repo_id}
+                  onValueChange={(value) => updateForm({ repo_id: value })}
+                >
+                  <SelectTrigger id="named-script-repo">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value={NONE}>
+                      {t('settings.projects.namedScripts.workspaceRoot')}
+                    </SelectItem>
+                    {repositories.map((repo) => (
+                      <SelectItem key={repo.id} value={repo.id}>
+                        {repo.display_name}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="named-script-working-dir">
+                  {t('settings.projects.namedScripts.workingDir')}
+                </Label>
+                <Input
+                  id="named-script-working-dir"
+                  value={editing.form.working_dir}
+                  onChange={(e) => updateForm({ working_dir: e.target.value })}
+                  placeholder="packages/api"
+                  className="font-mono"
+                />
+              </div>
+            </div>
+
+            <div className="grid grid-cols-2 gap-4">
+              <div className="space-y-2">
+                <Label htmlFor="named-script-depends-on">
+                  {t('settings.projects.namedScripts.dependsOn.label')}
+                </Label>
+                <Input
+                  id="named-script-depends-on"
+                  value={editing.form.depends_on}
+                  onChange={(e) => updateForm({ depends_on: e.target.value })}
+                  placeholder="install, db:migrate"
+                  className="font-mono"
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="named-script-pipeline">
+                  {t('settings.projects.namedScripts.pipeline.label')}
+                </Label>
+                <Select
+                  value={editing.form.pipeline}
+                  onValueChange={(value) =>
+                    updateForm({
+                      pipeline: value as ScriptFormState['pipeline'],
+                    })
+                  }
+                >
+                  <SelectTrigger id="named-script-pipeline">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value={NONE}>
+                      {t('settings.projects.namedScripts.pipeline.none')}
+                    </SelectItem>
+                    {PIPELINES.map((pipeline) => (
+                      <SelectItem key={pipeline} value={pipeline}>
+                        {t(
+                          `settings.projects.namedScripts.pipeline.${pipeline}`
+                        )}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+              </div>
+            </div>
+            <p className="text-sm text-muted-foreground">
+              {t('settings.projects.namedScripts.pipeline.helper')}
+            </p>
+
+            <div className="grid grid-cols-2 gap-4">
+              <div className="space-y-2">
+                <Label htmlFor="named-script-env">
+                  {t('settings.projects.namedScripts.env')}
+                </Label>
+                <AutoExpandingTextarea
+                  id="named-script-env"
+                  value={editing.form.env}
+                  onChange={(e) => updateForm({ env: e.target.value })}
+                  placeholder="DATABASE_URL=sqlite://dev.db"
+                  maxRows={6}
+                  className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md focus:outline-none focus:ring-2 focus:ring-ring font-mono"
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="named-script-timeout">
+                  {t('settings.projects.namedScripts.timeout')}
+                </Label>
+                <Input
+                  id="named-script-timeout"
+                  type="number"
+                  min={1}
+                  value={editing.form.timeout_seconds}
+                  onChange={(e) =>
+                    updateForm({ timeout_seconds: e.target.value })
+                  }
+                  placeholder="600"
+                />
+              </div>
+            </div>
+
+            <div className="flex justify-end gap-2">
+              <Button
+                variant="outline"
+                onClick={() => setEditing(null)}
+                disabled={saving}
+              >
+                {t('settings.projects.namedScripts.cancel')}
+              </Button>
+              <Button
+                onClick={handleSave}
+                disabled={
+                  saving || !editing.form.name.trim() || !editing.form.script
+                }
+              >
+                {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+                {t('settings.projects.namedScripts.save')}
+              </Button>
+            </div>
+          </div>
+        )}
+      </CardContent>
+    </Card>
+  );
+}
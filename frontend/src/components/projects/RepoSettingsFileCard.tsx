@@ -0,0 +1,234 @@
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Loader2 } from 'lucide-react';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { useUserSystem } from '@/components/ConfigProvider';
+import { mcpServersApi, projectsApi } from '@/lib/api';
+import type {
+  Project,
+  RepoSettingsFileStatus,
+  SettingsFileMode,
+} from 'shared/types';
+
+interface RepoSettingsFileCardProps {
+  project: Project;
+  repoId: string;
+  onApplied: () => void;
+}
+
+export function RepoSettingsFileCard({
+  project,
+  repoId,
+  onApplied,
+}: RepoSettingsFileCardProps) {
+  const { t } = useTranslation('settings');
+  const { config } = useUserSystem();
+  const [status, setStatus] = useState<RepoSettingsFileStatus | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [mcpInstalled, setMcpInstalled] = useState(false);
+
+  const load = useCallback(async () => {
+    setLoading(true);
+    setError(null);
+    try {
+      setStatus(await projectsApi.getSettingsFile(project.id, repoId));
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.projects.settingsFile.errors.load')
+      );
+    } finally {
+      setLoading(false);
+    }
+  }, [project.id, repoId, t]);
+
+  useEffect(() => {
+    setMcpInstalled(false);
+    load();
+  }, [load]);
+
+  const chooseMode = async (mode: SettingsFileMode) => {
+    setSaving(true);
+    setError(null);
+    try {
+      setStatus(
+        await projectsApi.setSettingsFileMode(project.id, repoId, { mode })
+      );
+      if (mode === 'repo_file') onApplied();
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.projects.settingsFile.errors.save')
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const executor =
+    project.default_executor_profile?.executor ??
+    config?.executor_profile?.executor;
+  const mcpServers = project.mcp_servers ?? {};
+  const hasMcpServers = Object.keys(mcpServers).length > 0;
+
+  const installMcpServers = async () => {
+    if (!executor) return;
+    setSaving(true);
+    setError(null);
+    try {
+      const current = await mcpServersApi.load({ executor });
+      await mcpServersApi.save(
+        { executor },
+        { servers: { ...current.mcp_config.servers, ...mcpServers } }
+      );
+      setMcpInstalled(true);
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.projects.settingsFile.errors.mcp')
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>{t('settings.projects.settingsFile.title')}</CardTitle>
+        <CardDescription>
+          {t('settings.projects.settingsFile.description')}
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+
+        {loading || !status ? (
+          <div className="flex items-center justify-center py-4">
+            <Loader2 className="h-5 w-5 animate-spin" />
+          </div>
+        ) : status.error ? (
+          <Alert variant="destructive">
+            <AlertDescription>{status.error}</AlertDescription>
+          </Alert>
+        ) : !status.file_name ? (
+          <p className="text-sm text-muted-foreground">
+            {t('settings.projects.settingsFile.notFound', {
+              branch: status.branch,
+            })}
+          </p>
+        ) : (
+          <>
+            <p className="text-sm text-muted-foreground">
+              {t('settings.projects.settingsFile.found', {
+                file: status.file_name,
+                branch: status.branch,
+              })}{' '}
+              {t(
+                `settings.projects.settingsFile.mode.${status.mode ?? 'unset'}`
+              )}
+            </p>
+
+            {status.differences.length === 0 ? (
+              <p className="text-sm">
+                {t('settings.projects.settingsFile.inSync')}
+              </p>
+            ) : (
+              <div className="space-y-2">
+                {status.differences.map((difference) => (
+                  <div
+                    key={difference.key}
+                    className="rounded-md border p-3 space-y-2"
+                  >
+                    <div className="text-sm font-medium font-mono">
+                      {difference.key}
+                    </div>
+                    <div className="grid gap-2 sm:grid-cols-2">
+                      <div>
+                        <div className="text-xs text-muted-foreground">
+                          {t('settings.projects.settingsFile.repoFile')}
+                        </div>
+                        <pre className="text-xs font-mono whitespace-pre-wrap break-all">
+                          {difference.repo_file}
+                        </pre>
+                      </div>
+                      <div>
+                        <div className="text-xs text-muted-foreground">
+                          {t('settings.projects.settingsFile.local')}
+                        </div>
+                        <pre className="text-xs font-mono whitespace-pre-wrap break-all">
+                          {difference.local ??
+                            t('settings.projects.settingsFile.notSet')}
+                        </pre>
+                      </div>
+                    </div>
+                  </div>
+                ))}
+              </div>
+            )}
+
+            <div className="flex flex-wrap gap-2">
+              <Button
+                onClick={() => chooseMode('repo_file')}
+                disabled={
+                  saving ||
+                  (status.mode === 'repo_file' &&
+                    status.differences.length === 0)
+                }
+              >
+                {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+                {t('settings.projects.settingsFile.useRepoFile')}
+              </Button>
+              <Button
+                variant="outline"
+                onClick={() => chooseMode('local')}
+                disabled={saving || status.mode === 'local'}
+              >
+                {t('settings.projects.settingsFile.keepLocal')}
+              </Button>
+            </div>
+          </>
+        )}
+
+        {hasMcpServers && executor && (
+          <div className="flex items-center justify-between gap-2 border-t pt-4">
+            <p className="text-sm text-muted-foreground">
+              {mcpInstalled
+                ? t('settings.projects.settingsFile.mcpInstalled', {
+                    executor,
+                  })
+                : t('settings.projects.settingsFile.mcpServers', {
+                    names: Object.keys(mcpServers).join(', '),
+                  })}
+            </p>
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={installMcpServers}
+              disabled={saving || mcpInstalled}
+            >
+              {t('settings.projects.settingsFile.installMcp', { executor })}
+            </Button>
+          </div>
+        )}
+      </CardContent>
+    </Card>
+  );
+}
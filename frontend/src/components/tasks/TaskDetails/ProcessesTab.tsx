@@ -66,6 +66,8 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
 
   const getStatusIcon = (status: ExecutionProcessStatus) => {
     switch (status) {
+      case 'queued':
+        return <Clock className="h-4 w-4 text-amber-500" />;
       case 'running':
         return <Play className="h-4 w-4 text-blue-500" />;
       case 'completed':
@@ -81,6 +83,8 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
 
   const getStatusColor = (status: ExecutionProcessStatus) => {
     switch (status) {
+      case 'queued':
+        return 'bg-amber-50 border-amber-200 text-amber-800';
       case 'running':
         return 'bg-blue-50 border-blue-200 text-blue-800';
       case 'completed':
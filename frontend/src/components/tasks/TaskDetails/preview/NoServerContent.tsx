@@ -87,6 +87,12 @@ export function NoServerContent({
           dev_script: script,
           dev_script_working_dir: project.dev_script_working_dir ?? null,
           default_agent_working_dir: project.default_agent_working_dir ?? null,
+          auto_review_follow_ups: null,
+          auto_fix_ci: null,
+          dev_server_ports: null,
+          dev_server_ready_pattern: null,
+          default_executor_profile: null,
+          mcp_servers: null,
         },
       },
       {
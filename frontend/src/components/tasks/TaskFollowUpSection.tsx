@@ -99,7 +99,12 @@ export function TaskFollowUpSection({
   const { branch: attemptBranch, refetch: refetchAttemptBranch } =
     useAttemptBranch(workspaceId);
   const { profiles } = useUserSystem();
-  const { comments, generateReviewMarkdown, clearComments } = useReview();
+  const {
+    pendingComments,
+    generateReviewMarkdown,
+    clearComments,
+    sendReview,
+  } = useReview();
   const {
     generateMarkdown: generateClickedMarkdown,
     clearElements: clearClickedElements,
@@ -321,10 +326,10 @@ export function TaskFollowUpSection({
       sessionId,
       message: localMessage,
       conflictMarkdown: conflictResolutionInstructions,
-      reviewMarkdown,
+      hasReviewComments: pendingComments.length > 0,
       clickedMarkdown,
       selectedVariant,
-      clearComments,
+      sendReview,
       clearClickedElements,
       onAfterSendCleanup: () => {
         cancelDebouncedSave(); // Cancel any pending debounced save to avoid race condition
@@ -412,10 +417,15 @@ export function TaskFollowUpSection({
     const parts = [
       conflictResolutionInstructions,
       clickedMarkdown,
-      reviewMarkdown,
       localMessage,
     ].filter(Boolean);
     const combinedMessage = parts.join('\n\n');
+    if (reviewMarkdown) {
+      // The server queues the review prompt behind the running agent
+      await sendReview(combinedMessage);
+      await refreshQueueStatus();
+      return;
+    }
     await queueMessage(combinedMessage, selectedVariant);
   }, [
     localMessage,
@@ -424,6 +434,8 @@ export function TaskFollowUpSection({
     clickedMarkdown,
     selectedVariant,
     queueMessage,
+    sendReview,
+    refreshQueueStatus,
     cancelDebouncedSave,
     saveToScratch,
   ]);
@@ -886,7 +898,7 @@ export function TaskFollowUpSection({
             </div>
           ) : (
             <div className="flex items-center gap-2">
-              {comments.length > 0 && (
+              {pendingComments.length > 0 && (
                 <Button
                   onClick={clearComments}
                   size="sm"
@@ -7,6 +7,8 @@ import {
   AlertTriangle,
   CheckCircle,
   ExternalLink,
+  Loader2,
+  XCircle,
 } from 'lucide-react';
 import { Button } from '@/components/ui/button.tsx';
 import {
@@ -19,6 +21,7 @@ import { useCallback, useMemo, useState } from 'react';
 import type {
   RepoBranchStatus,
   Merge,
+  PrCiStatus,
   TaskWithAttemptStatus,
   Workspace,
 } from 'shared/types';
@@ -272,6 +275,47 @@ function GitOperations({
     ? 'flex flex-wrap items-center gap-2'
     : 'shrink-0 flex flex-wrap items-center gap-2 overflow-y-hidden overflow-x-visible max-h-8';
 
+  const renderCiChip = (ci: PrCiStatus) => {
+    const failing = ci.checks
+      .filter((check) => check.status === 'failure')
+      .map((check) => check.name);
+    const style = {
+      pending:
+        'bg-amber-100/60 dark:bg-amber-900/30 text-amber-700 dark:text-amber-300',
+      success:
+        'bg-emerald-100/70 dark:bg-emerald-900/30 text-emerald-700 dark:text-emerald-300',
+      failure:
+        'bg-red-100/60 dark:bg-red-900/30 text-red-700 dark:text-red-300',
+    }[ci.status];
+    const Icon = {
+      pending: Loader2,
+      success: CheckCircle,
+      failure: XCircle,
+    }[ci.status];
+
+    return (
+      <TooltipProvider>
+        <Tooltip>
+          <TooltipTrigger asChild>
+            <span
+              className={`inline-flex items-center gap-1 px-2 py-0.5 rounded-full ${style}`}
+            >
+              <Icon
+                className={`h-3.5 w-3.5 ${ci.status === 'pending' ? 'animate-spin' : ''}`}
+              />
+              {t(`git.ci.${ci.status}`)}
+            </span>
+          </TooltipTrigger>
+          <TooltipContent side="bottom">
+            {failing.length > 0
+              ? t('git.ci.failingChecks', { checks: failing.join(', ') })
+              : t('git.ci.checkCount', { count: ci.checks.length })}
+          </TooltipContent>
+        </Tooltip>
+      </TooltipProvider>
+    );
+  };
+
   const statusChips = (
     <div className="flex items-center gap-2 text-xs min-w-0 overflow-hidden whitespace-nowrap">
       {(() => {
@@ -308,19 +352,22 @@ function GitOperations({
         if (mergeInfo.hasOpenPR && mergeInfo.openPR?.type === 'pr') {
           const prMerge = mergeInfo.openPR;
           return (
-            <button
-              onClick={() => window.open(prMerge.pr_info.url, '_blank')}
-              className="inline-flex items-center gap-1 px-2 py-0.5 rounded-full bg-sky-100/60 dark:bg-sky-900/30 text-sky-700 dark:text-sky-300 hover:underline truncate max-w-[180px] sm:max-w-none"
-              aria-label={t('git.pr.open', {
-                number: Number(prMerge.pr_info.number),
-              })}
-            >
-              <GitPullRequest className="h-3.5 w-3.5" />
-              {t('git.pr.number', {
-                number: Number(prMerge.pr_info.number),
-              })}
-              <ExternalLink className="h-3.5 w-3.5" />
-            </button>
+            <>
+              <button
+                onClick={() => window.open(prMerge.pr_info.url, '_blank')}
+                className="inline-flex items-center gap-1 px-2 py-0.5 rounded-full bg-sky-100/60 dark:bg-sky-900/30 text-sky-700 dark:text-sky-300 hover:underline truncate max-w-[180px] sm:max-w-none"
+                aria-label={t('git.pr.open', {
+                  number: Number(prMerge.pr_info.number),
+                })}
+              >
+                <GitPullRequest className="h-3.5 w-3.5" />
+                {t('git.pr.number', {
+                  number: Number(prMerge.pr_info.number),
+                })}
+                <ExternalLink className="h-3.5 w-3.5" />
+              </button>
+              {prMerge.ci && renderCiChip(prMerge.ci)}
+            </>
           );
         }
 
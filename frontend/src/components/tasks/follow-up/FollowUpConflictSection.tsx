@@ -30,10 +30,14 @@ export function FollowUpConflictSection({
   const op = repoWithConflicts?.conflict_op ?? null;
   const openInEditor = useOpenInEditor(workspaceId);
   const repoId = repoWithConflicts?.repo_id;
-  const { abortConflicts } = useAttemptConflicts(workspaceId, repoId);
+  const { abortConflicts, resolveWithAgent } = useAttemptConflicts(
+    workspaceId,
+    repoId
+  );
 
   // write using setAborting and read through abortingRef in async handlers
   const [aborting, setAborting] = useState(false);
+  const [resolving, setResolving] = useState(false);
   const abortingRef = useRef(false);
   useEffect(() => {
     abortingRef.current = aborting;
@@ -49,7 +53,18 @@ export function FollowUpConflictSection({
         conflictedFiles={repoWithConflicts.conflicted_files || []}
         op={op}
         onResolve={onResolve}
-        enableResolve={enableResolve && !aborting}
+        onResolveWithAgent={async () => {
+          if (!workspaceId || resolving) return;
+          try {
+            setResolving(true);
+            await resolveWithAgent();
+          } catch (e) {
+            console.error('Failed to start conflict resolution', e);
+          } finally {
+            setResolving(false);
+          }
+        }}
+        enableResolve={enableResolve && !aborting && !resolving}
         onOpenEditor={() => {
           if (!workspaceId) return;
           const first = repoWithConflicts.conflicted_files?.[0];
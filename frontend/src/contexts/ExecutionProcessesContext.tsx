@@ -49,8 +49,9 @@ export const ExecutionProcessesProvider: React.FC<{
         (process) =>
           (process.run_reason === 'codingagent' ||
             process.run_reason === 'setupscript' ||
-            process.run_reason === 'cleanupscript') &&
-          process.status === 'running'
+            process.run_reason === 'cleanupscript' ||
+            process.run_reason === 'script') &&
+          (process.status === 'running' || process.status === 'queued')
       ),
     [visible]
   );
@@ -6,8 +6,15 @@ import {
   ReactNode,
   useEffect,
   useCallback,
+  useMemo,
 } from 'react';
-import { genId } from '@/utils/id';
+import { useQueryClient } from '@tanstack/react-query';
+import { attemptsApi } from '@/lib/api';
+import {
+  reviewCommentsKeys,
+  useReviewComments,
+} from '@/hooks/useReviewComments';
+import type { ReviewCommentWithRepo, SendReviewResponse } from 'shared/types';
 
 export interface ReviewComment {
   id: string;
@@ -16,6 +23,10 @@ export interface ReviewComment {
   side: SplitSide;
   text: string;
   codeLine?: string;
+  /** The commented lines were changed by a later commit */
+  outdated: boolean;
+  /** Already handed to the agent */
+  sent: boolean;
 }
 
 export interface ReviewDraft {
@@ -27,12 +38,20 @@ export interface ReviewDraft {
 }
 
 interface ReviewContextType {
+  /** Unresolved comments, shown inline in the diff */
   comments: ReviewComment[];
+  /** Unresolved comments that were not sent to the agent yet */
+  pendingComments: ReviewComment[];
   drafts: Record<string, ReviewDraft>;
-  addComment: (comment: Omit<ReviewComment, 'id'>) => void;
-  updateComment: (id: string, text: string) => void;
-  deleteComment: (id: string) => void;
-  clearComments: () => void;
+  addComment: (
+    comment: Omit<ReviewComment, 'id' | 'outdated' | 'sent'>
+  ) => Promise<void>;
+  updateComment: (id: string, text: string) => Promise<void>;
+  deleteComment: (id: string) => Promise<void>;
+  resolveComment: (id: string) => Promise<void>;
+  clearComments: () => Promise<void>;
+  /** Send the pending comments, plus an optional message, as one follow-up */
+  sendReview: (message?: string) => Promise<SendReviewResponse | undefined>;
   setDraft: (key: string, draft: ReviewDraft | null) => void;
   generateReviewMarkdown: () => string;
 }
@@ -47,6 +66,20 @@ export function useReview() {
   return context;
 }
 
+function toReviewComment(comment: ReviewCommentWithRepo): ReviewComment {
+  return {
+    id: comment.id,
+    // Diff paths are prefixed with the repository name
+    filePath: `${comment.repo_name}/${comment.file_path}`,
+    lineNumber: Number(comment.line_end),
+    side: comment.side === 'old' ? SplitSide.old : SplitSide.new,
+    text: comment.body,
+    codeLine: comment.anchor_text ?? undefined,
+    outdated: comment.outdated,
+    sent: comment.sent_at !== null,
+  };
+}
+
 export function ReviewProvider({
   children,
   attemptId,
@@ -54,37 +87,100 @@ export function ReviewProvider({
   children: ReactNode;
   attemptId?: string;
 }) {
-  const [comments, setComments] = useState<ReviewComment[]>([]);
+  const queryClient = useQueryClient();
+  const { data } = useReviewComments(attemptId);
   const [drafts, setDrafts] = useState<Record<string, ReviewDraft>>({});
 
   useEffect(() => {
-    return () => clearComments();
+    return () => setDrafts({});
   }, [attemptId]);
 
-  const addComment = (comment: Omit<ReviewComment, 'id'>) => {
-    const newComment: ReviewComment = {
-      ...comment,
-      id: genId(),
-    };
-    setComments((prev) => [...prev, newComment]);
-  };
+  const comments = useMemo(
+    () =>
+      (data ?? [])
+        .filter((comment) => comment.resolved_at === null)
+        .map(toReviewComment),
+    [data]
+  );
+  const pendingComments = useMemo(
+    () => comments.filter((comment) => !comment.sent),
+    [comments]
+  );
 
-  const updateComment = (id: string, text: string) => {
-    setComments((prev) =>
-      prev.map((comment) =>
-        comment.id === id ? { ...comment, text } : comment
-      )
-    );
-  };
+  const refresh = useCallback(
+    () =>
+      queryClient.invalidateQueries({
+        queryKey: reviewCommentsKeys.byAttempt(attemptId),
+      }),
+    [queryClient, attemptId]
+  );
 
-  const deleteComment = (id: string) => {
-    setComments((prev) => prev.filter((comment) => comment.id !== id));
-  };
+  const addComment = useCallback(
+    async (comment: Omit<ReviewComment, 'id' | 'outdated' | 'sent'>) => {
+      if (!attemptId) return;
+      await attemptsApi.createReviewComment(attemptId, {
+        repo_id: null,
+        file_path: comment.filePath,
+        side: comment.side === SplitSide.old ? 'old' : 'new',
+        line_start: comment.lineNumber,
+        line_end: null,
+        body: comment.text,
+        execution_process_id: null,
+      });
+      await refresh();
+    },
+    [attemptId, refresh]
+  );
+
+  const updateComment = useCallback(
+    async (id: string, text: string) => {
+      if (!attemptId) return;
+      await attemptsApi.updateReviewComment(attemptId, id, text);
+      await refresh();
+    },
+    [attemptId, refresh]
+  );
+
+  const deleteComment = useCallback(
+    async (id: string) => {
+      if (!attemptId) return;
+      await attemptsApi.deleteReviewComment(attemptId, id);
+      await refresh();
+    },
+    [attemptId, refresh]
+  );
 
-  const clearComments = () => {
-    setComments([]);
+  const resolveComment = useCallback(
+    async (id: string) => {
+      if (!attemptId) return;
+      await attemptsApi.resolveReviewComment(attemptId, id, true);
+      await refresh();
+    },
+    [attemptId, refresh]
+  );
+
+  const clearComments = useCallback(async () => {
+    if (!attemptId) return;
+    await Promise.all(
+      pendingComments.map((comment) =>
+        attemptsApi.deleteReviewComment(attemptId, comment.id)
+      )
+    );
     setDrafts({});
-  };
+    await refresh();
+  }, [attemptId, pendingComments, refresh]);
+
+  const sendReview = useCallback(
+    async (message?: string) => {
+      if (!attemptId) return;
+      const result = await attemptsApi.sendReview(attemptId, {
+        message: message || null,
+      });
+      await refresh();
+      return result;
+    },
+    [attemptId, refresh]
+  );
 
   const setDraft = (key: string, draft: ReviewDraft | null) => {
     setDrafts((prev) => {
@@ -97,21 +193,22 @@ export function ReviewProvider({
     });
   };
 
+  // Preview of the pending review; the prompt itself is built by the server
   const generateReviewMarkdown = useCallback(() => {
-    if (comments.length === 0) return '';
+    if (pendingComments.length === 0) return '';
 
-    const commentsNum = comments.length;
+    const commentsNum = pendingComments.length;
 
     const header = `## Review Comments (${commentsNum})\n\n`;
     const formatCodeLine = (line?: string) => {
       if (!line) return '';
-      if (line.includes('`')) {
+      if (line.includes('`') || line.includes('\n')) {
         return `\`\`\`\n${line}\n\`\`\``;
       }
       return `\`${line}\``;
     };
 
-    const commentsMd = comments
+    const commentsMd = pendingComments
       .map((comment) => {
         const codeLine = formatCodeLine(comment.codeLine);
         // Format file paths in comment body with backticks
@@ -126,17 +223,20 @@ export function ReviewProvider({
       .join('\n');
 
     return header + commentsMd;
-  }, [comments]);
+  }, [pendingComments]);
 
   return (
     <ReviewContext.Provider
       value={{
         comments,
+        pendingComments,
         drafts,
         addComment,
         updateComment,
         deleteComment,
+        resolveComment,
         clearComments,
+        sendReview,
         setDraft,
         generateReviewMarkdown,
       }}
@@ -13,5 +13,18 @@ export function useAttemptConflicts(attemptId?: string, repoId?: string) {
     });
   }, [attemptId, repoId, queryClient]);
 
-  return { abortConflicts } as const;
+  const resolveWithAgent = useCallback(async () => {
+    if (!attemptId || !repoId) return;
+    const result = await attemptsApi.resolveConflicts(attemptId, {
+      repo_id: repoId,
+    });
+    if (!result.success) {
+      throw new Error(result.message ?? 'Failed to start conflict resolution');
+    }
+    await queryClient.invalidateQueries({
+      queryKey: ['branchStatus', attemptId],
+    });
+  }, [attemptId, repoId, queryClient]);
+
+  return { abortConflicts, resolveWithAgent } as const;
 }
@@ -118,6 +118,7 @@ export const useConversationHistory = ({
       (ep) =>
         ep.run_reason === 'setupscript' ||
         ep.run_reason === 'cleanupscript' ||
+        ep.run_reason === 'script' ||
         ep.run_reason === 'codingagent'
     );
   }, [executionProcessesRaw]);
@@ -316,17 +317,22 @@ export const useConversationHistory = ({
             p.executionProcess.executor_action.typ.type === 'ScriptRequest'
           ) {
             // Add setup and cleanup script as a tool call
+            const scriptName =
+              p.executionProcess.executor_action.typ.script_name;
             let toolName = '';
             switch (p.executionProcess.executor_action.typ.context) {
               case 'SetupScript':
-                toolName = 'Setup Script';
+                toolName = scriptName ?? 'Setup Script';
                 break;
               case 'CleanupScript':
-                toolName = 'Cleanup Script';
+                toolName = scriptName ?? 'Cleanup Script';
                 break;
               case 'ToolInstallScript':
                 toolName = 'Tool Install Script';
                 break;
+              case 'NamedScript':
+                toolName = scriptName ?? 'Script';
+                break;
               default:
                 return [];
             }
@@ -16,6 +16,8 @@ interface UseDevserverPreviewOptions {
     port?: number;
     scheme: 'http' | 'https';
   };
+  // Stable proxy URL, used when the dev server never logs its own URL
+  previewUrl?: string;
 }
 
 export function useDevserverPreview(
@@ -25,7 +27,7 @@ export function useDevserverPreview(
     projectHasDevScript: false,
   }
 ): DevserverPreviewState {
-  const { projectHasDevScript = false, lastKnownUrl } = options;
+  const { projectHasDevScript = false, lastKnownUrl, previewUrl } = options;
   const {
     executionProcessesVisible: executionProcesses,
     error: processesError,
@@ -88,13 +90,33 @@ export function useDevserverPreview(
       return;
     }
 
+    if (previewUrl) {
+      setState((prev) =>
+        prev.status === 'ready' && prev.url === previewUrl
+          ? prev
+          : {
+              status: 'ready',
+              url: previewUrl,
+              port: undefined,
+              scheme: window.location.protocol === 'https:' ? 'https' : 'http',
+            }
+      );
+      return;
+    }
+
     setState((prev) => ({
       status: 'searching',
       scheme: prev.scheme ?? 'http',
       url: undefined,
       port: undefined,
     }));
-  }, [processesError, selectedProcess, lastKnownUrl, projectHasDevScript]);
+  }, [
+    processesError,
+    selectedProcess,
+    lastKnownUrl,
+    previewUrl,
+    projectHasDevScript,
+  ]);
 
   useEffect(() => {
     setState({
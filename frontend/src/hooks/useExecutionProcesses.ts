@@ -57,8 +57,9 @@ export const useExecutionProcesses = (
     (process) =>
       (process.run_reason === 'codingagent' ||
         process.run_reason === 'setupscript' ||
-        process.run_reason === 'cleanupscript') &&
-      process.status === 'running'
+        process.run_reason === 'cleanupscript' ||
+        process.run_reason === 'script') &&
+      (process.status === 'running' || process.status === 'queued')
   );
   const isLoading = !!taskAttemptId && !data && !error; // until first snapshot
 
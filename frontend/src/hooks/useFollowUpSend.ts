@@ -6,10 +6,10 @@ type Args = {
   sessionId?: string;
   message: string;
   conflictMarkdown: string | null;
-  reviewMarkdown: string;
+  hasReviewComments: boolean;
   clickedMarkdown?: string;
   selectedVariant: string | null;
-  clearComments: () => void;
+  sendReview: (message?: string) => Promise<unknown>;
   clearClickedElements?: () => void;
   onAfterSendCleanup: () => void;
 };
@@ -18,10 +18,10 @@ export function useFollowUpSend({
   sessionId,
   message,
   conflictMarkdown,
-  reviewMarkdown,
+  hasReviewComments,
   clickedMarkdown,
   selectedVariant,
-  clearComments,
+  sendReview,
   clearClickedElements,
   onAfterSendCleanup,
 }: Args) {
@@ -31,27 +31,26 @@ export function useFollowUpSend({
   const onSendFollowUp = useCallback(async () => {
     if (!sessionId) return;
     const extraMessage = message.trim();
-    const finalPrompt = [
-      conflictMarkdown,
-      clickedMarkdown?.trim(),
-      reviewMarkdown?.trim(),
-      extraMessage,
-    ]
+    const finalPrompt = [conflictMarkdown, clickedMarkdown?.trim(), extraMessage]
       .filter(Boolean)
       .join('\n\n');
-    if (!finalPrompt) return;
+    if (!finalPrompt && !hasReviewComments) return;
     try {
       setIsSendingFollowUp(true);
       setFollowUpError(null);
-      const body: CreateFollowUpAttempt = {
-        prompt: finalPrompt,
-        variant: selectedVariant,
-        retry_process_id: null,
-        force_when_dirty: null,
-        perform_git_reset: null,
-      };
-      await sessionsApi.followUp(sessionId, body);
-      clearComments();
+      if (hasReviewComments) {
+        // The server builds the review prompt and appends the rest
+        await sendReview(finalPrompt);
+      } else {
+        const body: CreateFollowUpAttempt = {
+          prompt: finalPrompt,
+          variant: selectedVariant,
+          retry_process_id: null,
+          force_when_dirty: null,
+          perform_git_reset: null,
+        };
+        await sessionsApi.followUp(sessionId, body);
+      }
       clearClickedElements?.();
       onAfterSendCleanup();
       // Don't call jumpToLogsTab() - preserves focus on the follow-up editor
@@ -67,10 +66,10 @@ export function useFollowUpSend({
     sessionId,
     message,
     conflictMarkdown,
-    reviewMarkdown,
+    hasReviewComments,
     clickedMarkdown,
     selectedVariant,
-    clearComments,
+    sendReview,
     clearClickedElements,
     onAfterSendCleanup,
   ]);
@@ -0,0 +1,19 @@
+import { useQuery } from '@tanstack/react-query';
+import { attemptsApi } from '@/lib/api';
+import type { ReviewCommentWithRepo } from 'shared/types';
+
+export const reviewCommentsKeys = {
+  all: ['reviewComments'] as const,
+  byAttempt: (attemptId: string | undefined) =>
+    ['reviewComments', attemptId] as const,
+};
+
+export function useReviewComments(attemptId?: string) {
+  return useQuery<ReviewCommentWithRepo[]>({
+    queryKey: reviewCommentsKeys.byAttempt(attemptId),
+    queryFn: () => attemptsApi.getReviewComments(attemptId!),
+    enabled: !!attemptId,
+    // Re-anchoring happens on read, so refresh as the agent commits
+    refetchInterval: 10_000,
+  });
+}
@@ -3,6 +3,10 @@
 import {
   ApprovalStatus,
   ApiResponse,
+  ApiToken,
+  CreateApiTokenRequest,
+  CreateApiTokenResponse,
+  LocalAuthStatus,
   Config,
   CreateFollowUpAttempt,
   EditorType,
@@ -17,13 +21,21 @@ import {
   ExecutionProcessRepoState,
   GitBranch,
   Project,
+  ProjectGitHost,
   ProjectRepo,
   Repo,
   RepoWithTargetBranch,
   CreateProject,
   CreateProjectRepo,
   UpdateProjectRepo,
+  RepoSettingsFileStatus,
+  SetSettingsFileModeRequest,
+  ProjectScript,
+  CreateProjectScript,
+  UpdateProjectScript,
+  RunProjectScriptRequest,
   SearchResult,
+  ContentSearchResponse,
   ShareTaskResponse,
   Task,
   TaskRelationships,
@@ -87,8 +99,27 @@ import {
   PushTaskAttemptRequest,
   RepoBranchStatus,
   AbortConflictsRequest,
+  ResolveConflictsRequest,
+  ResolveConflictsError,
+  ConflictResolution,
+  DevServerInfo,
+  CreateReviewCommentRequest,
+  ReviewCommentWithRepo,
+  SendReviewRequest,
+  SendReviewResponse,
   Session,
   Workspace,
+  Webhook,
+  CreateWebhook,
+  UpdateWebhook,
+  WebhookDelivery,
+  NotificationChannel,
+  Secret,
+  CreateSecret,
+  UpdateSecret,
+  DependencyCacheInfo,
+  PruneDependencyCaches,
+  PruneDependencyCachesResult,
 } from 'shared/types';
 import type { WorkspaceWithSession } from '@/types/attempt';
 import { createWorkspaceWithSession } from '@/types/attempt';
@@ -252,6 +283,17 @@ export const projectsApi = {
     return handleApiResponse<Project>(response);
   },
 
+  setGitHost: async (
+    id: string,
+    gitHost: ProjectGitHost | null
+  ): Promise<Project> => {
+    const response = await makeRequest(`/api/projects/${id}/git-host`, {
+      method: 'PUT',
+      body: JSON.stringify(gitHost),
+    });
+    return handleApiResponse<Project>(response);
+  },
+
   getRemoteMembers: async (
     projectId: string
   ): Promise<RemoteProjectMembersResponse> => {
@@ -293,6 +335,21 @@ export const projectsApi = {
     return handleApiResponse<SearchResult[]>(response);
   },
 
+  searchContents: async (
+    id: string,
+    query: string,
+    options: { regex?: boolean; caseSensitive?: boolean; mode?: string } = {}
+  ): Promise<ContentSearchResponse> => {
+    const params = new URLSearchParams({ q: query });
+    if (options.regex) params.set('regex', 'true');
+    if (options.caseSensitive) params.set('case_sensitive', 'true');
+    if (options.mode) params.set('mode', options.mode);
+    const response = await makeRequest(
+      `/api/projects/${id}/search/content?${params.toString()}`
+    );
+    return handleApiResponse<ContentSearchResponse>(response);
+  },
+
   linkToExisting: async (
     localProjectId: string,
     data: LinkToExistingRequest
@@ -383,6 +440,72 @@ export const projectsApi = {
     );
     return handleApiResponse<ProjectRepo>(response);
   },
+
+  getSettingsFile: async (
+    projectId: string,
+    repoId: string
+  ): Promise<RepoSettingsFileStatus> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/repositories/${repoId}/settings-file`
+    );
+    return handleApiResponse<RepoSettingsFileStatus>(response);
+  },
+
+  setSettingsFileMode: async (
+    projectId: string,
+    repoId: string,
+    data: SetSettingsFileModeRequest
+  ): Promise<RepoSettingsFileStatus> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/repositories/${repoId}/settings-file`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<RepoSettingsFileStatus>(response);
+  },
+
+  getScripts: async (projectId: string): Promise<ProjectScript[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/scripts`);
+    return handleApiResponse<ProjectScript[]>(response);
+  },
+
+  createScript: async (
+    projectId: string,
+    data: CreateProjectScript
+  ): Promise<ProjectScript> => {
+    const response = await makeRequest(`/api/projects/${projectId}/scripts`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectScript>(response);
+  },
+
+  updateScript: async (
+    projectId: string,
+    scriptId: string,
+    data: UpdateProjectScript
+  ): Promise<ProjectScript> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/scripts/${scriptId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectScript>(response);
+  },
+
+  deleteScript: async (projectId: string, scriptId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/scripts/${scriptId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Task Management APIs
@@ -685,6 +808,32 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  resolveConflicts: async (
+    attemptId: string,
+    data: ResolveConflictsRequest
+  ): Promise<Result<ConflictResolution, ResolveConflictsError>> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts/resolve`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponseAsResult<
+      ConflictResolution,
+      ResolveConflictsError
+    >(response);
+  },
+
+  getConflictResolution: async (
+    attemptId: string
+  ): Promise<ConflictResolution | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts/resolution`
+    );
+    return handleApiResponse<ConflictResolution | null>(response);
+  },
+
   createPR: async (
     attemptId: string,
     data: CreateGitHubPrRequest
@@ -706,6 +855,13 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  getDevServer: async (attemptId: string): Promise<DevServerInfo | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/dev-server`
+    );
+    return handleApiResponse<DevServerInfo | null>(response);
+  },
+
   setupGhCli: async (attemptId: string): Promise<ExecutionProcess> => {
     const response = await makeRequest(
       `/api/task-attempts/${attemptId}/gh-cli-setup`,
@@ -744,6 +900,22 @@ export const attemptsApi = {
     );
   },
 
+  runProjectScript: async (
+    attemptId: string,
+    data: RunProjectScriptRequest
+  ): Promise<Result<ExecutionProcess, RunScriptError>> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/scripts/run`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponseAsResult<ExecutionProcess, RunScriptError>(
+      response
+    );
+  },
+
   getPrComments: async (
     attemptId: string,
     repoId: string
@@ -753,6 +925,86 @@ export const attemptsApi = {
     );
     return handleApiResponse<PrCommentsResponse>(response);
   },
+
+  getReviewComments: async (
+    attemptId: string
+  ): Promise<ReviewCommentWithRepo[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/review-comments`
+    );
+    return handleApiResponse<ReviewCommentWithRepo[]>(response);
+  },
+
+  createReviewComment: async (
+    attemptId: string,
+    data: CreateReviewCommentRequest
+  ): Promise<ReviewCommentWithRepo> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/review-comments`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ReviewCommentWithRepo>(response);
+  },
+
+  updateReviewComment: async (
+    attemptId: string,
+    commentId: string,
+    body: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/review-comments/${commentId}`,
+      {
+        method: 'PATCH',
+        body: JSON.stringify({ body }),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  deleteReviewComment: async (
+    attemptId: string,
+    commentId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/review-comments/${commentId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  resolveReviewComment: async (
+    attemptId: string,
+    commentId: string,
+    resolved: boolean
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/review-comments/${commentId}/resolve`,
+      {
+        method: 'POST',
+        body: JSON.stringify({ resolved }),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  sendReview: async (
+    attemptId: string,
+    data: SendReviewRequest
+  ): Promise<SendReviewResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/review-comments/send`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<SendReviewResponse>(response);
+  },
 };
 
 // Execution Process APIs
@@ -896,6 +1148,183 @@ export const tagsApi = {
   },
 };
 
+// Local server authentication
+export const localAuthApi = {
+  status: async (): Promise<LocalAuthStatus> => {
+    const response = await makeRequest('/api/local-auth/status', {
+      cache: 'no-store',
+    });
+    return handleApiResponse<LocalAuthStatus>(response);
+  },
+
+  login: async (token: string): Promise<void> => {
+    const response = await makeRequest('/api/local-auth/login', {
+      method: 'POST',
+      body: JSON.stringify({ token }),
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  logout: async (): Promise<void> => {
+    const response = await makeRequest('/api/local-auth/logout', {
+      method: 'POST',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+export const apiTokensApi = {
+  list: async (): Promise<ApiToken[]> => {
+    const response = await makeRequest('/api/api-tokens');
+    return handleApiResponse<ApiToken[]>(response);
+  },
+
+  create: async (
+    data: CreateApiTokenRequest
+  ): Promise<CreateApiTokenResponse> => {
+    const response = await makeRequest('/api/api-tokens', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CreateApiTokenResponse>(response);
+  },
+
+  delete: async (tokenId: string): Promise<void> => {
+    const response = await makeRequest(`/api/api-tokens/${tokenId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+export const webhooksApi = {
+  list: async (): Promise<Webhook[]> => {
+    const response = await makeRequest('/api/webhooks');
+    return handleApiResponse<Webhook[]>(response);
+  },
+
+  create: async (data: CreateWebhook): Promise<Webhook> => {
+    const response = await makeRequest('/api/webhooks', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Webhook>(response);
+  },
+
+  update: async (webhookId: string, data: UpdateWebhook): Promise<Webhook> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Webhook>(response);
+  },
+
+  delete: async (webhookId: string): Promise<void> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  deliveries: async (webhookId: string): Promise<WebhookDelivery[]> => {
+    const response = await makeRequest(
+      `/api/webhooks/${webhookId}/deliveries`
+    );
+    return handleApiResponse<WebhookDelivery[]>(response);
+  },
+
+  sendTest: async (webhookId: string): Promise<WebhookDelivery> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}/test`, {
+      method: 'POST',
+    });
+    return handleApiResponse<WebhookDelivery>(response);
+  },
+
+  redeliver: async (webhookId: string, deliveryId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/webhooks/${webhookId}/deliveries/${deliveryId}/redeliver`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Notification channel APIs; channels themselves live in the config
+export const notificationsApi = {
+  testChannel: async (channel: NotificationChannel): Promise<void> => {
+    const response = await makeRequest('/api/notifications/test', {
+      method: 'POST',
+      body: JSON.stringify(channel),
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Secrets APIs; values are write-only
+export const secretsApi = {
+  list: async (scope: {
+    projectId?: string;
+    repoId?: string;
+  }): Promise<Secret[]> => {
+    const params = new URLSearchParams();
+    if (scope.projectId) params.set('project_id', scope.projectId);
+    if (scope.repoId) params.set('repo_id', scope.repoId);
+    const query = params.toString();
+    const response = await makeRequest(
+      `/api/secrets${query ? `?${query}` : ''}`
+    );
+    return handleApiResponse<Secret[]>(response);
+  },
+
+  create: async (data: CreateSecret): Promise<Secret> => {
+    const response = await makeRequest('/api/secrets', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Secret>(response);
+  },
+
+  update: async (secretId: string, data: UpdateSecret): Promise<Secret> => {
+    const response = await makeRequest(`/api/secrets/${secretId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Secret>(response);
+  },
+
+  delete: async (secretId: string): Promise<void> => {
+    const response = await makeRequest(`/api/secrets/${secretId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Dependency caches shared between attempt worktrees
+export const dependencyCachesApi = {
+  list: async (): Promise<DependencyCacheInfo[]> => {
+    const response = await makeRequest('/api/dependency-caches');
+    return handleApiResponse<DependencyCacheInfo[]>(response);
+  },
+
+  prune: async (
+    data: PruneDependencyCaches
+  ): Promise<PruneDependencyCachesResult> => {
+    const response = await makeRequest('/api/dependency-caches/prune', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<PruneDependencyCachesResult>(response);
+  },
+
+  delete: async (cacheId: string): Promise<void> => {
+    const response = await makeRequest(`/api/dependency-caches/${cacheId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
 // MCP Servers APIs
 export const mcpServersApi = {
   load: async (query: McpServerQuery): Promise<GetMcpServerResponse> => {
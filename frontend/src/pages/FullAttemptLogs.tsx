@@ -34,7 +34,7 @@ export function FullAttemptLogsPage() {
         <main className="flex-1 min-h-0">
           {attempt ? (
             <ClickedElementsProvider attempt={attempt}>
-              <ReviewProvider key={attempt.id}>
+              <ReviewProvider key={attempt.id} attemptId={attempt.id}>
                 <ExecutionProcessesProvider
                   key={attempt.id}
                   attemptId={attempt.id}
@@ -24,6 +24,7 @@ import { Loader2, Volume2 } from 'lucide-react';
 import {
   DEFAULT_PR_DESCRIPTION_PROMPT,
   EditorType,
+  NotificationChannel,
   SoundFile,
   ThemeMode,
   UiLanguage,
@@ -36,6 +37,11 @@ import { EditorAvailabilityIndicator } from '@/components/EditorAvailabilityIndi
 import { useTheme } from '@/components/ThemeProvider';
 import { useUserSystem } from '@/components/ConfigProvider';
 import { TagManager } from '@/components/TagManager';
+import { ApiTokenManager } from '@/components/ApiTokenManager';
+import { WebhookManager } from '@/components/WebhookManager';
+import { NotificationChannelsEditor } from '@/components/NotificationChannelsEditor';
+import { SecretManager } from '@/components/SecretManager';
+import { DependencyCacheManager } from '@/components/DependencyCacheManager';
 
 export function GeneralSettings() {
   const { t } = useTranslation(['settings', 'common']);
@@ -121,6 +127,38 @@ export function GeneralSettings() {
     [config]
   );
 
+  // Replaces the list outright; merging would keep removed channels' fields
+  const updateNotificationChannels = useCallback(
+    (channels: NotificationChannel[]) => {
+      setDraft((prev: typeof config) => {
+        if (!prev) return prev;
+        const next = {
+          ...prev,
+          notifications: { ...prev.notifications, channels },
+        };
+        if (!isEqual(next, config)) {
+          setDirty(true);
+        }
+        return next;
+      });
+    },
+    [config]
+  );
+
+  const updateConcurrency = useCallback(
+    (key: 'max_agents' | 'max_agents_per_project', value: string) => {
+      if (!draft) return;
+      const limit = parseInt(value, 10);
+      updateDraft({
+        concurrency: {
+          ...draft.concurrency,
+          [key]: Number.isFinite(limit) && limit > 0 ? limit : null,
+        },
+      });
+    },
+    [draft, updateDraft]
+  );
+
   // Optional: warn on tab close/navigation with unsaved changes
   useEffect(() => {
     const handler = (e: BeforeUnloadEvent) => {
@@ -464,6 +502,86 @@ export function GeneralSettings() {
         </CardContent>
       </Card>
 
+      <Card>
+        <CardHeader>
+          <CardTitle>{t('settings.general.concurrency.title')}</CardTitle>
+          <CardDescription>
+            {t('settings.general.concurrency.description')}
+          </CardDescription>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="space-y-2">
+            <Label htmlFor="max-agents">
+              {t('settings.general.concurrency.maxAgents.label')}
+            </Label>
+            <Input
+              id="max-agents"
+              type="number"
+              min={1}
+              placeholder={t('settings.general.concurrency.unlimited')}
+              value={draft?.concurrency.max_agents ?? ''}
+              onChange={(e) => updateConcurrency('max_agents', e.target.value)}
+            />
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.concurrency.maxAgents.helper')}
+            </p>
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="max-agents-per-project">
+              {t('settings.general.concurrency.maxAgentsPerProject.label')}
+            </Label>
+            <Input
+              id="max-agents-per-project"
+              type="number"
+              min={1}
+              placeholder={t('settings.general.concurrency.unlimited')}
+              value={draft?.concurrency.max_agents_per_project ?? ''}
+              onChange={(e) =>
+                updateConcurrency('max_agents_per_project', e.target.value)
+              }
+            />
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.concurrency.maxAgentsPerProject.helper')}
+            </p>
+          </div>
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>{t('settings.general.devServers.title')}</CardTitle>
+          <CardDescription>
+            {t('settings.general.devServers.description')}
+          </CardDescription>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="space-y-2">
+            <Label htmlFor="dev-server-idle-shutdown">
+              {t('settings.general.devServers.idleShutdown.label')}
+            </Label>
+            <Input
+              id="dev-server-idle-shutdown"
+              type="number"
+              min={1}
+              placeholder={t('settings.general.devServers.idleShutdown.never')}
+              value={draft?.dev_servers.idle_shutdown_minutes ?? ''}
+              onChange={(e) => {
+                const minutes = parseInt(e.target.value, 10);
+                updateDraft({
+                  dev_servers: {
+                    idle_shutdown_minutes:
+                      Number.isFinite(minutes) && minutes > 0 ? minutes : null,
+                  },
+                });
+              }}
+            />
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.devServers.idleShutdown.helper')}
+            </p>
+          </div>
+        </CardContent>
+      </Card>
+
       <Card>
         <CardHeader>
           <CardTitle>{t('settings.general.pullRequests.title')}</CardTitle>
@@ -631,6 +749,16 @@ export function GeneralSettings() {
               </p>
             </div>
           </div>
+          <div className="space-y-2 pt-2">
+            <Label>{t('settings.general.notifications.channels.title')}</Label>
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.notifications.channels.description')}
+            </p>
+            <NotificationChannelsEditor
+              channels={draft?.notifications.channels ?? []}
+              onChange={updateNotificationChannels}
+            />
+          </div>
         </CardContent>
       </Card>
 
@@ -674,6 +802,54 @@ export function GeneralSettings() {
         </CardContent>
       </Card>
 
+      <Card>
+        <CardHeader>
+          <CardTitle>{t('settings.general.apiTokens.title')}</CardTitle>
+          <CardDescription>
+            {t('settings.general.apiTokens.description')}
+          </CardDescription>
+        </CardHeader>
+        <CardContent>
+          <ApiTokenManager />
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>{t('settings.general.webhooks.title')}</CardTitle>
+          <CardDescription>
+            {t('settings.general.webhooks.description')}
+          </CardDescription>
+        </CardHeader>
+        <CardContent>
+          <WebhookManager />
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>{t('settings.general.secrets.title')}</CardTitle>
+          <CardDescription>
+            {t('settings.general.secrets.description')}
+          </CardDescription>
+        </CardHeader>
+        <CardContent>
+          <SecretManager />
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>{t('settings.general.dependencyCaches.title')}</CardTitle>
+          <CardDescription>
+            {t('settings.general.dependencyCaches.description')}
+          </CardDescription>
+        </CardHeader>
+        <CardContent>
+          <DependencyCacheManager />
+        </CardContent>
+      </Card>
+
       <Card>
         <CardHeader>
           <CardTitle>{t('settings.general.safety.title')}</CardTitle>
@@ -27,6 +27,10 @@ import { useProjects } from '@/hooks/useProjects';
 import { useProjectMutations } from '@/hooks/useProjectMutations';
 import { useScriptPlaceholders } from '@/hooks/useScriptPlaceholders';
 import { CopyFilesField } from '@/components/projects/CopyFilesField';
+import { ProjectScriptsCard } from '@/components/projects/ProjectScriptsCard';
+import { ProjectGitHostCard } from '@/components/projects/ProjectGitHostCard';
+import { RepoSettingsFileCard } from '@/components/projects/RepoSettingsFileCard';
+import { SecretManager } from '@/components/SecretManager';
 import { AutoExpandingTextarea } from '@/components/ui/auto-expanding-textarea';
 import { RepoPickerDialog } from '@/components/dialogs/shared/RepoPickerDialog';
 import { projectsApi } from '@/lib/api';
@@ -38,13 +42,23 @@ interface ProjectFormState {
   dev_script: string;
   dev_script_working_dir: string;
   default_agent_working_dir: string;
+  auto_review_follow_ups: boolean;
+  auto_fix_ci: boolean;
+  dev_server_ports: string;
+  dev_server_ready_pattern: string;
 }
 
 interface RepoScriptsFormState {
   setup_script: string;
   parallel_setup_script: boolean;
   cleanup_script: string;
+  test_script: string;
   copy_files: string;
+  dependency_caches: string;
+  worktree_pool_size: string;
+  worktree_pool_max_age_minutes: string;
+  sparse_checkout_paths: string;
+  sparse_checkout_agent_working_dir: boolean;
 }
 
 function projectToFormState(project: Project): ProjectFormState {
@@ -53,6 +67,10 @@ function projectToFormState(project: Project): ProjectFormState {
     dev_script: project.dev_script ?? '',
     dev_script_working_dir: project.dev_script_working_dir ?? '',
     default_agent_working_dir: project.default_agent_working_dir ?? '',
+    auto_review_follow_ups: project.auto_review_follow_ups,
+    auto_fix_ci: project.auto_fix_ci,
+    dev_server_ports: project.dev_server_ports ?? '',
+    dev_server_ready_pattern: project.dev_server_ready_pattern ?? '',
   };
 }
 
@@ -63,7 +81,16 @@ function projectRepoToScriptsFormState(
     setup_script: projectRepo?.setup_script ?? '',
     parallel_setup_script: projectRepo?.parallel_setup_script ?? false,
     cleanup_script: projectRepo?.cleanup_script ?? '',
+    test_script: projectRepo?.test_script ?? '',
     copy_files: projectRepo?.copy_files ?? '',
+    dependency_caches: projectRepo?.dependency_caches ?? '',
+    worktree_pool_size: String(projectRepo?.worktree_pool_size ?? 0),
+    worktree_pool_max_age_minutes: String(
+      projectRepo?.worktree_pool_max_age_minutes ?? 60
+    ),
+    sparse_checkout_paths: projectRepo?.sparse_checkout_paths ?? '',
+    sparse_checkout_agent_working_dir:
+      projectRepo?.sparse_checkout_agent_working_dir ?? false,
   };
 }
 
@@ -306,6 +333,30 @@ export function ProjectSettings() {
       .finally(() => setLoadingProjectRepo(false));
   }, [selectedProjectId, selectedScriptsRepoId]);
 
+  // Kept current by the projects stream, unlike selectedProject
+  const liveProject = useMemo(
+    () => projects?.find((p) => p.id === selectedProjectId) ?? null,
+    [projects, selectedProjectId]
+  );
+
+  // Reload the repository scripts after the settings file overwrote them
+  const handleSettingsFileApplied = useCallback(() => {
+    if (!selectedProjectId || !selectedScriptsRepoId) return;
+    projectsApi
+      .getRepository(selectedProjectId, selectedScriptsRepoId)
+      .then((projectRepo) => {
+        setSelectedProjectRepo(projectRepo);
+        setScriptsDraft(projectRepoToScriptsFormState(projectRepo));
+      })
+      .catch((err) => {
+        setScriptsError(
+          err instanceof Error
+            ? err.message
+            : 'Failed to load repository scripts'
+        );
+      });
+  }, [selectedProjectId, selectedScriptsRepoId]);
+
   const handleAddRepository = async () => {
     if (!selectedProjectId) return;
 
@@ -397,6 +448,13 @@ export function ProjectSettings() {
         dev_script_working_dir: draft.dev_script_working_dir.trim() || null,
         default_agent_working_dir:
           draft.default_agent_working_dir.trim() || null,
+        auto_review_follow_ups: draft.auto_review_follow_ups,
+        auto_fix_ci: draft.auto_fix_ci,
+        // Empty strings clear these settings
+        dev_server_ports: draft.dev_server_ports.trim(),
+        dev_server_ready_pattern: draft.dev_server_ready_pattern.trim(),
+        default_executor_profile: null,
+        mcp_servers: null,
       };
 
       updateProject.mutate({
@@ -426,6 +484,16 @@ export function ProjectSettings() {
           cleanup_script: scriptsDraft.cleanup_script.trim() || null,
           copy_files: scriptsDraft.copy_files.trim() || null,
           parallel_setup_script: scriptsDraft.parallel_setup_script,
+          test_script: scriptsDraft.test_script.trim() || null,
+          worktree_pool_size:
+            parseInt(scriptsDraft.worktree_pool_size, 10) || 0,
+          worktree_pool_max_age_minutes:
+            parseInt(scriptsDraft.worktree_pool_max_age_minutes, 10) || null,
+          sparse_checkout_paths:
+            scriptsDraft.sparse_checkout_paths.trim() || null,
+          sparse_checkout_agent_working_dir:
+            scriptsDraft.sparse_checkout_agent_working_dir,
+          dependency_caches: scriptsDraft.dependency_caches.trim() || null,
         }
       );
       setSelectedProjectRepo(updatedRepo);
@@ -610,6 +678,46 @@ export function ProjectSettings() {
                 </p>
               </div>
 
+              <div className="space-y-2">
+                <Label htmlFor="dev-server-ports">
+                  {t('settings.projects.scripts.devServerPorts.label')}
+                </Label>
+                <Input
+                  id="dev-server-ports"
+                  value={draft.dev_server_ports}
+                  onChange={(e) =>
+                    updateDraft({ dev_server_ports: e.target.value })
+                  }
+                  placeholder={t(
+                    'settings.projects.scripts.devServerPorts.placeholder'
+                  )}
+                  className="font-mono"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.scripts.devServerPorts.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label htmlFor="dev-server-ready-pattern">
+                  {t('settings.projects.scripts.devServerReadyPattern.label')}
+                </Label>
+                <Input
+                  id="dev-server-ready-pattern"
+                  value={draft.dev_server_ready_pattern}
+                  onChange={(e) =>
+                    updateDraft({ dev_server_ready_pattern: e.target.value })
+                  }
+                  placeholder={t(
+                    'settings.projects.scripts.devServerReadyPattern.placeholder'
+                  )}
+                  className="font-mono"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.scripts.devServerReadyPattern.helper')}
+                </p>
+              </div>
+
               <div className="space-y-2">
                 <Label htmlFor="agent-working-dir">
                   {t('settings.projects.scripts.agentWorkingDir.label')}
@@ -630,6 +738,48 @@ export function ProjectSettings() {
                 </p>
               </div>
 
+              <div className="space-y-2">
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="auto-review-follow-ups"
+                    checked={draft.auto_review_follow_ups}
+                    onCheckedChange={(checked) =>
+                      updateDraft({ auto_review_follow_ups: checked === true })
+                    }
+                  />
+                  <Label
+                    htmlFor="auto-review-follow-ups"
+                    className="text-sm font-normal cursor-pointer"
+                  >
+                    {t('settings.projects.general.reviewFollowUps.label')}
+                  </Label>
+                </div>
+                <p className="text-sm text-muted-foreground pl-6">
+                  {t('settings.projects.general.reviewFollowUps.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="auto-fix-ci"
+                    checked={draft.auto_fix_ci}
+                    onCheckedChange={(checked) =>
+                      updateDraft({ auto_fix_ci: checked === true })
+                    }
+                  />
+                  <Label
+                    htmlFor="auto-fix-ci"
+                    className="text-sm font-normal cursor-pointer"
+                  >
+                    {t('settings.projects.general.autoFixCi.label')}
+                  </Label>
+                </div>
+                <p className="text-sm text-muted-foreground pl-6">
+                  {t('settings.projects.general.autoFixCi.helper')}
+                </p>
+              </div>
+
               {/* Save Button */}
               <div className="flex items-center justify-between pt-4 border-t">
                 {hasUnsavedProjectChanges ? (
@@ -875,6 +1025,131 @@ export function ProjectSettings() {
                         </p>
                       </div>
 
+                      <div className="space-y-2">
+                        <Label htmlFor="test-script">
+                          {t('settings.projects.scripts.test.label')}
+                        </Label>
+                        <AutoExpandingTextarea
+                          id="test-script"
+                          value={scriptsDraft.test_script}
+                          onChange={(e) =>
+                            updateScriptsDraft({
+                              test_script: e.target.value,
+                            })
+                          }
+                          maxRows={12}
+                          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md focus:outline-none focus:ring-2 focus:ring-ring font-mono"
+                        />
+                        <p className="text-sm text-muted-foreground">
+                          {t('settings.projects.scripts.test.helper')}
+                        </p>
+                      </div>
+
+                      <div className="space-y-2">
+                        <Label>
+                          {t('settings.projects.scripts.worktreePool.label')}
+                        </Label>
+                        <div className="flex gap-4">
+                          <div className="space-y-1">
+                            <Label
+                              htmlFor="worktree-pool-size"
+                              className="text-sm font-normal"
+                            >
+                              {t(
+                                'settings.projects.scripts.worktreePool.sizeLabel'
+                              )}
+                            </Label>
+                            <Input
+                              id="worktree-pool-size"
+                              type="number"
+                              min={0}
+                              value={scriptsDraft.worktree_pool_size}
+                              onChange={(e) =>
+                                updateScriptsDraft({
+                                  worktree_pool_size: e.target.value,
+                                })
+                              }
+                              className="w-32"
+                            />
+                          </div>
+                          <div className="space-y-1">
+                            <Label
+                              htmlFor="worktree-pool-max-age"
+                              className="text-sm font-normal"
+                            >
+                              {t(
+                                'settings.projects.scripts.worktreePool.maxAgeLabel'
+                              )}
+                            </Label>
+                            <Input
+                              id="worktree-pool-max-age"
+                              type="number"
+                              min={1}
+                              value={scriptsDraft.worktree_pool_max_age_minutes}
+                              onChange={(e) =>
+                                updateScriptsDraft({
+                                  worktree_pool_max_age_minutes: e.target.value,
+                                })
+                              }
+                              disabled={
+                                !(
+                                  parseInt(scriptsDraft.worktree_pool_size, 10) >
+                                  0
+                                )
+                              }
+                              className="w-32"
+                            />
+                          </div>
+                        </div>
+                        <p className="text-sm text-muted-foreground">
+                          {t('settings.projects.scripts.worktreePool.helper')}
+                        </p>
+                      </div>
+
+                      <div className="space-y-2">
+                        <Label htmlFor="sparse-checkout-paths">
+                          {t('settings.projects.scripts.sparseCheckout.label')}
+                        </Label>
+                        <AutoExpandingTextarea
+                          id="sparse-checkout-paths"
+                          value={scriptsDraft.sparse_checkout_paths}
+                          onChange={(e) =>
+                            updateScriptsDraft({
+                              sparse_checkout_paths: e.target.value,
+                            })
+                          }
+                          placeholder={'services/api\nlibs/common'}
+                          maxRows={12}
+                          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md focus:outline-none focus:ring-2 focus:ring-ring font-mono"
+                        />
+                        <p className="text-sm text-muted-foreground">
+                          {t('settings.projects.scripts.sparseCheckout.helper')}
+                        </p>
+
+                        <div className="flex items-center space-x-2 pt-2">
+                          <Checkbox
+                            id="sparse-checkout-agent-working-dir"
+                            checked={
+                              scriptsDraft.sparse_checkout_agent_working_dir
+                            }
+                            onCheckedChange={(checked) =>
+                              updateScriptsDraft({
+                                sparse_checkout_agent_working_dir:
+                                  checked === true,
+                              })
+                            }
+                          />
+                          <Label
+                            htmlFor="sparse-checkout-agent-working-dir"
+                            className="text-sm font-normal cursor-pointer"
+                          >
+                            {t(
+                              'settings.projects.scripts.sparseCheckout.agentWorkingDirLabel'
+                            )}
+                          </Label>
+                        </div>
+                      </div>
+
                       <div className="space-y-2">
                         <Label>
                           {t('settings.projects.scripts.copyFiles.label')}
@@ -891,6 +1166,33 @@ export function ProjectSettings() {
                         </p>
                       </div>
 
+                      <div className="space-y-2">
+                        <Label htmlFor="dependency-caches">
+                          {t(
+                            'settings.projects.scripts.dependencyCaches.label'
+                          )}
+                        </Label>
+                        <AutoExpandingTextarea
+                          id="dependency-caches"
+                          value={scriptsDraft.dependency_caches}
+                          onChange={(e) =>
+                            updateScriptsDraft({
+                              dependency_caches: e.target.value,
+                            })
+                          }
+                          placeholder={
+                            'node_modules: pnpm-lock.yaml\ntarget: Cargo.lock'
+                          }
+                          maxRows={8}
+                          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md focus:outline-none focus:ring-2 focus:ring-ring font-mono"
+                        />
+                        <p className="text-sm text-muted-foreground">
+                          {t(
+                            'settings.projects.scripts.dependencyCaches.helper'
+                          )}
+                        </p>
+                      </div>
+
                       {/* Scripts Save Buttons */}
                       <div className="flex items-center justify-between pt-4 border-t">
                         {hasUnsavedScriptsChanges ? (
@@ -930,6 +1232,57 @@ export function ProjectSettings() {
             </CardContent>
           </Card>
 
+          {liveProject && selectedScriptsRepoId && (
+            <RepoSettingsFileCard
+              project={liveProject}
+              repoId={selectedScriptsRepoId}
+              onApplied={handleSettingsFileApplied}
+            />
+          )}
+
+          {selectedProjectId && (
+            <ProjectScriptsCard
+              projectId={selectedProjectId}
+              repositories={repositories}
+            />
+          )}
+
+          {liveProject && <ProjectGitHostCard project={liveProject} />}
+
+          {selectedProjectId && (
+            <Card>
+              <CardHeader>
+                <CardTitle>{t('settings.projects.secrets.title')}</CardTitle>
+                <CardDescription>
+                  {t('settings.projects.secrets.description')}
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <SecretManager projectId={selectedProjectId} />
+              </CardContent>
+            </Card>
+          )}
+
+          {selectedScriptsRepoId && (
+            <Card>
+              <CardHeader>
+                <CardTitle>
+                  {t('settings.projects.secrets.repoTitle', {
+                    repo:
+                      repositories.find((r) => r.id === selectedScriptsRepoId)
+                        ?.display_name ?? '',
+                  })}
+                </CardTitle>
+                <CardDescription>
+                  {t('settings.projects.secrets.repoDescription')}
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <SecretManager repoId={selectedScriptsRepoId} />
+              </CardContent>
+            </Card>
+          )}
+
           {/* Sticky Save Button for Project Name */}
           {hasUnsavedProjectChanges && (
             <div className="sticky bottom-0 z-10 bg-background/80 backdrop-blur-sm border-t py-4">
@@ -147,10 +147,11 @@ async function main() {
   const args = process.argv.slice(2);
   const isMcpMode = args.includes("--mcp");
   const isReviewMode = args[0] === "review";
+  const isHeadlessMode = args[0] === "headless";
 
-  // Non-blocking update check (skip in MCP mode, local dev mode, and when R2 URL not configured)
+  // Non-blocking update check (skip in MCP and headless mode, local dev mode, and when R2 URL not configured)
   const hasValidR2Url = !R2_BASE_URL.startsWith("__");
-  if (!isMcpMode && !LOCAL_DEV_MODE && hasValidR2Url) {
+  if (!isMcpMode && !isHeadlessMode && !LOCAL_DEV_MODE && hasValidR2Url) {
     getLatestVersion()
       .then((latest) => {
         if (latest && latest !== CLI_VERSION) {
@@ -186,6 +187,17 @@ async function main() {
         process.exit(1);
       });
     });
+  } else if (isHeadlessMode) {
+    await extractAndRun("vibe-kanban", (bin) => {
+      const proc = spawn(bin, args, { stdio: "inherit" });
+      proc.on("exit", (c) => process.exit(c ?? 1));
+      proc.on("error", (e) => {
+        console.error("Headless run error:", e.message);
+        process.exit(2);
+      });
+      process.on("SIGINT", () => proc.kill("SIGINT"));
+      process.on("SIGTERM", () => proc.kill("SIGTERM"));
+    });
   } else {
     const modeLabel = LOCAL_DEV_MODE ? " (local dev)" : "";
     console.log(`Starting vibe-kanban v${CLI_VERSION}${modeLabel}...`);
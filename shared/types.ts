@@ -12,11 +12,70 @@ export type SharedTask = { id: string, organization_id: string, project_id: stri
 
 export type UserData = { user_id: string, first_name: string | null, last_name: string | null, username: string | null, };
 
-export type Project = { id: string, name: string, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, remote_project_id: string | null, 
+/**
+ * Turn new PR review comments into coding agent follow-ups
+ */
+auto_review_follow_ups: boolean, 
+/**
+ * Hand failing CI checks of open PRs to the coding agent
+ */
+auto_fix_ci: boolean, 
+/**
+ * Comma-separated names of extra port variables for dev servers, e.g. `API_PORT`
+ */
+dev_server_ports: string | null, 
+/**
+ * Regex matched against dev server output to detect readiness
+ */
+dev_server_ready_pattern: string | null, 
+/**
+ * Preselected for new attempts instead of the global default
+ */
+default_executor_profile: ExecutorProfileId | null, 
+/**
+ * MCP servers the project's repositories declare for coding agents
+ */
+mcp_servers: { [key in string]?: JsonValue } | null, 
+/**
+ * Overrides the `git_hosts` config entry for the repositories' host
+ */
+git_host: ProjectGitHost | null, created_at: Date, updated_at: Date, };
+
+/**
+ * Git host of a project's repositories, for hosts their remote URL doesn't
+ * identify or that need their own API URL or token
+ */
+export type ProjectGitHost = { provider: GitHostKind, 
+/**
+ * API base URL, derived from the remote URL when unset
+ */
+api_url: string | null, 
+/**
+ * Name of the secret holding the access token, starting with
+ * `GIT_HOST_`; a project secret of that name wins over a global one
+ */
+token_secret: string | null, };
 
 export type CreateProject = { name: string, repositories: Array<CreateProjectRepo>, };
 
-export type UpdateProject = { name: string | null, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, };
+export type UpdateProject = { name: string | null, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, auto_review_follow_ups: boolean | null, auto_fix_ci: boolean | null, 
+/**
+ * `None` keeps the current value, an empty string clears it
+ */
+dev_server_ports: string | null, 
+/**
+ * `None` keeps the current value, an empty string clears it
+ */
+dev_server_ready_pattern: string | null, 
+/**
+ * `None` keeps the current value
+ */
+default_executor_profile: ExecutorProfileId | null, 
+/**
+ * `None` keeps the current value
+ */
+mcp_servers: { [key in string]?: JsonValue } | null, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
 
@@ -24,11 +83,73 @@ export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
 export type Repo = { id: string, path: string, name: string, display_name: string, created_at: Date, updated_at: Date, };
 
-export type ProjectRepo = { id: string, project_id: string, repo_id: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, };
+export type ProjectRepo = { id: string, project_id: string, repo_id: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, test_script: string | null, 
+/**
+ * Number of pre-created worktrees kept ready for new attempts (0 disables the pool)
+ */
+worktree_pool_size: number, 
+/**
+ * Pooled worktrees older than this are discarded and recreated
+ */
+worktree_pool_max_age_minutes: number, 
+/**
+ * Directories checked out in attempt worktrees (sparse-checkout cone, one
+ * per line); `None` checks out the whole tree
+ */
+sparse_checkout_paths: string | null, 
+/**
+ * Add the task's agent working directory to the sparse-checkout cone
+ */
+sparse_checkout_agent_working_dir: boolean, 
+/**
+ * Directories shared between attempt worktrees through the dependency
+ * cache, one per line as `path` or `path: key files`
+ */
+dependency_caches: string | null, 
+/**
+ * Which side wins when the repository's settings file differs from these
+ * settings; `None` until the user chose
+ */
+settings_file_mode: SettingsFileMode | null, 
+/**
+ * Branch the settings file was last read from
+ */
+settings_file_branch: string | null, };
 
 export type CreateProjectRepo = { display_name: string, git_repo_path: string, };
 
-export type UpdateProjectRepo = { setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, };
+export type UpdateProjectRepo = { setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, test_script: string | null, worktree_pool_size: number | null, worktree_pool_max_age_minutes: number | null, sparse_checkout_paths: string | null, sparse_checkout_agent_working_dir: boolean | null, dependency_caches: string | null, };
+
+/**
+ * How a repository's `.vibe-kanban.toml` relates to the settings stored here
+ */
+export type SettingsFileMode = "repo_file" | "local";
+
+/**
+ * Pipelines a script can be a step of
+ */
+export type ScriptPipeline = "setup" | "cleanup" | "verification";
+
+/**
+ * A named script of a project, e.g. `db:migrate` or `test`
+ */
+export type ProjectScript = { id: string, project_id: string, 
+/**
+ * Repository the working directory is relative to; none for the workspace root
+ */
+repo_id: string | null, name: string, script: string, language: ScriptRequestLanguage, working_dir: string | null, env: Record<string, string>, timeout_seconds: number | null, 
+/**
+ * Names of the scripts that have to succeed before this one runs
+ */
+depends_on: Array<string>, pipeline: ScriptPipeline | null, created_at: Date, updated_at: Date, };
+
+export type CreateProjectScript = { repo_id: string | null, name: string, script: string, language: ScriptRequestLanguage | null, working_dir: string | null, env: Record<string, string> | null, timeout_seconds: number | null, depends_on: Array<string> | null, pipeline: ScriptPipeline | null, };
+
+/**
+ * Fields left out keep their value; an empty `working_dir` clears it.
+ * `repo_id`, `timeout_seconds` and `pipeline` are replaced as given.
+ */
+export type UpdateProjectScript = { repo_id: string | null, name: string | null, script: string | null, language: ScriptRequestLanguage | null, working_dir: string | null, env: Record<string, string> | null, timeout_seconds: number | null, depends_on: Array<string> | null, pipeline: ScriptPipeline | null, };
 
 export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, };
 
@@ -46,7 +167,7 @@ export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelle
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, has_queued_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
 
 export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
 
@@ -82,9 +203,25 @@ export type ExecutionProcess = { id: string, session_id: string, run_reason: Exe
  */
 dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
-export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+export enum ExecutionProcessStatus { queued = "queued", running = "running", completed = "completed", failed = "failed", killed = "killed" }
+
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver" | "script";
+
+export type QueuedExecution = { execution_process_id: string, session_id: string, workspace_id: string, task_id: string, task_title: string, project_id: string, executor: string | null, 
+/**
+ * Higher runs first
+ */
+priority: bigint, 
+/**
+ * Order among runs of the same priority
+ */
+sequence: bigint, created_at: Date, };
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type UpdateQueuedExecution = { priority: bigint | null, 
+/**
+ * Zero-based index to move the run to in the whole queue
+ */
+position: number | null, };
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
@@ -92,12 +229,120 @@ export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMe
 
 export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
 
-export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, };
+export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, 
+/**
+ * Latest CI results, refreshed while the PR is open
+ */
+ci: PrCiStatus | null, };
 
 export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
 export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
 
+export type CiStatus = "pending" | "success" | "failure";
+
+export type CiCheck = { name: string, status: CiStatus, url: string | null, 
+/**
+ * Host-specific job id, used to fetch the job log
+ */
+job_id: string | null, };
+
+export type PrCiStatus = { status: CiStatus, head_sha: string, checks: Array<CiCheck>, updated_at: string, };
+
+export type ReviewSide = "old" | "new";
+
+/**
+ * A line comment on a workspace's diff, anchored to a commit of the workspace
+ * branch. Comments are re-anchored as new commits arrive.
+ */
+export type ReviewComment = { id: string, workspace_id: string, repo_id: string, 
+/**
+ * Process whose changes were reviewed
+ */
+execution_process_id: string | null, 
+/**
+ * Path relative to the repository root
+ */
+file_path: string, side: ReviewSide, 
+/**
+ * First commented line (1-based)
+ */
+line_start: bigint, 
+/**
+ * Last commented line (inclusive)
+ */
+line_end: bigint, body: string, 
+/**
+ * Commit the line range refers to
+ */
+anchor_commit: string, 
+/**
+ * The commented lines as of `anchor_commit`
+ */
+anchor_text: string | null, 
+/**
+ * The commented lines were changed or removed by a later commit
+ */
+outdated: boolean, sent_at: Date | null, resolved_at: Date | null, created_at: Date, updated_at: Date, };
+
+export type ApiTokenScope = "read" | "tasks" | "execute" | "config";
+
+export type ApiToken = { id: string, name: string, 
+/**
+ * Leading characters of the token
+ */
+token_prefix: string, scopes: Array<ApiTokenScope>, last_used_at: Date | null, created_at: Date, };
+
+export type WebhookEventType = "task_status_changed" | "execution_finished" | "execution_failed" | "approval_requested" | "pr_opened" | "pr_merged" | "test";
+
+export type Webhook = { id: string, name: string, url: string, 
+/**
+ * Key for the `X-Vibe-Kanban-Signature` HMAC
+ */
+secret: string, 
+/**
+ * Subscribed events; empty means all events
+ */
+events: Array<WebhookEventType>, enabled: boolean, created_at: Date, updated_at: Date, };
+
+export type CreateWebhook = { name: string, url: string, 
+/**
+ * Generated when not given
+ */
+secret: string | null, events: Array<WebhookEventType>, enabled: boolean | null, };
+
+export type UpdateWebhook = { name: string | null, url: string | null, secret: string | null, events: Array<WebhookEventType> | null, enabled: boolean | null, };
+
+export type WebhookDeliveryStatus = "pending" | "succeeded" | "failed";
+
+export type WebhookDelivery = { id: string, webhook_id: string, event_type: WebhookEventType, 
+/**
+ * JSON body sent to the endpoint
+ */
+payload: string, status: WebhookDeliveryStatus, attempts: bigint, next_attempt_at: Date, 
+/**
+ * HTTP status of the last response
+ */
+response_status: bigint | null, 
+/**
+ * Start of the last response body
+ */
+response_body: string | null, last_error: string | null, delivered_at: Date | null, created_at: Date, updated_at: Date, };
+
+export type Secret = { id: string, name: string, 
+/**
+ * Set for secrets of one project
+ */
+project_id: string | null, 
+/**
+ * Set for secrets of one repository; neither id is set for global secrets
+ */
+repo_id: string | null, created_at: Date, updated_at: Date, };
+
+export type CreateSecret = { name: string, value: string, project_id: string | null, repo_id: string | null, };
+
+export type UpdateSecret = { value: string, };
+
 export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" };
 
 export type CreateApprovalRequest = { tool_name: string, tool_input: JsonValue, tool_call_id: string, };
@@ -178,8 +423,31 @@ export type CreateRemoteProjectRequest = { organization_id: string, name: string
 
 export type LinkToExistingRequest = { remote_project_id: string, };
 
+export type SetSettingsFileModeRequest = { mode: SettingsFileMode, };
+
 export type RegisterRepoRequest = { path: string, display_name: string | null, };
 
+/**
+ * A stored dependency cache with the name of its repository
+ */
+export type DependencyCacheInfo = { 
+/**
+ * `None` once the repository was removed
+ */
+repo_name: string | null, id: string, repo_id: string, 
+/**
+ * Cached directory, relative to the repository root
+ */
+path: string, 
+/**
+ * Files the cache is keyed by
+ */
+key_files: Array<string>, size_bytes: number, created_at: Date, last_used_at: Date, 
+/**
+ * A newer cache exists for the same repository and directory
+ */
+superseded: boolean, };
+
 export type InitRepoRequest = { parent_path: string, folder_name: string, };
 
 export type TagSearchParams = { search: string | null, };
@@ -208,6 +476,26 @@ export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
 
 export type CurrentUserResponse = { user_id: string, };
 
+export type LocalAuthStatus = { 
+/**
+ * The server requires a token
+ */
+enabled: boolean, 
+/**
+ * The request carried a valid token (always true when auth is disabled)
+ */
+authenticated: boolean, scopes: Array<ApiTokenScope>, };
+
+export type LocalLoginRequest = { token: string, };
+
+export type CreateApiTokenRequest = { name: string, scopes: Array<ApiTokenScope>, };
+
+export type CreateApiTokenResponse = { api_token: ApiToken, 
+/**
+ * The token itself; it cannot be retrieved again
+ */
+token: string, };
+
 export type CreateFollowUpAttempt = { prompt: string, variant: string | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
 
 export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
@@ -252,11 +540,15 @@ export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: strin
 
 export type AbortConflictsRequest = { repo_id: string, };
 
+export type ResolveConflictsRequest = { repo_id: string, };
+
+export type ResolveConflictsError = { "type": "no_conflicts" } | { "type": "resolution_in_progress" } | { "type": "process_already_running" };
+
 export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, } | { "type": "rebase_in_progress" };
 
 export type PushError = { "type": "force_push_required" };
 
-export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, };
+export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "git_host_not_logged_in", host: string, } | { "type": "unsupported_git_host", host: string, };
 
 export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
 /**
@@ -274,16 +566,113 @@ conflicted_files: Array<string>, };
 
 export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
 
+export type RunProjectScriptRequest = { 
+/**
+ * Script to run after the scripts it depends on
+ */
+name: string | null, 
+/**
+ * Pipeline to run instead of a single script
+ */
+pipeline: ScriptPipeline | null, };
+
 export type AttachPrResponse = { pr_attached: boolean, pr_url: string | null, pr_number: bigint | null, pr_status: MergeStatus | null, };
 
 export type AttachExistingPrRequest = { repo_id: string, };
 
 export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
 
-export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" };
+export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_host_not_logged_in", host: string, } | { "type": "unsupported_git_host", host: string, };
 
 export type GetPrCommentsQuery = { repo_id: string, };
 
+export type CreateReviewCommentRequest = { 
+/**
+ * Repository of the file. When omitted, `file_path` must start with the
+ * repository name, as paths are shown in the diff view.
+ */
+repo_id: string | null, file_path: string, side: ReviewSide, line_start: number, 
+/**
+ * Defaults to `line_start`
+ */
+line_end: number | null, body: string, 
+/**
+ * Anchor to the commit this process left behind instead of the current
+ * HEAD of the worktree
+ */
+execution_process_id: string | null, };
+
+export type UpdateReviewCommentRequest = { body: string, };
+
+export type ResolveReviewCommentRequest = { 
+/**
+ * Pass `false` to reopen the comment
+ */
+resolved: boolean | null, };
+
+export type ReviewCommentWithRepo = { repo_name: string, id: string, workspace_id: string, repo_id: string, 
+/**
+ * Process whose changes were reviewed
+ */
+execution_process_id: string | null, 
+/**
+ * Path relative to the repository root
+ */
+file_path: string, side: ReviewSide, 
+/**
+ * First commented line (1-based)
+ */
+line_start: bigint, 
+/**
+ * Last commented line (inclusive)
+ */
+line_end: bigint, body: string, 
+/**
+ * Commit the line range refers to
+ */
+anchor_commit: string, 
+/**
+ * The commented lines as of `anchor_commit`
+ */
+anchor_text: string | null, 
+/**
+ * The commented lines were changed or removed by a later commit
+ */
+outdated: boolean, sent_at: Date | null, resolved_at: Date | null, created_at: Date, updated_at: Date, };
+
+export type SendReviewRequest = { 
+/**
+ * Extra instructions appended after the comments
+ */
+message: string | null, };
+
+export type SendReviewResponse = { 
+/**
+ * Number of comments included in the prompt
+ */
+sent: number, 
+/**
+ * The agent was busy, so the prompt was queued as the next follow-up
+ */
+queued: boolean, execution_process_id: string | null, };
+
+export type RepoSparseCheckout = { repo_id: string, repo_name: string, 
+/**
+ * Checked out directories; empty when the whole tree is checked out
+ */
+paths: Array<string>, };
+
+export type ExpandSparseCheckoutRequest = { 
+/**
+ * Repository to expand. When omitted, every sparse repository of the
+ * workspace is expanded.
+ */
+repo_id: string | null, 
+/**
+ * Directories relative to the repository root
+ */
+paths: Array<string>, };
+
 export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string, body: string, created_at: string, url: string, } | { "comment_type": "review", id: bigint, author: string, author_association: string, body: string, created_at: string, url: string, path: string, line: bigint | null, diff_hunk: string, };
 
 export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
@@ -304,9 +693,78 @@ export type DirectoryEntry = { name: string, path: string, is_directory: boolean
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, };
+/**
+ * A matching line with its surrounding context
+ */
+export type ContentMatch = { 
+/**
+ * Path prefixed with the repository name
+ */
+path: string, 
+/**
+ * 1-based line number
+ */
+line_number: number, line: string, 
+/**
+ * Byte ranges of the matches within `line`
+ */
+ranges: Array<[number, number]>, before: Array<string>, after: Array<string>, };
+
+export type ContentSearchResponse = { matches: Array<ContentMatch>, 
+/**
+ * More lines matched than the limit allowed
+ */
+truncated: boolean, files_searched: number, 
+/**
+ * Every repository was answered from a warm trigram index
+ */
+indexed: boolean, };
+
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, git_hosts: Array<GitHostConfig>, concurrency: ConcurrencyLimits, dev_servers: DevServerSettings, 
+/**
+ * Users whose PR comments the agent acts on besides the repository's
+ * owners, members and collaborators. Needed on GitLab and Gitea, which
+ * don't report how an author relates to the repository.
+ */
+pr_review_authors: Array<string>, };
+
+export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, 
+/**
+ * Remote channels notified alongside the local sound and push notification
+ */
+channels: Array<NotificationChannel>, };
 
-export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
+export type NotificationEvent = "attempt_finished" | "attempt_failed" | "approval_needed" | "pr_merged" | "ci_failed";
+
+/**
+ * Shape of the JSON body posted by a webhook channel
+ */
+export type WebhookPreset = "generic" | "slack" | "discord" | "mattermost";
+
+export type NotificationChannelKind = { "type": "webhook", url: string, preset: WebhookPreset, } | { "type": "ntfy", server: string, topic: string, 
+/**
+ * Access token for protected topics
+ */
+token: string | null, } | { "type": "command", command: string, };
+
+/**
+ * A destination that receives notifications in addition to the local sound
+ * and push notification
+ */
+export type NotificationChannel = { name: string, enabled: boolean, 
+/**
+ * Events sent to this channel; every event when empty
+ */
+events: Array<NotificationEvent>, 
+/**
+ * Replaces the default title. Supports `{{event}}`, `{{title}}`,
+ * `{{message}}`, `{{task}}`, `{{branch}}` and `{{url}}`.
+ */
+title_template: string | null, 
+/**
+ * Replaces the default message; same placeholders as the title
+ */
+message_template: string | null, kind: NotificationChannelKind, };
 
 export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM" }
 
@@ -318,6 +776,131 @@ export type EditorOpenError = { "type": "executable_not_found", executable: stri
 
 export type GitHubConfig = { pat: string | null, oauth_token: string | null, username: string | null, primary_email: string | null, default_pr_base: string | null, };
 
+export type GitHostKind = "github" | "gitlab" | "gitea";
+
+/**
+ * Maps a git host to a provider
+ */
+export type GitHostConfig = { 
+/**
+ * Hostname as it appears in remote URLs, e.g. `gitlab.example.com`
+ */
+host: string, provider: GitHostKind, 
+/**
+ * API base URL, derived from the host when unset
+ */
+api_url: string | null, 
+/**
+ * Name of the secret holding the access token, starting with `GIT_HOST_`
+ * so it is never injected into agents. For GitHub, a token switches from
+ * the `gh` CLI to the REST API; GitLab and Gitea fall back to
+ * `GITLAB_TOKEN` / `GITEA_TOKEN`.
+ */
+token_secret: string | null, };
+
+/**
+ * Caps on coding agents running at the same time; unset means unlimited
+ */
+export type ConcurrencyLimits = { max_agents: number | null, max_agents_per_project: number | null, 
+/**
+ * Keyed by executor, e.g. `CLAUDE_CODE`
+ */
+max_agents_per_executor: { [key in string]?: number }, };
+
+export type DevServerSettings = { 
+/**
+ * Stop a dev server after this many minutes without preview traffic; unset keeps it running
+ */
+idle_shutdown_minutes: number | null, };
+
+/**
+ * Ports and readiness of the dev server running in a workspace
+ */
+export type DevServerInfo = { workspace_id: string, execution_process_id: string, 
+/**
+ * The port passed in `PORT`
+ */
+port: number, 
+/**
+ * Every reserved port by variable name, including `PORT`
+ */
+ports: { [key in string]?: number }, ready: boolean, 
+/**
+ * Path of the reverse proxy in front of `port`
+ */
+preview_path: string, 
+/**
+ * Port of the preview listener serving `preview_path`; unset when
+ * previews share the app's port
+ */
+preview_port: number | null, };
+
+/**
+ * A key whose value in the settings file differs from the local one
+ */
+export type SettingsFileDifference = { key: string, repo_file: string, 
+/**
+ * `None` when the key is not set locally
+ */
+local: string | null, };
+
+/**
+ * The settings file of a project repository compared against the local settings
+ */
+export type RepoSettingsFileStatus = { repo_id: string, 
+/**
+ * Branch the file was read from
+ */
+branch: string, 
+/**
+ * Name of the file found at the repository root, if any
+ */
+file_name: string | null, mode: SettingsFileMode | null, differences: Array<SettingsFileDifference>, 
+/**
+ * Why the file could not be read or parsed
+ */
+error: string | null, };
+
+/**
+ * A stored dependency cache
+ */
+export type DependencyCacheEntry = { id: string, repo_id: string, 
+/**
+ * Cached directory, relative to the repository root
+ */
+path: string, 
+/**
+ * Files the cache is keyed by
+ */
+key_files: Array<string>, size_bytes: number, created_at: Date, last_used_at: Date, 
+/**
+ * A newer cache exists for the same repository and directory
+ */
+superseded: boolean, };
+
+/**
+ * Which caches to remove. Criteria add up; with none, nothing is removed.
+ */
+export type PruneDependencyCaches = { 
+/**
+ * Only consider the caches of this repository
+ */
+repo_id: string | null, 
+/**
+ * Remove caches superseded by a newer one
+ */
+superseded: boolean, 
+/**
+ * Remove caches not provisioned for this many days
+ */
+unused_for_days: number | null, 
+/**
+ * Then remove the least recently used caches until the rest fit
+ */
+max_total_bytes: number | null, };
+
+export type PruneDependencyCachesResult = { removed: Array<string>, freed_bytes: number, };
+
 export enum SoundFile { ABSTRACT_SOUND1 = "ABSTRACT_SOUND1", ABSTRACT_SOUND2 = "ABSTRACT_SOUND2", ABSTRACT_SOUND3 = "ABSTRACT_SOUND3", ABSTRACT_SOUND4 = "ABSTRACT_SOUND4", COW_MOOING = "COW_MOOING", PHONE_VIBRATION = "PHONE_VIBRATION", ROOSTER = "ROOSTER" }
 
 export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES" | "KO" | "ZH_HANS";
@@ -344,6 +927,25 @@ queued_at: string, };
 
 export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
 
+export type ConflictResolutionState = "resolving" | "verifying" | "resolved" | "aborted";
+
+/**
+ * Tracks one agent-assisted conflict resolution in a workspace repo.
+ */
+export type ConflictResolution = { workspace_id: string, repo_id: string, op: ConflictOp, state: ConflictResolutionState, 
+/**
+ * Number of agent turns started so far (starts at 1)
+ */
+round: number, 
+/**
+ * The coding agent process working on the current round
+ */
+execution_process_id: string | null, 
+/**
+ * Files that were conflicted at the start of the current round
+ */
+files: Array<string>, message: string | null, test_output: string | null, started_at: Date, updated_at: Date, };
+
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
 export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
@@ -352,16 +954,28 @@ export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript" | "NamedScript";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
 /**
  * Optional relative path to execute the script in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, 
+/**
+ * Name of the project script this runs, if any
+ */
+script_name: string | null, 
+/**
+ * Variables added to the execution environment
+ */
+env: { [key in string]?: string }, 
+/**
+ * The script is killed and marked failed after this many seconds
+ */
+timeout_seconds: number | null, };
 
-export type ScriptRequestLanguage = "Bash";
+export type ScriptRequestLanguage = "Bash" | "Python" | "Node";
 
 export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID" }
 